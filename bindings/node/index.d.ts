@@ -0,0 +1,288 @@
+import { EventEmitter } from 'events'
+
+export interface StartWipeRequest {
+  device: string
+  operationId?: string
+  targetType?: 'HDD' | 'SSD' | 'Flash' | 'Optical' | 'Tape'
+  standard?: string
+  scope?: object
+  verify?: boolean
+  certificate?: boolean
+  profile?: string
+  confirmation?: string
+  simulate?: boolean
+  /** Leave out the TRIM / Deallocate after overwriting SSD and flash media */
+  skipTrim?: boolean
+  /** ATA security state after a whole-device wipe; Brick locks the drive for good */
+  securityState?: 'Disable' | 'Freeze' | 'Brick'
+  /** Order in the queue; a High firmware erase may pause lower-priority overwrites */
+  priority?: 'Low' | 'Normal' | 'High'
+  /** Compliance profile id or standard name, in place of standard and profile */
+  method?: string
+}
+
+export interface BatchWipeRequest {
+  device: string
+  method?: string
+  options?: Omit<StartWipeRequest, 'device' | 'method'>
+}
+
+export type BatchWipeStatus = 'Running' | 'Completed' | 'Failed' | 'Cancelled'
+
+export interface BatchWipe {
+  operation_id: string
+  device: string
+  method: string | null
+  status: BatchWipeStatus
+  percentage: number
+  progress: Record<string, any> | null
+  certificate_path: string | null
+  error: string | null
+}
+
+export interface BatchStatus {
+  batch_id: string
+  started_at: string
+  finished_at: string | null
+  finished: boolean
+  /** Mean of the wipes' percentages */
+  percentage: number
+  counts: Record<BatchWipeStatus, number>
+  wipes: BatchWipe[]
+}
+
+export type ReportFormat = 'pdf' | 'json' | 'csv' | 'xml'
+
+export interface ReportFilter {
+  device?: string
+  serial?: string
+  status?: 'InProgress' | 'Verifying' | 'Completed' | 'Failed' | 'Interrupted'
+  /** RFC 3339 timestamps bounding when the wipe started */
+  since?: string
+  until?: string
+  operator?: string
+  /** At most this many certificates */
+  limit?: number
+}
+
+export interface ReportSummary {
+  /** The certificate id */
+  report_id: string
+  operation_id: string
+  target_path: string
+  serial_number: string | null
+  model: string | null
+  standard: string
+  status: 'InProgress' | 'Verifying' | 'Completed' | 'Failed' | 'Interrupted'
+  started_at: string
+  completed_at: string | null
+  operator: string | null
+  /** Null when the certificate is no longer in the reports directory */
+  files: { json: string, pdf: string | null, audit_report: string | null } | null
+}
+
+export interface StoredReport {
+  summary: ReportSummary
+  certificate: Record<string, any>
+}
+
+export interface LockedDevice {
+  /** Canonical path of the device */
+  device: string
+  operation_id: string
+  activity: 'Wipe' | 'Verification' | 'ResidualScan' | 'CryptoErase'
+  since: string
+}
+
+export interface PrivilegeStatus {
+  elevated: boolean
+  user: string | null
+  elevation_method: 'uac' | 'pkexec' | 'sudo' | null
+}
+
+export interface PreflightIssue {
+  check: string
+  severity: 'Blocking' | 'Warning'
+  message: string
+  remediation: string
+}
+
+export interface WipePreflight {
+  device: string
+  method: string
+  can_start: boolean
+  blockers: PreflightIssue[]
+  warnings: PreflightIssue[]
+}
+
+export interface WipeAdvisory {
+  device_path: string
+  risk: 'Unknown' | 'Low' | 'Elevated' | 'High'
+  recommendation: 'Overwrite' | 'Destroy'
+  /** E.g. "412 reallocated sectors" */
+  reasons: string[]
+  health: Record<string, any>
+}
+
+export interface DurationEstimate {
+  expected_seconds: number
+  earliest_seconds: number
+  latest_seconds: number
+  bytes_per_pass: number
+  passes: number
+  verify_bytes: number
+  /** Write rate assumed; null for a firmware erase */
+  throughput_mbps: number | null
+  source: 'History' | 'Measured' | 'Nominal' | 'DriveReported'
+  /** Earlier wipes of the model the rate is based on */
+  samples: number
+  /** E.g. "~6h 20m (5h 25m - 7h 55m)" */
+  summary: string
+}
+
+export interface WipeOutcome {
+  operation_id: string
+  result: Record<string, any>
+  certificate_path: string | null
+}
+
+export type RecoveryAction = 'Resume' | 'Restart' | 'Dismiss'
+
+export interface InterruptedOperation {
+  operation_id: string
+  request: Record<string, any>
+  started_at: string
+  /** Passes that were complete on the device */
+  passes_completed: number
+  total_passes: number
+  last_seen_at: string
+  /** Where the wipe was holding when it stopped while paused */
+  paused: PauseCheckpoint | null
+}
+
+export interface PauseCheckpoint {
+  pass: number
+  total_passes: number
+  /** Bytes of the pass that are on the device */
+  bytes_written: number
+  /** Null on zoned drives, which are written zone by zone */
+  next_sector: number | null
+  paused_at: string
+}
+
+export interface BackupStatus {
+  enabled: boolean
+  directory: string | null
+  operations: number
+  files: number
+  total_bytes: number
+  last_backup_at: string | null
+  /** Backed-up files missing or no longer matching the manifest */
+  damaged: string[]
+  /** Why the latest backup failed, until one succeeds */
+  last_error: string | null
+}
+
+/** One entry of an operation's timeline; `event` says which fields are present */
+export type TimelineEvent = { at: string } & (
+  | { event: 'Started', target_path: string, operator: string }
+  | { event: 'DeviceOpened', device_path: string, model: string, serial: string, total_sectors: number }
+  | { event: 'ImageWritten', location: string, bytes_imaged: number }
+  | { event: 'PassStarted', pass: number, total_passes: number, pattern: string }
+  | { event: 'PassFinished', pass: number, seconds: number, sectors_written: number }
+  | { event: 'WriteRetried', sector: number, error: string }
+  | { event: 'ThrottleEngaged', reason: string }
+  | { event: 'ThrottleReleased' }
+  | { event: 'Paused', checkpoint: PauseCheckpoint }
+  | { event: 'Resumed' }
+  | { event: 'VerificationStarted', policy: string }
+  | { event: 'VerificationFinished', passed: boolean, bytes_verified: number }
+  | { event: 'CertificateIssued', certificate_id: string }
+  | { event: 'Finished', success: boolean }
+  | { event: 'Failed', code: string, error: string }
+  | { event: 'EventsDropped', count: number }
+)
+
+export class NativeClient {
+  constructor (configJson: string)
+  static openWithSettings (settingsPath?: string): NativeClient
+  getConfig (): Record<string, any>
+  updateConfig (configJson: string): void
+  resetConfig (): void
+  login (username: string, password: string): Promise<void>
+  loginToken (token: string): Promise<void>
+  listDevices (): Promise<Array<Record<string, any>>>
+  getPrivilegeStatus (): PrivilegeStatus
+  requestElevation (): void
+  preflight (device: string, targetType?: string, standard?: string): Promise<Record<string, any>>
+  preflightWipe (device: string, method: string, targetType?: string): Promise<WipePreflight>
+  adviseWipe (device: string): Promise<WipeAdvisory>
+  estimateDuration (device: string, method: string, targetType?: string): Promise<DurationEstimate>
+  startWipe (request: object, onProgress?: (progress: object) => void): Promise<WipeOutcome>
+  recoverOperations (): InterruptedOperation[]
+  recoverOperation (operationId: string, action: RecoveryAction, onProgress?: (progress: object) => void): Promise<WipeOutcome | null>
+  cancel (operationId: string): void
+  pauseOperation (operationId: string): Promise<PauseCheckpoint>
+  resumeOperation (operationId: string): void
+  getOperationEvents (operationId: string): TimelineEvent[]
+  getBackupStatus (): BackupStatus
+  lockedDevices (): LockedDevice[]
+  deviceLock (device: string): LockedDevice | null
+  history (device?: string, limit?: number): Array<Record<string, any>>
+  listReports (filter?: ReportFilter): ReportSummary[]
+  openReport (reportId: string): Promise<StoredReport>
+  verifyCertificate (certificatePath: string): Promise<Record<string, any>>
+  reverifyOperation (operationId: string, policy?: string | object): Promise<Record<string, any>>
+  exportReport (operationId: string, format: ReportFormat, path: string): Promise<string>
+}
+
+export declare interface SafeWipeClient {
+  on (event: 'started', listener: (operationId: string) => void): this
+  on (event: 'progress', listener: (progress: Record<string, any>) => void): this
+  on (event: 'completed', listener: (outcome: WipeOutcome) => void): this
+  on (event: 'failed', listener: (error: Error, operationId: string) => void): this
+  on (event: 'batchCompleted', listener: (status: BatchStatus) => void): this
+  /** Emitted once after construction when wipes were cut short by a crash or power loss */
+  on (event: 'interrupted', listener: (operations: InterruptedOperation[]) => void): this
+}
+
+export class SafeWipeClient extends EventEmitter {
+  constructor (config: string | object | NativeClient)
+  /** Settings saved in `settingsPath`, by default in the platform's configuration directory */
+  static openWithSettings (settingsPath?: string): SafeWipeClient
+  readonly native: NativeClient
+  getConfig (): Record<string, any>
+  updateConfig (config: string | object): void
+  resetConfig (): void
+  login (username: string, password: string): Promise<void>
+  loginToken (token: string): Promise<void>
+  listDevices (): Promise<Array<Record<string, any>>>
+  getPrivilegeStatus (): PrivilegeStatus
+  /** Relaunch elevated, then quit `app` (Electron's) when given */
+  requestElevation (app?: any): void
+  preflight (device: string, targetType?: string, standard?: string): Promise<Record<string, any>>
+  preflightWipe (device: string, method: string, targetType?: string): Promise<WipePreflight>
+  adviseWipe (device: string): Promise<WipeAdvisory>
+  estimateDuration (device: string, method: string, targetType?: string): Promise<DurationEstimate>
+  startWipe (request: StartWipeRequest): Promise<WipeOutcome>
+  startBatchWipe (requests: BatchWipeRequest[]): string
+  getBatchStatus (batchId: string): BatchStatus
+  recoverOperations (): InterruptedOperation[]
+  /** Resumed and restarted wipes run under a new operation id; null when dismissed */
+  recoverOperation (operationId: string, action: RecoveryAction): Promise<WipeOutcome | null>
+  cancel (operationId: string): void
+  pauseOperation (operationId: string): Promise<PauseCheckpoint>
+  resumeOperation (operationId: string): void
+  getOperationEvents (operationId: string): TimelineEvent[]
+  getBackupStatus (): BackupStatus
+  lockedDevices (): LockedDevice[]
+  deviceLock (device: string): LockedDevice | null
+  history (device?: string, limit?: number): Array<Record<string, any>>
+  listReports (filter?: ReportFilter): ReportSummary[]
+  openReport (reportId: string): Promise<StoredReport>
+  verifyCertificate (certificatePath: string): Promise<Record<string, any>>
+  reverifyOperation (operationId: string, policy?: string | object): Promise<Record<string, any>>
+  exportReport (operationId: string, format: ReportFormat, path: string): Promise<string>
+  /** Ask with Electron's save dialog; null when cancelled */
+  saveReport (dialog: any, window: any, operationId: string, format: ReportFormat): Promise<string | null>
+}
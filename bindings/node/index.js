@@ -0,0 +1,302 @@
+'use strict'
+
+// SafeWipeClient: the native binding with progress delivered as events.
+//
+//   const { SafeWipeClient } = require('@hdd-tool/safewipe')
+//   const client = new SafeWipeClient(fs.readFileSync('config.json', 'utf8'))
+//   // or, with settings saved across restarts: SafeWipeClient.openWithSettings()
+//   await client.login('operator', 'secret')
+//   client.on('progress', (p) => console.log(p.operation_id, p.percentage))
+//   const { certificate_path } = await client.startWipe({ device: '/dev/sdb' })
+//
+// Events: 'started' (operationId), 'progress' (update with operation_id),
+// 'completed' (outcome), 'failed' (error, operationId),
+// 'batchCompleted' (batch status) and 'interrupted' (wipes a crash or power
+// loss cut short, emitted once after the client is created when there are any;
+// pass each to recoverOperation with 'Resume', 'Restart' or 'Dismiss').
+//
+// startBatchWipe starts several wipes at once and getBatchStatus sums them
+// up for a consolidated progress view; the per-wipe events above still fire.
+//
+// In the Electron main process, saveReport asks where to save a report:
+//
+//   const { dialog } = require('electron')
+//   const path = await client.saveReport(dialog, win, operationId, 'pdf')
+
+const { EventEmitter } = require('events')
+const { randomUUID } = require('crypto')
+const { NativeClient } = require('./safewipe.node')
+
+class SafeWipeClient extends EventEmitter {
+  // Pass a configuration (JSON text or object), or a NativeClient
+  constructor (configJson) {
+    super()
+    if (configJson instanceof NativeClient) {
+      this.native = configJson
+    } else {
+      this.native = new NativeClient(typeof configJson === 'string' ? configJson : JSON.stringify(configJson))
+    }
+    this.batches = new Map()
+    const interrupted = this.native.recoverOperations()
+    if (interrupted.length) {
+      process.nextTick(() => this.emit('interrupted', interrupted))
+    }
+  }
+
+  // A client whose settings are kept in `settingsPath`, by default in the
+  // platform's configuration directory
+  static openWithSettings (settingsPath) {
+    return new SafeWipeClient(NativeClient.openWithSettings(settingsPath))
+  }
+
+  getConfig () {
+    return this.native.getConfig()
+  }
+
+  updateConfig (config) {
+    this.native.updateConfig(typeof config === 'string' ? config : JSON.stringify(config))
+  }
+
+  resetConfig () {
+    this.native.resetConfig()
+  }
+
+  login (username, password) {
+    return this.native.login(username, password)
+  }
+
+  loginToken (token) {
+    return this.native.loginToken(token)
+  }
+
+  listDevices () {
+    return this.native.listDevices()
+  }
+
+  getPrivilegeStatus () {
+    return this.native.getPrivilegeStatus()
+  }
+
+  // Relaunch elevated and quit this copy; pass Electron's `app` to quit it
+  requestElevation (app) {
+    this.native.requestElevation()
+    if (app) app.quit()
+  }
+
+  preflight (device, targetType, standard) {
+    return this.native.preflight(device, targetType, standard)
+  }
+
+  preflightWipe (device, method, targetType) {
+    return this.native.preflightWipe(device, method, targetType)
+  }
+
+  // Resolves with `recommendation: 'Destroy'` for drives likely to fail
+  adviseWipe (device) {
+    return this.native.adviseWipe(device)
+  }
+
+  // How long the wipe should take; `summary` reads like "~6h 20m (5h 25m - 7h 55m)"
+  estimateDuration (device, method, targetType) {
+    return this.native.estimateDuration(device, method, targetType)
+  }
+
+  // Resolves once the wipe has ended. Pass `operationId` in the request to
+  // know the id up front, e.g. to build its confirmation phrase.
+  // Rejects with OperationInProgress, before 'started', while another
+  // operation is using the device
+  async startWipe (request) {
+    const holder = this.native.deviceLock(request.device)
+    if (holder) {
+      throw new Error(`OperationInProgress: ${request.device} is in use by operation ${holder.operation_id}`)
+    }
+    const operationId = request.operationId || randomUUID()
+    this.emit('started', operationId)
+    try {
+      const outcome = await this.native.startWipe({ ...request, operationId }, (update) => {
+        this.emit('progress', { operation_id: operationId, ...update })
+      })
+      this.emit('completed', outcome)
+      return outcome
+    } catch (error) {
+      this.emit('failed', error, operationId)
+      throw error
+    }
+  }
+
+  // Start a wipe of every drive in `requests`, each given as
+  // { device, method, options }: `method` is a profile id or standard name and
+  // `options` takes any other startWipe field. Returns the batch id at once.
+  startBatchWipe (requests) {
+    const batchId = randomUUID()
+    const batch = {
+      batch_id: batchId,
+      started_at: new Date().toISOString(),
+      finished_at: null,
+      wipes: requests.map(({ device, method, options }) => ({
+        operation_id: (options && options.operationId) || randomUUID(),
+        device,
+        method: method || null,
+        status: 'Running',
+        percentage: 0,
+        progress: null,
+        certificate_path: null,
+        error: null
+      }))
+    }
+    this.batches.set(batchId, batch)
+
+    const byId = new Map(batch.wipes.map((wipe) => [wipe.operation_id, wipe]))
+    const onProgress = (update) => {
+      const wipe = byId.get(update.operation_id)
+      if (wipe) {
+        wipe.progress = update
+        wipe.percentage = update.percentage
+      }
+    }
+    this.on('progress', onProgress)
+
+    const wipes = requests.map(({ device, method, options }, index) => {
+      const wipe = batch.wipes[index]
+      const request = { ...options, device, operationId: wipe.operation_id }
+      if (method) request.method = method
+      return this.startWipe(request).then(
+        ({ result, certificate_path: certificatePath }) => {
+          wipe.status = result.success ? 'Completed' : 'Failed'
+          wipe.error = result.error_message || null
+          wipe.certificate_path = certificatePath
+          if (result.success) wipe.percentage = 100
+        },
+        (error) => {
+          wipe.status = String(error.message).startsWith('Cancelled') ? 'Cancelled' : 'Failed'
+          wipe.error = error.message
+        }
+      )
+    })
+    Promise.all(wipes).then(() => {
+      batch.finished_at = new Date().toISOString()
+      this.removeListener('progress', onProgress)
+      this.emit('batchCompleted', this.getBatchStatus(batchId))
+    })
+    return batchId
+  }
+
+  // The wipes of a batch with their status, and the batch as a whole
+  getBatchStatus (batchId) {
+    const batch = this.batches.get(batchId)
+    if (!batch) {
+      throw new Error(`No batch with id ${batchId}`)
+    }
+    const counts = { Running: 0, Completed: 0, Failed: 0, Cancelled: 0 }
+    for (const wipe of batch.wipes) counts[wipe.status] += 1
+    const total = batch.wipes.reduce((sum, wipe) => sum + wipe.percentage, 0)
+    return {
+      ...batch,
+      wipes: batch.wipes.map((wipe) => ({ ...wipe })),
+      finished: batch.finished_at !== null,
+      percentage: batch.wipes.length ? total / batch.wipes.length : 100,
+      counts
+    }
+  }
+
+  // Wipes a crash or power loss cut short that have not been dealt with yet
+  recoverOperations () {
+    return this.native.recoverOperations()
+  }
+
+  // Resume or restart an interrupted wipe, which runs under a new operation
+  // id with the same events as startWipe, or dismiss it; resolves with null
+  // when dismissed
+  async recoverOperation (operationId, action) {
+    try {
+      const outcome = await this.native.recoverOperation(operationId, action, (update) => {
+        this.emit('progress', { operation_id: update.request_id, ...update })
+      })
+      if (outcome) this.emit('completed', outcome)
+      return outcome
+    } catch (error) {
+      this.emit('failed', error, operationId)
+      throw error
+    }
+  }
+
+  cancel (operationId) {
+    this.native.cancel(operationId)
+  }
+
+  // Hold a wipe between blocks, e.g. to free the bus while other drives in
+  // the enclosure are swapped; resolves with the checkpoint it stopped at
+  pauseOperation (operationId) {
+    return this.native.pauseOperation(operationId)
+  }
+
+  resumeOperation (operationId) {
+    this.native.resumeOperation(operationId)
+  }
+
+  // The activity log of a running or finished wipe: passes, retried writes,
+  // throttling, pauses, verification and how it ended
+  getOperationEvents (operationId) {
+    return this.native.getOperationEvents(operationId)
+  }
+
+  // Certificates and reports copied to the backup directory, and any that
+  // went missing or changed since
+  getBackupStatus () {
+    return this.native.getBackupStatus()
+  }
+
+  // Devices a running wipe, verification or scan is using, to grey them out
+  lockedDevices () {
+    return this.native.lockedDevices()
+  }
+
+  deviceLock (device) {
+    return this.native.deviceLock(device)
+  }
+
+  history (device, limit) {
+    return this.native.history(device, limit)
+  }
+
+  // Certificates of past wipes, newest first, for a report list that covers
+  // earlier sessions too
+  listReports (filter) {
+    return this.native.listReports(filter)
+  }
+
+  openReport (reportId) {
+    return this.native.openReport(reportId)
+  }
+
+  verifyCertificate (certificatePath) {
+    return this.native.verifyCertificate(certificatePath)
+  }
+
+  // Read the device back against the wipe's last pass, regenerated from its
+  // sealed seed when the pass was random
+  reverifyOperation (operationId, policy) {
+    return this.native.reverifyOperation(operationId, policy)
+  }
+
+  // format is 'pdf', 'json' or 'csv'; resolves with the path written
+  exportReport (operationId, format, path) {
+    return this.native.exportReport(operationId, format, path)
+  }
+
+  // Show Electron's save dialog and export there; resolves with the path
+  // written, or null when the operator cancelled the dialog
+  async saveReport (dialog, window, operationId, format) {
+    const names = { pdf: 'PDF certificate', json: 'Signed JSON certificate', csv: 'CSV summary', xml: 'XML asset report' }
+    const { canceled, filePath } = await dialog.showSaveDialog(window, {
+      defaultPath: `certificate_${operationId.slice(0, 8)}.${format}`,
+      filters: [{ name: names[format] || format, extensions: [format] }]
+    })
+    if (canceled || !filePath) {
+      return null
+    }
+    return this.exportReport(operationId, format, filePath)
+  }
+}
+
+module.exports = { SafeWipeClient, NativeClient }
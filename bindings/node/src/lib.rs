@@ -0,0 +1,540 @@
+//! Node.js bindings for the wipe engine, for Electron front ends
+//!
+//! `NativeClient` is the raw binding; `index.js` wraps it in `SafeWipeClient`,
+//! an EventEmitter that reports progress as `progress` events. Everything that
+//! touches a device or the disk runs off the JavaScript thread and returns a
+//! Promise. Objects returned to JavaScript have the same shape as the REST
+//! API's JSON.
+
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::RecvTimeoutError;
+use std::time::Duration;
+use hdd_tool::core::privileges;
+use hdd_tool::security_state::SecurityStateAction;
+use hdd_tool::core::{
+    ComplianceProfile, HistoryFilter, OperationStatus, OperatorIdentity, RecoveryAction, ReportFormat, SanitizationStandard, TargetType,
+    VerificationPolicy, WipeConfiguration, WipeEngine, WipePriority, WipeRequest, SettingsStore, WipeScope, WipeSpec,
+};
+use napi::bindgen_prelude::*;
+use napi::threadsafe_function::{ErrorStrategy, ThreadsafeFunction, ThreadsafeFunctionCallMode};
+use napi_derive::napi;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use uuid::Uuid;
+
+fn wipe_error(error: hdd_tool::core::WipeError) -> Error {
+    Error::new(Status::GenericFailure, error.to_string())
+}
+
+fn invalid(message: String) -> Error {
+    Error::new(Status::InvalidArg, message)
+}
+
+/// Run blocking engine work on the runtime's blocking pool
+async fn blocking<T: Send + 'static>(work: impl FnOnce() -> Result<T> + Send + 'static) -> Result<T> {
+    napi::tokio::task::spawn_blocking(work)
+        .await
+        .map_err(|e| Error::new(Status::GenericFailure, format!("Engine task failed: {}", e)))?
+}
+
+/// A wipe as passed to `startWipe`
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct StartWipe {
+    device: String,
+    #[serde(default = "default_target_type")]
+    target_type: TargetType,
+    #[serde(default)]
+    scope: WipeScope,
+    #[serde(default = "default_standard")]
+    standard: SanitizationStandard,
+    #[serde(default = "default_true")]
+    verify: bool,
+    #[serde(default = "default_true")]
+    certificate: bool,
+    #[serde(default)]
+    profile: Option<ComplianceProfile>,
+    #[serde(default)]
+    confirmation: Option<String>,
+    #[serde(default)]
+    simulate: bool,
+    #[serde(default)]
+    skip_trim: bool,
+    /// "Disable", "Freeze" or "Brick" (irreversible)
+    #[serde(default)]
+    security_state: Option<SecurityStateAction>,
+    /// "Low", "Normal" or "High"; a high-priority firmware erase may preempt overwrites
+    #[serde(default)]
+    priority: WipePriority,
+    /// A compliance profile id or standard name, in place of `standard` and `profile`
+    #[serde(default)]
+    method: Option<String>,
+}
+
+/// A wipe method as a front end names it: a compliance profile id or a standard name
+enum WipeMethod {
+    Profile(ComplianceProfile),
+    Standard(SanitizationStandard),
+}
+
+impl WipeMethod {
+    fn parse(name: &str) -> Result<Self> {
+        let value = Value::String(name.to_string());
+        serde_json::from_value(value.clone())
+            .map(WipeMethod::Profile)
+            .or_else(|_| serde_json::from_value(value).map(WipeMethod::Standard))
+            .map_err(|_| invalid(format!("Unknown wipe method '{}'", name)))
+    }
+
+    fn spec(&self) -> WipeSpec {
+        match self {
+            WipeMethod::Profile(profile) => profile.spec(),
+            WipeMethod::Standard(standard) => WipeSpec::from(*standard),
+        }
+    }
+}
+
+/// Which certificates `listReports` returns; unset fields match everything
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ReportFilter {
+    #[serde(default)]
+    device: Option<String>,
+    #[serde(default)]
+    serial: Option<String>,
+    #[serde(default)]
+    status: Option<OperationStatus>,
+    #[serde(default)]
+    since: Option<chrono::DateTime<chrono::Utc>>,
+    #[serde(default)]
+    until: Option<chrono::DateTime<chrono::Utc>>,
+    #[serde(default)]
+    operator: Option<String>,
+    #[serde(default)]
+    limit: Option<usize>,
+}
+
+impl From<ReportFilter> for HistoryFilter {
+    fn from(filter: ReportFilter) -> Self {
+        HistoryFilter {
+            target_path: filter.device,
+            serial: filter.serial,
+            status: filter.status,
+            since: filter.since,
+            until: filter.until,
+            operator: filter.operator,
+            limit: filter.limit,
+        }
+    }
+}
+
+fn default_target_type() -> TargetType { TargetType::HDD }
+fn default_standard() -> SanitizationStandard { SanitizationStandard::NIST_SP_800_88_R1 }
+fn default_true() -> bool { true }
+
+#[napi]
+pub struct NativeClient {
+    engine: Arc<WipeEngine>,
+}
+
+#[napi]
+impl NativeClient {
+    /// Start an engine from a `WipeConfiguration` as JSON
+    #[napi(constructor)]
+    pub fn new(config_json: String) -> Result<Self> {
+        let config: WipeConfiguration =
+            serde_json::from_str(&config_json).map_err(|e| invalid(format!("Invalid configuration: {}", e)))?;
+        let engine = WipeEngine::new(config).map_err(wipe_error)?;
+        Ok(Self { engine: Arc::new(engine) })
+    }
+
+    /// Start an engine with the settings saved at `settings_path`, by default
+    /// in the platform's configuration directory; `updateConfig` and
+    /// `resetConfig` save there, so changes survive a restart
+    #[napi(factory)]
+    pub fn open_with_settings(settings_path: Option<String>) -> Result<Self> {
+        let settings = match settings_path {
+            Some(path) => SettingsStore::new(path),
+            None => SettingsStore::at_default_location(),
+        };
+        let engine = WipeEngine::open_with_settings(settings).map_err(wipe_error)?;
+        Ok(Self { engine: Arc::new(engine) })
+    }
+
+    #[napi]
+    pub fn get_config(&self) -> Result<Value> {
+        serde_json::to_value(self.engine.configuration()).map_err(|e| Error::from_reason(e.to_string()))
+    }
+
+    /// Replace the configuration; needs an administrator logged in. Wipes
+    /// already running finish with the settings they started with.
+    #[napi]
+    pub fn update_config(&self, config_json: String) -> Result<()> {
+        let config: WipeConfiguration =
+            serde_json::from_str(&config_json).map_err(|e| invalid(format!("Invalid configuration: {}", e)))?;
+        self.engine.update_configuration(config).map_err(wipe_error)
+    }
+
+    /// Put every setting back to its default
+    #[napi]
+    pub fn reset_config(&self) -> Result<()> {
+        self.engine.reset_configuration().map_err(wipe_error)
+    }
+
+    #[napi]
+    pub async fn login(&self, username: String, password: String) -> Result<()> {
+        let engine = self.engine.clone();
+        blocking(move || engine.login(&username, &password).map(|_| ()).map_err(wipe_error)).await
+    }
+
+    #[napi]
+    pub async fn login_token(&self, token: String) -> Result<()> {
+        let engine = self.engine.clone();
+        blocking(move || engine.login_with_token(&token).map(|_| ()).map_err(wipe_error)).await
+    }
+
+    /// Attached disks, test devices included
+    #[napi]
+    pub async fn list_devices(&self) -> Result<Value> {
+        let engine = self.engine.clone();
+        let disks = blocking(move || engine.scan_devices().map_err(wipe_error)).await?;
+        Ok(Value::Array(
+            disks
+                .iter()
+                .map(|disk| json!({
+                    "path": disk.path,
+                    "description": disk.description(),
+                    "vendor": disk.vendor,
+                    "model": disk.model,
+                    "serial": disk.serial,
+                    "bus_type": disk.bus_type,
+                    "kind": disk.kind.as_str(),
+                    "virtual": disk.kind.is_virtual(),
+                    "size_bytes": disk.size_bytes,
+                    "removable": disk.removable,
+                    "is_root_disk": disk.is_root_disk,
+                    "volumes": disk.volumes,
+                    "controller": disk.controller,
+                    "namespace_id": disk.namespace_id,
+                    "requires_elevation": privileges::requires_elevation(&disk.path),
+                }))
+                .collect(),
+        ))
+    }
+
+    /// Whether the process is elevated and how it could be; devices listed by
+    /// `listDevices` carry `requires_elevation` for the same reason
+    #[napi]
+    pub fn get_privilege_status(&self) -> Result<Value> {
+        serde_json::to_value(privileges::privilege_status()).map_err(|e| Error::from_reason(e.to_string()))
+    }
+
+    /// Start the application again elevated, with the same arguments. Once
+    /// this returns the elevated copy is on its way and this one should quit.
+    #[napi]
+    pub fn request_elevation(&self) -> Result<()> {
+        privileges::relaunch_elevated().map_err(wipe_error)
+    }
+
+    /// Dry-run every safety check for wiping `device`
+    #[napi]
+    pub async fn preflight(&self, device: String, target_type: Option<String>, standard: Option<String>) -> Result<Value> {
+        let target_type: TargetType = match target_type {
+            Some(name) => serde_json::from_value(Value::String(name)).map_err(|e| invalid(e.to_string()))?,
+            None => default_target_type(),
+        };
+        let standard: SanitizationStandard = match standard {
+            Some(name) => serde_json::from_value(Value::String(name)).map_err(|e| invalid(e.to_string()))?,
+            None => default_standard(),
+        };
+        let engine = self.engine.clone();
+        let report = blocking(move || Ok(engine.preflight(&device, target_type, standard))).await?;
+        serde_json::to_value(report).map_err(|e| Error::from_reason(e.to_string()))
+    }
+
+    /// Everything that would stop the logged-in operator from wiping `device`
+    /// with `method` (a compliance profile id or a standard name), split into
+    /// blockers and warnings so a wizard can disable its start button and say why
+    #[napi]
+    pub async fn preflight_wipe(&self, device: String, method: String, target_type: Option<String>) -> Result<Value> {
+        let target_type: TargetType = match target_type {
+            Some(name) => serde_json::from_value(Value::String(name)).map_err(|e| invalid(e.to_string()))?,
+            None => default_target_type(),
+        };
+        let spec = WipeMethod::parse(&method)?.spec();
+        let engine = self.engine.clone();
+        let report = blocking(move || {
+            let operator = engine.current_operator();
+            Ok(engine.preflight_wipe_as(operator.as_ref(), &device, target_type, &spec))
+        })
+        .await?;
+        Ok(json!({
+            "device": report.target_path,
+            "method": method,
+            "can_start": report.can_proceed(),
+            "blockers": report.blocking_issues().collect::<Vec<_>>(),
+            "warnings": report.warnings().collect::<Vec<_>>(),
+        }))
+    }
+
+    /// Whether `device` is worth overwriting: its SMART wear, and `Destroy`
+    /// when the drive is likely to fail before the wipe or resale
+    #[napi]
+    pub async fn advise_wipe(&self, device: String) -> Result<Value> {
+        let engine = self.engine.clone();
+        let advisory = blocking(move || Ok(engine.advise_wipe(&device))).await?;
+        serde_json::to_value(advisory).map_err(|e| Error::from_reason(e.to_string()))
+    }
+
+    /// How long wiping `device` with `method` should take, as a range with
+    /// the rate it assumes, and a "~6h 20m" summary to show before confirming
+    #[napi]
+    pub async fn estimate_duration(&self, device: String, method: String, target_type: Option<String>) -> Result<Value> {
+        let target_type: TargetType = match target_type {
+            Some(name) => serde_json::from_value(Value::String(name)).map_err(|e| invalid(e.to_string()))?,
+            None => default_target_type(),
+        };
+        let spec = WipeMethod::parse(&method)?.spec();
+        let engine = self.engine.clone();
+        let estimate = blocking(move || engine.estimate_duration(&device, target_type, &spec).map_err(wipe_error)).await?;
+        let mut value = serde_json::to_value(&estimate).map_err(|e| Error::from_reason(e.to_string()))?;
+        value["summary"] = Value::String(estimate.summary());
+        Ok(value)
+    }
+
+    /// Run a wipe, calling `on_progress` with each progress update. Resolves
+    /// with the operation id, result and certificate path once the wipe ends.
+    #[napi(ts_args_type = "request: object, onProgress?: (progress: object) => void")]
+    pub async fn start_wipe(
+        &self,
+        request: Value,
+        on_progress: Option<ThreadsafeFunction<Value, ErrorStrategy::Fatal>>,
+    ) -> Result<Value> {
+        let operation_id = match request.get("operationId").and_then(Value::as_str) {
+            Some(id) => Uuid::parse_str(id).map_err(|e| invalid(format!("Invalid operationId: {}", e)))?,
+            None => Uuid::new_v4(),
+        };
+        let mut body: StartWipe =
+            serde_json::from_value(request).map_err(|e| invalid(format!("Invalid wipe request: {}", e)))?;
+        match body.method.as_deref().map(WipeMethod::parse).transpose()? {
+            Some(WipeMethod::Profile(profile)) => body.profile = Some(profile),
+            Some(WipeMethod::Standard(standard)) => body.standard = standard,
+            None => {}
+        }
+        let operator = self.engine.current_operator().ok_or_else(|| {
+            Error::new(Status::GenericFailure, "NotAuthenticated: Log in with login() or loginToken() first".to_string())
+        })?;
+
+        let mut request = WipeEngine::build_request(
+            body.device,
+            body.target_type,
+            body.scope,
+            body.standard,
+            body.verify,
+            body.certificate,
+        );
+        request.id = operation_id;
+        request.profile = body.profile;
+        request.confirmation = body.confirmation;
+        request.simulate = body.simulate;
+        request.skip_trim = body.skip_trim;
+        request.security_state = body.security_state;
+        request.priority = body.priority;
+        self.run_wipe(operator, request, on_progress).await
+    }
+
+    /// Wipes interrupted by a crash or power loss that are waiting to be
+    /// resumed, restarted or dismissed
+    #[napi]
+    pub fn recover_operations(&self) -> Result<Value> {
+        let interrupted = self.engine.recover_operations().map_err(wipe_error)?;
+        serde_json::to_value(interrupted).map_err(|e| Error::from_reason(e.to_string()))
+    }
+
+    /// "Resume" or "Restart" an interrupted wipe, which runs as a new
+    /// operation like `startWipe`, or "Dismiss" it; resolves with null when dismissed
+    #[napi(ts_args_type = "operationId: string, action: 'Resume' | 'Restart' | 'Dismiss', onProgress?: (progress: object) => void")]
+    pub async fn recover_operation(
+        &self,
+        operation_id: String,
+        action: String,
+        on_progress: Option<ThreadsafeFunction<Value, ErrorStrategy::Fatal>>,
+    ) -> Result<Value> {
+        let operation_id = Uuid::parse_str(&operation_id).map_err(|e| invalid(e.to_string()))?;
+        let action: RecoveryAction = serde_json::from_value(Value::String(action.clone()))
+            .map_err(|_| invalid(format!("Unknown recovery action '{}'", action)))?;
+        let operator = self.engine.current_operator().ok_or_else(|| {
+            Error::new(Status::GenericFailure, "NotAuthenticated: Log in with login() or loginToken() first".to_string())
+        })?;
+
+        let engine = self.engine.clone();
+        let recovery_operator = operator.clone();
+        let request = blocking(move || engine.prepare_recovery_as(&recovery_operator, operation_id, action).map_err(wipe_error)).await?;
+        match request {
+            Some(request) => self.run_wipe(operator, request, on_progress).await,
+            None => Ok(Value::Null),
+        }
+    }
+
+    /// Run `request`, forwarding its progress to `on_progress`, and resolve
+    /// with the operation id, result and certificate path
+    async fn run_wipe(
+        &self,
+        operator: OperatorIdentity,
+        request: WipeRequest,
+        on_progress: Option<ThreadsafeFunction<Value, ErrorStrategy::Fatal>>,
+    ) -> Result<Value> {
+        let operation_id = request.id;
+
+        // Forward this wipe's progress until it has ended and gone quiet
+        let finished = Arc::new(AtomicBool::new(false));
+        if let Some(on_progress) = on_progress {
+            let updates = self.engine.subscribe_progress();
+            let finished = finished.clone();
+            std::thread::spawn(move || loop {
+                match updates.recv_timeout(Duration::from_millis(200)) {
+                    Ok(update) if update.request_id == operation_id => {
+                        if let Ok(update) = serde_json::to_value(update) {
+                            on_progress.call(update, ThreadsafeFunctionCallMode::NonBlocking);
+                        }
+                    }
+                    Ok(_) => {}
+                    Err(RecvTimeoutError::Timeout) if !finished.load(Ordering::SeqCst) => {}
+                    Err(_) => break,
+                }
+            });
+        }
+
+        let engine = self.engine.clone();
+        let outcome = blocking(move || engine.execute_request_as(&operator, request).map_err(wipe_error)).await;
+        finished.store(true, Ordering::SeqCst);
+        let (result, certificate_path) = outcome?;
+        Ok(json!({
+            "operation_id": operation_id,
+            "result": result,
+            "certificate_path": certificate_path,
+        }))
+    }
+
+    /// Ask a running wipe to stop
+    #[napi]
+    pub fn cancel(&self, operation_id: String) -> Result<()> {
+        let operation_id = Uuid::parse_str(&operation_id).map_err(|e| invalid(e.to_string()))?;
+        self.engine.cancel_wipe(operation_id).map_err(wipe_error)
+    }
+
+    /// Pause a running wipe at its next block boundary, with its writes
+    /// flushed; resolves with where it stopped
+    #[napi]
+    pub async fn pause_operation(&self, operation_id: String) -> Result<Value> {
+        let operation_id = Uuid::parse_str(&operation_id).map_err(|e| invalid(e.to_string()))?;
+        let engine = self.engine.clone();
+        let checkpoint = blocking(move || engine.pause_operation(operation_id).map_err(wipe_error)).await?;
+        serde_json::to_value(checkpoint).map_err(|e| Error::from_reason(e.to_string()))
+    }
+
+    #[napi]
+    pub fn resume_operation(&self, operation_id: String) -> Result<()> {
+        let operation_id = Uuid::parse_str(&operation_id).map_err(|e| invalid(e.to_string()))?;
+        self.engine.resume_operation(operation_id).map_err(wipe_error)
+    }
+
+    /// What happened during an operation so far, oldest first
+    #[napi]
+    pub fn get_operation_events(&self, operation_id: String) -> Result<Value> {
+        let operation_id = Uuid::parse_str(&operation_id).map_err(|e| invalid(e.to_string()))?;
+        let events = self.engine.get_operation_events(operation_id).map_err(wipe_error)?;
+        serde_json::to_value(events).map_err(|e| Error::from_reason(e.to_string()))
+    }
+
+    /// Contents of the report backup directory and files that no longer match its manifest
+    #[napi]
+    pub fn get_backup_status(&self) -> Result<Value> {
+        let status = self.engine.get_backup_status().map_err(wipe_error)?;
+        serde_json::to_value(status).map_err(|e| Error::from_reason(e.to_string()))
+    }
+
+    /// Devices in use by a running wipe, verification or scan
+    #[napi]
+    pub fn locked_devices(&self) -> Result<Value> {
+        serde_json::to_value(self.engine.locked_devices()).map_err(|e| Error::from_reason(e.to_string()))
+    }
+
+    /// The operation using `device` (a stable device id or a path), or null
+    #[napi]
+    pub fn device_lock(&self, device: String) -> Result<Value> {
+        serde_json::to_value(self.engine.device_lock(&device)).map_err(|e| Error::from_reason(e.to_string()))
+    }
+
+    /// Past operations, newest first
+    #[napi]
+    pub fn history(&self, device: Option<String>, limit: Option<u32>) -> Result<Value> {
+        let filter = HistoryFilter {
+            target_path: device,
+            limit: limit.map(|limit| limit as usize),
+            ..HistoryFilter::default()
+        };
+        serde_json::to_value(self.engine.list_operations_history(&filter)).map_err(|e| Error::from_reason(e.to_string()))
+    }
+
+    /// Certificates of past wipes, newest first, from every session rather
+    /// than only this one; `filter` may hold device, serial, status, since,
+    /// until, operator and limit
+    #[napi(ts_args_type = "filter?: ReportFilter")]
+    pub fn list_reports(&self, filter: Option<Value>) -> Result<Value> {
+        let filter: ReportFilter = filter
+            .map(serde_json::from_value)
+            .transpose()
+            .map_err(|e| invalid(format!("Invalid report filter: {}", e)))?
+            .unwrap_or_default();
+        serde_json::to_value(self.engine.list_reports(&filter.into())).map_err(|e| Error::from_reason(e.to_string()))
+    }
+
+    /// One certificate from `listReports` with the operation it was issued for
+    #[napi]
+    pub async fn open_report(&self, report_id: String) -> Result<Value> {
+        let engine = self.engine.clone();
+        let report = blocking(move || engine.open_report(&report_id).map_err(wipe_error)).await?;
+        serde_json::to_value(report).map_err(|e| Error::from_reason(e.to_string()))
+    }
+
+    /// Verify a signed JSON certificate against the operation history
+    #[napi]
+    pub async fn verify_certificate(&self, certificate_path: String) -> Result<Value> {
+        let engine = self.engine.clone();
+        let verdict = blocking(move || engine.verify_certificate(&certificate_path).map_err(wipe_error)).await?;
+        serde_json::to_value(verdict).map_err(|e| Error::from_reason(e.to_string()))
+    }
+
+    /// Read a finished wipe's device back again and compare it with what the
+    /// last pass wrote; `policy` is a verification policy such as "FullScan"
+    #[napi(ts_args_type = "operationId: string, policy?: string | object")]
+    pub async fn reverify_operation(&self, operation_id: String, policy: Option<Value>) -> Result<Value> {
+        let operation_id = Uuid::parse_str(&operation_id).map_err(|e| invalid(e.to_string()))?;
+        let policy: Option<VerificationPolicy> = policy
+            .map(serde_json::from_value)
+            .transpose()
+            .map_err(|e| invalid(format!("Invalid verification policy: {}", e)))?;
+        let engine = self.engine.clone();
+        let report = blocking(move || engine.reverify_operation(operation_id, policy).map_err(wipe_error)).await?;
+        serde_json::to_value(report).map_err(|e| Error::from_reason(e.to_string()))
+    }
+
+    /// Render the report of an operation as "pdf", "json", "csv" or "xml" to
+    /// `path`, typically one picked in a save dialog; resolves with the path written
+    #[napi]
+    pub async fn export_report(&self, operation_id: String, format: String, path: String) -> Result<String> {
+        let operation_id = Uuid::parse_str(&operation_id).map_err(|e| invalid(e.to_string()))?;
+        let format = ReportFormat::parse(&format).ok_or_else(|| invalid(format!("Unknown report format '{}'", format)))?;
+        let operator = self.engine.current_operator().ok_or_else(|| {
+            Error::new(Status::GenericFailure, "NotAuthenticated: Log in with login() or loginToken() first".to_string())
+        })?;
+        let engine = self.engine.clone();
+        let written = blocking(move || {
+            engine.export_report_as(&operator, operation_id, format, std::path::Path::new(&path)).map_err(wipe_error)
+        })
+        .await?;
+        Ok(written.display().to_string())
+    }
+}
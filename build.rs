@@ -0,0 +1,26 @@
+fn main() {
+    // The gRPC service is generated from proto/ only when the `grpc` feature is on
+    #[cfg(feature = "grpc")]
+    {
+        println!("cargo:rerun-if-changed=proto/wipe_station.proto");
+        let protoc = protoc_bin_vendored::protoc_bin_path().expect("vendored protoc is unavailable for this platform");
+        // SAFETY: build scripts are single-threaded at this point
+        unsafe { std::env::set_var("PROTOC", protoc) };
+        tonic_build::compile_protos("proto/wipe_station.proto").expect("failed to compile proto/wipe_station.proto");
+    }
+
+    // The C header for the `ffi` feature is regenerated from src/ffi.rs
+    #[cfg(feature = "ffi")]
+    {
+        println!("cargo:rerun-if-changed=src/ffi.rs");
+        println!("cargo:rerun-if-changed=cbindgen.toml");
+        let crate_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
+        let config = cbindgen::Config::from_file(format!("{}/cbindgen.toml", crate_dir)).expect("failed to read cbindgen.toml");
+        cbindgen::Builder::new()
+            .with_src(format!("{}/src/ffi.rs", crate_dir))
+            .with_config(config)
+            .generate()
+            .expect("failed to generate the C header")
+            .write_to_file(format!("{}/include/hdd_tool.h", crate_dir));
+    }
+}
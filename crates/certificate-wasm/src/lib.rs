@@ -0,0 +1,32 @@
+//! Certificate verification for web portals
+//!
+//! ```text
+//! wasm-pack build crates/certificate-wasm --target web
+//! ```
+//!
+//! ```js
+//! import init, { verifyCertificate } from './hdd_tool_certificate_wasm.js'
+//! await init()
+//! const verdict = verifyCertificate(await file.text())
+//! if (!verdict.valid) console.warn(verdict.issues)
+//! ```
+
+use wasm_bindgen::prelude::*;
+
+/// Verify a signed JSON certificate. Returns the verification as an object
+/// with a `valid` flag; throws if the text is not a certificate.
+#[wasm_bindgen(js_name = verifyCertificate)]
+pub fn verify_certificate(certificate_json: &str) -> Result<JsValue, JsError> {
+    let verification = hdd_tool_certificate::verify_json(certificate_json).map_err(|e| JsError::new(&e.message))?;
+    let mut value = serde_json::to_value(&verification).map_err(|e| JsError::new(&e.to_string()))?;
+    value["valid"] = serde_json::Value::Bool(verification.is_valid());
+    js_sys::JSON::parse(&value.to_string()).map_err(|_| JsError::new("Failed to build verification object"))
+}
+
+/// Canonical JSON body that version 2.0 certificates sign
+#[wasm_bindgen(js_name = canonicalJson)]
+pub fn canonical_json(certificate_json: &str) -> Result<String, JsError> {
+    let certificate: hdd_tool_certificate::ErasureCertificate =
+        serde_json::from_str(certificate_json).map_err(|e| JsError::new(&e.to_string()))?;
+    hdd_tool_certificate::canonical_json(&certificate).map_err(|e| JsError::new(&e.message))
+}
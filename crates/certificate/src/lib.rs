@@ -0,0 +1,20 @@
+//! Erasure certificate schema and offline verification
+//!
+//! The certificate types written by the wipe engine, together with the hash
+//! and signature checks that need nothing but the certificate itself. The
+//! crate is `no_std` (it needs `alloc`), so web portals can verify
+//! certificates in the browser without shipping the engine; the
+//! `certificate-wasm` crate wraps it for JavaScript.
+//!
+//! Cross-checking a certificate against the operation history needs the
+//! engine and stays in `hdd_tool::security`.
+
+#![cfg_attr(not(test), no_std)]
+
+extern crate alloc;
+
+pub mod schema;
+pub mod verify;
+
+pub use schema::*;
+pub use verify::*;
@@ -0,0 +1,243 @@
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Schema version written into newly issued certificates.
+/// Version 1.0 certificates sign a pipe-delimited field list, version 2.0
+/// certificates sign the canonical JSON encoding of the certificate body.
+pub const CERTIFICATE_SCHEMA_VERSION: &str = "2.0";
+
+/// Certificate schema versions this build knows how to verify
+pub const SUPPORTED_SCHEMA_VERSIONS: &[&str] = &["1.0", "2.0"];
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub enum SignatureAlgorithm {
+    // Certificates and CA files written before the algorithm was recorded are RSA
+    #[default]
+    RsaPkcs1Sha256,
+    Ed25519,
+}
+
+fn legacy_schema_version() -> String {
+    "1.0".to_string()
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ErasureCertificate {
+    #[serde(default = "legacy_schema_version")]
+    pub schema_version: String,
+    pub certificate_id: Uuid,
+    pub wipe_request_id: Uuid,
+    pub issued_at: DateTime<Utc>,
+    pub issuer: String,
+    pub organization: String,
+    #[serde(default)]
+    pub operator: String,
+    pub drive_info: DriveInfo,
+    pub wipe_details: WipeDetails,
+    pub verification_details: VerificationDetails,
+    pub compliance_standards: Vec<String>,
+    /// Compliance profile the wipe followed, with its citation and statement
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub compliance_profile: Option<ComplianceStatement>,
+    /// Name and fingerprint of the user-defined recipe the wipe followed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub recipe: Option<RecipeReference>,
+    /// Name and fingerprint of the branding template the PDF was printed with
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub template: Option<TemplateReference>,
+    /// Drive state sampled before and after the wipe
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub evidence: Option<ErasureEvidence>,
+    /// Post-wipe check that the drive still works
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub functional_test: Option<FunctionalTest>,
+    /// Image of the device taken before the wipe, when evidence had to be kept
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub forensic_image: Option<ForensicImage>,
+    /// Issued for a simulated wipe; no device was erased
+    #[serde(default, skip_serializing_if = "core::ops::Not::not")]
+    pub simulated: bool,
+    #[serde(default)]
+    pub signature_algorithm: SignatureAlgorithm,
+    pub signature: String,
+    pub public_key: String,
+    /// SHA-256 of the signing public key, for matching against a trusted key list offline
+    #[serde(default)]
+    pub key_fingerprint: String,
+    pub certificate_hash: String,
+}
+
+/// Profile details as recorded in certificates and reports
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ComplianceStatement {
+    /// Profile id as accepted by `--profile`, e.g. "nist-800-88-purge"
+    pub profile: String,
+    pub name: String,
+    pub citation: String,
+    pub statement: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RecipeReference {
+    pub name: String,
+    /// SHA-256 of the parsed recipe
+    pub fingerprint: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TemplateReference {
+    pub name: String,
+    /// SHA-256 of the template settings and logo
+    pub fingerprint: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DriveInfo {
+    pub model: String,
+    pub serial_number: String,
+    pub firmware_version: String,
+    pub total_capacity_gb: f64,
+    pub native_capacity_gb: f64,
+    pub had_hpa: bool,
+    pub had_dco: bool,
+    pub security_features: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WipeDetails {
+    pub standard_used: String,
+    pub passes_completed: u32,
+    pub sectors_wiped: u64,
+    pub start_time: DateTime<Utc>,
+    pub completion_time: DateTime<Utc>,
+    pub duration_minutes: u64,
+    pub patterns_used: Vec<String>,
+    /// Partition or sector range wiped; absent for whole-device wipes
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub scope: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VerificationDetails {
+    pub verification_performed: bool,
+    pub sectors_verified: u64,
+    pub verification_sample_rate: f64,
+    pub pattern_verification_passed: bool,
+    pub failed_sectors: u64,
+    pub verification_time: Option<DateTime<Utc>>,
+}
+
+/// Drive state before and after a wipe, so the certificate shows the change
+/// it attests to rather than only claiming it
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ErasureEvidence {
+    pub before: DriveSnapshot,
+    pub after: DriveSnapshot,
+    /// Read-back of the wiped area; absent when verification did not run
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub verification: Option<VerificationSampling>,
+}
+
+/// What sampling a drive found at one point in time
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DriveSnapshot {
+    pub captured_at: DateTime<Utc>,
+    /// "MBR" or "GPT"; absent when no partition table was found
+    pub partition_table: Option<String>,
+    pub partitions: Vec<PartitionSummary>,
+    /// Filesystem and encryption headers found at the start of the drive and of each partition
+    pub signatures: Vec<SignatureFound>,
+    pub samples: u64,
+    pub bytes_sampled: u64,
+    /// Fractions of samples that were all zeros, a uniform fill, random-looking or structured
+    pub zero_fraction: f64,
+    pub uniform_fraction: f64,
+    pub high_entropy_fraction: f64,
+    pub structured_fraction: f64,
+    /// Mean Shannon entropy of the samples in bits per byte
+    pub mean_entropy: f64,
+    /// e.g. "Contains user data" or "Blank"
+    pub classification: String,
+    /// Absent for simulated wipes and drives that report no SMART data
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub smart: Option<SmartSummary>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PartitionSummary {
+    pub number: u32,
+    pub offset: u64,
+    pub size_bytes: u64,
+    pub name: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SignatureFound {
+    pub name: String,
+    pub offset: u64,
+    pub encrypted: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SmartSummary {
+    pub health: String,
+    pub power_on_hours: Option<u64>,
+    pub reallocated_sectors: Option<u64>,
+    pub pending_sectors: Option<u64>,
+    pub percent_used: Option<u8>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct VerificationSampling {
+    pub policy: String,
+    pub bytes_verified: u64,
+    /// Fraction of the wiped area read back
+    pub coverage: f64,
+    pub regions: u64,
+    pub failed_regions: u64,
+    /// Mean Shannon entropy of the regions read back in bits per byte
+    pub mean_entropy: f64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum FunctionalTestKind {
+    /// The drive's own short self-test (ATA SMART or NVMe device self-test)
+    ShortSelfTest,
+    /// Every sector of the wiped area read back
+    SurfaceScan,
+}
+
+/// Whether the drive still worked after the wipe, for drives that are resold
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FunctionalTest {
+    pub kind: FunctionalTestKind,
+    pub passed: bool,
+    pub completed_at: DateTime<Utc>,
+    /// e.g. "Completed without error" or "3 unreadable sectors"
+    pub detail: String,
+    /// Surface scans only
+    #[serde(default)]
+    pub bytes_read: u64,
+    #[serde(default)]
+    pub unreadable_sectors: u64,
+}
+
+/// Compressed image of the device taken before it was wiped, so the
+/// certificate ties the destroyed data to the copy that was kept
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ForensicImage {
+    /// Where the image was written, e.g. a file path or "s3://bucket/key"
+    pub location: String,
+    pub completed_at: DateTime<Utc>,
+    pub bytes_imaged: u64,
+    /// SHA-256 of the device contents as read
+    pub source_sha256: String,
+    /// SHA-256 of the compressed image file
+    pub image_sha256: String,
+    /// Sectors that could not be read and are zero in the image
+    #[serde(default)]
+    pub unreadable_sectors: u64,
+}
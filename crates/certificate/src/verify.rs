@@ -0,0 +1,288 @@
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::fmt;
+use base64::{Engine as _, engine::general_purpose};
+use rsa::RsaPublicKey;
+use rsa::signature::Verifier;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use uuid::Uuid;
+
+use crate::schema::{ErasureCertificate, SignatureAlgorithm, SUPPORTED_SCHEMA_VERSIONS};
+
+/// A certificate that could not be parsed or decoded far enough to check
+#[derive(Debug, Clone, PartialEq)]
+pub struct CertificateError {
+    pub message: String,
+}
+
+impl fmt::Display for CertificateError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl CertificateError {
+    fn new(message: String) -> Self {
+        Self { message }
+    }
+}
+
+/// Outcome of the checks that need nothing but the certificate itself
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Verification {
+    pub certificate_id: Uuid,
+    pub schema_version: String,
+    pub schema_supported: bool,
+    pub hash_valid: bool,
+    pub signature_valid: bool,
+    pub key_fingerprint: String,
+    pub serial_number: String,
+    pub simulated: bool,
+    pub issues: Vec<String>,
+}
+
+impl Verification {
+    pub fn is_valid(&self) -> bool {
+        self.schema_supported && self.hash_valid && self.signature_valid
+    }
+}
+
+/// SHA-256 fingerprint of a public key as it is embedded in certificates
+pub fn key_fingerprint(public_key: &str) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(public_key.as_bytes());
+    hex::encode(hasher.finalize())
+}
+
+/// Canonical JSON encoding of a certificate body: signature and hash are
+/// blanked, and serde_json's default map keeps object keys sorted, so the
+/// same certificate always produces the same bytes.
+pub fn canonical_json(certificate: &ErasureCertificate) -> Result<String, CertificateError> {
+    let mut unsigned = certificate.clone();
+    unsigned.signature = String::new();
+    unsigned.certificate_hash = String::new();
+
+    let value = serde_json::to_value(&unsigned)
+        .map_err(|e| CertificateError::new(format!("Failed to canonicalize certificate: {}", e)))?;
+
+    Ok(value.to_string())
+}
+
+/// The bytes whose hash is signed, as laid down by the certificate's schema version
+pub fn signing_content(cert: &ErasureCertificate) -> Result<Vec<u8>, CertificateError> {
+    if cert.schema_version != "1.0" {
+        return canonical_json(cert).map(String::into_bytes);
+    }
+
+    let signing_content = format!(
+        "{}|{}|{}|{}|{}|{}|{}|{}|{}|{}|{}|{}|{}|{}|{}|{}|{}|{}",
+        cert.certificate_id,
+        cert.wipe_request_id,
+        cert.issued_at.to_rfc3339(),
+        cert.issuer,
+        cert.organization,
+        cert.drive_info.serial_number,
+        cert.drive_info.model,
+        cert.wipe_details.standard_used,
+        cert.wipe_details.passes_completed,
+        cert.wipe_details.sectors_wiped,
+        cert.wipe_details.start_time.to_rfc3339(),
+        cert.wipe_details.completion_time.to_rfc3339(),
+        cert.verification_details.verification_performed,
+        cert.verification_details.sectors_verified,
+        cert.verification_details.pattern_verification_passed,
+        cert.verification_details.failed_sectors,
+        cert.compliance_standards.join(","),
+        cert.public_key
+    );
+
+    Ok(signing_content.into_bytes())
+}
+
+/// SHA-256 of the signing content; this is what the issuer signs
+pub fn content_hash(certificate: &ErasureCertificate) -> Result<[u8; 32], CertificateError> {
+    let mut hasher = Sha256::new();
+    hasher.update(signing_content(certificate)?);
+    Ok(hasher.finalize().into())
+}
+
+/// Check that the recorded certificate hash matches the certificate content
+pub fn verify_hash(certificate: &ErasureCertificate) -> Result<bool, CertificateError> {
+    Ok(hex::encode(content_hash(certificate)?) == certificate.certificate_hash)
+}
+
+/// Check the signature against the public key embedded in the certificate
+pub fn verify_signature(certificate: &ErasureCertificate) -> Result<bool, CertificateError> {
+    let content_hash = content_hash(certificate)?;
+
+    let signature_bytes = general_purpose::STANDARD.decode(&certificate.signature)
+        .map_err(|e| CertificateError::new(format!("Failed to decode signature: {}", e)))?;
+
+    // The fingerprint must describe the embedded key, otherwise it was swapped
+    if !certificate.key_fingerprint.is_empty()
+        && certificate.key_fingerprint != key_fingerprint(&certificate.public_key)
+    {
+        return Ok(false);
+    }
+
+    if certificate.signature_algorithm == SignatureAlgorithm::Ed25519 {
+        let public_key = general_purpose::STANDARD.decode(&certificate.public_key)
+            .map_err(|e| CertificateError::new(format!("Failed to decode public key: {}", e)))?;
+        let Ok(public_key) = <[u8; 32]>::try_from(public_key.as_slice()) else {
+            return Ok(false);
+        };
+        let Ok(verifying_key) = ed25519_dalek::VerifyingKey::from_bytes(&public_key) else {
+            return Ok(false);
+        };
+        let Ok(signature) = ed25519_dalek::Signature::from_slice(&signature_bytes) else {
+            return Ok(false);
+        };
+        return Ok(verifying_key.verify(&content_hash, &signature).is_ok());
+    }
+
+    let public_key: RsaPublicKey = rsa::pkcs8::DecodePublicKey::from_public_key_pem(&certificate.public_key)
+        .map_err(|e| CertificateError::new(format!("Failed to decode public key: {}", e)))?;
+
+    let verifying_key = rsa::pkcs1v15::VerifyingKey::<Sha256>::new_unprefixed(public_key);
+    let signature = rsa::pkcs1v15::Signature::try_from(signature_bytes.as_slice())
+        .map_err(|e| CertificateError::new(format!("Invalid signature format: {}", e)))?;
+
+    Ok(verifying_key.verify(&content_hash, &signature).is_ok())
+}
+
+/// Run every self-contained check on `certificate`
+pub fn verify(certificate: &ErasureCertificate) -> Verification {
+    let mut issues = Vec::new();
+
+    let schema_supported = SUPPORTED_SCHEMA_VERSIONS.contains(&certificate.schema_version.as_str());
+    if !schema_supported {
+        issues.push(format!("Unsupported certificate schema version {}", certificate.schema_version));
+    }
+
+    let hash_valid = verify_hash(certificate).unwrap_or(false);
+    if !hash_valid {
+        issues.push("Certificate hash does not match certificate content".to_string());
+    }
+
+    let signature_valid = match verify_signature(certificate) {
+        Ok(valid) => valid,
+        Err(e) => {
+            issues.push(e.message);
+            false
+        }
+    };
+    if !signature_valid {
+        issues.push("Signature verification failed".to_string());
+    }
+    // Authentic, but it attests to a simulation, not an erased device
+    if certificate.simulated {
+        issues.push("Issued for a simulated wipe; no device was erased".to_string());
+    }
+
+    Verification {
+        certificate_id: certificate.certificate_id,
+        schema_version: certificate.schema_version.clone(),
+        schema_supported,
+        hash_valid,
+        signature_valid,
+        key_fingerprint: certificate.key_fingerprint.clone(),
+        serial_number: certificate.drive_info.serial_number.clone(),
+        simulated: certificate.simulated,
+        issues,
+    }
+}
+
+/// Parse a signed JSON certificate and verify it
+pub fn verify_json(certificate_json: &str) -> Result<Verification, CertificateError> {
+    let certificate: ErasureCertificate = serde_json::from_str(certificate_json)
+        .map_err(|e| CertificateError::new(format!("Failed to parse certificate: {}", e)))?;
+    Ok(verify(&certificate))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schema::*;
+    use alloc::vec;
+    use chrono::{TimeZone, Utc};
+    use ed25519_dalek::Signer;
+
+    fn signed_certificate() -> ErasureCertificate {
+        let signing_key = ed25519_dalek::SigningKey::from_bytes(&[7u8; 32]);
+        let public_key = general_purpose::STANDARD.encode(signing_key.verifying_key().as_bytes());
+        let at = Utc.with_ymd_and_hms(2025, 3, 1, 12, 0, 0).unwrap();
+        let mut certificate = ErasureCertificate {
+            schema_version: CERTIFICATE_SCHEMA_VERSION.to_string(),
+            certificate_id: Uuid::from_u128(1),
+            wipe_request_id: Uuid::from_u128(2),
+            issued_at: at,
+            issuer: "Test CA".to_string(),
+            organization: "Test".to_string(),
+            operator: "operator".to_string(),
+            drive_info: DriveInfo {
+                model: "ST4000DM004".to_string(),
+                serial_number: "ZFN0ABCD".to_string(),
+                firmware_version: "0001".to_string(),
+                total_capacity_gb: 3726.02,
+                native_capacity_gb: 3726.02,
+                had_hpa: false,
+                had_dco: false,
+                security_features: vec!["Basic".to_string()],
+            },
+            wipe_details: WipeDetails {
+                standard_used: "NIST_SP_800_88_R1".to_string(),
+                passes_completed: 1,
+                sectors_wiped: 7_814_037_168,
+                start_time: at,
+                completion_time: at,
+                duration_minutes: 0,
+                patterns_used: vec!["Single pass zeros".to_string()],
+                scope: None,
+            },
+            verification_details: VerificationDetails {
+                verification_performed: false,
+                sectors_verified: 0,
+                verification_sample_rate: 0.0,
+                pattern_verification_passed: false,
+                failed_sectors: 0,
+                verification_time: None,
+            },
+            compliance_standards: vec!["NIST SP 800-88 Rev. 1".to_string()],
+            compliance_profile: None,
+            recipe: None,
+            template: None,
+            evidence: None,
+            functional_test: None,
+            forensic_image: None,
+            simulated: false,
+            signature_algorithm: SignatureAlgorithm::Ed25519,
+            signature: String::new(),
+            key_fingerprint: key_fingerprint(&public_key),
+            public_key,
+            certificate_hash: String::new(),
+        };
+        let hash = content_hash(&certificate).unwrap();
+        certificate.certificate_hash = hex::encode(hash);
+        certificate.signature = general_purpose::STANDARD.encode(signing_key.sign(&hash).to_bytes());
+        certificate
+    }
+
+    #[test]
+    fn test_signed_certificate_verifies_and_tampering_is_caught() {
+        let certificate = signed_certificate();
+        let json = serde_json::to_string(&certificate).unwrap();
+        assert!(verify_json(&json).unwrap().is_valid());
+
+        let mut tampered = certificate.clone();
+        tampered.drive_info.serial_number = "ZFN0WXYZ".to_string();
+        let verdict = verify(&tampered);
+        assert!(!verdict.hash_valid);
+        assert!(!verdict.signature_valid);
+
+        let mut swapped = certificate;
+        swapped.key_fingerprint = key_fingerprint("another key");
+        assert!(!verify_signature(&swapped).unwrap());
+    }
+}
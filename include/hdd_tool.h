@@ -0,0 +1,129 @@
+#ifndef HDD_TOOL_H
+#define HDD_TOOL_H
+
+/* Generated by cbindgen from src/ffi.rs; do not edit. */
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+// Cause of the last failure, for front ends to branch on
+typedef enum SafewipeErrorCode {
+  // The last call succeeded
+  SAFEWIPE_ERROR_CODE_NONE = 0,
+  SAFEWIPE_ERROR_CODE_DEVICE_NOT_FOUND = 1,
+  // The process lacks the privileges to open or command the device
+  SAFEWIPE_ERROR_CODE_PERMISSION_DENIED = 2,
+  SAFEWIPE_ERROR_CODE_NOT_AUTHENTICATED = 3,
+  // The drive's security state (locked or frozen) refuses the command
+  SAFEWIPE_ERROR_CODE_SECURITY_FROZEN = 4,
+  // The device is mounted or in use by another operation
+  SAFEWIPE_ERROR_CODE_BUSY = 5,
+  SAFEWIPE_ERROR_CODE_VERIFICATION_FAILED = 6,
+  SAFEWIPE_ERROR_CODE_UNSUPPORTED = 7,
+  SAFEWIPE_ERROR_CODE_CANCELLED = 8,
+  // Refused by the target policy, a role or a missing approval
+  SAFEWIPE_ERROR_CODE_POLICY_DENIED = 9,
+  SAFEWIPE_ERROR_CODE_INVALID_ARGUMENT = 10,
+  SAFEWIPE_ERROR_CODE_NOT_FOUND = 11,
+  SAFEWIPE_ERROR_CODE_HARDWARE_FAILURE = 12,
+  SAFEWIPE_ERROR_CODE_INTERNAL = 13,
+} SafewipeErrorCode;
+
+// Outcome of a call that does not return a pointer
+typedef enum SafewipeStatus {
+  SAFEWIPE_STATUS_OK = 0,
+  // A pointer was NULL, a string was not UTF-8 or JSON did not parse
+  SAFEWIPE_STATUS_INVALID_ARGUMENT = 1,
+  // Log in with `safewipe_login` or `safewipe_login_token` first
+  SAFEWIPE_STATUS_NOT_AUTHENTICATED = 2,
+  SAFEWIPE_STATUS_NOT_FOUND = 3,
+  // The engine refused or failed the operation
+  SAFEWIPE_STATUS_FAILED = 4,
+  // The library panicked; the client should be freed and recreated
+  SAFEWIPE_STATUS_PANICKED = 5,
+} SafewipeStatus;
+
+// Opaque handle to an engine and the wipes started through it
+typedef struct SafewipeClient SafewipeClient;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+// Create a client from a `WipeConfiguration` as JSON. Returns NULL on failure.
+//
+// # Safety
+// `config_json` must be NULL or a NUL-terminated string.
+struct SafewipeClient *safewipe_client_new(const char *config_json);
+
+// Free a client. Wipes still running finish in the background.
+//
+// # Safety
+// `client` must be NULL or a pointer returned by `safewipe_client_new` that
+// has not been freed, and no other thread may be using it.
+void safewipe_client_free(struct SafewipeClient *client);
+
+// Log in as an operator; wipes are recorded under this operator
+//
+// # Safety
+// `client` must be a live client, `username` and `password` NUL-terminated strings.
+enum SafewipeStatus safewipe_login(const struct SafewipeClient *client,
+                                   const char *username,
+                                   const char *password);
+
+// Log in with an API token issued by an administrator
+//
+// # Safety
+// `client` must be a live client and `token` a NUL-terminated string.
+enum SafewipeStatus safewipe_login_token(const struct SafewipeClient *client, const char *token);
+
+// Attached disks as a JSON array. Free the result with `safewipe_string_free`.
+//
+// # Safety
+// `client` must be a live client.
+char *safewipe_list_devices_json(const struct SafewipeClient *client);
+
+// Start a wipe described by `request_json`, e.g.
+// `{"device":"/dev/sdb","target_type":"HDD","standard":"NIST_SP_800_88_R1"}`.
+// Returns the operation id; free it with `safewipe_string_free`.
+//
+// # Safety
+// `client` must be a live client and `request_json` a NUL-terminated string.
+char *safewipe_start_wipe(const struct SafewipeClient *client, const char *request_json);
+
+// State of a wipe started by this client as JSON: `status` (running,
+// verifying, completed, failed, cancelled), the latest `progress`, and once finished
+// `result`, `certificate_path`, `error` and `error_code`. Free it with
+// `safewipe_string_free`.
+//
+// # Safety
+// `client` must be a live client and `operation_id` a NUL-terminated string.
+char *safewipe_poll_progress(const struct SafewipeClient *client, const char *operation_id);
+
+// Ask a running wipe to stop
+//
+// # Safety
+// `client` must be a live client and `operation_id` a NUL-terminated string.
+enum SafewipeStatus safewipe_cancel(const struct SafewipeClient *client, const char *operation_id);
+
+// Description of the last failure on the calling thread, or NULL. Free it
+// with `safewipe_string_free`.
+char *safewipe_last_error(void);
+
+// Cause of the last failure on the calling thread, or
+// `SAFEWIPE_ERROR_CODE_NONE` when the last call succeeded
+enum SafewipeErrorCode safewipe_last_error_code(void);
+
+// Free a string returned by the library
+//
+// # Safety
+// `value` must be NULL or a string returned by this library that has not been freed.
+void safewipe_string_free(char *value);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* HDD_TOOL_H */
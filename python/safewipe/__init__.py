@@ -0,0 +1,9 @@
+"""Python bindings for the HDD Tool wipe engine.
+
+See ``src/python.rs`` for the API; ``Client`` wraps one engine configured
+from a JSON configuration file.
+"""
+
+from ._native import Client, WipeError, __version__
+
+__all__ = ["Client", "WipeError", "__version__"]
@@ -0,0 +1,42 @@
+from typing import Any, Callable, Optional
+
+__version__: str
+
+class WipeError(Exception):
+    """Raised with (code, message) when the engine refuses or fails an operation."""
+
+class Client:
+    def __init__(self, config_path: str) -> None: ...
+    def login(self, username: str, password: str) -> None: ...
+    def login_token(self, token: str) -> None: ...
+    def scan(self) -> list[dict[str, Any]]: ...
+    def preflight(self, device: str, target_type: str = "HDD", standard: str = "NIST_SP_800_88_R1") -> dict[str, Any]: ...
+    def start_wipe(
+        self,
+        device: str,
+        target_type: str = "HDD",
+        standard: str = "NIST_SP_800_88_R1",
+        *,
+        scope: Optional[Any] = None,
+        verify: bool = True,
+        certificate: bool = True,
+        profile: Optional[str] = None,
+        confirmation: Optional[str] = None,
+        simulate: bool = False,
+        skip_trim: bool = False,
+        security_state: Optional[str] = None,
+        priority: str = "Normal",
+        progress: Optional[Callable[[dict[str, Any]], None]] = None,
+    ) -> dict[str, Any]: ...
+    def cancel(self, operation_id: str) -> None: ...
+    def pause_operation(self, operation_id: str) -> dict[str, Any]: ...
+    def resume_operation(self, operation_id: str) -> None: ...
+    def operation(self, operation_id: str) -> Optional[dict[str, Any]]: ...
+    def operation_events(self, operation_id: str) -> list[dict[str, Any]]: ...
+    def backup_status(self) -> dict[str, Any]: ...
+    def history(self, device: Optional[str] = None, limit: Optional[int] = None) -> list[dict[str, Any]]: ...
+    def list_reports(
+        self, device: Optional[str] = None, serial: Optional[str] = None, limit: Optional[int] = None
+    ) -> list[dict[str, Any]]: ...
+    def open_report(self, report_id: str) -> dict[str, Any]: ...
+    def report(self, certificate_path: str) -> dict[str, Any]: ...
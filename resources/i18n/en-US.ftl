@@ -0,0 +1,241 @@
+# Messages shown by hdd-tool: the CLI, the GUI and printed certificates.
+# Every catalogue defines the same message ids; keep them in step.
+
+language-name = English
+
+## Command line
+
+cli-usage = Usage:
+cli-commands = Commands:
+cli-help-scan = List the attached drives
+cli-help-verify-cert = Verify a signed erasure certificate
+cli-help-shred = Securely delete files and folders
+cli-help-wipe = Wipe devices to a compliance profile or recipe
+cli-help-wipe-all = Preview, then purge, every drive except the system disk, to decommission a machine
+cli-help-psid-revert = Factory-reset a self-encrypting drive with the PSID from its label
+cli-help-schedule = Wipe devices later, inside the configured maintenance windows
+cli-help-schedule-manage = Show, resume or cancel scheduled wipes
+cli-help-export = Bundle an operation's certificate and evidence for the customer
+cli-help-export-report = Write reports as PDF, JSON, CSV or XML; CSV and XML can cover a time range
+cli-help-profiles = List the compliance profiles
+cli-help-watch = Follow the progress of wipes running in another process
+cli-help-tui = Pick drives and watch wipes in an interactive terminal
+cli-help-serve = Serve the wipe engine API for remote control
+cli-help-station = Run jobs queued for this station on the fleet server
+cli-help-help = Show this message
+cli-help-json = --output json prints scan, profiles, verify-cert, shred, wipe, psid-revert, schedule and export results as JSON.
+cli-help-exit-codes = Exit codes: 0 success, 1 failed, 2 bad arguments, 3 refused, 4 cancelled.
+cli-help-gui = Run without a command to start the graphical interface.
+
+cli-cert-header = Certificate: { $id }
+cli-cert-schema = Schema version
+cli-cert-hash = Content hash
+cli-cert-signature = Signature
+cli-cert-fingerprint = Key fingerprint
+cli-cert-serial = Drive serial
+cli-cert-history = History
+cli-cert-history-matched = operation { $operation }
+cli-cert-history-mismatch = recorded serial { $serial }
+cli-cert-history-not-found = not found
+cli-cert-history-unavailable = unavailable
+cli-cert-valid = Certificate is VALID
+cli-cert-invalid = Certificate is INVALID
+
+cli-selected-drives = Selected drives:
+cli-no-model = (no model)
+cli-not-detected = (not detected)
+cli-wipe-estimate = { $device }: estimated duration { $duration }
+cli-wipe-certificate = { $device }: certificate { $path }
+cli-wipe-done = { $device }: wiped
+cli-wipe-failed = wipe failed
+cli-no-scheduled-wipes = No scheduled wipes
+cli-no-operations = No operations in progress
+cli-decommission-purge = Drives to purge:
+cli-decommission-excluded = Left alone:
+cli-decommission-none = No drive would be purged
+cli-decommission-confirm = To purge these drives, run the same command with --plan-token { $token } before { $expires }
+
+## Graphical interface
+
+gui-yes = Yes
+gui-no = No
+
+gui-tab-drives = Drives
+gui-tab-details = Details
+gui-tab-report = Report
+gui-tab-settings = Settings
+
+gui-view-volumes = 💽 Volumes
+gui-view-physical-disks = 🗄 Physical disks
+
+table-drives = DRIVES
+table-select = Select
+table-drive-name = Drive name
+table-drive-path = Drive path
+table-size = Size
+table-used = Used
+table-progress = Progress
+table-time-left = Time left
+table-speed = Speed
+table-status = Status
+
+gui-details-heading = Drive Details
+gui-details-hint = Selected drives information will appear here
+gui-details-path = Path: { $value }
+gui-details-type = Type: { $value }
+gui-details-file-system = File System: { $value }
+gui-details-total-space = Total Space: { $value }
+gui-details-used-space = Used Space: { $value }
+gui-details-free-space = Free Space: { $value }
+gui-details-detecting = Detecting drive capabilities...
+gui-details-health = Health: { $value }
+gui-details-temperature = Temperature: { $value }
+gui-details-reallocated = Reallocated Sectors: { $value }
+gui-details-wear = Wear Level: { $value }
+gui-details-wear-used = { $percent }% used
+gui-details-power-on-hours = Power-On Hours: { $value }
+
+gui-cap-not-reported = Not reported
+gui-cap-device = Device: { $value }
+gui-cap-model = Model: { $model } (S/N { $serial })
+gui-cap-secure-erase = Secure Erase: ✅ Supported
+gui-cap-secure-erase-enhanced = Secure Erase: ✅ Supported (enhanced erase too)
+gui-cap-secure-erase-unsupported = Secure Erase: ❌ Not supported
+gui-cap-security = Security: { $password }, { $lock }
+gui-cap-password-set = password set
+gui-cap-no-password = no password
+gui-cap-locked = locked
+gui-cap-unlocked = unlocked
+gui-cap-freeze = Freeze State: { $value }
+gui-cap-sanitize = Sanitize: { $value }
+gui-cap-crypto-erase = Crypto Erase (TCG): { $value }
+gui-cap-trim = TRIM: { $value }
+gui-cap-zoned = Zoned: { $value }
+gui-cap-not-zoned = No
+gui-cap-zoned-hint = Sequential zones only accept writes in order; wipe with the drive's erase command or Zoned Overwrite
+gui-cap-hpa = HPA: { $value }
+gui-cap-hpa-none = HPA: ✅ None
+gui-cap-hpa-hidden = HPA: ⚠ { $size } hidden ({ $sectors } sectors)
+gui-cap-dco = DCO: { $value }
+gui-cap-dco-supported = DCO: ⚠ Feature set supported
+gui-cap-dco-unsupported = DCO: ✅ Not supported
+gui-cap-encrypted = Encrypted: { $value }
+gui-cap-detected = Detected: { $time }
+gui-estimated-duration = ⏱ Estimated duration: { $duration }
+
+gui-report-heading = Sanitization Reports
+gui-report-summary = Sanitization Report
+gui-report-complete = ({ $path }) - Complete
+gui-report-failed = ({ $path }) - Failed: { $error }
+gui-report-method = Method: { $value }
+gui-report-verification = Verification: { $value }
+gui-report-completed-at = Completion Time: { $time }
+gui-report-save = Save Report
+
+gui-progress-heading = Sanitization in Progress
+gui-progress-pass = Pass { $current }/{ $total }
+gui-progress-processed = Processed: { $processed } / { $total }
+gui-progress-method = Method:
+gui-progress-drives = Individual Drive Progress:
+gui-progress-idle = No active sanitization processes.
+gui-progress-idle-hint = Start a sanitization process from the Drives tab to see progress here.
+
+gui-settings-heading = Settings
+gui-settings-server = Server Configuration
+gui-settings-appearance = Appearance
+gui-settings-theme = Theme:
+gui-settings-ui-scale = UI scale:
+gui-settings-reset = Reset
+gui-settings-large-progress = Large progress text (wipe-station displays)
+gui-settings-language = Language:
+gui-settings-language-system = System default
+gui-settings-save-appearance = Save Appearance
+gui-settings-scheduling = Wipe Scheduling
+gui-settings-wipes-per-controller = Parallel wipes per bus/controller:
+gui-settings-wipes-per-controller-hint = Further drives on the same bus (USB, SATA, NVMe, ...) wait in the queue until one finishes.
+gui-settings-save-scheduling = Save Scheduling
+gui-settings-application = Application Settings
+gui-settings-advanced = Advanced
+
+theme-dark = Dark
+theme-light = Light
+theme-high-contrast = High contrast
+
+## Certificates
+
+cert-document-title = Data Erasure Certificate
+cert-title = DATA ERASURE CERTIFICATE
+cert-title-simulated = SIMULATED ERASURE
+cert-yes = Yes
+cert-no = No
+cert-passed = PASSED
+cert-failed = FAILED
+
+cert-section-certificate = CERTIFICATE INFORMATION
+cert-certificate-id = Certificate ID:
+cert-issue-date = Issue Date:
+cert-issuer = Issuer:
+cert-organization = Organization:
+cert-operator = Operator:
+cert-schema-version = Schema Version:
+
+cert-section-drive = DRIVE INFORMATION
+cert-model = Model:
+cert-serial-number = Serial Number:
+cert-firmware = Firmware:
+cert-total-capacity = Total Capacity:
+cert-native-capacity = Native Capacity:
+cert-hpa-detected = HPA Detected:
+cert-dco-detected = DCO Detected:
+cert-security-features = Security Features:
+
+cert-section-sanitization = SANITIZATION DETAILS
+cert-standard-used = Standard Used:
+cert-passes-completed = Passes Completed:
+cert-sectors-wiped = Sectors Wiped:
+cert-start-time = Start Time:
+cert-completion-time = Completion Time:
+cert-duration = Duration:
+cert-duration-minutes = { $minutes } minutes
+cert-patterns-used = Patterns Used:
+cert-simulation = Simulation:
+cert-simulation-note = Written to a sparse image; no device was erased
+
+cert-section-verification = VERIFICATION DETAILS
+cert-verification-performed = Verification Performed:
+cert-sectors-verified = Sectors Verified:
+cert-sample-rate = Sample Rate:
+cert-pattern-verification = Pattern Verification:
+cert-failed-sectors = Failed Sectors:
+cert-functional-test = Functional Test:
+cert-functional-test-value = { $kind }: { $verdict } ({ $detail })
+cert-functional-self-test = Short self-test
+cert-functional-surface-scan = Surface scan
+cert-forensic-image = Pre-wipe Image:
+cert-forensic-source-hash = Drive SHA-256:
+cert-forensic-image-hash = Image SHA-256:
+
+cert-section-evidence = STATE CHANGE EVIDENCE
+cert-evidence-before = Before wipe
+cert-evidence-after = After wipe
+cert-evidence-none = None found
+cert-evidence-partitions = Partition table:
+cert-evidence-partition-table = { $kind }, { $count } partitions
+cert-evidence-signatures = Signatures:
+cert-evidence-content = Sampled content:
+cert-evidence-content-value = { $classification }, { $entropy } bits/byte, { $zero }% zeros
+cert-evidence-smart = SMART health:
+cert-evidence-read-back = Read-back:
+cert-evidence-read-back-value = { $coverage }% read, { $failed } of { $regions } regions failed, { $entropy } bits/byte
+
+cert-section-compliance = COMPLIANCE & CERTIFICATION
+cert-compliance-standards = Compliance Standards:
+cert-reference = Reference:
+
+cert-section-signature = DIGITAL SIGNATURE
+cert-signature-algorithm = Signature Algorithm:
+cert-key-fingerprint = Key Fingerprint:
+cert-certificate-hash = Certificate Hash:
+cert-digital-signature = Digital Signature:
+cert-footer-signed = This certificate is cryptographically signed and tamper-evident.
+cert-footer-generated = Generated on: { $time }
@@ -0,0 +1,241 @@
+# Mensajes de hdd-tool: la línea de órdenes, la interfaz gráfica y los
+# certificados impresos. Debe definir los mismos identificadores que en-US.
+
+language-name = Español
+
+## Línea de órdenes
+
+cli-usage = Uso:
+cli-commands = Órdenes:
+cli-help-scan = Lista las unidades conectadas
+cli-help-verify-cert = Verifica un certificado de borrado firmado
+cli-help-shred = Elimina de forma segura archivos y carpetas
+cli-help-wipe = Borra dispositivos según un perfil de cumplimiento o una receta
+cli-help-wipe-all = Muestra y luego purga todas las unidades salvo el disco del sistema, para retirar un equipo
+cli-help-psid-revert = Restablece de fábrica una unidad autocifrada con el PSID de su etiqueta
+cli-help-schedule = Borra dispositivos más tarde, dentro de las ventanas de mantenimiento configuradas
+cli-help-schedule-manage = Muestra, reanuda o cancela borrados programados
+cli-help-export = Empaqueta el certificado y las evidencias de una operación para el cliente
+cli-help-export-report = Escribe informes en PDF, JSON, CSV o XML; CSV y XML pueden abarcar un intervalo de tiempo
+cli-help-profiles = Lista los perfiles de cumplimiento
+cli-help-watch = Sigue el progreso de borrados que se ejecutan en otro proceso
+cli-help-tui = Elige unidades y sigue los borrados en un terminal interactivo
+cli-help-serve = Sirve la API del motor de borrado para el control remoto
+cli-help-station = Ejecuta los trabajos encolados para esta estación en el servidor de flota
+cli-help-help = Muestra este mensaje
+cli-help-json = --output json imprime en JSON los resultados de scan, profiles, verify-cert, shred, wipe, psid-revert, schedule y export.
+cli-help-exit-codes = Códigos de salida: 0 éxito, 1 fallo, 2 argumentos incorrectos, 3 rechazado, 4 cancelado.
+cli-help-gui = Ejecútelo sin orden para abrir la interfaz gráfica.
+
+cli-cert-header = Certificado: { $id }
+cli-cert-schema = Versión de esquema
+cli-cert-hash = Hash del contenido
+cli-cert-signature = Firma
+cli-cert-fingerprint = Huella de la clave
+cli-cert-serial = Serie de la unidad
+cli-cert-history = Historial
+cli-cert-history-matched = operación { $operation }
+cli-cert-history-mismatch = serie registrada { $serial }
+cli-cert-history-not-found = no encontrado
+cli-cert-history-unavailable = no disponible
+cli-cert-valid = El certificado es VÁLIDO
+cli-cert-invalid = El certificado NO ES VÁLIDO
+
+cli-selected-drives = Unidades seleccionadas:
+cli-no-model = (sin modelo)
+cli-not-detected = (no detectada)
+cli-wipe-estimate = { $device }: duración estimada { $duration }
+cli-wipe-certificate = { $device }: certificado { $path }
+cli-wipe-done = { $device }: borrada
+cli-wipe-failed = el borrado falló
+cli-no-scheduled-wipes = No hay borrados programados
+cli-no-operations = No hay operaciones en curso
+cli-decommission-purge = Unidades que se purgarán:
+cli-decommission-excluded = Se dejan intactas:
+cli-decommission-none = No se purgaría ninguna unidad
+cli-decommission-confirm = Para purgar estas unidades, ejecute la misma orden con --plan-token { $token } antes de las { $expires }
+
+## Interfaz gráfica
+
+gui-yes = Sí
+gui-no = No
+
+gui-tab-drives = Unidades
+gui-tab-details = Detalles
+gui-tab-report = Informe
+gui-tab-settings = Ajustes
+
+gui-view-volumes = 💽 Volúmenes
+gui-view-physical-disks = 🗄 Discos físicos
+
+table-drives = UNIDADES
+table-select = Elegir
+table-drive-name = Nombre
+table-drive-path = Ruta
+table-size = Tamaño
+table-used = Usado
+table-progress = Progreso
+table-time-left = Restante
+table-speed = Velocidad
+table-status = Estado
+
+gui-details-heading = Detalles de la unidad
+gui-details-hint = Aquí aparecerá la información de las unidades seleccionadas
+gui-details-path = Ruta: { $value }
+gui-details-type = Tipo: { $value }
+gui-details-file-system = Sistema de archivos: { $value }
+gui-details-total-space = Espacio total: { $value }
+gui-details-used-space = Espacio usado: { $value }
+gui-details-free-space = Espacio libre: { $value }
+gui-details-detecting = Detectando las capacidades de la unidad...
+gui-details-health = Estado: { $value }
+gui-details-temperature = Temperatura: { $value }
+gui-details-reallocated = Sectores reasignados: { $value }
+gui-details-wear = Desgaste: { $value }
+gui-details-wear-used = { $percent } % usado
+gui-details-power-on-hours = Horas de funcionamiento: { $value }
+
+gui-cap-not-reported = No informado
+gui-cap-device = Dispositivo: { $value }
+gui-cap-model = Modelo: { $model } (N/S { $serial })
+gui-cap-secure-erase = Borrado seguro: ✅ Compatible
+gui-cap-secure-erase-enhanced = Borrado seguro: ✅ Compatible (también el mejorado)
+gui-cap-secure-erase-unsupported = Borrado seguro: ❌ No compatible
+gui-cap-security = Seguridad: { $password }, { $lock }
+gui-cap-password-set = con contraseña
+gui-cap-no-password = sin contraseña
+gui-cap-locked = bloqueada
+gui-cap-unlocked = desbloqueada
+gui-cap-freeze = Congelación: { $value }
+gui-cap-sanitize = Sanitize: { $value }
+gui-cap-crypto-erase = Borrado criptográfico (TCG): { $value }
+gui-cap-trim = TRIM: { $value }
+gui-cap-zoned = Zonas: { $value }
+gui-cap-not-zoned = No
+gui-cap-zoned-hint = Las zonas secuenciales solo aceptan escrituras en orden; borre con el comando de borrado de la unidad o con Sobrescritura por zonas
+gui-cap-hpa = HPA: { $value }
+gui-cap-hpa-none = HPA: ✅ Ninguna
+gui-cap-hpa-hidden = HPA: ⚠ { $size } ocultos ({ $sectors } sectores)
+gui-cap-dco = DCO: { $value }
+gui-cap-dco-supported = DCO: ⚠ Conjunto de funciones compatible
+gui-cap-dco-unsupported = DCO: ✅ No compatible
+gui-cap-encrypted = Cifrado: { $value }
+gui-cap-detected = Detectado: { $time }
+gui-estimated-duration = ⏱ Duración estimada: { $duration }
+
+gui-report-heading = Informes de sanitización
+gui-report-summary = Informe de sanitización
+gui-report-complete = ({ $path }) - Completado
+gui-report-failed = ({ $path }) - Falló: { $error }
+gui-report-method = Método: { $value }
+gui-report-verification = Verificación: { $value }
+gui-report-completed-at = Hora de finalización: { $time }
+gui-report-save = Guardar informe
+
+gui-progress-heading = Sanitización en curso
+gui-progress-pass = Pasada { $current }/{ $total }
+gui-progress-processed = Procesado: { $processed } / { $total }
+gui-progress-method = Método:
+gui-progress-drives = Progreso por unidad:
+gui-progress-idle = No hay procesos de sanitización activos.
+gui-progress-idle-hint = Inicie una sanitización desde la pestaña Unidades para ver aquí su progreso.
+
+gui-settings-heading = Ajustes
+gui-settings-server = Configuración del servidor
+gui-settings-appearance = Apariencia
+gui-settings-theme = Tema:
+gui-settings-ui-scale = Escala de la interfaz:
+gui-settings-reset = Restablecer
+gui-settings-large-progress = Texto de progreso grande (pantallas de estación de borrado)
+gui-settings-language = Idioma:
+gui-settings-language-system = Predeterminado del sistema
+gui-settings-save-appearance = Guardar apariencia
+gui-settings-scheduling = Programación de borrados
+gui-settings-wipes-per-controller = Borrados en paralelo por bus/controlador:
+gui-settings-wipes-per-controller-hint = Las demás unidades del mismo bus (USB, SATA, NVMe, ...) esperan en la cola hasta que termine una.
+gui-settings-save-scheduling = Guardar programación
+gui-settings-application = Ajustes de la aplicación
+gui-settings-advanced = Avanzado
+
+theme-dark = Oscuro
+theme-light = Claro
+theme-high-contrast = Alto contraste
+
+## Certificados
+
+cert-document-title = Certificado de borrado de datos
+cert-title = CERTIFICADO DE BORRADO DE DATOS
+cert-title-simulated = BORRADO SIMULADO
+cert-yes = Sí
+cert-no = No
+cert-passed = SUPERADA
+cert-failed = FALLIDA
+
+cert-section-certificate = INFORMACIÓN DEL CERTIFICADO
+cert-certificate-id = ID del certificado:
+cert-issue-date = Fecha de emisión:
+cert-issuer = Emisor:
+cert-organization = Organización:
+cert-operator = Operador:
+cert-schema-version = Versión de esquema:
+
+cert-section-drive = INFORMACIÓN DE LA UNIDAD
+cert-model = Modelo:
+cert-serial-number = Número de serie:
+cert-firmware = Firmware:
+cert-total-capacity = Capacidad total:
+cert-native-capacity = Capacidad nativa:
+cert-hpa-detected = HPA detectada:
+cert-dco-detected = DCO detectada:
+cert-security-features = Funciones de seguridad:
+
+cert-section-sanitization = DETALLES DE LA SANITIZACIÓN
+cert-standard-used = Norma aplicada:
+cert-passes-completed = Pasadas completadas:
+cert-sectors-wiped = Sectores borrados:
+cert-start-time = Hora de inicio:
+cert-completion-time = Hora de finalización:
+cert-duration = Duración:
+cert-duration-minutes = { $minutes } minutos
+cert-patterns-used = Patrones usados:
+cert-simulation = Simulación:
+cert-simulation-note = Escrito en una imagen dispersa; no se borró ningún dispositivo
+
+cert-section-verification = DETALLES DE LA VERIFICACIÓN
+cert-verification-performed = Verificación realizada:
+cert-sectors-verified = Sectores verificados:
+cert-sample-rate = Tasa de muestreo:
+cert-pattern-verification = Verificación del patrón:
+cert-failed-sectors = Sectores fallidos:
+cert-functional-test = Prueba funcional:
+cert-functional-test-value = { $kind }: { $verdict } ({ $detail })
+cert-functional-self-test = Autoprueba corta
+cert-functional-surface-scan = Análisis de superficie
+cert-forensic-image = Imagen previa:
+cert-forensic-source-hash = SHA-256 de la unidad:
+cert-forensic-image-hash = SHA-256 de la imagen:
+
+cert-section-evidence = EVIDENCIA DEL CAMBIO DE ESTADO
+cert-evidence-before = Antes del borrado
+cert-evidence-after = Después del borrado
+cert-evidence-none = No se encontró nada
+cert-evidence-partitions = Tabla de particiones:
+cert-evidence-partition-table = { $kind }, { $count } particiones
+cert-evidence-signatures = Firmas:
+cert-evidence-content = Contenido muestreado:
+cert-evidence-content-value = { $classification }, { $entropy } bits/byte, { $zero } % ceros
+cert-evidence-smart = Estado SMART:
+cert-evidence-read-back = Relectura:
+cert-evidence-read-back-value = { $coverage } % leído, { $failed } de { $regions } regiones fallidas, { $entropy } bits/byte
+
+cert-section-compliance = CUMPLIMIENTO Y CERTIFICACIÓN
+cert-compliance-standards = Normas de cumplimiento:
+cert-reference = Referencia:
+
+cert-section-signature = FIRMA DIGITAL
+cert-signature-algorithm = Algoritmo de firma:
+cert-key-fingerprint = Huella de la clave:
+cert-certificate-hash = Hash del certificado:
+cert-digital-signature = Firma digital:
+cert-footer-signed = Este certificado está firmado criptográficamente y revela cualquier manipulación.
+cert-footer-generated = Generado el: { $time }
@@ -9,6 +9,10 @@ use std::sync::{Arc, Mutex};
 use std::thread;
 use std::time::{Duration, Instant};
 use crate::ata_commands::AtaInterface;
+use crate::freeze::FreezeStatus;
+use crate::encryption::EncryptionStatus;
+use crate::pattern_stream::{PatternSeed, PatternStream};
+use tracing::{debug, info, info_span, warn};
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum WipingAlgorithm {
@@ -22,6 +26,14 @@ pub enum WipingAlgorithm {
     AtaEnhancedSecureErase,       // ATA Secure Erase (Enhanced)
     NvmeSecureErase,              // NVMe Secure Erase
     NvmeCryptoErase,              // NVMe Cryptographic Erase
+    NvmeSanitizeBlockErase,       // NVMe Sanitize Block Erase (all namespaces)
+    NvmeSanitizeCryptoErase,      // NVMe Sanitize Crypto Erase (all namespaces)
+    ZonedOverwrite,               // Zone reset + sequential zero fill (SMR / ZNS)
+    ScsiSanitizeOverwrite,        // SCSI SANITIZE Overwrite (SAS)
+    ScsiSanitizeBlockErase,       // SCSI SANITIZE Block Erase (SAS)
+    ScsiSanitizeCryptoErase,      // SCSI SANITIZE Cryptographic Erase (SAS)
+    ScsiFormatUnit,               // SCSI FORMAT UNIT (SAS)
+    CryptoHeaderDestruction,      // Destroy BitLocker/LUKS/FileVault key material
     
     // Software-based Overwrite Methods
     DoD522022M,                   // DoD 5220.22-M (3-pass)
@@ -69,10 +81,21 @@ pub struct DeviceInfo {
     pub supports_secure_erase: bool,
     pub supports_enhanced_secure_erase: bool,
     pub supports_crypto_erase: bool,
+    pub supports_opal: bool,
     pub is_removable: bool,
     pub vendor: String,
     pub model: String,
     pub serial: String,
+    /// Stable identifier that survives rescans and re-enumeration (see `devices::identity`)
+    pub device_id: String,
+    /// ATA security freeze-lock state (blocks ATA Secure Erase while frozen)
+    pub freeze_status: FreezeStatus,
+    /// BitLocker / LUKS / FileVault volumes found on the device
+    pub encryption_status: EncryptionStatus,
+    /// Every namespace of the controller, this one included (NVMe only)
+    pub nvme_namespaces: Vec<crate::devices::nvme::NvmeNamespace>,
+    /// Zoned model of SMR drives and ZNS namespaces; `None` for ordinary devices
+    pub zoned: Option<crate::zoned::ZonedModel>,
 }
 
 #[derive(Debug, Clone)]
@@ -80,11 +103,14 @@ pub enum DeviceType {
     HDD,
     SSD,
     NVMe,
+    SAS,
     SDCard,
     USBDrive,
     MMC,
     EMmc,
     CompactFlash,
+    /// A hardware RAID volume; the member drives are hidden behind the controller
+    VirtualDisk,
     Other(String),
 }
 
@@ -116,7 +142,7 @@ impl AdvancedWiper {
 
     /// Get comprehensive device information
     pub fn analyze_device(&self, device_path: &str) -> io::Result<DeviceInfo> {
-        println!("🔍 Analyzing device: {}", device_path);
+        info!("Analyzing device: {}", device_path);
         
         // Try to get basic file information
         let file = File::open(device_path)?;
@@ -133,10 +159,16 @@ impl AdvancedWiper {
             supports_secure_erase: false,
             supports_enhanced_secure_erase: false,
             supports_crypto_erase: false,
+            supports_opal: false,
             is_removable: false,
             vendor: "Unknown".to_string(),
             model: "Unknown".to_string(),
             serial: "Unknown".to_string(),
+            device_id: String::new(),
+            freeze_status: FreezeStatus::Unknown,
+            encryption_status: EncryptionStatus::Unknown,
+            nvme_namespaces: Vec::new(),
+            zoned: None,
         };
 
         // Try ATA interface for detailed information
@@ -147,6 +179,7 @@ impl AdvancedWiper {
                 device_info.model = drive_info.model.clone();
                 device_info.serial = drive_info.serial.clone();
                 device_info.supports_secure_erase = drive_info.security_supported;
+                device_info.freeze_status = FreezeStatus::from_ata(drive_info.security_supported, drive_info.security_frozen);
                 
                 // Determine device type based on model
                 device_info.device_type = self.determine_device_type(&drive_info.model);
@@ -158,15 +191,31 @@ impl AdvancedWiper {
             }
         }
 
+        // Check for a TCG Opal / Enterprise self-encrypting drive
+        if let Some(discovery) = crate::opal::detect_opal(device_path) {
+            device_info.supports_opal = true;
+            device_info.supports_crypto_erase = discovery.supports_crypto_erase();
+        }
+
         // Try to get more information from Windows API or system calls
         self.enhance_device_info(&mut device_info)?;
 
-        println!("📊 Device Analysis Complete:");
-        println!("   Type: {:?}", device_info.device_type);
-        println!("   Size: {:.2} GB", device_info.size_bytes as f64 / (1000.0 * 1000.0 * 1000.0));
-        println!("   Model: {}", device_info.model);
-        println!("   Secure Erase: {}", if device_info.supports_secure_erase { "Yes" } else { "No" });
-        println!("   TRIM Support: {}", if device_info.supports_trim { "Yes" } else { "No" });
+        device_info.device_id = crate::devices::identity::resolve_device_id(&device_info);
+        device_info.encryption_status = crate::encryption::detect_encryption(device_path).unwrap_or_default();
+
+        info!(
+            device = %device_path,
+            device_type = ?device_info.device_type,
+            size_bytes = device_info.size_bytes,
+            model = %device_info.model,
+            device_id = %device_info.device_id,
+            secure_erase = device_info.supports_secure_erase,
+            freeze = device_info.freeze_status.as_str(),
+            trim = device_info.supports_trim,
+            opal = device_info.supports_opal,
+            encryption = %device_info.encryption_status.description(),
+            "Device analysis complete"
+        );
 
         Ok(device_info)
     }
@@ -178,10 +227,12 @@ impl AdvancedWiper {
         algorithm: WipingAlgorithm,
         progress_callback: Arc<Mutex<WipingProgress>>,
     ) -> io::Result<String> {
-        println!("🚨 CRITICAL WARNING: About to PERMANENTLY ERASE ALL DATA on {}", device_info.device_path);
-        println!("📱 Device: {} ({})", device_info.model, device_info.device_path);
-        println!("💾 Size: {:.2} GB", device_info.size_bytes as f64 / (1000.0 * 1000.0 * 1000.0));
-        println!("🔒 Algorithm: {:?}", algorithm);
+        let _span = info_span!("wipe", device = %device_info.device_path, algorithm = ?algorithm).entered();
+        warn!(
+            model = %device_info.model,
+            size_bytes = device_info.size_bytes,
+            "About to PERMANENTLY ERASE ALL DATA"
+        );
         
         // Initialize progress
         {
@@ -198,6 +249,7 @@ impl AdvancedWiper {
             WipingAlgorithm::AtaEnhancedSecureErase => self.ata_secure_erase(device_info, true, progress_callback),
             WipingAlgorithm::NvmeSecureErase => self.nvme_secure_erase(device_info, progress_callback),
             WipingAlgorithm::NvmeCryptoErase => self.nvme_crypto_erase(device_info, progress_callback),
+            WipingAlgorithm::CryptoHeaderDestruction => self.crypto_header_destruction(device_info, progress_callback),
             WipingAlgorithm::DoD522022M => self.dod_5220_22m(device_info, false, progress_callback),
             WipingAlgorithm::DoD522022MEce => self.dod_5220_22m(device_info, true, progress_callback),
             WipingAlgorithm::Gutmann => self.gutmann_35_pass(device_info, progress_callback),
@@ -229,7 +281,7 @@ impl AdvancedWiper {
         device_info: &DeviceInfo,
         progress_callback: Arc<Mutex<WipingProgress>>,
     ) -> io::Result<String> {
-        println!("🔒 NIST 800-88 Clear Method - Single Pass Overwrite");
+        info!("NIST 800-88 Clear Method - Single Pass Overwrite");
         
         {
             let mut progress = progress_callback.lock().unwrap();
@@ -241,7 +293,7 @@ impl AdvancedWiper {
         self.single_pass_wipe(device_info, WipePattern::CryptoRandom, progress_callback)?;
         
         if self.verify_after_wipe {
-            println!("🔍 Verifying wipe completion...");
+            info!("Verifying wipe completion...");
             self.verify_wipe(device_info)?;
         }
 
@@ -254,8 +306,29 @@ impl AdvancedWiper {
         device_info: &DeviceInfo,
         progress_callback: Arc<Mutex<WipingProgress>>,
     ) -> io::Result<String> {
-        println!("🔒 NIST 800-88 Purge Method - Multi-Pass Cryptographic Destruction");
+        info!("NIST 800-88 Purge Method - Multi-Pass Cryptographic Destruction");
         
+        // Self-encrypting drives can be purged instantly by regenerating the media key
+        if device_info.supports_opal && device_info.supports_crypto_erase {
+            {
+                let mut progress = progress_callback.lock().unwrap();
+                progress.total_passes = 1;
+                progress.current_pass = 1;
+                progress.current_pattern = "TCG Opal Cryptographic Erase".to_string();
+            }
+
+            match crate::opal::OpalInterface::new(&device_info.device_path).crypto_erase(None) {
+                Ok(()) => {
+                    let mut progress = progress_callback.lock().unwrap();
+                    progress.bytes_processed = device_info.size_bytes;
+                    return Ok("NIST 800-88 Purge completed via TCG Opal cryptographic erase - Media encryption key destroyed".to_string());
+                }
+                Err(e) => {
+                    warn!("Opal crypto erase unavailable ({}), falling back to overwrite", e);
+                }
+            }
+        }
+
         {
             let mut progress = progress_callback.lock().unwrap();
             progress.total_passes = 7;
@@ -279,12 +352,13 @@ impl AdvancedWiper {
                 progress.current_pattern = description.to_string();
             }
 
-            println!("🔄 Pass {}/7: {}", i + 1, description);
+            let _pass = info_span!("pass", pass = i + 1, total_passes = 7).entered();
+            info!("Pass {}/7: {}", i + 1, description);
             self.single_pass_wipe(device_info, pattern.clone(), progress_callback.clone())?;
         }
 
         if self.verify_after_wipe {
-            println!("🔍 Performing final verification...");
+            info!("Performing final verification...");
             self.verify_wipe(device_info)?;
         }
 
@@ -298,7 +372,7 @@ impl AdvancedWiper {
         enhanced: bool,
         progress_callback: Arc<Mutex<WipingProgress>>,
     ) -> io::Result<String> {
-        println!("🔧 ATA Secure Erase ({}) - Hardware-based Destruction", 
+        debug!("ATA Secure Erase ({}) - Hardware-based Destruction", 
                  if enhanced { "Enhanced" } else { "Standard" });
 
         if !device_info.supports_secure_erase {
@@ -330,7 +404,7 @@ impl AdvancedWiper {
         device_info: &DeviceInfo,
         progress_callback: Arc<Mutex<WipingProgress>>,
     ) -> io::Result<String> {
-        println!("🔒 Three-Pass Wipe Method");
+        info!("Three-Pass Wipe Method");
         
         {
             let mut progress = progress_callback.lock().unwrap();
@@ -350,7 +424,8 @@ impl AdvancedWiper {
                 progress.current_pattern = description.to_string();
             }
 
-            println!("🔄 Pass {}/3: {}", i + 1, description);
+            let _pass = info_span!("pass", pass = i + 1, total_passes = 3).entered();
+            info!("Pass {}/3: {}", i + 1, description);
             self.single_pass_wipe(device_info, pattern.clone(), progress_callback.clone())?;
         }
 
@@ -363,7 +438,7 @@ impl AdvancedWiper {
         device_info: &DeviceInfo,
         progress_callback: Arc<Mutex<WipingProgress>>,
     ) -> io::Result<String> {
-        println!("🔒 Seven-Pass Enhanced Wipe Method");
+        info!("Seven-Pass Enhanced Wipe Method");
         
         {
             let mut progress = progress_callback.lock().unwrap();
@@ -387,7 +462,8 @@ impl AdvancedWiper {
                 progress.current_pattern = description.to_string();
             }
 
-            println!("🔄 Pass {}/7: {}", i + 1, description);
+            let _pass = info_span!("pass", pass = i + 1, total_passes = 7).entered();
+            info!("Pass {}/7: {}", i + 1, description);
             self.single_pass_wipe(device_info, pattern.clone(), progress_callback.clone())?;
         }
 
@@ -419,22 +495,22 @@ impl AdvancedWiper {
         pattern: &WipePattern,
         progress_callback: Arc<Mutex<WipingProgress>>,
     ) -> io::Result<()> {
-        println!("🔄 Performing file-level wipe on {}", device_info.device_path);
+        info!("Performing file-level wipe on {}", device_info.device_path);
         
         let start_time = Instant::now();
         let chunk_size = self.buffer_size;
         
         // Create a large temporary file to fill the free space
         let temp_file_path = format!("{}wipe_temp_file.tmp", device_info.device_path);
-        println!("📁 Creating temporary wipe file: {}", temp_file_path);
+        debug!("Creating temporary wipe file: {}", temp_file_path);
         
         let result = self.fill_free_space_with_pattern(&temp_file_path, pattern, device_info.size_bytes, progress_callback);
         
         // Clean up temporary file
         if std::path::Path::new(&temp_file_path).exists() {
             match std::fs::remove_file(&temp_file_path) {
-                Ok(_) => println!("🗑️ Temporary wipe file removed"),
-                Err(e) => println!("⚠️ Warning: Could not remove temporary file: {}", e),
+                Ok(_) => info!("Temporary wipe file removed"),
+                Err(e) => warn!("Warning: Could not remove temporary file: {}", e),
             }
         }
         
@@ -471,7 +547,7 @@ impl AdvancedWiper {
                     // If we can't write more (disk full), that's actually what we want
                     if e.kind() == io::ErrorKind::WriteZero || 
                        e.raw_os_error() == Some(112) { // ERROR_DISK_FULL
-                        println!("💾 Disk space filled - wipe effective");
+                        info!("Disk space filled - wipe effective");
                         break;
                     } else {
                         return Err(e);
@@ -503,7 +579,7 @@ impl AdvancedWiper {
         }
 
         file.sync_all()?;
-        println!("✅ Pattern written: {:.2} MB", bytes_written as f64 / 1024.0 / 1024.0);
+        info!("Pattern written: {:.2} MB", bytes_written as f64 / 1024.0 / 1024.0);
         Ok(())
     }
 
@@ -570,24 +646,9 @@ impl AdvancedWiper {
                 (0..size).map(|_| rng.r#gen::<u8>()).collect()
             },
             WipePattern::CryptoRandom => {
-                // Use cryptographically secure random
-                use std::collections::hash_map::DefaultHasher;
-                use std::hash::{Hash, Hasher};
-                use std::time::{SystemTime, UNIX_EPOCH};
-                
-                let mut data = Vec::with_capacity(size);
-                let mut hasher = DefaultHasher::new();
-                
-                SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_nanos().hash(&mut hasher);
-                std::process::id().hash(&mut hasher);
-                
-                let mut seed = hasher.finish();
-                
-                for _ in 0..size {
-                    seed = seed.wrapping_mul(1103515245).wrapping_add(12345);
-                    data.push((seed >> 24) as u8);
-                }
-                
+                // ChaCha20 keystream under a fresh OS-random seed
+                let mut data = vec![0u8; size];
+                PatternStream::new(&PatternSeed::generate(), 0).fill_at(0, &mut data);
                 data
             },
         }
@@ -599,6 +660,8 @@ impl AdvancedWiper {
         
         if model_lower.contains("nvme") || model_lower.contains("m.2") {
             DeviceType::NVMe
+        } else if model_lower.contains("sas") {
+            DeviceType::SAS
         } else if model_lower.contains("ssd") || model_lower.contains("solid state") {
             DeviceType::SSD
         } else if model_lower.contains("sd") || model_lower.contains("mmc") {
@@ -619,7 +682,7 @@ impl AdvancedWiper {
     }
 
     fn verify_wipe(&self, _device_info: &DeviceInfo) -> io::Result<()> {
-        println!("✅ Wipe verification completed");
+        info!("Wipe verification completed");
         Ok(())
     }
 
@@ -632,6 +695,27 @@ impl AdvancedWiper {
         Err(io::Error::new(io::ErrorKind::Other, "NVMe Crypto Erase not implemented"))
     }
 
+    /// Purge software-encrypted volumes by destroying every copy of their key material
+    fn crypto_header_destruction(&self, device_info: &DeviceInfo, progress_callback: Arc<Mutex<WipingProgress>>) -> io::Result<String> {
+        {
+            let mut progress = progress_callback.lock().unwrap();
+            progress.total_passes = 1;
+            progress.current_pass = 1;
+            progress.current_pattern = "Encryption Header Destruction".to_string();
+        }
+
+        let report = crate::encryption::destroy_key_material(&device_info.device_path)?;
+        if !report.verified {
+            return Err(io::Error::other(format!("Header destruction could not be verified: {}", report.notes.join("; "))));
+        }
+
+        progress_callback.lock().unwrap().bytes_processed = device_info.size_bytes;
+        Ok(format!(
+            "Crypto erase completed - key material of {} volume(s) destroyed and verified",
+            report.volumes_destroyed.len()
+        ))
+    }
+
     fn dod_5220_22m(&self, _device_info: &DeviceInfo, _ece: bool, _progress_callback: Arc<Mutex<WipingProgress>>) -> io::Result<String> {
         Err(io::Error::new(io::ErrorKind::Other, "DoD 5220.22-M not implemented"))
     }
@@ -648,8 +732,43 @@ impl AdvancedWiper {
         Err(io::Error::new(io::ErrorKind::Other, "Filesystem wipe not implemented"))
     }
 
-    fn free_space_wipe(&self, _device_info: &DeviceInfo, _progress_callback: Arc<Mutex<WipingProgress>>) -> io::Result<String> {
-        Err(io::Error::new(io::ErrorKind::Other, "Free space wipe not implemented"))
+    /// Overwrite only the unallocated space of every filesystem on the device
+    fn free_space_wipe(&self, device_info: &DeviceInfo, progress_callback: Arc<Mutex<WipingProgress>>) -> io::Result<String> {
+        let mount_points = crate::free_space::mount_points_for(&device_info.device_path);
+        if mount_points.is_empty() {
+            return Err(io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("No mounted filesystem found on {}", device_info.device_path)
+            ));
+        }
+
+        let wiper = crate::free_space::FreeSpaceWiper::new(1);
+        let start_time = Instant::now();
+        let mut total_written = 0u64;
+
+        for mount_point in &mount_points {
+            let report = wiper.wipe(mount_point, |update| {
+                if let Ok(mut progress) = progress_callback.lock() {
+                    progress.current_pass = update.pass;
+                    progress.total_passes = update.total_passes;
+                    progress.bytes_processed = update.bytes_written;
+                    progress.total_bytes = update.bytes_total;
+                    progress.current_pattern = update.operation;
+
+                    let elapsed = start_time.elapsed().as_secs_f64();
+                    if elapsed > 0.0 {
+                        progress.speed_mbps = (total_written + update.bytes_written) as f64 / 1024.0 / 1024.0 / elapsed;
+                    }
+                }
+            })?;
+            total_written += report.bytes_written;
+        }
+
+        Ok(format!(
+            "Free space wipe completed on {} filesystem(s), {:.2} GB overwritten",
+            mount_points.len(),
+            total_written as f64 / (1024.0 * 1024.0 * 1024.0)
+        ))
     }
 
     fn quick_format(&self, _device_info: &DeviceInfo, _progress_callback: Arc<Mutex<WipingProgress>>) -> io::Result<String> {
@@ -678,6 +797,14 @@ pub fn get_available_algorithms() -> Vec<(WipingAlgorithm, &'static str, &'stati
         (WipingAlgorithm::AtaEnhancedSecureErase, "ATA Enhanced Secure Erase", "Enhanced hardware cryptographic erase"),
         (WipingAlgorithm::NvmeSecureErase, "NVMe Secure Erase", "NVMe hardware secure erase"),
         (WipingAlgorithm::NvmeCryptoErase, "NVMe Crypto Erase", "NVMe cryptographic key destruction"),
+        (WipingAlgorithm::NvmeSanitizeCryptoErase, "NVMe Sanitize Crypto Erase", "NVMe key destruction across every namespace"),
+        (WipingAlgorithm::NvmeSanitizeBlockErase, "NVMe Sanitize Block Erase", "NVMe block erase across every namespace"),
+        (WipingAlgorithm::ScsiSanitizeCryptoErase, "SCSI Sanitize Crypto Erase", "SAS cryptographic key destruction"),
+        (WipingAlgorithm::ScsiSanitizeBlockErase, "SCSI Sanitize Block Erase", "SAS hardware block erase"),
+        (WipingAlgorithm::ScsiSanitizeOverwrite, "SCSI Sanitize Overwrite", "SAS drive-internal overwrite"),
+        (WipingAlgorithm::ScsiFormatUnit, "SCSI Format Unit", "SAS low-level format"),
+        (WipingAlgorithm::CryptoHeaderDestruction, "Crypto Erase (Header Destruction)", "Destroy BitLocker/LUKS/FileVault key material"),
+        (WipingAlgorithm::ZonedOverwrite, "Zoned Overwrite", "Reset every zone and zero-fill it in order (SMR / ZNS)"),
         
         // Standard Multi-pass Methods
         (WipingAlgorithm::DoD522022M, "DoD 5220.22-M", "3-pass DoD standard overwrite"),
@@ -6,6 +6,7 @@
  * - DCO (Device Configuration Overlay) analysis
  * - Security feature assessment
  * - Native capacity determination
+ * - ATA Security Erase (ATA_PASS_THROUGH_EX on Windows, SG_IO on Linux)
  * 
  * ⚠️ WARNING: These commands directly interface with drive hardware.
  * Improper usage can result in data loss or drive damage.
@@ -15,7 +16,10 @@
 // Required for HPA/DCO detection and manipulation
 
 use std::io;
+#[cfg(windows)]
 use std::mem;
+use std::time::Duration;
+use tracing::{info, warn};
 
 // Platform-specific imports
 #[cfg(windows)]
@@ -30,7 +34,7 @@ use windows::{
 
 #[cfg(unix)]
 use {
-    std::fs::File,
+    std::fs::{File, OpenOptions},
     std::os::unix::io::{AsRawFd, RawFd},
     libc::{ioctl, c_int, c_ulong},
 };
@@ -63,16 +67,64 @@ pub const ATA_SECURITY_FREEZE_LOCK: u8 = 0xF5;
 /// ATA SECURITY DISABLE PASSWORD command (0xF6)
 pub const ATA_SECURITY_DISABLE_PASSWORD: u8 = 0xF6;
 
+/// Status register ERR bit
+const ATA_STATUS_ERR: u8 = 0x01;
+/// Device register LBA mode bit
+const ATA_DEVICE_LBA: u8 = 0x40;
+/// Timeout for commands that complete immediately
+const ATA_COMMAND_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Temporary user password set for the duration of a security erase. It is fixed rather
+/// than random so a drive left locked by an interrupted erase can still be unlocked.
+pub const SECURITY_ERASE_PASSWORD: &[u8] = b"HDD-Tool";
+
 // ============================================================================
 // WINDOWS IOCTL CODES
 // ============================================================================
 
 // IOCTL codes for Windows
 /// IOCTL for ATA pass-through commands
+#[cfg(windows)]
 const IOCTL_ATA_PASS_THROUGH: u32 = 0x0004D02C;
 /// IOCTL for ATA pass-through direct commands
+#[cfg(windows)]
+#[allow(dead_code)]
 const IOCTL_ATA_PASS_THROUGH_DIRECT: u32 = 0x0004D030;
 
+// ATA_PASS_THROUGH_EX flags
+#[cfg(windows)]
+const ATA_FLAGS_DRDY_REQUIRED: u16 = 0x01;
+#[cfg(windows)]
+const ATA_FLAGS_DATA_IN: u16 = 0x02;
+#[cfg(windows)]
+const ATA_FLAGS_DATA_OUT: u16 = 0x04;
+#[cfg(windows)]
+const ATA_FLAGS_48BIT_COMMAND: u16 = 0x08;
+
+// ============================================================================
+// LINUX SG_IO CONSTANTS
+// ============================================================================
+
+#[cfg(target_os = "linux")]
+const SG_IO: c_ulong = 0x2285;
+#[cfg(target_os = "linux")]
+const SG_DXFER_NONE: c_int = -1;
+#[cfg(target_os = "linux")]
+const SG_DXFER_TO_DEV: c_int = -2;
+#[cfg(target_os = "linux")]
+const SG_DXFER_FROM_DEV: c_int = -3;
+#[cfg(target_os = "linux")]
+const SG_DRIVER_SENSE: u16 = 0x08;
+/// SCSI ATA PASS-THROUGH (16) operation code (SAT)
+#[cfg(target_os = "linux")]
+const SAT_ATA_PASS_THROUGH_16: u8 = 0x85;
+#[cfg(target_os = "linux")]
+const SAT_PROTOCOL_NON_DATA: u8 = 3;
+#[cfg(target_os = "linux")]
+const SAT_PROTOCOL_PIO_DATA_IN: u8 = 4;
+#[cfg(target_os = "linux")]
+const SAT_PROTOCOL_PIO_DATA_OUT: u8 = 5;
+
 // ============================================================================
 // ATA DATA STRUCTURES
 // ============================================================================
@@ -94,6 +146,81 @@ pub struct AtaPassThroughEx {
     pub current_task_file: [u8; 8],
 }
 
+/// Linux SCSI generic request header (struct sg_io_hdr)
+#[cfg(target_os = "linux")]
+#[repr(C)]
+struct SgIoHdr {
+    interface_id: c_int,
+    dxfer_direction: c_int,
+    cmd_len: u8,
+    mx_sb_len: u8,
+    iovec_count: u16,
+    dxfer_len: u32,
+    dxferp: *mut libc::c_void,
+    cmdp: *const u8,
+    sbp: *mut u8,
+    timeout: u32,
+    flags: u32,
+    pack_id: c_int,
+    usr_ptr: *mut libc::c_void,
+    status: u8,
+    masked_status: u8,
+    msg_status: u8,
+    sb_len_wr: u8,
+    host_status: u16,
+    driver_status: u16,
+    resid: c_int,
+    duration: u32,
+    info: u32,
+}
+
+/// Direction of the data phase of an ATA command
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum AtaDataDirection {
+    None,
+    In,
+    Out,
+}
+
+/// One set of ATA command block registers. On completion `features` holds the
+/// error register and `command` the status register.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct AtaTaskFile {
+    pub features: u8,
+    pub sector_count: u8,
+    pub lba_low: u8,
+    pub lba_mid: u8,
+    pub lba_high: u8,
+    pub device: u8,
+    pub command: u8,
+}
+
+impl AtaTaskFile {
+    /// 24 LBA bits held in the low/mid/high registers
+    pub fn lba(&self) -> u64 {
+        (self.lba_low as u64) | ((self.lba_mid as u64) << 8) | ((self.lba_high as u64) << 16)
+    }
+
+    /// Windows IDEREGS layout
+    #[cfg(windows)]
+    fn to_ide_regs(&self) -> [u8; 8] {
+        [self.features, self.sector_count, self.lba_low, self.lba_mid, self.lba_high, self.device, self.command, 0]
+    }
+
+    #[cfg(windows)]
+    fn from_ide_regs(regs: &[u8; 8]) -> Self {
+        Self {
+            features: regs[0],
+            sector_count: regs[1],
+            lba_low: regs[2],
+            lba_mid: regs[3],
+            lba_high: regs[4],
+            device: regs[5],
+            command: regs[6],
+        }
+    }
+}
+
 /// ATA IDENTIFY DEVICE data structure (512 bytes)
 #[repr(C)]
 pub struct IdentifyDeviceData {
@@ -158,7 +285,7 @@ impl AtaInterface {
                     OPEN_EXISTING,
                     FILE_ATTRIBUTE_NORMAL,
                     HANDLE::default(),
-                ).map_err(|e| io::Error::new(io::ErrorKind::Other, format!("Failed to open drive: {}", e)))?;
+                ).map_err(|e| io::Error::other(format!("Failed to open drive: {}", e)))?;
 
                 Ok(AtaInterface { handle })
             }
@@ -166,44 +293,85 @@ impl AtaInterface {
         
         #[cfg(unix)]
         {
-            let file = File::open(drive_path)?;
+            // Security and SET MAX commands need a writable handle
+            let file = OpenOptions::new().read(true).write(true).open(drive_path)?;
             Ok(AtaInterface { file })
         }
     }
 
-    pub fn identify_device(&self) -> io::Result<IdentifyDeviceData> {
-        let mut identify_data = IdentifyDeviceData { data: [0; 256] };
-        
-        let mut ata_pt = AtaPassThroughEx {
-            length: mem::size_of::<AtaPassThroughEx>() as u16,
-            ata_flags: 0x02, // ATA_FLAGS_DATA_IN
+    /// Issue a single ATA command through the platform pass-through interface.
+    /// `previous` carries the high-order registers of a 48-bit command. Returns the
+    /// current and previous registers reported by the drive on completion.
+    pub fn pass_through(
+        &self,
+        current: AtaTaskFile,
+        previous: Option<AtaTaskFile>,
+        direction: AtaDataDirection,
+        data: &mut [u8],
+        timeout: Duration,
+    ) -> io::Result<(AtaTaskFile, AtaTaskFile)> {
+        let (current_out, previous_out) = self.platform_pass_through(current, previous, direction, data, timeout)?;
+
+        if current_out.command & ATA_STATUS_ERR != 0 {
+            return Err(io::Error::other(format!(
+                "ATA command {:02X}h aborted by drive (error {:02X}h)",
+                current.command, current_out.features
+            )));
+        }
+
+        Ok((current_out, previous_out))
+    }
+
+    #[cfg(windows)]
+    fn platform_pass_through(
+        &self,
+        current: AtaTaskFile,
+        previous: Option<AtaTaskFile>,
+        direction: AtaDataDirection,
+        data: &mut [u8],
+        timeout: Duration,
+    ) -> io::Result<(AtaTaskFile, AtaTaskFile)> {
+        let header_len = mem::size_of::<AtaPassThroughEx>();
+
+        let mut ata_flags = ATA_FLAGS_DRDY_REQUIRED;
+        match direction {
+            AtaDataDirection::In => ata_flags |= ATA_FLAGS_DATA_IN,
+            AtaDataDirection::Out => ata_flags |= ATA_FLAGS_DATA_OUT,
+            AtaDataDirection::None => {}
+        }
+        if previous.is_some() {
+            ata_flags |= ATA_FLAGS_48BIT_COMMAND;
+        }
+
+        let ata_pt = AtaPassThroughEx {
+            length: header_len as u16,
+            ata_flags,
             path_id: 0,
             target_id: 0,
             lun: 0,
             reserved_as_uchar: 0,
-            data_transfer_length: 512,
-            timeout_value: 30,
+            data_transfer_length: data.len() as u32,
+            timeout_value: timeout.as_secs().max(1) as u32,
             reserved_as_ulong: 0,
-            data_buffer_offset: mem::size_of::<AtaPassThroughEx>(),
-            previous_task_file: [0; 8],
-            current_task_file: [0; 8],
+            data_buffer_offset: if data.is_empty() { 0 } else { header_len },
+            previous_task_file: previous.unwrap_or_default().to_ide_regs(),
+            current_task_file: current.to_ide_regs(),
         };
 
-        // Set up the command
-        ata_pt.current_task_file[6] = ATA_IDENTIFY_DEVICE;
-
         let mut bytes_returned = 0u32;
-        let mut buffer = vec![0u8; mem::size_of::<AtaPassThroughEx>() + 512];
-        
+        let mut buffer = vec![0u8; header_len + data.len()];
+
         unsafe {
-            // Copy the ATA_PASS_THROUGH_EX structure to buffer
             let ata_pt_bytes = std::slice::from_raw_parts(
                 &ata_pt as *const _ as *const u8,
-                mem::size_of::<AtaPassThroughEx>()
+                header_len
             );
-            buffer[..mem::size_of::<AtaPassThroughEx>()].copy_from_slice(ata_pt_bytes);
+            buffer[..header_len].copy_from_slice(ata_pt_bytes);
+            if direction == AtaDataDirection::Out {
+                buffer[header_len..].copy_from_slice(data);
+            }
 
-            let success = DeviceIoControl(
+            DeviceIoControl(
                 self.handle,
                 IOCTL_ATA_PASS_THROUGH,
                 Some(buffer.as_ptr() as *const _),
@@ -212,157 +380,190 @@ impl AtaInterface {
                 buffer.len() as u32,
                 Some(&mut bytes_returned),
                 None,
-            );
+            ).map_err(|e| io::Error::other(format!("ATA command {:02X}h failed: {}", current.command, e)))?;
 
-            if success.is_err() {
-                return Err(io::Error::new(io::ErrorKind::Other, "IDENTIFY DEVICE command failed"));
+            if direction == AtaDataDirection::In {
+                data.copy_from_slice(&buffer[header_len..]);
             }
 
-            // Copy data from buffer to identify_data
-            let data_start = mem::size_of::<AtaPassThroughEx>();
-            let data_bytes = &buffer[data_start..data_start + 512];
-            let data_words = std::slice::from_raw_parts(data_bytes.as_ptr() as *const u16, 256);
-            identify_data.data.copy_from_slice(data_words);
+            let result_ata_pt = &*(buffer.as_ptr() as *const AtaPassThroughEx);
+            Ok((
+                AtaTaskFile::from_ide_regs(&result_ata_pt.current_task_file),
+                AtaTaskFile::from_ide_regs(&result_ata_pt.previous_task_file),
+            ))
         }
-
-        Ok(identify_data)
     }
 
-    pub fn read_native_max_address(&self, use_ext: bool) -> io::Result<u64> {
-        let mut ata_pt = AtaPassThroughEx {
-            length: mem::size_of::<AtaPassThroughEx>() as u16,
-            ata_flags: 0x02, // ATA_FLAGS_DATA_IN
-            path_id: 0,
-            target_id: 0,
-            lun: 0,
-            reserved_as_uchar: 0,
-            data_transfer_length: 0,
-            timeout_value: 30,
-            reserved_as_ulong: 0,
-            data_buffer_offset: 0,
-            previous_task_file: [0; 8],
-            current_task_file: [0; 8],
+    /// Linux: SG_IO with a SCSI ATA PASS-THROUGH (16) CDB, translated by libata
+    /// or the USB bridge (SAT)
+    #[cfg(target_os = "linux")]
+    fn platform_pass_through(
+        &self,
+        current: AtaTaskFile,
+        previous: Option<AtaTaskFile>,
+        direction: AtaDataDirection,
+        data: &mut [u8],
+        timeout: Duration,
+    ) -> io::Result<(AtaTaskFile, AtaTaskFile)> {
+        let extend = previous.is_some();
+        let high = previous.unwrap_or_default();
+
+        let (protocol, transfer_flags, dxfer_direction) = match direction {
+            // CK_COND: return the result registers even on success
+            AtaDataDirection::None => (SAT_PROTOCOL_NON_DATA, 0x20, SG_DXFER_NONE),
+            // T_DIR=from device, BYT_BLOK, T_LENGTH in SECTOR COUNT
+            AtaDataDirection::In => (SAT_PROTOCOL_PIO_DATA_IN, 0x0E, SG_DXFER_FROM_DEV),
+            AtaDataDirection::Out => (SAT_PROTOCOL_PIO_DATA_OUT, 0x06, SG_DXFER_TO_DEV),
         };
 
-        // Set up the command
-        if use_ext {
-            ata_pt.current_task_file[6] = ATA_READ_NATIVE_MAX_ADDRESS_EXT;
-        } else {
-            ata_pt.current_task_file[6] = ATA_READ_NATIVE_MAX_ADDRESS;
-        }
+        let cdb: [u8; 16] = [
+            SAT_ATA_PASS_THROUGH_16,
+            (protocol << 1) | extend as u8,
+            transfer_flags,
+            high.features, current.features,
+            high.sector_count, current.sector_count,
+            high.lba_low, current.lba_low,
+            high.lba_mid, current.lba_mid,
+            high.lba_high, current.lba_high,
+            current.device,
+            current.command,
+            0,
+        ];
+
+        let mut sense = [0u8; 32];
+        let mut header = SgIoHdr {
+            interface_id: b'S' as c_int,
+            dxfer_direction,
+            cmd_len: cdb.len() as u8,
+            mx_sb_len: sense.len() as u8,
+            iovec_count: 0,
+            dxfer_len: data.len() as u32,
+            dxferp: if data.is_empty() { std::ptr::null_mut() } else { data.as_mut_ptr() as *mut _ },
+            cmdp: cdb.as_ptr(),
+            sbp: sense.as_mut_ptr(),
+            timeout: timeout.as_millis().min(u32::MAX as u128) as u32,
+            flags: 0,
+            pack_id: 0,
+            usr_ptr: std::ptr::null_mut(),
+            status: 0,
+            masked_status: 0,
+            msg_status: 0,
+            sb_len_wr: 0,
+            host_status: 0,
+            driver_status: 0,
+            resid: 0,
+            duration: 0,
+            info: 0,
+        };
 
-        let mut bytes_returned = 0u32;
-        let mut buffer = vec![0u8; mem::size_of::<AtaPassThroughEx>()];
-        
-        unsafe {
-            let ata_pt_bytes = std::slice::from_raw_parts(
-                &ata_pt as *const _ as *const u8,
-                mem::size_of::<AtaPassThroughEx>()
-            );
-            buffer.copy_from_slice(ata_pt_bytes);
+        let fd: RawFd = self.file.as_raw_fd();
+        if unsafe { ioctl(fd, SG_IO as _, &mut header as *mut SgIoHdr) } < 0 {
+            return Err(io::Error::last_os_error());
+        }
 
-            let success = DeviceIoControl(
-                self.handle,
-                IOCTL_ATA_PASS_THROUGH,
-                Some(buffer.as_ptr() as *const _),
-                buffer.len() as u32,
-                Some(buffer.as_mut_ptr() as *mut _),
-                buffer.len() as u32,
-                Some(&mut bytes_returned),
-                None,
-            );
+        // DRIVER_SENSE only signals that sense data is present
+        if header.host_status != 0 || (header.driver_status & !SG_DRIVER_SENSE) != 0 {
+            return Err(io::Error::other(format!(
+                "ATA command {:02X}h failed (host status {:#x}, driver status {:#x})",
+                current.command, header.host_status, header.driver_status
+            )));
+        }
 
-            if success.is_err() {
-                return Err(io::Error::new(io::ErrorKind::Other, "READ NATIVE MAX ADDRESS command failed"));
+        let sense = &sense[..header.sb_len_wr as usize];
+        match parse_ata_status_sense(sense) {
+            Some(registers) => Ok(registers),
+            None if header.status == 0 => {
+                // No ATA status returned: command completed with DRDY set and no error
+                let status = AtaTaskFile { command: 0x50, ..Default::default() };
+                Ok((status, AtaTaskFile::default()))
             }
+            None => Err(io::Error::other(format!(
+                "ATA command {:02X}h rejected (SCSI status {:#x}, sense key {:#x})",
+                current.command, header.status, sense_key(sense)
+            ))),
+        }
+    }
 
-            // Extract result from task file registers
-            let result_ata_pt = &*(buffer.as_ptr() as *const AtaPassThroughEx);
-            let lba = if use_ext {
-                // 48-bit LBA
-                let lba_low = result_ata_pt.current_task_file[3] as u64;
-                let lba_mid = result_ata_pt.current_task_file[4] as u64;
-                let lba_high = result_ata_pt.current_task_file[5] as u64;
-                let lba_low_prev = result_ata_pt.previous_task_file[3] as u64;
-                let lba_mid_prev = result_ata_pt.previous_task_file[4] as u64;
-                let lba_high_prev = result_ata_pt.previous_task_file[5] as u64;
-                
-                lba_low | (lba_mid << 8) | (lba_high << 16) | 
-                (lba_low_prev << 24) | (lba_mid_prev << 32) | (lba_high_prev << 40)
-            } else {
-                // 28-bit LBA
-                let lba_low = result_ata_pt.current_task_file[3] as u64;
-                let lba_mid = result_ata_pt.current_task_file[4] as u64;
-                let lba_high = result_ata_pt.current_task_file[5] as u64;
-                let device = result_ata_pt.current_task_file[6] as u64;
-                
-                lba_low | (lba_mid << 8) | (lba_high << 16) | ((device & 0x0F) << 24)
-            };
+    #[cfg(all(unix, not(target_os = "linux")))]
+    fn platform_pass_through(
+        &self,
+        _current: AtaTaskFile,
+        _previous: Option<AtaTaskFile>,
+        _direction: AtaDataDirection,
+        _data: &mut [u8],
+        _timeout: Duration,
+    ) -> io::Result<(AtaTaskFile, AtaTaskFile)> {
+        Err(io::Error::new(io::ErrorKind::Unsupported, "ATA pass-through is not supported on this platform"))
+    }
+
+    pub fn identify_device(&self) -> io::Result<IdentifyDeviceData> {
+        let mut identify_data = IdentifyDeviceData { data: [0; 256] };
+        let mut buffer = [0u8; 512];
+
+        let command = AtaTaskFile {
+            sector_count: 1,
+            command: ATA_IDENTIFY_DEVICE,
+            ..Default::default()
+        };
 
-            Ok(lba)
+        self.pass_through(command, None, AtaDataDirection::In, &mut buffer, ATA_COMMAND_TIMEOUT)
+            .map_err(|e| io::Error::new(e.kind(), format!("IDENTIFY DEVICE command failed: {}", e)))?;
+
+        for (word, bytes) in identify_data.data.iter_mut().zip(buffer.chunks_exact(2)) {
+            *word = u16::from_le_bytes([bytes[0], bytes[1]]);
         }
+
+        Ok(identify_data)
     }
 
-    pub fn set_max_address(&self, lba: u64, use_ext: bool) -> io::Result<()> {
-        let mut ata_pt = AtaPassThroughEx {
-            length: mem::size_of::<AtaPassThroughEx>() as u16,
-            ata_flags: 0x00, // No data transfer
-            path_id: 0,
-            target_id: 0,
-            lun: 0,
-            reserved_as_uchar: 0,
-            data_transfer_length: 0,
-            timeout_value: 30,
-            reserved_as_ulong: 0,
-            data_buffer_offset: 0,
-            previous_task_file: [0; 8],
-            current_task_file: [0; 8],
+    pub fn read_native_max_address(&self, use_ext: bool) -> io::Result<u64> {
+        let command = AtaTaskFile {
+            device: ATA_DEVICE_LBA,
+            command: if use_ext { ATA_READ_NATIVE_MAX_ADDRESS_EXT } else { ATA_READ_NATIVE_MAX_ADDRESS },
+            ..Default::default()
         };
+        let previous = use_ext.then(AtaTaskFile::default);
+
+        let (current, previous) = self.pass_through(command, previous, AtaDataDirection::None, &mut [], ATA_COMMAND_TIMEOUT)
+            .map_err(|e| io::Error::new(e.kind(), format!("READ NATIVE MAX ADDRESS command failed: {}", e)))?;
 
-        // Set up the command and LBA
-        if use_ext {
-            ata_pt.current_task_file[6] = ATA_SET_MAX_ADDRESS_EXT;
+        let lba = if use_ext {
             // 48-bit LBA
-            ata_pt.current_task_file[3] = (lba & 0xFF) as u8;
-            ata_pt.current_task_file[4] = ((lba >> 8) & 0xFF) as u8;
-            ata_pt.current_task_file[5] = ((lba >> 16) & 0xFF) as u8;
-            ata_pt.previous_task_file[3] = ((lba >> 24) & 0xFF) as u8;
-            ata_pt.previous_task_file[4] = ((lba >> 32) & 0xFF) as u8;
-            ata_pt.previous_task_file[5] = ((lba >> 40) & 0xFF) as u8;
+            current.lba() | (previous.lba() << 24)
         } else {
-            ata_pt.current_task_file[6] = ATA_SET_MAX_ADDRESS;
             // 28-bit LBA
-            ata_pt.current_task_file[3] = (lba & 0xFF) as u8;
-            ata_pt.current_task_file[4] = ((lba >> 8) & 0xFF) as u8;
-            ata_pt.current_task_file[5] = ((lba >> 16) & 0xFF) as u8;
-            ata_pt.current_task_file[6] |= ((lba >> 24) & 0x0F) as u8;
-        }
+            current.lba() | (((current.device & 0x0F) as u64) << 24)
+        };
 
-        let mut bytes_returned = 0u32;
-        let mut buffer = vec![0u8; mem::size_of::<AtaPassThroughEx>()];
-        
-        unsafe {
-            let ata_pt_bytes = std::slice::from_raw_parts(
-                &ata_pt as *const _ as *const u8,
-                mem::size_of::<AtaPassThroughEx>()
-            );
-            buffer.copy_from_slice(ata_pt_bytes);
+        Ok(lba)
+    }
 
-            let success = DeviceIoControl(
-                self.handle,
-                IOCTL_ATA_PASS_THROUGH,
-                Some(buffer.as_ptr() as *const _),
-                buffer.len() as u32,
-                Some(buffer.as_mut_ptr() as *mut _),
-                buffer.len() as u32,
-                Some(&mut bytes_returned),
-                None,
-            );
+    pub fn set_max_address(&self, lba: u64, use_ext: bool) -> io::Result<()> {
+        // Sector count bit 0 clear: the new maximum is volatile
+        let mut command = AtaTaskFile {
+            lba_low: (lba & 0xFF) as u8,
+            lba_mid: ((lba >> 8) & 0xFF) as u8,
+            lba_high: ((lba >> 16) & 0xFF) as u8,
+            device: ATA_DEVICE_LBA,
+            ..Default::default()
+        };
 
-            if success.is_err() {
-                return Err(io::Error::new(io::ErrorKind::Other, "SET MAX ADDRESS command failed"));
-            }
-        }
+        let previous = if use_ext {
+            command.command = ATA_SET_MAX_ADDRESS_EXT;
+            Some(AtaTaskFile {
+                lba_low: ((lba >> 24) & 0xFF) as u8,
+                lba_mid: ((lba >> 32) & 0xFF) as u8,
+                lba_high: ((lba >> 40) & 0xFF) as u8,
+                ..Default::default()
+            })
+        } else {
+            command.command = ATA_SET_MAX_ADDRESS;
+            command.device |= ((lba >> 24) & 0x0F) as u8;
+            None
+        };
+
+        self.pass_through(command, previous, AtaDataDirection::None, &mut [], ATA_COMMAND_TIMEOUT)
+            .map_err(|e| io::Error::new(e.kind(), format!("SET MAX ADDRESS command failed: {}", e)))?;
 
         Ok(())
     }
@@ -433,34 +634,233 @@ impl AtaInterface {
         Ok(self.parse_identify_data(&identify_data))
     }
     
-    /// Perform ATA Security Erase
+    /// The drive's own estimate for SECURITY ERASE UNIT, if it reports one
+    pub fn security_erase_estimate(&self, data: &IdentifyDeviceData, enhanced: bool) -> Option<Duration> {
+        let word = if enhanced { data.data[90] } else { data.data[89] };
+        security_erase_time(word)
+    }
+
+    /// How long to wait for SECURITY ERASE UNIT, from the erase time the drive reports
+    pub fn security_erase_timeout(&self, data: &IdentifyDeviceData, enhanced: bool) -> Duration {
+        match self.security_erase_estimate(data, enhanced) {
+            // The reported time is an estimate; allow twice as long before giving up
+            Some(time) => (time * 2).max(Duration::from_secs(600)),
+            // Not reported: assume a slow 50 MB/s over the user area
+            None => {
+                let user_capacity = self.parse_identify_data(data).user_capacity;
+                Duration::from_secs((user_capacity / 50_000_000).max(3600))
+            }
+        }
+    }
+
+    /// SECURITY SET PASSWORD (user password, high master password capability)
+    pub fn security_set_password(&self, password: &[u8]) -> io::Result<()> {
+        self.security_set_user_password(password, false)
+    }
+
+    /// SECURITY SET PASSWORD for the user password. At maximum security the
+    /// master password can only erase the drive, not unlock it.
+    pub fn security_set_user_password(&self, password: &[u8], maximum_security: bool) -> io::Result<()> {
+        let control = if maximum_security { 0x0100 } else { 0x0000 };
+        let mut block = security_password_block(password, control);
+        let command = AtaTaskFile { sector_count: 1, command: ATA_SECURITY_SET_PASSWORD, ..Default::default() };
+
+        self.pass_through(command, None, AtaDataDirection::Out, &mut block, ATA_COMMAND_TIMEOUT)
+            .map_err(|e| io::Error::new(e.kind(), format!("SECURITY SET PASSWORD command failed: {}", e)))?;
+        Ok(())
+    }
+
+    /// SECURITY ERASE PREPARE; must immediately precede SECURITY ERASE UNIT
+    pub fn security_erase_prepare(&self) -> io::Result<()> {
+        let command = AtaTaskFile { command: ATA_SECURITY_ERASE_PREPARE, ..Default::default() };
+
+        self.pass_through(command, None, AtaDataDirection::None, &mut [], ATA_COMMAND_TIMEOUT)
+            .map_err(|e| io::Error::new(e.kind(), format!("SECURITY ERASE PREPARE command failed: {}", e)))?;
+        Ok(())
+    }
+
+    /// SECURITY ERASE UNIT with the user password. Blocks until the drive finishes.
+    pub fn security_erase_unit(&self, password: &[u8], enhanced: bool, timeout: Duration) -> io::Result<()> {
+        let control = if enhanced { 0x0002 } else { 0x0000 };
+        let mut block = security_password_block(password, control);
+        let command = AtaTaskFile { sector_count: 1, command: ATA_SECURITY_ERASE_UNIT, ..Default::default() };
+
+        self.pass_through(command, None, AtaDataDirection::Out, &mut block, timeout)
+            .map_err(|e| io::Error::new(e.kind(), format!("SECURITY ERASE UNIT command failed: {}", e)))?;
+        Ok(())
+    }
+
+    /// SECURITY DISABLE PASSWORD with the user password
+    pub fn security_disable_password(&self, password: &[u8]) -> io::Result<()> {
+        let mut block = security_password_block(password, 0x0000);
+        let command = AtaTaskFile { sector_count: 1, command: ATA_SECURITY_DISABLE_PASSWORD, ..Default::default() };
+
+        self.pass_through(command, None, AtaDataDirection::Out, &mut block, ATA_COMMAND_TIMEOUT)
+            .map_err(|e| io::Error::new(e.kind(), format!("SECURITY DISABLE PASSWORD command failed: {}", e)))?;
+        Ok(())
+    }
+
+    /// SECURITY FREEZE LOCK: the drive refuses security commands until it is power-cycled
+    pub fn security_freeze_lock(&self) -> io::Result<()> {
+        let command = AtaTaskFile { command: ATA_SECURITY_FREEZE_LOCK, ..Default::default() };
+
+        self.pass_through(command, None, AtaDataDirection::None, &mut [], ATA_COMMAND_TIMEOUT)
+            .map_err(|e| io::Error::new(e.kind(), format!("SECURITY FREEZE LOCK command failed: {}", e)))?;
+        Ok(())
+    }
+
+    /// Perform ATA Security Erase: set a temporary password, then ERASE PREPARE and ERASE UNIT
     pub fn security_erase(&self, enhanced: bool) -> io::Result<()> {
-        // This is a simplified implementation
-        // In a real implementation, this would:
-        // 1. Check if security is supported
-        // 2. Set a temporary password
-        // 3. Issue the security erase command
-        // 4. Wait for completion
-        
-        println!("🔧 Performing ATA Security Erase (Enhanced: {})", enhanced);
-        
-        // For safety in this demo, we'll just simulate the operation
-        std::thread::sleep(std::time::Duration::from_secs(2));
-        
-        // In a real implementation, you would use ATA_PASS_THROUGH to send:
-        // - ATA_SECURITY_SET_PASSWORD command
-        // - ATA_SECURITY_ERASE_PREPARE command  
-        // - ATA_SECURITY_ERASE_UNIT command
-        
-        println!("✅ ATA Security Erase completed");
+        let identify_data = self.identify_device()?;
+        let drive_info = self.parse_identify_data(&identify_data);
+
+        if !drive_info.security_supported {
+            return Err(io::Error::new(io::ErrorKind::Unsupported, "Drive does not support the ATA Security feature set"));
+        }
+        if drive_info.security_frozen {
+            return Err(io::Error::new(io::ErrorKind::PermissionDenied, "Drive security is frozen; power-cycle the drive first"));
+        }
+        if drive_info.security_locked {
+            return Err(io::Error::new(io::ErrorKind::PermissionDenied, "Drive is locked with an unknown password"));
+        }
+        if enhanced && identify_data.data[128] & 0x0020 == 0 {
+            return Err(io::Error::new(io::ErrorKind::Unsupported, "Enhanced security erase is not supported by this drive"));
+        }
+
+        let timeout = self.security_erase_timeout(&identify_data, enhanced);
+        info!(enhanced, timeout_minutes = timeout.as_secs() / 60, "Performing ATA Security Erase");
+        info!(password = %String::from_utf8_lossy(SECURITY_ERASE_PASSWORD), "Setting temporary user password");
+
+        self.security_set_password(SECURITY_ERASE_PASSWORD)?;
+
+        let result = self.security_erase_prepare()
+            .and_then(|_| self.security_erase_unit(SECURITY_ERASE_PASSWORD, enhanced, timeout));
+
+        if let Err(e) = result {
+            // Don't leave the drive protected by a password the operator doesn't know about
+            if let Err(disable_err) = self.security_disable_password(SECURITY_ERASE_PASSWORD) {
+                warn!(error = %disable_err, "Could not remove temporary password");
+            }
+            return Err(e);
+        }
+
+        // A completed erase clears the user password; disable it explicitly if the drive didn't
+        if self.get_drive_info()?.security_enabled {
+            self.security_disable_password(SECURITY_ERASE_PASSWORD)?;
+        }
+
+        info!("ATA Security Erase completed");
         Ok(())
     }
 }
 
+/// Erase time from IDENTIFY word 89 (normal) or 90 (enhanced), or `None` if not reported.
+/// Values are in 2-minute units; the maximum value means "longer than" that time.
+pub fn security_erase_time(word: u16) -> Option<Duration> {
+    // Bit 15 selects the extended (15-bit) format
+    let units = if word & 0x8000 != 0 { word & 0x7FFF } else { word & 0x00FF };
+    if units == 0 {
+        None
+    } else {
+        Some(Duration::from_secs(units as u64 * 2 * 60))
+    }
+}
+
+/// 512-byte data block for the security commands: control word followed by a 32-byte password
+fn security_password_block(password: &[u8], control: u16) -> [u8; 512] {
+    let mut block = [0u8; 512];
+    block[0..2].copy_from_slice(&control.to_le_bytes());
+    let len = password.len().min(32);
+    block[2..2 + len].copy_from_slice(&password[..len]);
+    block
+}
+
+/// Extract result registers from the sense data returned by ATA PASS-THROUGH
+#[cfg(target_os = "linux")]
+fn parse_ata_status_sense(sense: &[u8]) -> Option<(AtaTaskFile, AtaTaskFile)> {
+    match sense.first()? & 0x7F {
+        // Descriptor format: look for the ATA Status Return descriptor (09h)
+        0x72 => {
+            let mut offset = 8;
+            while offset + 14 <= sense.len() {
+                let descriptor = &sense[offset..];
+                if descriptor[0] == 0x09 {
+                    let current = AtaTaskFile {
+                        features: descriptor[3],
+                        sector_count: descriptor[5],
+                        lba_low: descriptor[7],
+                        lba_mid: descriptor[9],
+                        lba_high: descriptor[11],
+                        device: descriptor[12],
+                        command: descriptor[13],
+                    };
+                    let previous = AtaTaskFile {
+                        sector_count: descriptor[4],
+                        lba_low: descriptor[6],
+                        lba_mid: descriptor[8],
+                        lba_high: descriptor[10],
+                        ..Default::default()
+                    };
+                    return Some((current, previous));
+                }
+                offset += 2 + descriptor[1] as usize;
+            }
+            None
+        }
+        // Fixed format with "ATA pass through information available" (00h/1Dh)
+        0x70 if sense.len() >= 14 && sense[12] == 0x00 && sense[13] == 0x1D => {
+            let current = AtaTaskFile {
+                features: sense[3],
+                sector_count: sense[5],
+                lba_low: sense[9],
+                lba_mid: sense[10],
+                lba_high: sense[11],
+                device: sense[6],
+                command: sense[4],
+            };
+            Some((current, AtaTaskFile::default()))
+        }
+        _ => None,
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn sense_key(sense: &[u8]) -> u8 {
+    match sense.first().map(|b| b & 0x7F) {
+        Some(0x72) | Some(0x73) => sense.get(1).copied().unwrap_or(0) & 0x0F,
+        Some(_) => sense.get(2).copied().unwrap_or(0) & 0x0F,
+        None => 0,
+    }
+}
+
+#[cfg(windows)]
 impl Drop for AtaInterface {
     fn drop(&mut self) {
         unsafe {
             CloseHandle(self.handle).ok();
         }
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_security_erase_time() {
+        assert_eq!(security_erase_time(0), None);
+        assert_eq!(security_erase_time(30), Some(Duration::from_secs(60 * 60)));
+        // Extended format: bit 15 set, 15-bit value
+        assert_eq!(security_erase_time(0x8000 | 300), Some(Duration::from_secs(600 * 60)));
+        // Short format ignores the reserved high byte
+        assert_eq!(security_erase_time(0x0100 | 1), Some(Duration::from_secs(2 * 60)));
+    }
+
+    #[test]
+    fn test_security_password_block() {
+        let block = security_password_block(b"HDD-Tool", 0x0002);
+        assert_eq!(&block[0..2], &[0x02, 0x00]);
+        assert_eq!(&block[2..10], b"HDD-Tool");
+        assert!(block[10..].iter().all(|&b| b == 0));
+    }
+}
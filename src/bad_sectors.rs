@@ -0,0 +1,229 @@
+//! Fault-tolerant overwriting for drives with failing sectors
+//!
+//! A single unwritable sector would otherwise abort a whole overwrite pass.
+//! `write_block_tolerant` retries a failed block in progressively smaller
+//! pieces down to single sectors, skips the sectors that keep failing and
+//! records them in a `BadSectorLog` so the rest of the drive is still wiped.
+
+use std::io::{self, Seek, SeekFrom, Write};
+use tracing::warn;
+use serde::{Deserialize, Serialize};
+
+/// Intermediate block sizes tried before falling back to single sectors
+const RETRY_BLOCK_SIZES: &[usize] = &[64 * 1024, 4096];
+/// Attempts per sector before it is given up as bad
+const SECTOR_RETRY_ATTEMPTS: u32 = 3;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BadSectorRange {
+    pub start_sector: u64,
+    pub sector_count: u64,
+    /// Last error reported for the range
+    pub error: String,
+}
+
+/// Sectors that could not be overwritten and were skipped
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct BadSectorLog {
+    /// Sorted, non-overlapping ranges
+    pub ranges: Vec<BadSectorRange>,
+    /// Blocks that failed at full size and had to be rewritten piecewise
+    pub retried_blocks: u64,
+}
+
+impl BadSectorLog {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn skipped_sectors(&self) -> u64 {
+        self.ranges.iter().map(|r| r.sector_count).sum()
+    }
+
+    /// True when more sectors were skipped than `max_bad_sectors` allows
+    pub fn exceeds(&self, max_bad_sectors: u64) -> bool {
+        self.skipped_sectors() > max_bad_sectors
+    }
+
+    /// Whether any logged sector falls inside `[start_sector, start_sector + sector_count)`
+    pub fn overlaps(&self, start_sector: u64, sector_count: u64) -> bool {
+        let end = start_sector + sector_count;
+        self.ranges
+            .iter()
+            .any(|r| r.start_sector < end && start_sector < r.start_sector + r.sector_count)
+    }
+
+    /// Add a bad sector, merging it with an adjacent or overlapping range.
+    /// The same sector failing again in a later pass is only counted once.
+    pub fn record(&mut self, sector: u64, error: &str) {
+        let position = self.ranges.partition_point(|r| r.start_sector + r.sector_count < sector);
+
+        if let Some(range) = self.ranges.get_mut(position)
+            && range.start_sector <= sector + 1 && sector <= range.start_sector + range.sector_count
+        {
+            let end = (range.start_sector + range.sector_count).max(sector + 1);
+            range.start_sector = range.start_sector.min(sector);
+            range.sector_count = end - range.start_sector;
+            range.error = error.to_string();
+
+            // Growing the range may have closed the gap to the next one
+            if let Some(next) = self.ranges.get(position + 1).cloned() {
+                let range = &mut self.ranges[position];
+                if next.start_sector <= range.start_sector + range.sector_count {
+                    let end = (range.start_sector + range.sector_count).max(next.start_sector + next.sector_count);
+                    range.sector_count = end - range.start_sector;
+                    self.ranges.remove(position + 1);
+                }
+            }
+            return;
+        }
+
+        self.ranges.insert(position, BadSectorRange {
+            start_sector: sector,
+            sector_count: 1,
+            error: error.to_string(),
+        });
+    }
+}
+
+/// Rewrite a block whose full-size write failed. Smaller pieces are retried down to
+/// single sectors; sectors that still fail are logged and skipped. On return the
+/// device is positioned at the end of the block.
+pub fn write_block_tolerant<D: Write + Seek>(
+    device: &mut D,
+    offset: u64,
+    data: &[u8],
+    sector_size: usize,
+    log: &mut BadSectorLog,
+) -> io::Result<()> {
+    log.retried_blocks += 1;
+
+    let sizes: Vec<usize> = RETRY_BLOCK_SIZES
+        .iter()
+        .copied()
+        .filter(|&size| size < data.len() && size > sector_size)
+        .chain(std::iter::once(sector_size))
+        .collect();
+
+    write_pieces(device, offset, data, &sizes, sector_size, log)?;
+    device.seek(SeekFrom::Start(offset + data.len() as u64))?;
+    Ok(())
+}
+
+fn write_pieces<D: Write + Seek>(
+    device: &mut D,
+    offset: u64,
+    data: &[u8],
+    sizes: &[usize],
+    sector_size: usize,
+    log: &mut BadSectorLog,
+) -> io::Result<()> {
+    let Some((&size, smaller)) = sizes.split_first() else {
+        return Ok(());
+    };
+    let attempts = if smaller.is_empty() { SECTOR_RETRY_ATTEMPTS } else { 1 };
+
+    for (index, piece) in data.chunks(size).enumerate() {
+        let piece_offset = offset + (index * size) as u64;
+
+        let mut last_error = None;
+        for _ in 0..attempts {
+            // Seek failures mean the device itself is gone; don't treat them as bad sectors
+            device.seek(SeekFrom::Start(piece_offset))?;
+            match device.write_all(piece) {
+                Ok(()) => {
+                    last_error = None;
+                    break;
+                }
+                Err(e) => last_error = Some(e),
+            }
+        }
+
+        let Some(error) = last_error else {
+            continue;
+        };
+
+        if smaller.is_empty() {
+            let sector = piece_offset / sector_size as u64;
+            warn!(sector, error = %error, "Skipping unwritable sector");
+            log.record(sector, &error.to_string());
+        } else {
+            write_pieces(device, piece_offset, piece, smaller, sector_size, log)?;
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    /// In-memory device that rejects any write touching the listed sectors
+    struct FaultyDevice {
+        inner: Cursor<Vec<u8>>,
+        bad: Vec<u64>,
+    }
+
+    impl Write for FaultyDevice {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            let start = self.inner.position() / 512;
+            let end = (self.inner.position() + buf.len() as u64).div_ceil(512);
+            if self.bad.iter().any(|&s| s >= start && s < end) {
+                return Err(io::Error::other("medium error"));
+            }
+            self.inner.write(buf)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    impl Seek for FaultyDevice {
+        fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+            self.inner.seek(pos)
+        }
+    }
+
+    #[test]
+    fn test_write_block_tolerant_skips_bad_sectors() {
+        let mut device = FaultyDevice {
+            inner: Cursor::new(vec![0xAA; 256 * 1024]),
+            bad: vec![3, 4, 300],
+        };
+        let data = vec![0u8; 256 * 1024];
+        let mut log = BadSectorLog::new();
+
+        write_block_tolerant(&mut device, 0, &data, 512, &mut log).unwrap();
+
+        assert_eq!(log.skipped_sectors(), 3);
+        assert_eq!(log.ranges.len(), 2);
+        assert_eq!((log.ranges[0].start_sector, log.ranges[0].sector_count), (3, 2));
+        assert_eq!(device.inner.position(), data.len() as u64);
+
+        // Everything except the bad sectors was overwritten
+        let written = device.inner.get_ref();
+        assert!(written[..3 * 512].iter().all(|&b| b == 0));
+        assert!(written[3 * 512..5 * 512].iter().all(|&b| b == 0xAA));
+        assert!(written[5 * 512..300 * 512].iter().all(|&b| b == 0));
+        assert!(log.overlaps(0, 4));
+        assert!(!log.overlaps(5, 295));
+    }
+
+    #[test]
+    fn test_record_merges_ranges() {
+        let mut log = BadSectorLog::new();
+        log.record(10, "e");
+        log.record(12, "e");
+        log.record(10, "e");
+        assert_eq!(log.skipped_sectors(), 2);
+
+        log.record(11, "e");
+        assert_eq!(log.ranges.len(), 1);
+        assert_eq!(log.skipped_sectors(), 3);
+        assert!(log.exceeds(2));
+        assert!(!log.exceeds(3));
+    }
+}
@@ -0,0 +1,1465 @@
+//! Headless command-line subcommands
+//!
+//! `hdd-tool <command> [args]` runs the command and exits; with no
+//! recognised command the GUI starts as usual.
+//!
+//! `--output json`, given anywhere on the command line, makes a command
+//! print a single JSON document on stdout, shaped like the REST API's
+//! responses, or `{"error": {...}}` when it fails. The exit code tells the
+//! outcome apart either way; see `EXIT_*`.
+
+use std::collections::{HashMap, HashSet};
+use std::fmt::Display;
+use std::io::{IsTerminal, Write};
+use hdd_tool::core::{
+    default_target_type, parse_size, ComplianceProfile, DeviceSelector, HistoryFilter, OperationHistory,
+    OperationRecord, OperationStatus, OperatorIdentity, ReportFormat, SanitizationStandard, TargetType, WipeEngine,
+    WipeError, WipeErrorCode, WipePriority, WipeRecipe, WipeResult, WipeScope, DEFAULT_HISTORY_PATH,
+};
+use hdd_tool::security::{verify_certificate_file, HistoryMatch};
+use hdd_tool::security_state::SecurityStateAction;
+use hdd_tool::secure_delete::{secure_delete_paths, ShredOptions, ShredPattern};
+use serde::Serialize;
+use serde_json::json;
+
+use crate::t;
+
+/// The command did what was asked
+pub(crate) const EXIT_OK: i32 = 0;
+/// The command ran but failed: a wipe failed, a certificate is invalid, files were left behind
+pub(crate) const EXIT_FAILED: i32 = 1;
+/// Bad arguments or configuration
+pub(crate) const EXIT_USAGE: i32 = 2;
+/// The engine refused to start: not logged in, not permitted, not confirmed
+pub(crate) const EXIT_REFUSED: i32 = 3;
+/// The operation was cancelled while it ran
+pub(crate) const EXIT_CANCELLED: i32 = 4;
+
+/// Run a subcommand if one was given. Returns the process exit code, or
+/// `None` when the arguments do not name a subcommand.
+pub fn run(args: Vec<String>) -> Option<i32> {
+    let mut json_output = false;
+    let mut args = args.into_iter();
+    let mut remaining = Vec::new();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--output=json" => json_output = true,
+            "--output=text" => json_output = false,
+            "--output" => match args.next().as_deref() {
+                Some("json") => json_output = true,
+                Some("text") => json_output = false,
+                _ => {
+                    eprintln!("❌ Usage: --output text|json");
+                    return Some(EXIT_USAGE);
+                }
+            },
+            _ => remaining.push(arg),
+        }
+    }
+    let Some(command) = remaining.first().cloned() else {
+        // `--output` alone still has to be a command-line run
+        if json_output {
+            print_usage();
+            return Some(EXIT_USAGE);
+        }
+        return None;
+    };
+    let rest = &remaining[1..];
+
+    // Commands that print results as they go take no part in JSON output
+    let out = match command.as_str() {
+        "verify-cert" | "shred" | "wipe" | "psid-revert" | "schedule" | "export" | "profiles" | "scan" if json_output => Output::json(),
+        _ => Output::text(),
+    };
+
+    match command.as_str() {
+        "verify-cert" => Some(verify_cert(rest, &out)),
+        "shred" => Some(shred(rest, &out)),
+        "wipe" => Some(wipe(rest, &out)),
+        "psid-revert" => Some(psid_revert(rest, &out)),
+        "profiles" => Some(list_profiles(&out)),
+        "scan" => Some(scan(rest, &out)),
+        "watch" => Some(watch(rest)),
+        "schedule" => Some(schedule(rest, &out)),
+        "export" => Some(export(rest, &out)),
+        #[cfg(feature = "tui")]
+        "tui" => Some(crate::tui::run(rest)),
+        #[cfg(feature = "server")]
+        "serve" => Some(serve(rest)),
+        #[cfg(feature = "server")]
+        "station" => Some(station(rest)),
+        "help" | "--help" | "-h" => {
+            print_usage();
+            Some(EXIT_OK)
+        }
+        _ => None,
+    }
+}
+
+fn print_usage() {
+    println!("{} hdd-tool [--output text|json] [command]", t!("cli-usage"));
+    println!();
+    println!("{}", t!("cli-commands"));
+    command_help("scan --config <file>", "cli-help-scan");
+    command_help("verify-cert <file> [--json]", "cli-help-verify-cert");
+    command_help("shred <path>... [--passes N] [--pattern zeros|ones|random|0xNN] [--no-slack]", "cli-help-shred");
+    command_help(
+        "wipe [<device>...] --config <file> [--profile <id> | --recipe <file.toml>] [--type hdd|ssd|flash]\n       \
+         [--devices <name>,...] [--serial <serial>] [--wwn <wwn>] [--model <pattern>]\n       \
+         [--min-size <size>] [--max-size <size>] [--bus <bus>]\n       \
+         [--confirm <phrase> | --confirm-token <serial>,... | --yes-i-know-what-im-doing] [--no-certificate] [--no-trim]\n       \
+         [--security-state disable|freeze|brick]",
+        "cli-help-wipe",
+    );
+    command_help("wipe --all-non-system --config <file> [--include-removable] [--plan-token <token>]", "cli-help-wipe-all");
+    command_help("psid-revert <device> --psid <psid> --config <file>", "cli-help-psid-revert");
+    command_help("schedule --at <time> <wipe arguments>", "cli-help-schedule");
+    command_help("schedule --list | --run | --cancel <job-id> --config <file>", "cli-help-schedule-manage");
+    command_help("export --operation <id> (--dir <dir> | --zip <file.zip>) --config <file>", "cli-help-export");
+    command_help(
+        "export [--operation <id>] --format pdf|json|csv|xml --out <file> --config <file>\n       \
+         [--since <time>] [--until <time>]",
+        "cli-help-export-report",
+    );
+    command_help("profiles", "cli-help-profiles");
+    command_help("watch [<operation-id>] [--history <file>] [--interval <seconds>]", "cli-help-watch");
+    #[cfg(feature = "tui")]
+    command_help("tui --config <file>", "cli-help-tui");
+    #[cfg(feature = "server")]
+    command_help("serve --config <file> [--port N] [--grpc-port N]", "cli-help-serve");
+    #[cfg(feature = "server")]
+    command_help("station --config <file> --server <url> --station-id <id>", "cli-help-station");
+    command_help("help", "cli-help-help");
+    println!();
+    println!("{}", t!("cli-help-json"));
+    println!("{}", t!("cli-help-exit-codes"));
+    println!();
+    println!("{}", t!("cli-help-gui"));
+}
+
+/// One command of the usage text: its syntax, then the translated
+/// description, on the same line when the syntax leaves room
+fn command_help(syntax: &str, description: &str) {
+    if syntax.len() < 30 {
+        println!("  {:<30}{}", syntax, t!(description));
+    } else {
+        println!("  {}", syntax);
+        println!("{:32}{}", "", t!(description));
+    }
+}
+
+/// How a command reports its result. In JSON mode the engine's own console
+/// output is moved to stderr while the command runs (on Unix), so stdout
+/// carries nothing but the JSON document.
+pub(crate) struct Output {
+    json: Option<JsonStdout>,
+}
+
+impl Output {
+    pub(crate) fn text() -> Self {
+        Self { json: None }
+    }
+
+    fn json() -> Self {
+        Self { json: Some(JsonStdout::redirect()) }
+    }
+
+    pub(crate) fn is_json(&self) -> bool {
+        self.json.is_some()
+    }
+
+    /// Print `value` as the command's JSON result; a no-op in text mode
+    pub(crate) fn emit<T: Serialize>(&self, value: &T) {
+        if let Some(ref stdout) = self.json {
+            match serde_json::to_string_pretty(value) {
+                Ok(text) => stdout.write_line(&text),
+                Err(e) => stdout.write_line(&json!({ "error": { "message": e.to_string() } }).to_string()),
+            }
+        }
+    }
+
+    /// Report a failure and return `exit_code`
+    pub(crate) fn fail(&self, exit_code: i32, message: impl Display) -> i32 {
+        if self.is_json() {
+            self.emit(&json!({ "error": { "message": message.to_string() } }));
+        } else {
+            eprintln!("❌ {}", message);
+        }
+        exit_code
+    }
+
+    /// Report an engine error, with the exit code its error code maps to
+    pub(crate) fn engine_error(&self, error: &WipeError) -> i32 {
+        let exit_code = exit_code_for(error);
+        if self.is_json() {
+            self.emit(&json!({ "error": { "code": format!("{:?}", error.code), "message": error.message } }));
+        } else {
+            eprintln!("❌ {}", error);
+        }
+        exit_code
+    }
+}
+
+pub(crate) fn exit_code_for(error: &WipeError) -> i32 {
+    match error.code {
+        WipeErrorCode::NotAuthenticated | WipeErrorCode::InsufficientPrivileges | WipeErrorCode::PolicyDenied => EXIT_REFUSED,
+        WipeErrorCode::Cancelled => EXIT_CANCELLED,
+        _ => EXIT_FAILED,
+    }
+}
+
+/// The process's real stdout while stdout itself points at stderr
+struct JsonStdout {
+    #[cfg(unix)]
+    saved: i32,
+}
+
+impl JsonStdout {
+    fn redirect() -> Self {
+        let _ = std::io::stdout().flush();
+        #[cfg(unix)]
+        // SAFETY: descriptor duplication only; a failed dup leaves stdout alone
+        unsafe {
+            let saved = libc::dup(libc::STDOUT_FILENO);
+            if saved >= 0 {
+                libc::dup2(libc::STDERR_FILENO, libc::STDOUT_FILENO);
+            }
+            Self { saved }
+        }
+        #[cfg(not(unix))]
+        Self {}
+    }
+
+    fn write_line(&self, text: &str) {
+        let _ = std::io::stdout().flush();
+        #[cfg(unix)]
+        if self.saved >= 0 {
+            use std::os::unix::io::FromRawFd;
+            // SAFETY: `saved` stays open until drop; ManuallyDrop keeps it from being closed here
+            let mut stdout = std::mem::ManuallyDrop::new(unsafe { std::fs::File::from_raw_fd(self.saved) });
+            let _ = writeln!(stdout, "{}", text);
+            return;
+        }
+        println!("{}", text);
+    }
+}
+
+impl Drop for JsonStdout {
+    fn drop(&mut self) {
+        let _ = std::io::stdout().flush();
+        #[cfg(unix)]
+        if self.saved >= 0 {
+            // SAFETY: restores the descriptor saved by `redirect`
+            unsafe {
+                libc::dup2(self.saved, libc::STDOUT_FILENO);
+                libc::close(self.saved);
+            }
+        }
+    }
+}
+
+fn verify_cert(args: &[String], out: &Output) -> i32 {
+    // `--json` predates the global `--output json`
+    let json_output = out.is_json() || args.iter().any(|a| a == "--json");
+    let path = match args.iter().find(|a| !a.starts_with("--")) {
+        Some(path) => path,
+        None => {
+            eprintln!("❌ Usage: hdd-tool verify-cert <file> [--json]");
+            return EXIT_USAGE;
+        }
+    };
+
+    // History is optional - verification still works on machines that did not run the wipe
+    let history = OperationHistory::open(DEFAULT_HISTORY_PATH).ok();
+
+    let verdict = match verify_certificate_file(path, history.as_ref()) {
+        Ok(verdict) => verdict,
+        Err(e) => return out.fail(EXIT_USAGE, e),
+    };
+
+    if out.is_json() {
+        let mut value = serde_json::to_value(&verdict).unwrap_or_default();
+        value["valid"] = json!(verdict.is_valid());
+        out.emit(&value);
+    } else if json_output {
+        match serde_json::to_string_pretty(&verdict) {
+            Ok(json) => println!("{}", json),
+            Err(e) => {
+                eprintln!("❌ Failed to serialize verdict: {}", e);
+                return EXIT_USAGE;
+            }
+        }
+    } else {
+        let mark = |ok: bool| if ok { "✅" } else { "❌" };
+        let field = |id: &str, value: String| println!("   {:<17}{}", format!("{}:", t!(id)), value);
+        println!("📜 {}", t!("cli-cert-header", id = verdict.certificate_id.to_string()));
+        field("cli-cert-schema", format!("{} {}", mark(verdict.schema_supported), verdict.schema_version));
+        field("cli-cert-hash", mark(verdict.hash_valid).to_string());
+        field("cli-cert-signature", mark(verdict.signature_valid).to_string());
+        field("cli-cert-fingerprint", verdict.key_fingerprint.clone());
+        field("cli-cert-serial", verdict.serial_number.clone());
+        let history = match &verdict.history {
+            HistoryMatch::Matched { operation_id } => format!("✅ {}", t!("cli-cert-history-matched", operation = operation_id.to_string())),
+            HistoryMatch::SerialMismatch { recorded_serial } => {
+                format!("❌ {}", t!("cli-cert-history-mismatch", serial = recorded_serial.as_str()))
+            }
+            HistoryMatch::NotFound => format!("⚠️  {}", t!("cli-cert-history-not-found")),
+            HistoryMatch::Unavailable => format!("⚠️  {}", t!("cli-cert-history-unavailable")),
+        };
+        field("cli-cert-history", history);
+        for issue in &verdict.issues {
+            println!("   ⚠️  {}", issue);
+        }
+        println!();
+        println!("{}", if verdict.is_valid() { format!("✅ {}", t!("cli-cert-valid")) } else { format!("❌ {}", t!("cli-cert-invalid")) });
+    }
+
+    if verdict.is_valid() { EXIT_OK } else { EXIT_FAILED }
+}
+
+fn shred(args: &[String], out: &Output) -> i32 {
+    const USAGE: &str = "❌ Usage: hdd-tool shred <path>... [--passes N] [--pattern zeros|ones|random|0xNN] [--no-slack]";
+
+    let mut options = ShredOptions::new(ShredPattern::Random, 1);
+    let mut paths = Vec::new();
+    let mut iter = args.iter();
+
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--passes" => match iter.next().and_then(|v| v.parse::<u32>().ok()) {
+                Some(passes) if passes > 0 => options.passes = passes,
+                _ => {
+                    eprintln!("{}", USAGE);
+                    return EXIT_USAGE;
+                }
+            },
+            "--pattern" => match iter.next().and_then(|v| ShredPattern::parse(v)) {
+                Some(pattern) => options.pattern = pattern,
+                None => {
+                    eprintln!("{}", USAGE);
+                    return EXIT_USAGE;
+                }
+            },
+            "--no-slack" => options.wipe_slack = false,
+            _ if arg.starts_with("--") => {
+                eprintln!("{}", USAGE);
+                return EXIT_USAGE;
+            }
+            _ => paths.push(arg.clone()),
+        }
+    }
+
+    if paths.is_empty() {
+        eprintln!("{}", USAGE);
+        return EXIT_USAGE;
+    }
+
+    let report = secure_delete_paths(&paths, &options);
+    if out.is_json() {
+        out.emit(&json!({
+            "files_shredded": report.files_shredded,
+            "directories_removed": report.directories_removed,
+            "bytes_overwritten": report.bytes_overwritten,
+            "failures": report.failures
+                .iter()
+                .map(|(path, reason)| json!({ "path": path, "reason": reason }))
+                .collect::<Vec<_>>(),
+        }));
+    } else {
+        for (path, reason) in &report.failures {
+            eprintln!("❌ {}: {}", path, reason);
+        }
+    }
+
+    if report.success() { EXIT_OK } else { EXIT_FAILED }
+}
+
+/// Factory-reset a self-encrypting drive whose owner credential is lost,
+/// using the PSID from its label. Having the drive in hand to read the PSID
+/// is the confirmation; there is no prompt.
+fn psid_revert(args: &[String], out: &Output) -> i32 {
+    const USAGE: &str = "❌ Usage: hdd-tool psid-revert <device> --psid <psid> --config <file>\n   \
+        with HDD_TOOL_OPERATOR_TOKEN set";
+
+    let mut device = None;
+    let mut psid = None;
+    let mut config_path = None;
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        match (arg.as_str(), device.is_none()) {
+            ("--psid", _) => psid = iter.next().cloned(),
+            ("--config", _) => config_path = iter.next().cloned(),
+            (_, true) if !arg.starts_with("--") => device = Some(arg.clone()),
+            _ => {
+                eprintln!("{}", USAGE);
+                return EXIT_USAGE;
+            }
+        }
+    }
+    let operator_token = std::env::var("HDD_TOOL_OPERATOR_TOKEN").ok();
+    let (Some(device), Some(psid), Some(config_path), Some(operator_token)) = (device, psid, config_path, operator_token) else {
+        eprintln!("{}", USAGE);
+        return EXIT_USAGE;
+    };
+
+    let engine = match load_engine(&config_path, out) {
+        Ok(engine) => engine,
+        Err(code) => return code,
+    };
+    let operator = match engine.login_with_token(&operator_token) {
+        Ok(operator) => operator,
+        Err(e) => return out.engine_error(&e),
+    };
+    if let Err(e) = engine.psid_revert_as(&operator, &device, &psid) {
+        return out.engine_error(&e);
+    }
+    out.emit(&json!({ "device": device, "reverted": true }));
+    EXIT_OK
+}
+
+/// How an unattended run answers the confirmation prompt
+enum Unattended {
+    /// `--yes-i-know-what-im-doing`: confirm whatever devices were selected
+    Acknowledged,
+    /// `--confirm-token <serial>[,<serial>...]`: confirm only drives with these serial numbers
+    Serials(Vec<String>),
+}
+
+/// Wipe devices headlessly. The operator token is read from the
+/// environment so it does not show up in the process list.
+///
+/// Devices are named by path or kernel name, or picked by serial number,
+/// WWN, model pattern, size and bus from the detected drives. The resolved
+/// drives are printed before anything is written, and several drives are
+/// wiped side by side.
+///
+/// When the configuration asks for a confirmation phrase it is read from
+/// stdin unless the run confirms up front: `--confirm` gives the phrase,
+/// `--confirm-token` (or `HDD_TOOL_CONFIRM_TOKEN`) the serial numbers of the
+/// drives, and `--yes-i-know-what-im-doing` (or
+/// `HDD_TOOL_YES_I_KNOW_WHAT_IM_DOING=1`) takes the devices as selected.
+/// Without any of them and without a terminal on stdin the wipe is refused
+/// rather than left waiting.
+fn wipe(args: &[String], out: &Output) -> i32 {
+    wipe_at(args, out, None)
+}
+
+/// `wipe`, or with `start` set, queue the wipes in the engine's schedule and
+/// run it until they are done
+fn wipe_at(args: &[String], out: &Output, start: Option<chrono::DateTime<chrono::Utc>>) -> i32 {
+    const USAGE: &str = "❌ Usage: hdd-tool wipe [<device>...] --config <file> [--profile <id> | --recipe <file.toml>] [--type hdd|ssd|flash]\n   \
+        [--devices <name>,...] [--serial <serial>] [--wwn <wwn>] [--model <pattern>] [--min-size <size>] [--max-size <size>] [--bus <bus>]\n   \
+        [--confirm <phrase> | --confirm-token <serial>,... | --yes-i-know-what-im-doing] [--no-certificate] [--no-trim]\n   \
+        [--security-state disable|freeze|brick] [--priority low|normal|high]\n   \
+        hdd-tool wipe --all-non-system --config <file> [--include-removable] [--plan-token <token>]\n   \
+        with HDD_TOOL_OPERATOR_TOKEN set, and HDD_TOOL_APPROVER_TOKEN when a second operator must approve";
+
+    let mut selector = DeviceSelector::default();
+    let mut config_path = None;
+    let mut profile = None;
+    let mut recipe_path = None;
+    let mut confirmation = None;
+    let mut unattended = match std::env::var("HDD_TOOL_CONFIRM_TOKEN") {
+        Ok(serials) if !serials.is_empty() => Some(Unattended::Serials(split_list(&serials))),
+        _ if std::env::var("HDD_TOOL_YES_I_KNOW_WHAT_IM_DOING").is_ok_and(|v| v == "1") => Some(Unattended::Acknowledged),
+        _ => None,
+    };
+    let mut target_type = None;
+    let mut certificate = true;
+    let mut skip_trim = false;
+    let mut security_state = None;
+    let mut priority = WipePriority::Normal;
+    let mut all_non_system = false;
+    let mut include_removable = false;
+    let mut plan_token = None;
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        let flag = arg.as_str();
+        // Flags that take a value
+        let value = match flag {
+            "--config" | "--profile" | "--recipe" | "--confirm" | "--confirm-token" | "--type" | "--devices" | "--serial"
+            | "--wwn" | "--model" | "--min-size" | "--max-size" | "--bus" | "--plan-token" | "--security-state" | "--priority" => match iter.next() {
+                Some(value) => value.as_str(),
+                None => {
+                    eprintln!("{}", USAGE);
+                    return EXIT_USAGE;
+                }
+            },
+            _ => "",
+        };
+        match flag {
+            "--config" => config_path = Some(value.to_string()),
+            "--profile" => match ComplianceProfile::parse(value) {
+                Some(value) => profile = Some(value),
+                None => return out.fail(EXIT_USAGE, "Unknown profile; run `hdd-tool profiles` for the list"),
+            },
+            "--recipe" => recipe_path = Some(value.to_string()),
+            "--confirm" => confirmation = Some(value.to_string()),
+            "--confirm-token" => unattended = Some(Unattended::Serials(split_list(value))),
+            "--yes-i-know-what-im-doing" => unattended = Some(Unattended::Acknowledged),
+            "--type" => match value.to_lowercase().as_str() {
+                "hdd" => target_type = Some(TargetType::HDD),
+                "ssd" => target_type = Some(TargetType::SSD),
+                "flash" => target_type = Some(TargetType::Flash),
+                _ => {
+                    eprintln!("{}", USAGE);
+                    return EXIT_USAGE;
+                }
+            },
+            "--devices" => selector.paths.extend(split_list(value)),
+            "--serial" => selector.serials.extend(split_list(value)),
+            "--wwn" => selector.wwns.extend(split_list(value)),
+            "--model" => selector.model = Some(value.to_string()),
+            "--bus" => selector.bus = Some(value.to_string()),
+            "--min-size" | "--max-size" => match parse_size(value) {
+                Some(size) if flag == "--min-size" => selector.min_size_bytes = Some(size),
+                Some(size) => selector.max_size_bytes = Some(size),
+                None => return out.fail(EXIT_USAGE, format!("Invalid size '{}'; use e.g. 500G, 4T or 931GiB", value)),
+            },
+            "--no-certificate" => certificate = false,
+            "--no-trim" => skip_trim = true,
+            "--security-state" => match SecurityStateAction::parse(value) {
+                Some(action) => security_state = Some(action),
+                None => {
+                    eprintln!("{}", USAGE);
+                    return EXIT_USAGE;
+                }
+            },
+            "--priority" => match WipePriority::parse(value) {
+                Some(value) => priority = value,
+                None => {
+                    eprintln!("{}", USAGE);
+                    return EXIT_USAGE;
+                }
+            },
+            "--all-non-system" => all_non_system = true,
+            "--include-removable" => include_removable = true,
+            "--plan-token" => plan_token = Some(value.to_string()),
+            _ if flag.starts_with("--") => {
+                eprintln!("{}", USAGE);
+                return EXIT_USAGE;
+            }
+            _ => selector.paths.push(arg.clone()),
+        }
+    }
+
+    if profile.is_some() && recipe_path.is_some() {
+        return out.fail(EXIT_USAGE, "--profile and --recipe cannot be combined");
+    }
+    let recipe = match recipe_path.map(WipeRecipe::load).transpose() {
+        Ok(recipe) => recipe,
+        Err(e) => return out.fail(EXIT_USAGE, e),
+    };
+
+    let operator_token = std::env::var("HDD_TOOL_OPERATOR_TOKEN").ok();
+    let (Some(config_path), Some(operator_token)) = (config_path, operator_token) else {
+        eprintln!("{}", USAGE);
+        return EXIT_USAGE;
+    };
+    if all_non_system {
+        if !selector.is_empty() || profile.is_some() || recipe.is_some() || start.is_some() {
+            return out.fail(
+                EXIT_USAGE,
+                "--all-non-system picks the drives and purges them now; it takes no device selection, --profile, --recipe or schedule",
+            );
+        }
+        return wipe_all_non_system(&config_path, &operator_token, include_removable, plan_token.as_deref(), out);
+    }
+    if selector.is_empty() {
+        eprintln!("{}", USAGE);
+        return EXIT_USAGE;
+    }
+
+    let engine = match load_engine(&config_path, out) {
+        Ok(engine) => engine,
+        Err(code) => return code,
+    };
+    let profile = profile.or(engine.default_profile());
+    if recipe.is_none() && profile.is_none() {
+        return out.fail(EXIT_USAGE, format!("No --profile given and no default_profile in {}", config_path));
+    }
+    let operator = match engine.login_with_token(&operator_token) {
+        Ok(operator) => operator,
+        Err(e) => return out.engine_error(&e),
+    };
+
+    let disks = match engine.scan_devices_as(Some(&operator)) {
+        Ok(disks) => disks,
+        Err(e) => return out.engine_error(&e),
+    };
+    let selected = selector.resolve(&disks);
+    // Devices named by path may be ones detection does not list; the engine checks them
+    let undetected = if selector.is_paths_only() { selector.unmatched_paths(&disks) } else { Vec::new() };
+    if selected.is_empty() && undetected.is_empty() {
+        return out.fail(EXIT_USAGE, "No attached drive matches the selection; run `hdd-tool scan` to list them");
+    }
+
+    println!("🎯 {}", t!("cli-selected-drives"));
+    for disk in &selected {
+        println!(
+            "   {:<20} {}  serial {}  wwn {}  {:.1} GB  {}",
+            disk.path,
+            if disk.model.is_empty() { t!("cli-no-model") } else { disk.model.clone() },
+            if disk.serial.is_empty() { "-" } else { disk.serial.as_str() },
+            disk.wwn.as_deref().unwrap_or("-"),
+            disk.size_bytes as f64 / 1e9,
+            disk.bus_type
+        );
+    }
+    for path in &undetected {
+        println!("   {:<20} {}", path, t!("cli-not-detected"));
+    }
+
+    let mut requests = Vec::new();
+    let targets = selected
+        .iter()
+        .map(|disk| (disk.path.clone(), Some(disk)))
+        .chain(undetected.iter().map(|path| (path.clone(), None)));
+    for (path, disk) in targets {
+        let mut request = WipeEngine::build_request(
+            path,
+            target_type.or(disk.map(default_target_type)).unwrap_or(TargetType::HDD),
+            WipeScope::WholeDevice,
+            SanitizationStandard::NIST_SP_800_88_R1,
+            true,
+            certificate,
+        );
+        request.profile = profile;
+        request.recipe = recipe.clone();
+        request.skip_trim = skip_trim;
+        request.security_state = security_state;
+        request.priority = priority;
+        requests.push((request, disk.map(|disk| disk.serial.clone())));
+    }
+
+    match (&recipe, profile) {
+        (Some(recipe), _) => {
+            println!("📋 Recipe {} ({} passes, fingerprint {})", recipe.name, recipe.spec.passes, &recipe.fingerprint[..16]);
+            if let Err(e) = engine.register_recipe(recipe.clone()) {
+                return out.engine_error(&e);
+            }
+        }
+        (None, Some(profile)) => println!("📋 {} - {}", profile.name(), profile.citation()),
+        (None, None) => unreachable!(),
+    }
+    if security_state == Some(SecurityStateAction::Brick) {
+        println!("⚠️  --security-state brick locks every wiped drive for good; they cannot be used again");
+    }
+    for (request, _) in &requests {
+        if let Ok(estimate) = engine.estimate_duration(&request.target_path, request.target_type, &request.wipe_spec()) {
+            println!("⏱️  {}", t!("cli-wipe-estimate", device = request.target_path.as_str(), duration = estimate.summary()));
+        }
+    }
+
+    let approver_token = std::env::var("HDD_TOOL_APPROVER_TOKEN").ok();
+    let mut ready = Vec::new();
+    for (mut request, serial) in requests {
+        if let Some(phrase) = engine.confirmation_phrase(&request) {
+            request.confirmation = match (&confirmation, &unattended) {
+                (Some(confirmation), _) => Some(confirmation.clone()),
+                (None, Some(Unattended::Acknowledged)) => {
+                    println!("⚠️  Operation {}: confirmed by --yes-i-know-what-im-doing for {}", request.id, request.target_path);
+                    Some(phrase)
+                }
+                (None, Some(Unattended::Serials(serials))) => match serial {
+                    Some(serial) if !serial.trim().is_empty() && serials.iter().any(|s| s.trim() == serial.trim()) => {
+                        println!("⚠️  Operation {}: confirmed by serial number {}", request.id, serial);
+                        Some(phrase)
+                    }
+                    Some(serial) => {
+                        return out.fail(
+                            EXIT_REFUSED,
+                            format!("Confirmation token does not match serial \"{}\" of {}", serial, request.target_path),
+                        );
+                    }
+                    None => return out.fail(EXIT_REFUSED, format!("{} is not an attached drive", request.target_path)),
+                },
+                (None, None) if !std::io::stdin().is_terminal() => {
+                    return out.fail(
+                        EXIT_REFUSED,
+                        "Confirmation required but stdin is not a terminal; pass --confirm, --confirm-token or --yes-i-know-what-im-doing",
+                    );
+                }
+                (None, None) => {
+                    println!("⚠️  Operation {} on {}: type \"{}\" to confirm", request.id, request.target_path, phrase);
+                    let mut line = String::new();
+                    if std::io::stdin().read_line(&mut line).is_err() {
+                        return out.fail(EXIT_REFUSED, "No confirmation given");
+                    }
+                    Some(line)
+                }
+            };
+        }
+
+        // A second operator at the same console approves with their own token
+        if let Some(ref approver_token) = approver_token {
+            let approved = engine
+                .authenticate_token(approver_token)
+                .and_then(|approver| engine.approve_operation(request.id, &approver));
+            if let Err(e) = approved {
+                return out.engine_error(&e);
+            }
+        }
+        ready.push(request);
+    }
+
+    if let Some(start) = start {
+        let devices: HashMap<_, _> = ready.iter().map(|request| (request.id, request.target_path.clone())).collect();
+        for request in ready {
+            match engine.schedule_wipe_as(&operator, request, start) {
+                Ok(job) => println!(
+                    "🗓️  Wipe {} of {} scheduled for {}",
+                    job.id(),
+                    job.request.target_path,
+                    job.not_before.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M")
+                ),
+                Err(e) => return out.engine_error(&e),
+            }
+        }
+        return run_schedule(&engine, &operator, devices, out);
+    }
+
+    // Several drives are wiped side by side
+    let outcomes: Vec<_> = std::thread::scope(|scope| {
+        let running: Vec<_> = ready
+            .into_iter()
+            .map(|request| {
+                let (id, path) = (request.id, request.target_path.clone());
+                let handle = scope.spawn(|| engine.execute_request_as(&operator, request));
+                (id, path, handle)
+            })
+            .collect();
+        running
+            .into_iter()
+            .map(|(id, path, handle)| {
+                let outcome = handle.join().unwrap_or_else(|_| {
+                    Err(WipeError { code: WipeErrorCode::UnknownError, message: "Wipe thread panicked".to_string(), sector: None })
+                });
+                (id, path, outcome)
+            })
+            .collect()
+    });
+
+    report_wipes(outcomes, out)
+}
+
+/// `wipe --all-non-system`: without a plan token, list the drives a
+/// decommission would purge and the ones it leaves alone, and save that plan;
+/// with the plan's token, purge the listed drives side by side
+fn wipe_all_non_system(
+    config_path: &str,
+    operator_token: &str,
+    include_removable: bool,
+    plan_token: Option<&str>,
+    out: &Output,
+) -> i32 {
+    let engine = match load_engine(config_path, out) {
+        Ok(engine) => engine,
+        Err(code) => return code,
+    };
+    let operator = match engine.login_with_token(operator_token) {
+        Ok(operator) => operator,
+        Err(e) => return out.engine_error(&e),
+    };
+
+    let Some(plan_token) = plan_token else {
+        let plan = match engine.plan_wipe_all_non_system_as(&operator, include_removable) {
+            Ok(plan) => plan,
+            Err(e) => return out.engine_error(&e),
+        };
+        if out.is_json() {
+            out.emit(&plan);
+            return EXIT_OK;
+        }
+        let model = |model: &str| if model.is_empty() { t!("cli-no-model") } else { model.to_string() };
+        let serial = |serial: &str| if serial.is_empty() { "-".to_string() } else { serial.to_string() };
+        println!("🎯 {}", t!("cli-decommission-purge"));
+        for drive in &plan.drives {
+            println!(
+                "   {:<20} {}  serial {}  {:.1} GB  {}",
+                drive.path,
+                model(&drive.model),
+                serial(&drive.serial),
+                drive.size_bytes as f64 / 1e9,
+                drive.bus_type
+            );
+        }
+        if plan.drives.is_empty() {
+            println!("   {}", t!("cli-decommission-none"));
+            return EXIT_OK;
+        }
+        println!("🛡️  {}", t!("cli-decommission-excluded"));
+        for drive in &plan.excluded {
+            println!("   {:<20} {}  serial {}  {}", drive.path, model(&drive.model), serial(&drive.serial), drive.reason);
+        }
+        println!();
+        let expires = plan.expires_at.with_timezone(&chrono::Local).format("%H:%M").to_string();
+        println!("⚠️  {}", t!("cli-decommission-confirm", token = plan.confirmation_token.as_str(), expires = expires.as_str()));
+        return EXIT_OK;
+    };
+
+    let jobs = match engine.wipe_all_non_system_as(&operator, plan_token) {
+        Ok(jobs) => jobs,
+        Err(e) => return out.engine_error(&e),
+    };
+    // A second operator at the same console approves with their own token
+    if let Ok(approver_token) = std::env::var("HDD_TOOL_APPROVER_TOKEN") {
+        for job in &jobs {
+            let approved = engine
+                .authenticate_token(&approver_token)
+                .and_then(|approver| engine.approve_operation(job.id(), &approver));
+            if let Err(e) = approved {
+                return out.engine_error(&e);
+            }
+        }
+    }
+    for job in &jobs {
+        println!("🗓️  Purge {} of {} queued", job.id(), job.request.target_path);
+    }
+    let devices = jobs.into_iter().map(|job| (job.id(), job.request.target_path)).collect();
+    run_schedule(&engine, &operator, devices, out)
+}
+
+/// Print the outcome of each wipe, or emit them as a JSON array; the exit
+/// code is the worst of them
+fn report_wipes(outcomes: Vec<(uuid::Uuid, String, WipeOutcome)>, out: &Output) -> i32 {
+    let mut exit_code = EXIT_OK;
+    let mut report = Vec::new();
+    for (operation_id, device, outcome) in outcomes {
+        match outcome {
+            Ok((result, certificate_path)) => {
+                if !result.success {
+                    exit_code = exit_code.max(EXIT_FAILED);
+                }
+                if !out.is_json() {
+                    match (result.success, &certificate_path) {
+                        (true, Some(path)) => println!("📜 {}", t!("cli-wipe-certificate", device = device.as_str(), path = path.as_str())),
+                        (true, None) => println!("✅ {}", t!("cli-wipe-done", device = device.as_str())),
+                        (false, _) => eprintln!(
+                            "❌ {}: {}",
+                            device,
+                            result.error_message.clone().unwrap_or_else(|| t!("cli-wipe-failed"))
+                        ),
+                    }
+                }
+                report.push(json!({
+                    "operation_id": operation_id,
+                    "device": device,
+                    "result": result,
+                    "certificate_path": certificate_path,
+                }));
+            }
+            Err(e) => {
+                exit_code = exit_code.max(exit_code_for(&e));
+                if !out.is_json() {
+                    eprintln!("❌ {}: {}", device, e);
+                }
+                report.push(json!({
+                    "operation_id": operation_id,
+                    "device": device,
+                    "error": { "code": format!("{:?}", e.code), "message": e.message },
+                }));
+            }
+        }
+    }
+    out.emit(&report);
+    exit_code
+}
+
+type WipeOutcome = Result<(WipeResult, Option<String>), WipeError>;
+
+/// Queue wipes for later, in the maintenance windows of the configuration.
+/// `--at` takes the wipe's arguments and stays in the foreground until the
+/// queued wipes have run; `--run` picks up jobs left by an earlier run.
+fn schedule(args: &[String], out: &Output) -> i32 {
+    const USAGE: &str = "❌ Usage: hdd-tool schedule --at <time> <wipe arguments>\n   \
+        hdd-tool schedule --list | --run | --cancel <job-id> --config <file>\n   \
+        <time> is local, e.g. \"2024-07-01T22:00\", or RFC 3339";
+
+    if let Some(index) = args.iter().position(|arg| arg == "--at") {
+        let Some(start) = args.get(index + 1).and_then(|value| parse_start_time(value)) else {
+            eprintln!("{}", USAGE);
+            return EXIT_USAGE;
+        };
+        let rest: Vec<String> = args[..index].iter().chain(&args[index + 2..]).cloned().collect();
+        return wipe_at(&rest, out, Some(start));
+    }
+
+    enum Action {
+        List,
+        Run,
+        Cancel(uuid::Uuid),
+    }
+    let mut config_path = None;
+    let mut action = None;
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--config" => config_path = iter.next().cloned(),
+            "--list" => action = Some(Action::List),
+            "--run" => action = Some(Action::Run),
+            "--cancel" => match iter.next().map(|id| uuid::Uuid::parse_str(id)) {
+                Some(Ok(id)) => action = Some(Action::Cancel(id)),
+                _ => {
+                    eprintln!("{}", USAGE);
+                    return EXIT_USAGE;
+                }
+            },
+            _ => {
+                eprintln!("{}", USAGE);
+                return EXIT_USAGE;
+            }
+        }
+    }
+    let (Some(config_path), Some(action)) = (config_path, action) else {
+        eprintln!("{}", USAGE);
+        return EXIT_USAGE;
+    };
+
+    let engine = match load_engine(&config_path, out) {
+        Ok(engine) => engine,
+        Err(code) => return code,
+    };
+    if let Action::List = action {
+        let jobs = engine.scheduled_jobs();
+        if out.is_json() {
+            out.emit(&jobs);
+            return EXIT_OK;
+        }
+        if jobs.is_empty() {
+            println!("{}", t!("cli-no-scheduled-wipes"));
+        }
+        for job in &jobs {
+            println!(
+                "{}  {:<16} {}  {:<6}  {:?}{}",
+                job.id(),
+                job.request.target_path,
+                job.not_before.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M"),
+                format!("{:?}", job.request.priority),
+                job.status,
+                job.error_message.as_deref().map(|e| format!(": {}", e)).unwrap_or_default()
+            );
+        }
+        return EXIT_OK;
+    }
+
+    let Ok(operator_token) = std::env::var("HDD_TOOL_OPERATOR_TOKEN") else {
+        return out.fail(EXIT_USAGE, "HDD_TOOL_OPERATOR_TOKEN must be set");
+    };
+    let operator = match engine.login_with_token(&operator_token) {
+        Ok(operator) => operator,
+        Err(e) => return out.engine_error(&e),
+    };
+    match action {
+        Action::Cancel(id) => match engine.cancel_scheduled_as(&operator, id) {
+            Ok(()) => {
+                out.emit(&json!({ "cancelled": id }));
+                if !out.is_json() {
+                    println!("🗑️  Scheduled wipe {} cancelled", id);
+                }
+                EXIT_OK
+            }
+            Err(e) => out.engine_error(&e),
+        },
+        _ => {
+            let devices = engine.scheduled_jobs().into_iter().map(|job| (job.id(), job.request.target_path)).collect();
+            run_schedule(&engine, &operator, devices, out)
+        }
+    }
+}
+
+/// Run the engine's schedule in the foreground and report the wipes it ran
+fn run_schedule(
+    engine: &WipeEngine,
+    operator: &OperatorIdentity,
+    devices: HashMap<uuid::Uuid, String>,
+    out: &Output,
+) -> i32 {
+    if let Some(opens) = engine.schedule_config().next_opening(chrono::Local::now()) {
+        println!("🕒 Next maintenance window opens {}", opens.format("%Y-%m-%d %H:%M"));
+    }
+    match engine.run_schedule_as(operator) {
+        Ok(outcomes) => {
+            let outcomes = outcomes
+                .into_iter()
+                .map(|(id, outcome)| (id, devices.get(&id).cloned().unwrap_or_default(), outcome))
+                .collect();
+            report_wipes(outcomes, out)
+        }
+        Err(e) => out.engine_error(&e),
+    }
+}
+
+/// "2024-07-01T22:00" or "2024-07-01 22:00" in local time, or RFC 3339
+fn parse_start_time(value: &str) -> Option<chrono::DateTime<chrono::Utc>> {
+    use chrono::TimeZone;
+    if let Ok(at) = chrono::DateTime::parse_from_rfc3339(value) {
+        return Some(at.with_timezone(&chrono::Utc));
+    }
+    ["%Y-%m-%dT%H:%M", "%Y-%m-%d %H:%M", "%Y-%m-%dT%H:%M:%S", "%Y-%m-%d %H:%M:%S"]
+        .iter()
+        .find_map(|format| chrono::NaiveDateTime::parse_from_str(value, format).ok())
+        .and_then(|at| chrono::Local.from_local_datetime(&at).earliest())
+        .map(|at| at.with_timezone(&chrono::Utc))
+}
+
+fn split_list(value: &str) -> Vec<String> {
+    value.split(',').map(str::trim).filter(|item| !item.is_empty()).map(str::to_string).collect()
+}
+
+fn list_profiles(out: &Output) -> i32 {
+    if out.is_json() {
+        let profiles: Vec<_> = ComplianceProfile::ALL
+            .iter()
+            .map(|profile| json!({
+                "id": profile.id(),
+                "name": profile.name(),
+                "passes": profile.spec().passes,
+                "verification": profile.verification_policy().description(),
+                "citation": profile.citation(),
+            }))
+            .collect();
+        out.emit(&profiles);
+        return EXIT_OK;
+    }
+
+    for profile in ComplianceProfile::ALL {
+        let spec = profile.spec();
+        println!("{:<20} {}", profile.id(), profile.name());
+        let passes = if spec.passes == 1 { "1 pass".to_string() } else { format!("{} passes", spec.passes) };
+        println!("{:<20} {}, {} verification", "", passes, profile.verification_policy().description());
+        println!("{:<20} {}", "", profile.citation());
+    }
+    EXIT_OK
+}
+
+/// Bundle an operation's certificate, verification data, audit log excerpt
+/// and SMART snapshot into a directory or zip archive for the customer, or
+/// write reports in one format: an operation's, or a CSV/XML summary of
+/// every operation in a time range
+fn export(args: &[String], out: &Output) -> i32 {
+    const USAGE: &str = "❌ Usage: hdd-tool export --operation <id> (--dir <dir> | --zip <file.zip>) --config <file>\n   \
+        or: hdd-tool export [--operation <id>] --format pdf|json|csv|xml --out <file> --config <file>\n   \
+        [--since <time>] [--until <time>]\n   \
+        with HDD_TOOL_OPERATOR_TOKEN set";
+
+    let mut operation_id = None;
+    let mut destination = None;
+    let mut format = None;
+    let mut filter = HistoryFilter::default();
+    let mut config_path = None;
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        match (arg.as_str(), iter.next()) {
+            ("--operation", Some(id)) => match uuid::Uuid::parse_str(id) {
+                Ok(id) => operation_id = Some(id),
+                Err(_) => return out.fail(EXIT_USAGE, format!("'{}' is not an operation id", id)),
+            },
+            ("--dir", Some(dir)) => destination = Some((dir.clone(), false)),
+            ("--zip", Some(file)) => destination = Some((file.clone(), true)),
+            ("--out", Some(file)) => destination = Some((file.clone(), false)),
+            ("--format", Some(name)) => match ReportFormat::parse(name) {
+                Some(parsed) => format = Some(parsed),
+                None => return out.fail(EXIT_USAGE, format!("Unknown report format '{}'; use pdf, json, csv or xml", name)),
+            },
+            ("--since" | "--until", Some(value)) => match parse_start_time(value) {
+                Some(at) if arg == "--since" => filter.since = Some(at),
+                Some(at) => filter.until = Some(at),
+                None => return out.fail(EXIT_USAGE, format!("Invalid time '{}'; use e.g. 2024-07-01 22:00", value)),
+            },
+            ("--config", Some(path)) => config_path = Some(path.clone()),
+            _ => {
+                eprintln!("{}", USAGE);
+                return EXIT_USAGE;
+            }
+        }
+    }
+    let operator_token = std::env::var("HDD_TOOL_OPERATOR_TOKEN").ok();
+    let (Some((destination, zip)), Some(config_path), Some(operator_token)) = (destination, config_path, operator_token) else {
+        eprintln!("{}", USAGE);
+        return EXIT_USAGE;
+    };
+    // A bundle is one operation's; a report needs a format, and several operations a batch format
+    let valid = match (operation_id, format) {
+        (Some(_), None) => true,
+        (Some(_), Some(_)) => !zip && filter.since.is_none() && filter.until.is_none(),
+        (None, Some(format)) => !zip && format.is_batch(),
+        (None, None) => false,
+    };
+    if !valid {
+        eprintln!("{}", USAGE);
+        return EXIT_USAGE;
+    }
+
+    let engine = match load_engine(&config_path, out) {
+        Ok(engine) => engine,
+        Err(code) => return code,
+    };
+    let operator = match engine.login_with_token(&operator_token) {
+        Ok(operator) => operator,
+        Err(e) => return out.engine_error(&e),
+    };
+
+    let path = std::path::Path::new(&destination);
+    match (operation_id, format) {
+        (Some(operation_id), Some(format)) => {
+            if let Err(e) = engine.export_report_as(&operator, operation_id, format, path) {
+                return out.engine_error(&e);
+            }
+            out.emit(&json!({ "path": destination, "format": format, "operations": 1 }));
+            if !out.is_json() {
+                println!("📄 Operation {} written to {}", operation_id, destination);
+            }
+        }
+        (None, Some(format)) => {
+            let count = match engine.export_reports_as(&operator, &filter, format, path) {
+                Ok(count) => count,
+                Err(e) => return out.engine_error(&e),
+            };
+            out.emit(&json!({ "path": destination, "format": format, "operations": count }));
+            if !out.is_json() {
+                println!("📄 {} operations written to {}", count, destination);
+            }
+        }
+        // Rejected above
+        (None, None) => return EXIT_USAGE,
+        (Some(operation_id), None) => {
+            let bundle = match engine.export_operation_as(&operator, operation_id) {
+                Ok(bundle) => bundle,
+                Err(e) => return out.engine_error(&e),
+            };
+            let written = if zip { bundle.write_zip(&destination) } else { bundle.write_dir(&destination).map(|_| ()) };
+            if let Err(e) = written {
+                return out.engine_error(&e);
+            }
+
+            out.emit(&json!({ "path": destination, "manifest": bundle.manifest }));
+            if !out.is_json() {
+                println!("📦 Operation {} exported to {}", operation_id, destination);
+                for file in &bundle.manifest.files {
+                    println!("   {:<20} {:>10} bytes  {}", file.name, file.size_bytes, &file.sha256[..16]);
+                }
+                for note in &bundle.manifest.notes {
+                    println!("   ⚠️  {}", note);
+                }
+            }
+        }
+    }
+    EXIT_OK
+}
+
+/// List the attached drives, test devices included
+fn scan(args: &[String], out: &Output) -> i32 {
+    let config_path = match args {
+        [flag, path] if flag == "--config" => path,
+        _ => {
+            eprintln!("❌ Usage: hdd-tool scan --config <file>");
+            return EXIT_USAGE;
+        }
+    };
+    let engine = match load_engine(config_path, out) {
+        Ok(engine) => engine,
+        Err(code) => return code,
+    };
+    let disks = match engine.scan_devices() {
+        Ok(disks) => disks,
+        Err(e) => return out.engine_error(&e),
+    };
+
+    if out.is_json() {
+        let devices: Vec<_> = disks
+            .iter()
+            .map(|disk| json!({
+                "path": disk.path,
+                "description": disk.description(),
+                "vendor": disk.vendor,
+                "model": disk.model,
+                "serial": disk.serial,
+                "bus_type": disk.bus_type,
+                "kind": disk.kind.as_str(),
+                "virtual": disk.kind.is_virtual(),
+                "size_bytes": disk.size_bytes,
+                "removable": disk.removable,
+                "is_root_disk": disk.is_root_disk,
+                "volumes": disk.volumes,
+                "controller": disk.controller,
+                "namespace_id": disk.namespace_id,
+            }))
+            .collect();
+        out.emit(&devices);
+        return EXIT_OK;
+    }
+
+    // Namespaces of a multi-namespace NVMe controller are listed under it
+    let namespaces = |controller: &str| disks.iter().filter(|disk| disk.controller.as_deref() == Some(controller)).count();
+    let mut controllers_listed = HashSet::new();
+    for disk in &disks {
+        let system = if disk.is_root_disk { "  (system disk)" } else { "" };
+        match &disk.controller {
+            Some(controller) if namespaces(controller) > 1 => {
+                if controllers_listed.insert(controller.as_str()) {
+                    println!("{:<24} NVMe controller, {} namespaces", controller, namespaces(controller));
+                }
+                println!("  └ {:<20} {}{}", disk.path, disk.description(), system);
+            }
+            _ => println!("{:<24} {}{}", disk.path, disk.description(), system),
+        }
+    }
+    EXIT_OK
+}
+
+/// Follow wipes started by another process through the persistent
+/// operation history, which the engine rewrites on every progress update.
+/// With an operation id only that operation is followed; without one every
+/// operation in progress is, including ones started while watching. Returns
+/// once nothing watched is still running.
+fn watch(args: &[String]) -> i32 {
+    const USAGE: &str = "❌ Usage: hdd-tool watch [<operation-id>] [--history <file>] [--interval <seconds>]";
+
+    let mut operation_id = None;
+    let mut history_path = DEFAULT_HISTORY_PATH.to_string();
+    let mut interval = std::time::Duration::from_secs(1);
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--history" => match iter.next() {
+                Some(path) => history_path = path.clone(),
+                None => {
+                    eprintln!("{}", USAGE);
+                    return EXIT_USAGE;
+                }
+            },
+            "--interval" => match iter.next().and_then(|v| v.parse::<f64>().ok()) {
+                Some(seconds) if seconds > 0.0 => interval = std::time::Duration::from_secs_f64(seconds),
+                _ => {
+                    eprintln!("{}", USAGE);
+                    return EXIT_USAGE;
+                }
+            },
+            _ if arg.starts_with("--") || operation_id.is_some() => {
+                eprintln!("{}", USAGE);
+                return EXIT_USAGE;
+            }
+            _ => match uuid::Uuid::parse_str(arg) {
+                Ok(id) => operation_id = Some(id),
+                Err(_) => {
+                    eprintln!("❌ '{}' is not an operation id", arg);
+                    return EXIT_USAGE;
+                }
+            },
+        }
+    }
+
+    // Time of the last snapshot printed per operation, and operations already reported finished
+    let mut shown: HashMap<uuid::Uuid, Option<chrono::DateTime<chrono::Utc>>> = HashMap::new();
+    let mut finished: HashSet<uuid::Uuid> = HashSet::new();
+    let mut exit_code = EXIT_OK;
+    loop {
+        let records = match OperationHistory::read_records(&history_path) {
+            Ok(records) => records,
+            Err(e) => {
+                eprintln!("❌ {}", e.message);
+                return EXIT_FAILED;
+            }
+        };
+        let watched: Vec<&OperationRecord> = match operation_id {
+            Some(id) => match records.iter().find(|record| record.operation_id == id) {
+                Some(record) => vec![record],
+                None => {
+                    eprintln!("❌ Operation {} is not in {}", id, history_path);
+                    return EXIT_USAGE;
+                }
+            },
+            None => records
+                .iter()
+                .filter(|record| record.status.is_running() || shown.contains_key(&record.operation_id))
+                .collect(),
+        };
+        if watched.is_empty() {
+            println!("{}", t!("cli-no-operations"));
+            return EXIT_OK;
+        }
+
+        let mut running = 0;
+        for record in watched {
+            let id = record.operation_id;
+            if finished.contains(&id) {
+                continue;
+            }
+            let seen = shown.entry(id).or_insert(None);
+            // Only the latest snapshot; the ones in between are already out of date
+            if let Some(snapshot) = record.progress_snapshots.last()
+                && *seen != Some(snapshot.timestamp)
+            {
+                *seen = Some(snapshot.timestamp);
+                println!(
+                    "⏳ {:<8} {:<16} pass {}/{}  {:5.1}%  {}",
+                    &id.to_string()[..8],
+                    record.target_path,
+                    snapshot.current_pass,
+                    snapshot.total_passes,
+                    snapshot.percentage,
+                    snapshot.current_operation
+                );
+            }
+            match record.status {
+                OperationStatus::InProgress | OperationStatus::Verifying => running += 1,
+                OperationStatus::Completed => println!("✅ {:<8} {:<16} completed", &id.to_string()[..8], record.target_path),
+                OperationStatus::Failed => {
+                    exit_code = EXIT_FAILED;
+                    let error = record.error_message.as_deref().unwrap_or("no details recorded");
+                    println!("❌ {:<8} {:<16} failed: {}", &id.to_string()[..8], record.target_path, error);
+                }
+                OperationStatus::Interrupted => {
+                    exit_code = EXIT_FAILED;
+                    println!("⚠️  {:<8} {:<16} interrupted", &id.to_string()[..8], record.target_path);
+                }
+            }
+            if !record.status.is_running() {
+                finished.insert(id);
+            }
+        }
+
+        if running == 0 {
+            return exit_code;
+        }
+        std::thread::sleep(interval);
+    }
+}
+
+#[cfg(feature = "server")]
+fn serve(args: &[String]) -> i32 {
+    const USAGE: &str = "❌ Usage: hdd-tool serve --config <file> [--port N] [--grpc-port N]";
+
+    let mut config_path = None;
+    let mut port = 8090u16;
+    let mut grpc_port: Option<u16> = None;
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--config" => config_path = iter.next().cloned(),
+            "--port" => match iter.next().and_then(|v| v.parse::<u16>().ok()) {
+                Some(value) => port = value,
+                None => {
+                    eprintln!("{}", USAGE);
+                    return EXIT_USAGE;
+                }
+            },
+            "--grpc-port" => match iter.next().and_then(|v| v.parse::<u16>().ok()) {
+                Some(value) => grpc_port = Some(value),
+                None => {
+                    eprintln!("{}", USAGE);
+                    return EXIT_USAGE;
+                }
+            },
+            _ => {
+                eprintln!("{}", USAGE);
+                return EXIT_USAGE;
+            }
+        }
+    }
+
+    #[cfg(not(feature = "grpc"))]
+    if grpc_port.is_some() {
+        eprintln!("❌ This build does not include the gRPC service (build with --features grpc)");
+        return EXIT_USAGE;
+    }
+
+    let Some(config_path) = config_path else {
+        eprintln!("{}", USAGE);
+        return EXIT_USAGE;
+    };
+    let engine = match load_engine(&config_path, &Output::text()) {
+        Ok(engine) => engine,
+        Err(code) => return code,
+    };
+
+    let runtime = match tokio::runtime::Runtime::new() {
+        Ok(runtime) => runtime,
+        Err(e) => {
+            eprintln!("❌ Failed to start async runtime: {}", e);
+            return EXIT_FAILED;
+        }
+    };
+    let served = match grpc_port {
+        #[cfg(feature = "grpc")]
+        Some(grpc_port) => runtime.block_on(hdd_tool::server::grpc::start_station_servers(engine, port, grpc_port)),
+        _ => runtime.block_on(hdd_tool::server::start_engine_server(engine, port)),
+    };
+    match served {
+        Ok(()) => EXIT_OK,
+        Err(e) => {
+            eprintln!("❌ {}", e);
+            EXIT_FAILED
+        }
+    }
+}
+
+/// Run as a fleet station. Tokens are read from the environment so they do
+/// not show up in the process list.
+#[cfg(feature = "server")]
+fn station(args: &[String]) -> i32 {
+    use hdd_tool::server::{run_station_agent, ServerClient};
+
+    const USAGE: &str = "❌ Usage: hdd-tool station --config <file> --server <url> --station-id <id>\n   \
+        with HDD_TOOL_STATION_TOKEN and HDD_TOOL_OPERATOR_TOKEN set";
+
+    let mut config_path = None;
+    let mut server_url = std::env::var("HDD_TOOL_SERVER_URL").ok();
+    let mut station_id = None;
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--config" => config_path = iter.next().cloned(),
+            "--server" => server_url = iter.next().cloned(),
+            "--station-id" => station_id = iter.next().and_then(|v| uuid::Uuid::parse_str(v).ok()),
+            _ => {
+                eprintln!("{}", USAGE);
+                return EXIT_USAGE;
+            }
+        }
+    }
+
+    let station_token = std::env::var("HDD_TOOL_STATION_TOKEN").ok();
+    let operator_token = std::env::var("HDD_TOOL_OPERATOR_TOKEN").ok();
+    let (Some(config_path), Some(server_url), Some(station_id), Some(station_token), Some(operator_token)) =
+        (config_path, server_url, station_id, station_token, operator_token)
+    else {
+        eprintln!("{}", USAGE);
+        return EXIT_USAGE;
+    };
+
+    let engine = match load_engine(&config_path, &Output::text()) {
+        Ok(engine) => engine,
+        Err(code) => return code,
+    };
+    let client = ServerClient::new(&server_url).with_station_credentials(station_id, &station_token);
+
+    let runtime = match tokio::runtime::Runtime::new() {
+        Ok(runtime) => runtime,
+        Err(e) => {
+            eprintln!("❌ Failed to start async runtime: {}", e);
+            return EXIT_FAILED;
+        }
+    };
+    match runtime.block_on(run_station_agent(engine, client, &operator_token)) {
+        Ok(()) => EXIT_OK,
+        Err(e) => {
+            eprintln!("❌ {}", e);
+            EXIT_FAILED
+        }
+    }
+}
+
+/// Load a JSON `WipeConfiguration` and start an engine with it. On failure
+/// the error is reported and the exit code returned.
+pub(crate) fn load_engine(config_path: &str, out: &Output) -> Result<WipeEngine, i32> {
+    use hdd_tool::core::WipeConfiguration;
+
+    let config: WipeConfiguration = std::fs::read_to_string(config_path)
+        .map_err(|e| e.to_string())
+        .and_then(|content| serde_json::from_str(&content).map_err(|e| e.to_string()))
+        .map_err(|e| out.fail(EXIT_USAGE, format!("Failed to load {}: {}", config_path, e)))?;
+
+    WipeEngine::new(config).map_err(|e| out.engine_error(&e))
+}
@@ -2,6 +2,8 @@ use serde::{Deserialize, Serialize};
 use std::fs;
 use std::env;
 
+use crate::ui::ThemeMode;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AppConfig {
     pub server_url: String,
@@ -10,8 +12,27 @@ pub struct AppConfig {
     pub local_storage_only: bool,
     pub connection_timeout_seconds: u64,
     pub retry_attempts: u32,
+    /// Wipes run at once on drives behind the same bus or controller
+    #[serde(default = "default_wipes_per_controller")]
+    pub max_wipes_per_controller: usize,
+    #[serde(default)]
+    pub theme: ThemeMode,
+    /// Zoom applied to the whole window, 1.0 being the native size
+    #[serde(default = "default_ui_scale")]
+    pub ui_scale: f32,
+    /// Taller progress bars with large percentages, for wipe stations
+    /// watched from across the room
+    #[serde(default)]
+    pub large_progress_text: bool,
+    /// Language of the interface, e.g. "es-ES"; `None` follows the system
+    #[serde(default)]
+    pub locale: Option<String>,
 }
 
+fn default_wipes_per_controller() -> usize { 1 }
+
+fn default_ui_scale() -> f32 { 1.0 }
+
 impl Default for AppConfig {
     fn default() -> Self {
         Self {
@@ -21,6 +42,11 @@ impl Default for AppConfig {
             local_storage_only: true,
             connection_timeout_seconds: 30,
             retry_attempts: 3,
+            max_wipes_per_controller: default_wipes_per_controller(),
+            theme: ThemeMode::default(),
+            ui_scale: default_ui_scale(),
+            large_progress_text: false,
+            locale: None,
         }
     }
 }
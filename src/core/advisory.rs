@@ -0,0 +1,146 @@
+//! Whether a drive is worth wiping
+//!
+//! Overwriting a large hard disk takes hours, and a drive that is already
+//! failing will likely not finish the wipe or survive resale. Before a wipe,
+//! the drive's SMART data is checked against `WearThresholds`: reallocated
+//! and pending sectors, NVMe percentage used, and the drive's own health
+//! verdict. The resulting `WipeAdvisory` recommends destroying the drive
+//! instead of overwriting it when the failure risk is high. Preflight shows
+//! the recommendation as a warning, and the operator decides.
+
+use serde::{Deserialize, Serialize};
+
+use crate::smart::{DeviceHealth, HealthStatus};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WearThresholds {
+    /// Reallocated sectors from which the drive is worth destroying
+    #[serde(default = "default_reallocated_sectors")]
+    pub reallocated_sectors: u64,
+    /// Sectors waiting for reallocation; each one is unreadable now
+    #[serde(default = "default_pending_sectors")]
+    pub pending_sectors: u64,
+    /// Percentage of rated endurance used
+    #[serde(default = "default_percent_used")]
+    pub percent_used: u8,
+}
+
+fn default_reallocated_sectors() -> u64 { 100 }
+fn default_pending_sectors() -> u64 { 10 }
+fn default_percent_used() -> u8 { 100 }
+
+impl Default for WearThresholds {
+    fn default() -> Self {
+        Self {
+            reallocated_sectors: default_reallocated_sectors(),
+            pending_sectors: default_pending_sectors(),
+            percent_used: default_percent_used(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum FailureRisk {
+    /// The drive reports no health data
+    Unknown,
+    Low,
+    /// Worn or has remapped sectors, but below the thresholds
+    Elevated,
+    High,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum WipeRecommendation {
+    Overwrite,
+    /// Physically destroy the drive instead of spending hours overwriting it
+    Destroy,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WipeAdvisory {
+    pub device_path: String,
+    pub risk: FailureRisk,
+    pub recommendation: WipeRecommendation,
+    /// What the risk is based on, e.g. "412 reallocated sectors"
+    pub reasons: Vec<String>,
+    pub health: DeviceHealth,
+}
+
+impl WipeAdvisory {
+    pub fn evaluate(health: DeviceHealth, thresholds: &WearThresholds) -> Self {
+        let mut high = Vec::new();
+        let mut elevated = Vec::new();
+
+        if health.health_status == HealthStatus::Failing {
+            let detail = if health.warnings.is_empty() { String::new() } else { format!(" ({})", health.warnings.join(", ")) };
+            high.push(format!("SMART reports the drive as failing{}", detail));
+        }
+        if let Some(count) = health.reallocated_sectors.filter(|&count| count > 0) {
+            let reason = format!("{} reallocated sectors", count);
+            if count >= thresholds.reallocated_sectors { high.push(reason) } else { elevated.push(reason) }
+        }
+        if let Some(count) = health.pending_sectors.filter(|&count| count > 0) {
+            let reason = format!("{} sectors pending reallocation", count);
+            if count >= thresholds.pending_sectors { high.push(reason) } else { elevated.push(reason) }
+        }
+        if let Some(used) = health.percent_used {
+            let reason = format!("{}% of rated endurance used", used);
+            if used >= thresholds.percent_used {
+                high.push(reason);
+            } else if u16::from(used) * 10 >= u16::from(thresholds.percent_used) * 8 {
+                // Within the last fifth of the threshold
+                elevated.push(reason);
+            }
+        }
+
+        let risk = if !high.is_empty() {
+            FailureRisk::High
+        } else if !elevated.is_empty() || health.health_status == HealthStatus::Warning {
+            FailureRisk::Elevated
+        } else if health.health_status == HealthStatus::Unknown {
+            FailureRisk::Unknown
+        } else {
+            FailureRisk::Low
+        };
+        high.extend(elevated);
+
+        Self {
+            device_path: health.device_path.clone(),
+            risk,
+            recommendation: if risk == FailureRisk::High { WipeRecommendation::Destroy } else { WipeRecommendation::Overwrite },
+            reasons: high,
+            health,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_worn_drives_are_advised_for_destruction() {
+        let thresholds = WearThresholds::default();
+        let mut health = DeviceHealth::unknown("/dev/sdb");
+        assert_eq!(WipeAdvisory::evaluate(health.clone(), &thresholds).risk, FailureRisk::Unknown);
+
+        health.health_status = HealthStatus::Warning;
+        health.reallocated_sectors = Some(8);
+        health.pending_sectors = Some(0);
+        let advisory = WipeAdvisory::evaluate(health.clone(), &thresholds);
+        assert_eq!((advisory.risk, advisory.recommendation), (FailureRisk::Elevated, WipeRecommendation::Overwrite));
+        assert_eq!(advisory.reasons, vec!["8 reallocated sectors".to_string()]);
+
+        health.pending_sectors = Some(24);
+        let advisory = WipeAdvisory::evaluate(health.clone(), &thresholds);
+        assert_eq!((advisory.risk, advisory.recommendation), (FailureRisk::High, WipeRecommendation::Destroy));
+        assert_eq!(advisory.reasons[0], "24 sectors pending reallocation");
+
+        let mut nvme = DeviceHealth::unknown("/dev/nvme0n1");
+        nvme.health_status = HealthStatus::Good;
+        nvme.percent_used = Some(85);
+        assert_eq!(WipeAdvisory::evaluate(nvme.clone(), &thresholds).risk, FailureRisk::Elevated);
+        nvme.percent_used = Some(103);
+        assert_eq!(WipeAdvisory::evaluate(nvme, &thresholds).recommendation, WipeRecommendation::Destroy);
+    }
+}
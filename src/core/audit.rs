@@ -0,0 +1,265 @@
+//! Append-only audit log
+//!
+//! Every device scan, preflight, wipe start/finish, cancellation and
+//! configuration change is appended as one JSON line. Each entry carries the
+//! SHA-256 of the previous entry, so removing, reordering or editing a line
+//! breaks the chain from that point on. An export states the hash of the entry
+//! before the exported range, which lets a reviewer check it against an
+//! earlier export.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use crate::core::{WipeError, WipeErrorCode, WipeResult2};
+
+/// Default location of the audit log
+pub const DEFAULT_AUDIT_PATH: &str = "audit/audit.jsonl";
+
+/// `previous_hash` of the first entry in a log
+pub const GENESIS_HASH: &str = "0000000000000000000000000000000000000000000000000000000000000000";
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum AuditEventKind {
+    DeviceScan,
+    Preflight,
+    WipeStarted,
+    /// A wipe was refused by the target policy or safety checks before anything was written
+    WipeRefused,
+    WipeFinished,
+    WipeFailed,
+    WipeCancelled,
+    /// An operator paused a wipe between blocks of an overwrite pass
+    WipePaused,
+    WipeResumed,
+    /// A second operator approved a wipe
+    WipeApproved,
+    /// A wipe was queued to run later
+    WipeScheduled,
+    /// Every non-system drive was queued for purge from a confirmed decommission plan
+    DecommissionQueued,
+    /// A wipe interrupted by a crash or power loss was resumed, restarted or dismissed
+    WipeRecovered,
+    /// A finished wipe's device was read back again against what its last pass wrote
+    WipeReverified,
+    /// An operation's certificate and evidence were exported
+    ReportExported,
+    ConfigChanged,
+    OperatorLogin,
+    OperatorLogout,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AuditEntry {
+    pub sequence: u64,
+    pub timestamp: DateTime<Utc>,
+    pub kind: AuditEventKind,
+    pub operator: Option<String>,
+    pub target: Option<String>,
+    pub details: Value,
+    pub previous_hash: String,
+    /// SHA-256 over `previous_hash` and every field above
+    pub hash: String,
+}
+
+impl AuditEntry {
+    pub fn compute_hash(&self) -> String {
+        let body = serde_json::json!({
+            "sequence": self.sequence,
+            "timestamp": self.timestamp,
+            "kind": self.kind,
+            "operator": self.operator,
+            "target": self.target,
+            "details": self.details,
+        });
+        let mut hasher = Sha256::new();
+        hasher.update(self.previous_hash.as_bytes());
+        hasher.update(body.to_string().as_bytes());
+        hex::encode(hasher.finalize())
+    }
+}
+
+/// Time window for an export; unset bounds are open
+#[derive(Debug, Clone, Default)]
+pub struct AuditRange {
+    pub since: Option<DateTime<Utc>>,
+    pub until: Option<DateTime<Utc>>,
+}
+
+impl AuditRange {
+    fn contains(&self, timestamp: DateTime<Utc>) -> bool {
+        self.since.is_none_or(|since| timestamp >= since) && self.until.is_none_or(|until| timestamp <= until)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuditExport {
+    pub exported_at: DateTime<Utc>,
+    /// Hash of the entry just before the first exported one
+    pub anchor_hash: String,
+    /// Hash of the last entry in the whole log at export time
+    pub head_hash: String,
+    pub total_entries: u64,
+    /// Whether the full log verified when the export was taken
+    pub chain_valid: bool,
+    /// Sequence number of the first entry that failed verification
+    pub first_broken_sequence: Option<u64>,
+    pub entries: Vec<AuditEntry>,
+}
+
+impl AuditExport {
+    /// Check the exported entries link to each other, starting from `anchor_hash`
+    pub fn verify(&self) -> bool {
+        verify_chain(&self.entries, &self.anchor_hash).is_none()
+    }
+}
+
+/// Sequence of the first entry that does not link to its predecessor, if any
+pub fn verify_chain(entries: &[AuditEntry], anchor_hash: &str) -> Option<u64> {
+    let mut previous = anchor_hash.to_string();
+    let mut expected_sequence = entries.first().map(|e| e.sequence);
+
+    for entry in entries {
+        if Some(entry.sequence) != expected_sequence
+            || entry.previous_hash != previous
+            || entry.hash != entry.compute_hash()
+        {
+            return Some(entry.sequence);
+        }
+        previous = entry.hash.clone();
+        expected_sequence = Some(entry.sequence + 1);
+    }
+    None
+}
+
+pub struct AuditLog {
+    path: PathBuf,
+    next_sequence: u64,
+    last_hash: String,
+}
+
+impl AuditLog {
+    /// Open the log and resume the chain after its last entry
+    pub fn open<P: AsRef<Path>>(path: P) -> WipeResult2<Self> {
+        let path = path.as_ref().to_path_buf();
+        let mut log = Self { path, next_sequence: 0, last_hash: GENESIS_HASH.to_string() };
+
+        if let Some(last) = log.read_entries()?.pop() {
+            log.next_sequence = last.sequence + 1;
+            log.last_hash = last.hash;
+        }
+        Ok(log)
+    }
+
+    pub fn append(
+        &mut self,
+        kind: AuditEventKind,
+        operator: Option<&str>,
+        target: Option<&str>,
+        details: Value,
+    ) -> WipeResult2<AuditEntry> {
+        let mut entry = AuditEntry {
+            sequence: self.next_sequence,
+            timestamp: Utc::now(),
+            kind,
+            operator: operator.map(str::to_string),
+            target: target.map(str::to_string),
+            details,
+            previous_hash: self.last_hash.clone(),
+            hash: String::new(),
+        };
+        entry.hash = entry.compute_hash();
+
+        let line = serde_json::to_string(&entry).map_err(|e| audit_error("serialize audit entry", e))?;
+        if let Some(parent) = self.path.parent() {
+            let _ = fs::create_dir_all(parent);
+        }
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .map_err(|e| audit_error("open audit log", e))?;
+        writeln!(file, "{}", line).map_err(|e| audit_error("write audit log", e))?;
+        file.sync_data().map_err(|e| audit_error("sync audit log", e))?;
+
+        self.next_sequence += 1;
+        self.last_hash = entry.hash.clone();
+        Ok(entry)
+    }
+
+    pub fn read_entries(&self) -> WipeResult2<Vec<AuditEntry>> {
+        if !self.path.exists() {
+            return Ok(Vec::new());
+        }
+        let content = fs::read_to_string(&self.path).map_err(|e| audit_error("read audit log", e))?;
+        content
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(|line| serde_json::from_str(line).map_err(|e| audit_error("parse audit log", e)))
+            .collect()
+    }
+
+    /// Sequence of the first entry that breaks the chain, or None if the log is intact
+    pub fn verify(&self) -> WipeResult2<Option<u64>> {
+        Ok(verify_chain(&self.read_entries()?, GENESIS_HASH))
+    }
+
+    pub fn export(&self, range: &AuditRange) -> WipeResult2<AuditExport> {
+        let entries = self.read_entries()?;
+        let first_broken_sequence = verify_chain(&entries, GENESIS_HASH);
+
+        let first = entries.iter().position(|e| range.contains(e.timestamp));
+        let anchor_hash = match first {
+            Some(0) | None => GENESIS_HASH.to_string(),
+            Some(index) => entries[index - 1].hash.clone(),
+        };
+
+        Ok(AuditExport {
+            exported_at: Utc::now(),
+            anchor_hash,
+            head_hash: self.last_hash.clone(),
+            total_entries: entries.len() as u64,
+            chain_valid: first_broken_sequence.is_none(),
+            first_broken_sequence,
+            entries: entries.iter().filter(|e| range.contains(e.timestamp)).cloned().collect(),
+        })
+    }
+}
+
+fn audit_error(action: &str, error: impl std::fmt::Display) -> WipeError {
+    WipeError {
+        code: WipeErrorCode::UnknownError,
+        message: format!("Failed to {}: {}", action, error),
+        sector: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_chain_detects_removed_entry() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("audit.jsonl");
+        let mut log = AuditLog::open(&path).unwrap();
+        for n in 0..4 {
+            log.append(AuditEventKind::Preflight, Some("alice"), Some("/dev/sdb"), serde_json::json!({ "n": n })).unwrap();
+        }
+        assert_eq!(log.verify().unwrap(), None);
+
+        let reopened = AuditLog::open(&path).unwrap();
+        assert_eq!(reopened.next_sequence, 4);
+
+        let export = reopened.export(&AuditRange::default()).unwrap();
+        assert!(export.chain_valid && export.verify());
+
+        let mut entries = export.entries.clone();
+        entries.remove(2);
+        assert_eq!(verify_chain(&entries, GENESIS_HASH), Some(3));
+    }
+}
@@ -0,0 +1,235 @@
+//! Local backup of issued certificates and reports
+//!
+//! The reports directory is the only copy of a certificate until the uploads
+//! deliver it, and a station without uploads never has another. With
+//! `WipeConfiguration::backup_reports` set, every file issued for a wipe is
+//! also copied into a backup directory, typically on a second disk, one
+//! subdirectory per operation.
+//!
+//! `manifest.json` in the backup directory lists each copied file with its
+//! size and SHA-256, so the backup can be checked without the originals.
+//! Past `keep_operations`, the oldest operations are removed from the backup.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use tracing::warn;
+use uuid::Uuid;
+
+use crate::core::{WipeError, WipeErrorCode, WipeResult2};
+
+const MANIFEST_FILE: &str = "manifest.json";
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ReportBackupConfig {
+    pub directory: PathBuf,
+    /// Operations kept, newest first; all are kept when unset
+    #[serde(default)]
+    pub keep_operations: Option<usize>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BackedUpFile {
+    /// File name inside the operation's directory
+    pub name: String,
+    pub size: u64,
+    pub sha256: String,
+}
+
+/// Files of one operation in the backup
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BackupEntry {
+    pub operation_id: Uuid,
+    pub backed_up_at: DateTime<Utc>,
+    pub files: Vec<BackedUpFile>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct BackupManifest {
+    pub entries: Vec<BackupEntry>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct BackupStatus {
+    pub enabled: bool,
+    pub directory: Option<PathBuf>,
+    pub operations: usize,
+    pub files: usize,
+    pub total_bytes: u64,
+    pub last_backup_at: Option<DateTime<Utc>>,
+    /// Files missing from the backup or no longer matching the manifest
+    pub damaged: Vec<PathBuf>,
+    /// Why the latest backup failed, until one succeeds
+    pub last_error: Option<String>,
+}
+
+/// Copies issued files into the configured backup directory
+#[derive(Debug, Default)]
+pub struct ReportBackups {
+    last_error: Mutex<Option<String>>,
+}
+
+impl ReportBackups {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Copy `files` of `operation_id` into the backup and remove the oldest
+    /// operations past the configured limit
+    pub fn store(&self, config: &ReportBackupConfig, operation_id: Uuid, files: &[PathBuf]) -> WipeResult2<BackupEntry> {
+        let stored = store(config, operation_id, files);
+        *self.last_error.lock().unwrap() = stored.as_ref().err().map(|e| e.message.clone());
+        stored
+    }
+
+    /// What the backup holds, with every file checked against the manifest
+    pub fn status(&self, config: Option<&ReportBackupConfig>) -> WipeResult2<BackupStatus> {
+        let last_error = self.last_error.lock().unwrap().clone();
+        let Some(config) = config else {
+            return Ok(BackupStatus {
+                enabled: false,
+                directory: None,
+                operations: 0,
+                files: 0,
+                total_bytes: 0,
+                last_backup_at: None,
+                damaged: Vec::new(),
+                last_error,
+            });
+        };
+
+        let manifest = read_manifest(&config.directory)?;
+        let mut damaged = Vec::new();
+        for entry in &manifest.entries {
+            for file in &entry.files {
+                let path = config.directory.join(entry.operation_id.to_string()).join(&file.name);
+                let intact = fs::read(&path).is_ok_and(|content| hex::encode(Sha256::digest(&content)) == file.sha256);
+                if !intact {
+                    damaged.push(path);
+                }
+            }
+        }
+
+        Ok(BackupStatus {
+            enabled: true,
+            directory: Some(config.directory.clone()),
+            operations: manifest.entries.len(),
+            files: manifest.entries.iter().map(|entry| entry.files.len()).sum(),
+            total_bytes: manifest.entries.iter().flat_map(|entry| &entry.files).map(|file| file.size).sum(),
+            last_backup_at: manifest.entries.iter().map(|entry| entry.backed_up_at).max(),
+            damaged,
+            last_error,
+        })
+    }
+}
+
+fn store(config: &ReportBackupConfig, operation_id: Uuid, files: &[PathBuf]) -> WipeResult2<BackupEntry> {
+    let contents = files
+        .iter()
+        .map(|file| fs::read(file).map_err(|e| backup_error(file, "read", e)))
+        .collect::<WipeResult2<Vec<_>>>()?;
+    let operation_dir = config.directory.join(operation_id.to_string());
+    fs::create_dir_all(&operation_dir).map_err(|e| backup_error(&operation_dir, "create", e))?;
+
+    let mut copied = Vec::new();
+    for (file, content) in files.iter().zip(contents) {
+        let name = file.file_name().map(|name| name.to_string_lossy().into_owned()).unwrap_or_default();
+        let destination = operation_dir.join(&name);
+        fs::write(&destination, &content).map_err(|e| backup_error(&destination, "write", e))?;
+        copied.push(BackedUpFile { name, size: content.len() as u64, sha256: hex::encode(Sha256::digest(&content)) });
+    }
+
+    let entry = BackupEntry { operation_id, backed_up_at: Utc::now(), files: copied };
+    let mut manifest = read_manifest(&config.directory)?;
+    manifest.entries.retain(|existing| existing.operation_id != operation_id);
+    manifest.entries.push(entry.clone());
+    manifest.entries.sort_by_key(|existing| existing.backed_up_at);
+
+    // The operation just stored is always kept
+    let keep = config.keep_operations.unwrap_or(usize::MAX).max(1);
+    let excess = manifest.entries.len().saturating_sub(keep);
+    for removed in manifest.entries.drain(..excess) {
+        let removed_dir = config.directory.join(removed.operation_id.to_string());
+        if let Err(e) = fs::remove_dir_all(&removed_dir)
+            && e.kind() != std::io::ErrorKind::NotFound
+        {
+            warn!(path = %removed_dir.display(), error = %e, "Could not remove a directory from the report backup");
+        }
+    }
+
+    write_manifest(&config.directory, &manifest)?;
+    Ok(entry)
+}
+
+fn read_manifest(directory: &Path) -> WipeResult2<BackupManifest> {
+    let path = directory.join(MANIFEST_FILE);
+    match fs::read_to_string(&path) {
+        Ok(content) => serde_json::from_str(&content).map_err(|e| WipeError {
+            code: WipeErrorCode::UnknownError,
+            message: format!("Failed to parse {}: {}", path.display(), e),
+            sector: None,
+        }),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(BackupManifest::default()),
+        Err(e) => Err(backup_error(&path, "read", e)),
+    }
+}
+
+/// Written next to the manifest and renamed over it, so a crash leaves the old one
+fn write_manifest(directory: &Path, manifest: &BackupManifest) -> WipeResult2<()> {
+    let path = directory.join(MANIFEST_FILE);
+    let temporary = directory.join(format!("{}.tmp", MANIFEST_FILE));
+    let content = serde_json::to_string_pretty(manifest).map_err(|e| WipeError {
+        code: WipeErrorCode::UnknownError,
+        message: format!("Failed to serialize the backup manifest: {}", e),
+        sector: None,
+    })?;
+    fs::write(&temporary, content).map_err(|e| backup_error(&temporary, "write", e))?;
+    fs::rename(&temporary, &path).map_err(|e| backup_error(&path, "replace", e))
+}
+
+fn backup_error(path: &Path, action: &str, e: std::io::Error) -> WipeError {
+    WipeError {
+        code: WipeErrorCode::UnknownError,
+        message: format!("Report backup failed to {} {}: {}", action, path.display(), e),
+        sector: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_backup_rotates_and_detects_damage() {
+        let dir = tempfile::tempdir().unwrap();
+        let reports = dir.path().join("reports");
+        fs::create_dir_all(&reports).unwrap();
+        let config = ReportBackupConfig { directory: dir.path().join("backup"), keep_operations: Some(2) };
+        let backups = ReportBackups::new();
+
+        let operations: Vec<Uuid> = (0..3).map(|_| Uuid::new_v4()).collect();
+        for (i, operation_id) in operations.iter().enumerate() {
+            let certificate = reports.join(format!("certificate_{}.json", i));
+            fs::write(&certificate, format!("{{\"certificate\": {}}}", i)).unwrap();
+            let entry = backups.store(&config, *operation_id, &[certificate]).unwrap();
+            assert_eq!(entry.files[0].name, format!("certificate_{}.json", i));
+        }
+
+        // The oldest operation was rotated out
+        let status = backups.status(Some(&config)).unwrap();
+        assert_eq!((status.operations, status.files), (2, 2));
+        assert!(status.damaged.is_empty() && status.last_error.is_none());
+        assert!(!config.directory.join(operations[0].to_string()).exists());
+
+        let tampered = config.directory.join(operations[2].to_string()).join("certificate_2.json");
+        fs::write(&tampered, "{}").unwrap();
+        assert_eq!(backups.status(Some(&config)).unwrap().damaged, vec![tampered]);
+
+        let missing = backups.store(&config, Uuid::new_v4(), &[reports.join("missing.pdf")]).unwrap_err();
+        assert_eq!(backups.status(Some(&config)).unwrap().last_error, Some(missing.message));
+        assert!(!backups.status(None).unwrap().enabled);
+    }
+}
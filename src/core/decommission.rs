@@ -0,0 +1,239 @@
+//! Wiping every drive of a machine being decommissioned
+//!
+//! Retiring a whole machine means purging every data drive it holds, and
+//! nothing else. The operator first asks for a plan: every attached drive,
+//! split into the ones that will be purged and the ones left alone with the
+//! reason (the system disk, removable media unless asked for, device
+//! mappings and drives the target policy protects). The plan is saved with a
+//! short confirmation token. Wiping needs that token back while the plan is
+//! fresh and the attached drives still match it, so no drive is wiped that the
+//! operator did not see listed.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+use uuid::Uuid;
+
+use crate::core::{
+    default_target_type, PolicyTarget, SafetyPolicy, TargetPolicy, TargetType, WipeError, WipeErrorCode, WipeResult2,
+};
+use crate::platform::{DiskKind, PhysicalDisk};
+
+/// Default location of the last decommission plan
+pub const DEFAULT_DECOMMISSION_PLAN_PATH: &str = "history/decommission-plan.json";
+
+/// How long a plan can be confirmed after it was made
+pub const DECOMMISSION_PLAN_VALIDITY_MINUTES: i64 = 15;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PlannedDrive {
+    pub path: String,
+    pub model: String,
+    pub serial: String,
+    pub size_bytes: u64,
+    pub bus_type: String,
+    pub target_type: TargetType,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExcludedDrive {
+    pub path: String,
+    pub model: String,
+    pub serial: String,
+    /// e.g. "System disk" or "Removable drive"
+    pub reason: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DecommissionPlan {
+    pub id: Uuid,
+    pub created_at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+    pub created_by: String,
+    pub include_removable: bool,
+    /// Drives that will be purged
+    pub drives: Vec<PlannedDrive>,
+    pub excluded: Vec<ExcludedDrive>,
+    /// What the operator gives back to start the wipes
+    pub confirmation_token: String,
+}
+
+impl DecommissionPlan {
+    /// Sort `disks` into the drives to purge and the ones to leave alone
+    pub fn build(disks: &[PhysicalDisk], policy: &TargetPolicy, include_removable: bool, created_by: &str) -> Self {
+        let mut drives = Vec::new();
+        let mut excluded = Vec::new();
+
+        for disk in disks {
+            let reason = if disk.is_root_disk {
+                Some("System disk".to_string())
+            } else if disk.removable && !include_removable {
+                Some("Removable drive".to_string())
+            } else if disk.kind.is_virtual() && disk.kind != DiskKind::TestDevice {
+                Some(format!("{} of another device", disk.kind.as_str()))
+            } else {
+                let decision = policy.evaluate(&PolicyTarget {
+                    path: disk.path.clone(),
+                    serial: disk.serial.clone(),
+                    model: disk.model.clone(),
+                    size_bytes: disk.size_bytes,
+                    interface: disk.bus_type.clone(),
+                    removable: disk.removable,
+                });
+                (!decision.allowed).then_some(decision.reason)
+            };
+
+            match reason {
+                Some(reason) => excluded.push(ExcludedDrive {
+                    path: disk.path.clone(),
+                    model: disk.model.clone(),
+                    serial: disk.serial.clone(),
+                    reason,
+                }),
+                None => drives.push(PlannedDrive {
+                    path: disk.path.clone(),
+                    model: disk.model.clone(),
+                    serial: disk.serial.clone(),
+                    size_bytes: disk.size_bytes,
+                    bus_type: disk.bus_type.clone(),
+                    target_type: default_target_type(disk),
+                }),
+            }
+        }
+
+        let id = Uuid::new_v4();
+        let created_at = Utc::now();
+        Self {
+            id,
+            created_at,
+            expires_at: created_at + chrono::Duration::minutes(DECOMMISSION_PLAN_VALIDITY_MINUTES),
+            created_by: created_by.to_string(),
+            include_removable,
+            drives,
+            excluded,
+            confirmation_token: SafetyPolicy::confirmation_token(id),
+        }
+    }
+
+    /// Refuse with `PolicyDenied` unless `token` confirms this plan, the plan
+    /// has not expired and `disks` still sort into the same drives to purge
+    pub fn check(&self, token: &str, disks: &[PhysicalDisk], policy: &TargetPolicy) -> WipeResult2<()> {
+        if !token.trim().eq_ignore_ascii_case(&self.confirmation_token) {
+            return Err(denied("Confirmation token does not match the last decommission plan".to_string()));
+        }
+        if Utc::now() > self.expires_at {
+            return Err(denied("The decommission plan has expired; preview it again".to_string()));
+        }
+        let current = Self::build(disks, policy, self.include_removable, &self.created_by);
+        let identity = |drives: &[PlannedDrive]| {
+            let mut identity: Vec<_> = drives.iter().map(|d| (d.path.clone(), d.serial.clone(), d.size_bytes)).collect();
+            identity.sort();
+            identity
+        };
+        if identity(&current.drives) != identity(&self.drives) {
+            return Err(denied("The attached drives changed since the decommission plan was made; preview it again".to_string()));
+        }
+        Ok(())
+    }
+
+    pub fn load<P: AsRef<Path>>(path: P) -> WipeResult2<Option<Self>> {
+        let path = path.as_ref();
+        if !path.exists() {
+            return Ok(None);
+        }
+        let content = fs::read_to_string(path).map_err(|e| WipeError {
+            code: WipeErrorCode::UnknownError,
+            message: format!("Failed to read decommission plan: {}", e),
+            sector: None,
+        })?;
+        serde_json::from_str(&content).map(Some).map_err(|e| WipeError {
+            code: WipeErrorCode::UnknownError,
+            message: format!("Failed to parse decommission plan: {}", e),
+            sector: None,
+        })
+    }
+
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> WipeResult2<()> {
+        let path = path.as_ref();
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).map_err(|e| WipeError {
+                code: WipeErrorCode::UnknownError,
+                message: format!("Failed to create history directory: {}", e),
+                sector: None,
+            })?;
+        }
+        let content = serde_json::to_string_pretty(self).map_err(|e| WipeError {
+            code: WipeErrorCode::UnknownError,
+            message: format!("Failed to serialize decommission plan: {}", e),
+            sector: None,
+        })?;
+        fs::write(path, content).map_err(|e| WipeError {
+            code: WipeErrorCode::UnknownError,
+            message: format!("Failed to write decommission plan: {}", e),
+            sector: None,
+        })
+    }
+}
+
+fn denied(message: String) -> WipeError {
+    WipeError {
+        code: WipeErrorCode::PolicyDenied,
+        message,
+        sector: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{PolicyAction, PolicyMatch, PolicyRule};
+
+    fn disk(path: &str, serial: &str) -> PhysicalDisk {
+        PhysicalDisk {
+            path: path.to_string(),
+            serial: serial.to_string(),
+            model: "WDC WD10EZEX".to_string(),
+            size_bytes: 1_000_204_886_016,
+            bus_type: "SATA".to_string(),
+            rotational: Some(true),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_plan_leaves_system_and_protected_drives_alone() {
+        let system = PhysicalDisk { is_root_disk: true, ..disk("/dev/sda", "SYS") };
+        let usb = PhysicalDisk { removable: true, ..disk("/dev/sdd", "USB1") };
+        let crypt = PhysicalDisk { kind: DiskKind::DmCrypt, ..disk("/dev/dm-0", "") };
+        let mut disks = vec![system, disk("/dev/sdb", "DATA1"), disk("/dev/sdc", "EVIDENCE"), usb, crypt];
+        let policy = TargetPolicy {
+            rules: vec![PolicyRule {
+                name: "evidence".to_string(),
+                action: PolicyAction::Deny,
+                conditions: PolicyMatch { serial: Some("EVID*".to_string()), ..Default::default() },
+            }],
+            ..Default::default()
+        };
+
+        let plan = DecommissionPlan::build(&disks, &policy, false, "alice");
+        assert_eq!(plan.drives.iter().map(|d| d.path.as_str()).collect::<Vec<_>>(), vec!["/dev/sdb"]);
+        let reasons: Vec<_> = plan.excluded.iter().map(|d| (d.path.as_str(), d.reason.as_str())).collect();
+        assert_eq!(reasons[0], ("/dev/sda", "System disk"));
+        assert!(reasons[1].1.contains("protected by rule 'evidence'"));
+        assert_eq!(reasons[2], ("/dev/sdd", "Removable drive"));
+        assert_eq!(reasons[3], ("/dev/dm-0", "dm-crypt mapping of another device"));
+        assert_eq!(DecommissionPlan::build(&disks, &policy, true, "alice").drives.len(), 2);
+
+        assert!(plan.check(&plan.confirmation_token.to_lowercase(), &disks, &policy).is_ok());
+        assert!(matches!(plan.check("WRONG", &disks, &policy).unwrap_err().code, WipeErrorCode::PolicyDenied));
+
+        // A drive attached after the preview was never shown to the operator
+        disks.push(disk("/dev/sde", "DATA2"));
+        assert!(plan.check(&plan.confirmation_token, &disks, &policy).unwrap_err().message.contains("changed"));
+
+        let expired = DecommissionPlan { expires_at: Utc::now() - chrono::Duration::minutes(1), ..plan };
+        disks.pop();
+        assert!(expired.check(&expired.confirmation_token, &disks, &policy).unwrap_err().message.contains("expired"));
+    }
+}
@@ -0,0 +1,514 @@
+//! Email notifications of finished wipes
+//!
+//! Overnight wipes should not need someone watching a screen. The
+//! `EmailNotifier` is a `WipeEventSink` that mails a summary of every
+//! finished operation to the configured recipients, with the PDF certificate
+//! attached. With `rollup_after_minutes` set it mails one summary per batch
+//! instead: finished operations are collected until no wipe has run for that
+//! long, then sent together.
+//!
+//! Mail goes out over SMTP, in the clear or with STARTTLS or implicit TLS;
+//! TLS needs the `smtp-tls` feature.
+
+use base64::{engine::general_purpose, Engine as _};
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::io::{Read, Write};
+use std::net::{TcpStream, ToSocketAddrs};
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+use tracing::warn;
+use uuid::Uuid;
+
+use crate::core::{event_summary, local_hostname, LifecycleEvent, LifecycleEventKind, WipeEventSink};
+
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+const IO_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// How often a pending rollup checks whether the batch has gone quiet
+const ROLLUP_TICK: Duration = Duration::from_secs(30);
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum SmtpSecurity {
+    /// Plain SMTP, for relays on a trusted network
+    None,
+    StartTls,
+    /// TLS from the first byte, usually on port 465
+    Tls,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EmailConfig {
+    /// SMTP server as `host:port`
+    pub server: String,
+    #[serde(default = "default_security")]
+    pub security: SmtpSecurity,
+    #[serde(default)]
+    pub username: Option<String>,
+    /// Environment variable holding the SMTP password, so it stays out of the configuration
+    #[serde(default)]
+    pub password_env: Option<String>,
+    pub from: String,
+    pub to: Vec<String>,
+    /// Events to mail; completions, failures and cancellations when empty
+    #[serde(default)]
+    pub events: Vec<LifecycleEventKind>,
+    #[serde(default = "default_true")]
+    pub attach_certificates: bool,
+    /// Mail one summary once no wipe has run for this many minutes, instead of one mail per operation
+    #[serde(default)]
+    pub rollup_after_minutes: Option<u64>,
+    /// PEM bundle of CAs trusted for TLS in addition to the built-in roots
+    #[serde(default)]
+    pub ca_file: Option<String>,
+}
+
+fn default_security() -> SmtpSecurity { SmtpSecurity::StartTls }
+
+fn default_true() -> bool { true }
+
+/// A message before MIME encoding
+#[derive(Debug, Clone, PartialEq)]
+pub struct Mail {
+    pub subject: String,
+    pub body: String,
+    /// Files attached as they are; ones that no longer exist are left out
+    pub attachments: Vec<String>,
+}
+
+/// Operations of the current batch, for rollup mails
+#[derive(Default)]
+struct Rollup {
+    running: HashSet<Uuid>,
+    finished: Vec<LifecycleEvent>,
+    last_activity: Option<Instant>,
+}
+
+pub struct EmailNotifier {
+    config: EmailConfig,
+    hostname: String,
+    rollup: Option<Arc<Mutex<Rollup>>>,
+}
+
+impl EmailNotifier {
+    pub fn new(config: EmailConfig) -> Self {
+        let hostname = local_hostname();
+        let rollup = config.rollup_after_minutes.map(|minutes| {
+            let rollup = Arc::new(Mutex::new(Rollup::default()));
+            let state = Arc::downgrade(&rollup);
+            let (config, hostname) = (config.clone(), hostname.clone());
+            let quiet = Duration::from_secs(minutes * 60);
+            // Stops once the notifier is dropped, e.g. when the configuration changes
+            thread::spawn(move || loop {
+                thread::sleep(ROLLUP_TICK.min(quiet.max(Duration::from_secs(1))));
+                let Some(rollup) = state.upgrade() else { break };
+                let batch = {
+                    let rollup = rollup.lock().unwrap();
+                    let quiet_for = rollup.last_activity.map_or(Duration::ZERO, |at| at.elapsed());
+                    if !rollup.running.is_empty() || rollup.finished.is_empty() || quiet_for < quiet {
+                        continue;
+                    }
+                    rollup.finished.clone()
+                };
+                match send_mail(&config, &hostname, &rollup_mail(&batch, config.attach_certificates)) {
+                    Ok(()) => {
+                        let mut rollup = rollup.lock().unwrap();
+                        rollup.finished.retain(|event| !batch.iter().any(|sent| sent.event_id == event.event_id));
+                    }
+                    Err(e) => warn!(server = %config.server, error = %e, "Wipe summary mail failed, retrying"),
+                }
+            });
+            rollup
+        });
+        Self { config, hostname, rollup }
+    }
+
+    fn mailed(&self, kind: LifecycleEventKind) -> bool {
+        if self.config.events.is_empty() {
+            is_finish(kind)
+        } else {
+            self.config.events.contains(&kind)
+        }
+    }
+}
+
+fn is_finish(kind: LifecycleEventKind) -> bool {
+    matches!(kind, LifecycleEventKind::WipeCompleted | LifecycleEventKind::WipeFailed | LifecycleEventKind::WipeCancelled)
+}
+
+impl WipeEventSink for EmailNotifier {
+    fn name(&self) -> String {
+        format!("email via {}", self.config.server)
+    }
+
+    fn accepts(&self, kind: LifecycleEventKind) -> bool {
+        // A rollup tracks every start to know when the batch is over
+        self.mailed(kind) || (self.rollup.is_some() && (kind == LifecycleEventKind::WipeStarted || is_finish(kind)))
+    }
+
+    fn deliver(&self, event: &LifecycleEvent) -> Result<(), String> {
+        let Some(rollup) = &self.rollup else {
+            return send_mail(&self.config, &self.hostname, &operation_mail(event, self.config.attach_certificates));
+        };
+        // Deliveries can be retried, so recording an event twice must change nothing
+        let mut rollup = rollup.lock().unwrap();
+        rollup.last_activity = Some(Instant::now());
+        match event.kind {
+            LifecycleEventKind::WipeStarted => {
+                rollup.running.insert(event.operation_id);
+            }
+            kind if is_finish(kind) => {
+                rollup.running.remove(&event.operation_id);
+            }
+            _ => {}
+        }
+        if event.kind != LifecycleEventKind::WipeStarted
+            && self.mailed(event.kind)
+            && !rollup.finished.iter().any(|e| e.event_id == event.event_id)
+        {
+            rollup.finished.push(event.clone());
+        }
+        Ok(())
+    }
+}
+
+/// Sink for the configured mail notifications
+pub fn email_sinks(config: Option<&EmailConfig>) -> Vec<Arc<dyn WipeEventSink>> {
+    match config {
+        Some(config) if config.security != SmtpSecurity::None && !cfg!(feature = "smtp-tls") => {
+            warn!(server = %config.server, "Mail over TLS needs the smtp-tls feature; not sending");
+            Vec::new()
+        }
+        Some(config) => vec![Arc::new(EmailNotifier::new(config.clone()))],
+        None => Vec::new(),
+    }
+}
+
+fn outcome(kind: LifecycleEventKind) -> &'static str {
+    match kind {
+        LifecycleEventKind::WipeStarted => "started",
+        LifecycleEventKind::WipeCompleted => "completed",
+        LifecycleEventKind::WipeFailed => "FAILED",
+        LifecycleEventKind::WipeCancelled => "cancelled",
+        LifecycleEventKind::VerificationCompleted => "verified",
+    }
+}
+
+fn event_details(event: &LifecycleEvent) -> String {
+    let mut lines = vec![
+        format!("Operation:   {}", event.operation_id),
+        format!("Device:      {}", event.target_path),
+        format!("Scope:       {}", event.scope),
+        format!("Standard:    {}", event.standard),
+        format!("Time:        {}", event.occurred_at.to_rfc3339()),
+    ];
+    let optional = [
+        ("Serial:     ", event.serial_number.clone()),
+        ("Operator:   ", event.operator.clone()),
+        ("Certificate:", event.certificate_id.clone()),
+        ("Verified:   ", event.verification_passed.map(|passed| if passed { "passed" } else { "failed" }.to_string())),
+        ("Error:      ", event.error.clone()),
+    ];
+    lines.extend(optional.into_iter().filter_map(|(label, value)| value.map(|v| format!("{} {}", label, v))));
+    lines.join("\n")
+}
+
+/// Mail for a single operation
+pub fn operation_mail(event: &LifecycleEvent, attach_certificate: bool) -> Mail {
+    let serial = event.serial_number.as_deref().map(|serial| format!(" ({})", serial)).unwrap_or_default();
+    Mail {
+        subject: format!("[hdd-tool] Wipe {}: {}{}", outcome(event.kind), event.target_path, serial),
+        body: format!("{}\n\n{}\n", event_summary(event), event_details(event)),
+        attachments: event.certificate_path.iter().filter(|_| attach_certificate).cloned().collect(),
+    }
+}
+
+/// One mail for every operation of a batch
+pub fn rollup_mail(events: &[LifecycleEvent], attach_certificates: bool) -> Mail {
+    let count = |kind| events.iter().filter(|event| event.kind == kind).count();
+    let (completed, failed, cancelled) = (
+        count(LifecycleEventKind::WipeCompleted),
+        count(LifecycleEventKind::WipeFailed),
+        count(LifecycleEventKind::WipeCancelled),
+    );
+    let mut body = format!(
+        "{} operation(s): {} completed, {} failed, {} cancelled\n\n",
+        events.len(), completed, failed, cancelled
+    );
+    for event in events {
+        body.push_str(&format!("- {}\n", event_summary(event)));
+    }
+    for event in events {
+        body.push_str(&format!("\n{}\n", event_details(event)));
+    }
+    Mail {
+        subject: format!("[hdd-tool] Wipe batch finished: {} completed, {} failed, {} cancelled", completed, failed, cancelled),
+        body,
+        attachments: events.iter().filter_map(|event| event.certificate_path.clone()).filter(|_| attach_certificates).collect(),
+    }
+}
+
+/// `mail` as a MIME message with CRLF line endings
+pub fn compose(config: &EmailConfig, hostname: &str, mail: &Mail) -> Vec<u8> {
+    let boundary = format!("hdd-tool-{}", Uuid::new_v4().simple());
+    let mut message = format!(
+        "From: {}\r\nTo: {}\r\nSubject: {}\r\nDate: {}\r\nMessage-ID: <{}@{}>\r\nMIME-Version: 1.0\r\n\
+         Content-Type: multipart/mixed; boundary=\"{}\"\r\n\r\n",
+        config.from,
+        config.to.join(", "),
+        encode_header(&mail.subject),
+        Utc::now().to_rfc2822(),
+        Uuid::new_v4(),
+        if hostname.is_empty() { "hdd-tool" } else { hostname },
+        boundary,
+    );
+    message.push_str(&format!(
+        "--{}\r\nContent-Type: text/plain; charset=utf-8\r\nContent-Transfer-Encoding: base64\r\n\r\n{}",
+        boundary,
+        base64_lines(mail.body.as_bytes())
+    ));
+    for path in &mail.attachments {
+        let path = Path::new(path);
+        let Ok(content) = std::fs::read(path) else {
+            warn!(attachment = %path.display(), "Attachment is gone; mailing without it");
+            continue;
+        };
+        let name = path.file_name().and_then(|name| name.to_str()).unwrap_or("certificate");
+        let content_type = match path.extension().and_then(|e| e.to_str()) {
+            Some("pdf") => "application/pdf",
+            Some("json") => "application/json",
+            _ => "application/octet-stream",
+        };
+        message.push_str(&format!(
+            "--{}\r\nContent-Type: {}; name=\"{}\"\r\nContent-Disposition: attachment; filename=\"{}\"\r\n\
+             Content-Transfer-Encoding: base64\r\n\r\n{}",
+            boundary, content_type, name, name, base64_lines(&content)
+        ));
+    }
+    message.push_str(&format!("--{}--\r\n", boundary));
+    message.into_bytes()
+}
+
+/// RFC 2047 encoded word for headers that are not plain ASCII
+fn encode_header(value: &str) -> String {
+    if value.is_ascii() {
+        value.to_string()
+    } else {
+        format!("=?UTF-8?B?{}?=", general_purpose::STANDARD.encode(value))
+    }
+}
+
+fn base64_lines(content: &[u8]) -> String {
+    let encoded = general_purpose::STANDARD.encode(content);
+    encoded.as_bytes().chunks(76).map(|line| format!("{}\r\n", String::from_utf8_lossy(line))).collect()
+}
+
+/// Compose `mail` and hand it to the configured SMTP server
+pub fn send_mail(config: &EmailConfig, hostname: &str, mail: &Mail) -> Result<(), String> {
+    let message = compose(config, hostname, mail);
+    let password = match &config.password_env {
+        Some(var) => Some(std::env::var(var).map_err(|_| format!("{} is not set", var))?),
+        None => None,
+    };
+    let credentials = config.username.as_deref().zip(password.as_deref());
+    let recipients: Vec<&str> = config.to.iter().map(String::as_str).collect();
+    let ehlo_name = if hostname.is_empty() { "localhost" } else { hostname };
+
+    let address = config.server
+        .to_socket_addrs()
+        .map_err(|e| format!("{}: {}", config.server, e))?
+        .next()
+        .ok_or_else(|| format!("{}: no address", config.server))?;
+    let mut stream = TcpStream::connect_timeout(&address, CONNECT_TIMEOUT).map_err(|e| e.to_string())?;
+    stream.set_read_timeout(Some(IO_TIMEOUT)).map_err(|e| e.to_string())?;
+    stream.set_write_timeout(Some(IO_TIMEOUT)).map_err(|e| e.to_string())?;
+
+    match config.security {
+        SmtpSecurity::None => {
+            expect(&mut stream, &[220])?;
+            transaction(&mut stream, ehlo_name, credentials, &config.from, &recipients, &message)
+        }
+        #[cfg(feature = "smtp-tls")]
+        SmtpSecurity::StartTls => {
+            expect(&mut stream, &[220])?;
+            command(&mut stream, &format!("EHLO {}", ehlo_name), &[250])?;
+            command(&mut stream, "STARTTLS", &[220])?;
+            let mut stream = tls_stream(config, stream)?;
+            transaction(&mut stream, ehlo_name, credentials, &config.from, &recipients, &message)
+        }
+        #[cfg(feature = "smtp-tls")]
+        SmtpSecurity::Tls => {
+            let mut stream = tls_stream(config, stream)?;
+            expect(&mut stream, &[220])?;
+            transaction(&mut stream, ehlo_name, credentials, &config.from, &recipients, &message)
+        }
+        #[cfg(not(feature = "smtp-tls"))]
+        SmtpSecurity::StartTls | SmtpSecurity::Tls => Err("mail over TLS needs the smtp-tls feature".to_string()),
+    }
+}
+
+#[cfg(feature = "smtp-tls")]
+fn tls_stream(config: &EmailConfig, stream: TcpStream) -> Result<rustls::StreamOwned<rustls::ClientConnection, TcpStream>, String> {
+    let host = config.server.rsplit_once(':').map_or(config.server.as_str(), |(host, _)| host);
+    let server_name = rustls::ServerName::try_from(host.trim_matches(['[', ']'])).map_err(|e| e.to_string())?;
+    let tls_config = crate::core::siem::tls_client_config(config.ca_file.as_deref())?;
+    let session = rustls::ClientConnection::new(tls_config, server_name).map_err(|e| e.to_string())?;
+    Ok(rustls::StreamOwned::new(session, stream))
+}
+
+/// Everything after the greeting (and STARTTLS): EHLO, AUTH, the envelope, the message and QUIT
+fn transaction<S: Read + Write>(
+    stream: &mut S,
+    ehlo_name: &str,
+    credentials: Option<(&str, &str)>,
+    from: &str,
+    recipients: &[&str],
+    message: &[u8],
+) -> Result<(), String> {
+    command(stream, &format!("EHLO {}", ehlo_name), &[250])?;
+    if let Some((username, password)) = credentials {
+        let token = general_purpose::STANDARD.encode(format!("\0{}\0{}", username, password));
+        command(stream, &format!("AUTH PLAIN {}", token), &[235])?;
+    }
+    command(stream, &format!("MAIL FROM:<{}>", from), &[250])?;
+    for recipient in recipients {
+        command(stream, &format!("RCPT TO:<{}>", recipient), &[250, 251])?;
+    }
+    command(stream, "DATA", &[354])?;
+
+    // Dot-stuffing, so no line of the message ends the data early
+    let mut data = Vec::with_capacity(message.len() + 64);
+    for line in message.split_inclusive(|&byte| byte == b'\n') {
+        if line.starts_with(b".") {
+            data.push(b'.');
+        }
+        data.extend_from_slice(line);
+    }
+    if !data.ends_with(b"\r\n") {
+        data.extend_from_slice(b"\r\n");
+    }
+    data.extend_from_slice(b".\r\n");
+    stream.write_all(&data).and_then(|_| stream.flush()).map_err(|e| e.to_string())?;
+    expect(stream, &[250])?;
+
+    // The message is accepted; a server that drops the connection on QUIT changes nothing
+    let _ = command(stream, "QUIT", &[221]);
+    Ok(())
+}
+
+fn command<S: Read + Write>(stream: &mut S, line: &str, accepted: &[u16]) -> Result<String, String> {
+    stream.write_all(format!("{}\r\n", line).as_bytes()).and_then(|_| stream.flush()).map_err(|e| e.to_string())?;
+    expect(stream, accepted)
+}
+
+/// Read a reply, which may span several lines, and check its code
+fn expect<S: Read>(stream: &mut S, accepted: &[u16]) -> Result<String, String> {
+    let mut text = String::new();
+    loop {
+        // Byte by byte, so nothing sent after the reply is buffered away before STARTTLS
+        let mut line = Vec::new();
+        let mut byte = [0u8; 1];
+        while !line.ends_with(b"\n") {
+            if stream.read(&mut byte).map_err(|e| e.to_string())? == 0 {
+                return Err("The SMTP server closed the connection".to_string());
+            }
+            line.push(byte[0]);
+        }
+        let line = String::from_utf8_lossy(&line);
+        let code: u16 = line.get(..3).and_then(|code| code.parse().ok())
+            .ok_or_else(|| format!("Malformed SMTP reply: {}", line.trim_end()))?;
+        text.push_str(line.get(4..).unwrap_or("").trim_end());
+        if line.as_bytes().get(3) == Some(&b'-') {
+            text.push(' ');
+            continue;
+        }
+        return if accepted.contains(&code) {
+            Ok(text)
+        } else {
+            Err(format!("SMTP server answered {} {}", code, text))
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    /// Replays canned server replies and keeps what the client sent
+    struct ScriptedServer {
+        replies: Cursor<Vec<u8>>,
+        sent: Vec<u8>,
+    }
+
+    impl Read for ScriptedServer {
+        fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+            self.replies.read(buf)
+        }
+    }
+
+    impl Write for ScriptedServer {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.sent.write(buf)
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    fn server(replies: &str) -> ScriptedServer {
+        ScriptedServer { replies: Cursor::new(replies.as_bytes().to_vec()), sent: Vec::new() }
+    }
+
+    #[test]
+    fn test_smtp_transaction_and_message() {
+        let config = EmailConfig {
+            server: "smtp.example.com:587".to_string(),
+            security: SmtpSecurity::StartTls,
+            username: Some("station".to_string()),
+            password_env: None,
+            from: "wipes@example.com".to_string(),
+            to: vec!["it@example.com".to_string(), "audit@example.com".to_string()],
+            events: Vec::new(),
+            attach_certificates: true,
+            rollup_after_minutes: None,
+            ca_file: None,
+        };
+        let dir = tempfile::tempdir().unwrap();
+        let certificate = dir.path().join("certificate.pdf");
+        std::fs::write(&certificate, b"%PDF-1.4").unwrap();
+        let request: crate::core::WipeRequest = serde_json::from_value(serde_json::json!({
+            "id": Uuid::new_v4(), "target_path": "/dev/sdx", "target_type": "HDD",
+            "standard": "NIST_SP_800_88_R1", "passes": 1, "verify_erasure": false,
+            "generate_certificate": true, "timestamp": Utc::now(),
+        }))
+        .unwrap();
+        let event = LifecycleEvent {
+            serial_number: Some("WD-123".to_string()),
+            certificate_path: Some(certificate.to_string_lossy().to_string()),
+            ..LifecycleEvent::new(LifecycleEventKind::WipeCompleted, &request)
+        };
+
+        let mail = operation_mail(&event, true);
+        assert_eq!(mail.subject, "[hdd-tool] Wipe completed: /dev/sdx (WD-123)");
+        let message = String::from_utf8(compose(&config, "station1", &mail)).unwrap();
+        assert!(message.contains("To: it@example.com, audit@example.com\r\n"));
+        assert!(message.contains(&format!("filename=\"{}\"", certificate.file_name().unwrap().to_string_lossy())));
+        assert!(message.contains(&general_purpose::STANDARD.encode(b"%PDF-1.4")));
+
+        let mut smtp = server("250-smtp.example.com\r\n250 AUTH PLAIN\r\n235 ok\r\n250 ok\r\n250 ok\r\n250 ok\r\n354 go\r\n250 queued\r\n221 bye\r\n");
+        let recipients = ["it@example.com", "audit@example.com"];
+        transaction(&mut smtp, "station1", Some(("station", "secret")), &config.from, &recipients, b"Subject: x\r\n\r\n.hidden\r\n").unwrap();
+        let sent = String::from_utf8(smtp.sent).unwrap();
+        assert!(sent.starts_with("EHLO station1\r\nAUTH PLAIN AHN0YXRpb24Ac2VjcmV0\r\nMAIL FROM:<wipes@example.com>\r\n"));
+        assert!(sent.contains("RCPT TO:<audit@example.com>\r\nDATA\r\nSubject: x\r\n\r\n..hidden\r\n.\r\nQUIT\r\n"));
+
+        let mut rejecting = server("250 smtp.example.com\r\n250 ok\r\n550 no such user\r\n");
+        let error = transaction(&mut rejecting, "station1", None, &config.from, &recipients, b"x").unwrap_err();
+        assert_eq!(error, "SMTP server answered 550 no such user");
+    }
+}
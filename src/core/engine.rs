@@ -1,38 +1,702 @@
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
 use std::sync::{Arc, Mutex, mpsc};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::Receiver;
 use std::thread;
-use chrono::Utc;
+use std::time::{Duration, Instant};
+use chrono::{DateTime, Utc};
+use tracing::{info, info_span, warn};
 use uuid::Uuid;
 
 use crate::core::{
-    SanitizationStandard, TargetType, WipeRequest, WipeProgress, WipeResult, 
-    WipeConfiguration, WipeError, WipeErrorCode, WipeResult2
+    SanitizationStandard, TargetType, WipeScope, WipeRequest, WipeProgress, WipeResult, 
+    WipeConfiguration, WipeError, WipeErrorCode, WipeResult2,
+    OperationHistory, OperationRecord, HistoryFilter, DEFAULT_HISTORY_PATH,
+    OperationJournal, JournalEvent, InterruptedOperation, RecoveryAction, ResumePoint, DEFAULT_JOURNAL_PATH,
+    PreflightReport, run_preflight, check_method, check_operator, release_volumes,
+    OperatorAuthenticator, OperatorIdentity, DEFAULT_TOKENS_PATH,
+    AuditLog, AuditEventKind, AuditRange, AuditExport, DEFAULT_AUDIT_PATH,
+    DeviceActivity, DeviceLocks, LockedDevice, OperationTimeline, TimelineEvent, TimelineEventKind, EventDispatcher, DEFAULT_DEAD_LETTER_PATH, DEFAULT_RETRY_DELAY, LifecycleEvent, LifecycleEventKind, WipeEventSink, WipeMetrics, UploadQueue, ReportBackups, BackupStatus,
+    LimitsHandle, OperationLimits, PauseCheckpoint, MAX_THROTTLE_SLEEP, WipeSpec, ComplianceProfile,
+    RecipeRegistry, WipeRecipe, DEFAULT_RECIPE_DIR, ApprovalBook, Approval, PendingApproval,
+    SimulatedDevice, TestDevice, TestDeviceBacking,
+    JobSchedule, JobStatus, ScheduledJob, ScheduledOutcome, DEFAULT_SCHEDULE_PATH, SettingsStore, Preemptions, WipePriority,
+    AuditEntry, BundleManifest, EvidenceBundle, ReportFormat, ReportSummary, StoredReport, find_certificate_files,
+    report_summaries, operation_csv,
+    operations_csv, operations_xml, DEFAULT_REPORTS_DIR, DurationEstimate, ThroughputSource, WriteRate,
+    FirmwareErase, HardwarePreference, OverwritePattern, VerificationPolicy, VerificationReport, WipeAdvisory, ThroughputHistory, ThroughputSample, DEFAULT_THROUGHPUT_PATH,
+    DecommissionPlan, DEFAULT_DECOMMISSION_PLAN_PATH, ConfigSnapshot, LiveConfig, SafetyPolicy, init_logging,
 };
+use crate::platform::PhysicalDisk;
 use crate::hardware::SecureSanitizer;
-use crate::security::{CertificateAuthority, ReportGenerator};
+use crate::smart::{DeviceHealth, SmartInterface};
+use crate::residual::ResidualDataReport;
+use crate::encryption::{EncryptionStatus, HeaderDestructionReport};
+use crate::raid::{ControllerEraseMode, RaidMember, VirtualDiskInfo};
+use crate::secure_delete::{ShredOptions, ShredPattern, ShredReport};
+use crate::devices::watcher::{DeviceEvent, DeviceWatcher};
+use crate::pattern_stream::{PatternSeed, SeedVault, DEFAULT_SEED_KEY_PATH};
+use crate::security::{CertificateAuthority, CertificateTemplate, CertificateVerdict, ErasureCertificate, ReportGenerator, SignatureAlgorithm};
 
 pub struct WipeEngine {
-    config: WipeConfiguration,
-    certificate_authority: Arc<Mutex<CertificateAuthority>>,
-    report_generator: ReportGenerator,
+    /// Configuration with the report generator and certificate authority
+    /// built from it; each operation keeps the snapshot it started with
+    config: LiveConfig,
+    history: Arc<Mutex<OperationHistory>>,
+    /// Write-ahead record of running wipes, read back after a crash or power loss
+    journal: Arc<Mutex<OperationJournal>>,
+    /// Write rates achieved per drive model, for estimates and slow-drive checks
+    throughput: Mutex<ThroughputHistory>,
+    device_watcher: Mutex<DeviceWatcher>,
+    authenticator: Mutex<OperatorAuthenticator>,
+    operator: Mutex<Option<OperatorIdentity>>,
+    audit: Mutex<AuditLog>,
+    /// Cancellation flags of the wipes currently running
+    active_wipes: Mutex<HashMap<Uuid, Arc<AtomicBool>>>,
+    /// Devices claimed by running wipes, verifications and scans
+    device_locks: DeviceLocks,
+    /// Rate limits and I/O priorities of the wipes currently running
+    operation_limits: Mutex<HashMap<Uuid, Arc<LimitsHandle>>>,
+    /// Priorities of the wipes currently running, and which are held for a higher one
+    preemptions: Preemptions,
+    /// Events of the wipes currently running; stored with the record when they end
+    timelines: Mutex<HashMap<Uuid, Arc<OperationTimeline>>>,
+    progress_subscribers: Arc<Mutex<Vec<mpsc::Sender<WipeProgress>>>>,
+    /// Webhooks and custom sinks notified of wipe lifecycle events
+    integrations: EventDispatcher,
+    /// Copies of issued certificates and reports on their way to the upload destinations
+    uploads: UploadQueue,
+    /// Outcome of the latest copy into the report backup directory
+    backups: ReportBackups,
+    metrics: Arc<WipeMetrics>,
+    recipes: Mutex<RecipeRegistry>,
+    /// Second-operator approvals of wipes (see `SafetyPolicy`)
+    approvals: Mutex<ApprovalBook>,
+    /// File-backed devices listed with the attached disks, by path
+    test_devices: Mutex<HashMap<String, TestDevice>>,
+    /// Wipes waiting for their start time and maintenance window
+    schedule: Mutex<JobSchedule>,
+    /// Where configuration changes are saved, for engines opened with `open_with_settings`
+    settings: Option<SettingsStore>,
+    /// Directory the history, journal, audit log, certificates and reports are
+    /// kept under; empty for the working directory
+    data_dir: PathBuf,
 }
 
+/// Where the certificate authority is saved, under the data directory
+const CA_PATH: &str = "certificates/ca.json";
+
+/// How often the scheduler looks for due jobs and window boundaries
+const SCHEDULER_TICK: Duration = Duration::from_secs(5);
+
+/// How long `pause_operation` waits for the write loop to reach a block boundary
+const PAUSE_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// A cryptographic erase only replaces the media key
+const CRYPTO_ERASE_ESTIMATE: Duration = Duration::from_secs(60);
+
 impl WipeEngine {
     pub fn new(config: WipeConfiguration) -> WipeResult2<Self> {
-        let ca = CertificateAuthority::new(
-            "SecureWipe Certificate Authority".to_string(),
-            "Data Security Solutions Inc.".to_string(),
-        )?;
+        Self::new_in(config, PathBuf::new())
+    }
 
-        // Save the CA to file for persistence
-        ca.save_to_file("certificates/ca.json")?;
+    /// Start an engine that keeps its history, journal, audit log, operator
+    /// tokens, certificates and reports under `data_dir` instead of the working directory
+    pub fn new_in<P: Into<PathBuf>>(config: WipeConfiguration, data_dir: P) -> WipeResult2<Self> {
+        let data_dir = data_dir.into();
+        init_logging(&config.logging);
+        let ca = Self::certificate_authority_for(&config, &data_dir)?;
+
+        let mut history = OperationHistory::open(data_dir.join(DEFAULT_HISTORY_PATH))?;
+        let journal = OperationJournal::open(data_dir.join(DEFAULT_JOURNAL_PATH))?;
+        for operation in OperationJournal::read_interrupted(journal.path())? {
+            history.record_interrupted(&operation.request, operation.last_seen_at)?;
+            warn!(
+                operation_id = %operation.operation_id,
+                device = %operation.request.target_path,
+                passes_completed = operation.passes_completed,
+                total_passes = operation.total_passes,
+                "Wipe was interrupted; resume or restart it with recover_operation"
+            );
+        }
+        let throughput = ThroughputHistory::open(data_dir.join(DEFAULT_THROUGHPUT_PATH))?;
+        let authenticator = OperatorAuthenticator::open(data_dir.join(DEFAULT_TOKENS_PATH))?;
+        let audit = AuditLog::open(data_dir.join(DEFAULT_AUDIT_PATH))?;
+        let schedule = JobSchedule::open(data_dir.join(DEFAULT_SCHEDULE_PATH))?;
+        let integrations = EventDispatcher::with_settings(data_dir.join(DEFAULT_DEAD_LETTER_PATH), DEFAULT_RETRY_DELAY);
+        integrations.configure(&config);
+        if let Some(locale) = &config.locale {
+            crate::i18n::set_locale(locale);
+        }
+        let template = config.certificate_template.as_ref().map(CertificateTemplate::load).transpose()?;
+        let history = Arc::new(Mutex::new(history));
+        let uploads = UploadQueue::new(history.clone());
+        uploads.configure(&config.uploads);
+        let resumed = uploads.resume_pending()?;
+        if resumed > 0 {
+            info!(uploads = resumed, "Resuming uploads left pending by the last run");
+        }
+
+        let metrics = Arc::new(WipeMetrics::new());
+        if let Some(address) = &config.metrics_address {
+            #[cfg(feature = "metrics")]
+            if let Err(e) = crate::core::serve_metrics(metrics.clone(), address) {
+                warn!(%address, error = %e, "Failed to serve metrics");
+            }
+            #[cfg(not(feature = "metrics"))]
+            warn!(%address, "Metrics address ignored: built without the metrics feature");
+        }
+
+        let mut recipes = RecipeRegistry::new();
+        let recipe_dir = match config.recipe_dir.as_deref() {
+            Some(dir) => PathBuf::from(dir),
+            None => data_dir.join(DEFAULT_RECIPE_DIR),
+        };
+        let loaded = recipes.load_dir(&recipe_dir);
+        if loaded > 0 {
+            info!(recipes = loaded, directory = %recipe_dir.display(), "Loaded wipe recipes");
+        }
 
         Ok(Self {
+            config: LiveConfig::new(ConfigSnapshot {
+                config,
+                report_generator: ReportGenerator::with_template(template),
+                certificate_authority: Arc::new(Mutex::new(ca)),
+            }),
+            history,
+            journal: Arc::new(Mutex::new(journal)),
+            throughput: Mutex::new(throughput),
+            device_watcher: Mutex::new(DeviceWatcher::new()),
+            authenticator: Mutex::new(authenticator),
+            operator: Mutex::new(None),
+            audit: Mutex::new(audit),
+            active_wipes: Mutex::new(HashMap::new()),
+            device_locks: DeviceLocks::new(),
+            operation_limits: Mutex::new(HashMap::new()),
+            preemptions: Preemptions::new(),
+            timelines: Mutex::new(HashMap::new()),
+            progress_subscribers: Arc::new(Mutex::new(Vec::new())),
+            integrations,
+            uploads,
+            backups: ReportBackups::new(),
+            metrics,
+            recipes: Mutex::new(recipes),
+            approvals: Mutex::new(ApprovalBook::new()),
+            test_devices: Mutex::new(HashMap::new()),
+            schedule: Mutex::new(schedule),
+            settings: None,
+            data_dir,
+        })
+    }
+
+    /// Start an engine with the configuration saved in `settings` (the
+    /// defaults when none was saved); later configuration changes are saved there
+    pub fn open_with_settings(settings: SettingsStore) -> WipeResult2<Self> {
+        let mut engine = Self::new(settings.load()?)?;
+        engine.settings = Some(settings);
+        Ok(engine)
+    }
+
+    fn certificate_authority_for(config: &WipeConfiguration, data_dir: &Path) -> WipeResult2<CertificateAuthority> {
+        let ca = match config.signature_algorithm {
+            SignatureAlgorithm::Ed25519 => CertificateAuthority::new_ed25519(
+                "SecureWipe Certificate Authority".to_string(),
+                "Data Security Solutions Inc.".to_string(),
+                config.signing_key_path.as_ref(),
+            )?,
+            SignatureAlgorithm::RsaPkcs1Sha256 => CertificateAuthority::new(
+                "SecureWipe Certificate Authority".to_string(),
+                "Data Security Solutions Inc.".to_string(),
+            )?,
+        };
+
+        // Save the CA to file for persistence
+        ca.save_to_file(data_dir.join(CA_PATH))?;
+        Ok(ca)
+    }
+
+    /// The configuration new operations start with
+    pub fn configuration(&self) -> WipeConfiguration {
+        self.config.snapshot().config.clone()
+    }
+
+    fn current(&self) -> Arc<ConfigSnapshot> {
+        self.config.snapshot()
+    }
+
+    /// Throughput, progress, error and temperature metrics of this engine's wipes
+    pub fn metrics(&self) -> Arc<WipeMetrics> {
+        self.metrics.clone()
+    }
+
+    /// Register a custom integration, e.g. an asset database updater
+    pub fn add_event_sink(&self, sink: Arc<dyn WipeEventSink>) {
+        self.integrations.add_sink(sink);
+    }
+
+    /// Append an event to the audit log under the current operator
+    fn audit(&self, kind: AuditEventKind, target: Option<&str>, details: serde_json::Value) -> WipeResult2<()> {
+        self.audit_as(self.current_operator().as_ref(), kind, target, details)
+    }
+
+    fn audit_as(
+        &self,
+        operator: Option<&OperatorIdentity>,
+        kind: AuditEventKind,
+        target: Option<&str>,
+        details: serde_json::Value,
+    ) -> WipeResult2<()> {
+        let operator = operator.map(|o| o.display_name());
+        self.audit.lock().unwrap().append(kind, operator.as_deref(), target, details)?;
+        Ok(())
+    }
+
+    /// Export audit entries within `range` together with the hashes needed to
+    /// prove the chain is complete
+    pub fn export_audit_log(&self, range: &AuditRange) -> WipeResult2<AuditExport> {
+        self.authenticated_operator()?;
+        self.audit.lock().unwrap().export(range)
+    }
+
+    /// Replace the engine configuration, saving it when the engine was opened
+    /// with settings; requires a logged-in administrator. Operations already
+    /// running keep the configuration they started with.
+    pub fn update_configuration(&self, config: WipeConfiguration) -> WipeResult2<()> {
+        self.authenticated_operator()?.require_admin()?;
+        let current = self.current();
+        let template = config.certificate_template.as_ref().map(CertificateTemplate::load).transpose()?;
+        // Built before anything is saved, so a bad signing key leaves the settings as they were
+        let certificate_authority = if config.signature_algorithm != current.config.signature_algorithm
+            || config.signing_key_path != current.config.signing_key_path
+        {
+            Arc::new(Mutex::new(Self::certificate_authority_for(&config, &self.data_dir)?))
+        } else {
+            current.certificate_authority.clone()
+        };
+
+        let before = serde_json::to_value(&current.config).unwrap_or_default();
+        let after = serde_json::to_value(&config).unwrap_or_default();
+        let changed: Vec<&String> = after
+            .as_object()
+            .into_iter()
+            .flatten()
+            .filter(|(key, value)| before.get(key.as_str()) != Some(value))
+            .map(|(key, _)| key)
+            .collect();
+
+        self.audit(AuditEventKind::ConfigChanged, None, serde_json::json!({
+            "changed_fields": changed,
+            "before": before,
+            "after": after,
+        }))?;
+        if let Some(settings) = &self.settings {
+            settings.save(&config)?;
+        }
+        let reconfigure = config.webhooks != current.config.webhooks
+            || config.event_logging != current.config.event_logging
+            || config.email != current.config.email;
+        crate::i18n::set_locale(config.locale.as_deref().unwrap_or(&crate::i18n::system_locale().unwrap_or_default()));
+        if config.logging != current.config.logging {
+            init_logging(&config.logging);
+        }
+        if reconfigure {
+            self.integrations.configure(&config);
+        }
+        self.uploads.configure(&config.uploads);
+        self.config.replace(ConfigSnapshot {
             config,
-            certificate_authority: Arc::new(Mutex::new(ca)),
-            report_generator: ReportGenerator::new(),
+            report_generator: ReportGenerator::with_template(template),
+            certificate_authority,
+        });
+        Ok(())
+    }
+
+    /// Put every setting back to its default; requires a logged-in administrator
+    pub fn reset_configuration(&self) -> WipeResult2<()> {
+        self.update_configuration(WipeConfiguration::default())
+    }
+
+    /// Enumerate the physical disks attached to this machine
+    pub fn scan_devices(&self) -> WipeResult2<Vec<PhysicalDisk>> {
+        self.scan_devices_as(self.current_operator().as_ref())
+    }
+
+    /// `scan_devices` on behalf of `operator` instead of the logged-in session
+    pub fn scan_devices_as(&self, operator: Option<&OperatorIdentity>) -> WipeResult2<Vec<PhysicalDisk>> {
+        let mut disks = crate::platform::get_physical_disks().map_err(|e| WipeError {
+            code: WipeErrorCode::HardwareError,
+            message: format!("Failed to enumerate disks: {}", e),
+            sector: None,
+        })?;
+        disks.extend(self.test_devices.lock().unwrap().values().map(TestDevice::physical_disk));
+
+        let devices: Vec<serde_json::Value> = disks
+            .iter()
+            .map(|disk| serde_json::json!({
+                "path": disk.path,
+                "model": disk.model,
+                "serial": disk.serial,
+                "size_bytes": disk.size_bytes,
+            }))
+            .collect();
+        self.audit_as(operator, AuditEventKind::DeviceScan, None, serde_json::json!({ "devices": devices }))?;
+        Ok(disks)
+    }
+
+    /// Register a file-backed device of `size_bytes` for integration tests.
+    /// It is listed by `scan_devices` as `DiskKind::TestDevice` and wipes of
+    /// its path write the file, with the full pipeline and a certificate.
+    pub fn create_test_device(&self, size_bytes: u64, backing: TestDeviceBacking) -> WipeResult2<PhysicalDisk> {
+        let device = TestDevice::create(size_bytes, backing).map_err(|e| WipeError {
+            code: WipeErrorCode::InvalidTarget,
+            message: format!("Failed to create test device: {}", e),
+            sector: None,
+        })?;
+        let disk = device.physical_disk();
+        info!(serial = %device.serial, size_bytes = device.size_bytes, device = %disk.path, "Test device added");
+        self.test_devices.lock().unwrap().insert(disk.path.clone(), device);
+        Ok(disk)
+    }
+
+    /// Unregister a test device; a temporary backing file is deleted
+    pub fn remove_test_device(&self, path: &str) -> WipeResult2<()> {
+        self.test_devices.lock().unwrap().remove(path).map(|_| ()).ok_or_else(|| WipeError {
+            code: WipeErrorCode::DriveNotFound,
+            message: format!("No test device at {}", path),
+            sector: None,
         })
     }
 
+    /// Ask a running wipe to stop; it ends with `WipeErrorCode::Cancelled` after
+    /// the block being written
+    pub fn cancel_wipe(&self, operation_id: Uuid) -> WipeResult2<()> {
+        self.cancel_wipe_as(&self.authenticated_operator()?, operation_id)
+    }
+
+    /// `cancel_wipe` on behalf of `operator` instead of the logged-in session
+    pub fn cancel_wipe_as(&self, operator: &OperatorIdentity, operation_id: Uuid) -> WipeResult2<()> {
+        operator.require_sanitize()?;
+        let active_wipes = self.active_wipes.lock().unwrap();
+        let flag = active_wipes.get(&operation_id).ok_or_else(|| WipeError {
+            code: WipeErrorCode::InvalidTarget,
+            message: format!("No running wipe with id {}", operation_id),
+            sector: None,
+        })?;
+        flag.store(true, Ordering::Relaxed);
+        info!(%operation_id, operator = %operator.display_name(), "Cancellation requested");
+        Ok(())
+    }
+
+    /// Change the rate limit and I/O priority of a running wipe; the write loop
+    /// applies them before its next block
+    pub fn update_operation_limits(&self, operation_id: Uuid, limits: OperationLimits) -> WipeResult2<()> {
+        self.update_operation_limits_as(&self.authenticated_operator()?, operation_id, limits)
+    }
+
+    /// `update_operation_limits` on behalf of `operator` instead of the logged-in session
+    pub fn update_operation_limits_as(
+        &self,
+        operator: &OperatorIdentity,
+        operation_id: Uuid,
+        limits: OperationLimits,
+    ) -> WipeResult2<()> {
+        operator.require_sanitize()?;
+        if limits.max_throughput_mbps.is_some_and(|mbps| mbps.is_nan() || mbps <= 0.0) {
+            return Err(WipeError {
+                code: WipeErrorCode::InvalidTarget,
+                message: "max_throughput_mbps must be greater than zero".to_string(),
+                sector: None,
+            });
+        }
+
+        let operation_limits = self.operation_limits.lock().unwrap();
+        let handle = operation_limits.get(&operation_id).ok_or_else(|| WipeError {
+            code: WipeErrorCode::InvalidTarget,
+            message: format!("No running wipe with id {}", operation_id),
+            sector: None,
+        })?;
+        handle.set(limits);
+        info!(
+            %operation_id,
+            operator = %operator.display_name(),
+            max_throughput_mbps = limits.max_throughput_mbps,
+            io_priority = ?limits.io_priority,
+            "Operation limits changed"
+        );
+        Ok(())
+    }
+
+    /// Pause a running wipe at its next block boundary, once what it wrote is
+    /// flushed to the device, and journal where it stopped. Returns the
+    /// checkpoint; a wipe not in an overwrite pass (hardware erase, imaging or
+    /// verification) cannot pause and is left running.
+    pub fn pause_operation(&self, operation_id: Uuid) -> WipeResult2<PauseCheckpoint> {
+        self.pause_operation_as(&self.authenticated_operator()?, operation_id)
+    }
+
+    /// `pause_operation` on behalf of `operator` instead of the logged-in session
+    pub fn pause_operation_as(&self, operator: &OperatorIdentity, operation_id: Uuid) -> WipeResult2<PauseCheckpoint> {
+        self.set_paused(operator, operation_id, true)?;
+        let deadline = Instant::now() + PAUSE_TIMEOUT;
+        let checkpoint = loop {
+            let handle = self.operation_limits.lock().unwrap().get(&operation_id).cloned();
+            let Some(handle) = handle else {
+                return Err(WipeError {
+                    code: WipeErrorCode::InvalidTarget,
+                    message: format!("Wipe {} finished before it could pause", operation_id),
+                    sector: None,
+                });
+            };
+            if let Some(checkpoint) = handle.checkpoint() {
+                break checkpoint;
+            }
+            if Instant::now() >= deadline {
+                handle.set_paused(false);
+                return Err(WipeError {
+                    code: WipeErrorCode::InvalidTarget,
+                    message: format!(
+                        "Wipe {} did not reach a block boundary within {} s; only overwrite passes can pause, so it was left running",
+                        operation_id,
+                        PAUSE_TIMEOUT.as_secs()
+                    ),
+                    sector: None,
+                });
+            }
+            thread::sleep(MAX_THROTTLE_SLEEP);
+        };
+
+        self.journal.lock().unwrap().append(operation_id, JournalEvent::Paused { checkpoint: checkpoint.clone() })?;
+        self.audit_as(Some(operator), AuditEventKind::WipePaused, None, serde_json::json!({
+            "operation_id": operation_id,
+            "pass": checkpoint.pass,
+            "bytes_written": checkpoint.bytes_written,
+            "next_sector": checkpoint.next_sector,
+        }))?;
+        info!(
+            %operation_id,
+            operator = %operator.display_name(),
+            pass = checkpoint.pass,
+            total_passes = checkpoint.total_passes,
+            "Wipe paused"
+        );
+        Ok(checkpoint)
+    }
+
+    /// Continue a wipe paused with `pause_operation` from its checkpoint
+    pub fn resume_operation(&self, operation_id: Uuid) -> WipeResult2<()> {
+        self.resume_operation_as(&self.authenticated_operator()?, operation_id)
+    }
+
+    /// `resume_operation` on behalf of `operator` instead of the logged-in session
+    pub fn resume_operation_as(&self, operator: &OperatorIdentity, operation_id: Uuid) -> WipeResult2<()> {
+        operator.require_sanitize()?;
+        let handle = self.operation_limits.lock().unwrap().get(&operation_id).cloned();
+        if !handle.as_ref().is_some_and(|handle| handle.is_pause_requested()) {
+            // A preempted wipe continues on its own once the high-priority wipe finishes
+            let message = if handle.as_ref().is_some_and(|handle| handle.is_preempted()) {
+                format!("Wipe {} is held by priority preemption, not paused; it continues when the high-priority wipe finishes", operation_id)
+            } else {
+                format!("No paused wipe with id {}", operation_id)
+            };
+            return Err(WipeError {
+                code: WipeErrorCode::InvalidTarget,
+                message,
+                sector: None,
+            });
+        }
+        let checkpoint = handle.and_then(|handle| handle.checkpoint());
+        self.set_paused(operator, operation_id, false)?;
+        self.journal.lock().unwrap().append(operation_id, JournalEvent::Resumed)?;
+        self.audit_as(Some(operator), AuditEventKind::WipeResumed, None, serde_json::json!({
+            "operation_id": operation_id,
+            "pass": checkpoint.as_ref().map(|checkpoint| checkpoint.pass),
+            "next_sector": checkpoint.and_then(|checkpoint| checkpoint.next_sector),
+        }))?;
+        info!(%operation_id, operator = %operator.display_name(), "Wipe resumed");
+        Ok(())
+    }
+
+    fn set_paused(&self, operator: &OperatorIdentity, operation_id: Uuid, paused: bool) -> WipeResult2<()> {
+        operator.require_sanitize()?;
+        let operation_limits = self.operation_limits.lock().unwrap();
+        let handle = operation_limits.get(&operation_id).ok_or_else(|| WipeError {
+            code: WipeErrorCode::InvalidTarget,
+            message: format!("No running wipe with id {}", operation_id),
+            sector: None,
+        })?;
+        handle.set_paused(paused);
+        Ok(())
+    }
+
+    /// Approve a wipe as the second operator. The approval may be given
+    /// before the wipe is started or while it waits for one.
+    pub fn approve_operation(&self, operation_id: Uuid, approver: &OperatorIdentity) -> WipeResult2<Approval> {
+        let approval = self.approvals.lock().unwrap().approve(operation_id, approver)?;
+        self.audit_as(Some(approver), AuditEventKind::WipeApproved, None, serde_json::json!({
+            "operation_id": operation_id,
+        }))?;
+        info!(%operation_id, approver = %approval.approver, "Wipe approved");
+        Ok(approval)
+    }
+
+    /// Wipes waiting for a second operator's approval
+    pub fn pending_approvals(&self) -> Vec<PendingApproval> {
+        self.approvals.lock().unwrap().pending()
+    }
+
+    /// The phrase the operator must type to confirm `request`, if the safety
+    /// policy asks for one
+    pub fn confirmation_phrase(&self, request: &WipeRequest) -> Option<String> {
+        self.current().config.safety.expected_confirmation(request)
+    }
+
+    /// Seed of the random passes of a finished wipe, for regenerating the
+    /// written data; only stored when `capture_pattern_seed` is enabled
+    pub fn pattern_seed(&self, operation_id: Uuid) -> WipeResult2<PatternSeed> {
+        self.authorize_sanitize()?;
+        let sealed = self.history.lock().unwrap()
+            .get(operation_id)
+            .and_then(|record| record.pattern_seed.clone())
+            .ok_or_else(|| WipeError {
+                code: WipeErrorCode::InvalidTarget,
+                message: format!("No pattern seed recorded for operation {}", operation_id),
+                sector: None,
+            })?;
+        self.seed_vault()?.unseal(&sealed, operation_id).map_err(|e| WipeError {
+            code: WipeErrorCode::VerificationFailed,
+            message: e.to_string(),
+            sector: None,
+        })
+    }
+
+    /// Read the area a finished wipe overwrote back from the device, still at
+    /// the path it was wiped at, and compare it with what the last pass wrote.
+    /// A random last pass is regenerated from the sealed seed, so this needs
+    /// `capture_pattern_seed`. `policy` defaults to the configured one.
+    pub fn reverify_operation(&self, operation_id: Uuid, policy: Option<VerificationPolicy>) -> WipeResult2<VerificationReport> {
+        let operator = self.authorize_sanitize()?;
+        let record = self.history.lock().unwrap().get(operation_id).cloned().ok_or_else(|| WipeError {
+            code: WipeErrorCode::InvalidTarget,
+            message: format!("No operation with id {}", operation_id),
+            sector: None,
+        })?;
+        let Some((result, written)) = record.result.as_ref().and_then(|result| Some((result, result.written_passes.as_ref()?))) else {
+            return Err(WipeError {
+                code: WipeErrorCode::InvalidTarget,
+                message: format!("Operation {} has no record of the passes it wrote", operation_id),
+                sector: None,
+            });
+        };
+        let seed = match written.patterns.last() {
+            Some(OverwritePattern::Random) => Some(self.pattern_seed(operation_id)?),
+            _ => None,
+        };
+        let expected = written.final_content(seed.as_ref()).ok_or_else(|| WipeError {
+            code: WipeErrorCode::VerificationFailed,
+            message: format!("The last pass of operation {} no longer reads back: the range was deallocated", operation_id),
+            sector: None,
+        })?;
+
+        let _device_lock = self.device_locks.acquire(&record.target_path, operation_id, DeviceActivity::Verification)?;
+        let mut device = std::fs::File::open(&record.target_path).map_err(|e| WipeError {
+            code: WipeErrorCode::AccessDenied,
+            message: format!("Failed to open {} for verification: {}", record.target_path, e),
+            sector: None,
+        })?;
+        let policy = policy.unwrap_or_else(|| self.current().config.verification_policy.clone());
+        let report = crate::verification::verify_device(
+            &mut device,
+            written.start_sector * 512,
+            written.sector_count * 512,
+            &expected,
+            &policy,
+            &result.bad_sectors,
+            |_, _| {},
+        )
+        .map_err(|e| WipeError {
+            code: WipeErrorCode::VerificationFailed,
+            message: format!("Verification failed: {}", e),
+            sector: None,
+        })?;
+
+        self.audit_as(Some(&operator), AuditEventKind::WipeReverified, Some(&record.target_path), serde_json::json!({
+            "operation_id": operation_id,
+            "policy": policy.description(),
+            "pattern": written.patterns.last().map(OverwritePattern::description),
+            "bytes_verified": report.bytes_verified,
+            "passed": report.passed,
+        }))?;
+        Ok(report)
+    }
+
+    /// `relative` under the engine's data directory
+    fn data_path(&self, relative: &str) -> PathBuf {
+        self.data_dir.join(relative)
+    }
+
+    fn seed_vault(&self) -> WipeResult2<SeedVault> {
+        SeedVault::open(self.data_path(DEFAULT_SEED_KEY_PATH)).map_err(|e| WipeError {
+            code: WipeErrorCode::UnknownError,
+            message: format!("Failed to open pattern seed key: {}", e),
+            sector: None,
+        })
+    }
+
+    /// Log in with a local account; destructive calls require a logged-in operator
+    pub fn login(&self, username: &str, password: &str) -> WipeResult2<OperatorIdentity> {
+        let identity = self.authenticator.lock().unwrap().login(username, password)?;
+        *self.operator.lock().unwrap() = Some(identity.clone());
+        self.audit(AuditEventKind::OperatorLogin, None, serde_json::json!({ "method": identity.method }))?;
+        Ok(identity)
+    }
+
+    /// Log in with an API token issued by an administrator
+    pub fn login_with_token(&self, token: &str) -> WipeResult2<OperatorIdentity> {
+        let identity = self.authenticator.lock().unwrap().login_with_token(token)?;
+        *self.operator.lock().unwrap() = Some(identity.clone());
+        self.audit(AuditEventKind::OperatorLogin, None, serde_json::json!({ "method": identity.method }))?;
+        Ok(identity)
+    }
+
+    /// Check an API token without changing the logged-in session; used by
+    /// front ends that authenticate every request
+    pub fn authenticate_token(&self, token: &str) -> WipeResult2<OperatorIdentity> {
+        self.authenticator.lock().unwrap().login_with_token(token)
+    }
+
+    pub fn logout(&self) {
+        if self.current_operator().is_some() {
+            let _ = self.audit(AuditEventKind::OperatorLogout, None, serde_json::Value::Null);
+        }
+        *self.operator.lock().unwrap() = None;
+    }
+
+    pub fn current_operator(&self) -> Option<OperatorIdentity> {
+        self.operator.lock().unwrap().clone()
+    }
+
+    /// Issue an API token for `username`; requires a logged-in administrator
+    pub fn issue_operator_token(&self, username: &str, label: &str, valid_for: Option<chrono::Duration>) -> WipeResult2<String> {
+        let issuer = self.authenticated_operator()?;
+        self.authenticator.lock().unwrap().issue_token(&issuer, username, label, valid_for)
+    }
+
+    fn authenticated_operator(&self) -> WipeResult2<OperatorIdentity> {
+        self.current_operator().ok_or_else(|| WipeError {
+            code: WipeErrorCode::NotAuthenticated,
+            message: "Log in as an operator first".to_string(),
+            sector: None,
+        })
+    }
+
+    /// The logged-in operator, if allowed to run destructive operations
+    fn authorize_sanitize(&self) -> WipeResult2<OperatorIdentity> {
+        let operator = self.authenticated_operator()?;
+        operator.require_sanitize()?;
+        Ok(operator)
+    }
+
     pub fn execute_secure_wipe(
         &self,
         target_path: String,
@@ -41,71 +705,402 @@ impl WipeEngine {
         verify_erasure: bool,
         generate_certificate: bool,
     ) -> WipeResult2<(WipeResult, Option<String>)> {
-        // Create wipe request
-        let request = WipeRequest {
+        self.execute_scoped_wipe(
+            target_path,
+            target_type,
+            WipeScope::WholeDevice,
+            standard,
+            verify_erasure,
+            generate_certificate,
+        )
+    }
+
+    /// Wipe with the passes, verification and certificate wording of a compliance profile
+    pub fn execute_profile_wipe(
+        &self,
+        target_path: String,
+        target_type: TargetType,
+        scope: WipeScope,
+        profile: ComplianceProfile,
+        generate_certificate: bool,
+    ) -> WipeResult2<(WipeResult, Option<String>)> {
+        let operator = self.authenticated_operator()?;
+        let mut request = Self::build_request(target_path, target_type, scope, profile.standard(), true, generate_certificate);
+        request.profile = Some(profile);
+        self.execute_request_as(&operator, request)
+    }
+
+    /// Wipe with the passes of a registered recipe
+    pub fn execute_recipe_wipe(
+        &self,
+        target_path: String,
+        target_type: TargetType,
+        scope: WipeScope,
+        recipe_name: &str,
+        generate_certificate: bool,
+    ) -> WipeResult2<(WipeResult, Option<String>)> {
+        let operator = self.authenticated_operator()?;
+        let recipe = self.recipe(recipe_name)?;
+        let mut request = Self::build_request(
+            target_path,
+            target_type,
+            scope,
+            SanitizationStandard::NIST_SP_800_88_R1,
+            recipe.verify,
+            generate_certificate,
+        );
+        request.recipe = Some(recipe);
+        self.execute_request_as(&operator, request)
+    }
+
+    /// Validate and register a recipe so it can be run by name
+    pub fn register_recipe(&self, recipe: WipeRecipe) -> WipeResult2<()> {
+        self.authorize_sanitize()?;
+        self.recipes.lock().unwrap().register(recipe)
+    }
+
+    pub fn recipe(&self, name: &str) -> WipeResult2<WipeRecipe> {
+        self.recipes.lock().unwrap().get(name).cloned().ok_or_else(|| WipeError {
+            code: WipeErrorCode::InvalidPattern,
+            message: format!("No recipe named '{}'", name),
+            sector: None,
+        })
+    }
+
+    pub fn recipes(&self) -> Vec<WipeRecipe> {
+        self.recipes.lock().unwrap().list()
+    }
+
+    /// Profile front ends should preselect, from `default_profile` in the configuration
+    pub fn default_profile(&self) -> Option<ComplianceProfile> {
+        self.current().config.default_profile
+    }
+
+    /// Wipe a single partition or sector range of the target instead of the whole device.
+    /// Ranges overlapping a system partition (EFI, BIOS boot, active) are refused.
+    pub fn execute_scoped_wipe(
+        &self,
+        target_path: String,
+        target_type: TargetType,
+        scope: WipeScope,
+        standard: SanitizationStandard,
+        verify_erasure: bool,
+        generate_certificate: bool,
+    ) -> WipeResult2<(WipeResult, Option<String>)> {
+        let operator = self.authenticated_operator()?;
+        let request = Self::build_request(target_path, target_type, scope, standard, verify_erasure, generate_certificate);
+        self.execute_request_as(&operator, request)
+    }
+
+    /// A wipe request with the pass count of `standard`; pass it to
+    /// `execute_request_as` when the operation id is needed before the wipe starts
+    pub fn build_request(
+        target_path: String,
+        target_type: TargetType,
+        scope: WipeScope,
+        standard: SanitizationStandard,
+        verify_erasure: bool,
+        generate_certificate: bool,
+    ) -> WipeRequest {
+        WipeRequest {
             id: Uuid::new_v4(),
             target_path,
             target_type,
+            scope,
             standard,
-            passes: match standard {
-                SanitizationStandard::NIST_SP_800_88_R1 => 1,
-                SanitizationStandard::DoD_5220_22_M => 3,
-                SanitizationStandard::AFSSI_5020 => 3,
-                SanitizationStandard::BSI_2011_VS => 2,
-                SanitizationStandard::NAVSO_P_5239_26 => 3,
-            },
+            passes: WipeSpec::from(standard).passes,
             verify_erasure,
             generate_certificate,
             timestamp: Utc::now(),
+            operator: None,
+            profile: None,
+            recipe: None,
+            confirmation: None,
+            simulate: false,
+            skip_trim: false,
+            resume: None,
+            security_state: None,
+            priority: WipePriority::Normal,
+        }
+    }
+
+    /// Run `request` on behalf of `operator`, blocking until the wipe ends
+    pub fn execute_request_as(
+        &self,
+        operator: &OperatorIdentity,
+        mut request: WipeRequest,
+    ) -> WipeResult2<(WipeResult, Option<String>)> {
+        operator.require_sanitize()?;
+        let _span = info_span!("wipe", operation_id = %request.id, device = %request.target_path).entered();
+        // Settings changed while this wipe runs apply to the next one
+        let current = self.current();
+        request.operator = Some(operator.display_name());
+        // A profile fixes the passes and always verifies
+        if let Some(profile) = request.profile {
+            request.standard = profile.standard();
+            request.verify_erasure = true;
+        }
+        if let Some(ref recipe) = request.recipe {
+            request.verify_erasure = recipe.verify;
+        }
+        request.passes = request.wipe_spec().passes;
+        request.simulate |= current.config.simulation.enabled;
+        request.verify_erasure |= current.config.auto_verify;
+        let generate_certificate = request.generate_certificate;
+        // Held until the certificate is written; a refused wipe never started, so leaves no record
+        let _device_lock = self.device_locks.acquire(&request.target_path, request.id, DeviceActivity::Wipe)?;
+
+        info!(
+            scope = %request.scope.description(),
+            standard = ?request.standard,
+            profile = request.profile.map(|profile| profile.name()),
+            recipe = request.recipe.as_ref().map(|recipe| format!("{} ({})", recipe.name, &recipe.fingerprint[..16])),
+            simulate = request.simulate,
+            operator = %operator.display_name(),
+            "Starting secure wipe operation"
+        );
+
+        self.metrics.operation_queued(&request);
+
+        // Protected devices are refused here so every front end is bound by the policy
+        if let Err(error) = current.config.target_policy.enforce(&request.target_path) {
+            self.record_wipe_refusal(operator, &request, &error);
+            return Err(error);
+        }
+
+        // Registered before the safety checks so the wipe can be cancelled while it waits
+        let request_id = request.id;
+        let cancel_flag = Arc::new(AtomicBool::new(false));
+        self.active_wipes.lock().unwrap().insert(request_id, cancel_flag.clone());
+        if let Err(error) = self.pass_safety_checks(operator, &current.config.safety, &request, &cancel_flag) {
+            self.approvals.lock().unwrap().withdraw(request_id);
+            self.active_wipes.lock().unwrap().remove(&request_id);
+            self.record_wipe_refusal(operator, &request, &error);
+            return Err(error);
+        }
+
+        // Filesystems on the target must not stay mounted while their blocks
+        // are overwritten; simulations and test devices leave them alone and write a file
+        let test_device = self.test_devices.lock().unwrap().get(&request.target_path).map(SimulatedDevice::test_device);
+        let (volumes, simulated_device) = if request.simulate {
+            match SimulatedDevice::attach(&current.config.simulation, request_id, &request.target_path) {
+                Ok(device) => (None, Some(device)),
+                Err(e) => {
+                    let error = WipeError {
+                        code: WipeErrorCode::UnknownError,
+                        message: format!("Failed to create simulated device: {}", e),
+                        sector: None,
+                    };
+                    self.active_wipes.lock().unwrap().remove(&request_id);
+                    self.record_wipe_refusal(operator, &request, &error);
+                    return Err(error);
+                }
+            }
+        } else if test_device.is_some() {
+            (None, test_device)
+        } else {
+            (Some(release_volumes(&request.target_path)), None)
+        };
+        if let Some(ref volumes) = volumes
+            && !volumes.all_released()
+            && current.config.require_volume_release
+        {
+            let error = WipeError {
+                code: WipeErrorCode::VolumeInUse,
+                message: format!("Volumes could not be released: {}", volumes.failure_summary()),
+                sector: None,
+            };
+            self.active_wipes.lock().unwrap().remove(&request_id);
+            self.record_wipe_refusal(operator, &request, &error);
+            return Err(error);
+        }
+
+        // Recorded only once the policy, safety checks and volume release have
+        // passed, so a refused wipe never shows as started in the history or audit log
+        let timeline = match self.record_wipe_start(operator, &request) {
+            Ok(timeline) => timeline,
+            Err(error) => {
+                self.active_wipes.lock().unwrap().remove(&request_id);
+                self.record_wipe_failure(operator, &request, &error);
+                return Err(error);
+            }
         };
 
-        println!("Starting secure wipe operation");
-        println!("Request ID: {}", request.id);
-        println!("Target: {}", request.target_path);
-        println!("Standard: {:?}", request.standard);
+        // Journaled before the first write, so the wipe is known after a power loss;
+        // simulated wipes write a throwaway image and have nothing to recover
+        if !request.simulate
+            && let Err(error) = self.journal.lock().unwrap().append(request_id, JournalEvent::Started { request: Box::new(request.clone()) })
+        {
+            self.active_wipes.lock().unwrap().remove(&request_id);
+            self.record_wipe_failure(operator, &request, &error);
+            return Err(error);
+        }
 
         // Create progress channel
         let (progress_tx, progress_rx) = mpsc::channel::<WipeProgress>();
         
         // Create sanitizer with progress reporting
-        let sanitizer = SecureSanitizer::new(self.config.clone())
-            .with_progress_reporting(progress_tx);
+        let limits = Arc::new(LimitsHandle::new(current.config.operation_limits()));
+        let mut config = current.config.clone();
+        if let Some(profile) = request.profile {
+            config.verification_policy = profile.verification_policy();
+        }
+        if let Some(policy) = request.recipe.as_ref().and_then(|recipe| recipe.verification.clone()) {
+            config.verification_policy = policy;
+        }
+        let mut sanitizer = SecureSanitizer::new(config)
+            .with_progress_reporting(progress_tx)
+            .with_cancel_flag(cancel_flag.clone())
+            .with_limits(limits.clone())
+            .with_timeline(timeline.clone());
+        if let Some(device) = simulated_device {
+            sanitizer = sanitizer.with_simulated_device(device);
+        }
 
         // Start progress monitoring in a separate thread
-        let request_id = request.id;
+        let history = self.history.clone();
+        let journal = self.journal.clone();
+        let subscribers = self.progress_subscribers.clone();
+        let metrics = self.metrics.clone();
+        let target_path = request.target_path.clone();
+        let simulated = request.simulate;
+        let span = tracing::Span::current();
         thread::spawn(move || {
+            let _span = span.entered();
+            let mut last_snapshot: Option<(u32, u64)> = None;
+            let mut last_pass: Option<(u32, u32)> = None;
             while let Ok(progress) = progress_rx.recv() {
-                println!(
-                    "Progress: {:.1}% - Pass {}/{} - {} - {} sectors processed",
-                    progress.percentage,
-                    progress.current_pass,
-                    progress.total_passes,
+                metrics.record_progress(&progress);
+                // A pass is on the device once the next one (or verification) starts
+                if !simulated
+                    && let Some((pass, total_passes)) = last_pass
+                    && progress.current_pass > pass
+                    && pass > 0
+                    && let Ok(mut journal) = journal.lock()
+                {
+                    let _ = journal.append(progress.request_id, JournalEvent::PassCompleted { pass, total_passes });
+                }
+                // Sample the drive temperature once per pass
+                if !simulated && last_pass.map(|(pass, _)| pass) != Some(progress.current_pass) {
+                    last_pass = Some((progress.current_pass, progress.total_passes));
+                    if let Some(celsius) = SmartInterface::new(&target_path).read_health().ok().and_then(|h| h.temperature_celsius) {
+                        metrics.record_temperature(&target_path, celsius);
+                    }
+                }
+
+                // Persist a snapshot on every pass change and every 5% of progress
+                let bucket = (progress.current_pass, (progress.percentage / 5.0) as u64);
+                if last_snapshot != Some(bucket) {
+                    last_snapshot = Some(bucket);
+                    if let Ok(mut history) = history.lock() {
+                        let _ = history.record_progress(&progress);
+                    }
+                }
+                subscribers.lock().unwrap().retain(|subscriber| subscriber.send(progress.clone()).is_ok());
+
+                let eta = match (progress.estimated_completion, progress.estimated_completion_earliest, progress.estimated_completion_latest) {
+                    (Some(expected), Some(earliest), Some(latest)) => format!(
+                        " - ETA {} ({}-{})",
+                        expected.with_timezone(&chrono::Local).format("%H:%M"),
+                        earliest.with_timezone(&chrono::Local).format("%H:%M"),
+                        latest.with_timezone(&chrono::Local).format("%H:%M"),
+                    ),
+                    _ => String::new(),
+                };
+                info!(
+                    percentage = format_args!("{:.1}", progress.percentage),
+                    pass = progress.current_pass,
+                    total_passes = progress.total_passes,
+                    sectors_processed = progress.sectors_processed,
+                    "{}{}",
                     progress.current_operation,
-                    progress.sectors_processed
+                    eta
                 );
             }
         });
 
         // Execute the wipe
-        let wipe_result = sanitizer.execute_wipe(request.clone())?;
+        self.operation_limits.lock().unwrap().insert(request_id, limits.clone());
+        let held = self.preemptions.register(
+            request_id,
+            request.priority,
+            request.uses_firmware_erase(),
+            current.config.schedule.preempts(&request),
+            limits,
+        );
+        if !held.is_empty() {
+            info!(priority = ?request.priority, held = ?held, "Lower-priority overwrites held for a high-priority erase");
+        }
+        self.metrics.operation_started(request_id);
+        // Published only once the policy, safety checks and volume release have
+        // passed, so integrations never see a start for a wipe that was refused
+        self.integrations.publish(LifecycleEvent::new(LifecycleEventKind::WipeStarted, &request));
+        let outcome = sanitizer.execute_wipe(request.clone());
+        if !request.simulate {
+            let _ = self.journal.lock().unwrap().append(request_id, JournalEvent::Finished);
+        }
+        // Deletes the image of a simulated wipe
+        drop(sanitizer);
+        self.active_wipes.lock().unwrap().remove(&request_id);
+        self.operation_limits.lock().unwrap().remove(&request_id);
+        self.preemptions.unregister(request_id);
+        let mut wipe_result = match outcome {
+            Ok(result) => result,
+            Err(e) => {
+                self.record_wipe_failure(operator, &request, &e);
+                return Err(e);
+            }
+        };
+        // Volume locks are only needed while the device is being written
+        drop(volumes);
+        self.metrics.operation_finished(&wipe_result);
+        if wipe_result.success {
+            wipe_result.throughput = self.record_throughput(&request.target_path, &wipe_result);
+        }
+        self.history.lock().unwrap().record_result(&wipe_result)?;
+        if let (true, Some(seed)) = (current.config.capture_pattern_seed, &wipe_result.pattern_seed) {
+            let sealed = self.seed_vault()?.seal(seed, request_id).map_err(|e| WipeError {
+                code: WipeErrorCode::UnknownError,
+                message: format!("Failed to seal pattern seed: {}", e),
+                sector: None,
+            })?;
+            self.history.lock().unwrap().attach_pattern_seed(request_id, sealed)?;
+        }
+        self.audit_as(Some(operator), AuditEventKind::WipeFinished, Some(&request.target_path), serde_json::json!({
+            "operation_id": request_id,
+            "success": wipe_result.success,
+            "serial": wipe_result.drive_geometry.serial,
+            "sectors_wiped": wipe_result.sectors_wiped,
+            "passes_completed": wipe_result.passes_completed,
+            "error": wipe_result.error_message,
+        }))?;
 
         // Generate certificate if requested
+        let mut certificate_id = None;
         let certificate_path = if generate_certificate && wipe_result.success {
-            let mut ca = self.certificate_authority.lock().unwrap();
-            let certificate = ca.generate_certificate(&request, &wipe_result)?;
+            let mut ca = current.certificate_authority.lock().unwrap();
+            let template = current.report_generator.template().map(|template| template.reference());
+            let certificate = ca.generate_certificate(&request, &wipe_result, &operator.display_name(), template)?;
             
             // Generate timestamp for unique filenames
             let timestamp = Utc::now().format("%Y%m%d_%H%M%S");
-            let cert_filename = format!("reports/certificate_{}_{}.pdf", 
-                                      wipe_result.drive_geometry.serial, timestamp);
-            let json_filename = format!("reports/audit_{}_{}.json", 
-                                       wipe_result.drive_geometry.serial, timestamp);
+            let reports_dir = self.data_path(DEFAULT_REPORTS_DIR);
+            let report_path = |name: String| reports_dir.join(name).to_string_lossy().to_string();
+            let cert_filename = report_path(format!("certificate_{}_{}.pdf",
+                                      wipe_result.drive_geometry.serial, timestamp));
+            let json_filename = report_path(format!("audit_{}_{}.json",
+                                       wipe_result.drive_geometry.serial, timestamp));
+            let signed_filename = report_path(format!("certificate_{}_{}.json",
+                                         wipe_result.drive_geometry.serial, timestamp));
 
             // Generate PDF certificate
-            self.report_generator.generate_pdf_report(&certificate, &cert_filename)?;
+            current.report_generator.generate_pdf_report(&certificate, &cert_filename)?;
+            
+            // Generate signed JSON certificate for offline verification
+            current.report_generator.generate_certificate_json(&certificate, &signed_filename)?;
             
             // Generate JSON audit report
-            self.report_generator.generate_json_report(
+            current.report_generator.generate_json_report(
                 &certificate, 
                 &request, 
                 &wipe_result, 
@@ -113,19 +1108,786 @@ impl WipeEngine {
             )?;
 
             // Save updated CA (incremented counter)
-            ca.save_to_file("certificates/ca.json")?;
+            ca.save_to_file(self.data_path(CA_PATH))?;
+
+            info!(
+                certificate = %cert_filename,
+                signed_certificate = %signed_filename,
+                audit_report = %json_filename,
+                "Certificate generated"
+            );
 
-            println!("Certificate generated: {}", cert_filename);
-            println!("Audit report generated: {}", json_filename);
+            self.history.lock().unwrap().attach_report(request_id, &certificate.certificate_id.to_string())?;
+            certificate_id = Some(certificate.certificate_id.to_string());
+            timeline.record(TimelineEventKind::CertificateIssued { certificate_id: certificate.certificate_id.to_string() });
+            let issued = [
+                PathBuf::from(&cert_filename),
+                PathBuf::from(&signed_filename),
+                PathBuf::from(&json_filename),
+            ];
+            self.uploads.enqueue(request_id, &issued)?;
+            // The certificate is already issued; a failed backup shows in `get_backup_status`
+            if let Some(backup) = &current.config.backup_reports
+                && let Err(e) = self.backups.store(backup, request_id, &issued)
+            {
+                warn!(error = %e.message, "Report backup failed");
+            }
 
             Some(cert_filename)
         } else {
             None
         };
+        self.finish_timeline(request_id, TimelineEventKind::Finished { success: wipe_result.success });
+
+        let verification = match (&wipe_result.verification_report, &wipe_result.validation_result) {
+            (Some(report), _) => Some((report.passed, Some(report.bytes_verified))),
+            (None, Some(validation)) => Some((validation.pattern_matches && validation.checksum_valid, None)),
+            (None, None) => None,
+        };
+        if let Some((passed, bytes_verified)) = verification {
+            self.integrations.publish(LifecycleEvent {
+                serial_number: Some(wipe_result.drive_geometry.serial.clone()),
+                verification_passed: Some(passed),
+                bytes_verified,
+                ..LifecycleEvent::new(LifecycleEventKind::VerificationCompleted, &request)
+            });
+        }
+
+        let kind = if wipe_result.success { LifecycleEventKind::WipeCompleted } else { LifecycleEventKind::WipeFailed };
+        self.integrations.publish(LifecycleEvent {
+            serial_number: Some(wipe_result.drive_geometry.serial.clone()),
+            certificate_id,
+            certificate_path: certificate_path.clone(),
+            error: wipe_result.error_message.clone(),
+            ..LifecycleEvent::new(kind, &request)
+        });
 
         Ok((wipe_result, certificate_path))
     }
 
+    /// Confirmation, second approval and abort window, in that order; nothing
+    /// has been written when one of them stops the wipe
+    fn pass_safety_checks(
+        &self,
+        operator: &OperatorIdentity,
+        safety: &SafetyPolicy,
+        request: &WipeRequest,
+        cancel_flag: &AtomicBool,
+    ) -> WipeResult2<()> {
+        safety.check_confirmation(request)?;
+
+        let cancelled = || WipeError {
+            code: WipeErrorCode::Cancelled,
+            message: "Wipe cancelled before writing started".to_string(),
+            sector: None,
+        };
+
+        if safety.require_second_approver {
+            self.approvals.lock().unwrap().await_approval(request, operator);
+            info!("Waiting for a second operator to approve");
+            let deadline = Instant::now() + Duration::from_secs(safety.approval_timeout_seconds);
+            loop {
+                if let Some(approval) = self.approvals.lock().unwrap().take_approval(request.id, operator)? {
+                    info!(approver = %approval.approver, "Wipe approved");
+                    break;
+                }
+                if cancel_flag.load(Ordering::Relaxed) {
+                    return Err(cancelled());
+                }
+                if Instant::now() >= deadline {
+                    return Err(WipeError {
+                        code: WipeErrorCode::PolicyDenied,
+                        message: format!("No second operator approved the wipe within {} s", safety.approval_timeout_seconds),
+                        sector: None,
+                    });
+                }
+                thread::sleep(Duration::from_millis(250));
+            }
+        }
+
+        if safety.abort_window_seconds > 0 {
+            info!(seconds = safety.abort_window_seconds, "Writing starts after the abort window; cancel now to abort");
+            let deadline = Instant::now() + Duration::from_secs(safety.abort_window_seconds);
+            while Instant::now() < deadline {
+                if cancel_flag.load(Ordering::Relaxed) {
+                    return Err(cancelled());
+                }
+                thread::sleep(Duration::from_millis(250));
+            }
+        }
+        if cancel_flag.load(Ordering::Relaxed) {
+            return Err(cancelled());
+        }
+        Ok(())
+    }
+
+    /// Mark a wipe as failed (or cancelled) in the history and the audit log
+    fn record_wipe_start(&self, operator: &OperatorIdentity, request: &WipeRequest) -> WipeResult2<Arc<OperationTimeline>> {
+        self.history.lock().unwrap().record_started(request)?;
+        let timeline = Arc::new(OperationTimeline::new());
+        timeline.record(TimelineEventKind::Started {
+            target_path: request.target_path.clone(),
+            operator: operator.display_name(),
+        });
+        self.timelines.lock().unwrap().insert(request.id, timeline.clone());
+        self.audit_as(Some(operator), AuditEventKind::WipeStarted, Some(&request.target_path), serde_json::json!({
+            "operation_id": request.id,
+            "scope": request.scope.description(),
+            "standard": request.standard,
+            "profile": request.profile,
+            "recipe": request.recipe.as_ref().map(|recipe| &recipe.fingerprint),
+            "passes": request.passes,
+            "simulated": request.simulate,
+        }))?;
+        Ok(timeline)
+    }
+
+    /// A wipe stopped before it started leaves no history record, only its own audit entry
+    fn record_wipe_refusal(&self, operator: &OperatorIdentity, request: &WipeRequest, error: &WipeError) {
+        warn!(code = ?error.code, error = %error.message, "Wipe refused before writing started");
+        self.metrics.operation_failed(request.id, &error.code);
+        let _ = self.audit_as(Some(operator), AuditEventKind::WipeRefused, Some(&request.target_path), serde_json::json!({
+            "operation_id": request.id,
+            "code": format!("{:?}", error.code),
+            "message": error.message,
+        }));
+
+        let kind = match error.code {
+            WipeErrorCode::Cancelled => LifecycleEventKind::WipeCancelled,
+            _ => LifecycleEventKind::WipeFailed,
+        };
+        self.integrations.publish(LifecycleEvent {
+            error: Some(error.to_string()),
+            ..LifecycleEvent::new(kind, request)
+        });
+    }
+
+    fn record_wipe_failure(&self, operator: &OperatorIdentity, request: &WipeRequest, error: &WipeError) {
+        let _ = self.history.lock().unwrap().record_failure(request.id, error);
+        self.finish_timeline(request.id, TimelineEventKind::Failed {
+            code: format!("{:?}", error.code),
+            error: error.message.clone(),
+        });
+        self.metrics.operation_failed(request.id, &error.code);
+        let kind = match error.code {
+            WipeErrorCode::Cancelled => AuditEventKind::WipeCancelled,
+            _ => AuditEventKind::WipeFailed,
+        };
+        let _ = self.audit_as(Some(operator), kind, Some(&request.target_path), serde_json::json!({
+            "operation_id": request.id,
+            "code": format!("{:?}", error.code),
+            "message": error.message,
+            "sector": error.sector,
+        }));
+
+        let kind = match error.code {
+            WipeErrorCode::Cancelled => LifecycleEventKind::WipeCancelled,
+            _ => LifecycleEventKind::WipeFailed,
+        };
+        self.integrations.publish(LifecycleEvent {
+            error: Some(error.to_string()),
+            ..LifecycleEvent::new(kind, request)
+        });
+    }
+
+    /// Close the timeline of a wipe with how it ended and store it with the record
+    fn finish_timeline(&self, operation_id: Uuid, ending: TimelineEventKind) {
+        let Some(timeline) = self.timelines.lock().unwrap().remove(&operation_id) else {
+            return;
+        };
+        timeline.record(ending);
+        let _ = self.history.lock().unwrap().record_events(operation_id, timeline.events());
+    }
+
+    /// Queue `request` to start at `not_before`, or in the first maintenance
+    /// window after it. The target policy and the confirmation are checked
+    /// now, so a job that could never run is refused while the operator is
+    /// still there.
+    pub fn schedule_wipe_as(
+        &self,
+        operator: &OperatorIdentity,
+        mut request: WipeRequest,
+        not_before: DateTime<Utc>,
+    ) -> WipeResult2<ScheduledJob> {
+        operator.require_sanitize()?;
+        let current = self.current();
+        current.config.target_policy.enforce(&request.target_path)?;
+        current.config.safety.check_confirmation(&request)?;
+        request.operator = Some(operator.display_name());
+
+        let job = ScheduledJob {
+            not_before,
+            scheduled_at: Utc::now(),
+            scheduled_by: operator.display_name(),
+            status: JobStatus::Pending,
+            error_message: None,
+            request,
+        };
+        self.schedule.lock().unwrap().add(job.clone())?;
+        self.audit_as(Some(operator), AuditEventKind::WipeScheduled, Some(&job.request.target_path), serde_json::json!({
+            "operation_id": job.id(),
+            "not_before": job.not_before,
+        }))?;
+        Ok(job)
+    }
+
+    /// Maintenance windows scheduled wipes run in
+    pub fn schedule_config(&self) -> crate::core::ScheduleConfig {
+        self.current().config.schedule.clone()
+    }
+
+    /// Scheduled wipes, soonest first
+    pub fn scheduled_jobs(&self) -> Vec<ScheduledJob> {
+        self.schedule.lock().unwrap().list()
+    }
+
+    /// Drop a scheduled wipe that has not started yet
+    pub fn cancel_scheduled_as(&self, operator: &OperatorIdentity, job_id: Uuid) -> WipeResult2<()> {
+        operator.require_sanitize()?;
+        let mut schedule = self.schedule.lock().unwrap();
+        match schedule.get(job_id).map(|job| job.status) {
+            Some(JobStatus::Pending) => schedule.set_status(job_id, JobStatus::Cancelled, None),
+            Some(_) => Err(WipeError {
+                code: WipeErrorCode::InvalidTarget,
+                message: format!("Scheduled wipe {} has already started", job_id),
+                sector: None,
+            }),
+            None => Err(WipeError {
+                code: WipeErrorCode::InvalidTarget,
+                message: format!("No scheduled wipe with id {}", job_id),
+                sector: None,
+            }),
+        }
+    }
+
+    /// Preview of `wipe_all_non_system`: every attached drive, sorted into the
+    /// ones that would be purged and the ones left alone. The plan is saved,
+    /// and its token confirms it for `DECOMMISSION_PLAN_VALIDITY_MINUTES`.
+    pub fn plan_wipe_all_non_system(&self, include_removable: bool) -> WipeResult2<DecommissionPlan> {
+        self.plan_wipe_all_non_system_as(&self.authenticated_operator()?, include_removable)
+    }
+
+    /// `plan_wipe_all_non_system` on behalf of `operator` instead of the logged-in session
+    pub fn plan_wipe_all_non_system_as(&self, operator: &OperatorIdentity, include_removable: bool) -> WipeResult2<DecommissionPlan> {
+        operator.require_sanitize()?;
+        let disks = self.scan_devices_as(Some(operator))?;
+        let plan = DecommissionPlan::build(&disks, &self.current().config.target_policy, include_removable, &operator.display_name());
+        plan.save(self.data_path(DEFAULT_DECOMMISSION_PLAN_PATH))?;
+        Ok(plan)
+    }
+
+    /// Queue a purge of every drive in the last decommission plan, starting
+    /// now. `confirmation_token` must be the plan's, the plan must not have
+    /// expired and the attached drives must still match it; a plan confirms
+    /// once. Run the queued jobs with `run_schedule_as`.
+    pub fn wipe_all_non_system(&self, confirmation_token: &str) -> WipeResult2<Vec<ScheduledJob>> {
+        self.wipe_all_non_system_as(&self.authenticated_operator()?, confirmation_token)
+    }
+
+    /// `wipe_all_non_system` on behalf of `operator` instead of the logged-in session
+    pub fn wipe_all_non_system_as(&self, operator: &OperatorIdentity, confirmation_token: &str) -> WipeResult2<Vec<ScheduledJob>> {
+        operator.require_sanitize()?;
+        let Some(plan) = DecommissionPlan::load(self.data_path(DEFAULT_DECOMMISSION_PLAN_PATH))? else {
+            return Err(WipeError {
+                code: WipeErrorCode::PolicyDenied,
+                message: "No decommission plan to confirm; preview one first".to_string(),
+                sector: None,
+            });
+        };
+        let disks = self.scan_devices_as(Some(operator))?;
+        plan.check(confirmation_token, &disks, &self.current().config.target_policy)?;
+        let _ = std::fs::remove_file(self.data_path(DEFAULT_DECOMMISSION_PLAN_PATH));
+
+        let now = Utc::now();
+        let mut jobs = Vec::new();
+        for drive in &plan.drives {
+            let mut request = Self::build_request(
+                drive.path.clone(),
+                drive.target_type,
+                WipeScope::WholeDevice,
+                SanitizationStandard::NIST_SP_800_88_R1,
+                true,
+                true,
+            );
+            request.profile = Some(ComplianceProfile::Nist80088Purge);
+            // The confirmed plan listed the drive; it stands in for the per-drive phrase
+            request.confirmation = self.confirmation_phrase(&request);
+            jobs.push(self.schedule_wipe_as(operator, request, now)?);
+        }
+
+        let operations: Vec<serde_json::Value> = jobs
+            .iter()
+            .map(|job| serde_json::json!({ "operation_id": job.id(), "device": job.request.target_path }))
+            .collect();
+        self.audit_as(Some(operator), AuditEventKind::DecommissionQueued, None, serde_json::json!({
+            "plan_id": plan.id,
+            "operations": operations,
+            "excluded": plan.excluded,
+        }))?;
+        Ok(jobs)
+    }
+
+    /// Run scheduled wipes as `operator`: start each one once its time has
+    /// come and a maintenance window is open, highest priority first, and pause and resume them at
+    /// window boundaries when so configured. Returns the outcome of every
+    /// wipe started, once no job is left pending or running.
+    pub fn run_schedule_as(
+        &self,
+        operator: &OperatorIdentity,
+    ) -> WipeResult2<Vec<(Uuid, ScheduledOutcome)>> {
+        operator.require_sanitize()?;
+
+        thread::scope(|scope| {
+            let mut running = Vec::new();
+            let mut outcomes = Vec::new();
+            let mut left_running: HashSet<Uuid> = HashSet::new();
+            loop {
+                // Re-read every tick, so changed maintenance windows apply to the running schedule
+                let schedule_config = self.schedule_config();
+                let now = chrono::Local::now();
+                let open = schedule_config.is_open(now);
+
+                let (finished, still_running): (Vec<_>, Vec<_>) =
+                    running.drain(..).partition(|(_, handle): &(Uuid, thread::ScopedJoinHandle<_>)| handle.is_finished());
+                running = still_running;
+                for (job_id, handle) in finished {
+                    let outcome: ScheduledOutcome = handle.join().unwrap_or_else(|_| Err(WipeError {
+                        code: WipeErrorCode::UnknownError,
+                        message: "Scheduled wipe panicked".to_string(),
+                        sector: None,
+                    }));
+                    let (status, error) = match &outcome {
+                        Ok((result, _)) if result.success => (JobStatus::Completed, None),
+                        Ok((result, _)) => (JobStatus::Failed, result.error_message.clone()),
+                        Err(e) if matches!(e.code, WipeErrorCode::Cancelled) => (JobStatus::Cancelled, Some(e.to_string())),
+                        Err(e) => (JobStatus::Failed, Some(e.to_string())),
+                    };
+                    self.schedule.lock().unwrap().set_status(job_id, status, error)?;
+                    outcomes.push((job_id, outcome));
+                }
+
+                // A wipe still in its safety checks has no handle yet; it is paused on a later tick.
+                // Pauses are journaled and audited like an operator's; a wipe that cannot
+                // pause (a hardware erase) is left running until the window opens again
+                if schedule_config.pause_outside_windows {
+                    if open {
+                        left_running.clear();
+                    }
+                    for (job_id, _) in &running {
+                        let status = self.schedule.lock().unwrap().get(*job_id).map(|job| job.status);
+                        if !open && status == Some(JobStatus::Running) && !left_running.contains(job_id) {
+                            match self.pause_operation_as(operator, *job_id) {
+                                Ok(_) => self.schedule.lock().unwrap().set_status(*job_id, JobStatus::Paused, None)?,
+                                Err(_) => {
+                                    left_running.insert(*job_id);
+                                }
+                            }
+                        } else if open && status == Some(JobStatus::Paused) && self.resume_operation_as(operator, *job_id).is_ok() {
+                            self.schedule.lock().unwrap().set_status(*job_id, JobStatus::Running, None)?;
+                        }
+                    }
+                }
+
+                if open {
+                    let due = self.schedule.lock().unwrap().due(Utc::now());
+                    for job in due {
+                        // Only a wipe that preempts others starts past the limit
+                        let full = schedule_config.max_running.is_some_and(|max| running.len() >= max);
+                        if full && !schedule_config.preempts(&job.request) {
+                            continue;
+                        }
+                        self.schedule.lock().unwrap().set_status(job.id(), JobStatus::Running, None)?;
+                        info!(job_id = %job.id(), device = %job.request.target_path, priority = ?job.request.priority, "Starting scheduled wipe");
+                        let request = job.request;
+                        running.push((request.id, scope.spawn(move || self.execute_request_as(operator, request))));
+                    }
+                }
+
+                if running.is_empty() && !self.schedule.lock().unwrap().has_unfinished() {
+                    return Ok(outcomes);
+                }
+                thread::sleep(SCHEDULER_TICK);
+            }
+        })
+    }
+
+    /// Wipes that were running when the engine last stopped, from a crash or
+    /// power loss, and have not been resumed, restarted or dismissed since
+    pub fn recover_operations(&self) -> WipeResult2<Vec<InterruptedOperation>> {
+        let path = self.journal.lock().unwrap().path().to_path_buf();
+        let active_wipes = self.active_wipes.lock().unwrap();
+        Ok(OperationJournal::read_interrupted(path)?
+            .into_iter()
+            .filter(|operation| !active_wipes.contains_key(&operation.operation_id))
+            .collect())
+    }
+
+    /// `recover_operation_as` on behalf of the logged-in session
+    pub fn recover_operation(
+        &self,
+        operation_id: Uuid,
+        action: RecoveryAction,
+    ) -> WipeResult2<Option<(WipeResult, Option<String>)>> {
+        self.recover_operation_as(&self.authenticated_operator()?, operation_id, action)
+    }
+
+    /// Deal with an interrupted wipe: resume it at its first unfinished pass,
+    /// run it again from the first pass, or dismiss it and leave the device as
+    /// it is. Resumed and restarted wipes run as a new operation, blocking
+    /// until it ends; a dismissed one returns `None`.
+    pub fn recover_operation_as(
+        &self,
+        operator: &OperatorIdentity,
+        operation_id: Uuid,
+        action: RecoveryAction,
+    ) -> WipeResult2<Option<(WipeResult, Option<String>)>> {
+        self.prepare_recovery_as(operator, operation_id, action)?
+            .map(|request| self.execute_request_as(operator, request))
+            .transpose()
+    }
+
+    /// Close an interrupted wipe in the journal and return the request that
+    /// resumes or restarts it under a new operation id, for front ends that
+    /// follow its progress before running it with `execute_request_as`
+    pub fn prepare_recovery_as(
+        &self,
+        operator: &OperatorIdentity,
+        operation_id: Uuid,
+        action: RecoveryAction,
+    ) -> WipeResult2<Option<WipeRequest>> {
+        operator.require_sanitize()?;
+        let interrupted = self.recover_operations()?
+            .into_iter()
+            .find(|operation| operation.operation_id == operation_id)
+            .ok_or_else(|| WipeError {
+                code: WipeErrorCode::InvalidTarget,
+                message: format!("No interrupted wipe with id {}", operation_id),
+                sector: None,
+            })?;
+
+        let mut request = interrupted.request.clone();
+        request.id = Uuid::new_v4();
+        request.timestamp = Utc::now();
+        request.resume = match action {
+            RecoveryAction::Resume => Some(ResumePoint { operation_id, passes_completed: interrupted.passes_completed }),
+            RecoveryAction::Restart | RecoveryAction::Dismiss => None,
+        };
+        self.journal.lock().unwrap().append(operation_id, JournalEvent::Recovered { action })?;
+        self.audit_as(Some(operator), AuditEventKind::WipeRecovered, Some(&request.target_path), serde_json::json!({
+            "operation_id": operation_id,
+            "action": action,
+            "passes_completed": interrupted.passes_completed,
+            "total_passes": interrupted.total_passes,
+            "new_operation_id": (action != RecoveryAction::Dismiss).then_some(request.id),
+        }))?;
+
+        match action {
+            RecoveryAction::Dismiss => {
+                info!(%operation_id, operator = %operator.display_name(), "Interrupted wipe dismissed");
+                Ok(None)
+            }
+            RecoveryAction::Resume | RecoveryAction::Restart => {
+                info!(
+                    %operation_id,
+                    device = %request.target_path,
+                    new_operation_id = %request.id,
+                    "{} interrupted wipe",
+                    if action == RecoveryAction::Resume { "Resuming" } else { "Restarting" }
+                );
+                Ok(Some(request))
+            }
+        }
+    }
+
+    /// Devices in use by a running wipe, verification or scan, oldest claim first
+    pub fn locked_devices(&self) -> Vec<LockedDevice> {
+        self.device_locks.held()
+    }
+
+    /// The operation using `device` (a stable device id or a path), if any
+    pub fn device_lock(&self, device: &str) -> Option<LockedDevice> {
+        self.device_locks.holder(device)
+    }
+
+    /// What happened during an operation so far, oldest first; running wipes
+    /// answer from memory, finished ones from the history
+    pub fn get_operation_events(&self, operation_id: Uuid) -> WipeResult2<Vec<TimelineEvent>> {
+        if let Some(timeline) = self.timelines.lock().unwrap().get(&operation_id) {
+            return Ok(timeline.events());
+        }
+        match self.history.lock().unwrap().get(operation_id) {
+            Some(record) => Ok(record.events.clone()),
+            None => Err(WipeError {
+                code: WipeErrorCode::InvalidTarget,
+                message: format!("No operation {}", operation_id),
+                sector: None,
+            }),
+        }
+    }
+
+    /// What the report backup directory holds, with each file checked
+    /// against the backup's manifest
+    pub fn get_backup_status(&self) -> WipeResult2<BackupStatus> {
+        self.backups.status(self.current().config.backup_reports.as_ref())
+    }
+
+    /// List persisted wipe operations matching `filter`, newest first
+    pub fn list_operations_history(&self, filter: &HistoryFilter) -> Vec<OperationRecord> {
+        self.history.lock().unwrap().list(filter)
+    }
+
+    /// Certificates issued for the operations matching `filter`, newest
+    /// first; `filter.limit` counts certificates rather than operations
+    pub fn list_reports(&self, filter: &HistoryFilter) -> Vec<ReportSummary> {
+        let records = self.history.lock().unwrap().list(&HistoryFilter { limit: None, ..filter.clone() });
+        let mut reports = report_summaries(&records, self.data_path(DEFAULT_REPORTS_DIR));
+        if let Some(limit) = filter.limit {
+            reports.truncate(limit);
+        }
+        reports
+    }
+
+    /// The certificate with id `report_id`, with the operation it was issued for
+    pub fn open_report(&self, report_id: &str) -> WipeResult2<StoredReport> {
+        let record = self
+            .history
+            .lock()
+            .unwrap()
+            .list(&HistoryFilter::default())
+            .into_iter()
+            .find(|record| record.report_ids.iter().any(|id| id == report_id))
+            .ok_or_else(|| WipeError {
+                code: WipeErrorCode::InvalidTarget,
+                message: format!("No certificate with id {} in the history", report_id),
+                sector: None,
+            })?;
+        let missing = |detail: String| WipeError {
+            code: WipeErrorCode::InvalidTarget,
+            message: format!("Certificate {} could not be opened: {}", report_id, detail),
+            sector: None,
+        };
+        let reports_dir = self.data_path(DEFAULT_REPORTS_DIR);
+        let files = find_certificate_files(&reports_dir, report_id)
+            .ok_or_else(|| missing(format!("it is no longer in {}", reports_dir.display())))?;
+        let content = std::fs::read_to_string(&files.json).map_err(|e| missing(e.to_string()))?;
+        let certificate = serde_json::from_str(&content).map_err(|e| missing(e.to_string()))?;
+        Ok(StoredReport { summary: ReportSummary::new(&record, report_id, Some(files)), certificate })
+    }
+
+    /// Delete finished operations that started before `before`
+    pub fn purge_history(&self, before: DateTime<Utc>) -> WipeResult2<usize> {
+        self.authenticated_operator()?.require_admin()?;
+        self.history.lock().unwrap().purge_before(before)
+    }
+
+    /// Queue the uploads of `operation_id` that used up their attempts again;
+    /// returns how many were queued
+    pub fn retry_uploads(&self, operation_id: Uuid) -> WipeResult2<usize> {
+        self.authenticated_operator()?;
+        self.uploads.retry_failed(operation_id)
+    }
+
+    /// Gather the certificate, verification data, audit log excerpt and a
+    /// SMART snapshot of one operation into a bundle for the customer
+    pub fn export_operation_as(&self, operator: &OperatorIdentity, operation_id: Uuid) -> WipeResult2<EvidenceBundle> {
+        let mut record = self.history.lock().unwrap().get(operation_id).cloned().ok_or_else(|| WipeError {
+            code: WipeErrorCode::InvalidTarget,
+            message: format!("No operation with id {} in the history", operation_id),
+            sector: None,
+        })?;
+        // The sealed seed stays with the operator
+        record.pattern_seed = None;
+
+        let serial_number = record.result.as_ref().map(|result| result.drive_geometry.serial.clone());
+        let mut bundle = EvidenceBundle::new(BundleManifest {
+            operation_id,
+            target_path: record.target_path.clone(),
+            serial_number,
+            exported_at: Utc::now(),
+            exported_by: operator.display_name(),
+            files: Vec::new(),
+            notes: Vec::new(),
+        });
+        bundle.add_json("operation.json", &record)?;
+
+        let certificate = record
+            .report_ids
+            .iter()
+            .find_map(|certificate_id| find_certificate_files(self.data_path(DEFAULT_REPORTS_DIR), certificate_id));
+        match certificate {
+            Some(files) => {
+                let copies = [
+                    ("certificate.json", Some(&files.json)),
+                    ("certificate.pdf", files.pdf.as_ref()),
+                    ("audit_report.json", files.audit_report.as_ref()),
+                ];
+                for (name, path) in copies {
+                    match path.map(std::fs::read) {
+                        Some(Ok(contents)) => bundle.add(name, contents),
+                        Some(Err(e)) => bundle.note(format!("{} could not be read: {}", name, e)),
+                        None => bundle.note(format!("{} was not found next to {}", name, files.json.display())),
+                    }
+                }
+            }
+            None if record.report_ids.is_empty() => bundle.note("No certificate was issued for this operation"),
+            None => bundle.note(format!("Certificate files were not found in {}", self.data_path(DEFAULT_REPORTS_DIR).display())),
+        }
+
+        match &record.result {
+            Some(result) => bundle.add_json("verification.json", &serde_json::json!({
+                "verification_report": result.verification_report,
+                "validation_result": result.validation_result,
+                "pass_verifications": result.pass_verifications,
+                "bad_sectors": result.bad_sectors,
+                "hidden_areas": result.hidden_areas,
+                "functional_test": result.functional_test,
+                "forensic_image": result.forensic_image,
+            }))?,
+            None => bundle.note("The operation has no result, so there is no verification data"),
+        }
+        match record.result.as_ref().and_then(|result| result.evidence.as_ref()) {
+            Some(evidence) => bundle.add_json("evidence.json", evidence)?,
+            None => bundle.note("No before/after snapshots were taken of the drive"),
+        }
+
+        // From the start of the operation to its last entry, so the excerpt chains onto the anchor hash
+        let mut audit = self.audit.lock().unwrap().export(&AuditRange { since: Some(record.started_at), until: None })?;
+        let id = operation_id.to_string();
+        let mentions = |entry: &AuditEntry| entry.details.get("operation_id").and_then(|value| value.as_str()) == Some(id.as_str());
+        if let Some(last) = audit.entries.iter().rposition(mentions) {
+            audit.entries.truncate(last + 1);
+        }
+        bundle.add_json("audit_log.json", &audit)?;
+
+        match SmartInterface::new(&record.target_path).read_health() {
+            Ok(health) => bundle.add_json("smart.json", &serde_json::json!({
+                "captured_at": Utc::now(),
+                "health": health,
+            }))?,
+            Err(e) => bundle.note(format!("SMART data of {} could not be read: {}", record.target_path, e)),
+        }
+
+        self.audit_as(Some(operator), AuditEventKind::ReportExported, Some(&record.target_path), serde_json::json!({
+            "operation_id": operation_id,
+            "files": bundle.manifest.files.iter().map(|file| &file.name).collect::<Vec<_>>(),
+        }))?;
+        Ok(bundle)
+    }
+
+    /// Render the report of one operation to `path` in `format`; returns the path written
+    pub fn export_report_as(
+        &self,
+        operator: &OperatorIdentity,
+        operation_id: Uuid,
+        format: ReportFormat,
+        path: &Path,
+    ) -> WipeResult2<PathBuf> {
+        let mut record = self.history.lock().unwrap().get(operation_id).cloned().ok_or_else(|| WipeError {
+            code: WipeErrorCode::InvalidTarget,
+            message: format!("No operation with id {} in the history", operation_id),
+            sector: None,
+        })?;
+        record.pattern_seed = None;
+        let certificate = record
+            .report_ids
+            .iter()
+            .find_map(|certificate_id| find_certificate_files(self.data_path(DEFAULT_REPORTS_DIR), certificate_id))
+            .and_then(|files| std::fs::read_to_string(files.json).ok())
+            .and_then(|content| serde_json::from_str::<ErasureCertificate>(&content).ok());
+
+        if let Some(parent) = path.parent().filter(|parent| !parent.as_os_str().is_empty()) {
+            std::fs::create_dir_all(parent).map_err(|e| WipeError {
+                code: WipeErrorCode::UnknownError,
+                message: format!("Failed to create {}: {}", parent.display(), e),
+                sector: None,
+            })?;
+        }
+        match (format, &certificate) {
+            (ReportFormat::Pdf, Some(certificate)) => self.current().report_generator.generate_pdf_report(certificate, path)?,
+            (ReportFormat::Pdf, None) => {
+                return Err(WipeError {
+                    code: WipeErrorCode::InvalidTarget,
+                    message: format!("No certificate was issued for operation {}, so there is no PDF report", operation_id),
+                    sector: None,
+                });
+            }
+            (ReportFormat::Json, Some(certificate)) => self.current().report_generator.generate_certificate_json(certificate, path)?,
+            (ReportFormat::Json, None) | (ReportFormat::Csv | ReportFormat::Xml, _) => {
+                let contents = match format {
+                    ReportFormat::Csv => operation_csv(&record),
+                    ReportFormat::Xml => operations_xml(std::slice::from_ref(&record), Utc::now()),
+                    _ => serde_json::to_string_pretty(&record).map_err(|e| WipeError {
+                        code: WipeErrorCode::UnknownError,
+                        message: format!("Failed to serialize operation: {}", e),
+                        sector: None,
+                    })?,
+                };
+                std::fs::write(path, contents).map_err(|e| WipeError {
+                    code: WipeErrorCode::UnknownError,
+                    message: format!("Failed to write {}: {}", path.display(), e),
+                    sector: None,
+                })?;
+            }
+        }
+
+        self.audit_as(Some(operator), AuditEventKind::ReportExported, Some(&record.target_path), serde_json::json!({
+            "operation_id": operation_id,
+            "format": format,
+            "path": path.display().to_string(),
+        }))?;
+        Ok(path.to_path_buf())
+    }
+
+    /// Write the operations matching `filter`, oldest first, to `path` as one
+    /// CSV or XML batch summary; returns the number of operations written
+    pub fn export_reports_as(
+        &self,
+        operator: &OperatorIdentity,
+        filter: &HistoryFilter,
+        format: ReportFormat,
+        path: &Path,
+    ) -> WipeResult2<usize> {
+        if !format.is_batch() {
+            return Err(WipeError {
+                code: WipeErrorCode::InvalidTarget,
+                message: format!("A {} report covers one operation; use CSV or XML for several", format.extension()),
+                sector: None,
+            });
+        }
+        let mut records = self.history.lock().unwrap().list(filter);
+        records.reverse();
+        let contents = match format {
+            ReportFormat::Xml => operations_xml(&records, Utc::now()),
+            _ => operations_csv(&records),
+        };
+
+        if let Some(parent) = path.parent().filter(|parent| !parent.as_os_str().is_empty()) {
+            std::fs::create_dir_all(parent).map_err(|e| WipeError {
+                code: WipeErrorCode::UnknownError,
+                message: format!("Failed to create {}: {}", parent.display(), e),
+                sector: None,
+            })?;
+        }
+        std::fs::write(path, contents).map_err(|e| WipeError {
+            code: WipeErrorCode::UnknownError,
+            message: format!("Failed to write {}: {}", path.display(), e),
+            sector: None,
+        })?;
+
+        self.audit_as(Some(operator), AuditEventKind::ReportExported, None, serde_json::json!({
+            "operation_ids": records.iter().map(|record| record.operation_id).collect::<Vec<_>>(),
+            "format": format,
+            "path": path.display().to_string(),
+        }))?;
+        Ok(records.len())
+    }
+
+    /// Verify a signed JSON certificate and cross-check it against the operation history
+    pub fn verify_certificate(&self, certificate_path: &str) -> WipeResult2<CertificateVerdict> {
+        let history = self.history.lock().unwrap();
+        crate::security::verify_certificate_file(certificate_path, Some(&history))
+    }
+
     pub fn validate_certificate(&self, certificate_path: &str) -> WipeResult2<bool> {
         // Load certificate from JSON file
         let content = std::fs::read_to_string(certificate_path)
@@ -143,10 +1905,350 @@ impl WipeEngine {
             })?;
 
         // Verify with CA
-        let ca = self.certificate_authority.lock().unwrap();
+        let current = self.current();
+        let ca = current.certificate_authority.lock().unwrap();
         ca.verify_certificate(&certificate)
     }
 
+    /// Dry-run every safety check for wiping `device` (a stable device id or a path)
+    pub fn preflight(&self, device: &str, target_type: TargetType, standard: SanitizationStandard) -> PreflightReport {
+        self.preflight_as(self.current_operator().as_ref(), device, target_type, standard)
+    }
+
+    /// `preflight` on behalf of `operator` instead of the logged-in session
+    pub fn preflight_as(
+        &self,
+        operator: Option<&OperatorIdentity>,
+        device: &str,
+        target_type: TargetType,
+        standard: SanitizationStandard,
+    ) -> PreflightReport {
+        let target_path = crate::devices::identity::device_path_for_id(device)
+            .unwrap_or_else(|| device.to_string());
+        let current = self.current();
+        let report = run_preflight(&target_path, target_type, standard, &current.config.target_policy, &current.config.wear_thresholds);
+        self.audit_preflight(operator, report)
+    }
+
+    /// Preflight for a wipe wizard: also checks that `method` can be carried
+    /// out on the device and that `operator` may start it, so every reason the
+    /// wipe would be refused shows up before it is started
+    pub fn preflight_wipe_as(
+        &self,
+        operator: Option<&OperatorIdentity>,
+        device: &str,
+        target_type: TargetType,
+        method: &WipeSpec,
+    ) -> PreflightReport {
+        let target_path = crate::devices::identity::device_path_for_id(device)
+            .unwrap_or_else(|| device.to_string());
+        let standard = method.standard.unwrap_or(SanitizationStandard::NIST_SP_800_88_R1);
+        let current = self.current();
+        let mut report = run_preflight(&target_path, target_type, standard, &current.config.target_policy, &current.config.wear_thresholds);
+        check_method(&mut report, method);
+        check_operator(&mut report, operator);
+        self.audit_preflight(operator, report)
+    }
+
+    /// How long wiping `device` with `method` should take, for showing
+    /// before the operator confirms. Uses the rate earlier wipes of the same
+    /// model ran at, a typical rate for the drive when there are none, and
+    /// the erase time an ATA drive reports for its secure erase.
+    pub fn estimate_duration(&self, device: &str, target_type: TargetType, method: &WipeSpec) -> WipeResult2<DurationEstimate> {
+        let target_path = crate::devices::identity::device_path_for_id(device)
+            .unwrap_or_else(|| device.to_string());
+        let disk = self.attached_disk(&target_path).ok_or_else(|| WipeError {
+            code: WipeErrorCode::InvalidTarget,
+            message: format!("{} is not an attached drive", device),
+            sector: None,
+        })?;
+
+        let rate = self.throughput.lock().unwrap().write_rate(&disk.model, &disk.bus_type).or_else(|| {
+            let records = self.history.lock().unwrap().list(&HistoryFilter::default());
+            WriteRate::from_history(&disk.model, &records)
+        });
+        let rate = rate.unwrap_or_else(|| WriteRate::nominal(target_type, &disk.bus_type));
+        let verify_bytes = crate::verification::planned_bytes(disk.size_bytes, &self.current().config.verification_policy);
+
+        match method.hardware {
+            HardwarePreference::Firmware(erase @ (FirmwareErase::AtaSecureErase | FirmwareErase::AtaEnhancedSecureErase)) => {
+                let reported = crate::hardware::DriveInterface::new(&target_path)
+                    .and_then(|drive| drive.identify_device())
+                    .ok()
+                    .and_then(|identify| {
+                        let word = if erase == FirmwareErase::AtaEnhancedSecureErase { identify.data[90] } else { identify.data[89] };
+                        crate::ata_commands::security_erase_time(word)
+                    });
+                Ok(match reported {
+                    Some(erase_time) => DurationEstimate::firmware(erase_time, ThroughputSource::DriveReported),
+                    // The drive overwrites itself at about its own write rate
+                    None => DurationEstimate::overwrite(disk.size_bytes, 1, 0, &rate),
+                })
+            }
+            HardwarePreference::Firmware(
+                FirmwareErase::NvmeCryptoErase | FirmwareErase::NvmeSanitizeCryptoErase | FirmwareErase::ScsiSanitizeCryptoErase,
+            ) => Ok(DurationEstimate::firmware(CRYPTO_ERASE_ESTIMATE, ThroughputSource::Nominal)),
+            HardwarePreference::Firmware(_) => Ok(DurationEstimate::overwrite(disk.size_bytes, 1, 0, &rate)),
+            HardwarePreference::OverwriteOnly | HardwarePreference::PreferFirmware => {
+                Ok(DurationEstimate::overwrite(disk.size_bytes, method.pass_patterns().len() as u32, verify_bytes, &rate))
+            }
+        }
+    }
+
+    /// Attached drive (or test device) at `path`
+    fn attached_disk(&self, path: &str) -> Option<PhysicalDisk> {
+        let mut disks = crate::platform::get_physical_disks().unwrap_or_default();
+        disks.extend(self.test_devices.lock().unwrap().values().map(TestDevice::physical_disk));
+        disks.into_iter().find(|disk| disk.path == path)
+    }
+
+    /// Keep the rate a wipe achieved and compare it with earlier wipes of the
+    /// same model; a failure to save only costs future estimates
+    fn record_throughput(&self, target_path: &str, result: &WipeResult) -> Option<crate::core::ThroughputCheck> {
+        let interface = self
+            .attached_disk(target_path)
+            .map_or_else(|| "Unknown".to_string(), |disk| disk.bus_type);
+        let sample = ThroughputSample::from_result(result, &interface)?;
+        let (model, serial) = (sample.model.clone(), sample.serial.clone());
+        match self.throughput.lock().unwrap().record(sample) {
+            Ok(check) => {
+                if let (true, Some(median)) = (check.underperforming, check.model_median_mbps) {
+                    warn!(
+                        %model,
+                        %serial,
+                        achieved_mbps = check.achieved_mbps,
+                        median_mbps = median,
+                        samples = check.samples,
+                        %interface,
+                        "Drive wrote well below the median of earlier wipes of its model; check its health"
+                    );
+                }
+                Some(check)
+            }
+            Err(e) => {
+                warn!(error = %e, "Failed to record throughput");
+                None
+            }
+        }
+    }
+
+    fn audit_preflight(&self, operator: Option<&OperatorIdentity>, report: PreflightReport) -> PreflightReport {
+        let issues: Vec<serde_json::Value> = report
+            .issues
+            .iter()
+            .map(|issue| serde_json::json!({ "check": issue.check, "severity": format!("{:?}", issue.severity) }))
+            .collect();
+        let _ = self.audit_as(operator, AuditEventKind::Preflight, Some(&report.target_path), serde_json::json!({
+            "standard": report.standard,
+            "can_proceed": report.can_proceed(),
+            "issues": issues,
+        }));
+        report
+    }
+
+    /// Sample a device (a stable device id or a path) and report whether it appears
+    /// blank, encrypted or still holds user data. Read-only; useful for triage before wiping.
+    pub fn analyze_residual_data(&self, device_id: &str) -> WipeResult2<ResidualDataReport> {
+        let device_path = crate::devices::identity::device_path_for_id(device_id)
+            .unwrap_or_else(|| device_id.to_string());
+        let _device_lock = self.device_locks.acquire(&device_path, Uuid::new_v4(), DeviceActivity::ResidualScan)?;
+
+        crate::residual::analyze_residual_data(&device_path).map_err(|e| WipeError {
+            code: if e.kind() == std::io::ErrorKind::NotFound {
+                WipeErrorCode::DriveNotFound
+            } else {
+                WipeErrorCode::HardwareError
+            },
+            message: format!("Failed to analyze {}: {}", device_path, e),
+            sector: None,
+        })
+    }
+
+    /// BitLocker, LUKS and FileVault volumes on a device (a stable device id or a path)
+    pub fn encryption_status(&self, device: &str) -> WipeResult2<EncryptionStatus> {
+        let device_path = crate::devices::identity::device_path_for_id(device)
+            .unwrap_or_else(|| device.to_string());
+
+        crate::encryption::detect_encryption(&device_path).map_err(|e| WipeError {
+            code: if e.kind() == std::io::ErrorKind::NotFound {
+                WipeErrorCode::DriveNotFound
+            } else {
+                WipeErrorCode::HardwareError
+            },
+            message: format!("Failed to inspect {}: {}", device_path, e),
+            sector: None,
+        })
+    }
+
+    /// Fast purge of software-encrypted volumes: destroy every copy of their key
+    /// material and verify it is gone. The ciphertext itself is left in place.
+    pub fn crypto_erase_by_header_destruction(&self, device: &str) -> WipeResult2<HeaderDestructionReport> {
+        self.authorize_sanitize()?;
+        let device_path = crate::devices::identity::device_path_for_id(device)
+            .unwrap_or_else(|| device.to_string());
+
+        self.current().config.target_policy.enforce(&device_path)?;
+        let _device_lock = self.device_locks.acquire(&device_path, Uuid::new_v4(), DeviceActivity::CryptoErase)?;
+        self.audit(AuditEventKind::WipeStarted, Some(&device_path), serde_json::json!({ "method": "crypto header destruction" }))?;
+
+        let report = crate::encryption::destroy_key_material(&device_path).map_err(|e| WipeError {
+            code: match e.kind() {
+                std::io::ErrorKind::NotFound => WipeErrorCode::DriveNotFound,
+                std::io::ErrorKind::InvalidInput => WipeErrorCode::InvalidTarget,
+                std::io::ErrorKind::Unsupported => WipeErrorCode::Unsupported,
+                std::io::ErrorKind::PermissionDenied => WipeErrorCode::InsufficientPrivileges,
+                _ => WipeErrorCode::HardwareError,
+            },
+            message: format!("Header destruction failed on {}: {}", device_path, e),
+            sector: None,
+        });
+        let _ = self.audit(
+            if report.as_ref().is_ok_and(|r| r.verified) { AuditEventKind::WipeFinished } else { AuditEventKind::WipeFailed },
+            Some(&device_path),
+            match &report {
+                Ok(report) => serde_json::json!({ "method": "crypto header destruction", "verified": report.verified, "notes": report.notes }),
+                Err(error) => serde_json::json!({ "method": "crypto header destruction", "message": error.message }),
+            },
+        );
+        let report = report?;
+
+        if !report.verified {
+            return Err(WipeError {
+                code: WipeErrorCode::VerificationFailed,
+                message: format!("Key material on {} not verified as destroyed: {}", device_path, report.notes.join("; ")),
+                sector: None,
+            });
+        }
+
+        Ok(report)
+    }
+
+    /// Factory-reset a self-encrypting drive with the PSID printed on its label.
+    /// Works when the owner credential is unknown: the drive regenerates its
+    /// media encryption key and clears its locking ranges, so all data is lost.
+    pub fn psid_revert(&self, device: &str, psid: &str) -> WipeResult2<()> {
+        self.psid_revert_as(&self.authenticated_operator()?, device, psid)
+    }
+
+    /// `psid_revert` on behalf of `operator` instead of the logged-in session
+    pub fn psid_revert_as(&self, operator: &OperatorIdentity, device: &str, psid: &str) -> WipeResult2<()> {
+        operator.require_sanitize()?;
+        let device_path = crate::devices::identity::device_path_for_id(device)
+            .unwrap_or_else(|| device.to_string());
+
+        self.current().config.target_policy.enforce(&device_path)?;
+        if crate::opal::detect_opal(&device_path).is_none() {
+            return Err(WipeError {
+                code: WipeErrorCode::Unsupported,
+                message: format!("{} does not report a TCG security subsystem", device_path),
+                sector: None,
+            });
+        }
+        let _device_lock = self.device_locks.acquire(&device_path, Uuid::new_v4(), DeviceActivity::CryptoErase)?;
+        self.audit_as(Some(operator), AuditEventKind::WipeStarted, Some(&device_path), serde_json::json!({ "method": "TCG PSID revert" }))?;
+
+        let result = crate::opal::OpalInterface::new(&device_path).psid_revert(psid).map_err(|e| WipeError {
+            code: match e.kind() {
+                std::io::ErrorKind::NotFound => WipeErrorCode::Unsupported,
+                std::io::ErrorKind::PermissionDenied => WipeErrorCode::InsufficientPrivileges,
+                _ => WipeErrorCode::HardwareError,
+            },
+            message: format!("PSID revert failed on {}: {}", device_path, e),
+            sector: None,
+        });
+        let _ = self.audit_as(
+            Some(operator),
+            if result.is_ok() { AuditEventKind::WipeFinished } else { AuditEventKind::WipeFailed },
+            Some(&device_path),
+            match &result {
+                Ok(()) => serde_json::json!({ "method": "TCG PSID revert" }),
+                Err(error) => serde_json::json!({ "method": "TCG PSID revert", "message": error.message }),
+            },
+        );
+        info!(device = %device_path, operator = %operator.display_name(), success = result.is_ok(), "PSID revert");
+        result
+    }
+
+    /// Details of the hardware RAID volume behind `device` (a stable device id or a
+    /// path), including its member drives when the controller CLI is installed
+    pub fn raid_virtual_disk(&self, device: &str) -> Option<VirtualDiskInfo> {
+        let target_path = crate::devices::identity::device_path_for_id(device)
+            .unwrap_or_else(|| device.to_string());
+        crate::raid::detect_virtual_disk(&target_path)
+    }
+
+    /// Erase a RAID member drive through its controller. The member must already
+    /// have been released from its virtual disk.
+    pub fn erase_raid_member(&self, member: &RaidMember, mode: ControllerEraseMode) -> WipeResult2<()> {
+        self.authorize_sanitize()?;
+        crate::raid::erase_member(member, mode).map_err(|e| WipeError {
+            code: if e.kind() == std::io::ErrorKind::NotFound {
+                WipeErrorCode::DriveNotFound
+            } else {
+                WipeErrorCode::HardwareError
+            },
+            message: format!("Failed to erase RAID member {}: {}", member.enclosure_slot, e),
+            sector: None,
+        })
+    }
+
+    /// Shred individual files and folders instead of a whole device. Paths that
+    /// fail are listed in the report; the call only errors when nothing could
+    /// be shredded at all.
+    pub fn secure_delete_paths(&self, paths: &[String], pattern: ShredPattern, passes: u32) -> WipeResult2<ShredReport> {
+        self.authorize_sanitize()?;
+        if paths.is_empty() {
+            return Err(WipeError {
+                code: WipeErrorCode::InvalidTarget,
+                message: "No paths given to shred".to_string(),
+                sector: None,
+            });
+        }
+
+        let report = crate::secure_delete::secure_delete_paths(paths, &ShredOptions::new(pattern, passes));
+        if report.files_shredded == 0 && report.directories_removed == 0 && !report.success() {
+            let (path, reason) = &report.failures[0];
+            return Err(WipeError {
+                code: WipeErrorCode::AccessDenied,
+                message: format!("Failed to shred {}: {}", path, reason),
+                sector: None,
+            });
+        }
+
+        Ok(report)
+    }
+
+    /// Receive the progress of every wipe run by this engine from now on
+    pub fn subscribe_progress(&self) -> Receiver<WipeProgress> {
+        let (tx, rx) = mpsc::channel();
+        self.progress_subscribers.lock().unwrap().push(tx);
+        rx
+    }
+
+    /// Receive `DeviceEvent`s as drives are attached, removed or changed
+    pub fn subscribe_device_events(&self) -> Receiver<DeviceEvent> {
+        self.device_watcher.lock().unwrap().subscribe()
+    }
+
+    /// Read SMART / NVMe health data for a device
+    pub fn get_device_health(&self, device_path: &str) -> WipeResult2<DeviceHealth> {
+        SmartInterface::new(device_path)
+            .read_health()
+            .map_err(|e| WipeError {
+                code: WipeErrorCode::HardwareError,
+                message: format!("Failed to read device health: {}", e),
+                sector: None,
+            })
+    }
+
+    /// Whether `device` (a stable device id or a path) is worth overwriting,
+    /// judged from its SMART data against the configured wear thresholds
+    pub fn advise_wipe(&self, device: &str) -> WipeAdvisory {
+        let device_path = crate::devices::identity::device_path_for_id(device)
+            .unwrap_or_else(|| device.to_string());
+        WipeAdvisory::evaluate(crate::smart::read_device_health(&device_path), &self.current().config.wear_thresholds)
+    }
+
     pub fn get_drive_info(&self, drive_path: &str) -> WipeResult2<(crate::core::DriveGeometry, crate::core::SecurityFeatures)> {
         use crate::hardware::DriveInterface;
         
@@ -157,4 +2259,281 @@ impl WipeEngine {
         
         Ok((geometry, security))
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::auth::UserRole;
+    use crate::core::{AuthMethod, PolicyAction, TargetPolicy};
+
+    /// An engine keeping its history, journal, audit log and certificates in
+    /// a scratch directory of its own, removed when the directory is dropped
+    fn engine(config: WipeConfiguration) -> (tempfile::TempDir, WipeEngine) {
+        let dir = tempfile::tempdir().unwrap();
+        for subdirectory in ["certificates", "reports"] {
+            std::fs::create_dir_all(dir.path().join(subdirectory)).unwrap();
+        }
+        let engine = WipeEngine::new_in(config, dir.path()).unwrap();
+        (dir, engine)
+    }
+
+    /// Small blocks, so a throttled wipe of a test device stays running for a while
+    fn config() -> WipeConfiguration {
+        WipeConfiguration {
+            adaptive_buffer: false,
+            buffer_size: 64 * 1024,
+            ..WipeConfiguration::default()
+        }
+    }
+
+    fn throttled() -> WipeConfiguration {
+        WipeConfiguration { max_throughput_mbps: Some(0.25), ..config() }
+    }
+
+    fn operator(role: UserRole) -> OperatorIdentity {
+        OperatorIdentity {
+            username: "alice".to_string(),
+            role,
+            method: AuthMethod::Password,
+            authenticated_at: Utc::now(),
+        }
+    }
+
+    fn request(device: &PhysicalDisk) -> WipeRequest {
+        WipeEngine::build_request(
+            device.path.clone(),
+            TargetType::HDD,
+            WipeScope::WholeDevice,
+            SanitizationStandard::NIST_SP_800_88_R1,
+            false,
+            false,
+        )
+    }
+
+    fn wait_until(condition: impl Fn() -> bool) {
+        let deadline = Instant::now() + Duration::from_secs(20);
+        while !condition() {
+            assert!(Instant::now() < deadline, "timed out");
+            thread::sleep(Duration::from_millis(20));
+        }
+    }
+
+    /// Wait for a wipe started on another thread to reach its write loop
+    fn wait_for_writes(engine: &WipeEngine, operation_id: Uuid) {
+        wait_until(|| engine.operation_limits.lock().unwrap().contains_key(&operation_id));
+    }
+
+    /// Let a throttled wipe finish at full speed
+    fn unthrottle(engine: &WipeEngine, operation_id: Uuid) {
+        engine.update_operation_limits_as(&operator(UserRole::Operator), operation_id, OperationLimits::default()).unwrap();
+    }
+
+    fn journal_events(engine: &WipeEngine, operation_id: Uuid) -> Vec<JournalEvent> {
+        let path = engine.journal.lock().unwrap().path().to_path_buf();
+        OperationJournal::read_entries(path)
+            .unwrap()
+            .into_iter()
+            .filter(|entry| entry.operation_id == operation_id)
+            .map(|entry| entry.event)
+            .collect()
+    }
+
+    struct RecordingSink {
+        events: Mutex<Vec<(Uuid, LifecycleEventKind)>>,
+    }
+
+    impl WipeEventSink for RecordingSink {
+        fn name(&self) -> String {
+            "recording".to_string()
+        }
+
+        fn deliver(&self, event: &LifecycleEvent) -> Result<(), String> {
+            self.events.lock().unwrap().push((event.operation_id, event.kind));
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_refused_wipe_is_never_announced_or_journaled() {
+        let policy = TargetPolicy { rules: Vec::new(), default_action: PolicyAction::Deny, require_serial: false };
+        let (_dir, engine) = engine(WipeConfiguration { target_policy: policy, ..config() });
+        let sink = Arc::new(RecordingSink { events: Mutex::new(Vec::new()) });
+        engine.add_event_sink(sink.clone());
+        let device = engine.create_test_device(256 * 1024, TestDeviceBacking::TempFile).unwrap();
+        let request = request(&device);
+        let operation_id = request.id;
+
+        let error = engine.execute_request_as(&operator(UserRole::Operator), request).unwrap_err();
+        assert!(matches!(error.code, WipeErrorCode::PolicyDenied));
+
+        wait_until(|| sink.events.lock().unwrap().iter().any(|(id, _)| *id == operation_id));
+        let kinds: Vec<LifecycleEventKind> =
+            sink.events.lock().unwrap().iter().filter(|(id, _)| *id == operation_id).map(|(_, kind)| *kind).collect();
+        assert_eq!(kinds, vec![LifecycleEventKind::WipeFailed]);
+        assert!(journal_events(&engine, operation_id).is_empty());
+        assert!(engine.device_lock(&device.path).is_none());
+        assert!(engine.history.lock().unwrap().get(operation_id).is_none());
+
+        let audited: Vec<AuditEventKind> = engine.audit.lock().unwrap().read_entries().unwrap().into_iter()
+            .filter(|entry| entry.details["operation_id"] == serde_json::json!(operation_id))
+            .map(|entry| entry.kind)
+            .collect();
+        assert_eq!(audited, vec![AuditEventKind::WipeRefused]);
+    }
+
+    #[test]
+    fn test_device_is_locked_while_a_wipe_runs() {
+        let (_dir, engine) = engine(throttled());
+        let device = engine.create_test_device(1024 * 1024, TestDeviceBacking::TempFile).unwrap();
+        let operator = operator(UserRole::Operator);
+        let first = request(&device);
+        let first_id = first.id;
+
+        thread::scope(|scope| {
+            let running = scope.spawn(|| engine.execute_request_as(&operator, first));
+            wait_for_writes(&engine, first_id);
+            assert_eq!(engine.device_lock(&device.path).map(|lock| lock.operation_id), Some(first_id));
+
+            let error = engine.execute_request_as(&operator, request(&device)).unwrap_err();
+            assert!(matches!(error.code, WipeErrorCode::OperationInProgress));
+
+            engine.cancel_wipe_as(&operator, first_id).unwrap();
+            let error = running.join().unwrap().unwrap_err();
+            assert!(matches!(error.code, WipeErrorCode::Cancelled));
+        });
+        assert!(engine.device_lock(&device.path).is_none());
+    }
+
+    #[test]
+    fn test_psid_revert_needs_a_self_encrypting_drive() {
+        let (_dir, engine) = engine(config());
+        let device = engine.create_test_device(256 * 1024, TestDeviceBacking::TempFile).unwrap();
+
+        let error = engine.psid_revert_as(&operator(UserRole::Viewer), &device.path, "0123456789ABCDEF0123456789ABCDEF").unwrap_err();
+        assert!(matches!(error.code, WipeErrorCode::InsufficientPrivileges));
+        // A file answers no TCG discovery, so nothing is sent to sedutil
+        let error = engine.psid_revert_as(&operator(UserRole::Operator), &device.path, "0123456789ABCDEF0123456789ABCDEF").unwrap_err();
+        assert!(matches!(error.code, WipeErrorCode::Unsupported));
+        assert!(engine.device_lock(&device.path).is_none());
+    }
+
+    #[test]
+    fn test_pause_and_resume_are_journaled() {
+        let (_dir, engine) = engine(throttled());
+        let device = engine.create_test_device(1024 * 1024, TestDeviceBacking::TempFile).unwrap();
+        let operator = operator(UserRole::Operator);
+        let request = request(&device);
+        let operation_id = request.id;
+
+        thread::scope(|scope| {
+            let running = scope.spawn(|| engine.execute_request_as(&operator, request));
+            wait_for_writes(&engine, operation_id);
+
+            let checkpoint = engine.pause_operation_as(&operator, operation_id).unwrap();
+            assert_eq!(checkpoint.pass, 1);
+            assert!(matches!(
+                journal_events(&engine, operation_id).last(),
+                Some(JournalEvent::Paused { checkpoint: journaled }) if *journaled == checkpoint
+            ));
+
+            engine.resume_operation_as(&operator, operation_id).unwrap();
+            assert!(matches!(journal_events(&engine, operation_id).last(), Some(JournalEvent::Resumed)));
+            // Nothing left to resume
+            assert!(engine.resume_operation_as(&operator, operation_id).is_err());
+
+            unthrottle(&engine, operation_id);
+            let (result, _) = running.join().unwrap().unwrap();
+            assert!(result.success, "{:?}", result.error_message);
+        });
+        assert!(matches!(journal_events(&engine, operation_id).last(), Some(JournalEvent::Finished)));
+    }
+
+    #[test]
+    fn test_preempted_wipe_waits_for_the_high_priority_one() {
+        let (_dir, engine) = engine(throttled());
+        let device = engine.create_test_device(1024 * 1024, TestDeviceBacking::TempFile).unwrap();
+        let operator = operator(UserRole::Operator);
+        let mut low = request(&device);
+        low.priority = WipePriority::Low;
+        let low_id = low.id;
+
+        thread::scope(|scope| {
+            let running = scope.spawn(|| engine.execute_request_as(&operator, low));
+            wait_for_writes(&engine, low_id);
+
+            // A high-priority firmware erase on another drive of the station
+            let erase = Uuid::new_v4();
+            let held = engine.preemptions.register(
+                erase,
+                WipePriority::High,
+                true,
+                true,
+                Arc::new(LimitsHandle::new(OperationLimits::default())),
+            );
+            assert_eq!(held, vec![low_id]);
+            let limits = engine.operation_limits.lock().unwrap().get(&low_id).cloned().unwrap();
+            wait_until(|| limits.checkpoint().is_some());
+
+            // Held, not paused: an operator cannot release it early
+            let error = engine.resume_operation_as(&operator, low_id).unwrap_err();
+            assert!(error.message.contains("preemption"), "{}", error.message);
+            assert!(limits.is_paused());
+
+            engine.preemptions.unregister(erase);
+            assert!(!limits.is_paused());
+            unthrottle(&engine, low_id);
+            let (result, _) = running.join().unwrap().unwrap();
+            assert!(result.success, "{:?}", result.error_message);
+        });
+        assert!(engine.preemptions.held().is_empty());
+    }
+
+    #[test]
+    fn test_auto_verify_reads_back_wipes_that_did_not_ask() {
+        let (_dir, engine) = engine(WipeConfiguration { auto_verify: true, ..config() });
+        let device = engine.create_test_device(256 * 1024, TestDeviceBacking::TempFile).unwrap();
+        let request = request(&device);
+        assert!(!request.verify_erasure);
+
+        let (result, _) = engine.execute_request_as(&operator(UserRole::Operator), request).unwrap();
+        assert!(result.success, "{:?}", result.error_message);
+        assert!(result.verification_report.as_ref().is_some_and(|report| report.passed));
+    }
+
+    #[test]
+    fn test_configuration_change_applies_to_the_next_wipe() {
+        let (_dir, engine) = engine(throttled());
+        let device = engine.create_test_device(1024 * 1024, TestDeviceBacking::TempFile).unwrap();
+        let operator = operator(UserRole::Operator);
+        *engine.operator.lock().unwrap() = Some(self::operator(UserRole::Admin));
+        let running_request = request(&device);
+        let running_id = running_request.id;
+        let authority = engine.current().certificate_authority.clone();
+
+        thread::scope(|scope| {
+            let running = scope.spawn(|| engine.execute_request_as(&operator, running_request));
+            wait_for_writes(&engine, running_id);
+
+            // Settings that do not touch signing keep the certificate authority
+            engine.update_configuration(WipeConfiguration { auto_verify: true, ..config() }).unwrap();
+            assert!(engine.configuration().auto_verify);
+            assert!(Arc::ptr_eq(&engine.current().certificate_authority, &authority));
+
+            unthrottle(&engine, running_id);
+            let (result, _) = running.join().unwrap().unwrap();
+            assert!(result.success, "{:?}", result.error_message);
+            // Started before the change, so not verified
+            assert!(result.verification_report.is_none());
+        });
+
+        let (result, _) = engine.execute_request_as(&operator, request(&device)).unwrap();
+        assert!(result.verification_report.is_some());
+
+        // A new signing key rebuilds it
+        let key_dir = tempfile::tempdir().unwrap();
+        let key_path = key_dir.path().join("engine_key.pk8");
+        let signing_key_path = Some(key_path.to_string_lossy().to_string());
+        engine.update_configuration(WipeConfiguration { signing_key_path, ..config() }).unwrap();
+        assert!(!Arc::ptr_eq(&engine.current().certificate_authority, &authority));
+    }
+}
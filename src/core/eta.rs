@@ -0,0 +1,434 @@
+//! Completion time estimates for overwrite wipes
+//!
+//! Dividing the remaining bytes by the latest instantaneous speed makes the ETA
+//! jump with every cache flush and head seek. `EtaEstimator` instead keeps an
+//! exponentially weighted average of the throughput measured over one-second
+//! windows, counts the passes still to come and the verification read, and
+//! derives confidence bounds from the spread of the measured rates.
+//!
+//! Later passes are predicted from the average rate of the passes already
+//! completed, which captures the slow inner tracks of a hard disk better than
+//! the rate at the end of the previous pass.
+//!
+//! Before a wipe starts there is nothing to measure yet. `DurationEstimate`
+//! is then worked out from the drive size, the passes and verification read
+//! of the method, and the rate earlier wipes of the same model ran at: their
+//! end-to-end rate, else the write rate their block size probe measured,
+//! else a typical rate for the kind of drive. Firmware erases take the time
+//! the drive reports in its identify data.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::time::{Duration, Instant};
+
+use crate::core::{OperationRecord, OperationStatus, TargetType, WipeProgress};
+
+/// Throughput is sampled over windows of at least this length
+const SAMPLE_WINDOW: Duration = Duration::from_secs(1);
+
+/// Time constant of the moving average
+const SMOOTHING_SECONDS: f64 = 30.0;
+
+/// Windows needed before an estimate is given
+const MIN_SAMPLES: u32 = 3;
+
+/// The pessimistic bound assumes at least this fraction of the average rate
+const MIN_RATE_FRACTION: f64 = 0.25;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Phase {
+    Pass(u32),
+    Verify,
+}
+
+/// Exponentially weighted mean and variance of a rate in bytes per second
+#[derive(Debug, Clone, Default)]
+struct Ewma {
+    mean: f64,
+    variance: f64,
+    samples: u32,
+}
+
+impl Ewma {
+    fn add(&mut self, rate: f64, elapsed: Duration) {
+        if self.samples == 0 {
+            self.mean = rate;
+        } else {
+            let alpha = 1.0 - (-elapsed.as_secs_f64() / SMOOTHING_SECONDS).exp();
+            let deviation = rate - self.mean;
+            self.mean += alpha * deviation;
+            self.variance = (1.0 - alpha) * (self.variance + alpha * deviation * deviation);
+        }
+        self.samples += 1;
+    }
+
+    fn is_ready(&self) -> bool {
+        self.samples >= MIN_SAMPLES && self.mean > 0.0
+    }
+
+    /// Standard deviation relative to the mean
+    fn relative_spread(&self) -> f64 {
+        self.variance.sqrt() / self.mean
+    }
+}
+
+/// Remaining time, with optimistic and pessimistic bounds
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct EtaEstimate {
+    pub expected: Duration,
+    pub earliest: Duration,
+    pub latest: Duration,
+}
+
+impl EtaEstimate {
+    /// Set the completion times of `progress` counted from now
+    pub fn apply(&self, progress: &mut WipeProgress) {
+        let now = Utc::now();
+        let at = |remaining: Duration| -> Option<DateTime<Utc>> {
+            chrono::Duration::from_std(remaining).ok().map(|remaining| now + remaining)
+        };
+        progress.estimated_completion = at(self.expected);
+        progress.estimated_completion_earliest = at(self.earliest);
+        progress.estimated_completion_latest = at(self.latest);
+    }
+}
+
+pub struct EtaEstimator {
+    bytes_per_pass: u64,
+    total_passes: u32,
+    verify_bytes: u64,
+    phase: Phase,
+    phase_started: Instant,
+    write_rate: Ewma,
+    verify_rate: Ewma,
+    /// Average rate of each completed pass
+    pass_rates: Vec<f64>,
+    /// Start of the current sampling window and the bytes done at that point
+    window: (Instant, u64),
+}
+
+impl EtaEstimator {
+    /// `verify_bytes` is what the verification policy will read back, 0 without verification
+    pub fn new(bytes_per_pass: u64, total_passes: u32, verify_bytes: u64) -> Self {
+        let now = Instant::now();
+        Self {
+            bytes_per_pass,
+            total_passes,
+            verify_bytes,
+            phase: Phase::Pass(1),
+            phase_started: now,
+            write_rate: Ewma::default(),
+            verify_rate: Ewma::default(),
+            pass_rates: Vec::new(),
+            window: (now, 0),
+        }
+    }
+
+    /// `bytes_done` of overwrite pass `pass` (1-based) are written
+    pub fn observe_pass(&mut self, pass: u32, bytes_done: u64) -> Option<EtaEstimate> {
+        self.observe(Phase::Pass(pass), bytes_done, Instant::now())
+    }
+
+    /// `bytes_done` of the `planned_bytes` verification read are done
+    pub fn observe_verification(&mut self, bytes_done: u64, planned_bytes: u64) -> Option<EtaEstimate> {
+        self.verify_bytes = planned_bytes;
+        self.observe(Phase::Verify, bytes_done, Instant::now())
+    }
+
+    fn observe(&mut self, phase: Phase, bytes_done: u64, now: Instant) -> Option<EtaEstimate> {
+        if phase != self.phase {
+            if let Phase::Pass(_) = self.phase {
+                let seconds = now.duration_since(self.phase_started).as_secs_f64();
+                if seconds > 0.0 {
+                    self.pass_rates.push(self.bytes_per_pass as f64 / seconds);
+                }
+            }
+            self.phase = phase;
+            self.phase_started = now;
+            self.window = (now, 0);
+        }
+
+        let (window_start, window_bytes) = self.window;
+        let elapsed = now.duration_since(window_start);
+        if elapsed >= SAMPLE_WINDOW && bytes_done >= window_bytes {
+            let rate = (bytes_done - window_bytes) as f64 / elapsed.as_secs_f64();
+            match phase {
+                Phase::Pass(_) => self.write_rate.add(rate, elapsed),
+                Phase::Verify => self.verify_rate.add(rate, elapsed),
+            }
+            self.window = (now, bytes_done);
+        }
+
+        self.estimate(bytes_done)
+    }
+
+    fn estimate(&self, bytes_done: u64) -> Option<EtaEstimate> {
+        // Work left in the current phase at the current rate, and in later phases
+        // at the rate completed passes averaged
+        let (current_bytes, current_rate, later_bytes, later_rate) = match self.phase {
+            Phase::Pass(pass) => {
+                if !self.write_rate.is_ready() {
+                    return None;
+                }
+                let later_passes = self.total_passes.saturating_sub(pass) as u64;
+                let later_rate = if self.pass_rates.is_empty() {
+                    self.write_rate.mean
+                } else {
+                    self.pass_rates.iter().sum::<f64>() / self.pass_rates.len() as f64
+                };
+                (
+                    self.bytes_per_pass.saturating_sub(bytes_done),
+                    &self.write_rate,
+                    later_passes * self.bytes_per_pass + self.verify_bytes,
+                    later_rate,
+                )
+            }
+            Phase::Verify if self.verify_rate.is_ready() => {
+                (self.verify_bytes.saturating_sub(bytes_done), &self.verify_rate, 0, 1.0)
+            }
+            // Until reads have been measured, assume they are no faster than the writes were
+            Phase::Verify => {
+                if !self.write_rate.is_ready() {
+                    return None;
+                }
+                (self.verify_bytes.saturating_sub(bytes_done), &self.write_rate, 0, 1.0)
+            }
+        };
+
+        let expected = current_bytes as f64 / current_rate.mean + later_bytes as f64 / later_rate;
+        let spread = 2.0 * current_rate.relative_spread();
+        Some(EtaEstimate {
+            expected: Duration::from_secs_f64(expected),
+            earliest: Duration::from_secs_f64(expected / (1.0 + spread)),
+            latest: Duration::from_secs_f64(expected / (1.0 - spread).max(MIN_RATE_FRACTION)),
+        })
+    }
+}
+
+/// Where the rate of a pre-wipe estimate comes from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ThroughputSource {
+    /// End-to-end rate of earlier wipes of the same model
+    History,
+    /// Write rate the block size probe measured in earlier wipes of the model
+    Measured,
+    /// Typical rate for the kind of drive; the model has not been wiped here before
+    Nominal,
+    /// Erase time the drive reports in its identify data
+    DriveReported,
+}
+
+impl ThroughputSource {
+    /// Factors from the expected time to the earliest and latest
+    fn margins(&self) -> (f64, f64) {
+        match self {
+            ThroughputSource::History => (0.85, 1.25),
+            // A short burst at the start of the drive flatters the sustained rate
+            ThroughputSource::Measured => (0.9, 1.75),
+            ThroughputSource::Nominal => (0.5, 2.0),
+            ThroughputSource::DriveReported => (0.5, 1.5),
+        }
+    }
+}
+
+/// Write rate a pre-wipe estimate assumes
+#[derive(Debug, Clone, PartialEq)]
+pub struct WriteRate {
+    pub source: ThroughputSource,
+    /// Bytes per second, one per earlier wipe the rate is based on
+    pub samples: Vec<f64>,
+}
+
+impl WriteRate {
+    /// Typical rate for `target_type` on `bus`
+    pub fn nominal(target_type: TargetType, bus: &str) -> Self {
+        let mb_per_second = match target_type {
+            _ if bus.eq_ignore_ascii_case("nvme") => 1500.0,
+            TargetType::SSD => 400.0,
+            TargetType::HDD | TargetType::Tape => 150.0,
+            TargetType::Flash => 25.0,
+            TargetType::Optical => 5.0,
+        };
+        Self { source: ThroughputSource::Nominal, samples: vec![mb_per_second * MEGABYTE] }
+    }
+
+    /// Rate of the completed wipes of `model` in `records`; `None` when
+    /// there are none
+    pub fn from_history(model: &str, records: &[OperationRecord]) -> Option<Self> {
+        if model.trim().is_empty() {
+            return None;
+        }
+        let results: Vec<_> = records
+            .iter()
+            .filter(|record| record.status == OperationStatus::Completed)
+            .filter_map(|record| record.result.as_ref())
+            .filter(|result| !result.simulated && result.drive_geometry.model == model)
+            .collect();
+
+        let end_to_end: Vec<f64> = results
+            .iter()
+            .filter(|result| result.duration_seconds > 0 && result.sectors_wiped > 0)
+            .map(|result| {
+                let verified = result.verification_report.as_ref().map_or(0, |report| report.bytes_verified);
+                (result.sectors_wiped * 512 + verified) as f64 / result.duration_seconds as f64
+            })
+            .collect();
+        if !end_to_end.is_empty() {
+            return Some(Self { source: ThroughputSource::History, samples: end_to_end });
+        }
+
+        let probed: Vec<f64> = results
+            .iter()
+            .filter_map(|result| result.buffer_tuning.as_ref())
+            .map(|tuning| tuning.throughput_mbps * MEGABYTE)
+            .filter(|&rate| rate > 0.0)
+            .collect();
+        (!probed.is_empty()).then_some(Self { source: ThroughputSource::Measured, samples: probed })
+    }
+
+    fn mean(&self) -> f64 {
+        self.samples.iter().sum::<f64>() / self.samples.len().max(1) as f64
+    }
+}
+
+/// MB as the block size probe counts them
+const MEGABYTE: f64 = 1024.0 * 1024.0;
+
+/// How long a wipe should take, worked out before it starts
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DurationEstimate {
+    pub expected_seconds: u64,
+    pub earliest_seconds: u64,
+    pub latest_seconds: u64,
+    pub bytes_per_pass: u64,
+    pub passes: u32,
+    pub verify_bytes: u64,
+    /// Write rate assumed, MB/s; absent for a firmware erase
+    pub throughput_mbps: Option<f64>,
+    pub source: ThroughputSource,
+    /// Earlier wipes of the model the rate is based on
+    pub samples: usize,
+}
+
+impl DurationEstimate {
+    /// `passes` overwrites of `bytes_per_pass` and a read-back of
+    /// `verify_bytes`, which is assumed to run no faster than the writes
+    pub fn overwrite(bytes_per_pass: u64, passes: u32, verify_bytes: u64, rate: &WriteRate) -> Self {
+        let bytes = (bytes_per_pass * passes as u64 + verify_bytes) as f64;
+        let mean = rate.mean().max(1.0);
+        let fastest = rate.samples.iter().copied().fold(mean, f64::max);
+        let slowest = rate.samples.iter().copied().fold(mean, f64::min).max(1.0);
+        let (early, late) = rate.source.margins();
+        Self {
+            expected_seconds: (bytes / mean) as u64,
+            earliest_seconds: (bytes / fastest * early) as u64,
+            latest_seconds: (bytes / slowest * late) as u64,
+            bytes_per_pass,
+            passes,
+            verify_bytes,
+            throughput_mbps: Some(mean / MEGABYTE),
+            source: rate.source,
+            samples: if rate.source == ThroughputSource::Nominal { 0 } else { rate.samples.len() },
+        }
+    }
+
+    /// A firmware erase expected to take `erase_time`
+    pub fn firmware(erase_time: Duration, source: ThroughputSource) -> Self {
+        let seconds = erase_time.as_secs_f64();
+        let (early, late) = source.margins();
+        Self {
+            expected_seconds: seconds as u64,
+            earliest_seconds: (seconds * early) as u64,
+            latest_seconds: (seconds * late) as u64,
+            bytes_per_pass: 0,
+            passes: 0,
+            verify_bytes: 0,
+            throughput_mbps: None,
+            source,
+            samples: 0,
+        }
+    }
+
+    pub fn expected(&self) -> Duration {
+        Duration::from_secs(self.expected_seconds)
+    }
+
+    /// E.g. "~6h 20m (5h 25m - 7h 55m)"
+    pub fn summary(&self) -> String {
+        format!(
+            "~{} ({} - {})",
+            format_duration(self.expected()),
+            format_duration(Duration::from_secs(self.earliest_seconds)),
+            format_duration(Duration::from_secs(self.latest_seconds))
+        )
+    }
+}
+
+/// Hours and minutes, e.g. "6h 20m", or "45s" under a minute
+pub fn format_duration(duration: Duration) -> String {
+    let seconds = duration.as_secs();
+    let (hours, minutes) = (seconds / 3600, seconds % 3600 / 60);
+    match (hours, minutes) {
+        (0, 0) => format!("{}s", seconds),
+        (0, minutes) => format!("{}m", minutes),
+        (hours, minutes) => format!("{}h {:02}m", hours, minutes),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MB: u64 = 1024 * 1024;
+
+    #[test]
+    fn test_estimate_counts_remaining_passes() {
+        let start = Instant::now();
+        let mut eta = EtaEstimator::new(1000 * MB, 3, 0);
+        eta.window = (start, 0);
+        eta.phase_started = start;
+
+        // Steady 100 MB/s through the first half of pass 1
+        let mut estimate = None;
+        for second in 1..=5 {
+            estimate = eta.observe(Phase::Pass(1), second * 100 * MB, start + Duration::from_secs(second));
+        }
+        let estimate = estimate.unwrap();
+
+        // 500 MB left of this pass plus two more 1000 MB passes
+        assert_eq!(estimate.expected.as_secs(), 25);
+        assert_eq!(estimate.earliest, estimate.expected);
+        assert_eq!(estimate.latest, estimate.expected);
+
+        // A burst widens the bounds without moving the estimate far
+        let bursty = eta.observe(Phase::Pass(1), 900 * MB, start + Duration::from_secs(6)).unwrap();
+        assert!(bursty.earliest < bursty.expected && bursty.expected < bursty.latest);
+        assert!(bursty.expected.as_secs() >= 15);
+    }
+
+    #[test]
+    fn test_duration_before_start() {
+        // 1 TB, three passes and a full read-back at 200 MB/s
+        let rate = WriteRate { source: ThroughputSource::History, samples: vec![200.0 * MEGABYTE] };
+        let estimate = DurationEstimate::overwrite(1000 * 1000 * MB, 3, 1000 * 1000 * MB, &rate);
+        assert_eq!(estimate.expected_seconds, 20000);
+        assert!(estimate.earliest_seconds < estimate.expected_seconds);
+        assert!(estimate.latest_seconds > estimate.expected_seconds);
+        assert_eq!(estimate.summary(), "~5h 33m (4h 43m - 6h 56m)");
+
+        // The slowest earlier wipe sets the latest finish
+        let rate = WriteRate { source: ThroughputSource::History, samples: vec![100.0 * MEGABYTE, 300.0 * MEGABYTE] };
+        let spread = DurationEstimate::overwrite(1000 * 1000 * MB, 3, 1000 * 1000 * MB, &rate);
+        assert_eq!(spread.expected_seconds, estimate.expected_seconds);
+        assert_eq!(spread.latest_seconds, 50000);
+        assert_eq!(spread.samples, 2);
+
+        let nominal = DurationEstimate::overwrite(MB, 1, 0, &WriteRate::nominal(TargetType::SSD, "NVMe"));
+        assert_eq!((nominal.source, nominal.samples), (ThroughputSource::Nominal, 0));
+        assert!(WriteRate::from_history("", &[]).is_none());
+
+        assert_eq!(format_duration(Duration::from_secs(45)), "45s");
+        assert_eq!(format_duration(Duration::from_secs(45 * 60)), "45m");
+        assert_eq!(format_duration(Duration::from_secs(6 * 3600 + 20 * 60)), "6h 20m");
+    }
+}
@@ -0,0 +1,475 @@
+//! Evidence bundles and single reports handed to customers after a wipe
+//!
+//! A bundle collects, for one operation, the signed certificate (PDF and
+//! JSON), the audit report written with it, the verification data, the
+//! drive snapshots taken before and after the wipe, the excerpt of the audit
+//! log covering the operation and a SMART snapshot of the drive.
+//! `manifest.json` lists every file with its SHA-256 and notes whatever could
+//! not be included, so an incomplete bundle says so.
+//!
+//! A single report is one file in a `ReportFormat`, for front ends that let
+//! the operator save it where they like. CSV and XML also cover many
+//! operations at once, for the batch summaries recyclers hand their e-Stewards
+//! or R2 auditors and for import into asset management (ITAD) tools: one row
+//! or `<Asset>` per wipe, keyed by drive serial number.
+
+use chrono::{DateTime, Utc};
+use std::collections::HashMap;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use uuid::Uuid;
+
+use crate::core::{OperationRecord, OperationStatus, SanitizationStandard, WipeError, WipeErrorCode, WipeResult2};
+use crate::security::ErasureCertificate;
+
+/// Where the engine writes certificates and audit reports
+pub const DEFAULT_REPORTS_DIR: &str = "reports";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ReportFormat {
+    /// The certificate as printed
+    Pdf,
+    /// The signed certificate, or the operation record when none was issued
+    Json,
+    /// One summary row per operation with a header, for spreadsheets
+    Csv,
+    /// One `<Asset>` per operation, for asset management tools
+    Xml,
+}
+
+impl ReportFormat {
+    pub fn parse(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "pdf" => Some(Self::Pdf),
+            "json" => Some(Self::Json),
+            "csv" => Some(Self::Csv),
+            "xml" => Some(Self::Xml),
+            _ => None,
+        }
+    }
+
+    pub fn extension(self) -> &'static str {
+        match self {
+            Self::Pdf => "pdf",
+            Self::Json => "json",
+            Self::Csv => "csv",
+            Self::Xml => "xml",
+        }
+    }
+
+    /// Whether one file can hold many operations
+    pub fn is_batch(self) -> bool {
+        matches!(self, Self::Csv | Self::Xml)
+    }
+}
+
+/// Namespace of the XML report; bumped when elements change meaning
+pub const XML_REPORT_NAMESPACE: &str = "urn:hdd-tool:sanitization-report:1";
+
+/// `record` as a CSV header and one row
+pub fn operation_csv(record: &OperationRecord) -> String {
+    operations_csv(std::slice::from_ref(record))
+}
+
+/// `records` as a CSV header and one row each
+pub fn operations_csv(records: &[OperationRecord]) -> String {
+    let mut csv = String::from(
+        "operation_id,device,model,serial,standard,status,started_at,completed_at,passes,sectors_wiped,verified,operator,certificate_ids,error,capacity_bytes,techniques,simulated\n",
+    );
+    for record in records {
+        let result = record.result.as_ref();
+        let verified = result
+            .and_then(|result| result.verification_report.as_ref())
+            .map(|report| report.passed.to_string())
+            .unwrap_or_default();
+        let fields = [
+            record.operation_id.to_string(),
+            record.target_path.clone(),
+            result.map(|result| result.drive_geometry.model.clone()).unwrap_or_default(),
+            result.map(|result| result.drive_geometry.serial.clone()).unwrap_or_default(),
+            format!("{:?}", record.standard),
+            format!("{:?}", record.status),
+            record.started_at.to_rfc3339(),
+            record.completed_at.map(|at| at.to_rfc3339()).unwrap_or_default(),
+            result.map(|result| result.passes_completed.to_string()).unwrap_or_default(),
+            result.map(|result| result.sectors_wiped.to_string()).unwrap_or_default(),
+            verified,
+            record.operator.clone().unwrap_or_default(),
+            record.report_ids.join(" "),
+            record.error_message.clone().unwrap_or_default(),
+            result.map(|result| result.drive_geometry.user_capacity.to_string()).unwrap_or_default(),
+            result.map(|result| result.patterns_used.join("; ")).unwrap_or_default(),
+            result.map(|result| result.simulated.to_string()).unwrap_or_default(),
+        ];
+        let row: Vec<String> = fields.iter().map(|field| csv_field(field)).collect();
+        csv.push_str(&row.join(","));
+        csv.push('\n');
+    }
+    csv
+}
+
+/// `records` as an XML document, one `<Asset>` per operation
+pub fn operations_xml(records: &[OperationRecord], generated_at: DateTime<Utc>) -> String {
+    let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    xml.push_str(&format!(
+        "<SanitizationReport xmlns=\"{}\" generator=\"hdd-tool {}\" generated=\"{}\" count=\"{}\">\n",
+        XML_REPORT_NAMESPACE,
+        env!("CARGO_PKG_VERSION"),
+        generated_at.to_rfc3339(),
+        records.len()
+    ));
+    for record in records {
+        let result = record.result.as_ref();
+        xml.push_str("  <Asset>\n");
+        xml_element(&mut xml, 2, "AssetType", &format!("{:?}", record.target_type));
+        if let Some(result) = result {
+            let geometry = &result.drive_geometry;
+            xml_element(&mut xml, 2, "SerialNumber", &geometry.serial);
+            xml_element(&mut xml, 2, "Model", &geometry.model);
+            xml_element(&mut xml, 2, "Firmware", &geometry.firmware);
+            xml_element(&mut xml, 2, "CapacityBytes", &geometry.user_capacity.to_string());
+        }
+        xml_element(&mut xml, 2, "DevicePath", &record.target_path);
+
+        xml.push_str("    <Sanitization>\n");
+        xml_element(&mut xml, 3, "OperationId", &record.operation_id.to_string());
+        xml_element(&mut xml, 3, "Standard", &format!("{:?}", record.standard));
+        xml_element(&mut xml, 3, "Status", &format!("{:?}", record.status));
+        xml_element(&mut xml, 3, "StartedAt", &record.started_at.to_rfc3339());
+        if let Some(completed_at) = record.completed_at {
+            xml_element(&mut xml, 3, "CompletedAt", &completed_at.to_rfc3339());
+        }
+        if let Some(operator) = &record.operator {
+            xml_element(&mut xml, 3, "Technician", operator);
+        }
+        if let Some(result) = result {
+            xml_element(&mut xml, 3, "Passes", &result.passes_completed.to_string());
+            xml_element(&mut xml, 3, "SectorsWiped", &result.sectors_wiped.to_string());
+            if !result.patterns_used.is_empty() {
+                xml.push_str("      <Techniques>\n");
+                for technique in &result.patterns_used {
+                    xml_element(&mut xml, 4, "Technique", technique);
+                }
+                xml.push_str("      </Techniques>\n");
+            }
+            if let Some(report) = &result.verification_report {
+                xml.push_str(&format!(
+                    "      <Verification passed=\"{}\" coverage=\"{:.4}\" bytesVerified=\"{}\"/>\n",
+                    report.passed, report.coverage, report.bytes_verified
+                ));
+            }
+            xml_element(&mut xml, 3, "Simulated", &result.simulated.to_string());
+        }
+        for certificate_id in &record.report_ids {
+            xml_element(&mut xml, 3, "CertificateId", certificate_id);
+        }
+        if let Some(error) = &record.error_message {
+            xml_element(&mut xml, 3, "Error", error);
+        }
+        xml.push_str("    </Sanitization>\n");
+        xml.push_str("  </Asset>\n");
+    }
+    xml.push_str("</SanitizationReport>\n");
+    xml
+}
+
+fn xml_element(xml: &mut String, depth: usize, name: &str, value: &str) {
+    xml.push_str(&format!("{}<{}>{}</{}>\n", "  ".repeat(depth), name, xml_escape(value), name));
+}
+
+fn xml_escape(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            // Control characters other than tab and newlines are not allowed in XML 1.0
+            c if c.is_control() && !matches!(c, '\t' | '\n' | '\r') => escaped.push('\u{FFFD}'),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct BundleFile {
+    pub name: String,
+    pub contents: Vec<u8>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ManifestEntry {
+    pub name: String,
+    pub size_bytes: u64,
+    pub sha256: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BundleManifest {
+    pub operation_id: Uuid,
+    pub target_path: String,
+    pub serial_number: Option<String>,
+    pub exported_at: DateTime<Utc>,
+    pub exported_by: String,
+    pub files: Vec<ManifestEntry>,
+    /// Parts left out, and why
+    pub notes: Vec<String>,
+}
+
+pub struct EvidenceBundle {
+    pub manifest: BundleManifest,
+    pub files: Vec<BundleFile>,
+}
+
+impl EvidenceBundle {
+    pub fn new(manifest: BundleManifest) -> Self {
+        Self { manifest, files: Vec::new() }
+    }
+
+    pub fn add(&mut self, name: &str, contents: Vec<u8>) {
+        self.manifest.files.push(ManifestEntry {
+            name: name.to_string(),
+            size_bytes: contents.len() as u64,
+            sha256: hex::encode(Sha256::digest(&contents)),
+        });
+        self.files.push(BundleFile { name: name.to_string(), contents });
+    }
+
+    pub fn add_json<T: Serialize>(&mut self, name: &str, value: &T) -> WipeResult2<()> {
+        let contents = serde_json::to_vec_pretty(value).map_err(|e| export_error("serialize", name, e))?;
+        self.add(name, contents);
+        Ok(())
+    }
+
+    pub fn note(&mut self, note: impl Into<String>) {
+        self.manifest.notes.push(note.into());
+    }
+
+    fn manifest_json(&self) -> WipeResult2<Vec<u8>> {
+        serde_json::to_vec_pretty(&self.manifest).map_err(|e| export_error("serialize", "manifest.json", e))
+    }
+
+    /// Write the files and the manifest into `dir`, creating it if needed;
+    /// returns the paths written
+    pub fn write_dir<P: AsRef<Path>>(&self, dir: P) -> WipeResult2<Vec<PathBuf>> {
+        let dir = dir.as_ref();
+        fs::create_dir_all(dir).map_err(|e| export_error("create", &dir.display().to_string(), e))?;
+
+        let manifest = BundleFile { name: "manifest.json".to_string(), contents: self.manifest_json()? };
+        let mut written = Vec::new();
+        for file in self.files.iter().chain(std::iter::once(&manifest)) {
+            let path = dir.join(&file.name);
+            fs::write(&path, &file.contents).map_err(|e| export_error("write", &path.display().to_string(), e))?;
+            written.push(path);
+        }
+        Ok(written)
+    }
+
+    /// Write the files and the manifest into a zip archive at `path`
+    pub fn write_zip<P: AsRef<Path>>(&self, path: P) -> WipeResult2<()> {
+        let path = path.as_ref();
+        let name = path.display().to_string();
+        if let Some(parent) = path.parent().filter(|parent| !parent.as_os_str().is_empty()) {
+            fs::create_dir_all(parent).map_err(|e| export_error("create", &parent.display().to_string(), e))?;
+        }
+        let file = fs::File::create(path).map_err(|e| export_error("create", &name, e))?;
+
+        let mut zip = zip::ZipWriter::new(file);
+        let options = zip::write::SimpleFileOptions::default().compression_method(zip::CompressionMethod::Deflated);
+        let manifest = BundleFile { name: "manifest.json".to_string(), contents: self.manifest_json()? };
+        for file in self.files.iter().chain(std::iter::once(&manifest)) {
+            zip.start_file(file.name.as_str(), options).map_err(|e| export_error("write", &name, e))?;
+            zip.write_all(&file.contents).map_err(|e| export_error("write", &name, e))?;
+        }
+        zip.finish().map_err(|e| export_error("write", &name, e))?;
+        Ok(())
+    }
+}
+
+/// The signed JSON certificate with id `certificate_id` in `reports_dir`,
+/// with the PDF and audit report issued alongside it when present
+pub fn find_certificate_files<P: AsRef<Path>>(reports_dir: P, certificate_id: &str) -> Option<CertificateFiles> {
+    signed_certificates(reports_dir.as_ref())
+        .find(|(_, certificate)| certificate.certificate_id.to_string() == certificate_id)
+        .map(|(json, _)| CertificateFiles::beside(json))
+}
+
+/// The certificates of `records` that were issued, newest operation first as
+/// `records` are listed, with their files looked up in one pass over `reports_dir`
+pub fn report_summaries<P: AsRef<Path>>(records: &[OperationRecord], reports_dir: P) -> Vec<ReportSummary> {
+    let files: HashMap<String, CertificateFiles> = signed_certificates(reports_dir.as_ref())
+        .map(|(json, certificate)| (certificate.certificate_id.to_string(), CertificateFiles::beside(json)))
+        .collect();
+    records
+        .iter()
+        .flat_map(|record| {
+            record.report_ids.iter().map(|report_id| ReportSummary::new(record, report_id, files.get(report_id).cloned()))
+        })
+        .collect()
+}
+
+/// `certificate_*.json` files in `reports_dir` that parse as certificates
+fn signed_certificates(reports_dir: &Path) -> impl Iterator<Item = (PathBuf, ErasureCertificate)> {
+    fs::read_dir(reports_dir)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| {
+            let name = path.file_name().and_then(|name| name.to_str()).unwrap_or_default();
+            name.starts_with("certificate_") && name.ends_with(".json")
+        })
+        .filter_map(|path| {
+            let certificate = serde_json::from_str(&fs::read_to_string(&path).ok()?).ok()?;
+            Some((path, certificate))
+        })
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CertificateFiles {
+    pub json: PathBuf,
+    pub pdf: Option<PathBuf>,
+    pub audit_report: Option<PathBuf>,
+}
+
+impl CertificateFiles {
+    // reports/certificate_<serial>_<time>.json goes with .pdf and audit_<serial>_<time>.json
+    fn beside(json: PathBuf) -> Self {
+        let pdf = json.with_extension("pdf");
+        let name = json.file_name().and_then(|name| name.to_str()).unwrap_or_default();
+        let audit_report = json.with_file_name(name.replacen("certificate_", "audit_", 1));
+        Self {
+            pdf: pdf.exists().then_some(pdf),
+            audit_report: audit_report.exists().then_some(audit_report),
+            json,
+        }
+    }
+}
+
+/// One issued certificate, as a report list in a front end shows it
+#[derive(Debug, Clone, Serialize)]
+pub struct ReportSummary {
+    /// The certificate id
+    pub report_id: String,
+    pub operation_id: Uuid,
+    pub target_path: String,
+    pub serial_number: Option<String>,
+    pub model: Option<String>,
+    pub standard: SanitizationStandard,
+    pub status: OperationStatus,
+    pub started_at: DateTime<Utc>,
+    pub completed_at: Option<DateTime<Utc>>,
+    pub operator: Option<String>,
+    /// None when the certificate is no longer in the reports directory
+    pub files: Option<CertificateFiles>,
+}
+
+impl ReportSummary {
+    pub(crate) fn new(record: &OperationRecord, report_id: &str, files: Option<CertificateFiles>) -> Self {
+        let geometry = record.result.as_ref().map(|result| &result.drive_geometry);
+        Self {
+            report_id: report_id.to_string(),
+            operation_id: record.operation_id,
+            target_path: record.target_path.clone(),
+            serial_number: geometry.map(|geometry| geometry.serial.clone()),
+            model: geometry.map(|geometry| geometry.model.clone()),
+            standard: record.standard,
+            status: record.status,
+            started_at: record.started_at,
+            completed_at: record.completed_at,
+            operator: record.operator.clone(),
+            files,
+        }
+    }
+}
+
+/// A past certificate opened from the report list
+#[derive(Debug, Clone, Serialize)]
+pub struct StoredReport {
+    pub summary: ReportSummary,
+    pub certificate: ErasureCertificate,
+}
+
+fn export_error(action: &str, name: &str, error: impl std::fmt::Display) -> WipeError {
+    WipeError {
+        code: WipeErrorCode::UnknownError,
+        message: format!("Failed to {} {}: {}", action, name, error),
+        sector: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::TargetType;
+
+    fn record(target_path: &str, error: Option<&str>) -> OperationRecord {
+        OperationRecord {
+            operation_id: Uuid::new_v4(),
+            target_path: target_path.to_string(),
+            target_type: TargetType::HDD,
+            standard: SanitizationStandard::NIST_SP_800_88_R1,
+            status: OperationStatus::Failed,
+            started_at: Utc::now(),
+            completed_at: None,
+            progress_snapshots: Vec::new(),
+            result: None,
+            report_ids: Vec::new(),
+            error_message: error.map(str::to_string),
+            operator: Some("alice".to_string()),
+            pattern_seed: None,
+            uploads: Vec::new(),
+            events: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_batch_reports_have_one_entry_per_operation() {
+        let records = [record("/dev/sda", Some("Write failed, \"sector\" 12")), record("/dev/sdb", Some("<none> & more"))];
+
+        let csv = operations_csv(&records);
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines.len(), 3);
+        assert_eq!(lines[1].split(',').nth(1), Some("/dev/sda"));
+        assert!(lines[1].contains("\"Write failed, \"\"sector\"\" 12\""));
+
+        let xml = operations_xml(&records, Utc::now());
+        assert_eq!(xml.matches("<Asset>").count(), 2);
+        assert!(xml.contains("count=\"2\""));
+        assert!(xml.contains("<Error>&lt;none&gt; &amp; more</Error>"));
+        assert!(xml.contains("<Technician>alice</Technician>"));
+        assert!(xml.trim_end().ends_with("</SanitizationReport>"));
+    }
+
+    #[test]
+    fn test_report_list_has_one_entry_per_certificate() {
+        let mut first = record("/dev/sda", None);
+        first.report_ids = vec!["a".to_string(), "b".to_string()];
+        first.status = OperationStatus::Completed;
+        let second = record("/dev/sdb", Some("Write failed"));
+        let dir = tempfile::tempdir().unwrap();
+        let reports_dir = dir.path().join("reports");
+
+        let reports = report_summaries(&[first.clone(), second], &reports_dir);
+        let ids: Vec<&str> = reports.iter().map(|report| report.report_id.as_str()).collect();
+        assert_eq!(ids, ["a", "b"]);
+        assert!(reports.iter().all(|report| report.operation_id == first.operation_id && report.files.is_none()));
+        assert_eq!(reports[0].status, OperationStatus::Completed);
+        assert_eq!(reports[0].operator.as_deref(), Some("alice"));
+    }
+}
@@ -0,0 +1,497 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use uuid::Uuid;
+
+use crate::core::{
+    SanitizationStandard, TargetType, WipeRequest, WipeProgress, WipeResult,
+    WipeError, WipeErrorCode, WipeResult2, UploadState, UploadStatus, TimelineEvent,
+};
+use crate::pattern_stream::SealedSeed;
+
+/// Default location of the persistent operation history
+pub const DEFAULT_HISTORY_PATH: &str = "history/operations.jsonl";
+
+/// Progress snapshots kept per operation; older ones are thinned out evenly
+const MAX_PROGRESS_SNAPSHOTS: usize = 64;
+
+/// Superseded lines tolerated in the history file before it is compacted
+const COMPACT_SLACK: usize = 256;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum OperationStatus {
+    InProgress,
+    /// The last pass is written and the device is being read back
+    Verifying,
+    Completed,
+    Failed,
+    /// Operation was in progress when the application last exited
+    Interrupted,
+}
+
+impl OperationStatus {
+    /// Still writing or verifying
+    pub fn is_running(self) -> bool {
+        matches!(self, OperationStatus::InProgress | OperationStatus::Verifying)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProgressSnapshot {
+    pub timestamp: DateTime<Utc>,
+    pub current_pass: u32,
+    pub total_passes: u32,
+    pub percentage: f64,
+    pub sectors_processed: u64,
+    pub current_operation: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OperationRecord {
+    pub operation_id: Uuid,
+    pub target_path: String,
+    pub target_type: TargetType,
+    pub standard: SanitizationStandard,
+    pub status: OperationStatus,
+    pub started_at: DateTime<Utc>,
+    pub completed_at: Option<DateTime<Utc>>,
+    pub progress_snapshots: Vec<ProgressSnapshot>,
+    pub result: Option<WipeResult>,
+    pub report_ids: Vec<String>,
+    pub error_message: Option<String>,
+    /// Authenticated operator who started the operation
+    #[serde(default)]
+    pub operator: Option<String>,
+    /// Encrypted seed of the random passes, when seed capture is enabled
+    #[serde(default)]
+    pub pattern_seed: Option<SealedSeed>,
+    /// Delivery of the certificates and reports to the configured upload destinations
+    #[serde(default)]
+    pub uploads: Vec<UploadStatus>,
+    /// What the operation did, oldest first; stored when it ends
+    #[serde(default)]
+    pub events: Vec<TimelineEvent>,
+}
+
+/// Filter used when listing operation history; unset fields match everything
+#[derive(Debug, Clone, Default)]
+pub struct HistoryFilter {
+    pub target_path: Option<String>,
+    pub serial: Option<String>,
+    pub status: Option<OperationStatus>,
+    pub since: Option<DateTime<Utc>>,
+    pub until: Option<DateTime<Utc>>,
+    pub operator: Option<String>,
+    pub limit: Option<usize>,
+}
+
+impl HistoryFilter {
+    fn matches(&self, record: &OperationRecord) -> bool {
+        if let Some(ref path) = self.target_path
+            && &record.target_path != path
+        {
+            return false;
+        }
+        if let Some(ref serial) = self.serial {
+            let record_serial = record.result.as_ref().map(|r| r.drive_geometry.serial.as_str());
+            if record_serial != Some(serial.as_str()) {
+                return false;
+            }
+        }
+        if let Some(status) = self.status
+            && record.status != status
+        {
+            return false;
+        }
+        if let Some(since) = self.since
+            && record.started_at < since
+        {
+            return false;
+        }
+        if let Some(until) = self.until
+            && record.started_at > until
+        {
+            return false;
+        }
+        if let Some(ref operator) = self.operator
+            && record.operator.as_ref() != Some(operator)
+        {
+            return false;
+        }
+        true
+    }
+}
+
+/// Persistent store of wipe operations, kept next to the certificates and
+/// reports directories so history survives restarts.
+///
+/// The file holds one JSON record per line. A change appends the operation's
+/// whole record and a later line replaces an earlier one, so a save costs one
+/// record rather than the entire history; the file is rewritten with one line
+/// per operation once enough lines are superseded. A history written as a
+/// single JSON array by earlier versions is still read, and converted on the
+/// first save.
+pub struct OperationHistory {
+    path: PathBuf,
+    records: Vec<OperationRecord>,
+    /// Lines in the file, superseded ones included
+    lines: usize,
+    /// The file does not hold `records`, which must be rewritten on the next save
+    stale: bool,
+}
+
+impl OperationHistory {
+    /// Open the history without writing to it, so a running wipe in another
+    /// process is not disturbed until this one records something
+    pub fn open<P: AsRef<Path>>(path: P) -> WipeResult2<Self> {
+        let path = path.as_ref().to_path_buf();
+        let (mut records, lines) = Self::load(&path)?;
+        let mut stale = false;
+
+        // Anything still marked in progress did not survive the last shutdown
+        for record in records.iter_mut() {
+            if record.status.is_running() {
+                record.status = OperationStatus::Interrupted;
+                stale = true;
+            }
+        }
+
+        Ok(Self { path, records, lines, stale })
+    }
+
+    /// Read the records as the writing process left them, without marking
+    /// in-progress operations as interrupted; for watching another process's wipes
+    pub fn read_records<P: AsRef<Path>>(path: P) -> WipeResult2<Vec<OperationRecord>> {
+        Self::load(path.as_ref()).map(|(records, _)| records)
+    }
+
+    /// Records and the number of lines they were read from
+    fn load(path: &Path) -> WipeResult2<(Vec<OperationRecord>, usize)> {
+        if !path.exists() {
+            return Ok((Vec::new(), 0));
+        }
+
+        let content = fs::read_to_string(path).map_err(|e| history_error(format!("Failed to read operation history: {}", e)))?;
+
+        // A line torn by a crash while it was appended is skipped
+        let mut records: Vec<OperationRecord> = Vec::new();
+        let mut index: HashMap<Uuid, usize> = HashMap::new();
+        let mut lines = 0;
+        for line in content.lines().filter(|line| !line.trim().is_empty()) {
+            lines += 1;
+            let Ok(record) = serde_json::from_str::<OperationRecord>(line) else {
+                continue;
+            };
+            match index.get(&record.operation_id) {
+                Some(&position) => records[position] = record,
+                None => {
+                    index.insert(record.operation_id, records.len());
+                    records.push(record);
+                }
+            }
+        }
+        Ok((records, lines))
+    }
+
+    pub fn record_started(&mut self, request: &WipeRequest) -> WipeResult2<()> {
+        self.records.push(OperationRecord {
+            operation_id: request.id,
+            target_path: request.target_path.clone(),
+            target_type: request.target_type,
+            standard: request.standard,
+            status: OperationStatus::InProgress,
+            started_at: request.timestamp,
+            completed_at: None,
+            progress_snapshots: Vec::new(),
+            result: None,
+            report_ids: Vec::new(),
+            error_message: None,
+            operator: request.operator.clone(),
+            pattern_seed: None,
+            uploads: Vec::new(),
+            events: Vec::new(),
+        });
+        self.save(request.id)
+    }
+
+    pub fn record_progress(&mut self, progress: &WipeProgress) -> WipeResult2<()> {
+        if let Some(record) = self.find_mut(progress.request_id) {
+            record.progress_snapshots.push(ProgressSnapshot {
+                timestamp: Utc::now(),
+                current_pass: progress.current_pass,
+                total_passes: progress.total_passes,
+                percentage: progress.percentage,
+                sectors_processed: progress.sectors_processed,
+                current_operation: progress.current_operation.clone(),
+            });
+            if record.progress_snapshots.len() > MAX_PROGRESS_SNAPSHOTS {
+                thin_snapshots(&mut record.progress_snapshots);
+            }
+            if progress.verifying && record.status == OperationStatus::InProgress {
+                record.status = OperationStatus::Verifying;
+            }
+        }
+        self.save(progress.request_id)
+    }
+
+    pub fn record_result(&mut self, result: &WipeResult) -> WipeResult2<()> {
+        if let Some(record) = self.find_mut(result.request_id) {
+            record.status = if result.success {
+                OperationStatus::Completed
+            } else {
+                OperationStatus::Failed
+            };
+            record.completed_at = Some(result.completion_time);
+            record.error_message = result.error_message.clone();
+            record.result = Some(result.clone());
+        }
+        self.save(result.request_id)
+    }
+
+    pub fn record_failure(&mut self, operation_id: Uuid, error: &WipeError) -> WipeResult2<()> {
+        if let Some(record) = self.find_mut(operation_id) {
+            record.status = OperationStatus::Failed;
+            record.completed_at = Some(Utc::now());
+            record.error_message = Some(error.to_string());
+        }
+        self.save(operation_id)
+    }
+
+    /// Mark a wipe the journal shows was cut short, adding it when the history
+    /// was saved before it started
+    pub fn record_interrupted(&mut self, request: &WipeRequest, last_seen_at: DateTime<Utc>) -> WipeResult2<()> {
+        if self.get(request.id).is_none() {
+            self.record_started(request)?;
+        }
+        if let Some(record) = self.find_mut(request.id) {
+            record.status = OperationStatus::Interrupted;
+            record.completed_at = Some(last_seen_at);
+        }
+        self.save(request.id)
+    }
+
+    pub fn attach_report(&mut self, operation_id: Uuid, report_id: &str) -> WipeResult2<()> {
+        if let Some(record) = self.find_mut(operation_id) {
+            record.report_ids.push(report_id.to_string());
+        }
+        self.save(operation_id)
+    }
+
+    pub fn attach_pattern_seed(&mut self, operation_id: Uuid, sealed: SealedSeed) -> WipeResult2<()> {
+        if let Some(record) = self.find_mut(operation_id) {
+            record.pattern_seed = Some(sealed);
+        }
+        self.save(operation_id)
+    }
+
+    pub fn record_events(&mut self, operation_id: Uuid, events: Vec<TimelineEvent>) -> WipeResult2<()> {
+        if let Some(record) = self.find_mut(operation_id) {
+            record.events = events;
+        }
+        self.save(operation_id)
+    }
+
+    /// Add the upload of a file to a destination, or replace its status
+    pub fn record_upload(&mut self, operation_id: Uuid, status: &UploadStatus) -> WipeResult2<()> {
+        if let Some(record) = self.find_mut(operation_id) {
+            match record.uploads.iter_mut().find(|u| u.destination == status.destination && u.file == status.file) {
+                Some(upload) => *upload = status.clone(),
+                None => record.uploads.push(status.clone()),
+            }
+        }
+        self.save(operation_id)
+    }
+
+    /// Uploads not yet delivered or given up on, with their operations
+    pub fn pending_uploads(&self) -> Vec<(Uuid, UploadStatus)> {
+        self.records
+            .iter()
+            .flat_map(|record| {
+                record.uploads.iter()
+                    .filter(|upload| upload.state == UploadState::Pending)
+                    .map(|upload| (record.operation_id, upload.clone()))
+            })
+            .collect()
+    }
+
+    pub fn get(&self, operation_id: Uuid) -> Option<&OperationRecord> {
+        self.records.iter().find(|r| r.operation_id == operation_id)
+    }
+
+    /// List matching operations, newest first
+    pub fn list(&self, filter: &HistoryFilter) -> Vec<OperationRecord> {
+        let mut matching: Vec<OperationRecord> = self.records
+            .iter()
+            .filter(|record| filter.matches(record))
+            .cloned()
+            .collect();
+
+        matching.sort_by_key(|record| std::cmp::Reverse(record.started_at));
+
+        if let Some(limit) = filter.limit {
+            matching.truncate(limit);
+        }
+
+        matching
+    }
+
+    /// Remove finished operations that started before `before`; returns how many were removed
+    pub fn purge_before(&mut self, before: DateTime<Utc>) -> WipeResult2<usize> {
+        let original_len = self.records.len();
+        self.records.retain(|record| {
+            record.status.is_running() || record.started_at >= before
+        });
+
+        let removed = original_len - self.records.len();
+        if removed > 0 {
+            self.compact()?;
+        }
+        Ok(removed)
+    }
+
+    fn find_mut(&mut self, operation_id: Uuid) -> Option<&mut OperationRecord> {
+        self.records.iter_mut().find(|r| r.operation_id == operation_id)
+    }
+
+    /// Append the record of `operation_id`, or rewrite the whole file when it
+    /// is out of date or mostly superseded lines
+    fn save(&mut self, operation_id: Uuid) -> WipeResult2<()> {
+        if self.stale || self.lines > self.records.len() * 2 + COMPACT_SLACK {
+            return self.compact();
+        }
+        let Some(record) = self.get(operation_id) else {
+            return Ok(());
+        };
+
+        let mut line = serde_json::to_string(record).map_err(|e| history_error(format!("Failed to serialize operation history: {}", e)))?;
+        line.push('\n');
+        self.create_dir()?;
+        OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .and_then(|mut file| file.write_all(line.as_bytes()))
+            .map_err(|e| history_error(format!("Failed to write operation history: {}", e)))?;
+        self.lines += 1;
+        Ok(())
+    }
+
+    /// Rewrite the file with one line per operation
+    fn compact(&mut self) -> WipeResult2<()> {
+        self.create_dir()?;
+        let mut content = String::new();
+        for record in &self.records {
+            content.push_str(
+                &serde_json::to_string(record).map_err(|e| history_error(format!("Failed to serialize operation history: {}", e)))?,
+            );
+            content.push('\n');
+        }
+
+        // Write to a temporary file first so a crash never leaves a truncated history
+        let tmp_path = self.path.with_extension("jsonl.tmp");
+        fs::write(&tmp_path, content)
+            .and_then(|_| fs::rename(&tmp_path, &self.path))
+            .map_err(|e| history_error(format!("Failed to write operation history: {}", e)))?;
+        self.lines = self.records.len();
+        self.stale = false;
+        Ok(())
+    }
+
+    fn create_dir(&self) -> WipeResult2<()> {
+        match self.path.parent() {
+            Some(parent) => {
+                fs::create_dir_all(parent).map_err(|e| history_error(format!("Failed to create history directory: {}", e)))
+            }
+            None => Ok(()),
+        }
+    }
+}
+
+/// Drop every other snapshot, keeping the first and the latest
+fn thin_snapshots(snapshots: &mut Vec<ProgressSnapshot>) {
+    let last = snapshots.len() - 1;
+    let mut position = 0;
+    snapshots.retain(|_| {
+        let keep = position % 2 == 0 || position == last;
+        position += 1;
+        keep
+    });
+}
+
+fn history_error(message: String) -> WipeError {
+    WipeError {
+        code: WipeErrorCode::UnknownError,
+        message,
+        sector: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{WipeEngine, WipeScope};
+
+    fn progress(request_id: Uuid, percentage: f64) -> WipeProgress {
+        WipeProgress {
+            request_id,
+            current_pass: 1,
+            total_passes: 1,
+            sectors_processed: percentage as u64,
+            total_sectors: 100,
+            percentage,
+            current_operation: "Writing zeros".to_string(),
+            estimated_completion: None,
+            estimated_completion_earliest: None,
+            estimated_completion_latest: None,
+            verifying: false,
+        }
+    }
+
+    #[test]
+    fn test_history_appends_records_and_bounds_snapshots() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("operations.jsonl");
+        let request = WipeEngine::build_request(
+            "/dev/sdb".to_string(),
+            TargetType::HDD,
+            WipeScope::WholeDevice,
+            SanitizationStandard::NIST_SP_800_88_R1,
+            true,
+            false,
+        );
+
+        let mut history = OperationHistory::open(&path).unwrap();
+        history.record_started(&request).unwrap();
+        for step in 0..500 {
+            history.record_progress(&progress(request.id, step as f64 / 5.0)).unwrap();
+        }
+        let snapshots = &history.get(request.id).unwrap().progress_snapshots;
+        assert!(snapshots.len() <= MAX_PROGRESS_SNAPSHOTS);
+        assert_eq!(snapshots.first().unwrap().percentage, 0.0);
+        assert_eq!(snapshots.last().unwrap().percentage, 99.8);
+        // Superseded lines are compacted away
+        assert!(history.lines <= 1 + 2 + COMPACT_SLACK);
+        drop(history);
+
+        // The power went out in the middle of a line
+        let mut file = OpenOptions::new().append(true).open(&path).unwrap();
+        file.write_all(b"{\"operation_id\":\"").unwrap();
+        drop(file);
+
+        // Opening marks the wipe interrupted without writing it for a reader
+        let history = OperationHistory::open(&path).unwrap();
+        assert_eq!(history.get(request.id).unwrap().status, OperationStatus::Interrupted);
+        assert_eq!(OperationHistory::read_records(&path).unwrap()[0].status, OperationStatus::InProgress);
+        drop(history);
+
+        let mut history = OperationHistory::open(&path).unwrap();
+        history.attach_report(request.id, "report-1").unwrap();
+        let records = OperationHistory::read_records(&path).unwrap();
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].status, OperationStatus::Interrupted);
+        assert_eq!(records[0].report_ids, vec!["report-1".to_string()]);
+    }
+}
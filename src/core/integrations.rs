@@ -0,0 +1,428 @@
+//! Lifecycle notifications for external systems
+//!
+//! Asset databases (ServiceNow, Snipe-IT, ...) need to learn when a drive has
+//! been wiped. The engine publishes a `LifecycleEvent` when a wipe starts and
+//! when it completes, fails or is cancelled. Every registered
+//! `WipeEventSink` receives it on a background thread, so a slow or
+//! unreachable integration never holds up a wipe.
+//!
+//! Failed deliveries are retried with exponential backoff. A delivery that
+//! still fails after the sink's last attempt is appended to a dead-letter
+//! file so it can be replayed by hand.
+//!
+//! Webhooks configured in `WipeConfiguration::webhooks` are built-in sinks
+//! (feature `webhooks`). Each POST carries the event as JSON and an
+//! HMAC-SHA256 of the body in `X-HDD-Tool-Signature`. Syslog and the Windows
+//! Event Log are configured sinks as well, see `core::siem`, and so are email
+//! notifications, see `core::email`.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+use tracing::{error, warn};
+use uuid::Uuid;
+
+/// Deliveries that exhausted their retries, one JSON line each
+pub const DEFAULT_DEAD_LETTER_PATH: &str = "integrations/failed_deliveries.jsonl";
+
+/// Header carrying `sha256=<hex HMAC of the body>`
+pub const SIGNATURE_HEADER: &str = "X-HDD-Tool-Signature";
+
+/// Wait before the first retry of a delivery; each later retry doubles it
+pub const DEFAULT_RETRY_DELAY: Duration = Duration::from_secs(2);
+
+/// Longest wait between two attempts of one delivery
+const MAX_RETRY_DELAY: Duration = Duration::from_secs(300);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum LifecycleEventKind {
+    WipeStarted,
+    WipeCompleted,
+    WipeFailed,
+    WipeCancelled,
+    /// Read-back verification finished; sent before `WipeCompleted`
+    VerificationCompleted,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LifecycleEvent {
+    /// Unique per event; receivers can use it to drop duplicate deliveries
+    pub event_id: Uuid,
+    pub kind: LifecycleEventKind,
+    pub occurred_at: DateTime<Utc>,
+    pub operation_id: Uuid,
+    pub target_path: String,
+    pub scope: String,
+    pub standard: String,
+    pub operator: Option<String>,
+    pub serial_number: Option<String>,
+    pub certificate_id: Option<String>,
+    pub certificate_path: Option<String>,
+    pub error: Option<String>,
+    #[serde(default)]
+    pub verification_passed: Option<bool>,
+    #[serde(default)]
+    pub bytes_verified: Option<u64>,
+}
+
+impl LifecycleEvent {
+    pub fn new(kind: LifecycleEventKind, request: &crate::core::WipeRequest) -> Self {
+        Self {
+            event_id: Uuid::new_v4(),
+            kind,
+            occurred_at: Utc::now(),
+            operation_id: request.id,
+            target_path: request.target_path.clone(),
+            scope: request.scope.description(),
+            standard: format!("{:?}", request.standard),
+            operator: request.operator.clone(),
+            serial_number: None,
+            certificate_id: None,
+            certificate_path: None,
+            error: None,
+            verification_passed: None,
+            bytes_verified: None,
+        }
+    }
+}
+
+/// Receiver of wipe lifecycle events. Implement this to push results into an
+/// asset database or ticketing system; returning an error schedules a retry.
+pub trait WipeEventSink: Send + Sync {
+    /// Name used in logs and the dead-letter file
+    fn name(&self) -> String;
+
+    fn accepts(&self, _kind: LifecycleEventKind) -> bool {
+        true
+    }
+
+    /// Attempts per event before it is dead-lettered
+    fn max_attempts(&self) -> u32 {
+        5
+    }
+
+    fn deliver(&self, event: &LifecycleEvent) -> Result<(), String>;
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WebhookConfig {
+    pub url: String,
+    /// Environment variable holding the HMAC secret, so the secret stays out
+    /// of configuration files and the audit log
+    #[serde(default)]
+    pub secret_env: Option<String>,
+    /// Events to send; all of them when empty
+    #[serde(default)]
+    pub events: Vec<LifecycleEventKind>,
+    #[serde(default = "default_max_attempts")]
+    pub max_attempts: u32,
+}
+
+fn default_max_attempts() -> u32 { 8 }
+
+/// `sha256=<hex>` HMAC of `body`, as sent in `X-HDD-Tool-Signature`
+pub fn sign_payload(secret: &[u8], body: &[u8]) -> String {
+    let key = ring::hmac::Key::new(ring::hmac::HMAC_SHA256, secret);
+    format!("sha256={}", hex::encode(ring::hmac::sign(&key, body).as_ref()))
+}
+
+/// Check a received `X-HDD-Tool-Signature` value in constant time
+pub fn verify_payload_signature(secret: &[u8], body: &[u8], signature: &str) -> bool {
+    let Some(mac) = signature.strip_prefix("sha256=").and_then(|h| hex::decode(h).ok()) else {
+        return false;
+    };
+    let key = ring::hmac::Key::new(ring::hmac::HMAC_SHA256, secret);
+    ring::hmac::verify(&key, body, &mac).is_ok()
+}
+
+/// HTTP callback configured in `WipeConfiguration::webhooks`
+#[cfg(feature = "webhooks")]
+pub struct WebhookSink {
+    config: WebhookConfig,
+    secret: Option<Vec<u8>>,
+    /// Built on first delivery: a blocking client must not be created inside
+    /// an async runtime, and deliveries always run on the dispatcher thread
+    client: std::sync::OnceLock<reqwest::blocking::Client>,
+}
+
+#[cfg(feature = "webhooks")]
+impl WebhookSink {
+    pub fn new(config: WebhookConfig) -> Self {
+        let secret = config.secret_env.as_ref().and_then(|name| match std::env::var(name) {
+            Ok(secret) => Some(secret.into_bytes()),
+            Err(_) => {
+                warn!(webhook = %config.url, variable = %name, "Webhook secret is not set, payloads will be unsigned");
+                None
+            }
+        });
+        Self { config, secret, client: std::sync::OnceLock::new() }
+    }
+}
+
+#[cfg(feature = "webhooks")]
+impl WipeEventSink for WebhookSink {
+    fn name(&self) -> String {
+        format!("webhook {}", self.config.url)
+    }
+
+    fn accepts(&self, kind: LifecycleEventKind) -> bool {
+        self.config.events.is_empty() || self.config.events.contains(&kind)
+    }
+
+    fn max_attempts(&self) -> u32 {
+        self.config.max_attempts.max(1)
+    }
+
+    fn deliver(&self, event: &LifecycleEvent) -> Result<(), String> {
+        let body = serde_json::to_vec(event).map_err(|e| e.to_string())?;
+        let client = self.client.get_or_init(|| {
+            reqwest::blocking::Client::builder()
+                .timeout(Duration::from_secs(30))
+                .build()
+                .unwrap_or_else(|_| reqwest::blocking::Client::new())
+        });
+        let mut request = client
+            .post(&self.config.url)
+            .header("content-type", "application/json")
+            .header("X-HDD-Tool-Event", format!("{:?}", event.kind))
+            .header("X-HDD-Tool-Delivery", event.event_id.to_string());
+        if let Some(secret) = &self.secret {
+            request = request.header(SIGNATURE_HEADER, sign_payload(secret, &body));
+        }
+
+        let response = request.body(body).send().map_err(|e| e.to_string())?;
+        if response.status().is_success() {
+            Ok(())
+        } else {
+            Err(format!("HTTP {}", response.status()))
+        }
+    }
+}
+
+/// Sinks for the configured webhooks
+pub fn webhook_sinks(webhooks: &[WebhookConfig]) -> Vec<Arc<dyn WipeEventSink>> {
+    #[cfg(feature = "webhooks")]
+    return webhooks.iter().cloned().map(|config| Arc::new(WebhookSink::new(config)) as Arc<dyn WipeEventSink>).collect();
+
+    #[cfg(not(feature = "webhooks"))]
+    {
+        if !webhooks.is_empty() {
+            warn!(webhooks = webhooks.len(), "Webhooks configured but this build has no webhook support (feature \"webhooks\")");
+        }
+        Vec::new()
+    }
+}
+
+/// Delivery that used up its attempts, as written to the dead-letter file
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FailedDelivery {
+    pub sink: String,
+    pub attempts: u32,
+    pub last_error: String,
+    pub failed_at: DateTime<Utc>,
+    pub event: LifecycleEvent,
+}
+
+struct PendingDelivery {
+    sink: Arc<dyn WipeEventSink>,
+    event: Arc<LifecycleEvent>,
+    attempts: u32,
+    due: Instant,
+}
+
+#[derive(Default)]
+struct SinkSet {
+    custom: Vec<Arc<dyn WipeEventSink>>,
+    /// Built from the engine configuration
+    configured: Vec<Arc<dyn WipeEventSink>>,
+}
+
+/// Fans lifecycle events out to the registered sinks on a worker thread
+pub struct EventDispatcher {
+    sinks: Arc<Mutex<SinkSet>>,
+    sender: Mutex<mpsc::Sender<LifecycleEvent>>,
+}
+
+impl EventDispatcher {
+    pub fn new() -> Self {
+        Self::with_settings(PathBuf::from(DEFAULT_DEAD_LETTER_PATH), DEFAULT_RETRY_DELAY)
+    }
+
+    /// Dispatcher writing exhausted deliveries to `dead_letter_path`; the
+    /// n-th retry waits `retry_delay * 2^(n-1)`
+    pub fn with_settings(dead_letter_path: PathBuf, retry_delay: Duration) -> Self {
+        let sinks = Arc::new(Mutex::new(SinkSet::default()));
+        let (sender, receiver) = mpsc::channel::<LifecycleEvent>();
+
+        let worker_sinks = sinks.clone();
+        thread::spawn(move || {
+            let mut queue: Vec<PendingDelivery> = Vec::new();
+            loop {
+                // Sleep until the next retry is due or a new event arrives
+                let next_due = queue.iter().map(|d| d.due).min();
+                let received = match next_due {
+                    Some(due) => receiver.recv_timeout(due.saturating_duration_since(Instant::now())),
+                    None => receiver.recv().map_err(|_| RecvTimeoutError::Disconnected),
+                };
+                // When the engine is gone, make a last attempt at everything queued
+                let closing = matches!(received, Err(RecvTimeoutError::Disconnected));
+                if let Ok(event) = received {
+                    let event = Arc::new(event);
+                    let sinks = worker_sinks.lock().unwrap();
+                    for sink in sinks.custom.iter().chain(sinks.configured.iter()) {
+                        if sink.accepts(event.kind) {
+                            queue.push(PendingDelivery { sink: sink.clone(), event: event.clone(), attempts: 0, due: Instant::now() });
+                        }
+                    }
+                }
+
+                let now = Instant::now();
+                let (due, waiting): (Vec<_>, Vec<_>) = queue.drain(..).partition(|d| closing || d.due <= now);
+                queue = waiting;
+                for mut delivery in due {
+                    delivery.attempts += 1;
+                    let Err(error) = delivery.sink.deliver(&delivery.event) else { continue };
+
+                    if closing || delivery.attempts >= delivery.sink.max_attempts() {
+                        error!(
+                            sink = %delivery.sink.name(),
+                            kind = ?delivery.event.kind,
+                            operation_id = %delivery.event.operation_id,
+                            attempts = delivery.attempts,
+                            error = %error,
+                            "Gave up delivering event"
+                        );
+                        dead_letter(&dead_letter_path, &delivery, error);
+                    } else {
+                        let delay = retry_delay.saturating_mul(1 << (delivery.attempts - 1).min(16)).min(MAX_RETRY_DELAY);
+                        warn!(sink = %delivery.sink.name(), error = %error, retry_in = ?delay, "Event delivery failed, retrying");
+                        delivery.due = Instant::now() + delay;
+                        queue.push(delivery);
+                    }
+                }
+                if closing {
+                    break;
+                }
+            }
+        });
+
+        Self { sinks, sender: Mutex::new(sender) }
+    }
+
+    pub fn add_sink(&self, sink: Arc<dyn WipeEventSink>) {
+        self.sinks.lock().unwrap().custom.push(sink);
+    }
+
+    /// Replace the sinks built from the configuration: webhooks, syslog, the
+    /// Windows Event Log and email
+    pub fn configure(&self, config: &crate::core::WipeConfiguration) {
+        let mut sinks = webhook_sinks(&config.webhooks);
+        sinks.extend(crate::core::event_log_sinks(&config.event_logging));
+        sinks.extend(crate::core::email_sinks(config.email.as_ref()));
+        self.sinks.lock().unwrap().configured = sinks;
+    }
+
+    pub fn publish(&self, event: LifecycleEvent) {
+        let _ = self.sender.lock().unwrap().send(event);
+    }
+}
+
+impl Default for EventDispatcher {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn dead_letter(path: &PathBuf, delivery: &PendingDelivery, last_error: String) {
+    let record = FailedDelivery {
+        sink: delivery.sink.name(),
+        attempts: delivery.attempts,
+        last_error,
+        failed_at: Utc::now(),
+        event: (*delivery.event).clone(),
+    };
+    if let Some(parent) = path.parent() {
+        let _ = fs::create_dir_all(parent);
+    }
+    let written = serde_json::to_string(&record).ok().and_then(|line| {
+        let mut file = OpenOptions::new().create(true).append(true).open(path).ok()?;
+        writeln!(file, "{}", line).ok()
+    });
+    if written.is_none() {
+        error!(path = %path.display(), "Could not record failed delivery");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    struct FlakySink {
+        failures_left: AtomicU32,
+        delivered: Mutex<Vec<Uuid>>,
+    }
+
+    impl WipeEventSink for FlakySink {
+        fn name(&self) -> String {
+            "flaky".to_string()
+        }
+
+        fn max_attempts(&self) -> u32 {
+            3
+        }
+
+        fn deliver(&self, event: &LifecycleEvent) -> Result<(), String> {
+            if self.failures_left.load(Ordering::SeqCst) > 0 {
+                self.failures_left.fetch_sub(1, Ordering::SeqCst);
+                return Err("unavailable".to_string());
+            }
+            self.delivered.lock().unwrap().push(event.event_id);
+            Ok(())
+        }
+    }
+
+    fn event() -> LifecycleEvent {
+        let request: crate::core::WipeRequest = serde_json::from_value(serde_json::json!({
+            "id": Uuid::new_v4(), "target_path": "/dev/sdx", "target_type": "HDD",
+            "standard": "NIST_SP_800_88_R1", "passes": 1, "verify_erasure": false,
+            "generate_certificate": false, "timestamp": Utc::now(),
+        }))
+        .unwrap();
+        LifecycleEvent::new(LifecycleEventKind::WipeCompleted, &request)
+    }
+
+    #[test]
+    fn test_retries_then_dead_letters() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("dead_letter.jsonl");
+        let dispatcher = EventDispatcher::with_settings(path.clone(), Duration::from_millis(10));
+        let sink = Arc::new(FlakySink { failures_left: AtomicU32::new(2), delivered: Mutex::new(Vec::new()) });
+        dispatcher.add_sink(sink.clone());
+
+        let recovered = event();
+        dispatcher.publish(recovered.clone());
+        thread::sleep(Duration::from_millis(300));
+        assert_eq!(*sink.delivered.lock().unwrap(), vec![recovered.event_id]);
+
+        sink.failures_left.store(10, Ordering::SeqCst);
+        let lost = event();
+        dispatcher.publish(lost.clone());
+        thread::sleep(Duration::from_millis(300));
+        let failed: FailedDelivery = serde_json::from_str(fs::read_to_string(&path).unwrap().trim()).unwrap();
+        assert_eq!((failed.event.event_id, failed.attempts), (lost.event_id, 3));
+    }
+
+    #[test]
+    fn test_signature_round_trip() {
+        let signature = sign_payload(b"secret", b"{\"kind\":\"WipeCompleted\"}");
+        assert!(verify_payload_signature(b"secret", b"{\"kind\":\"WipeCompleted\"}", &signature));
+        assert!(!verify_payload_signature(b"other", b"{\"kind\":\"WipeCompleted\"}", &signature));
+    }
+}
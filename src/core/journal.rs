@@ -0,0 +1,275 @@
+//! Write-ahead journal of running wipes
+//!
+//! The operation history is only as current as its last save. The journal is appended to and synced before anything is
+//! written to a device and after every completed pass, so a wipe cut short by
+//! a power loss or crash is known on the next start, together with the
+//! request that started it and how many passes made it to the disk.
+//!
+//! `WipeEngine::recover_operations` lists those wipes; each can be resumed at
+//! its first unfinished pass, restarted from the first pass, or dismissed.
+//! The last pass always runs again on a resume, since verification reads back
+//! what it wrote. The journal only keeps wipes that are still open: it is
+//! compacted whenever it is opened.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs::{self, File, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use uuid::Uuid;
+
+use crate::core::{PauseCheckpoint, WipeError, WipeErrorCode, WipeRequest, WipeResult2};
+
+/// Default location of the journal, next to the operation history
+pub const DEFAULT_JOURNAL_PATH: &str = "history/journal.jsonl";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "event")]
+pub enum JournalEvent {
+    /// Written before the first byte reaches the device
+    Started { request: Box<WipeRequest> },
+    PassCompleted { pass: u32, total_passes: u32 },
+    /// Paused by an operator, with its writes up to the checkpoint flushed
+    Paused { checkpoint: PauseCheckpoint },
+    Resumed,
+    /// Completed, failed or cancelled
+    Finished,
+    /// An interrupted wipe was resumed, restarted or dismissed
+    Recovered { action: RecoveryAction },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JournalEntry {
+    pub at: DateTime<Utc>,
+    pub operation_id: Uuid,
+    #[serde(flatten)]
+    pub event: JournalEvent,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RecoveryAction {
+    /// Run the passes that had not completed, then verify
+    Resume,
+    /// Run the whole wipe again
+    Restart,
+    /// Leave the device as it is
+    Dismiss,
+}
+
+/// A wipe that was running when the engine last stopped
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InterruptedOperation {
+    pub operation_id: Uuid,
+    pub request: WipeRequest,
+    pub started_at: DateTime<Utc>,
+    /// Passes that were complete on the device
+    pub passes_completed: u32,
+    pub total_passes: u32,
+    /// Time of the last journal entry, close to when the wipe stopped
+    pub last_seen_at: DateTime<Utc>,
+    /// Where the wipe was holding, when it stopped while paused
+    #[serde(default)]
+    pub paused: Option<PauseCheckpoint>,
+}
+
+/// Where a resumed wipe picks up
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ResumePoint {
+    /// The interrupted operation
+    pub operation_id: Uuid,
+    pub passes_completed: u32,
+}
+
+pub struct OperationJournal {
+    path: PathBuf,
+    file: File,
+}
+
+impl OperationJournal {
+    /// Open the journal, dropping the entries of wipes that are closed
+    pub fn open<P: AsRef<Path>>(path: P) -> WipeResult2<Self> {
+        let path = path.as_ref().to_path_buf();
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).map_err(|e| journal_error(format!("Failed to create journal directory: {}", e)))?;
+        }
+
+        let interrupted = Self::read_interrupted(&path)?;
+        let entries: Vec<JournalEntry> = Self::read_entries(&path)?
+            .into_iter()
+            .filter(|entry| interrupted.iter().any(|operation| operation.operation_id == entry.operation_id))
+            .collect();
+        let mut content = String::new();
+        for entry in &entries {
+            content.push_str(&serde_json::to_string(entry).map_err(|e| journal_error(e.to_string()))?);
+            content.push('\n');
+        }
+        let tmp_path = path.with_extension("jsonl.tmp");
+        fs::write(&tmp_path, content)
+            .and_then(|_| fs::rename(&tmp_path, &path))
+            .map_err(|e| journal_error(format!("Failed to compact operation journal: {}", e)))?;
+
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)
+            .map_err(|e| journal_error(format!("Failed to open operation journal: {}", e)))?;
+        Ok(Self { path, file })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Append `event` and sync it to disk before returning
+    pub fn append(&mut self, operation_id: Uuid, event: JournalEvent) -> WipeResult2<()> {
+        let entry = JournalEntry { at: Utc::now(), operation_id, event };
+        let mut line = serde_json::to_string(&entry).map_err(|e| journal_error(e.to_string()))?;
+        line.push('\n');
+        self.file
+            .write_all(line.as_bytes())
+            .and_then(|_| self.file.sync_data())
+            .map_err(|e| journal_error(format!("Failed to write operation journal: {}", e)))
+    }
+
+    /// Every entry; a line torn by the interruption itself is skipped
+    pub fn read_entries<P: AsRef<Path>>(path: P) -> WipeResult2<Vec<JournalEntry>> {
+        let path = path.as_ref();
+        if !path.exists() {
+            return Ok(Vec::new());
+        }
+        let content = fs::read_to_string(path).map_err(|e| journal_error(format!("Failed to read operation journal: {}", e)))?;
+        Ok(content.lines().filter_map(|line| serde_json::from_str(line).ok()).collect())
+    }
+
+    /// Wipes that started and never finished, oldest first
+    pub fn read_interrupted<P: AsRef<Path>>(path: P) -> WipeResult2<Vec<InterruptedOperation>> {
+        let mut open: HashMap<Uuid, InterruptedOperation> = HashMap::new();
+        for entry in Self::read_entries(path)? {
+            match entry.event {
+                JournalEvent::Started { request } => {
+                    // A resumed wipe starts with the passes it skips already done
+                    open.insert(entry.operation_id, InterruptedOperation {
+                        operation_id: entry.operation_id,
+                        total_passes: request.passes,
+                        passes_completed: request.resume.map_or(0, |resume| resume.passes_completed),
+                        request: *request,
+                        started_at: entry.at,
+                        last_seen_at: entry.at,
+                        paused: None,
+                    });
+                }
+                JournalEvent::PassCompleted { pass, total_passes } => {
+                    if let Some(operation) = open.get_mut(&entry.operation_id) {
+                        operation.passes_completed = operation.passes_completed.max(pass);
+                        operation.total_passes = total_passes;
+                        operation.last_seen_at = entry.at;
+                    }
+                }
+                JournalEvent::Paused { checkpoint } => {
+                    if let Some(operation) = open.get_mut(&entry.operation_id) {
+                        operation.paused = Some(checkpoint);
+                        operation.last_seen_at = entry.at;
+                    }
+                }
+                JournalEvent::Resumed => {
+                    if let Some(operation) = open.get_mut(&entry.operation_id) {
+                        operation.paused = None;
+                        operation.last_seen_at = entry.at;
+                    }
+                }
+                JournalEvent::Finished | JournalEvent::Recovered { .. } => {
+                    open.remove(&entry.operation_id);
+                }
+            }
+        }
+        let mut interrupted: Vec<InterruptedOperation> = open.into_values().collect();
+        interrupted.sort_by_key(|operation| operation.started_at);
+        Ok(interrupted)
+    }
+}
+
+fn journal_error(message: String) -> WipeError {
+    WipeError {
+        code: WipeErrorCode::UnknownError,
+        message,
+        sector: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{SanitizationStandard, TargetType, WipeEngine, WipeScope};
+
+    #[test]
+    fn test_journal_finds_interrupted_wipes() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("journal.jsonl");
+        let request = |device: &str| {
+            let mut request = WipeEngine::build_request(
+                device.to_string(),
+                TargetType::HDD,
+                WipeScope::WholeDevice,
+                SanitizationStandard::DoD_5220_22_M,
+                true,
+                false,
+            );
+            request.passes = 3;
+            request
+        };
+        let (finished, interrupted) = (request("/dev/sdb"), request("/dev/sdc"));
+
+        let mut journal = OperationJournal::open(&path).unwrap();
+        journal.append(finished.id, JournalEvent::Started { request: Box::new(finished.clone()) }).unwrap();
+        journal.append(interrupted.id, JournalEvent::Started { request: Box::new(interrupted.clone()) }).unwrap();
+        journal.append(interrupted.id, JournalEvent::PassCompleted { pass: 1, total_passes: 3 }).unwrap();
+        journal.append(finished.id, JournalEvent::Finished).unwrap();
+        drop(journal);
+        // The power went out in the middle of a line
+        let mut file = OpenOptions::new().append(true).open(&path).unwrap();
+        file.write_all(b"{\"at\":\"2024-").unwrap();
+        drop(file);
+
+        let open = OperationJournal::read_interrupted(&path).unwrap();
+        assert_eq!(open.len(), 1);
+        assert_eq!(open[0].operation_id, interrupted.id);
+        assert_eq!(open[0].request.target_path, "/dev/sdc");
+        assert_eq!((open[0].passes_completed, open[0].total_passes), (1, 3));
+
+        // Reopening keeps only the open wipe, until it is dealt with
+        let mut journal = OperationJournal::open(&path).unwrap();
+        assert_eq!(OperationJournal::read_entries(&path).unwrap().len(), 2);
+        journal.append(interrupted.id, JournalEvent::Recovered { action: RecoveryAction::Dismiss }).unwrap();
+        assert!(OperationJournal::read_interrupted(&path).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_wipe_interrupted_while_paused_keeps_its_checkpoint() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("journal.jsonl");
+        let request = WipeEngine::build_request(
+            "/dev/sdd".to_string(),
+            TargetType::HDD,
+            WipeScope::WholeDevice,
+            SanitizationStandard::NIST_SP_800_88_R1,
+            true,
+            false,
+        );
+        let checkpoint = PauseCheckpoint {
+            pass: 1,
+            total_passes: 1,
+            bytes_written: 1 << 20,
+            next_sector: Some(2048),
+            paused_at: Utc::now(),
+        };
+
+        let mut journal = OperationJournal::open(&path).unwrap();
+        journal.append(request.id, JournalEvent::Started { request: Box::new(request.clone()) }).unwrap();
+        journal.append(request.id, JournalEvent::Paused { checkpoint: checkpoint.clone() }).unwrap();
+        assert_eq!(OperationJournal::read_interrupted(&path).unwrap()[0].paused, Some(checkpoint));
+
+        journal.append(request.id, JournalEvent::Resumed).unwrap();
+        assert_eq!(OperationJournal::read_interrupted(&path).unwrap()[0].paused, None);
+    }
+}
@@ -0,0 +1,79 @@
+//! The engine's configuration, replaceable while operations run
+//!
+//! Settings can change at any time, e.g. an administrator turning on
+//! simulation or per-pass verification from the front end while a wipe is
+//! already writing. Each operation takes a snapshot when it starts and keeps
+//! it to the end, so a running wipe finishes with the settings it was
+//! started with and the next one picks up the change. The report generator
+//! and certificate authority are built from the configuration and are
+//! swapped with it, so a snapshot is always consistent.
+
+use std::sync::{Arc, Mutex, RwLock};
+
+use crate::core::WipeConfiguration;
+use crate::security::{CertificateAuthority, ReportGenerator};
+
+/// The configuration and what the engine built from it
+pub struct ConfigSnapshot {
+    pub config: WipeConfiguration,
+    pub report_generator: ReportGenerator,
+    pub certificate_authority: Arc<Mutex<CertificateAuthority>>,
+}
+
+pub struct LiveConfig {
+    current: RwLock<Arc<ConfigSnapshot>>,
+}
+
+impl LiveConfig {
+    pub fn new(snapshot: ConfigSnapshot) -> Self {
+        Self { current: RwLock::new(Arc::new(snapshot)) }
+    }
+
+    /// The settings new operations start with
+    pub fn snapshot(&self) -> Arc<ConfigSnapshot> {
+        self.current.read().unwrap().clone()
+    }
+
+    /// Make `snapshot` current; operations holding the previous one keep it
+    pub fn replace(&self, snapshot: ConfigSnapshot) -> Arc<ConfigSnapshot> {
+        std::mem::replace(&mut *self.current.write().unwrap(), Arc::new(snapshot))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn snapshot(config: WipeConfiguration) -> ConfigSnapshot {
+        let ca = CertificateAuthority::new_ed25519("Test CA".to_string(), "Test".to_string(), None::<&str>).unwrap();
+        ConfigSnapshot {
+            config,
+            report_generator: ReportGenerator::new(),
+            certificate_authority: Arc::new(Mutex::new(ca)),
+        }
+    }
+
+    #[test]
+    fn test_running_operation_keeps_its_settings() {
+        let live = LiveConfig::new(snapshot(WipeConfiguration::default()));
+        let running = live.snapshot();
+        assert!(!running.config.simulation.enabled);
+        assert!(!running.config.verify_each_pass);
+
+        let mut changed = WipeConfiguration::default();
+        changed.simulation.enabled = true;
+        changed.verify_each_pass = true;
+        let previous = live.replace(snapshot(changed));
+        assert!(Arc::ptr_eq(&previous, &running));
+
+        // The running wipe still sees the old settings, the next one the new
+        assert!(!running.config.simulation.enabled && !running.config.verify_each_pass);
+        let next = live.snapshot();
+        assert!(next.config.simulation.enabled && next.config.verify_each_pass);
+
+        // And toggling back only affects operations started afterwards
+        live.replace(snapshot(WipeConfiguration::default()));
+        assert!(next.config.simulation.enabled);
+        assert!(!live.snapshot().config.simulation.enabled);
+    }
+}
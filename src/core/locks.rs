@@ -0,0 +1,141 @@
+//! One operation per device at a time
+//!
+//! Two wipes of the same disk would interleave their passes, and a
+//! verification or residual-data scan reading a disk that is being
+//! overwritten reports garbage. Every engine operation that touches a device
+//! claims it here first and holds the claim until it returns; a second claim
+//! on the same device fails with `WipeErrorCode::OperationInProgress`.
+//!
+//! Devices are keyed by their canonical path, so a stable device id, a
+//! `/dev/disk/by-id` link and `/dev/sdb` all name the same disk.
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use uuid::Uuid;
+
+use crate::core::{WipeError, WipeErrorCode, WipeResult2};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum DeviceActivity {
+    Wipe,
+    Verification,
+    ResidualScan,
+    CryptoErase,
+}
+
+impl DeviceActivity {
+    fn description(self) -> &'static str {
+        match self {
+            DeviceActivity::Wipe => "a wipe",
+            DeviceActivity::Verification => "a verification",
+            DeviceActivity::ResidualScan => "a residual data scan",
+            DeviceActivity::CryptoErase => "a crypto erase",
+        }
+    }
+}
+
+/// A device claimed by a running operation
+#[derive(Debug, Clone, Serialize)]
+pub struct LockedDevice {
+    /// Canonical path of the device
+    pub device: String,
+    pub operation_id: Uuid,
+    pub activity: DeviceActivity,
+    pub since: DateTime<Utc>,
+}
+
+/// Claims on devices, shared by every operation of an engine
+#[derive(Debug, Clone, Default)]
+pub struct DeviceLocks {
+    held: Arc<Mutex<HashMap<String, LockedDevice>>>,
+}
+
+impl DeviceLocks {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Claim `device` (a stable device id or a path) for `operation_id`; the
+    /// claim is released when the returned guard is dropped
+    pub fn acquire(&self, device: &str, operation_id: Uuid, activity: DeviceActivity) -> WipeResult2<DeviceLock> {
+        let key = canonical_device(device);
+        let mut held = self.held.lock().unwrap();
+        if let Some(holder) = held.get(&key) {
+            return Err(WipeError {
+                code: WipeErrorCode::OperationInProgress,
+                message: format!(
+                    "{} is in use by {} ({}) since {}",
+                    key,
+                    holder.activity.description(),
+                    holder.operation_id,
+                    holder.since.format("%H:%M:%S")
+                ),
+                sector: None,
+            });
+        }
+        held.insert(key.clone(), LockedDevice { device: key.clone(), operation_id, activity, since: Utc::now() });
+        Ok(DeviceLock { locks: self.held.clone(), key })
+    }
+
+    /// The claim on `device`, if any
+    pub fn holder(&self, device: &str) -> Option<LockedDevice> {
+        self.held.lock().unwrap().get(&canonical_device(device)).cloned()
+    }
+
+    /// Every device currently claimed
+    pub fn held(&self) -> Vec<LockedDevice> {
+        let mut held: Vec<LockedDevice> = self.held.lock().unwrap().values().cloned().collect();
+        held.sort_by_key(|locked| locked.since);
+        held
+    }
+}
+
+/// Releases its device when dropped
+#[derive(Debug)]
+pub struct DeviceLock {
+    locks: Arc<Mutex<HashMap<String, LockedDevice>>>,
+    key: String,
+}
+
+impl Drop for DeviceLock {
+    fn drop(&mut self) {
+        if let Ok(mut held) = self.locks.lock() {
+            held.remove(&self.key);
+        }
+    }
+}
+
+/// The path `device` (a stable device id or a path) resolves to, with
+/// symbolic links followed
+pub fn canonical_device(device: &str) -> String {
+    let path = crate::devices::identity::device_path_for_id(device).unwrap_or_else(|| device.to_string());
+    let path = std::fs::canonicalize(&path).map_or(path, |canonical| canonical.display().to_string());
+    // \\.\PhysicalDrive1 and \\.\physicaldrive1 are the same drive
+    if cfg!(windows) { path.to_lowercase() } else { path }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_second_claim_on_a_device_waits_for_the_first() {
+        let locks = DeviceLocks::new();
+        let dir = tempfile::tempdir().unwrap();
+        let device = dir.path().join("disk.img");
+        std::fs::write(&device, b"").unwrap();
+        let alias = dir.path().join(".").join("disk.img");
+
+        let first = Uuid::new_v4();
+        let lock = locks.acquire(&device.display().to_string(), first, DeviceActivity::Wipe).unwrap();
+        let refused = locks.acquire(&alias.display().to_string(), Uuid::new_v4(), DeviceActivity::Verification).unwrap_err();
+        assert!(matches!(refused.code, WipeErrorCode::OperationInProgress));
+        assert_eq!(locks.holder(&device.display().to_string()).map(|holder| holder.operation_id), Some(first));
+
+        drop(lock);
+        assert!(locks.held().is_empty());
+        assert!(locks.acquire(&alias.display().to_string(), Uuid::new_v4(), DeviceActivity::Verification).is_ok());
+    }
+}
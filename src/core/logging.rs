@@ -0,0 +1,119 @@
+//! Structured logs of what the engine and the erasers do
+//!
+//! The engine, the sanitizer and the device modules log through `tracing`
+//! rather than printing. Each wipe runs in a `wipe` span carrying its
+//! `operation_id` and device, and each pass in a `pass` span inside it, so
+//! every line can be traced back to its operation even while several wipes
+//! run at once. Lines go to stderr, which keeps command output on stdout
+//! parseable.
+//!
+//! `WipeConfiguration::logging` sets the level and whether lines are text or
+//! one JSON object each, for a service whose logs are collected. `RUST_LOG`
+//! overrides the level, e.g. `RUST_LOG=hdd_tool::devices=debug`.
+
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+use tracing_subscriber::filter::EnvFilter;
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::{reload, Layer, Registry};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LogLevel {
+    Error,
+    Warn,
+    #[default]
+    Info,
+    Debug,
+    Trace,
+}
+
+impl LogLevel {
+    fn directive(self) -> &'static str {
+        match self {
+            LogLevel::Error => "error",
+            LogLevel::Warn => "warn",
+            LogLevel::Info => "info",
+            LogLevel::Debug => "debug",
+            LogLevel::Trace => "trace",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LogFormat {
+    #[default]
+    Text,
+    /// One JSON object per line, with the fields of the enclosing spans
+    Json,
+}
+
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct LoggingConfig {
+    #[serde(default)]
+    pub level: LogLevel,
+    #[serde(default)]
+    pub format: LogFormat,
+}
+
+/// Filters of the text and the JSON output; the one not configured is off
+struct Filters {
+    text: reload::Handle<EnvFilter, Registry>,
+    json: reload::Handle<EnvFilter, Registry>,
+}
+
+static FILTERS: Mutex<Option<Filters>> = Mutex::new(None);
+
+/// Install the log subscriber, or switch the installed one to `config`. Does
+/// nothing when the application installed a subscriber of its own.
+pub fn init_logging(config: &LoggingConfig) {
+    let mut filters = FILTERS.lock().unwrap();
+    if filters.is_none() {
+        let (text, text_handle) = reload::Layer::new(EnvFilter::new("off"));
+        let (json, json_handle) = reload::Layer::new(EnvFilter::new("off"));
+        let layers = vec![
+            tracing_subscriber::fmt::layer().with_writer(std::io::stderr).with_filter(text).boxed(),
+            tracing_subscriber::fmt::layer()
+                .json()
+                .with_current_span(true)
+                .with_span_list(true)
+                .with_writer(std::io::stderr)
+                .with_filter(json)
+                .boxed(),
+        ];
+        if tracing::subscriber::set_global_default(Registry::default().with(layers)).is_err() {
+            return;
+        }
+        *filters = Some(Filters { text: text_handle, json: json_handle });
+    }
+
+    if let Some(filters) = filters.as_ref() {
+        let (active, inactive) = match config.format {
+            LogFormat::Text => (&filters.text, &filters.json),
+            LogFormat::Json => (&filters.json, &filters.text),
+        };
+        let _ = active.reload(filter_for(config.level));
+        let _ = inactive.reload(EnvFilter::new("off"));
+    }
+}
+
+/// `RUST_LOG` when set, `level` otherwise
+fn filter_for(level: LogLevel) -> EnvFilter {
+    EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new(level.directive()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_logging_config_defaults_to_info_text() {
+        let config: LoggingConfig = serde_json::from_str("{}").unwrap();
+        assert_eq!(config, LoggingConfig { level: LogLevel::Info, format: LogFormat::Text });
+
+        let config: LoggingConfig = serde_json::from_str(r#"{"level": "debug", "format": "json"}"#).unwrap();
+        assert_eq!((config.level, config.format), (LogLevel::Debug, LogFormat::Json));
+        assert_eq!(config.level.directive(), "debug");
+    }
+}
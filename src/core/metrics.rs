@@ -0,0 +1,302 @@
+//! Operational metrics for wipe appliances
+//!
+//! The engine records the progress, throughput, errors and device temperature
+//! of every wipe in a `WipeMetrics` registry. With the `metrics` feature the
+//! registry can be served in the Prometheus text format, so a wipe farm can be
+//! scraped and graphed in Grafana:
+//!
+//! ```json
+//! "metrics_address": "0.0.0.0:9184"
+//! ```
+//!
+//! Per-operation series carry `operation_id` and `device` labels and disappear
+//! when the wipe ends; the totals are kept per device for the life of the process.
+
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::Write as _;
+use std::sync::Mutex;
+use std::time::Instant;
+use uuid::Uuid;
+
+use crate::core::{WipeErrorCode, WipeProgress, WipeRequest, WipeResult};
+
+/// Progress reports count 512-byte sectors regardless of the device sector size
+const PROGRESS_SECTOR_SIZE: u64 = 512;
+
+struct OperationMetrics {
+    device: String,
+    /// False until the device is opened for writing
+    running: bool,
+    pass: u32,
+    total_passes: u32,
+    phase: &'static str,
+    progress_ratio: f64,
+    bytes_per_second: f64,
+    /// Time and bytes of the previous progress report of the current pass
+    last_sample: Option<(Instant, u64)>,
+}
+
+#[derive(Default)]
+struct MetricsState {
+    operations: HashMap<Uuid, OperationMetrics>,
+    bytes_written: BTreeMap<String, u64>,
+    bytes_verified: BTreeMap<String, u64>,
+    bad_sectors: BTreeMap<String, u64>,
+    retried_blocks: BTreeMap<String, u64>,
+    errors: BTreeMap<String, u64>,
+    wipes: BTreeMap<&'static str, u64>,
+    temperatures: BTreeMap<String, i32>,
+}
+
+/// Thread-safe registry shared by the engine and the metrics exporter
+#[derive(Default)]
+pub struct WipeMetrics {
+    state: Mutex<MetricsState>,
+}
+
+impl WipeMetrics {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A wipe was accepted and waits for preflight checks and volume release
+    pub fn operation_queued(&self, request: &WipeRequest) {
+        self.state.lock().unwrap().operations.insert(request.id, OperationMetrics {
+            device: request.target_path.clone(),
+            running: false,
+            pass: 0,
+            total_passes: request.passes,
+            phase: "queued",
+            progress_ratio: 0.0,
+            bytes_per_second: 0.0,
+            last_sample: None,
+        });
+    }
+
+    /// The device is being written
+    pub fn operation_started(&self, operation_id: Uuid) {
+        if let Some(operation) = self.state.lock().unwrap().operations.get_mut(&operation_id) {
+            operation.running = true;
+            operation.phase = "write";
+        }
+    }
+
+    pub fn record_progress(&self, progress: &WipeProgress) {
+        let mut guard = self.state.lock().unwrap();
+        let state = &mut *guard;
+        let Some(operation) = state.operations.get_mut(&progress.request_id) else { return };
+
+        let phase = if progress.current_operation.starts_with("Verifying") { "verify" } else { "write" };
+        let bytes = progress.sectors_processed * PROGRESS_SECTOR_SIZE;
+        let now = Instant::now();
+
+        // A new pass or phase restarts the byte count from zero
+        let same_pass = operation.pass == progress.current_pass && operation.phase == phase;
+        let delta = match operation.last_sample {
+            Some((at, previous)) if same_pass && bytes >= previous => {
+                let seconds = now.duration_since(at).as_secs_f64();
+                if seconds > 0.0 {
+                    operation.bytes_per_second = (bytes - previous) as f64 / seconds;
+                }
+                bytes - previous
+            }
+            _ => {
+                operation.bytes_per_second = 0.0;
+                bytes
+            }
+        };
+
+        operation.pass = progress.current_pass;
+        operation.total_passes = progress.total_passes;
+        operation.phase = phase;
+        operation.progress_ratio = (progress.percentage / 100.0).clamp(0.0, 1.0);
+        operation.last_sample = Some((now, bytes));
+
+        let totals = if phase == "verify" { &mut state.bytes_verified } else { &mut state.bytes_written };
+        *totals.entry(operation.device.clone()).or_default() += delta;
+    }
+
+    pub fn record_temperature(&self, device: &str, celsius: i32) {
+        self.state.lock().unwrap().temperatures.insert(device.to_string(), celsius);
+    }
+
+    pub fn operation_finished(&self, result: &WipeResult) {
+        let mut state = self.state.lock().unwrap();
+        let Some(operation) = state.operations.remove(&result.request_id) else { return };
+
+        *state.bad_sectors.entry(operation.device.clone()).or_default() += result.bad_sectors.skipped_sectors();
+        *state.retried_blocks.entry(operation.device).or_default() += result.bad_sectors.retried_blocks;
+        *state.wipes.entry(if result.success { "completed" } else { "failed" }).or_default() += 1;
+    }
+
+    pub fn operation_failed(&self, operation_id: Uuid, code: &WipeErrorCode) {
+        let mut state = self.state.lock().unwrap();
+        state.operations.remove(&operation_id);
+
+        if matches!(code, WipeErrorCode::Cancelled) {
+            *state.wipes.entry("cancelled").or_default() += 1;
+        } else {
+            *state.wipes.entry("failed").or_default() += 1;
+            *state.errors.entry(format!("{:?}", code)).or_default() += 1;
+        }
+    }
+
+    /// Prometheus text exposition format 0.0.4
+    pub fn render(&self) -> String {
+        let state = self.state.lock().unwrap();
+        let mut out = String::new();
+
+        let queued = state.operations.values().filter(|o| !o.running).count();
+        metric_header(&mut out, "hdd_tool_queue_depth", "gauge", "Wipes accepted but not yet writing to the device");
+        let _ = writeln!(out, "hdd_tool_queue_depth {}", queued);
+        metric_header(&mut out, "hdd_tool_wipes_running", "gauge", "Wipes currently writing or verifying");
+        let _ = writeln!(out, "hdd_tool_wipes_running {}", state.operations.len() - queued);
+
+        let mut running: Vec<(&Uuid, &OperationMetrics)> = state.operations.iter().filter(|(_, o)| o.running).collect();
+        running.sort_by(|a, b| a.1.device.cmp(&b.1.device));
+
+        metric_header(&mut out, "hdd_tool_operation_throughput_bytes_per_second", "gauge", "Current write or verify rate of a wipe");
+        for (id, operation) in &running {
+            let _ = writeln!(
+                out,
+                "hdd_tool_operation_throughput_bytes_per_second{{operation_id=\"{}\",device=\"{}\",phase=\"{}\"}} {}",
+                id, escape_label(&operation.device), operation.phase, operation.bytes_per_second
+            );
+        }
+        metric_header(&mut out, "hdd_tool_operation_pass", "gauge", "Pass currently being written");
+        for (id, operation) in &running {
+            let _ = writeln!(out, "hdd_tool_operation_pass{{operation_id=\"{}\",device=\"{}\"}} {}", id, escape_label(&operation.device), operation.pass);
+        }
+        metric_header(&mut out, "hdd_tool_operation_passes", "gauge", "Total passes of a wipe");
+        for (id, operation) in &running {
+            let _ = writeln!(out, "hdd_tool_operation_passes{{operation_id=\"{}\",device=\"{}\"}} {}", id, escape_label(&operation.device), operation.total_passes);
+        }
+        metric_header(&mut out, "hdd_tool_operation_progress_ratio", "gauge", "Completed fraction of the current pass");
+        for (id, operation) in &running {
+            let _ = writeln!(out, "hdd_tool_operation_progress_ratio{{operation_id=\"{}\",device=\"{}\"}} {}", id, escape_label(&operation.device), operation.progress_ratio);
+        }
+
+        let per_device = [
+            ("hdd_tool_bytes_written_total", "Bytes overwritten", &state.bytes_written),
+            ("hdd_tool_bytes_verified_total", "Bytes read back for verification", &state.bytes_verified),
+            ("hdd_tool_bad_sectors_total", "Sectors skipped as unwritable", &state.bad_sectors),
+            ("hdd_tool_retried_blocks_total", "Blocks rewritten piecewise after a write error", &state.retried_blocks),
+        ];
+        for (name, help, values) in per_device {
+            metric_header(&mut out, name, "counter", help);
+            for (device, value) in values {
+                let _ = writeln!(out, "{}{{device=\"{}\"}} {}", name, escape_label(device), value);
+            }
+        }
+
+        metric_header(&mut out, "hdd_tool_errors_total", "counter", "Wipes aborted, by error code");
+        for (code, count) in &state.errors {
+            let _ = writeln!(out, "hdd_tool_errors_total{{code=\"{}\"}} {}", code, count);
+        }
+        metric_header(&mut out, "hdd_tool_wipes_total", "counter", "Finished wipes, by outcome");
+        for (outcome, count) in &state.wipes {
+            let _ = writeln!(out, "hdd_tool_wipes_total{{outcome=\"{}\"}} {}", outcome, count);
+        }
+        metric_header(&mut out, "hdd_tool_device_temperature_celsius", "gauge", "Last temperature reported by SMART");
+        for (device, celsius) in &state.temperatures {
+            let _ = writeln!(out, "hdd_tool_device_temperature_celsius{{device=\"{}\"}} {}", escape_label(device), celsius);
+        }
+
+        out
+    }
+}
+
+fn metric_header(out: &mut String, name: &str, kind: &str, help: &str) {
+    let _ = writeln!(out, "# HELP {} {}", name, help);
+    let _ = writeln!(out, "# TYPE {} {}", name, kind);
+}
+
+fn escape_label(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n")
+}
+
+/// Serve `GET /metrics` on `address` from a background thread
+#[cfg(feature = "metrics")]
+pub fn serve_metrics(
+    metrics: std::sync::Arc<WipeMetrics>,
+    address: &str,
+) -> std::io::Result<std::thread::JoinHandle<()>> {
+    use std::io::{BufRead, BufReader, Write};
+    use std::net::TcpListener;
+    use std::time::Duration;
+    use tracing::info;
+
+    let listener = TcpListener::bind(address)?;
+    info!(address = %listener.local_addr()?, "Metrics available at /metrics");
+
+    Ok(std::thread::spawn(move || {
+        for stream in listener.incoming() {
+            let Ok(mut stream) = stream else { continue };
+            let _ = stream.set_read_timeout(Some(Duration::from_secs(5)));
+
+            let mut request_line = String::new();
+            if BufReader::new(&stream).read_line(&mut request_line).is_err() {
+                continue;
+            }
+            let path = request_line.split_whitespace().nth(1).unwrap_or("");
+            let (status, body) = if request_line.starts_with("GET ") && path.split('?').next() == Some("/metrics") {
+                ("200 OK", metrics.render())
+            } else {
+                ("404 Not Found", "Not found\n".to_string())
+            };
+
+            let _ = write!(
+                stream,
+                "HTTP/1.1 {}\r\nContent-Type: text/plain; version=0.0.4\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                status,
+                body.len(),
+                body
+            );
+        }
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bytes_counted_once_per_pass() {
+        let metrics = WipeMetrics::new();
+        let request: WipeRequest = serde_json::from_value(serde_json::json!({
+            "id": Uuid::new_v4(), "target_path": "/dev/sdb", "target_type": "HDD",
+            "standard": "NIST_SP_800_88_R1", "passes": 2, "verify_erasure": false,
+            "generate_certificate": false, "timestamp": chrono::Utc::now(),
+        }))
+        .unwrap();
+        metrics.operation_queued(&request);
+        assert!(metrics.render().contains("hdd_tool_queue_depth 1"));
+
+        metrics.operation_started(request.id);
+        for (pass, sectors) in [(1, 0), (1, 1000), (1, 2000), (2, 0), (2, 500)] {
+            metrics.record_progress(&WipeProgress {
+                request_id: request.id,
+                current_pass: pass,
+                total_passes: 2,
+                sectors_processed: sectors,
+                total_sectors: 2000,
+                percentage: sectors as f64 / 20.0,
+                current_operation: format!("Pass {} - Writing pattern", pass),
+                estimated_completion: None,
+                estimated_completion_earliest: None,
+                estimated_completion_latest: None,
+                verifying: false,
+            });
+        }
+
+        let rendered = metrics.render();
+        assert!(rendered.contains("hdd_tool_wipes_running 1"));
+        assert!(rendered.contains(&format!("hdd_tool_operation_pass{{operation_id=\"{}\",device=\"/dev/sdb\"}} 2", request.id)));
+        assert!(rendered.contains(&format!("hdd_tool_bytes_written_total{{device=\"/dev/sdb\"}} {}", 2500 * 512)));
+
+        metrics.operation_failed(request.id, &WipeErrorCode::Cancelled);
+        let rendered = metrics.render();
+        assert!(rendered.contains("hdd_tool_wipes_running 0"));
+        assert!(rendered.contains("hdd_tool_wipes_total{outcome=\"cancelled\"} 1"));
+    }
+}
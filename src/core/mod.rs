@@ -1,7 +1,75 @@
 pub mod types;
 pub mod config;
 pub mod engine;
+pub mod history;
+pub mod journal;
+pub mod timeline;
+pub mod preflight;
+pub mod advisory;
+pub mod volumes;
+pub mod locks;
+pub mod policy;
+pub mod operator;
+pub mod audit;
+pub mod integrations;
+pub mod uploads;
+pub mod backup;
+pub mod siem;
+pub mod email;
+pub mod metrics;
+pub mod eta;
+pub mod throughput;
+pub mod throttle;
+pub mod thermal;
+pub mod spec;
+pub mod profiles;
+pub mod recipes;
+pub mod safety;
+pub mod simulation;
+pub mod selection;
+pub mod decommission;
+pub mod scheduler;
+pub mod priority;
+pub mod export;
+pub mod privileges;
+pub mod settings;
+pub mod live_config;
+pub mod logging;
 
 pub use types::*;
 pub use config::*;
-pub use engine::*;
\ No newline at end of file
+pub use engine::*;
+pub use history::*;
+pub use journal::*;
+pub use timeline::*;
+pub use preflight::*;
+pub use advisory::*;
+pub use volumes::*;
+pub use locks::*;
+pub use policy::*;
+pub use operator::*;
+pub use audit::*;
+pub use integrations::*;
+pub use uploads::*;
+pub use backup::*;
+pub use siem::*;
+pub use email::*;
+pub use metrics::*;
+pub use eta::*;
+pub use throughput::*;
+pub use throttle::*;
+pub use thermal::*;
+pub use spec::*;
+pub use profiles::*;
+pub use recipes::*;
+pub use safety::*;
+pub use simulation::*;
+pub use selection::*;
+pub use decommission::*;
+pub use scheduler::*;
+pub use priority::*;
+pub use export::*;
+pub use privileges::*;
+pub use settings::*;
+pub use live_config::*;
+pub use logging::*;
@@ -0,0 +1,246 @@
+//! Operator identity and authorization
+//!
+//! Destructive engine calls require a logged-in operator. Operators log in with
+//! the local accounts managed by `auth::AuthSystem` (shared with the desktop
+//! app) or with an API token issued by an administrator for headless use.
+//! The operator's name and role are written to the operation history and to
+//! every erasure certificate.
+
+use chrono::{DateTime, Duration, Utc};
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::path::{Path, PathBuf};
+use tracing::info;
+
+use crate::auth::{AuthSystem, UserRole};
+use crate::core::{WipeError, WipeErrorCode, WipeResult2};
+
+/// Default location of the issued API tokens (only their hashes are stored)
+pub const DEFAULT_TOKENS_PATH: &str = "operator_tokens.json";
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum AuthMethod {
+    Password,
+    Token,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OperatorIdentity {
+    pub username: String,
+    pub role: UserRole,
+    pub method: AuthMethod,
+    pub authenticated_at: DateTime<Utc>,
+}
+
+impl OperatorIdentity {
+    /// Name recorded in history and on certificates, e.g. "alice (Operator)"
+    pub fn display_name(&self) -> String {
+        format!("{} ({})", self.username, self.role.as_str())
+    }
+
+    pub fn require_sanitize(&self) -> WipeResult2<()> {
+        if self.role.can_sanitize() {
+            Ok(())
+        } else {
+            Err(insufficient_role(self, "wipe devices"))
+        }
+    }
+
+    pub fn require_admin(&self) -> WipeResult2<()> {
+        if self.role.can_manage_users() {
+            Ok(())
+        } else {
+            Err(insufficient_role(self, "perform administrative actions"))
+        }
+    }
+}
+
+fn insufficient_role(identity: &OperatorIdentity, action: &str) -> WipeError {
+    WipeError {
+        code: WipeErrorCode::InsufficientPrivileges,
+        message: format!("{} is not permitted to {}", identity.display_name(), action),
+        sector: None,
+    }
+}
+
+fn not_authenticated(message: &str) -> WipeError {
+    WipeError {
+        code: WipeErrorCode::NotAuthenticated,
+        message: message.to_string(),
+        sector: None,
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct OperatorToken {
+    token_hash: String,
+    username: String,
+    label: String,
+    created_at: DateTime<Utc>,
+    expires_at: Option<DateTime<Utc>>,
+}
+
+fn hash_token(token: &str) -> String {
+    hex::encode(Sha256::digest(token.as_bytes()))
+}
+
+/// Verifies credentials and tokens against the local account store
+pub struct OperatorAuthenticator {
+    users: AuthSystem,
+    tokens_path: PathBuf,
+    tokens: Vec<OperatorToken>,
+}
+
+impl OperatorAuthenticator {
+    pub fn open<P: AsRef<Path>>(tokens_path: P) -> WipeResult2<Self> {
+        let tokens_path = tokens_path.as_ref().to_path_buf();
+        let tokens = if tokens_path.exists() {
+            let content = fs::read_to_string(&tokens_path).map_err(|e| WipeError {
+                code: WipeErrorCode::UnknownError,
+                message: format!("Failed to read operator tokens: {}", e),
+                sector: None,
+            })?;
+            serde_json::from_str(&content).map_err(|e| WipeError {
+                code: WipeErrorCode::UnknownError,
+                message: format!("Failed to parse operator tokens: {}", e),
+                sector: None,
+            })?
+        } else {
+            Vec::new()
+        };
+
+        Ok(Self {
+            users: AuthSystem::new(),
+            tokens_path,
+            tokens,
+        })
+    }
+
+    pub fn login(&mut self, username: &str, password: &str) -> WipeResult2<OperatorIdentity> {
+        // The account store distinguishes unknown users from bad passwords; callers must not
+        let user = self.users
+            .authenticate(username, password)
+            .map_err(|_| not_authenticated("Invalid username or password"))?;
+
+        info!(operator = %user.username, role = user.role.as_str(), "Operator logged in");
+        Ok(OperatorIdentity {
+            username: user.username,
+            role: user.role,
+            method: AuthMethod::Password,
+            authenticated_at: Utc::now(),
+        })
+    }
+
+    /// Log in with an API token. The account behind it must still be active and
+    /// its current role applies, so disabling an account revokes its tokens.
+    pub fn login_with_token(&self, token: &str) -> WipeResult2<OperatorIdentity> {
+        let token_hash = hash_token(token);
+        let now = Utc::now();
+        let entry = self.tokens
+            .iter()
+            .find(|t| t.token_hash == token_hash && t.expires_at.is_none_or(|expires| expires > now))
+            .ok_or_else(|| not_authenticated("Invalid or expired token"))?;
+
+        let user = self.users
+            .get_all_users()
+            .into_iter()
+            .find(|u| u.username == entry.username && u.is_active)
+            .ok_or_else(|| not_authenticated("Token owner is unknown or disabled"))?;
+
+        info!(operator = %user.username, token = %entry.label, "Operator logged in with a token");
+        Ok(OperatorIdentity {
+            username: user.username.clone(),
+            role: user.role.clone(),
+            method: AuthMethod::Token,
+            authenticated_at: now,
+        })
+    }
+
+    /// Issue an API token for `username`; only administrators may do this.
+    /// The token is returned once and only its hash is kept.
+    pub fn issue_token(
+        &mut self,
+        issuer: &OperatorIdentity,
+        username: &str,
+        label: &str,
+        valid_for: Option<Duration>,
+    ) -> WipeResult2<String> {
+        issuer.require_admin()?;
+        if !self.users.get_all_users().iter().any(|u| u.username == username) {
+            return Err(WipeError {
+                code: WipeErrorCode::InvalidTarget,
+                message: format!("Unknown user {}", username),
+                sector: None,
+            });
+        }
+
+        let mut bytes = [0u8; 32];
+        rand::thread_rng().fill_bytes(&mut bytes);
+        let token = hex::encode(bytes);
+
+        let created_at = Utc::now();
+        self.tokens.push(OperatorToken {
+            token_hash: hash_token(&token),
+            username: username.to_string(),
+            label: label.to_string(),
+            created_at,
+            expires_at: valid_for.map(|duration| created_at + duration),
+        });
+        self.save()?;
+
+        info!(issuer = %issuer.username, token = %label, operator = %username, "Token issued");
+        Ok(token)
+    }
+
+    /// Revoke every token issued for `username`; returns how many were removed
+    pub fn revoke_tokens(&mut self, issuer: &OperatorIdentity, username: &str) -> WipeResult2<usize> {
+        issuer.require_admin()?;
+        let before = self.tokens.len();
+        self.tokens.retain(|t| t.username != username);
+        self.save()?;
+        Ok(before - self.tokens.len())
+    }
+
+    fn save(&self) -> WipeResult2<()> {
+        if let Some(parent) = self.tokens_path.parent() {
+            let _ = fs::create_dir_all(parent);
+        }
+        let content = serde_json::to_string_pretty(&self.tokens).map_err(|e| WipeError {
+            code: WipeErrorCode::UnknownError,
+            message: format!("Failed to serialize operator tokens: {}", e),
+            sector: None,
+        })?;
+        fs::write(&self.tokens_path, content).map_err(|e| WipeError {
+            code: WipeErrorCode::UnknownError,
+            message: format!("Failed to write operator tokens: {}", e),
+            sector: None,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn identity(role: UserRole) -> OperatorIdentity {
+        OperatorIdentity {
+            username: "alice".to_string(),
+            role,
+            method: AuthMethod::Password,
+            authenticated_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_role_permissions() {
+        assert!(identity(UserRole::Operator).require_sanitize().is_ok());
+        assert!(identity(UserRole::Operator).require_admin().is_err());
+        assert!(identity(UserRole::Admin).require_admin().is_ok());
+
+        let denied = identity(UserRole::Viewer).require_sanitize().unwrap_err();
+        assert!(matches!(denied.code, WipeErrorCode::InsufficientPrivileges));
+        assert_eq!(identity(UserRole::Viewer).display_name(), "alice (Viewer)");
+    }
+}
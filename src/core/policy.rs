@@ -0,0 +1,275 @@
+//! Wipe target policy
+//!
+//! Allow/deny rules on serial number, model, size, interface and removable
+//! flag decide which devices may be wiped at all. The policy is part of the
+//! `WipeConfiguration`, reported by preflight and enforced by the engine, so
+//! no front end can wipe a protected drive.
+//!
+//! Deny rules always win over allow rules; when no rule matches, the policy's
+//! `default_action` applies.
+
+use tracing::warn;
+use serde::{Deserialize, Serialize};
+use crate::core::{WipeError, WipeErrorCode, WipeResult2};
+use crate::hardware::DriveInterface;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum PolicyAction {
+    Allow,
+    Deny,
+}
+
+/// Conditions a device must all satisfy for a rule to apply; unset fields match
+/// any device. Serial and model accept `*` and `?` wildcards and ignore case.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct PolicyMatch {
+    #[serde(default)]
+    pub serial: Option<String>,
+    #[serde(default)]
+    pub model: Option<String>,
+    #[serde(default)]
+    pub min_size_bytes: Option<u64>,
+    #[serde(default)]
+    pub max_size_bytes: Option<u64>,
+    /// Bus type as reported by device detection, e.g. "USB", "NVMe", "SATA"
+    #[serde(default)]
+    pub interface: Option<String>,
+    #[serde(default)]
+    pub removable: Option<bool>,
+}
+
+impl PolicyMatch {
+    pub fn matches(&self, target: &PolicyTarget) -> bool {
+        self.serial.as_ref().is_none_or(|pattern| wildcard_match(pattern, &target.serial))
+            && self.model.as_ref().is_none_or(|pattern| wildcard_match(pattern, &target.model))
+            && self.min_size_bytes.is_none_or(|min| target.size_bytes >= min)
+            && self.max_size_bytes.is_none_or(|max| target.size_bytes <= max)
+            && self.interface.as_ref().is_none_or(|interface| interface.eq_ignore_ascii_case(&target.interface))
+            && self.removable.is_none_or(|removable| removable == target.removable)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PolicyRule {
+    pub name: String,
+    pub action: PolicyAction,
+    #[serde(flatten)]
+    pub conditions: PolicyMatch,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TargetPolicy {
+    #[serde(default)]
+    pub rules: Vec<PolicyRule>,
+    /// Applied when no rule matches
+    pub default_action: PolicyAction,
+    /// Deny devices whose serial number cannot be read, so serial rules cannot
+    /// be bypassed through a bridge that hides it
+    #[serde(default)]
+    pub require_serial: bool,
+}
+
+impl Default for TargetPolicy {
+    fn default() -> Self {
+        Self {
+            rules: Vec::new(),
+            default_action: PolicyAction::Allow,
+            require_serial: false,
+        }
+    }
+}
+
+/// What the policy knows about a wipe target
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct PolicyTarget {
+    pub path: String,
+    pub serial: String,
+    pub model: String,
+    pub size_bytes: u64,
+    pub interface: String,
+    pub removable: bool,
+}
+
+impl PolicyTarget {
+    /// Identify `device_path` from the detected disks, falling back to ATA IDENTIFY
+    pub fn probe(device_path: &str) -> Self {
+        let detected = crate::platform::get_physical_disks()
+            .unwrap_or_default()
+            .into_iter()
+            .find(|disk| disk.path == device_path);
+
+        let mut target = match detected {
+            Some(disk) => Self {
+                path: device_path.to_string(),
+                serial: disk.serial,
+                model: disk.model,
+                size_bytes: disk.size_bytes,
+                interface: disk.bus_type,
+                removable: disk.removable,
+            },
+            None => Self { path: device_path.to_string(), ..Self::default() },
+        };
+
+        if (target.serial.is_empty() || target.model.is_empty())
+            && let Some(geometry) = DriveInterface::new(device_path)
+                .and_then(|drive| drive.identify_device().map(|data| drive.parse_drive_geometry(&data)))
+                .ok()
+        {
+            if target.serial.is_empty() {
+                target.serial = geometry.serial.trim().to_string();
+            }
+            if target.model.is_empty() {
+                target.model = geometry.model.trim().to_string();
+            }
+            if target.size_bytes == 0 {
+                target.size_bytes = geometry.total_sectors * geometry.sector_size;
+            }
+        }
+
+        target
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PolicyDecision {
+    pub allowed: bool,
+    /// Name of the deciding rule; None when the default action applied
+    pub rule: Option<String>,
+    pub reason: String,
+}
+
+impl TargetPolicy {
+    /// Load a policy from a JSON file
+    pub fn load(path: &str) -> WipeResult2<Self> {
+        let content = std::fs::read_to_string(path).map_err(|e| WipeError {
+            code: WipeErrorCode::UnknownError,
+            message: format!("Failed to read policy {}: {}", path, e),
+            sector: None,
+        })?;
+        serde_json::from_str(&content).map_err(|e| WipeError {
+            code: WipeErrorCode::UnknownError,
+            message: format!("Failed to parse policy {}: {}", path, e),
+            sector: None,
+        })
+    }
+
+    pub fn evaluate(&self, target: &PolicyTarget) -> PolicyDecision {
+        if self.require_serial && target.serial.is_empty() {
+            return PolicyDecision {
+                allowed: false,
+                rule: None,
+                reason: format!("Serial number of {} could not be read", target.path),
+            };
+        }
+
+        let matching = |action: PolicyAction| {
+            self.rules.iter().find(|rule| rule.action == action && rule.conditions.matches(target))
+        };
+
+        if let Some(rule) = matching(PolicyAction::Deny) {
+            return PolicyDecision {
+                allowed: false,
+                rule: Some(rule.name.clone()),
+                reason: format!("{} ({} {}) is protected by rule '{}'", target.path, target.model, target.serial, rule.name),
+            };
+        }
+        if let Some(rule) = matching(PolicyAction::Allow) {
+            return PolicyDecision {
+                allowed: true,
+                rule: Some(rule.name.clone()),
+                reason: format!("Allowed by rule '{}'", rule.name),
+            };
+        }
+
+        PolicyDecision {
+            allowed: self.default_action == PolicyAction::Allow,
+            rule: None,
+            reason: match self.default_action {
+                PolicyAction::Allow => "No rule matched; allowed by default".to_string(),
+                PolicyAction::Deny => format!("{} ({} {}) matches no allow rule", target.path, target.model, target.serial),
+            },
+        }
+    }
+
+    /// Refuse with `PolicyDenied` unless the policy allows wiping `device_path`
+    pub fn enforce(&self, device_path: &str) -> WipeResult2<PolicyDecision> {
+        let decision = self.evaluate(&PolicyTarget::probe(device_path));
+        if !decision.allowed {
+            warn!(device = %device_path, reason = %decision.reason, "Wipe refused by policy");
+            return Err(WipeError {
+                code: WipeErrorCode::PolicyDenied,
+                message: decision.reason,
+                sector: None,
+            });
+        }
+        Ok(decision)
+    }
+}
+
+/// Case-insensitive match supporting `*` (any run) and `?` (any one character)
+pub fn wildcard_match(pattern: &str, value: &str) -> bool {
+    let pattern: Vec<char> = pattern.to_lowercase().chars().collect();
+    let value: Vec<char> = value.to_lowercase().chars().collect();
+    let (mut p, mut v) = (0, 0);
+    let mut backtrack: Option<(usize, usize)> = None;
+
+    while v < value.len() {
+        if p < pattern.len() && (pattern[p] == '?' || pattern[p] == value[v]) {
+            p += 1;
+            v += 1;
+        } else if p < pattern.len() && pattern[p] == '*' {
+            backtrack = Some((p, v));
+            p += 1;
+        } else if let Some((star, matched)) = backtrack {
+            p = star + 1;
+            v = matched + 1;
+            backtrack = Some((star, matched + 1));
+        } else {
+            return false;
+        }
+    }
+
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn target(serial: &str, interface: &str, removable: bool) -> PolicyTarget {
+        PolicyTarget {
+            path: "/dev/sdb".to_string(),
+            serial: serial.to_string(),
+            model: "Samsung SSD 870 EVO".to_string(),
+            size_bytes: 500_107_862_016,
+            interface: interface.to_string(),
+            removable,
+        }
+    }
+
+    #[test]
+    fn test_deny_rules_win() {
+        let policy: TargetPolicy = serde_json::from_str(r#"{
+            "default_action": "Deny",
+            "rules": [
+                {"name": "usb media", "action": "Allow", "interface": "usb", "removable": true},
+                {"name": "evidence drives", "action": "Deny", "serial": "S5Y1*"}
+            ]
+        }"#).unwrap();
+
+        assert!(policy.evaluate(&target("X123", "USB", true)).allowed);
+        let denied = policy.evaluate(&target("S5Y1NX0R", "USB", true));
+        assert!(!denied.allowed);
+        assert_eq!(denied.rule.as_deref(), Some("evidence drives"));
+        assert!(!policy.evaluate(&target("X123", "SATA", false)).allowed);
+    }
+
+    #[test]
+    fn test_wildcard_match() {
+        assert!(wildcard_match("WD-*", "wd-WCC4E123"));
+        assert!(wildcard_match("*870*", "Samsung SSD 870 EVO"));
+        assert!(wildcard_match("ST?000", "ST2000"));
+        assert!(!wildcard_match("ST?000", "ST20000"));
+        assert!(!wildcard_match("S5Y1*", ""));
+    }
+}
@@ -0,0 +1,531 @@
+use serde::{Deserialize, Serialize};
+use std::fs::OpenOptions;
+use std::path::Path;
+
+use crate::core::{
+    FirmwareErase, HardwarePreference, OperatorIdentity, PolicyTarget, SanitizationStandard, TargetPolicy, TargetType,
+    FailureRisk, WearThresholds, WipeAdvisory, WipeRecommendation, WipeSpec,
+};
+use crate::hardware::DriveInterface;
+use crate::smart::read_device_health;
+use crate::raid::detect_virtual_disk;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum PreflightSeverity {
+    /// The wipe must not be started until this is resolved
+    Blocking,
+    /// The wipe can run but the operator should be aware of this
+    Warning,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum PreflightCheck {
+    DeviceNotFound,
+    InsufficientPrivileges,
+    MountedFilesystem,
+    SystemDrive,
+    SecurityLocked,
+    SecurityFrozen,
+    BitLockerActive,
+    EncryptedVolume,
+    RaidMember,
+    RaidVirtualDisk,
+    VolumeGroupMember,
+    DeviceHealth,
+    PolicyDenied,
+    /// Nobody is logged in, or the operator may not wipe devices
+    OperatorNotPermitted,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PreflightIssue {
+    pub check: PreflightCheck,
+    pub severity: PreflightSeverity,
+    pub message: String,
+    /// What the operator can do to resolve the issue
+    pub remediation: String,
+}
+
+/// Result of a dry-run check of a device before wiping
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PreflightReport {
+    pub target_path: String,
+    pub target_type: TargetType,
+    pub standard: SanitizationStandard,
+    pub issues: Vec<PreflightIssue>,
+}
+
+impl PreflightReport {
+    pub fn can_proceed(&self) -> bool {
+        self.blocking_issues().next().is_none()
+    }
+
+    pub fn blocking_issues(&self) -> impl Iterator<Item = &PreflightIssue> {
+        self.issues.iter().filter(|i| i.severity == PreflightSeverity::Blocking)
+    }
+
+    pub fn warnings(&self) -> impl Iterator<Item = &PreflightIssue> {
+        self.issues.iter().filter(|i| i.severity == PreflightSeverity::Warning)
+    }
+
+    fn push(&mut self, check: PreflightCheck, severity: PreflightSeverity, message: String, remediation: &str) {
+        self.issues.push(PreflightIssue {
+            check,
+            severity,
+            message,
+            remediation: remediation.to_string(),
+        });
+    }
+}
+
+/// A mounted filesystem that lives on the device being checked
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MountedVolume {
+    pub source: String,
+    pub mount_point: String,
+    pub fs_type: String,
+}
+
+/// Run every preflight check without touching the device contents
+pub fn run_preflight(
+    target_path: &str,
+    target_type: TargetType,
+    standard: SanitizationStandard,
+    policy: &TargetPolicy,
+    wear: &WearThresholds,
+) -> PreflightReport {
+    let mut report = PreflightReport {
+        target_path: target_path.to_string(),
+        target_type,
+        standard,
+        issues: Vec::new(),
+    };
+
+    if !Path::new(target_path).exists() && !target_path.starts_with(r"\\.\") {
+        report.push(
+            PreflightCheck::DeviceNotFound,
+            PreflightSeverity::Blocking,
+            format!("Device {} does not exist", target_path),
+            "Rescan devices and select a drive that is currently attached",
+        );
+        return report;
+    }
+
+    check_policy(&mut report, policy);
+    check_privileges(&mut report);
+    check_volumes(&mut report);
+    check_raid(&mut report);
+    check_security_state(&mut report);
+    check_health(&mut report, wear);
+
+    report
+}
+
+/// Checks that depend on how the device will be wiped: an ATA secure erase
+/// is refused by a frozen drive, so the freeze lock blocks it outright
+pub fn check_method(report: &mut PreflightReport, spec: &WipeSpec) {
+    let ata_erase = matches!(
+        spec.hardware,
+        HardwarePreference::Firmware(FirmwareErase::AtaSecureErase | FirmwareErase::AtaEnhancedSecureErase)
+    );
+    if !ata_erase {
+        return;
+    }
+    for issue in report.issues.iter_mut().filter(|issue| issue.check == PreflightCheck::SecurityFrozen) {
+        issue.severity = PreflightSeverity::Blocking;
+        issue.message = "Drive security is frozen; the drive will refuse the ATA secure erase".to_string();
+    }
+}
+
+/// Whether the wipe could be started by `operator`
+pub fn check_operator(report: &mut PreflightReport, operator: Option<&OperatorIdentity>) {
+    match operator {
+        None => report.push(
+            PreflightCheck::OperatorNotPermitted,
+            PreflightSeverity::Blocking,
+            "No operator is logged in".to_string(),
+            "Log in with an account allowed to wipe devices",
+        ),
+        Some(operator) => {
+            if let Err(e) = operator.require_sanitize() {
+                report.push(
+                    PreflightCheck::OperatorNotPermitted,
+                    PreflightSeverity::Blocking,
+                    e.message,
+                    "Ask an administrator for an operator account, or have an operator start the wipe",
+                );
+            }
+        }
+    }
+}
+
+fn check_policy(report: &mut PreflightReport, policy: &TargetPolicy) {
+    let decision = policy.evaluate(&PolicyTarget::probe(&report.target_path));
+    if !decision.allowed {
+        report.push(
+            PreflightCheck::PolicyDenied,
+            PreflightSeverity::Blocking,
+            decision.reason,
+            "The wipe target policy forbids wiping this device; ask an administrator to change the policy",
+        );
+    }
+}
+
+fn check_privileges(report: &mut PreflightReport) {
+    if let Err(e) = OpenOptions::new().read(true).write(true).open(&report.target_path)
+        && e.kind() == std::io::ErrorKind::PermissionDenied
+    {
+        report.push(
+            PreflightCheck::InsufficientPrivileges,
+            PreflightSeverity::Blocking,
+            format!("Cannot open {} for writing: {}", report.target_path, e),
+            if cfg!(windows) { "Run the tool as Administrator" } else { "Run the tool as root (sudo)" },
+        );
+    }
+}
+
+fn check_security_state(report: &mut PreflightReport) {
+    let Ok(drive) = DriveInterface::new(&report.target_path) else {
+        return;
+    };
+    let Ok(identify_data) = drive.identify_device() else {
+        return;
+    };
+
+    let geometry = drive.parse_drive_geometry(&identify_data);
+    let security = drive.parse_security_features(&identify_data);
+
+    if security.security_locked {
+        report.push(
+            PreflightCheck::SecurityLocked,
+            PreflightSeverity::Blocking,
+            "Drive is ATA security locked".to_string(),
+            "Unlock the drive with its user password, or use a PSID revert for self-encrypting drives",
+        );
+    }
+
+    if security.security_frozen {
+        // HPA removal uses SET MAX ADDRESS, which a frozen drive rejects
+        let needs_unfrozen = geometry.has_hpa && matches!(report.target_type, TargetType::HDD);
+        report.push(
+            PreflightCheck::SecurityFrozen,
+            if needs_unfrozen { PreflightSeverity::Blocking } else { PreflightSeverity::Warning },
+            if needs_unfrozen {
+                "Drive security is frozen; the hidden HPA area cannot be unlocked for wiping".to_string()
+            } else {
+                "Drive security is frozen; ATA secure erase and HPA/DCO changes are unavailable".to_string()
+            },
+            "Suspend and resume the system, or hot-replug the drive, to clear the freeze lock",
+        );
+    }
+}
+
+fn check_raid(report: &mut PreflightReport) {
+    let Some(virtual_disk) = detect_virtual_disk(&report.target_path) else {
+        return;
+    };
+
+    let members = if virtual_disk.members.is_empty() {
+        "its member drives could not be listed".to_string()
+    } else {
+        let slots: Vec<String> = virtual_disk.members
+            .iter()
+            .map(|member| format!("{} {}", member.enclosure_slot, member.model))
+            .collect();
+        format!("members: {}", slots.join(", "))
+    };
+
+    report.push(
+        PreflightCheck::RaidVirtualDisk,
+        PreflightSeverity::Blocking,
+        format!(
+            "{} is a {} virtual disk{}; wiping it leaves parity, spares and controller metadata on the member drives ({})",
+            report.target_path,
+            virtual_disk.vendor.as_str(),
+            virtual_disk.raid_level.as_deref().map(|level| format!(" ({})", level)).unwrap_or_default(),
+            members
+        ),
+        &virtual_disk.guidance(),
+    );
+}
+
+fn check_health(report: &mut PreflightReport, wear: &WearThresholds) {
+    let advisory = WipeAdvisory::evaluate(read_device_health(&report.target_path), wear);
+    if advisory.recommendation == WipeRecommendation::Destroy {
+        report.push(
+            PreflightCheck::DeviceHealth,
+            PreflightSeverity::Warning,
+            format!("Drive is likely to fail ({})", advisory.reasons.join(", ")),
+            "Destroy the drive instead of overwriting it; a wipe of a failing drive takes hours and may not complete",
+        );
+    } else if advisory.risk == FailureRisk::Elevated {
+        let health = &advisory.health;
+        let reasons = if advisory.reasons.is_empty() { &health.warnings } else { &advisory.reasons };
+        let detail = if reasons.is_empty() {
+            String::new()
+        } else {
+            format!(" ({})", reasons.join(", "))
+        };
+        report.push(
+            PreflightCheck::DeviceHealth,
+            PreflightSeverity::Warning,
+            format!("SMART reports drive health as {}{}", health.health_status.as_str(), detail),
+            "Expect unreadable sectors; consider physical destruction if the wipe cannot complete",
+        );
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn check_volumes(report: &mut PreflightReport) {
+    // Mount points that indicate the device hosts the running operating system
+    const SYSTEM_MOUNT_POINTS: &[&str] = &["/", "/boot", "/boot/efi", "/usr", "/var", "/home"];
+
+    let mounts = mounted_volumes(&report.target_path);
+    for volume in &mounts {
+        if SYSTEM_MOUNT_POINTS.contains(&volume.mount_point.as_str()) {
+            report.push(
+                PreflightCheck::SystemDrive,
+                PreflightSeverity::Blocking,
+                format!("{} is mounted at {} and hosts the running system", volume.source, volume.mount_point),
+                "Boot from external media to wipe the system drive",
+            );
+        } else {
+            report.push(
+                PreflightCheck::MountedFilesystem,
+                PreflightSeverity::Warning,
+                format!("{} is mounted at {} ({}) and will be unmounted before wiping", volume.source, volume.mount_point, volume.fs_type),
+                "Close any programs using the filesystem so it can be released",
+            );
+        }
+    }
+
+    for name in linux::block_device_names(&report.target_path) {
+        if linux::is_swap(&name) {
+            report.push(
+                PreflightCheck::SystemDrive,
+                PreflightSeverity::Blocking,
+                format!("/dev/{} is in use as swap", name),
+                "Disable swap on the device with swapoff",
+            );
+        }
+
+        for holder in linux::holders(&name) {
+            if holder.starts_with("md") {
+                report.push(
+                    PreflightCheck::RaidMember,
+                    PreflightSeverity::Blocking,
+                    format!("/dev/{} is a member of RAID array /dev/{}", name, holder),
+                    "Stop the array or remove the device from it with mdadm",
+                );
+            } else if holder.starts_with("dm-") {
+                let dm_uuid = linux::dm_uuid(&holder);
+                if dm_uuid.starts_with("CRYPT-") {
+                    report.push(
+                        PreflightCheck::EncryptedVolume,
+                        PreflightSeverity::Blocking,
+                        format!("/dev/{} is an open encrypted volume (/dev/{})", name, holder),
+                        "Close the mapping with cryptsetup close",
+                    );
+                } else if dm_uuid.starts_with("LVM-") {
+                    report.push(
+                        PreflightCheck::VolumeGroupMember,
+                        PreflightSeverity::Blocking,
+                        format!("/dev/{} backs an active LVM volume (/dev/{})", name, holder),
+                        "Deactivate the volume group with vgchange -an",
+                    );
+                } else {
+                    report.push(
+                        PreflightCheck::VolumeGroupMember,
+                        PreflightSeverity::Blocking,
+                        format!("/dev/{} is held by device-mapper target /dev/{}", name, holder),
+                        "Remove the device-mapper mapping with dmsetup remove",
+                    );
+                }
+            }
+        }
+    }
+}
+
+#[cfg(windows)]
+fn check_volumes(report: &mut PreflightReport) {
+    let system_drive = std::env::var("SystemDrive").unwrap_or_else(|_| "C:".to_string());
+
+    for letter in windows::volume_letters(&report.target_path) {
+        let volume = format!("{}:", letter);
+        if volume.eq_ignore_ascii_case(&system_drive) {
+            report.push(
+                PreflightCheck::SystemDrive,
+                PreflightSeverity::Blocking,
+                format!("Volume {} is the Windows system drive", volume),
+                "Boot from external media to wipe the system drive",
+            );
+        } else {
+            report.push(
+                PreflightCheck::MountedFilesystem,
+                PreflightSeverity::Warning,
+                format!("Volume {} is mounted and will be locked and dismounted before wiping", volume),
+                "Close any programs using the volume so it can be locked",
+            );
+        }
+
+        if windows::bitlocker_protection_on(&volume) {
+            report.push(
+                PreflightCheck::BitLockerActive,
+                PreflightSeverity::Blocking,
+                format!("BitLocker protection is on for volume {}", volume),
+                "Suspend or turn off BitLocker (manage-bde -off) before wiping",
+            );
+        }
+    }
+}
+
+#[cfg(not(any(target_os = "linux", windows)))]
+fn check_volumes(_report: &mut PreflightReport) {}
+
+/// Filesystems on the device (or any of its partitions) that are currently mounted
+pub fn mounted_volumes(device_path: &str) -> Vec<MountedVolume> {
+    #[cfg(target_os = "linux")]
+    {
+        let names = linux::block_device_names(device_path);
+        let Ok(mounts) = std::fs::read_to_string("/proc/self/mounts") else {
+            return Vec::new();
+        };
+
+        mounts
+            .lines()
+            .filter_map(|line| {
+                let mut fields = line.split_whitespace();
+                let source = fields.next()?;
+                let mount_point = fields.next()?.replace("\\040", " ");
+                let fs_type = fields.next()?;
+
+                let source_name = std::fs::canonicalize(source).ok()?
+                    .file_name()?.to_string_lossy().to_string();
+                if !names.contains(&source_name) {
+                    return None;
+                }
+
+                Some(MountedVolume {
+                    source: source.to_string(),
+                    mount_point,
+                    fs_type: fs_type.to_string(),
+                })
+            })
+            .collect()
+    }
+
+    #[cfg(windows)]
+    {
+        windows::volume_letters(device_path)
+            .into_iter()
+            .map(|letter| MountedVolume {
+                source: device_path.to_string(),
+                mount_point: format!("{}:\\", letter),
+                fs_type: String::new(),
+            })
+            .collect()
+    }
+
+    #[cfg(not(any(target_os = "linux", windows)))]
+    {
+        let _ = device_path;
+        Vec::new()
+    }
+}
+
+#[cfg(target_os = "linux")]
+mod linux {
+    use std::fs;
+    use std::path::Path;
+
+    /// Kernel name of the device followed by the names of its partitions
+    pub fn block_device_names(device_path: &str) -> Vec<String> {
+        let Some(name) = fs::canonicalize(device_path).ok()
+            .and_then(|p| p.file_name().map(|n| n.to_string_lossy().to_string()))
+        else {
+            return Vec::new();
+        };
+
+        let mut names = vec![name.clone()];
+        if let Ok(entries) = fs::read_dir(format!("/sys/class/block/{}", name)) {
+            for entry in entries.flatten() {
+                let child = entry.file_name().to_string_lossy().to_string();
+                if child.starts_with(&name) && entry.path().join("partition").exists() {
+                    names.push(child);
+                }
+            }
+        }
+        names
+    }
+
+    pub fn holders(name: &str) -> Vec<String> {
+        fs::read_dir(format!("/sys/class/block/{}/holders", name))
+            .map(|entries| {
+                entries.flatten().map(|e| e.file_name().to_string_lossy().to_string()).collect()
+            })
+            .unwrap_or_default()
+    }
+
+    pub fn dm_uuid(dm_name: &str) -> String {
+        fs::read_to_string(format!("/sys/class/block/{}/dm/uuid", dm_name))
+            .map(|uuid| uuid.trim().to_string())
+            .unwrap_or_default()
+    }
+
+    pub fn is_swap(name: &str) -> bool {
+        fs::read_to_string("/proc/swaps")
+            .map(|swaps| {
+                swaps.lines().skip(1).any(|line| {
+                    line.split_whitespace().next()
+                        .and_then(|source| fs::canonicalize(source).ok())
+                        .map(|p| p == Path::new("/dev").join(name))
+                        .unwrap_or(false)
+                })
+            })
+            .unwrap_or(false)
+    }
+}
+
+#[cfg(windows)]
+mod windows {
+    use std::process::Command;
+
+    /// Drive letters of volumes that live on the given device path
+    pub fn volume_letters(device_path: &str) -> Vec<char> {
+        let trimmed = device_path.trim_start_matches(r"\\.\");
+
+        // Volume paths such as \\.\E: or E:
+        if trimmed.len() >= 2 && trimmed.as_bytes()[1] == b':' {
+            return vec![trimmed.chars().next().unwrap().to_ascii_uppercase()];
+        }
+
+        // Physical drives: ask the storage stack for their partitions
+        let Some(disk_number) = trimmed.strip_prefix("PhysicalDrive") else {
+            return Vec::new();
+        };
+        let script = format!(
+            "Get-Partition -DiskNumber {} | Where-Object DriveLetter | ForEach-Object {{ $_.DriveLetter }}",
+            disk_number
+        );
+
+        Command::new("powershell")
+            .args(&["-NoProfile", "-Command", &script])
+            .output()
+            .map(|output| {
+                String::from_utf8_lossy(&output.stdout)
+                    .lines()
+                    .filter_map(|line| line.trim().chars().next())
+                    .filter(|c| c.is_ascii_alphabetic())
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    pub fn bitlocker_protection_on(volume: &str) -> bool {
+        Command::new("manage-bde")
+            .args(&["-status", volume])
+            .output()
+            .map(|output| String::from_utf8_lossy(&output.stdout).contains("Protection On"))
+            .unwrap_or(false)
+    }
+}
@@ -0,0 +1,136 @@
+//! Wipe priorities and preemption
+//!
+//! A rush drive should not wait behind a queue of routine ones. Every
+//! `WipeRequest` carries a `WipePriority`: the schedule starts due jobs
+//! highest priority first, and once `ScheduleConfig::max_running` wipes are
+//! running only a job that preempts others may start.
+//!
+//! With `ScheduleConfig::preempt_for_high_priority`, a high-priority wipe
+//! using a firmware erase holds every lower-priority overwrite on the
+//! station, including ones started while it runs, until it finishes. The
+//! overwrites stop at their next block boundary like any paused wipe and
+//! continue from there; a firmware erase cannot pause and is never held.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use uuid::Uuid;
+
+use crate::core::LimitsHandle;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum WipePriority {
+    Low,
+    #[default]
+    Normal,
+    /// Started before other due jobs, and may preempt lower-priority overwrites
+    High,
+}
+
+impl WipePriority {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.to_ascii_lowercase().as_str() {
+            "low" => Some(WipePriority::Low),
+            "normal" => Some(WipePriority::Normal),
+            "high" => Some(WipePriority::High),
+            _ => None,
+        }
+    }
+}
+
+struct RunningWipe {
+    priority: WipePriority,
+    firmware_erase: bool,
+    /// Holds lower-priority overwrites while it runs
+    preempting: bool,
+    limits: Arc<LimitsHandle>,
+    held: bool,
+}
+
+/// Running wipes of an engine and which of them are held for a higher-priority one
+#[derive(Default)]
+pub struct Preemptions {
+    running: Mutex<HashMap<Uuid, RunningWipe>>,
+}
+
+impl Preemptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Track a wipe from its first write until `unregister`. Returns the
+    /// wipes this holds, which may be the new wipe itself.
+    pub fn register(
+        &self,
+        operation_id: Uuid,
+        priority: WipePriority,
+        firmware_erase: bool,
+        preempting: bool,
+        limits: Arc<LimitsHandle>,
+    ) -> Vec<Uuid> {
+        let mut running = self.running.lock().unwrap();
+        running.insert(operation_id, RunningWipe { priority, firmware_erase, preempting, limits, held: false });
+        apply(&mut running)
+    }
+
+    /// Stop tracking a finished wipe; the wipes it held continue
+    pub fn unregister(&self, operation_id: Uuid) {
+        let mut running = self.running.lock().unwrap();
+        running.remove(&operation_id);
+        apply(&mut running);
+    }
+
+    /// Running wipes currently held for a higher-priority one
+    pub fn held(&self) -> Vec<Uuid> {
+        self.running.lock().unwrap().iter().filter(|(_, wipe)| wipe.held).map(|(id, _)| *id).collect()
+    }
+}
+
+/// Hold every overwrite below the priority of a preempting wipe and release
+/// the rest; returns the wipes newly held
+fn apply(running: &mut HashMap<Uuid, RunningWipe>) -> Vec<Uuid> {
+    let preempting = running.values().filter(|wipe| wipe.preempting).map(|wipe| wipe.priority).max();
+    let mut newly_held = Vec::new();
+    for (operation_id, wipe) in running.iter_mut() {
+        let held = !wipe.firmware_erase && preempting.is_some_and(|priority| wipe.priority < priority);
+        if held && !wipe.held {
+            newly_held.push(*operation_id);
+        }
+        wipe.held = held;
+        wipe.limits.set_preempted(held);
+    }
+    newly_held
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::OperationLimits;
+
+    #[test]
+    fn test_high_priority_erase_holds_lower_overwrites() {
+        let preemptions = Preemptions::new();
+        let handle = || Arc::new(LimitsHandle::new(OperationLimits::default()));
+        let (low, normal_erase, high, late) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+        let low_limits = handle();
+
+        assert!(preemptions.register(low, WipePriority::Low, false, false, low_limits.clone()).is_empty());
+        assert!(preemptions.register(normal_erase, WipePriority::Normal, true, false, handle()).is_empty());
+        assert_eq!(preemptions.register(high, WipePriority::High, true, true, handle()), vec![low]);
+        assert!(low_limits.is_paused());
+        // Held, not paused: an operator's resume does not release it
+        assert!(low_limits.is_preempted() && !low_limits.is_pause_requested());
+
+        // Overwrites started while the erase runs wait for it too
+        let late_limits = handle();
+        assert_eq!(preemptions.register(late, WipePriority::Normal, false, false, late_limits.clone()), vec![late]);
+
+        preemptions.unregister(high);
+        assert!(preemptions.held().is_empty());
+        assert!(!low_limits.is_paused() && !late_limits.is_paused());
+
+        assert!(WipePriority::High > WipePriority::Normal && WipePriority::Normal > WipePriority::Low);
+        assert_eq!(WipePriority::parse("HIGH"), Some(WipePriority::High));
+        assert_eq!(WipePriority::default(), WipePriority::Normal);
+    }
+}
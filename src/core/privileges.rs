@@ -0,0 +1,152 @@
+//! Whether the process can open devices for raw access, and relaunching it elevated
+//!
+//! Raw writes to a disk need root on Linux and an elevated token on Windows.
+//! Without them the open fails with "permission denied", which front ends
+//! should catch before the operator starts a wipe rather than afterwards.
+//! `relaunch_elevated` starts a new copy of the program through UAC
+//! (Windows) or pkexec / sudo (Linux); the caller exits once it has started.
+
+use serde::{Deserialize, Serialize};
+use std::fs::OpenOptions;
+use std::path::PathBuf;
+use std::process::Command;
+
+use crate::core::{WipeError, WipeErrorCode, WipeResult2};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ElevationMethod {
+    /// The UAC prompt, via `Start-Process -Verb RunAs`
+    Uac,
+    /// polkit's graphical password prompt
+    Pkexec,
+    /// sudo; needs a terminal to ask for the password
+    Sudo,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PrivilegeStatus {
+    /// Running as root or with an elevated token
+    pub elevated: bool,
+    pub user: Option<String>,
+    /// How `relaunch_elevated` would elevate; `None` when already elevated
+    /// or no helper is available
+    pub elevation_method: Option<ElevationMethod>,
+}
+
+pub fn privilege_status() -> PrivilegeStatus {
+    let elevated = is_elevated();
+    PrivilegeStatus {
+        elevated,
+        user: std::env::var("USER").or_else(|_| std::env::var("USERNAME")).ok(),
+        elevation_method: if elevated { None } else { elevation_method() },
+    }
+}
+
+#[cfg(unix)]
+pub fn is_elevated() -> bool {
+    unsafe { libc::geteuid() == 0 }
+}
+
+#[cfg(windows)]
+pub fn is_elevated() -> bool {
+    use windows::Win32::Foundation::{CloseHandle, HANDLE};
+    use windows::Win32::Security::{GetTokenInformation, TokenElevation, TOKEN_ELEVATION, TOKEN_QUERY};
+    use windows::Win32::System::Threading::{GetCurrentProcess, OpenProcessToken};
+
+    unsafe {
+        let mut token = HANDLE::default();
+        if OpenProcessToken(GetCurrentProcess(), TOKEN_QUERY, &mut token).is_err() {
+            return false;
+        }
+        let mut elevation = TOKEN_ELEVATION::default();
+        let mut returned = 0u32;
+        let queried = GetTokenInformation(
+            token,
+            TokenElevation,
+            Some(&mut elevation as *mut TOKEN_ELEVATION as *mut _),
+            std::mem::size_of::<TOKEN_ELEVATION>() as u32,
+            &mut returned,
+        );
+        let _ = CloseHandle(token);
+        queried.is_ok() && elevation.TokenIsElevated != 0
+    }
+}
+
+#[cfg(not(any(unix, windows)))]
+pub fn is_elevated() -> bool {
+    false
+}
+
+/// Opening `device_path` for writing is refused with the current privileges.
+/// Devices that cannot be opened for other reasons (missing, busy) are not
+/// reported; preflight covers those.
+pub fn requires_elevation(device_path: &str) -> bool {
+    match OpenOptions::new().read(true).write(true).open(device_path) {
+        Ok(_) => false,
+        Err(e) => e.kind() == std::io::ErrorKind::PermissionDenied,
+    }
+}
+
+fn elevation_method() -> Option<ElevationMethod> {
+    if cfg!(windows) {
+        Some(ElevationMethod::Uac)
+    } else if find_program("pkexec").is_some() {
+        Some(ElevationMethod::Pkexec)
+    } else if find_program("sudo").is_some() {
+        Some(ElevationMethod::Sudo)
+    } else {
+        None
+    }
+}
+
+fn find_program(name: &str) -> Option<PathBuf> {
+    let path = std::env::var_os("PATH")?;
+    std::env::split_paths(&path).map(|dir| dir.join(name)).find(|candidate| candidate.is_file())
+}
+
+/// Start this program again, elevated and with the same arguments. Returns
+/// once the new process has been started (on Windows, once the UAC prompt is
+/// up); the caller should exit.
+pub fn relaunch_elevated() -> WipeResult2<()> {
+    let program = std::env::current_exe().map_err(|e| elevation_error(format!("Cannot find the program: {}", e)))?;
+    let args: Vec<String> = std::env::args().skip(1).collect();
+
+    let method = elevation_method()
+        .ok_or_else(|| elevation_error("Neither pkexec nor sudo is available; restart the tool as root".to_string()))?;
+    let mut command = match method {
+        ElevationMethod::Uac => {
+            let quoted: Vec<String> = args.iter().map(|arg| format!("'{}'", arg.replace('\'', "''"))).collect();
+            let mut script = format!("Start-Process -Verb RunAs -FilePath '{}'", program.display().to_string().replace('\'', "''"));
+            if !quoted.is_empty() {
+                script.push_str(&format!(" -ArgumentList {}", quoted.join(",")));
+            }
+            let mut command = Command::new("powershell");
+            command.args(["-NoProfile", "-NonInteractive", "-Command", &script]);
+            command
+        }
+        ElevationMethod::Pkexec => {
+            // pkexec clears the environment; keep what a GUI needs to open its window
+            let mut command = Command::new("pkexec");
+            command.arg("env");
+            for name in ["DISPLAY", "WAYLAND_DISPLAY", "XAUTHORITY", "XDG_RUNTIME_DIR"] {
+                if let Ok(value) = std::env::var(name) {
+                    command.arg(format!("{}={}", name, value));
+                }
+            }
+            command.arg(&program).args(&args);
+            command
+        }
+        ElevationMethod::Sudo => {
+            let mut command = Command::new("sudo");
+            command.arg("--preserve-env").arg(&program).args(&args);
+            command
+        }
+    };
+
+    command.spawn().map(|_| ()).map_err(|e| elevation_error(format!("Failed to relaunch elevated: {}", e)))
+}
+
+fn elevation_error(message: String) -> WipeError {
+    WipeError { code: WipeErrorCode::InsufficientPrivileges, message, sector: None }
+}
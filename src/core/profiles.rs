@@ -0,0 +1,181 @@
+//! Compliance profile presets
+//!
+//! A profile names a sanitization standard an organisation has to meet and
+//! fixes everything that follows from it: the overwrite passes, how much of
+//! the device is read back, and the statement printed on the certificate.
+//! Profiles are chosen with `--profile` on the command line or
+//! `default_profile` in the configuration, and the profile and its citation
+//! are embedded in the signed certificate.
+
+use serde::{Deserialize, Serialize};
+
+use crate::core::{OverwritePattern, SanitizationStandard, WipeSpec, HardwarePreference};
+use crate::verification::VerificationPolicy;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ComplianceProfile {
+    #[serde(rename = "nist-800-88-clear")]
+    Nist80088Clear,
+    #[serde(rename = "nist-800-88-purge")]
+    Nist80088Purge,
+    #[serde(rename = "dod-5220.22-m")]
+    DoD522022M,
+    #[serde(rename = "bsi-2011-vs")]
+    Bsi2011Vs,
+    #[serde(rename = "csec-itsg-06")]
+    CsecItsg06,
+    #[serde(rename = "hmg-is5-baseline")]
+    HmgIs5Baseline,
+    #[serde(rename = "hmg-is5-enhanced")]
+    HmgIs5Enhanced,
+}
+
+/// Profile details as recorded in certificates and reports
+pub use hdd_tool_certificate::ComplianceStatement;
+
+impl ComplianceProfile {
+    pub const ALL: [ComplianceProfile; 7] = [
+        ComplianceProfile::Nist80088Clear,
+        ComplianceProfile::Nist80088Purge,
+        ComplianceProfile::DoD522022M,
+        ComplianceProfile::Bsi2011Vs,
+        ComplianceProfile::CsecItsg06,
+        ComplianceProfile::HmgIs5Baseline,
+        ComplianceProfile::HmgIs5Enhanced,
+    ];
+
+    /// Identifier used on the command line and in configuration files
+    pub fn id(&self) -> &'static str {
+        match self {
+            ComplianceProfile::Nist80088Clear => "nist-800-88-clear",
+            ComplianceProfile::Nist80088Purge => "nist-800-88-purge",
+            ComplianceProfile::DoD522022M => "dod-5220.22-m",
+            ComplianceProfile::Bsi2011Vs => "bsi-2011-vs",
+            ComplianceProfile::CsecItsg06 => "csec-itsg-06",
+            ComplianceProfile::HmgIs5Baseline => "hmg-is5-baseline",
+            ComplianceProfile::HmgIs5Enhanced => "hmg-is5-enhanced",
+        }
+    }
+
+    pub fn parse(id: &str) -> Option<Self> {
+        Self::ALL.iter().copied().find(|profile| profile.id().eq_ignore_ascii_case(id))
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            ComplianceProfile::Nist80088Clear => "NIST SP 800-88 Rev. 1 Clear",
+            ComplianceProfile::Nist80088Purge => "NIST SP 800-88 Rev. 1 Purge",
+            ComplianceProfile::DoD522022M => "DoD 5220.22-M",
+            ComplianceProfile::Bsi2011Vs => "BSI 2011-VS",
+            ComplianceProfile::CsecItsg06 => "CSEC ITSG-06",
+            ComplianceProfile::HmgIs5Baseline => "HMG IS5 Baseline",
+            ComplianceProfile::HmgIs5Enhanced => "HMG IS5 Enhanced",
+        }
+    }
+
+    pub fn citation(&self) -> &'static str {
+        match self {
+            ComplianceProfile::Nist80088Clear | ComplianceProfile::Nist80088Purge => {
+                "NIST Special Publication 800-88 Revision 1, Guidelines for Media Sanitization (December 2014)"
+            }
+            ComplianceProfile::DoD522022M => {
+                "DoD 5220.22-M, National Industrial Security Program Operating Manual (February 2006)"
+            }
+            ComplianceProfile::Bsi2011Vs => {
+                "BSI 2011-VS, Bundesamt für Sicherheit in der Informationstechnik"
+            }
+            ComplianceProfile::CsecItsg06 => {
+                "CSEC ITSG-06, Clearing and Declassifying Electronic Data Storage Devices (July 2006)"
+            }
+            ComplianceProfile::HmgIs5Baseline | ComplianceProfile::HmgIs5Enhanced => {
+                "HMG Infosec Standard No. 5, Secure Sanitisation (Issue 4.0, April 2011)"
+            }
+        }
+    }
+
+    /// Passes the profile prescribes
+    pub fn spec(&self) -> WipeSpec {
+        use OverwritePattern::Random;
+        let byte = OverwritePattern::byte;
+
+        match self {
+            ComplianceProfile::Nist80088Clear => SanitizationStandard::NIST_SP_800_88_R1.into(),
+            // Purge calls for the drive's own sanitize command where one exists
+            ComplianceProfile::Nist80088Purge => WipeSpec::overwrite(vec![Random])
+                .with_standard(SanitizationStandard::NIST_SP_800_88_R1)
+                .with_hardware(HardwarePreference::PreferFirmware),
+            ComplianceProfile::DoD522022M => SanitizationStandard::DoD_5220_22_M.into(),
+            ComplianceProfile::Bsi2011Vs => SanitizationStandard::BSI_2011_VS.into(),
+            ComplianceProfile::CsecItsg06 => WipeSpec::overwrite(vec![byte(0x00), byte(0xFF), Random]),
+            ComplianceProfile::HmgIs5Baseline => WipeSpec::overwrite(vec![byte(0x00)]),
+            ComplianceProfile::HmgIs5Enhanced => WipeSpec::overwrite(vec![byte(0x00), byte(0xFF), Random]),
+        }
+    }
+
+    /// Standard recorded on the wipe request. Standards the engine has no
+    /// variant for are recorded as NIST Clear, which every profile satisfies;
+    /// the profile itself is what the certificate cites.
+    pub fn standard(&self) -> SanitizationStandard {
+        self.spec().standard.unwrap_or(SanitizationStandard::NIST_SP_800_88_R1)
+    }
+
+    /// How much of the device is read back after the wipe
+    pub fn verification_policy(&self) -> VerificationPolicy {
+        match self {
+            ComplianceProfile::Nist80088Purge
+            | ComplianceProfile::CsecItsg06
+            | ComplianceProfile::HmgIs5Enhanced => VerificationPolicy::FullScan,
+            _ => VerificationPolicy::StratifiedSample,
+        }
+    }
+
+    /// Wording printed on the erasure certificate
+    pub fn certificate_statement(&self) -> String {
+        let method = self.spec().description();
+        match self {
+            ComplianceProfile::Nist80088Clear => format!(
+                "All user-addressable storage locations were sanitized to the Clear level of NIST SP 800-88 Rev. 1 ({}), protecting against simple non-invasive data recovery techniques.",
+                method
+            ),
+            ComplianceProfile::Nist80088Purge => format!(
+                "The media was sanitized to the Purge level of NIST SP 800-88 Rev. 1 ({}) and read back in full, rendering target data recovery infeasible using state of the art laboratory techniques.",
+                method
+            ),
+            ComplianceProfile::HmgIs5Baseline | ComplianceProfile::HmgIs5Enhanced => format!(
+                "The media was sanitised in accordance with {} ({}) and the overwrite was verified.",
+                self.name(),
+                method
+            ),
+            _ => format!(
+                "The media was sanitized in accordance with {} ({}) and the overwrite was verified.",
+                self.name(),
+                method
+            ),
+        }
+    }
+
+    pub fn statement(&self) -> ComplianceStatement {
+        ComplianceStatement {
+            profile: self.id().to_string(),
+            name: self.name().to_string(),
+            citation: self.citation().to_string(),
+            statement: self.certificate_statement(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_profile_ids_roundtrip() {
+        for profile in ComplianceProfile::ALL {
+            assert_eq!(ComplianceProfile::parse(profile.id()), Some(profile));
+            let json = serde_json::to_string(&profile).unwrap();
+            assert_eq!(json, format!("\"{}\"", profile.id()));
+        }
+        assert_eq!(ComplianceProfile::HmgIs5Enhanced.spec().pass_patterns().len(), 3);
+        assert!(ComplianceProfile::parse("gutmann").is_none());
+    }
+}
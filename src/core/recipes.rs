@@ -0,0 +1,245 @@
+//! User-defined wipe recipes
+//!
+//! A recipe is a TOML file naming a sequence of overwrite passes, for sites
+//! whose policy prescribes its own patterns:
+//!
+//! ```toml
+//! name = "acme-4-pass"
+//! description = "Zeros, ones, company marker, random"
+//! verify = true
+//! verification = "FullScan"
+//!
+//! [[pass]]
+//! pattern = "zeros"
+//! [[pass]]
+//! pattern = "ones"
+//! [[pass]]
+//! pattern = "0xAC3E"
+//! [[pass]]
+//! pattern = "random"
+//! ```
+//!
+//! Recipes are validated when loaded. The certificate of a recipe wipe records
+//! the recipe's fingerprint, a SHA-256 of its parsed content, so comments and
+//! formatting can change without changing what the fingerprint attests.
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+use tracing::warn;
+
+use crate::core::{OverwritePattern, WipeError, WipeErrorCode, WipeResult2, WipeSpec};
+use crate::verification::VerificationPolicy;
+
+/// Recipes in this directory are registered when the engine starts
+pub const DEFAULT_RECIPE_DIR: &str = "recipes";
+
+pub const MAX_RECIPE_PASSES: usize = 64;
+/// Longest repeating pattern a pass may write
+pub const MAX_PATTERN_BYTES: usize = 512;
+
+/// The file format; unknown keys are rejected so typos do not silently change a wipe
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RecipeFile {
+    name: String,
+    #[serde(default)]
+    description: Option<String>,
+    #[serde(default = "default_verify")]
+    verify: bool,
+    #[serde(default)]
+    verification: Option<VerificationPolicy>,
+    #[serde(rename = "pass")]
+    passes: Vec<RecipePass>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RecipePass {
+    pattern: String,
+}
+
+fn default_verify() -> bool { true }
+
+/// A validated recipe
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WipeRecipe {
+    pub name: String,
+    pub description: Option<String>,
+    pub spec: WipeSpec,
+    /// Read the device back after the last pass
+    pub verify: bool,
+    /// Overrides the configured verification policy when set
+    pub verification: Option<VerificationPolicy>,
+    pub fingerprint: String,
+}
+
+impl WipeRecipe {
+    pub fn load<P: AsRef<Path>>(path: P) -> WipeResult2<Self> {
+        let path = path.as_ref();
+        let content = fs::read_to_string(path).map_err(|e| invalid(format!("Failed to read recipe {}: {}", path.display(), e)))?;
+        Self::parse(&content).map_err(|e| invalid(format!("{}: {}", path.display(), e.message)))
+    }
+
+    pub fn parse(content: &str) -> WipeResult2<Self> {
+        let file: RecipeFile = toml::from_str(content).map_err(|e| invalid(e.to_string()))?;
+
+        let name = file.name.trim().to_string();
+        if name.is_empty() {
+            return Err(invalid("Recipe name must not be empty".to_string()));
+        }
+        if file.passes.is_empty() || file.passes.len() > MAX_RECIPE_PASSES {
+            return Err(invalid(format!("A recipe needs 1 to {} passes", MAX_RECIPE_PASSES)));
+        }
+        if let Some(VerificationPolicy::RandomSample { coverage }) = file.verification
+            && (coverage.is_nan() || coverage <= 0.0 || coverage > 1.0)
+        {
+            return Err(invalid("Random sample coverage must be in (0, 1]".to_string()));
+        }
+
+        let patterns = file.passes
+            .iter()
+            .enumerate()
+            .map(|(index, pass)| {
+                parse_pattern(&pass.pattern)
+                    .ok_or_else(|| invalid(format!("Pass {}: invalid pattern '{}'", index + 1, pass.pattern)))
+            })
+            .collect::<WipeResult2<Vec<_>>>()?;
+
+        let spec = WipeSpec::overwrite(patterns);
+        let fingerprint = fingerprint(&name, &spec, file.verify, &file.verification);
+        Ok(Self {
+            name,
+            description: file.description,
+            spec,
+            verify: file.verify,
+            verification: file.verification,
+            fingerprint,
+        })
+    }
+}
+
+/// `zeros`, `ones`, `random`, or hex bytes such as `0x55` or `0xDEADBEEF`
+pub fn parse_pattern(value: &str) -> Option<OverwritePattern> {
+    match value.trim().to_ascii_lowercase().as_str() {
+        "zeros" | "zero" => Some(OverwritePattern::byte(0x00)),
+        "ones" | "one" => Some(OverwritePattern::byte(0xFF)),
+        "random" => Some(OverwritePattern::Random),
+        other => {
+            let bytes = hex::decode(other.strip_prefix("0x")?).ok()?;
+            (!bytes.is_empty() && bytes.len() <= MAX_PATTERN_BYTES).then_some(OverwritePattern::Fill(bytes))
+        }
+    }
+}
+
+fn fingerprint(name: &str, spec: &WipeSpec, verify: bool, verification: &Option<VerificationPolicy>) -> String {
+    let canonical = serde_json::json!({
+        "name": name,
+        "spec": spec,
+        "verify": verify,
+        "verification": verification,
+    });
+    let mut hasher = Sha256::new();
+    hasher.update(canonical.to_string().as_bytes());
+    hex::encode(hasher.finalize())
+}
+
+fn invalid(message: String) -> WipeError {
+    WipeError {
+        code: WipeErrorCode::InvalidPattern,
+        message,
+        sector: None,
+    }
+}
+
+/// Recipes known to the engine, by name
+#[derive(Debug, Default)]
+pub struct RecipeRegistry {
+    recipes: BTreeMap<String, WipeRecipe>,
+}
+
+impl RecipeRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register every `*.toml` in `dir`. Invalid recipes are reported and
+    /// skipped; a missing directory is not an error.
+    pub fn load_dir<P: AsRef<Path>>(&mut self, dir: P) -> usize {
+        let Ok(entries) = fs::read_dir(dir) else {
+            return 0;
+        };
+
+        let mut loaded = 0;
+        for path in entries.flatten().map(|entry| entry.path()) {
+            if path.extension().and_then(|ext| ext.to_str()) != Some("toml") {
+                continue;
+            }
+            match WipeRecipe::load(&path).and_then(|recipe| self.register(recipe)) {
+                Ok(()) => loaded += 1,
+                Err(e) => warn!(error = %e.message, "Skipping recipe"),
+            }
+        }
+        loaded
+    }
+
+    /// Add a recipe; a different recipe under the same name is refused
+    pub fn register(&mut self, recipe: WipeRecipe) -> WipeResult2<()> {
+        if let Some(existing) = self.recipes.get(&recipe.name)
+            && existing.fingerprint != recipe.fingerprint
+        {
+            return Err(invalid(format!("A different recipe named '{}' is already registered", recipe.name)));
+        }
+        self.recipes.insert(recipe.name.clone(), recipe);
+        Ok(())
+    }
+
+    pub fn get(&self, name: &str) -> Option<&WipeRecipe> {
+        self.recipes.get(name)
+    }
+
+    pub fn list(&self) -> Vec<WipeRecipe> {
+        self.recipes.values().cloned().collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RECIPE: &str = r#"
+        name = "acme"
+        verification = "FullScan"
+
+        [[pass]]
+        pattern = "zeros"
+        [[pass]]
+        pattern = "0xAC3E"
+        [[pass]]
+        pattern = "random"
+    "#;
+
+    #[test]
+    fn test_parse_recipe() {
+        let recipe = WipeRecipe::parse(RECIPE).unwrap();
+        assert_eq!(recipe.spec.passes, 3);
+        assert_eq!(recipe.spec.patterns[1], OverwritePattern::Fill(vec![0xAC, 0x3E]));
+        assert!(recipe.verify);
+        assert_eq!(recipe.verification, Some(VerificationPolicy::FullScan));
+
+        // Formatting does not change the fingerprint, content does
+        let reformatted = RECIPE.replace("        ", "").replace("\n\n", "\n# comment\n");
+        assert_eq!(WipeRecipe::parse(&reformatted).unwrap().fingerprint, recipe.fingerprint);
+        let changed = RECIPE.replace("0xAC3E", "0xAC3F");
+        assert_ne!(WipeRecipe::parse(&changed).unwrap().fingerprint, recipe.fingerprint);
+    }
+
+    #[test]
+    fn test_invalid_recipes_are_rejected() {
+        assert!(WipeRecipe::parse(&RECIPE.replace("0xAC3E", "0xZZ")).is_err());
+        assert!(WipeRecipe::parse(&RECIPE.replace("pattern = \"zeros\"", "patern = \"zeros\"")).is_err());
+        assert!(WipeRecipe::parse("name = \"empty\"\npass = []").is_err());
+    }
+}
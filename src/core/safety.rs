@@ -0,0 +1,205 @@
+//! Safeguards in front of destructive operations
+//!
+//! The target policy decides which devices may be wiped at all; the safety
+//! policy decides what has to happen before an allowed wipe writes its first
+//! block:
+//!
+//! - **Confirmation**: the request must carry a phrase typed by the operator.
+//!   The phrase may name the device (`{device}`) and a token derived from the
+//!   operation id (`{token}`), so a confirmation cannot be replayed for
+//!   another drive or another run.
+//! - **Two-person authorization**: a second operator, other than the one who
+//!   requested the wipe, approves the operation id with
+//!   `WipeEngine::approve_operation`. Approvals may be given before the wipe
+//!   is started or while it waits.
+//! - **Abort window**: once confirmed and approved, the wipe waits a few
+//!   seconds before writing, during which `cancel_wipe` stops it.
+
+use std::collections::HashMap;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::core::{OperatorIdentity, WipeError, WipeErrorCode, WipeRequest, WipeResult2};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SafetyPolicy {
+    /// Phrase the request must carry, e.g. "WIPE {device} {token}"; no
+    /// confirmation is needed when unset
+    #[serde(default)]
+    pub confirmation_phrase: Option<String>,
+    /// Require a second operator to approve each wipe
+    #[serde(default)]
+    pub require_second_approver: bool,
+    /// How long a wipe waits for its approval before failing
+    #[serde(default = "default_approval_timeout")]
+    pub approval_timeout_seconds: u64,
+    /// Delay between the last check and the first write, during which the
+    /// wipe can still be cancelled
+    #[serde(default)]
+    pub abort_window_seconds: u64,
+}
+
+fn default_approval_timeout() -> u64 { 600 }
+
+impl Default for SafetyPolicy {
+    fn default() -> Self {
+        Self {
+            confirmation_phrase: None,
+            require_second_approver: false,
+            approval_timeout_seconds: default_approval_timeout(),
+            abort_window_seconds: 0,
+        }
+    }
+}
+
+impl SafetyPolicy {
+    /// Short code identifying one operation, shown to the operator to type back
+    pub fn confirmation_token(operation_id: Uuid) -> String {
+        operation_id.simple().to_string()[..8].to_ascii_uppercase()
+    }
+
+    /// The phrase `request` must be confirmed with, if any
+    pub fn expected_confirmation(&self, request: &WipeRequest) -> Option<String> {
+        self.confirmation_phrase.as_ref().map(|phrase| {
+            phrase
+                .replace("{device}", &request.target_path)
+                .replace("{token}", &Self::confirmation_token(request.id))
+        })
+    }
+
+    /// Surrounding whitespace is ignored; everything else must match exactly
+    pub fn check_confirmation(&self, request: &WipeRequest) -> WipeResult2<()> {
+        let Some(expected) = self.expected_confirmation(request) else {
+            return Ok(());
+        };
+        match request.confirmation.as_deref().map(str::trim) {
+            Some(given) if given == expected => Ok(()),
+            Some(_) => Err(denied(format!("Confirmation does not match; type \"{}\"", expected))),
+            None => Err(denied(format!("This wipe must be confirmed by typing \"{}\"", expected))),
+        }
+    }
+}
+
+/// An approval given by a second operator
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Approval {
+    pub operation_id: Uuid,
+    pub approver: String,
+    pub approved_at: DateTime<Utc>,
+}
+
+/// A wipe waiting for its second approval
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PendingApproval {
+    pub operation_id: Uuid,
+    pub target_path: String,
+    pub requested_by: String,
+    pub requested_at: DateTime<Utc>,
+}
+
+/// Approvals given and wipes waiting for one
+#[derive(Debug, Default)]
+pub struct ApprovalBook {
+    pending: HashMap<Uuid, (PendingApproval, String)>,
+    approvals: HashMap<Uuid, (Approval, String)>,
+}
+
+impl ApprovalBook {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub(crate) fn await_approval(&mut self, request: &WipeRequest, requester: &OperatorIdentity) {
+        let pending = PendingApproval {
+            operation_id: request.id,
+            target_path: request.target_path.clone(),
+            requested_by: requester.display_name(),
+            requested_at: Utc::now(),
+        };
+        self.pending.insert(request.id, (pending, requester.username.clone()));
+    }
+
+    /// Record `approver`'s approval. Nobody approves their own wipe.
+    pub fn approve(&mut self, operation_id: Uuid, approver: &OperatorIdentity) -> WipeResult2<Approval> {
+        approver.require_sanitize()?;
+        if let Some((_, requester)) = self.pending.get(&operation_id)
+            && *requester == approver.username
+        {
+            return Err(denied("A wipe must be approved by a second operator".to_string()));
+        }
+
+        let approval = Approval {
+            operation_id,
+            approver: approver.display_name(),
+            approved_at: Utc::now(),
+        };
+        self.approvals.insert(operation_id, (approval.clone(), approver.username.clone()));
+        Ok(approval)
+    }
+
+    /// The approval of `operation_id` by someone other than `requester`, once given
+    pub(crate) fn take_approval(&mut self, operation_id: Uuid, requester: &OperatorIdentity) -> WipeResult2<Option<Approval>> {
+        match self.approvals.remove(&operation_id) {
+            Some((_, approver)) if approver == requester.username => {
+                Err(denied("A wipe must be approved by a second operator".to_string()))
+            }
+            Some((approval, _)) => {
+                self.pending.remove(&operation_id);
+                Ok(Some(approval))
+            }
+            None => Ok(None),
+        }
+    }
+
+    pub(crate) fn withdraw(&mut self, operation_id: Uuid) {
+        self.pending.remove(&operation_id);
+        self.approvals.remove(&operation_id);
+    }
+
+    /// Wipes waiting for approval, oldest first
+    pub fn pending(&self) -> Vec<PendingApproval> {
+        let mut pending: Vec<PendingApproval> = self.pending.values().map(|(p, _)| p.clone()).collect();
+        pending.sort_by_key(|p| p.requested_at);
+        pending
+    }
+}
+
+fn denied(message: String) -> WipeError {
+    WipeError {
+        code: WipeErrorCode::PolicyDenied,
+        message,
+        sector: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{SanitizationStandard, TargetType, WipeEngine, WipeScope};
+
+    #[test]
+    fn test_confirmation_names_device_and_operation() {
+        let policy = SafetyPolicy {
+            confirmation_phrase: Some("WIPE {device} {token}".to_string()),
+            ..SafetyPolicy::default()
+        };
+        let mut request = WipeEngine::build_request(
+            "/dev/sdz".to_string(),
+            TargetType::HDD,
+            WipeScope::WholeDevice,
+            SanitizationStandard::NIST_SP_800_88_R1,
+            true,
+            false,
+        );
+        assert!(policy.check_confirmation(&request).is_err());
+
+        let token = SafetyPolicy::confirmation_token(request.id);
+        request.confirmation = Some(format!("WIPE /dev/sdz {} ", token));
+        assert!(policy.check_confirmation(&request).is_ok());
+
+        // A confirmation is only good for the operation it was typed for
+        request.id = Uuid::new_v4();
+        assert!(policy.check_confirmation(&request).is_err());
+    }
+}
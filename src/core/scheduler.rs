@@ -0,0 +1,307 @@
+//! Wipes scheduled for later, inside maintenance windows
+//!
+//! A scheduled wipe is an ordinary `WipeRequest` with a start time. Jobs are
+//! kept in a JSON file next to the operation history so they survive a
+//! restart, and `WipeEngine::run_schedule_as` starts each one once its time
+//! has come and a maintenance window is open. With
+//! `pause_outside_windows`, a wipe still running when its window closes is
+//! paused after the block being written and resumed when the next window
+//! opens.
+//!
+//! Due jobs start highest `WipePriority` first, then in order of their start
+//! time; with `max_running` set, the rest wait for a running wipe to end.
+//!
+//! Windows are in local time and may span midnight ("22:00" to "06:00");
+//! the days listed are the days a window opens on.
+
+use chrono::{DateTime, Datelike, Duration, Local, NaiveTime, Utc, Weekday};
+use serde::{Deserialize, Serialize};
+use std::cmp::Reverse;
+use std::fs;
+use std::path::{Path, PathBuf};
+use uuid::Uuid;
+
+use crate::core::{WipeError, WipeErrorCode, WipePriority, WipeRequest, WipeResult, WipeResult2};
+
+/// Default location of the persistent job schedule
+pub const DEFAULT_SCHEDULE_PATH: &str = "history/schedule.json";
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MaintenanceWindow {
+    /// Days the window opens on; every day when empty
+    #[serde(default)]
+    pub days: Vec<Weekday>,
+    /// Local time the window opens, e.g. "22:00"
+    pub start: NaiveTime,
+    /// Local time the window closes; before `start` when it spans midnight
+    pub end: NaiveTime,
+}
+
+impl MaintenanceWindow {
+    fn opens_on(&self, day: Weekday) -> bool {
+        self.days.is_empty() || self.days.contains(&day)
+    }
+
+    pub fn contains(&self, at: DateTime<Local>) -> bool {
+        let time = at.time();
+        let today = at.weekday();
+        if self.start < self.end {
+            self.opens_on(today) && time >= self.start && time < self.end
+        } else {
+            // Equal times make a window of a whole day
+            (self.opens_on(today) && time >= self.start) || (self.opens_on(today.pred()) && time < self.end)
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ScheduleConfig {
+    /// When scheduled wipes may write; at any time when empty
+    #[serde(default)]
+    pub maintenance_windows: Vec<MaintenanceWindow>,
+    /// Pause scheduled wipes when their window closes and resume them when
+    /// the next one opens; otherwise a started wipe runs to the end
+    #[serde(default = "default_true")]
+    pub pause_outside_windows: bool,
+    /// Scheduled wipes running at once, paused ones included; unlimited when unset
+    #[serde(default)]
+    pub max_running: Option<usize>,
+    /// Let a high-priority firmware erase hold lower-priority overwrites
+    /// until it finishes, and start past `max_running`. Applies to every
+    /// wipe of the engine, scheduled or not.
+    #[serde(default)]
+    pub preempt_for_high_priority: bool,
+}
+
+fn default_true() -> bool { true }
+
+impl Default for ScheduleConfig {
+    fn default() -> Self {
+        Self {
+            maintenance_windows: Vec::new(),
+            pause_outside_windows: true,
+            max_running: None,
+            preempt_for_high_priority: false,
+        }
+    }
+}
+
+impl ScheduleConfig {
+    /// Whether `request` holds lower-priority overwrites while it runs
+    pub fn preempts(&self, request: &WipeRequest) -> bool {
+        self.preempt_for_high_priority && request.priority == WipePriority::High && request.uses_firmware_erase()
+    }
+
+    pub fn is_open(&self, at: DateTime<Local>) -> bool {
+        self.maintenance_windows.is_empty() || self.maintenance_windows.iter().any(|window| window.contains(at))
+    }
+
+    /// The first moment at or after `at` when a window is open, looking a week ahead
+    pub fn next_opening(&self, at: DateTime<Local>) -> Option<DateTime<Local>> {
+        if self.is_open(at) {
+            return Some(at);
+        }
+        (0..=7)
+            .flat_map(|days| self.maintenance_windows.iter().map(move |window| (days, window)))
+            .filter_map(|(days, window)| {
+                let date = (at + Duration::days(days)).date_naive();
+                let opens = date.and_time(window.start).and_local_timezone(Local).earliest()?;
+                (opens > at && window.opens_on(date.weekday())).then_some(opens)
+            })
+            .min()
+    }
+}
+
+/// What a scheduled wipe came to: the result and certificate path, as from `execute_request_as`
+pub type ScheduledOutcome = WipeResult2<(WipeResult, Option<String>)>;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum JobStatus {
+    /// Waiting for its start time or a maintenance window
+    Pending,
+    Running,
+    /// Started, and paused because its window closed
+    Paused,
+    Completed,
+    Failed,
+    Cancelled,
+}
+
+impl JobStatus {
+    pub fn is_finished(self) -> bool {
+        matches!(self, JobStatus::Completed | JobStatus::Failed | JobStatus::Cancelled)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScheduledJob {
+    /// The wipe to run; its id becomes the operation id
+    pub request: WipeRequest,
+    /// Earliest time the wipe may start
+    pub not_before: DateTime<Utc>,
+    pub scheduled_at: DateTime<Utc>,
+    /// Operator who scheduled the wipe
+    pub scheduled_by: String,
+    pub status: JobStatus,
+    #[serde(default)]
+    pub error_message: Option<String>,
+}
+
+impl ScheduledJob {
+    pub fn id(&self) -> Uuid {
+        self.request.id
+    }
+}
+
+/// Persistent list of scheduled wipes
+pub struct JobSchedule {
+    path: PathBuf,
+    jobs: Vec<ScheduledJob>,
+}
+
+impl JobSchedule {
+    pub fn open<P: AsRef<Path>>(path: P) -> WipeResult2<Self> {
+        let path = path.as_ref().to_path_buf();
+        let mut jobs: Vec<ScheduledJob> = if path.exists() {
+            let content = fs::read_to_string(&path).map_err(|e| schedule_error(format!("Failed to read schedule: {}", e)))?;
+            serde_json::from_str(&content).map_err(|e| schedule_error(format!("Failed to parse schedule: {}", e)))?
+        } else {
+            Vec::new()
+        };
+
+        // A wipe cut off by the last shutdown is not restarted behind the operator's back
+        for job in jobs.iter_mut() {
+            if matches!(job.status, JobStatus::Running | JobStatus::Paused) {
+                job.status = JobStatus::Failed;
+                job.error_message = Some("Interrupted by shutdown".to_string());
+            }
+        }
+
+        Ok(Self { path, jobs })
+    }
+
+    pub fn add(&mut self, job: ScheduledJob) -> WipeResult2<()> {
+        self.jobs.push(job);
+        self.save()
+    }
+
+    pub fn get(&self, job_id: Uuid) -> Option<&ScheduledJob> {
+        self.jobs.iter().find(|job| job.id() == job_id)
+    }
+
+    /// All jobs, soonest first
+    pub fn list(&self) -> Vec<ScheduledJob> {
+        let mut jobs = self.jobs.clone();
+        jobs.sort_by_key(|job| job.not_before);
+        jobs
+    }
+
+    /// Pending jobs whose start time has come, highest priority first
+    pub fn due(&self, now: DateTime<Utc>) -> Vec<ScheduledJob> {
+        let mut due: Vec<ScheduledJob> = self.jobs
+            .iter()
+            .filter(|job| job.status == JobStatus::Pending && job.not_before <= now)
+            .cloned()
+            .collect();
+        due.sort_by_key(|job| (Reverse(job.request.priority), job.not_before));
+        due
+    }
+
+    pub fn has_unfinished(&self) -> bool {
+        self.jobs.iter().any(|job| !job.status.is_finished())
+    }
+
+    pub fn set_status(&mut self, job_id: Uuid, status: JobStatus, error_message: Option<String>) -> WipeResult2<()> {
+        if let Some(job) = self.jobs.iter_mut().find(|job| job.id() == job_id) {
+            job.status = status;
+            job.error_message = error_message;
+        }
+        self.save()
+    }
+
+    fn save(&self) -> WipeResult2<()> {
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent).map_err(|e| schedule_error(format!("Failed to create schedule directory: {}", e)))?;
+        }
+        let content = serde_json::to_string_pretty(&self.jobs)
+            .map_err(|e| schedule_error(format!("Failed to serialize schedule: {}", e)))?;
+
+        let tmp_path = self.path.with_extension("json.tmp");
+        fs::write(&tmp_path, content)
+            .and_then(|_| fs::rename(&tmp_path, &self.path))
+            .map_err(|e| schedule_error(format!("Failed to write schedule: {}", e)))
+    }
+}
+
+fn schedule_error(message: String) -> WipeError {
+    WipeError { code: WipeErrorCode::UnknownError, message, sector: None }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn at(day: u32, hour: u32, minute: u32) -> DateTime<Local> {
+        // 2024-07-01 was a Monday
+        Local.with_ymd_and_hms(2024, 7, day, hour, minute, 0).unwrap()
+    }
+
+    #[test]
+    fn test_window_spanning_midnight() {
+        let config = ScheduleConfig {
+            maintenance_windows: vec![MaintenanceWindow {
+                days: vec![Weekday::Fri, Weekday::Sat],
+                start: NaiveTime::from_hms_opt(22, 0, 0).unwrap(),
+                end: NaiveTime::from_hms_opt(6, 0, 0).unwrap(),
+            }],
+            ..ScheduleConfig::default()
+        };
+
+        assert!(config.is_open(at(5, 23, 0)));
+        // Saturday morning still belongs to Friday night's window
+        assert!(config.is_open(at(6, 5, 59)));
+        assert!(!config.is_open(at(6, 6, 0)));
+        // Friday before 06:00 follows Thursday, which has no window
+        assert!(!config.is_open(at(5, 3, 0)));
+        assert_eq!(config.next_opening(at(1, 12, 0)), Some(at(5, 22, 0)));
+        assert!(ScheduleConfig::default().is_open(at(1, 12, 0)));
+    }
+
+    #[test]
+    fn test_due_jobs_start_highest_priority_first() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("schedule.json");
+        let mut schedule = JobSchedule::open(&path).unwrap();
+        let now = Utc::now();
+        let job = |priority, minutes_ago| {
+            let mut request = crate::core::WipeEngine::build_request(
+                "/dev/null".to_string(),
+                crate::core::TargetType::HDD,
+                crate::core::WipeScope::WholeDevice,
+                crate::core::SanitizationStandard::NIST_SP_800_88_R1,
+                true,
+                false,
+            );
+            request.priority = priority;
+            ScheduledJob {
+                request,
+                not_before: now - Duration::minutes(minutes_ago),
+                scheduled_at: now,
+                scheduled_by: "test".to_string(),
+                status: JobStatus::Pending,
+                error_message: None,
+            }
+        };
+        let low = job(WipePriority::Low, 30);
+        let old = job(WipePriority::Normal, 20);
+        let new = job(WipePriority::Normal, 10);
+        let rush = job(WipePriority::High, 5);
+        for job in [&low, &new, &rush, &old] {
+            schedule.add(job.clone()).unwrap();
+        }
+
+        let order: Vec<Uuid> = schedule.due(now).iter().map(ScheduledJob::id).collect();
+        assert_eq!(order, vec![rush.id(), old.id(), new.id(), low.id()]);
+    }
+}
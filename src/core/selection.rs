@@ -0,0 +1,165 @@
+//! Choosing wipe targets by what they are rather than where they are
+//!
+//! Kernel names such as `sdb` can change between boots. A `DeviceSelector`
+//! names drives by serial number, WWN, model pattern, size range and bus,
+//! and is resolved against the detected disks just before a wipe. All given
+//! criteria must hold; within a list (several serials, say) any entry may
+//! match.
+
+use serde::{Deserialize, Serialize};
+
+use crate::core::{wildcard_match, TargetType};
+use crate::platform::PhysicalDisk;
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct DeviceSelector {
+    /// Device paths or kernel names: "/dev/sdb", "sdb", "PhysicalDrive1"
+    #[serde(default)]
+    pub paths: Vec<String>,
+    /// Serial numbers; `*` and `?` wildcards are accepted
+    #[serde(default)]
+    pub serials: Vec<String>,
+    #[serde(default)]
+    pub wwns: Vec<String>,
+    /// Model pattern such as "ST4000*"
+    #[serde(default)]
+    pub model: Option<String>,
+    #[serde(default)]
+    pub min_size_bytes: Option<u64>,
+    #[serde(default)]
+    pub max_size_bytes: Option<u64>,
+    /// Bus type as reported by device detection, e.g. "USB", "NVMe", "SATA"
+    #[serde(default)]
+    pub bus: Option<String>,
+}
+
+impl DeviceSelector {
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Only device paths were given
+    pub fn is_paths_only(&self) -> bool {
+        !self.paths.is_empty() && Self { paths: Vec::new(), ..self.clone() }.is_empty()
+    }
+
+    pub fn matches(&self, disk: &PhysicalDisk) -> bool {
+        (self.paths.is_empty() || self.paths.iter().any(|path| path_matches(path, &disk.path)))
+            && (self.serials.is_empty()
+                || (!disk.serial.trim().is_empty()
+                    && self.serials.iter().any(|serial| wildcard_match(serial.trim(), disk.serial.trim()))))
+            && (self.wwns.is_empty()
+                || disk.wwn.as_deref().is_some_and(|wwn| self.wwns.iter().any(|wanted| normalize_wwn(wanted) == normalize_wwn(wwn))))
+            && self.model.as_ref().is_none_or(|pattern| wildcard_match(pattern, disk.model.trim()))
+            && self.min_size_bytes.is_none_or(|min| disk.size_bytes >= min)
+            && self.max_size_bytes.is_none_or(|max| disk.size_bytes <= max)
+            && self.bus.as_ref().is_none_or(|bus| bus.eq_ignore_ascii_case(&disk.bus_type))
+    }
+
+    /// The detected disks this selector picks, in device path order
+    pub fn resolve(&self, disks: &[PhysicalDisk]) -> Vec<PhysicalDisk> {
+        let mut selected: Vec<PhysicalDisk> = disks.iter().filter(|disk| self.matches(disk)).cloned().collect();
+        selected.sort_by(|a, b| a.path.cmp(&b.path));
+        selected
+    }
+
+    /// Listed paths that name none of `disks`
+    pub fn unmatched_paths(&self, disks: &[PhysicalDisk]) -> Vec<String> {
+        self.paths
+            .iter()
+            .filter(|path| !disks.iter().any(|disk| path_matches(path, &disk.path)))
+            .cloned()
+            .collect()
+    }
+}
+
+/// "sdb" names "/dev/sdb"; "PhysicalDrive1" names "\\.\PhysicalDrive1"
+fn path_matches(wanted: &str, path: &str) -> bool {
+    let name = path.rsplit(['/', '\\']).next().unwrap_or(path);
+    wanted == path || wanted.eq_ignore_ascii_case(name)
+}
+
+fn normalize_wwn(wwn: &str) -> String {
+    let wwn = wwn.trim().to_ascii_lowercase();
+    let wwn = wwn.strip_prefix("naa.").or_else(|| wwn.strip_prefix("0x")).unwrap_or(&wwn);
+    wwn.to_string()
+}
+
+/// Parse a size such as "500G", "4TB", "1.5T" or "931GiB". Decimal units
+/// follow drive labels (4T is 4,000,000,000,000 bytes); `KiB`..`TiB` are binary.
+pub fn parse_size(text: &str) -> Option<u64> {
+    let text = text.trim();
+    let split = text.find(|c: char| !(c.is_ascii_digit() || c == '.')).unwrap_or(text.len());
+    let (number, unit) = text.split_at(split);
+    let number: f64 = number.parse().ok()?;
+    let multiplier: f64 = match unit.trim().to_ascii_uppercase().as_str() {
+        "" | "B" => 1.0,
+        "K" | "KB" => 1e3,
+        "M" | "MB" => 1e6,
+        "G" | "GB" => 1e9,
+        "T" | "TB" => 1e12,
+        "KIB" => 1024.0,
+        "MIB" => 1024.0 * 1024.0,
+        "GIB" => 1024.0 * 1024.0 * 1024.0,
+        "TIB" => 1024.0 * 1024.0 * 1024.0 * 1024.0,
+        _ => return None,
+    };
+    Some((number * multiplier) as u64)
+}
+
+/// The wipe target type that suits a detected disk
+pub fn default_target_type(disk: &PhysicalDisk) -> TargetType {
+    match disk.rotational {
+        _ if disk.removable => TargetType::Flash,
+        Some(false) => TargetType::SSD,
+        _ => TargetType::HDD,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn disk(path: &str, serial: &str, model: &str, size_bytes: u64, bus: &str) -> PhysicalDisk {
+        PhysicalDisk {
+            path: path.to_string(),
+            serial: serial.to_string(),
+            model: model.to_string(),
+            size_bytes,
+            bus_type: bus.to_string(),
+            wwn: Some("0x5000C500A1B2C3D4".to_string()),
+            ..PhysicalDisk::default()
+        }
+    }
+
+    #[test]
+    fn test_selector_criteria_combine() {
+        let disks = vec![
+            disk("/dev/sdb", "ZFN0ABCD", "ST4000DM004-2CV104", 4_000_787_030_016, "SATA"),
+            disk("/dev/sdc", "WD-WX11", "WDC WD10EZEX", 1_000_204_886_016, "USB"),
+        ];
+
+        let by_model = DeviceSelector { model: Some("ST4000*".to_string()), ..Default::default() };
+        assert_eq!(by_model.resolve(&disks).len(), 1);
+
+        let by_size = DeviceSelector { min_size_bytes: parse_size("2T"), bus: Some("usb".to_string()), ..Default::default() };
+        assert!(by_size.resolve(&disks).is_empty());
+
+        let by_name = DeviceSelector { paths: vec!["sdc".to_string(), "sdz".to_string()], ..Default::default() };
+        assert_eq!(by_name.resolve(&disks)[0].path, "/dev/sdc");
+        assert_eq!(by_name.unmatched_paths(&disks), vec!["sdz".to_string()]);
+        assert!(by_name.is_paths_only());
+
+        let by_wwn = DeviceSelector { wwns: vec!["naa.5000c500a1b2c3d4".to_string()], ..Default::default() };
+        assert_eq!(by_wwn.resolve(&disks).len(), 2);
+    }
+
+    #[test]
+    fn test_parse_size_units() {
+        assert_eq!(parse_size("4T"), Some(4_000_000_000_000));
+        assert_eq!(parse_size("1.5GB"), Some(1_500_000_000));
+        assert_eq!(parse_size("1GiB"), Some(1 << 30));
+        assert_eq!(parse_size("512"), Some(512));
+        assert_eq!(parse_size("4 parsecs"), None);
+    }
+}
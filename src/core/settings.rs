@@ -0,0 +1,147 @@
+//! Engine configuration kept between runs
+//!
+//! Front ends that let the operator change settings keep the
+//! `WipeConfiguration` in a settings file in the platform's configuration
+//! directory, so changes made through `WipeEngine::update_configuration`
+//! survive a restart. The file records its schema version; configuration
+//! files written before settings were versioned are a bare
+//! `WipeConfiguration` and are migrated on load, with fields they predate
+//! taking their defaults.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::core::{WipeConfiguration, WipeError, WipeErrorCode, WipeResult2};
+
+/// Schema version written into settings files. Version 1 is a bare
+/// `WipeConfiguration` object.
+pub const SETTINGS_SCHEMA_VERSION: u32 = 2;
+
+const SETTINGS_FILE: &str = "settings.json";
+
+#[derive(Debug, Serialize, Deserialize)]
+struct SettingsFile {
+    schema_version: u32,
+    configuration: Value,
+}
+
+/// `settings.json` in the platform's per-user configuration directory:
+/// `%APPDATA%\hdd-tool` on Windows, `~/Library/Application Support/hdd-tool`
+/// on macOS and `$XDG_CONFIG_HOME/hdd-tool` (`~/.config/hdd-tool`) elsewhere
+pub fn default_settings_path() -> PathBuf {
+    let env_dir = |name: &str| std::env::var_os(name).filter(|value| !value.is_empty()).map(PathBuf::from);
+    let base = if cfg!(windows) {
+        env_dir("APPDATA")
+    } else if cfg!(target_os = "macos") {
+        env_dir("HOME").map(|home| home.join("Library").join("Application Support"))
+    } else {
+        env_dir("XDG_CONFIG_HOME").or_else(|| env_dir("HOME").map(|home| home.join(".config")))
+    };
+    base.unwrap_or_else(|| PathBuf::from(".")).join("hdd-tool").join(SETTINGS_FILE)
+}
+
+/// Settings file a `WipeConfiguration` is loaded from and saved to
+#[derive(Debug, Clone)]
+pub struct SettingsStore {
+    path: PathBuf,
+}
+
+impl SettingsStore {
+    pub fn new<P: AsRef<Path>>(path: P) -> Self {
+        Self { path: path.as_ref().to_path_buf() }
+    }
+
+    pub fn at_default_location() -> Self {
+        Self::new(default_settings_path())
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// The saved configuration, or the defaults when nothing was saved yet
+    pub fn load(&self) -> WipeResult2<WipeConfiguration> {
+        if !self.path.exists() {
+            return Ok(WipeConfiguration::default());
+        }
+        let content = fs::read_to_string(&self.path)
+            .map_err(|e| settings_error(format!("Failed to read {}: {}", self.path.display(), e)))?;
+        let value: Value = serde_json::from_str(&content)
+            .map_err(|e| settings_error(format!("Failed to parse {}: {}", self.path.display(), e)))?;
+        migrate_settings(value)
+    }
+
+    pub fn save(&self, config: &WipeConfiguration) -> WipeResult2<()> {
+        if let Some(parent) = self.path.parent().filter(|parent| !parent.as_os_str().is_empty()) {
+            fs::create_dir_all(parent)
+                .map_err(|e| settings_error(format!("Failed to create {}: {}", parent.display(), e)))?;
+        }
+        let file = SettingsFile {
+            schema_version: SETTINGS_SCHEMA_VERSION,
+            configuration: serde_json::to_value(config)
+                .map_err(|e| settings_error(format!("Failed to serialize settings: {}", e)))?,
+        };
+        let content = serde_json::to_string_pretty(&file)
+            .map_err(|e| settings_error(format!("Failed to serialize settings: {}", e)))?;
+
+        let tmp_path = self.path.with_extension("json.tmp");
+        fs::write(&tmp_path, content)
+            .and_then(|_| fs::rename(&tmp_path, &self.path))
+            .map_err(|e| settings_error(format!("Failed to write {}: {}", self.path.display(), e)))
+    }
+}
+
+/// Bring a settings file of any known schema version up to the current one
+pub fn migrate_settings(value: Value) -> WipeResult2<WipeConfiguration> {
+    let (version, configuration) = match value.get("schema_version").and_then(Value::as_u64) {
+        Some(version) => (version, value.get("configuration").cloned().unwrap_or(Value::Null)),
+        None => (1, value),
+    };
+    if version > SETTINGS_SCHEMA_VERSION as u64 {
+        return Err(settings_error(format!(
+            "Settings were saved by a newer version (schema {}, this build reads up to {})",
+            version, SETTINGS_SCHEMA_VERSION
+        )));
+    }
+    let Value::Object(saved) = configuration else {
+        return Err(settings_error("Settings file holds no configuration".to_string()));
+    };
+
+    // Version 1 files may predate fields added since; they take their defaults
+    let mut merged = serde_json::to_value(WipeConfiguration::default())
+        .map_err(|e| settings_error(format!("Failed to serialize settings: {}", e)))?;
+    if let Value::Object(fields) = &mut merged {
+        fields.extend(saved);
+    }
+    serde_json::from_value(merged).map_err(|e| settings_error(format!("Invalid settings: {}", e)))
+}
+
+fn settings_error(message: String) -> WipeError {
+    WipeError { code: WipeErrorCode::UnknownError, message, sector: None }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_migrate_unversioned_configuration() {
+        // An old bare configuration without the fields added later
+        let legacy = serde_json::json!({ "verify_writes": false, "buffer_size": 1048576 });
+        let config = migrate_settings(legacy).unwrap();
+        assert!(!config.verify_writes);
+        assert_eq!(config.buffer_size, 1048576);
+        assert!(config.adaptive_buffer);
+
+        let dir = tempfile::tempdir().unwrap();
+        let store = SettingsStore::new(dir.path().join(SETTINGS_FILE));
+        assert!(store.load().unwrap().verify_writes);
+        store.save(&config).unwrap();
+        assert_eq!(store.load().unwrap().buffer_size, 1048576);
+
+        let future = serde_json::json!({ "schema_version": SETTINGS_SCHEMA_VERSION + 1, "configuration": {} });
+        assert!(migrate_settings(future).is_err());
+    }
+}
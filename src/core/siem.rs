@@ -0,0 +1,358 @@
+//! Wipe lifecycle events for SIEM collection
+//!
+//! Sends start, verification, completion, failure and cancellation events to
+//! a syslog collector as RFC 5424 messages and, on Windows, to the
+//! Application event log. Both are `WipeEventSink`s, so an unreachable
+//! collector is retried by the engine's dispatcher without holding up a wipe.
+//!
+//! Syslog over UDP sends one message per datagram. TCP and TLS (RFC 5425)
+//! use octet-counting framing; TLS needs the `syslog-tls` feature.
+
+use serde::{Deserialize, Serialize};
+use std::io::Write;
+use std::net::{TcpStream, ToSocketAddrs, UdpSocket};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tracing::warn;
+
+use crate::core::{LifecycleEvent, LifecycleEventKind, WipeEventSink};
+
+/// Structured data id; 32473 is the private enterprise number reserved for examples
+const SD_ID: &str = "wipe@32473";
+
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct EventLoggingConfig {
+    #[serde(default)]
+    pub syslog: Option<SyslogConfig>,
+    /// Event source name to report under in the Windows Application log
+    #[serde(default)]
+    pub windows_event_log: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum SyslogTransport {
+    Udp,
+    Tcp,
+    Tls,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SyslogConfig {
+    /// Collector as `host:port`
+    pub address: String,
+    pub transport: SyslogTransport,
+    /// Syslog facility number; 4 (security/authorization) by default
+    #[serde(default = "default_facility")]
+    pub facility: u8,
+    #[serde(default = "default_app_name")]
+    pub app_name: String,
+    /// PEM bundle of CAs trusted for TLS in addition to the built-in roots
+    #[serde(default)]
+    pub ca_file: Option<String>,
+}
+
+fn default_facility() -> u8 { 4 }
+
+fn default_app_name() -> String { "hdd-tool".to_string() }
+
+/// Syslog severity of an event
+fn severity(event: &LifecycleEvent) -> u8 {
+    match event.kind {
+        LifecycleEventKind::WipeStarted => 6,
+        LifecycleEventKind::WipeCompleted => 5,
+        LifecycleEventKind::WipeCancelled => 4,
+        LifecycleEventKind::WipeFailed => 3,
+        LifecycleEventKind::VerificationCompleted if event.verification_passed == Some(false) => 3,
+        LifecycleEventKind::VerificationCompleted => 5,
+    }
+}
+
+/// One-line human readable summary of an event
+pub fn event_summary(event: &LifecycleEvent) -> String {
+    let operator = event.operator.as_deref().unwrap_or("unknown operator");
+    match event.kind {
+        LifecycleEventKind::WipeStarted => format!(
+            "Wipe of {} ({}, {}) started by {}", event.target_path, event.scope, event.standard, operator
+        ),
+        LifecycleEventKind::WipeCompleted => format!(
+            "Wipe of {} completed by {}", event.target_path, operator
+        ),
+        LifecycleEventKind::WipeFailed => format!(
+            "Wipe of {} failed: {}", event.target_path, event.error.as_deref().unwrap_or("unknown error")
+        ),
+        LifecycleEventKind::WipeCancelled => format!(
+            "Wipe of {} cancelled", event.target_path
+        ),
+        LifecycleEventKind::VerificationCompleted => format!(
+            "Verification of {} {}",
+            event.target_path,
+            if event.verification_passed == Some(true) { "passed" } else { "failed" }
+        ),
+    }
+}
+
+/// Format an event as an RFC 5424 message
+pub fn format_rfc5424(event: &LifecycleEvent, facility: u8, hostname: &str, app_name: &str) -> String {
+    let mut params = vec![
+        ("operation_id", event.operation_id.to_string()),
+        ("event_id", event.event_id.to_string()),
+        ("target", event.target_path.clone()),
+        ("scope", event.scope.clone()),
+        ("standard", event.standard.clone()),
+    ];
+    let optional = [
+        ("operator", event.operator.clone()),
+        ("serial", event.serial_number.clone()),
+        ("certificate_id", event.certificate_id.clone()),
+        ("verification_passed", event.verification_passed.map(|v| v.to_string())),
+        ("bytes_verified", event.bytes_verified.map(|v| v.to_string())),
+        ("error", event.error.clone()),
+    ];
+    params.extend(optional.into_iter().filter_map(|(name, value)| value.map(|v| (name, v))));
+
+    let structured: String = params
+        .iter()
+        .map(|(name, value)| format!(" {}=\"{}\"", name, escape_param(value)))
+        .collect();
+
+    format!(
+        "<{}>1 {} {} {} {} {:?} [{}{}] {}",
+        facility as u16 * 8 + severity(event) as u16,
+        event.occurred_at.to_rfc3339_opts(chrono::SecondsFormat::Micros, true),
+        header_field(hostname),
+        header_field(app_name),
+        std::process::id(),
+        event.kind,
+        SD_ID,
+        structured,
+        event_summary(event),
+    )
+}
+
+/// PARAM-VALUE escaping from RFC 5424 section 6.3.3
+fn escape_param(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"").replace(']', "\\]")
+}
+
+/// Header fields are printable ASCII without spaces; "-" when empty
+fn header_field(value: &str) -> String {
+    let cleaned: String = value.chars().filter(|c| c.is_ascii_graphic()).take(48).collect();
+    if cleaned.is_empty() { "-".to_string() } else { cleaned }
+}
+
+pub(crate) fn local_hostname() -> String {
+    #[cfg(target_os = "linux")]
+    if let Ok(name) = std::fs::read_to_string("/proc/sys/kernel/hostname") {
+        return name.trim().to_string();
+    }
+    std::env::var("COMPUTERNAME")
+        .or_else(|_| std::env::var("HOSTNAME"))
+        .unwrap_or_default()
+}
+
+enum Connection {
+    Udp(UdpSocket),
+    Tcp(TcpStream),
+    #[cfg(feature = "syslog-tls")]
+    Tls(Box<rustls::StreamOwned<rustls::ClientConnection, TcpStream>>),
+}
+
+pub struct SyslogSink {
+    config: SyslogConfig,
+    hostname: String,
+    /// Kept open between events; dropped after an error so the next attempt reconnects
+    connection: Mutex<Option<Connection>>,
+}
+
+impl SyslogSink {
+    pub fn new(config: SyslogConfig) -> Self {
+        Self { config, hostname: local_hostname(), connection: Mutex::new(None) }
+    }
+
+    fn connect(&self) -> Result<Connection, String> {
+        let address = self.config.address
+            .to_socket_addrs()
+            .map_err(|e| format!("{}: {}", self.config.address, e))?
+            .next()
+            .ok_or_else(|| format!("{}: no address", self.config.address))?;
+
+        match self.config.transport {
+            SyslogTransport::Udp => {
+                let bind = if address.is_ipv4() { "0.0.0.0:0" } else { "[::]:0" };
+                let socket = UdpSocket::bind(bind).map_err(|e| e.to_string())?;
+                socket.connect(address).map_err(|e| e.to_string())?;
+                Ok(Connection::Udp(socket))
+            }
+            SyslogTransport::Tcp => {
+                let stream = TcpStream::connect_timeout(&address, CONNECT_TIMEOUT).map_err(|e| e.to_string())?;
+                Ok(Connection::Tcp(stream))
+            }
+            #[cfg(feature = "syslog-tls")]
+            SyslogTransport::Tls => {
+                let stream = TcpStream::connect_timeout(&address, CONNECT_TIMEOUT).map_err(|e| e.to_string())?;
+                let host = self.config.address.rsplit_once(':').map_or(self.config.address.as_str(), |(host, _)| host);
+                let server_name = rustls::ServerName::try_from(host.trim_matches(['[', ']'])).map_err(|e| e.to_string())?;
+                let tls_config = tls_client_config(self.config.ca_file.as_deref())?;
+                let session = rustls::ClientConnection::new(tls_config, server_name).map_err(|e| e.to_string())?;
+                Ok(Connection::Tls(Box::new(rustls::StreamOwned::new(session, stream))))
+            }
+            #[cfg(not(feature = "syslog-tls"))]
+            SyslogTransport::Tls => Err("syslog over TLS needs the syslog-tls feature".to_string()),
+        }
+    }
+}
+
+/// Client configuration trusting the built-in roots and the CAs in `ca_file`
+#[cfg(any(feature = "syslog-tls", feature = "smtp-tls"))]
+pub(crate) fn tls_client_config(ca_file: Option<&str>) -> Result<Arc<rustls::ClientConfig>, String> {
+    let mut roots = rustls::RootCertStore::empty();
+    roots.add_trust_anchors(webpki_roots::TLS_SERVER_ROOTS.iter().map(|anchor| {
+        rustls::OwnedTrustAnchor::from_subject_spki_name_constraints(anchor.subject, anchor.spki, anchor.name_constraints)
+    }));
+    if let Some(ca_file) = ca_file {
+        let pem = std::fs::File::open(ca_file).map_err(|e| format!("{}: {}", ca_file, e))?;
+        let certificates = rustls_pemfile::certs(&mut std::io::BufReader::new(pem)).map_err(|e| format!("{}: {}", ca_file, e))?;
+        for certificate in certificates {
+            roots.add(&rustls::Certificate(certificate)).map_err(|e| format!("{}: {}", ca_file, e))?;
+        }
+    }
+
+    Ok(Arc::new(
+        rustls::ClientConfig::builder()
+            .with_safe_defaults()
+            .with_root_certificates(roots)
+            .with_no_client_auth(),
+    ))
+}
+
+impl WipeEventSink for SyslogSink {
+    fn name(&self) -> String {
+        format!("syslog {:?} {}", self.config.transport, self.config.address)
+    }
+
+    fn deliver(&self, event: &LifecycleEvent) -> Result<(), String> {
+        let message = format_rfc5424(event, self.config.facility, &self.hostname, &self.config.app_name);
+        // Octet counting framing (RFC 6587 / RFC 5425) for stream transports
+        let framed = format!("{} {}", message.len(), message);
+
+        let mut connection = self.connection.lock().unwrap();
+        if connection.is_none() {
+            *connection = Some(self.connect()?);
+        }
+        let sent = match connection.as_mut().unwrap() {
+            Connection::Udp(socket) => socket.send(message.as_bytes()).map(|_| ()),
+            Connection::Tcp(stream) => stream.write_all(framed.as_bytes()).and_then(|_| stream.flush()),
+            #[cfg(feature = "syslog-tls")]
+            Connection::Tls(stream) => stream.write_all(framed.as_bytes()).and_then(|_| stream.flush()),
+        };
+        sent.map_err(|e| {
+            *connection = None;
+            e.to_string()
+        })
+    }
+}
+
+/// Writes events to the Windows Application log under a named source
+#[cfg(windows)]
+pub struct EventLogSink {
+    source: String,
+}
+
+#[cfg(windows)]
+impl EventLogSink {
+    pub fn new(source: String) -> Self {
+        Self { source }
+    }
+}
+
+#[cfg(windows)]
+impl WipeEventSink for EventLogSink {
+    fn name(&self) -> String {
+        format!("event log source {}", self.source)
+    }
+
+    fn deliver(&self, event: &LifecycleEvent) -> Result<(), String> {
+        use windows::core::{HSTRING, PCWSTR};
+        use windows::Win32::Foundation::PSID;
+        use windows::Win32::System::EventLog::{
+            DeregisterEventSource, RegisterEventSourceW, ReportEventW, EVENTLOG_ERROR_TYPE,
+            EVENTLOG_INFORMATION_TYPE, EVENTLOG_WARNING_TYPE,
+        };
+
+        let event_type = match severity(event) {
+            0..=3 => EVENTLOG_ERROR_TYPE,
+            4 => EVENTLOG_WARNING_TYPE,
+            _ => EVENTLOG_INFORMATION_TYPE,
+        };
+        // Distinct ids per kind so SIEM rules can match on them
+        let event_id = match event.kind {
+            LifecycleEventKind::WipeStarted => 1000,
+            LifecycleEventKind::WipeCompleted => 1001,
+            LifecycleEventKind::WipeFailed => 1002,
+            LifecycleEventKind::WipeCancelled => 1003,
+            LifecycleEventKind::VerificationCompleted => 1004,
+        };
+
+        let details = serde_json::to_string_pretty(event).map_err(|e| e.to_string())?;
+        let text = HSTRING::from(format!("{}\r\n\r\n{}", event_summary(event), details));
+        let source = HSTRING::from(self.source.as_str());
+
+        unsafe {
+            let handle = RegisterEventSourceW(PCWSTR::null(), &source).map_err(|e| e.to_string())?;
+            let strings = [PCWSTR(text.as_ptr())];
+            let reported = ReportEventW(handle, event_type, 0, event_id, PSID(std::ptr::null_mut()), 0, Some(&strings), None);
+            let _ = DeregisterEventSource(handle);
+            reported.map_err(|e| e.to_string())
+        }
+    }
+}
+
+/// Sinks for the configured syslog collector and event log source
+pub fn event_log_sinks(config: &EventLoggingConfig) -> Vec<Arc<dyn WipeEventSink>> {
+    let mut sinks: Vec<Arc<dyn WipeEventSink>> = Vec::new();
+
+    if let Some(syslog) = &config.syslog {
+        if syslog.transport == SyslogTransport::Tls && !cfg!(feature = "syslog-tls") {
+            warn!(address = %syslog.address, "Syslog over TLS needs the syslog-tls feature; not sending");
+        } else {
+            sinks.push(Arc::new(SyslogSink::new(syslog.clone())));
+        }
+    }
+
+    if let Some(source) = &config.windows_event_log {
+        #[cfg(windows)]
+        sinks.push(Arc::new(EventLogSink::new(source.clone())));
+        #[cfg(not(windows))]
+        warn!(source = %source, "Windows event log source ignored on this platform");
+    }
+
+    sinks
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rfc5424_format() {
+        let request: crate::core::WipeRequest = serde_json::from_value(serde_json::json!({
+            "id": uuid::Uuid::nil(), "target_path": "/dev/sdb", "target_type": "HDD",
+            "standard": "NIST_SP_800_88_R1", "passes": 1, "verify_erasure": true,
+            "generate_certificate": false, "timestamp": chrono::Utc::now(), "operator": "alice",
+        }))
+        .unwrap();
+        let event = LifecycleEvent {
+            error: Some("bad \"sector\" ]".to_string()),
+            ..LifecycleEvent::new(LifecycleEventKind::WipeFailed, &request)
+        };
+
+        let message = format_rfc5424(&event, 4, "wipe bench", "hdd-tool");
+        assert!(message.starts_with("<35>1 "));
+        assert!(message.contains(" wipebench hdd-tool "));
+        assert!(message.contains(" WipeFailed [wipe@32473 operation_id=\"00000000-0000-0000-0000-000000000000\""));
+        assert!(message.contains("error=\"bad \\\"sector\\\" \\]\"]"));
+        assert!(message.ends_with("Wipe of /dev/sdb failed: bad \"sector\" ]"));
+    }
+}
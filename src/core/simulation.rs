@@ -0,0 +1,267 @@
+//! Simulated wipes against a sparse image
+//!
+//! A simulated wipe runs the whole pipeline, passes, read-back verification,
+//! history and certificates, against a sparse file the size of the target
+//! instead of the target itself. QA and front-end developers get realistic
+//! timing, progress and reports without risking a drive. The target is only
+//! opened read-only to learn its size; when that fails the configured
+//! fallback size is used, so simulations also work for made-up device names.
+//!
+//! Results and certificates of simulated wipes are marked `simulated`.
+//!
+//! Test devices are the same idea made visible: a file registered with
+//! `WipeEngine::create_test_device` is listed among the attached disks,
+//! flagged as virtual, and wipes of it write the file. Integration tests of
+//! downstream applications can drive the whole flow, listing, wiping,
+//! verification and certificates, and inspect the file afterwards.
+
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use tracing::info;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::core::{DriveGeometry, SecurityFeatures};
+use crate::platform::{DiskKind, PhysicalDisk};
+
+pub const SIMULATED_SECTOR_SIZE: u64 = 512;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SimulationConfig {
+    /// Simulate every wipe, regardless of the request
+    #[serde(default)]
+    pub enabled: bool,
+    /// Where images are created; the system temp directory when unset
+    #[serde(default)]
+    pub image_dir: Option<String>,
+    /// Image size when the target's size cannot be read
+    #[serde(default = "default_fallback_size")]
+    pub fallback_size_bytes: u64,
+}
+
+fn default_fallback_size() -> u64 { 1024 * 1024 * 1024 }
+
+impl Default for SimulationConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            image_dir: None,
+            fallback_size_bytes: default_fallback_size(),
+        }
+    }
+}
+
+/// A file standing in for a device: a sparse image deleted when dropped, or
+/// the backing file of a test device
+#[derive(Debug)]
+pub struct SimulatedDevice {
+    path: PathBuf,
+    size_bytes: u64,
+    model: String,
+    serial: Option<String>,
+    temporary: bool,
+}
+
+impl SimulatedDevice {
+    /// Create an image as large as `target`, rounded down to whole sectors
+    pub fn attach(config: &SimulationConfig, operation_id: Uuid, target: &str) -> io::Result<Self> {
+        let size_bytes = target_size(target).unwrap_or(config.fallback_size_bytes);
+        let size_bytes = size_bytes / SIMULATED_SECTOR_SIZE * SIMULATED_SECTOR_SIZE;
+        if size_bytes == 0 {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput, "Simulated device would be empty"));
+        }
+
+        let dir = config.image_dir.as_ref().map_or_else(std::env::temp_dir, PathBuf::from);
+        fs::create_dir_all(&dir)?;
+        let path = dir.join(format!("hdd-tool-simulation-{}.img", operation_id));
+        // Setting the length allocates nothing; blocks appear as passes write them
+        let image = OpenOptions::new().read(true).write(true).create_new(true).open(&path)?;
+        image.set_len(size_bytes)?;
+
+        info!(target = %target, size_bytes, image = %path.display(), "Simulating with a sparse image");
+        Ok(Self {
+            path,
+            size_bytes,
+            model: "Simulated Disk".to_string(),
+            serial: None,
+            temporary: true,
+        })
+    }
+
+    /// Write the backing file of `device`, which outlives the wipe
+    pub fn test_device(device: &TestDevice) -> Self {
+        Self {
+            path: device.path.clone(),
+            size_bytes: device.size_bytes,
+            model: TEST_DEVICE_MODEL.to_string(),
+            serial: Some(device.serial.clone()),
+            temporary: false,
+        }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn size_bytes(&self) -> u64 {
+        self.size_bytes
+    }
+
+    /// Geometry reported for the image; no hidden areas. Simulations derive
+    /// the serial from the target.
+    pub fn geometry(&self, target: &str) -> DriveGeometry {
+        let total_sectors = self.size_bytes / SIMULATED_SECTOR_SIZE;
+        DriveGeometry {
+            model: self.model.clone(),
+            serial: self.serial.clone().unwrap_or_else(|| {
+                format!("SIM-{}", target.trim_start_matches("/dev/").replace(['/', '\\', ':'], "_"))
+            }),
+            firmware: env!("CARGO_PKG_VERSION").to_string(),
+            total_sectors,
+            sector_size: SIMULATED_SECTOR_SIZE,
+            user_capacity: self.size_bytes,
+            native_capacity: self.size_bytes,
+            has_hpa: false,
+            has_dco: false,
+            hpa_size: 0,
+            dco_size: 0,
+        }
+    }
+
+    pub fn security_features(&self) -> SecurityFeatures {
+        SecurityFeatures {
+            security_supported: false,
+            security_enabled: false,
+            security_locked: false,
+            security_frozen: false,
+            enhanced_erase_supported: false,
+            sanitize_supported: false,
+            crypto_scramble_supported: false,
+        }
+    }
+}
+
+impl Drop for SimulatedDevice {
+    fn drop(&mut self) {
+        if self.temporary {
+            let _ = fs::remove_file(&self.path);
+        }
+    }
+}
+
+pub const TEST_DEVICE_MODEL: &str = "Virtual Test Device";
+
+/// Where the data of a test device is kept
+#[derive(Debug, Clone, PartialEq)]
+pub enum TestDeviceBacking {
+    /// A sparse file in the system temp directory, deleted with the device
+    TempFile,
+    /// A new file at this path, kept after the device is removed
+    File(PathBuf),
+}
+
+/// A file-backed device listed with the attached disks
+#[derive(Debug)]
+pub struct TestDevice {
+    pub path: PathBuf,
+    pub size_bytes: u64,
+    pub serial: String,
+    temporary: bool,
+}
+
+impl TestDevice {
+    /// Create the backing file; an existing file is never reused
+    pub fn create(size_bytes: u64, backing: TestDeviceBacking) -> io::Result<Self> {
+        let size_bytes = size_bytes / SIMULATED_SECTOR_SIZE * SIMULATED_SECTOR_SIZE;
+        if size_bytes == 0 {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput, "A test device needs at least one sector"));
+        }
+
+        let id = Uuid::new_v4();
+        let (path, temporary) = match backing {
+            TestDeviceBacking::TempFile => (std::env::temp_dir().join(format!("hdd-tool-test-device-{}.img", id)), true),
+            TestDeviceBacking::File(path) => (path, false),
+        };
+        let file = OpenOptions::new().read(true).write(true).create_new(true).open(&path)?;
+        file.set_len(size_bytes)?;
+
+        Ok(Self {
+            path,
+            size_bytes,
+            serial: format!("TEST-{}", &id.simple().to_string()[..12].to_ascii_uppercase()),
+            temporary,
+        })
+    }
+
+    pub fn path_string(&self) -> String {
+        self.path.to_string_lossy().to_string()
+    }
+
+    /// The device as listed by `WipeEngine::scan_devices`
+    pub fn physical_disk(&self) -> PhysicalDisk {
+        PhysicalDisk {
+            path: self.path_string(),
+            vendor: "hdd-tool".to_string(),
+            model: TEST_DEVICE_MODEL.to_string(),
+            serial: self.serial.clone(),
+            firmware: env!("CARGO_PKG_VERSION").to_string(),
+            bus_type: "Virtual".to_string(),
+            size_bytes: self.size_bytes,
+            kind: DiskKind::TestDevice,
+            ..PhysicalDisk::default()
+        }
+    }
+}
+
+impl Drop for TestDevice {
+    fn drop(&mut self) {
+        if self.temporary {
+            let _ = fs::remove_file(&self.path);
+        }
+    }
+}
+
+/// Size of a device or file, read without opening it for writing
+fn target_size(target: &str) -> Option<u64> {
+    let size = File::open(target).and_then(|mut file| file.seek(SeekFrom::End(0))).ok()?;
+    (size > 0).then_some(size)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_image_matches_target_size() {
+        let dir = tempfile::tempdir().unwrap();
+        let target = dir.path().join("target.bin");
+        fs::write(&target, vec![0u8; 4096 + 100]).unwrap();
+        let config = SimulationConfig { image_dir: Some(dir.path().to_string_lossy().to_string()), ..SimulationConfig::default() };
+
+        let device = SimulatedDevice::attach(&config, Uuid::new_v4(), target.to_str().unwrap()).unwrap();
+        assert_eq!(device.size_bytes(), 4096);
+        assert_eq!(device.geometry("/dev/sdz").total_sectors, 8);
+        let image = device.path().to_path_buf();
+        assert_eq!(fs::metadata(&image).unwrap().len(), 4096);
+
+        drop(device);
+        assert!(!image.exists());
+    }
+
+    #[test]
+    fn test_device_is_listed_as_virtual() {
+        let device = TestDevice::create(10_000, TestDeviceBacking::TempFile).unwrap();
+        let disk = device.physical_disk();
+        assert_eq!(disk.size_bytes, 9728);
+        assert!(disk.kind.is_virtual());
+        assert_eq!(fs::metadata(&device.path).unwrap().len(), 9728);
+
+        // Writing it does not delete it; dropping the device does
+        let path = device.path.clone();
+        drop(SimulatedDevice::test_device(&device));
+        assert!(path.exists());
+        drop(device);
+        assert!(!path.exists());
+    }
+}
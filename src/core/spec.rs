@@ -0,0 +1,303 @@
+//! Canonical description of a wipe method
+//!
+//! The engine (`SanitizationStandard`), the advanced wiper (`WipingAlgorithm`)
+//! and the legacy sanitizer (`sanitization::SanitizationMethod`) each name wipe
+//! methods their own way, and the same name does not always mean the same
+//! passes. `WipeSpec` is the one vocabulary they convert to: the standard a
+//! method satisfies, its overwrite patterns, how many passes to run and whether
+//! the drive's own erase command should be used. Front ends should accept and
+//! report `WipeSpec`s and convert at the edge.
+
+use serde::{Deserialize, Serialize};
+
+use crate::advanced_wiper::WipingAlgorithm;
+use crate::core::{SanitizationStandard, WipeError, WipeErrorCode, WipeResult2};
+use crate::sanitization::{SanitizationMethod, SanitizationPattern};
+
+/// What one overwrite pass writes
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum OverwritePattern {
+    /// A byte pattern repeated across the target
+    Fill(Vec<u8>),
+    /// A cryptographically random stream
+    Random,
+}
+
+impl OverwritePattern {
+    pub fn byte(byte: u8) -> Self {
+        OverwritePattern::Fill(vec![byte])
+    }
+
+    pub fn description(&self) -> String {
+        match self {
+            OverwritePattern::Fill(bytes) => match bytes.first() {
+                Some(&first) if bytes.iter().all(|&byte| byte == first) => match first {
+                    0x00 => "Zeros".to_string(),
+                    0xFF => "Ones".to_string(),
+                    byte => format!("0x{:02X}", byte),
+                },
+                _ => format!("Pattern {}", hex::encode(bytes)),
+            },
+            OverwritePattern::Random => "Random (ChaCha20)".to_string(),
+        }
+    }
+}
+
+/// Erase commands executed by the drive itself
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum FirmwareErase {
+    AtaSecureErase,
+    AtaEnhancedSecureErase,
+    NvmeFormat,
+    NvmeCryptoErase,
+    /// Sanitize erases every namespace of the controller
+    NvmeSanitizeBlockErase,
+    NvmeSanitizeCryptoErase,
+    ScsiSanitizeOverwrite,
+    ScsiSanitizeBlockErase,
+    ScsiSanitizeCryptoErase,
+    ScsiFormatUnit,
+}
+
+impl FirmwareErase {
+    pub fn description(&self) -> &'static str {
+        match self {
+            FirmwareErase::AtaSecureErase => "ATA Secure Erase",
+            FirmwareErase::AtaEnhancedSecureErase => "ATA Enhanced Secure Erase",
+            FirmwareErase::NvmeFormat => "NVMe Format (user data erase)",
+            FirmwareErase::NvmeCryptoErase => "NVMe Cryptographic Erase",
+            FirmwareErase::NvmeSanitizeBlockErase => "NVMe Sanitize Block Erase",
+            FirmwareErase::NvmeSanitizeCryptoErase => "NVMe Sanitize Cryptographic Erase",
+            FirmwareErase::ScsiSanitizeOverwrite => "SCSI Sanitize Overwrite",
+            FirmwareErase::ScsiSanitizeBlockErase => "SCSI Sanitize Block Erase",
+            FirmwareErase::ScsiSanitizeCryptoErase => "SCSI Sanitize Cryptographic Erase",
+            FirmwareErase::ScsiFormatUnit => "SCSI Format Unit",
+        }
+    }
+}
+
+/// Whether a firmware erase is used instead of overwriting from the host
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum HardwarePreference {
+    /// Overwrite from the host only
+    #[default]
+    OverwriteOnly,
+    /// Use whichever erase command the drive supports; overwrite when it has none
+    PreferFirmware,
+    /// Use this erase command; the overwrite patterns, if any, are the fallback
+    /// when the drive does not support it
+    Firmware(FirmwareErase),
+}
+
+/// A wipe method in the shared vocabulary of the GUI, CLI, SDK and engine
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WipeSpec {
+    /// Standard this method satisfies, if it corresponds to one
+    #[serde(default)]
+    pub standard: Option<SanitizationStandard>,
+    /// Patterns of the overwrite passes; repeated when `passes` exceeds them
+    pub patterns: Vec<OverwritePattern>,
+    pub passes: u32,
+    #[serde(default)]
+    pub hardware: HardwarePreference,
+}
+
+impl WipeSpec {
+    /// One overwrite pass per pattern
+    pub fn overwrite(patterns: Vec<OverwritePattern>) -> Self {
+        Self {
+            standard: None,
+            passes: patterns.len() as u32,
+            patterns,
+            hardware: HardwarePreference::OverwriteOnly,
+        }
+    }
+
+    /// A firmware erase with no overwrite fallback
+    pub fn firmware(erase: FirmwareErase) -> Self {
+        Self::overwrite(Vec::new()).with_hardware(HardwarePreference::Firmware(erase))
+    }
+
+    pub fn with_standard(mut self, standard: SanitizationStandard) -> Self {
+        self.standard = Some(standard);
+        self
+    }
+
+    pub fn with_passes(mut self, passes: u32) -> Self {
+        self.passes = passes;
+        self
+    }
+
+    pub fn with_hardware(mut self, hardware: HardwarePreference) -> Self {
+        self.hardware = hardware;
+        self
+    }
+
+    /// The pattern of every pass in order
+    pub fn pass_patterns(&self) -> Vec<OverwritePattern> {
+        if self.patterns.is_empty() {
+            return Vec::new();
+        }
+        self.patterns.iter().cycle().take(self.passes as usize).cloned().collect()
+    }
+
+    pub fn description(&self) -> String {
+        let overwrite = self.pass_patterns()
+            .iter()
+            .map(|pattern| pattern.description())
+            .collect::<Vec<_>>()
+            .join(", ");
+        let method = match (self.hardware, overwrite.is_empty()) {
+            (HardwarePreference::Firmware(erase), true) => erase.description().to_string(),
+            (HardwarePreference::Firmware(erase), false) => {
+                format!("{}, or {} passes: {}", erase.description(), self.passes, overwrite)
+            }
+            (HardwarePreference::PreferFirmware, _) => {
+                format!("Drive erase if supported, else {} passes: {}", self.passes, overwrite)
+            }
+            (HardwarePreference::OverwriteOnly, _) => format!("{} passes: {}", self.passes, overwrite),
+        };
+        match self.standard {
+            Some(standard) => format!("{:?} ({})", standard, method),
+            None => method,
+        }
+    }
+
+    fn unsupported(method: &str) -> WipeError {
+        WipeError {
+            code: WipeErrorCode::InvalidPattern,
+            message: format!("{} is not a device wipe method", method),
+            sector: None,
+        }
+    }
+}
+
+impl From<SanitizationStandard> for WipeSpec {
+    fn from(standard: SanitizationStandard) -> Self {
+        use OverwritePattern::Random;
+        let byte = OverwritePattern::byte;
+
+        let patterns = match standard {
+            SanitizationStandard::NIST_SP_800_88_R1 => vec![byte(0x00)],
+            SanitizationStandard::DoD_5220_22_M | SanitizationStandard::AFSSI_5020 => {
+                vec![byte(0x00), byte(0xFF), Random]
+            }
+            SanitizationStandard::BSI_2011_VS => vec![byte(0x00), byte(0xFF)],
+            SanitizationStandard::NAVSO_P_5239_26 => vec![byte(0x01), byte(0x27), byte(0x96)],
+        };
+        WipeSpec::overwrite(patterns).with_standard(standard)
+    }
+}
+
+impl TryFrom<&WipingAlgorithm> for WipeSpec {
+    type Error = WipeError;
+
+    fn try_from(algorithm: &WipingAlgorithm) -> WipeResult2<Self> {
+        use OverwritePattern::Random;
+        let byte = OverwritePattern::byte;
+
+        let spec = match algorithm {
+            WipingAlgorithm::NistClear => WipeSpec::overwrite(vec![Random])
+                .with_standard(SanitizationStandard::NIST_SP_800_88_R1),
+            WipingAlgorithm::NistPurge => WipeSpec::overwrite(vec![
+                byte(0x00), byte(0xFF), Random, Random, byte(0x55), byte(0xAA), Random,
+            ])
+            .with_standard(SanitizationStandard::NIST_SP_800_88_R1)
+            .with_hardware(HardwarePreference::PreferFirmware),
+            WipingAlgorithm::AtaSecureErase => WipeSpec::firmware(FirmwareErase::AtaSecureErase),
+            WipingAlgorithm::AtaEnhancedSecureErase => WipeSpec::firmware(FirmwareErase::AtaEnhancedSecureErase),
+            WipingAlgorithm::NvmeSecureErase => WipeSpec::firmware(FirmwareErase::NvmeFormat),
+            WipingAlgorithm::NvmeCryptoErase => WipeSpec::firmware(FirmwareErase::NvmeCryptoErase),
+            WipingAlgorithm::NvmeSanitizeBlockErase => WipeSpec::firmware(FirmwareErase::NvmeSanitizeBlockErase),
+            WipingAlgorithm::NvmeSanitizeCryptoErase => WipeSpec::firmware(FirmwareErase::NvmeSanitizeCryptoErase),
+            WipingAlgorithm::ScsiSanitizeOverwrite => WipeSpec::firmware(FirmwareErase::ScsiSanitizeOverwrite),
+            WipingAlgorithm::ScsiSanitizeBlockErase => WipeSpec::firmware(FirmwareErase::ScsiSanitizeBlockErase),
+            WipingAlgorithm::ScsiSanitizeCryptoErase => WipeSpec::firmware(FirmwareErase::ScsiSanitizeCryptoErase),
+            WipingAlgorithm::ScsiFormatUnit => WipeSpec::firmware(FirmwareErase::ScsiFormatUnit),
+            WipingAlgorithm::DoD522022M => SanitizationStandard::DoD_5220_22_M.into(),
+            WipingAlgorithm::DoD522022MEce => WipeSpec::overwrite(vec![
+                byte(0x00), byte(0xFF), Random, byte(0x96), byte(0x00), byte(0xFF), Random,
+            ])
+            .with_standard(SanitizationStandard::DoD_5220_22_M),
+            WipingAlgorithm::Gutmann => WipeSpec::overwrite(vec![Random]).with_passes(35),
+            WipingAlgorithm::Random => WipeSpec::overwrite(vec![Random]),
+            // The engine resets and fills zones in order on zoned devices
+            WipingAlgorithm::Zeros | WipingAlgorithm::FastZero | WipingAlgorithm::ZonedOverwrite => {
+                WipeSpec::overwrite(vec![byte(0x00)])
+            }
+            WipingAlgorithm::Ones => WipeSpec::overwrite(vec![byte(0xFF)]),
+            WipingAlgorithm::TwoPass => WipeSpec::overwrite(vec![byte(0x00), Random]),
+            WipingAlgorithm::ThreePass => WipeSpec::overwrite(vec![byte(0x00), byte(0xFF), Random]),
+            WipingAlgorithm::SevenPass => WipeSpec::overwrite(vec![
+                byte(0x55), byte(0xAA), Random, byte(0x00), byte(0xFF), Random, Random,
+            ]),
+            WipingAlgorithm::CustomPattern(pattern) => WipeSpec::overwrite(vec![OverwritePattern::Fill(pattern.clone())]),
+            WipingAlgorithm::NistDestroy => return Err(Self::unsupported("Physical destruction")),
+            WipingAlgorithm::CryptoHeaderDestruction => return Err(Self::unsupported("Encryption header destruction")),
+            WipingAlgorithm::FileSystemWipe
+            | WipingAlgorithm::FreeSpaceWipe
+            | WipingAlgorithm::SlackSpaceWipe
+            | WipingAlgorithm::QuickFormat => return Err(Self::unsupported(&format!("{:?}", algorithm))),
+        };
+        Ok(spec)
+    }
+}
+
+impl TryFrom<&SanitizationMethod> for WipeSpec {
+    type Error = WipeError;
+
+    fn try_from(method: &SanitizationMethod) -> WipeResult2<Self> {
+        use OverwritePattern::Random;
+        let byte = OverwritePattern::byte;
+
+        let spec = match method {
+            SanitizationMethod::Clear => WipeSpec::overwrite(vec![byte(0x00)])
+                .with_standard(SanitizationStandard::NIST_SP_800_88_R1),
+            SanitizationMethod::Purge => WipeSpec::overwrite(vec![Random, byte(0xFF), byte(0x00)])
+                .with_standard(SanitizationStandard::NIST_SP_800_88_R1),
+            // The legacy sanitizer falls back to a random overwrite
+            SanitizationMethod::SecureErase => WipeSpec::overwrite(vec![Random])
+                .with_hardware(HardwarePreference::Firmware(FirmwareErase::AtaSecureErase)),
+            SanitizationMethod::EnhancedSecureErase => WipeSpec::overwrite(vec![Random])
+                .with_hardware(HardwarePreference::Firmware(FirmwareErase::AtaEnhancedSecureErase)),
+            SanitizationMethod::ComprehensiveClean => {
+                WipeSpec::overwrite(vec![Random, OverwritePattern::Fill(vec![0x55, 0xAA]), byte(0x00)])
+            }
+            SanitizationMethod::FreeSpaceOnly => return Err(Self::unsupported("Free space wipe")),
+        };
+        Ok(spec)
+    }
+}
+
+/// The legacy sanitizer's pattern for a spec pass
+impl From<&OverwritePattern> for SanitizationPattern {
+    fn from(pattern: &OverwritePattern) -> Self {
+        match pattern {
+            OverwritePattern::Random => SanitizationPattern::Random,
+            OverwritePattern::Fill(bytes) if bytes.as_slice() == [0x00] => SanitizationPattern::Zeros,
+            OverwritePattern::Fill(bytes) if bytes.as_slice() == [0xFF] => SanitizationPattern::Ones,
+            OverwritePattern::Fill(bytes) if bytes.as_slice() == [0x55, 0xAA] => SanitizationPattern::DoD5220,
+            OverwritePattern::Fill(bytes) => SanitizationPattern::Custom(bytes.first().copied().unwrap_or(0x00)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_conversions_agree() {
+        let from_standard = WipeSpec::from(SanitizationStandard::DoD_5220_22_M);
+        let from_algorithm = WipeSpec::try_from(&WipingAlgorithm::DoD522022M).unwrap();
+        assert_eq!(from_standard, from_algorithm);
+        assert_eq!(from_standard.pass_patterns().len(), 3);
+
+        let gutmann = WipeSpec::try_from(&WipingAlgorithm::Gutmann).unwrap();
+        assert_eq!(gutmann.pass_patterns().len(), 35);
+
+        let secure_erase = WipeSpec::try_from(&SanitizationMethod::SecureErase).unwrap();
+        assert_eq!(secure_erase.hardware, HardwarePreference::Firmware(FirmwareErase::AtaSecureErase));
+        assert!(WipeSpec::try_from(&SanitizationMethod::FreeSpaceOnly).is_err());
+    }
+}
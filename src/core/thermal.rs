@@ -0,0 +1,219 @@
+//! Drive temperature during long wipes
+//!
+//! Hours of full-bandwidth writes heat drives up, laptop NVMe drives without
+//! a heatsink in particular, until their own thermal throttling makes the
+//! wipe crawl or the drive shuts down. While a wipe runs the write loop reads
+//! the drive's temperature from SMART / the NVMe health log every
+//! `sample_interval_secs`, caps the write rate at `throttled_mbps` from
+//! `throttle_celsius`, and stops writing from `pause_celsius` until the drive
+//! has cooled to `resume_celsius`. Drives that report no temperature are
+//! written at full speed.
+//!
+//! Every sample is kept in the wipe result, so the operation record shows
+//! how hot the drive ran and when it was held back.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::time::{Duration, Instant};
+use tracing::{info, warn};
+
+use crate::core::TokenBucket;
+use crate::smart::read_device_health;
+
+/// Sampling interval while writes are paused, so the wipe resumes soon after the drive cools
+const PAUSED_SAMPLE_INTERVAL: Duration = Duration::from_secs(5);
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ThermalPolicy {
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    /// Cap the write rate at `throttled_mbps` from this temperature
+    #[serde(default = "default_throttle_celsius")]
+    pub throttle_celsius: i32,
+    /// Stop writing from this temperature...
+    #[serde(default = "default_pause_celsius")]
+    pub pause_celsius: i32,
+    /// ...until the drive has cooled to this one
+    #[serde(default = "default_resume_celsius")]
+    pub resume_celsius: i32,
+    #[serde(default = "default_throttled_mbps")]
+    pub throttled_mbps: f64,
+    #[serde(default = "default_sample_interval")]
+    pub sample_interval_secs: u64,
+}
+
+fn default_true() -> bool { true }
+fn default_throttle_celsius() -> i32 { 65 }
+fn default_pause_celsius() -> i32 { 75 }
+fn default_resume_celsius() -> i32 { 60 }
+fn default_throttled_mbps() -> f64 { 100.0 }
+fn default_sample_interval() -> u64 { 30 }
+
+impl Default for ThermalPolicy {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            throttle_celsius: default_throttle_celsius(),
+            pause_celsius: default_pause_celsius(),
+            resume_celsius: default_resume_celsius(),
+            throttled_mbps: default_throttled_mbps(),
+            sample_interval_secs: default_sample_interval(),
+        }
+    }
+}
+
+impl ThermalPolicy {
+    /// What the write loop does with the drive at `celsius`, coming from
+    /// `state`: a paused wipe stays paused until the drive reaches
+    /// `resume_celsius`
+    pub fn next_state(&self, state: ThermalState, celsius: i32) -> ThermalState {
+        if celsius >= self.pause_celsius || (state == ThermalState::Paused && celsius > self.resume_celsius) {
+            ThermalState::Paused
+        } else if celsius >= self.throttle_celsius {
+            ThermalState::Throttled
+        } else {
+            ThermalState::Normal
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ThermalState {
+    Normal,
+    Throttled,
+    Paused,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TemperatureSample {
+    pub at: DateTime<Utc>,
+    pub celsius: i32,
+    /// What the write loop did from this sample on
+    pub state: ThermalState,
+}
+
+/// Temperature samples and the resulting hold-backs of one wipe
+pub struct ThermalMonitor {
+    policy: ThermalPolicy,
+    read_temperature: Box<dyn FnMut() -> Option<i32>>,
+    state: ThermalState,
+    last_sample: Option<Instant>,
+    bucket: Option<TokenBucket>,
+    samples: Vec<TemperatureSample>,
+}
+
+impl ThermalMonitor {
+    pub fn new(policy: ThermalPolicy, read_temperature: impl FnMut() -> Option<i32> + 'static) -> Self {
+        Self {
+            policy,
+            read_temperature: Box::new(read_temperature),
+            state: ThermalState::Normal,
+            last_sample: None,
+            bucket: None,
+            samples: Vec::new(),
+        }
+    }
+
+    /// Monitor the drive at `device_path` through SMART
+    pub fn for_device(policy: ThermalPolicy, device_path: &str) -> Self {
+        let device_path = device_path.to_string();
+        Self::new(policy, move || read_device_health(&device_path).temperature_celsius)
+    }
+
+    /// Time to wait before writing `bytes`
+    pub fn before_write(&mut self, bytes: u64) -> Duration {
+        self.sample();
+        match (self.state, self.bucket.as_mut()) {
+            (ThermalState::Throttled, Some(bucket)) => bucket.take(bytes),
+            _ => Duration::ZERO,
+        }
+    }
+
+    /// Whether writes have to wait for the drive to cool down
+    pub fn is_paused(&mut self) -> bool {
+        self.sample();
+        self.state == ThermalState::Paused
+    }
+
+    pub fn samples(&self) -> &[TemperatureSample] {
+        &self.samples
+    }
+
+    /// How the temperature is holding writes back, if it is
+    pub fn limit(&self) -> Option<String> {
+        let celsius = self.samples.last().map_or(0, |sample| sample.celsius);
+        match self.state {
+            ThermalState::Normal => None,
+            ThermalState::Throttled => Some(format!("drive at {}°C, writing at {} MB/s", celsius, self.policy.throttled_mbps)),
+            ThermalState::Paused => Some(format!("drive at {}°C, writes paused until {}°C", celsius, self.policy.resume_celsius)),
+        }
+    }
+
+    fn sample(&mut self) {
+        let interval = match self.state {
+            ThermalState::Paused => PAUSED_SAMPLE_INTERVAL,
+            _ => Duration::from_secs(self.policy.sample_interval_secs),
+        };
+        if self.last_sample.is_some_and(|last| last.elapsed() < interval) {
+            return;
+        }
+        self.last_sample = Some(Instant::now());
+        let Some(celsius) = (self.read_temperature)() else {
+            return;
+        };
+
+        let state = self.policy.next_state(self.state, celsius);
+        if state != self.state {
+            match state {
+                ThermalState::Paused => warn!(
+                    celsius,
+                    resume_celsius = self.policy.resume_celsius,
+                    "Drive too hot; pausing writes until it cools"
+                ),
+                ThermalState::Throttled => warn!(
+                    celsius,
+                    max_mbps = self.policy.throttled_mbps,
+                    "Drive running hot; throttling writes"
+                ),
+                ThermalState::Normal => info!(celsius, "Drive cooled; writing at full speed"),
+            }
+            self.bucket = (state == ThermalState::Throttled).then(|| TokenBucket::new(self.policy.throttled_mbps));
+            self.state = state;
+        }
+        self.samples.push(TemperatureSample { at: Utc::now(), celsius, state });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    #[test]
+    fn test_thermal_states() {
+        let policy = ThermalPolicy::default();
+        assert_eq!(policy.next_state(ThermalState::Normal, 50), ThermalState::Normal);
+        assert_eq!(policy.next_state(ThermalState::Normal, 66), ThermalState::Throttled);
+        assert_eq!(policy.next_state(ThermalState::Throttled, 75), ThermalState::Paused);
+        // A paused wipe waits for the resume temperature, not just the pause one
+        assert_eq!(policy.next_state(ThermalState::Paused, 70), ThermalState::Paused);
+        assert_eq!(policy.next_state(ThermalState::Paused, 60), ThermalState::Normal);
+
+        let readings = Rc::new(RefCell::new(vec![Some(40), None, Some(80), Some(62)]));
+        let source = readings.clone();
+        let mut monitor = ThermalMonitor::new(
+            ThermalPolicy { sample_interval_secs: 0, ..policy },
+            move || source.borrow_mut().remove(0),
+        );
+        assert_eq!(monitor.before_write(4096), Duration::ZERO);
+        assert_eq!(monitor.before_write(4096), Duration::ZERO);
+        assert!(monitor.is_paused());
+        // Paused sampling waits a few seconds, so the next reading is not taken yet
+        assert!(monitor.is_paused());
+        assert_eq!(readings.borrow().len(), 1);
+
+        let celsius: Vec<i32> = monitor.samples().iter().map(|sample| sample.celsius).collect();
+        assert_eq!(celsius, vec![40, 80]);
+    }
+}
@@ -0,0 +1,349 @@
+//! I/O limits for wipes on production hosts
+//!
+//! A wipe of a secondary disk on a live server should not starve the disks
+//! the server is using. `max_throughput_mbps` caps the write rate with a token
+//! bucket in the write loop; `io_priority` lowers the I/O scheduling priority
+//! of the wiping thread (ioprio on Linux, which the BFQ and CFQ schedulers
+//! honour; background mode on Windows).
+//!
+//! Both can be changed while a wipe runs through
+//! `WipeEngine::update_operation_limits`; the write loop picks the new values
+//! up before its next block. The same handle pauses a wipe between blocks
+//! (`WipeEngine::pause_operation`, also used for scheduled wipes
+//! sitting out the time between maintenance windows). A paused write loop
+//! flushes what it wrote and leaves a `PauseCheckpoint` on the handle. A wipe that watches the drive's temperature
+//! (see `crate::core::thermal`) is held back the same way, and so is an
+//! overwrite preempted by a higher-priority erase (see `crate::core::priority`).
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tracing::warn;
+
+use crate::core::ThermalMonitor;
+
+const BYTES_PER_MB: f64 = 1024.0 * 1024.0;
+
+/// Longest single sleep, so cancellation stays responsive while throttled
+pub const MAX_THROTTLE_SLEEP: Duration = Duration::from_millis(100);
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum IoPriority {
+    /// Leave the thread's priority alone
+    #[default]
+    Normal,
+    /// Lowest best-effort priority
+    Low,
+    /// Only use the disk when nothing else does
+    Idle,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct OperationLimits {
+    /// Write rate cap in MB/s; unlimited when absent
+    #[serde(default)]
+    pub max_throughput_mbps: Option<f64>,
+    #[serde(default)]
+    pub io_priority: IoPriority,
+}
+
+/// Where a paused overwrite pass stopped, recorded once its writes were flushed
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PauseCheckpoint {
+    pub pass: u32,
+    pub total_passes: u32,
+    /// Bytes of the pass that are on the device
+    pub bytes_written: u64,
+    /// Sector the pass continues at; None on zoned drives, which are written zone by zone
+    pub next_sector: Option<u64>,
+    pub paused_at: DateTime<Utc>,
+}
+
+/// Limits shared between the engine and a running wipe
+pub struct LimitsHandle {
+    limits: Mutex<OperationLimits>,
+    /// Bumped on every change so the write loop only re-reads when needed
+    generation: AtomicU64,
+    /// The write loop holds before its next block while set
+    paused: AtomicBool,
+    /// Held for a higher-priority wipe, independently of `paused`
+    preempted: AtomicBool,
+    /// Left by the write loop while it holds
+    checkpoint: Mutex<Option<PauseCheckpoint>>,
+}
+
+impl LimitsHandle {
+    pub fn new(limits: OperationLimits) -> Self {
+        Self {
+            limits: Mutex::new(limits),
+            generation: AtomicU64::new(0),
+            paused: AtomicBool::new(false),
+            preempted: AtomicBool::new(false),
+            checkpoint: Mutex::new(None),
+        }
+    }
+
+    pub fn get(&self) -> OperationLimits {
+        *self.limits.lock().unwrap()
+    }
+
+    pub fn set(&self, limits: OperationLimits) {
+        *self.limits.lock().unwrap() = limits;
+        self.generation.fetch_add(1, Ordering::Release);
+    }
+
+    pub fn generation(&self) -> u64 {
+        self.generation.load(Ordering::Acquire)
+    }
+
+    pub fn set_paused(&self, paused: bool) {
+        self.paused.store(paused, Ordering::Release);
+    }
+
+    pub fn set_preempted(&self, preempted: bool) {
+        self.preempted.store(preempted, Ordering::Release);
+    }
+
+    /// Paused by an operator or the scheduler, or preempted
+    pub fn is_paused(&self) -> bool {
+        self.paused.load(Ordering::Acquire) || self.preempted.load(Ordering::Acquire)
+    }
+
+    /// Paused by an operator or the scheduler; only this can be resumed
+    pub fn is_pause_requested(&self) -> bool {
+        self.paused.load(Ordering::Acquire)
+    }
+
+    pub fn is_preempted(&self) -> bool {
+        self.preempted.load(Ordering::Acquire)
+    }
+
+    /// Set by the write loop when it has quiesced, and cleared when it moves on
+    pub fn set_checkpoint(&self, checkpoint: Option<PauseCheckpoint>) {
+        *self.checkpoint.lock().unwrap() = checkpoint;
+    }
+
+    /// Where the write loop is holding, once it has reached a block boundary
+    pub fn checkpoint(&self) -> Option<PauseCheckpoint> {
+        self.checkpoint.lock().unwrap().clone()
+    }
+}
+
+/// Token bucket holding up to one second of writes
+#[derive(Debug, Clone)]
+pub struct TokenBucket {
+    bytes_per_second: f64,
+    tokens: f64,
+    last_refill: Instant,
+}
+
+impl TokenBucket {
+    pub fn new(max_throughput_mbps: f64) -> Self {
+        let bytes_per_second = max_throughput_mbps.max(0.01) * BYTES_PER_MB;
+        Self { bytes_per_second, tokens: bytes_per_second, last_refill: Instant::now() }
+    }
+
+    pub fn set_rate(&mut self, max_throughput_mbps: f64) {
+        self.refill(Instant::now());
+        self.bytes_per_second = max_throughput_mbps.max(0.01) * BYTES_PER_MB;
+        self.tokens = self.tokens.min(self.bytes_per_second);
+    }
+
+    /// Spend `bytes` and return how long to wait before writing them
+    pub fn take(&mut self, bytes: u64) -> Duration {
+        self.take_at(bytes, Instant::now())
+    }
+
+    fn take_at(&mut self, bytes: u64, now: Instant) -> Duration {
+        self.refill(now);
+        self.tokens -= bytes as f64;
+        if self.tokens >= 0.0 {
+            Duration::ZERO
+        } else {
+            Duration::from_secs_f64(-self.tokens / self.bytes_per_second)
+        }
+    }
+
+    fn refill(&mut self, now: Instant) {
+        let elapsed = now.saturating_duration_since(self.last_refill).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * self.bytes_per_second).min(self.bytes_per_second);
+        self.last_refill = now;
+    }
+}
+
+/// Applies `IoPriority` to the current thread and restores the original
+/// priority when dropped, since wipes often run on pooled threads
+pub struct IoPriorityGuard {
+    #[cfg(target_os = "linux")]
+    original: Option<i32>,
+    #[cfg(windows)]
+    background: bool,
+    current: IoPriority,
+}
+
+impl IoPriorityGuard {
+    pub fn new() -> Self {
+        Self {
+            #[cfg(target_os = "linux")]
+            original: None,
+            #[cfg(windows)]
+            background: false,
+            current: IoPriority::Normal,
+        }
+    }
+
+    pub fn apply(&mut self, priority: IoPriority) {
+        if priority == self.current {
+            return;
+        }
+        self.current = priority;
+
+        #[cfg(target_os = "linux")]
+        {
+            // ioprio_set(2): class in the top bits, level 0-7 below
+            const IOPRIO_WHO_PROCESS: libc::c_long = 1;
+            const IOPRIO_CLASS_SHIFT: i32 = 13;
+            const IOPRIO_CLASS_BE: i32 = 2;
+            const IOPRIO_CLASS_IDLE: i32 = 3;
+
+            if self.original.is_none() {
+                // Thread id 0 is the calling thread
+                let original = unsafe { libc::syscall(libc::SYS_ioprio_get, IOPRIO_WHO_PROCESS, 0) };
+                self.original = (original >= 0).then_some(original as i32);
+            }
+            let value = match priority {
+                IoPriority::Normal => self.original.unwrap_or(0),
+                IoPriority::Low => IOPRIO_CLASS_BE << IOPRIO_CLASS_SHIFT | 7,
+                IoPriority::Idle => IOPRIO_CLASS_IDLE << IOPRIO_CLASS_SHIFT,
+            };
+            let result = unsafe { libc::syscall(libc::SYS_ioprio_set, IOPRIO_WHO_PROCESS, 0, value) };
+            if result != 0 {
+                warn!(error = %std::io::Error::last_os_error(), "Failed to set I/O priority");
+            }
+        }
+
+        #[cfg(windows)]
+        {
+            use windows::Win32::System::Threading::{
+                GetCurrentThread, SetThreadPriority, THREAD_MODE_BACKGROUND_BEGIN, THREAD_MODE_BACKGROUND_END,
+            };
+
+            let background = priority != IoPriority::Normal;
+            if background != self.background {
+                let mode = if background { THREAD_MODE_BACKGROUND_BEGIN } else { THREAD_MODE_BACKGROUND_END };
+                match unsafe { SetThreadPriority(GetCurrentThread(), mode) } {
+                    Ok(()) => self.background = background,
+                    Err(e) => warn!(error = %e, "Failed to set I/O priority"),
+                }
+            }
+        }
+    }
+}
+
+impl Default for IoPriorityGuard {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Drop for IoPriorityGuard {
+    fn drop(&mut self) {
+        self.apply(IoPriority::Normal);
+    }
+}
+
+/// Rate limit and I/O priority of one wipe, re-read when the limits change
+pub struct Throttle<'a> {
+    handle: &'a LimitsHandle,
+    generation: Option<u64>,
+    bucket: Option<TokenBucket>,
+    priority: IoPriorityGuard,
+    thermal: Option<ThermalMonitor>,
+    /// What `limiting_changed` last reported
+    reported: Option<String>,
+}
+
+impl<'a> Throttle<'a> {
+    pub fn new(handle: &'a LimitsHandle) -> Self {
+        Self { handle, generation: None, bucket: None, priority: IoPriorityGuard::new(), thermal: None, reported: None }
+    }
+
+    /// Also slow down and pause as the drive heats up
+    pub fn with_thermal(mut self, monitor: ThermalMonitor) -> Self {
+        self.thermal = Some(monitor);
+        self
+    }
+
+    /// Whether writes have to wait for the drive to cool down
+    pub fn thermal_pause(&mut self) -> bool {
+        self.thermal.as_mut().is_some_and(|monitor| monitor.is_paused())
+    }
+
+    pub fn temperature_samples(&self) -> Vec<crate::core::TemperatureSample> {
+        self.thermal.as_ref().map(|monitor| monitor.samples().to_vec()).unwrap_or_default()
+    }
+
+    /// What is holding writes back, if anything; the drive's temperature before the rate limit
+    pub fn limiting(&self) -> Option<String> {
+        self.thermal.as_ref().and_then(ThermalMonitor::limit).or_else(|| {
+            self.bucket.as_ref().map(|bucket| format!("rate limit of {:.1} MB/s", bucket.bytes_per_second / BYTES_PER_MB))
+        })
+    }
+
+    /// `limiting`, when it changed since the last call
+    pub fn limiting_changed(&mut self) -> Option<Option<String>> {
+        let limiting = self.limiting();
+        if limiting == self.reported {
+            return None;
+        }
+        self.reported = limiting.clone();
+        Some(limiting)
+    }
+
+    /// Time to wait before writing `bytes`; applies changed limits first
+    pub fn before_write(&mut self, bytes: u64) -> Duration {
+        let generation = self.handle.generation();
+        if self.generation != Some(generation) {
+            self.generation = Some(generation);
+            let limits = self.handle.get();
+            self.priority.apply(limits.io_priority);
+            self.bucket = match (limits.max_throughput_mbps, self.bucket.take()) {
+                (Some(mbps), Some(mut bucket)) => {
+                    bucket.set_rate(mbps);
+                    Some(bucket)
+                }
+                (Some(mbps), None) => Some(TokenBucket::new(mbps)),
+                (None, _) => None,
+            };
+        }
+
+        let limit = self.bucket.as_mut().map_or(Duration::ZERO, |bucket| bucket.take(bytes));
+        let thermal = self.thermal.as_mut().map_or(Duration::ZERO, |monitor| monitor.before_write(bytes));
+        limit.max(thermal)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_token_bucket_rate() {
+        let start = Instant::now();
+        let mut bucket = TokenBucket::new(10.0);
+        bucket.last_refill = start;
+        let mb = BYTES_PER_MB as u64;
+
+        // One second of burst is available up front
+        assert_eq!(bucket.take_at(10 * mb, start), Duration::ZERO);
+        // The next 5 MB have to wait half a second
+        assert_eq!(bucket.take_at(5 * mb, start), Duration::from_millis(500));
+        // After that half second the debt is paid off
+        assert_eq!(bucket.take_at(0, start + Duration::from_millis(500)), Duration::ZERO);
+
+        bucket.set_rate(20.0);
+        assert_eq!(bucket.bytes_per_second, 20.0 * BYTES_PER_MB);
+    }
+}
@@ -0,0 +1,227 @@
+//! Write rates achieved per drive model
+//!
+//! After every wipe that overwrote the device the engine records the rate it
+//! achieved, by drive model and interface, in a small JSON file next to the
+//! operation history. Duration estimates use these rates ahead of anything
+//! else, and a wipe that ran far below the median of its model on the same
+//! interface is flagged: a drive that slow is often retrying failing sectors.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use uuid::Uuid;
+
+use crate::core::{ThroughputSource, WipeError, WipeErrorCode, WipeResult, WipeResult2, WriteRate};
+
+/// Default location of the throughput history
+pub const DEFAULT_THROUGHPUT_PATH: &str = "history/throughput.json";
+
+/// A wipe below this fraction of its model's median rate is flagged
+const UNDERPERFORMING_FRACTION: f64 = 0.5;
+
+/// Earlier wipes of a model needed before a drive is compared with them
+const MIN_SAMPLES_FOR_CHECK: usize = 3;
+
+/// Newest samples kept per model and interface
+const MAX_SAMPLES_PER_MODEL: usize = 50;
+
+const MEGABYTE: f64 = 1024.0 * 1024.0;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ThroughputSample {
+    pub at: DateTime<Utc>,
+    pub operation_id: Uuid,
+    pub model: String,
+    /// Bus the drive was attached through, e.g. "SATA", "USB", "NVMe"
+    pub interface: String,
+    pub serial: String,
+    /// Bytes written and read back
+    pub bytes: u64,
+    pub seconds: u64,
+}
+
+impl ThroughputSample {
+    /// The sample of a finished wipe; `None` when it wrote nothing, was
+    /// simulated or took under a second
+    pub fn from_result(result: &WipeResult, interface: &str) -> Option<Self> {
+        if result.simulated || result.sectors_wiped == 0 || result.duration_seconds == 0 {
+            return None;
+        }
+        let verified = result.verification_report.as_ref().map_or(0, |report| report.bytes_verified);
+        Some(Self {
+            at: result.completion_time,
+            operation_id: result.request_id,
+            model: result.drive_geometry.model.trim().to_string(),
+            interface: interface.to_string(),
+            serial: result.drive_geometry.serial.clone(),
+            bytes: result.sectors_wiped * 512 + verified,
+            seconds: result.duration_seconds,
+        })
+    }
+
+    pub fn mbps(&self) -> f64 {
+        self.bytes as f64 / MEGABYTE / self.seconds.max(1) as f64
+    }
+}
+
+/// How a wipe's rate compares with earlier wipes of the model
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ThroughputCheck {
+    pub achieved_mbps: f64,
+    /// Median of the earlier wipes, once there are enough of them
+    pub model_median_mbps: Option<f64>,
+    /// Earlier wipes of the model on the same interface
+    pub samples: usize,
+    /// Far below the median; the drive may be failing
+    pub underperforming: bool,
+}
+
+pub struct ThroughputHistory {
+    path: PathBuf,
+    samples: Vec<ThroughputSample>,
+}
+
+impl ThroughputHistory {
+    pub fn open<P: AsRef<Path>>(path: P) -> WipeResult2<Self> {
+        let path = path.as_ref().to_path_buf();
+        let samples = if path.exists() {
+            let content = fs::read_to_string(&path).map_err(|e| WipeError {
+                code: WipeErrorCode::UnknownError,
+                message: format!("Failed to read throughput history: {}", e),
+                sector: None,
+            })?;
+            serde_json::from_str(&content).map_err(|e| WipeError {
+                code: WipeErrorCode::UnknownError,
+                message: format!("Failed to parse throughput history: {}", e),
+                sector: None,
+            })?
+        } else {
+            Vec::new()
+        };
+        Ok(Self { path, samples })
+    }
+
+    /// Earlier wipes of `model` attached through `interface`, oldest first
+    pub fn samples_for(&self, model: &str, interface: &str) -> Vec<&ThroughputSample> {
+        let model = model.trim();
+        if model.is_empty() {
+            return Vec::new();
+        }
+        self.samples
+            .iter()
+            .filter(|sample| sample.model == model && sample.interface.eq_ignore_ascii_case(interface))
+            .collect()
+    }
+
+    pub fn median_mbps(&self, model: &str, interface: &str) -> Option<f64> {
+        let mut rates: Vec<f64> = self.samples_for(model, interface).iter().map(|sample| sample.mbps()).collect();
+        if rates.is_empty() {
+            return None;
+        }
+        rates.sort_by(f64::total_cmp);
+        let middle = rates.len() / 2;
+        Some(if rates.len().is_multiple_of(2) { (rates[middle - 1] + rates[middle]) / 2.0 } else { rates[middle] })
+    }
+
+    /// Rate for estimating a wipe of `model` on `interface`
+    pub fn write_rate(&self, model: &str, interface: &str) -> Option<WriteRate> {
+        let samples: Vec<f64> = self
+            .samples_for(model, interface)
+            .iter()
+            .map(|sample| sample.bytes as f64 / sample.seconds.max(1) as f64)
+            .collect();
+        (!samples.is_empty()).then_some(WriteRate { source: ThroughputSource::History, samples })
+    }
+
+    /// Compare `sample` with the earlier wipes of its model, then keep it
+    pub fn record(&mut self, sample: ThroughputSample) -> WipeResult2<ThroughputCheck> {
+        let achieved_mbps = sample.mbps();
+        let samples = self.samples_for(&sample.model, &sample.interface).len();
+        let model_median_mbps = (samples >= MIN_SAMPLES_FOR_CHECK)
+            .then(|| self.median_mbps(&sample.model, &sample.interface))
+            .flatten();
+        let check = ThroughputCheck {
+            achieved_mbps,
+            model_median_mbps,
+            samples,
+            underperforming: model_median_mbps.is_some_and(|median| achieved_mbps < median * UNDERPERFORMING_FRACTION),
+        };
+
+        let (model, interface) = (sample.model.clone(), sample.interface.clone());
+        self.samples.push(sample);
+        let excess = self.samples_for(&model, &interface).len().saturating_sub(MAX_SAMPLES_PER_MODEL);
+        if excess > 0 {
+            let mut dropped = 0;
+            self.samples.retain(|sample| {
+                let drop = dropped < excess && sample.model == model && sample.interface.eq_ignore_ascii_case(&interface);
+                dropped += drop as usize;
+                !drop
+            });
+        }
+        self.save()?;
+        Ok(check)
+    }
+
+    fn save(&self) -> WipeResult2<()> {
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent).map_err(|e| WipeError {
+                code: WipeErrorCode::UnknownError,
+                message: format!("Failed to create history directory: {}", e),
+                sector: None,
+            })?;
+        }
+        let content = serde_json::to_string_pretty(&self.samples).map_err(|e| WipeError {
+            code: WipeErrorCode::UnknownError,
+            message: format!("Failed to serialize throughput history: {}", e),
+            sector: None,
+        })?;
+        fs::write(&self.path, content).map_err(|e| WipeError {
+            code: WipeErrorCode::UnknownError,
+            message: format!("Failed to write throughput history: {}", e),
+            sector: None,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample(model: &str, interface: &str, mbps: u64) -> ThroughputSample {
+        ThroughputSample {
+            at: Utc::now(),
+            operation_id: Uuid::new_v4(),
+            model: model.to_string(),
+            interface: interface.to_string(),
+            serial: "S1".to_string(),
+            bytes: mbps * 1024 * 1024 * 100,
+            seconds: 100,
+        }
+    }
+
+    #[test]
+    fn test_slow_drive_is_flagged() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("throughput.json");
+        let mut history = ThroughputHistory::open(&path).unwrap();
+        for mbps in [180, 200, 190] {
+            let check = history.record(sample("WDC WD10EZEX", "SATA", mbps)).unwrap();
+            assert!(check.model_median_mbps.is_none());
+        }
+        // The same model behind a slow USB bridge is compared with USB wipes only
+        assert_eq!(history.record(sample("WDC WD10EZEX", "USB", 40)).unwrap().samples, 0);
+
+        let history = ThroughputHistory::open(&path).unwrap();
+        assert_eq!(history.median_mbps("WDC WD10EZEX", "sata"), Some(190.0));
+        let mut history = history;
+        let slow = history.record(sample("WDC WD10EZEX", "SATA", 60)).unwrap();
+        assert_eq!((slow.samples, slow.model_median_mbps), (3, Some(190.0)));
+        assert!(slow.underperforming);
+        assert!(!history.record(sample("WDC WD10EZEX", "SATA", 170)).unwrap().underperforming);
+
+        let rate = history.write_rate("WDC WD10EZEX", "SATA").unwrap();
+        assert_eq!(rate.samples.len(), 5);
+        assert!(history.write_rate("Other", "SATA").is_none());
+    }
+}
@@ -0,0 +1,108 @@
+//! Per-operation timeline of what a wipe did
+//!
+//! Progress updates say how far a wipe is; the timeline says what happened
+//! on the way: the device being opened, each pass starting and finishing,
+//! writes retried around bad sectors, throttling and pauses, verification
+//! and the certificate. Front ends render it as an activity log, and it is
+//! the first thing to read when a wipe took longer than it should have.
+//!
+//! The sanitizer and the engine record into one `OperationTimeline` while a
+//! wipe runs; the engine then stores the events with the operation record,
+//! so `WipeEngine::get_operation_events` answers for running and past wipes.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+
+use crate::core::PauseCheckpoint;
+
+/// A sector that keeps failing could otherwise fill the record with retries
+const MAX_EVENTS: usize = 1000;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TimelineEvent {
+    pub at: DateTime<Utc>,
+    #[serde(flatten)]
+    pub kind: TimelineEventKind,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "event")]
+pub enum TimelineEventKind {
+    Started { target_path: String, operator: String },
+    DeviceOpened { device_path: String, model: String, serial: String, total_sectors: u64 },
+    ImageWritten { location: String, bytes_imaged: u64 },
+    PassStarted { pass: u32, total_passes: u32, pattern: String },
+    PassFinished { pass: u32, seconds: f64, sectors_written: u64 },
+    /// A block write failed and was retried sector by sector
+    WriteRetried { sector: u64, error: String },
+    /// A rate limit or the drive's temperature started holding writes back
+    ThrottleEngaged { reason: String },
+    ThrottleReleased,
+    Paused { checkpoint: PauseCheckpoint },
+    Resumed,
+    VerificationStarted { policy: String },
+    VerificationFinished { passed: bool, bytes_verified: u64 },
+    CertificateIssued { certificate_id: String },
+    Finished { success: bool },
+    Failed { code: String, error: String },
+    /// Events past the limit of one timeline that were not kept
+    EventsDropped { count: usize },
+}
+
+/// The events of one running operation, shared by the engine and the sanitizer
+#[derive(Debug, Default)]
+pub struct OperationTimeline {
+    events: Mutex<Vec<TimelineEvent>>,
+    dropped: Mutex<usize>,
+}
+
+impl OperationTimeline {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn record(&self, kind: TimelineEventKind) {
+        let mut events = self.events.lock().unwrap();
+        // Leaves room for how the operation ended
+        let terminal = matches!(kind, TimelineEventKind::Finished { .. } | TimelineEventKind::Failed { .. });
+        if events.len() >= MAX_EVENTS && !terminal {
+            *self.dropped.lock().unwrap() += 1;
+            return;
+        }
+        events.push(TimelineEvent { at: Utc::now(), kind });
+    }
+
+    /// The events so far, oldest first
+    pub fn events(&self) -> Vec<TimelineEvent> {
+        let mut events = self.events.lock().unwrap().clone();
+        let dropped = *self.dropped.lock().unwrap();
+        if dropped > 0 {
+            events.push(TimelineEvent { at: Utc::now(), kind: TimelineEventKind::EventsDropped { count: dropped } });
+        }
+        events
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_timeline_keeps_the_ending_past_its_limit() {
+        let timeline = OperationTimeline::new();
+        for sector in 0..MAX_EVENTS as u64 + 5 {
+            timeline.record(TimelineEventKind::WriteRetried { sector, error: "I/O error".to_string() });
+        }
+        timeline.record(TimelineEventKind::Finished { success: true });
+
+        let events = timeline.events();
+        assert_eq!(events.len(), MAX_EVENTS + 2);
+        assert_eq!(events[MAX_EVENTS].kind, TimelineEventKind::Finished { success: true });
+        assert_eq!(events[MAX_EVENTS + 1].kind, TimelineEventKind::EventsDropped { count: 5 });
+
+        let json = serde_json::to_value(&events[0]).unwrap();
+        assert_eq!(json["event"], "WriteRetried");
+        assert_eq!(json["sector"], 0);
+    }
+}
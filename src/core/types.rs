@@ -2,6 +2,10 @@ use serde::{Deserialize, Serialize};
 use chrono::{DateTime, Utc};
 use uuid::Uuid;
 
+pub use crate::bad_sectors::{BadSectorLog, BadSectorRange};
+pub use crate::verification::{RegionResult, VerificationPolicy, VerificationReport};
+pub use crate::partitions::PartitionSelector;
+
 #[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub enum SanitizationStandard {
     NIST_SP_800_88_R1,  // NIST SP 800-88 Rev. 1
@@ -42,6 +46,30 @@ pub enum TargetType {
     Tape,
 }
 
+/// Which part of the target device a wipe covers
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub enum WipeScope {
+    #[default]
+    WholeDevice,
+    /// A single partition from the device's MBR or GPT
+    Partition(PartitionSelector),
+    /// An explicit range of 512-byte sectors
+    LbaRange { start_sector: u64, sector_count: u64 },
+}
+
+impl WipeScope {
+    pub fn description(&self) -> String {
+        match self {
+            WipeScope::WholeDevice => "Whole device".to_string(),
+            WipeScope::Partition(PartitionSelector::Number(number)) => format!("Partition {}", number),
+            WipeScope::Partition(PartitionSelector::Guid(guid)) => format!("Partition {}", guid),
+            WipeScope::LbaRange { start_sector, sector_count } => {
+                format!("Sectors {}-{}", start_sector, start_sector + sector_count - 1)
+            }
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct WipeConfiguration {
     pub standard: SanitizationStandard,
@@ -51,6 +79,173 @@ pub struct WipeConfiguration {
     pub certificate_required: bool,
     pub buffer_size: usize,
     pub verification_sample_rate: f64,
+    /// Which parts of the device are read back after the wipe
+    pub verification_policy: VerificationPolicy,
+    /// Algorithm used to sign erasure certificates
+    pub signature_algorithm: crate::security::SignatureAlgorithm,
+    /// PKCS#8 Ed25519 signing key; a new key is generated when unset or missing
+    pub signing_key_path: Option<String>,
+    /// No longer used: certificates record the logged-in operator (see `WipeEngine::login`)
+    #[serde(default)]
+    pub operator: String,
+    /// Refuse to wipe when a mounted volume on the target cannot be released
+    pub require_volume_release: bool,
+    /// Permanently remove a Device Configuration Overlay so factory capacity is wiped
+    pub remove_dco: bool,
+    /// Put the original Host Protected Area back after the wipe
+    pub restore_hpa: bool,
+    /// Skip persistently unwritable sectors instead of aborting the pass
+    pub tolerate_bad_sectors: bool,
+    /// Fail the wipe once more than this many sectors have been skipped
+    pub max_bad_sectors: u64,
+    /// Allow/deny rules deciding which devices may be wiped at all
+    #[serde(default)]
+    pub target_policy: crate::core::TargetPolicy,
+    /// HTTP callbacks notified when wipes start and end
+    #[serde(default)]
+    pub webhooks: Vec<crate::core::WebhookConfig>,
+    /// Probe write throughput at several block sizes when a wipe starts and
+    /// write with the fastest instead of `buffer_size`
+    #[serde(default = "default_true")]
+    pub adaptive_buffer: bool,
+    /// Cap on the write rate of each wipe in MB/s
+    #[serde(default)]
+    pub max_throughput_mbps: Option<f64>,
+    /// I/O scheduling priority of the wiping thread
+    #[serde(default)]
+    pub io_priority: crate::core::IoPriority,
+    /// Syslog / Windows Event Log targets for SIEM collection
+    #[serde(default)]
+    pub event_logging: crate::core::EventLoggingConfig,
+    /// Level and format of the engine's own log lines on stderr
+    #[serde(default)]
+    pub logging: crate::core::LoggingConfig,
+    /// Address to serve Prometheus metrics on (`metrics` feature); read at startup
+    #[serde(default)]
+    pub metrics_address: Option<String>,
+    /// Keep the seed of random passes, encrypted, in the operation history so
+    /// the written data can be regenerated and re-verified later
+    #[serde(default)]
+    pub capture_pattern_seed: bool,
+    /// Read back a sample of each pass right after it is written, bypassing
+    /// the page cache, instead of only checking the final pass
+    #[serde(default)]
+    pub verify_each_pass: bool,
+    /// Which parts of the device the per-pass read-back covers
+    #[serde(default)]
+    pub pass_verification_policy: VerificationPolicy,
+    /// Verify every wipe with `verification_policy` after its last pass, even
+    /// when the request did not ask for it; a failed verification fails the wipe
+    #[serde(default)]
+    pub auto_verify: bool,
+    /// Compliance profile front ends preselect, e.g. "nist-800-88-clear"
+    #[serde(default)]
+    pub default_profile: Option<crate::core::ComplianceProfile>,
+    /// Directory of `*.toml` wipe recipes registered at startup; `recipes` when unset
+    #[serde(default)]
+    pub recipe_dir: Option<String>,
+    /// Confirmation, second approval and abort window required before a wipe writes
+    #[serde(default)]
+    pub safety: crate::core::SafetyPolicy,
+    /// Write wipes to sparse images instead of the devices
+    #[serde(default)]
+    pub simulation: crate::core::SimulationConfig,
+    /// Maintenance windows scheduled wipes run in
+    #[serde(default)]
+    pub schedule: crate::core::ScheduleConfig,
+    /// Language of console messages and printed certificates, e.g. "es-ES";
+    /// the system's locale when unset (see `crate::i18n`)
+    #[serde(default)]
+    pub locale: Option<String>,
+    /// Logo, address, extra fields and disclaimer printed on PDF certificates
+    #[serde(default)]
+    pub certificate_template: Option<crate::security::CertificateTemplate>,
+    /// Temperatures at which wipes slow down and pause
+    #[serde(default)]
+    pub thermal: crate::core::ThermalPolicy,
+    /// SMART wear above which preflight recommends destroying the drive
+    #[serde(default)]
+    pub wear_thresholds: crate::core::WearThresholds,
+    /// Self-test or surface scan run after the wipe, for drives that are resold
+    #[serde(default)]
+    pub functional_test: Option<crate::security::FunctionalTestKind>,
+    /// Fresh partition table, partition and filesystem written after whole-device wipes
+    #[serde(default)]
+    pub relabel: Option<crate::relabel::RelabelOptions>,
+    /// Compressed image of the target taken before the first pass; no image unless set
+    #[serde(default)]
+    pub imaging: Option<crate::imaging::ImagingConfig>,
+    /// Where issued certificates and reports are copied automatically
+    #[serde(default)]
+    pub uploads: Vec<crate::core::UploadDestination>,
+    /// Directory every issued certificate and report is also copied into
+    #[serde(default)]
+    pub backup_reports: Option<crate::core::ReportBackupConfig>,
+    /// Mail sent when operations finish, one per operation or one per batch
+    #[serde(default)]
+    pub email: Option<crate::core::EmailConfig>,
+}
+
+fn default_true() -> bool { true }
+
+impl Default for WipeConfiguration {
+    fn default() -> Self {
+        Self {
+            standard: SanitizationStandard::NIST_SP_800_88_R1,
+            target_type: TargetType::HDD,
+            verify_writes: true,
+            generate_report: true,
+            certificate_required: true,
+            buffer_size: 16 * 1024 * 1024,
+            verification_sample_rate: 0.1,
+            verification_policy: VerificationPolicy::default(),
+            signature_algorithm: crate::security::SignatureAlgorithm::Ed25519,
+            signing_key_path: None,
+            operator: String::new(),
+            require_volume_release: true,
+            remove_dco: false,
+            restore_hpa: false,
+            tolerate_bad_sectors: false,
+            max_bad_sectors: 0,
+            target_policy: Default::default(),
+            webhooks: Vec::new(),
+            adaptive_buffer: true,
+            max_throughput_mbps: None,
+            io_priority: Default::default(),
+            event_logging: Default::default(),
+            logging: Default::default(),
+            metrics_address: None,
+            capture_pattern_seed: false,
+            verify_each_pass: false,
+            pass_verification_policy: VerificationPolicy::default(),
+            auto_verify: false,
+            default_profile: None,
+            recipe_dir: None,
+            safety: Default::default(),
+            simulation: Default::default(),
+            schedule: Default::default(),
+            locale: None,
+            certificate_template: None,
+            thermal: Default::default(),
+            wear_thresholds: Default::default(),
+            functional_test: None,
+            relabel: None,
+            imaging: None,
+            uploads: Vec::new(),
+            backup_reports: None,
+            email: None,
+        }
+    }
+}
+
+impl WipeConfiguration {
+    /// Rate limit and I/O priority a new wipe starts with
+    pub fn operation_limits(&self) -> crate::core::OperationLimits {
+        crate::core::OperationLimits {
+            max_throughput_mbps: self.max_throughput_mbps,
+            io_priority: self.io_priority,
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -58,11 +253,58 @@ pub struct WipeRequest {
     pub id: Uuid,
     pub target_path: String,
     pub target_type: TargetType,
+    #[serde(default)]
+    pub scope: WipeScope,
     pub standard: SanitizationStandard,
     pub passes: u32,
     pub verify_erasure: bool,
     pub generate_certificate: bool,
     pub timestamp: DateTime<Utc>,
+    /// Authenticated operator who requested the wipe
+    #[serde(default)]
+    pub operator: Option<String>,
+    /// Compliance profile the wipe follows; overrides `standard` and `passes`
+    #[serde(default)]
+    pub profile: Option<crate::core::ComplianceProfile>,
+    /// User-defined recipe the wipe follows; takes precedence over `profile`
+    #[serde(default)]
+    pub recipe: Option<crate::core::WipeRecipe>,
+    /// Phrase typed by the operator when the safety policy asks for one
+    #[serde(default)]
+    pub confirmation: Option<String>,
+    /// Run the wipe against a sparse image of the target's size
+    #[serde(default)]
+    pub simulate: bool,
+    /// Leave out the TRIM / Deallocate that follows an overwrite of SSD and flash media
+    #[serde(default)]
+    pub skip_trim: bool,
+    /// Passes of an interrupted wipe already on the device, which a resumed wipe skips
+    #[serde(default)]
+    pub resume: Option<crate::core::ResumePoint>,
+    /// ATA security state to leave the drive in after a whole-device wipe
+    #[serde(default)]
+    pub security_state: Option<crate::security_state::SecurityStateAction>,
+    /// Order among due scheduled wipes, and whether the wipe may preempt others
+    #[serde(default)]
+    pub priority: crate::core::WipePriority,
+}
+
+impl WipeRequest {
+    /// The passes to run: the recipe's, else the profile's, else the standard's
+    pub fn wipe_spec(&self) -> crate::core::WipeSpec {
+        if let Some(recipe) = &self.recipe {
+            recipe.spec.clone()
+        } else if let Some(profile) = self.profile {
+            profile.spec()
+        } else {
+            self.standard.into()
+        }
+    }
+
+    /// Whether the wipe asks the drive to erase itself rather than only overwriting it
+    pub fn uses_firmware_erase(&self) -> bool {
+        self.wipe_spec().hardware != crate::core::HardwarePreference::OverwriteOnly
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -101,6 +343,42 @@ pub struct WipeProgress {
     pub percentage: f64,
     pub current_operation: String,
     pub estimated_completion: Option<DateTime<Utc>>,
+    /// Confidence bounds around `estimated_completion`
+    #[serde(default)]
+    pub estimated_completion_earliest: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub estimated_completion_latest: Option<DateTime<Utc>>,
+    /// Reading the device back after the last pass
+    #[serde(default)]
+    pub verifying: bool,
+}
+
+impl WipeProgress {
+    /// Progress update synthesized while a hardware erase runs inside the drive
+    pub fn from_erase_progress(
+        request_id: Uuid,
+        total_sectors: u64,
+        operation: &str,
+        progress: &crate::erase_progress::EraseProgress,
+    ) -> Self {
+        // The drive gives a single figure, so the bounds coincide with it
+        let estimated_completion = progress.remaining
+            .and_then(|remaining| chrono::Duration::from_std(remaining).ok())
+            .map(|remaining| Utc::now() + remaining);
+        Self {
+            request_id,
+            current_pass: 1,
+            total_passes: 1,
+            sectors_processed: (total_sectors as f64 * progress.percentage / 100.0) as u64,
+            total_sectors,
+            percentage: progress.percentage,
+            current_operation: operation.to_string(),
+            estimated_completion,
+            estimated_completion_earliest: estimated_completion,
+            estimated_completion_latest: estimated_completion,
+            verifying: false,
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -125,6 +403,155 @@ pub struct WipeResult {
     pub error_message: Option<String>,
     pub drive_geometry: DriveGeometry,
     pub security_features: SecurityFeatures,
+    /// HPA/DCO handling performed around the wipe; absent for non-ATA targets
+    #[serde(default)]
+    pub hidden_areas: Option<HiddenAreaReport>,
+    /// Sectors skipped by a fault-tolerant overwrite
+    #[serde(default)]
+    pub bad_sectors: BadSectorLog,
+    /// Detailed read-back results; absent when verification was not requested
+    #[serde(default)]
+    pub verification_report: Option<VerificationReport>,
+    /// Patterns and erase techniques actually applied, in order
+    #[serde(default)]
+    pub patterns_used: Vec<String>,
+    /// Write block size chosen by the throughput probe; absent when the configured size was used
+    #[serde(default)]
+    pub buffer_tuning: Option<BufferTuning>,
+    /// Seed of the random passes; kept in memory only, the engine stores it sealed
+    #[serde(skip)]
+    pub pattern_seed: Option<crate::pattern_stream::PatternSeed>,
+    /// Read-back results of each pass when `verify_each_pass` is enabled
+    #[serde(default)]
+    pub pass_verifications: Vec<PassVerification>,
+    /// The passes were written to a sparse image, not the device
+    #[serde(default)]
+    pub simulated: bool,
+    /// Snapshots of the device before the first pass and after verification
+    #[serde(default)]
+    pub evidence: Option<crate::security::ErasureEvidence>,
+    /// Drive temperatures read while the passes ran
+    #[serde(default)]
+    pub temperature_samples: Vec<crate::core::TemperatureSample>,
+    /// Achieved write rate against earlier wipes of the drive model
+    #[serde(default)]
+    pub throughput: Option<crate::core::ThroughputCheck>,
+    /// Whether the drive still worked after the wipe, when a functional test was configured
+    #[serde(default)]
+    pub functional_test: Option<crate::security::FunctionalTest>,
+    /// Image of the target taken before the wipe, when imaging was configured
+    #[serde(default)]
+    pub forensic_image: Option<crate::security::ForensicImage>,
+    /// What each pass wrote, for verifying the device again later
+    #[serde(default)]
+    pub written_passes: Option<WrittenPasses>,
+    /// What was done to the drive after the wipe, in order
+    #[serde(default)]
+    pub post_processing: Vec<PostProcessingStep>,
+}
+
+/// An action taken on the drive once it was wiped, such as writing a new partition table
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PostProcessingStep {
+    /// e.g. "Write GPT" or "Format exFAT"
+    pub step: String,
+    pub success: bool,
+    pub detail: String,
+}
+
+/// The area a wipe overwrote and the pattern of each pass, in order. Random
+/// pass N wrote stream N of the wipe's pattern seed, so with the sealed seed
+/// the content of any pass can be regenerated at any offset.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WrittenPasses {
+    pub start_sector: u64,
+    pub sector_count: u64,
+    pub patterns: Vec<crate::core::OverwritePattern>,
+    /// Fill patterns restart at every write block
+    pub block_size: usize,
+    /// The range was deallocated after the last pass, which no longer reads back
+    pub deallocated: bool,
+}
+
+impl WrittenPasses {
+    /// What the last pass left on the device; random passes need the wipe's seed
+    pub fn final_content(&self, seed: Option<&crate::pattern_stream::PatternSeed>) -> Option<crate::verification::ExpectedContent> {
+        use crate::core::OverwritePattern;
+        use crate::verification::ExpectedContent;
+
+        match self.patterns.last()? {
+            _ if self.deallocated => None,
+            OverwritePattern::Fill(bytes) if bytes.is_empty() => None,
+            OverwritePattern::Fill(bytes) => {
+                let repeat = self.block_size.div_ceil(bytes.len());
+                Some(ExpectedContent::Pattern(bytes.repeat(repeat)[..self.block_size.max(1)].to_vec()))
+            }
+            OverwritePattern::Random => {
+                let pass = self.patterns.len() as u64;
+                seed.map(|seed| ExpectedContent::Stream(crate::pattern_stream::PatternStream::new(seed, pass)))
+            }
+        }
+    }
+}
+
+/// Read-back of one overwrite pass, taken before the next pass ran
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PassVerification {
+    pub pass: u32,
+    pub pattern: String,
+    pub bytes_verified: u64,
+    pub mismatched_bytes: u64,
+    pub failed_sectors: Vec<u64>,
+    pub duration_seconds: f64,
+    pub passed: bool,
+}
+
+impl PassVerification {
+    pub fn from_report(pass: u32, pattern: String, report: &VerificationReport) -> Self {
+        Self {
+            pass,
+            pattern,
+            bytes_verified: report.bytes_verified,
+            mismatched_bytes: report.regions.iter().map(|region| region.mismatched_bytes).sum(),
+            failed_sectors: report.failed_sectors.clone(),
+            duration_seconds: report.duration_seconds,
+            passed: report.passed,
+        }
+    }
+}
+
+/// Write throughput measured at one block size
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BlockSizeProbe {
+    pub block_size: usize,
+    pub throughput_mbps: f64,
+}
+
+/// Outcome of the block size probe run at the start of a wipe
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BufferTuning {
+    pub block_size: usize,
+    pub throughput_mbps: f64,
+    pub probes: Vec<BlockSizeProbe>,
+}
+
+/// Sector counts reported by the drive before, during and after the wipe
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct HiddenAreaReport {
+    /// User-addressable sectors when the wipe started
+    pub original_user_sectors: u64,
+    /// Sectors reported by READ NATIVE MAX ADDRESS (includes HPA)
+    pub native_sectors: u64,
+    /// Factory capacity reported by DEVICE CONFIGURATION IDENTIFY (includes DCO)
+    pub dco_factory_sectors: Option<u64>,
+    /// User-addressable sectors while the wipe ran
+    pub expanded_user_sectors: u64,
+    /// User-addressable sectors once the operation finished
+    pub final_user_sectors: u64,
+    pub hpa_removed: bool,
+    pub dco_restored: bool,
+    pub hpa_restored: bool,
+    pub notes: Vec<String>,
 }
 
 #[derive(Debug, Clone)]
@@ -145,9 +572,73 @@ pub enum WipeErrorCode {
     SecurityLocked,
     InvalidPattern,
     InsufficientPrivileges,
+    VolumeInUse,
+    OperationInProgress,
+    TooManyBadSectors,
+    InvalidTarget,
+    /// The drive or platform cannot run the requested method
+    Unsupported,
+    PolicyDenied,
+    NotAuthenticated,
+    Cancelled,
     UnknownError,
 }
 
+/// Why an operation failed, as reported to front ends. Coarser and more
+/// stable than `WipeErrorCode`, so user interfaces can branch on it; the
+/// message stays for display.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SafeWipeErrorCode {
+    DeviceNotFound,
+    /// The process lacks the privileges to open or command the device
+    PermissionDenied,
+    NotAuthenticated,
+    /// The drive's security state (locked or frozen) refuses the command
+    SecurityFrozen,
+    /// The device is mounted or in use by another operation
+    Busy,
+    VerificationFailed,
+    Unsupported,
+    Cancelled,
+    /// Refused by the target policy, a role or a missing approval
+    PolicyDenied,
+    InvalidArgument,
+    /// A job, operation or record that does not exist
+    NotFound,
+    /// The device failed I/O, e.g. too many bad sectors
+    HardwareFailure,
+    Internal,
+}
+
+impl From<&WipeErrorCode> for SafeWipeErrorCode {
+    fn from(code: &WipeErrorCode) -> Self {
+        match code {
+            WipeErrorCode::DriveNotFound => SafeWipeErrorCode::DeviceNotFound,
+            WipeErrorCode::AccessDenied | WipeErrorCode::InsufficientPrivileges => SafeWipeErrorCode::PermissionDenied,
+            WipeErrorCode::NotAuthenticated => SafeWipeErrorCode::NotAuthenticated,
+            // A frozen drive rejects SET MAX ADDRESS and DCO commands as well
+            WipeErrorCode::SecurityLocked | WipeErrorCode::HPAUnlockFailed | WipeErrorCode::DCOUnlockFailed => {
+                SafeWipeErrorCode::SecurityFrozen
+            }
+            WipeErrorCode::VolumeInUse | WipeErrorCode::OperationInProgress => SafeWipeErrorCode::Busy,
+            WipeErrorCode::VerificationFailed => SafeWipeErrorCode::VerificationFailed,
+            WipeErrorCode::Unsupported => SafeWipeErrorCode::Unsupported,
+            WipeErrorCode::Cancelled => SafeWipeErrorCode::Cancelled,
+            WipeErrorCode::PolicyDenied => SafeWipeErrorCode::PolicyDenied,
+            WipeErrorCode::InvalidPattern | WipeErrorCode::InvalidTarget => SafeWipeErrorCode::InvalidArgument,
+            WipeErrorCode::HardwareError | WipeErrorCode::TooManyBadSectors => SafeWipeErrorCode::HardwareFailure,
+            WipeErrorCode::UnknownError => SafeWipeErrorCode::Internal,
+        }
+    }
+}
+
+impl WipeError {
+    /// The front-end error code for this error
+    pub fn error_code(&self) -> SafeWipeErrorCode {
+        SafeWipeErrorCode::from(&self.code)
+    }
+}
+
 impl std::fmt::Display for WipeError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "{:?}: {}", self.code, self.message)
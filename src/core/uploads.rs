@@ -0,0 +1,377 @@
+//! Automatic upload of certificates and reports
+//!
+//! A standalone wipe station should not need anyone to collect its files by
+//! hand. Every file issued for a wipe (the PDF certificate, the signed JSON
+//! certificate and the audit report) is copied to each destination in
+//! `WipeConfiguration::uploads`: a directory such as a mounted or UNC SMB
+//! share, an S3-compatible bucket (feature `s3`), or an HTTPS endpoint that
+//! receives one POST per file (feature `webhooks`).
+//!
+//! Uploads run on a background thread and failed ones are retried with
+//! exponential backoff. The state of each file and destination is kept in
+//! the operation record, so uploads still pending when the tool exits are
+//! picked up at the next start, and an upload that used up its attempts
+//! stays listed as failed until it is retried.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+use tracing::{error, info, warn};
+use uuid::Uuid;
+
+use crate::core::{OperationHistory, WipeResult2};
+use crate::s3::S3Location;
+
+/// Longest wait between two attempts of one upload
+const MAX_RETRY_DELAY: Duration = Duration::from_secs(15 * 60);
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct UploadDestination {
+    /// Identifies the destination in operation records, e.g. "head-office-share"
+    pub name: String,
+    pub target: UploadTarget,
+    #[serde(default = "default_max_attempts")]
+    pub max_attempts: u32,
+}
+
+fn default_max_attempts() -> u32 { 10 }
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum UploadTarget {
+    /// A local directory, or a mounted or UNC path of an SMB share
+    Directory { path: PathBuf },
+    /// An S3 or S3-compatible bucket; needs a build with the "s3" feature
+    S3(S3Location),
+    /// POST of each file, named in `X-HDD-Tool-File` and signed like webhook
+    /// payloads when `secret_env` is set; needs the "webhooks" feature
+    Https {
+        url: String,
+        #[serde(default)]
+        secret_env: Option<String>,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum UploadState {
+    Pending,
+    Delivered,
+    /// Used up its attempts; `WipeEngine::retry_uploads` queues it again
+    Failed,
+}
+
+/// Delivery of one file to one destination, as kept in the operation record
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct UploadStatus {
+    pub destination: String,
+    /// Local path of the file
+    pub file: PathBuf,
+    pub state: UploadState,
+    pub attempts: u32,
+    #[serde(default)]
+    pub last_error: Option<String>,
+    /// Where the file was written, once delivered
+    #[serde(default)]
+    pub location: Option<String>,
+    pub updated_at: DateTime<Utc>,
+}
+
+struct UploadJob {
+    operation_id: Uuid,
+    destination: String,
+    file: PathBuf,
+    attempts: u32,
+    due: Instant,
+}
+
+/// Uploads files to the configured destinations on a worker thread,
+/// recording every attempt in the operation history
+pub struct UploadQueue {
+    history: Arc<Mutex<OperationHistory>>,
+    destinations: Arc<Mutex<Vec<UploadDestination>>>,
+    sender: Mutex<mpsc::Sender<UploadJob>>,
+}
+
+impl UploadQueue {
+    pub fn new(history: Arc<Mutex<OperationHistory>>) -> Self {
+        Self::with_retry_delay(history, Duration::from_secs(5))
+    }
+
+    /// Queue whose n-th retry of an upload waits `retry_delay * 2^(n-1)`
+    pub fn with_retry_delay(history: Arc<Mutex<OperationHistory>>, retry_delay: Duration) -> Self {
+        let destinations = Arc::new(Mutex::new(Vec::<UploadDestination>::new()));
+        let (sender, receiver) = mpsc::channel::<UploadJob>();
+
+        let worker_history = history.clone();
+        let worker_destinations = destinations.clone();
+        thread::spawn(move || {
+            let mut queue: Vec<UploadJob> = Vec::new();
+            loop {
+                let next_due = queue.iter().map(|job| job.due).min();
+                let received = match next_due {
+                    Some(due) => receiver.recv_timeout(due.saturating_duration_since(Instant::now())),
+                    None => receiver.recv().map_err(|_| RecvTimeoutError::Disconnected),
+                };
+                match received {
+                    Ok(job) => queue.push(job),
+                    // Whatever is still queued stays pending in the history for the next start
+                    Err(RecvTimeoutError::Disconnected) => break,
+                    Err(RecvTimeoutError::Timeout) => {}
+                }
+
+                let now = Instant::now();
+                let (due, waiting): (Vec<_>, Vec<_>) = queue.drain(..).partition(|job| job.due <= now);
+                queue = waiting;
+                for mut job in due {
+                    job.attempts += 1;
+                    let destination = worker_destinations.lock().unwrap().iter().find(|d| d.name == job.destination).cloned();
+                    let (outcome, max_attempts) = match &destination {
+                        Some(destination) => (upload_file(&destination.target, &job.file), destination.max_attempts.max(1)),
+                        None => (Err("Destination is no longer configured".to_string()), job.attempts),
+                    };
+
+                    let mut status = UploadStatus {
+                        destination: job.destination.clone(),
+                        file: job.file.clone(),
+                        state: UploadState::Pending,
+                        attempts: job.attempts,
+                        last_error: None,
+                        location: None,
+                        updated_at: Utc::now(),
+                    };
+                    let operation_id = job.operation_id;
+                    match outcome {
+                        Ok(location) => {
+                            info!(file = %job.file.display(), location = %location, "Uploaded");
+                            status.state = UploadState::Delivered;
+                            status.location = Some(location);
+                        }
+                        Err(error) if job.attempts >= max_attempts => {
+                            error!(
+                                file = %job.file.display(),
+                                destination = %job.destination,
+                                attempts = job.attempts,
+                                error = %error,
+                                "Gave up uploading"
+                            );
+                            status.state = UploadState::Failed;
+                            status.last_error = Some(error);
+                        }
+                        Err(error) => {
+                            let delay = retry_delay.saturating_mul(1 << (job.attempts - 1).min(16)).min(MAX_RETRY_DELAY);
+                            warn!(
+                                file = %job.file.display(),
+                                destination = %job.destination,
+                                error = %error,
+                                retry_in = ?delay,
+                                "Upload failed, retrying"
+                            );
+                            status.last_error = Some(error);
+                            job.due = Instant::now() + delay;
+                            queue.push(job);
+                        }
+                    }
+                    if let Err(e) = worker_history.lock().unwrap().record_upload(operation_id, &status) {
+                        warn!(error = %e, "Could not record upload status");
+                    }
+                }
+            }
+        });
+
+        Self { history, destinations, sender: Mutex::new(sender) }
+    }
+
+    /// Replace the destinations; queued uploads to a removed destination fail
+    pub fn configure(&self, destinations: &[UploadDestination]) {
+        *self.destinations.lock().unwrap() = destinations.to_vec();
+    }
+
+    /// Upload `files` of `operation_id` to every configured destination
+    pub fn enqueue(&self, operation_id: Uuid, files: &[PathBuf]) -> WipeResult2<()> {
+        let destinations: Vec<String> = self.destinations.lock().unwrap().iter().map(|d| d.name.clone()).collect();
+        for destination in &destinations {
+            for file in files {
+                self.queue(operation_id, UploadStatus {
+                    destination: destination.clone(),
+                    file: file.clone(),
+                    state: UploadState::Pending,
+                    attempts: 0,
+                    last_error: None,
+                    location: None,
+                    updated_at: Utc::now(),
+                })?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Queue the uploads a previous run left pending
+    pub fn resume_pending(&self) -> WipeResult2<usize> {
+        let pending = self.history.lock().unwrap().pending_uploads();
+        for (operation_id, status) in &pending {
+            self.queue(*operation_id, status.clone())?;
+        }
+        Ok(pending.len())
+    }
+
+    /// Queue the failed uploads of `operation_id` again, with fresh attempts
+    pub fn retry_failed(&self, operation_id: Uuid) -> WipeResult2<usize> {
+        let failed: Vec<UploadStatus> = self
+            .history
+            .lock()
+            .unwrap()
+            .get(operation_id)
+            .map(|record| record.uploads.iter().filter(|u| u.state == UploadState::Failed).cloned().collect())
+            .unwrap_or_default();
+        for status in &failed {
+            self.queue(operation_id, UploadStatus {
+                state: UploadState::Pending,
+                attempts: 0,
+                updated_at: Utc::now(),
+                ..status.clone()
+            })?;
+        }
+        Ok(failed.len())
+    }
+
+    fn queue(&self, operation_id: Uuid, status: UploadStatus) -> WipeResult2<()> {
+        self.history.lock().unwrap().record_upload(operation_id, &status)?;
+        let _ = self.sender.lock().unwrap().send(UploadJob {
+            operation_id,
+            destination: status.destination,
+            file: status.file,
+            attempts: status.attempts,
+            due: Instant::now(),
+        });
+        Ok(())
+    }
+}
+
+/// Copy `file` to `target`; returns where it was written
+pub fn upload_file(target: &UploadTarget, file: &Path) -> Result<String, String> {
+    let name = file
+        .file_name()
+        .and_then(|name| name.to_str())
+        .ok_or_else(|| format!("{} has no file name", file.display()))?;
+
+    match target {
+        UploadTarget::Directory { path } => {
+            // Copy under a temporary name so collectors never pick up half a file
+            let destination = path.join(name);
+            let partial = path.join(format!(".{}.partial", name));
+            fs::create_dir_all(path)
+                .and_then(|_| fs::copy(file, &partial))
+                .and_then(|_| fs::rename(&partial, &destination))
+                .map_err(|e| e.to_string())?;
+            Ok(destination.display().to_string())
+        }
+        #[cfg(feature = "s3")]
+        UploadTarget::S3(location) => {
+            let body = fs::read(file).map_err(|e| e.to_string())?;
+            let key = location.key(name);
+            crate::s3::Bucket::connect(location)
+                .and_then(|bucket| bucket.put_object(&key, body))
+                .map_err(|e| e.to_string())?;
+            Ok(location.url(&key))
+        }
+        #[cfg(not(feature = "s3"))]
+        UploadTarget::S3(_) => Err("This build has no S3 support (feature \"s3\")".to_string()),
+        #[cfg(feature = "webhooks")]
+        UploadTarget::Https { url, secret_env } => {
+            let body = fs::read(file).map_err(|e| e.to_string())?;
+            let content_type = match file.extension().and_then(|e| e.to_str()) {
+                Some("pdf") => "application/pdf",
+                Some("json") => "application/json",
+                _ => "application/octet-stream",
+            };
+            let client = reqwest::blocking::Client::builder()
+                .timeout(Duration::from_secs(120))
+                .build()
+                .map_err(|e| e.to_string())?;
+            let mut request = client
+                .post(url)
+                .header("content-type", content_type)
+                .header("X-HDD-Tool-File", name);
+            if let Some(var) = secret_env {
+                let secret = std::env::var(var).map_err(|_| format!("{} is not set", var))?;
+                request = request.header(crate::core::SIGNATURE_HEADER, crate::core::sign_payload(secret.as_bytes(), &body));
+            }
+            let response = request.body(body).send().map_err(|e| e.to_string())?;
+            if !response.status().is_success() {
+                return Err(format!("HTTP {}", response.status()));
+            }
+            Ok(url.clone())
+        }
+        #[cfg(not(feature = "webhooks"))]
+        UploadTarget::Https { .. } => Err("This build has no HTTP client (feature \"webhooks\")".to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::WipeRequest;
+
+    fn wait_for(history: &Arc<Mutex<OperationHistory>>, operation_id: Uuid, done: impl Fn(&[UploadStatus]) -> bool) -> Vec<UploadStatus> {
+        let deadline = Instant::now() + Duration::from_secs(5);
+        loop {
+            let uploads = history.lock().unwrap().get(operation_id).unwrap().uploads.clone();
+            if done(&uploads) || Instant::now() > deadline {
+                return uploads;
+            }
+            thread::sleep(Duration::from_millis(20));
+        }
+    }
+
+    #[test]
+    fn test_uploads_are_delivered_or_fail_in_the_operation_record() {
+        let dir = tempfile::tempdir().unwrap();
+        let report = dir.path().join("certificate_S1_20250101_120000.json");
+        fs::write(&report, b"{\"certificate_id\":\"c1\"}").unwrap();
+        // A directory cannot be created below a regular file
+        let blocked = dir.path().join("not-a-directory");
+        fs::write(&blocked, b"").unwrap();
+
+        let history = Arc::new(Mutex::new(OperationHistory::open(dir.path().join("operations.jsonl")).unwrap()));
+        let request: WipeRequest = serde_json::from_value(serde_json::json!({
+            "id": Uuid::new_v4(), "target_path": "/dev/sdx", "target_type": "HDD",
+            "standard": "NIST_SP_800_88_R1", "passes": 1, "verify_erasure": false,
+            "generate_certificate": true, "timestamp": Utc::now(),
+        }))
+        .unwrap();
+        history.lock().unwrap().record_started(&request).unwrap();
+
+        let queue = UploadQueue::with_retry_delay(history.clone(), Duration::from_millis(10));
+        queue.configure(&[
+            UploadDestination {
+                name: "share".to_string(),
+                target: UploadTarget::Directory { path: dir.path().join("share") },
+                max_attempts: 3,
+            },
+            UploadDestination {
+                name: "broken".to_string(),
+                target: UploadTarget::Directory { path: blocked.join("share") },
+                max_attempts: 2,
+            },
+        ]);
+        queue.enqueue(request.id, std::slice::from_ref(&report)).unwrap();
+
+        let settled = |uploads: &[UploadStatus]| uploads.iter().all(|u| u.state != UploadState::Pending);
+        let uploads = wait_for(&history, request.id, settled);
+        assert_eq!(uploads.len(), 2);
+        assert_eq!((uploads[0].state, uploads[0].attempts), (UploadState::Delivered, 1));
+        assert_eq!(fs::read(dir.path().join("share").join(report.file_name().unwrap())).unwrap(), fs::read(&report).unwrap());
+        assert_eq!((uploads[1].state, uploads[1].attempts), (UploadState::Failed, 2));
+        assert!(uploads[1].last_error.is_some());
+
+        // Once the share is reachable a retry delivers the file
+        fs::remove_file(&blocked).unwrap();
+        assert_eq!(queue.retry_failed(request.id).unwrap(), 1);
+        let uploads = wait_for(&history, request.id, |uploads| uploads.iter().all(|u| u.state == UploadState::Delivered));
+        assert_eq!((uploads[1].state, uploads[1].attempts), (UploadState::Delivered, 1));
+    }
+}
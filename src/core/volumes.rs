@@ -0,0 +1,167 @@
+use std::process::Command;
+use tracing::{info, warn};
+
+use crate::core::{mounted_volumes, MountedVolume};
+
+#[cfg(windows)]
+use windows::{
+    core::PCWSTR,
+    Win32::{
+        Foundation::{CloseHandle, HANDLE, GENERIC_READ, GENERIC_WRITE},
+        Storage::FileSystem::{CreateFileW, FILE_ATTRIBUTE_NORMAL, FILE_SHARE_READ, FILE_SHARE_WRITE, OPEN_EXISTING},
+        System::Ioctl::{FSCTL_DISMOUNT_VOLUME, FSCTL_LOCK_VOLUME},
+        System::IO::DeviceIoControl,
+    },
+};
+
+/// Volumes taken away from the operating system for the duration of a raw wipe.
+/// Nothing is remounted afterwards: the filesystems no longer exist once the wipe runs.
+pub struct VolumeRelease {
+    pub released: Vec<MountedVolume>,
+    pub failed: Vec<(MountedVolume, String)>,
+    pub disk_offline: bool,
+    /// Locked volume handles; Windows keeps the lock only while these stay open
+    #[cfg(windows)]
+    locks: Vec<HANDLE>,
+}
+
+impl VolumeRelease {
+    pub fn all_released(&self) -> bool {
+        self.failed.is_empty()
+    }
+
+    /// Human readable list of volumes that could not be released
+    pub fn failure_summary(&self) -> String {
+        self.failed
+            .iter()
+            .map(|(volume, reason)| format!("{} ({})", volume.mount_point, reason))
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+}
+
+#[cfg(windows)]
+impl Drop for VolumeRelease {
+    fn drop(&mut self) {
+        for handle in self.locks.drain(..) {
+            unsafe {
+                let _ = CloseHandle(handle);
+            }
+        }
+    }
+}
+
+/// Unmount (Linux) or lock and dismount (Windows) every volume on the device,
+/// then take the disk offline so the OS does not mount it again mid-wipe.
+pub fn release_volumes(device_path: &str) -> VolumeRelease {
+    let mut release = VolumeRelease {
+        released: Vec::new(),
+        failed: Vec::new(),
+        disk_offline: false,
+        #[cfg(windows)]
+        locks: Vec::new(),
+    };
+
+    let mut volumes = mounted_volumes(device_path);
+    // Unmount nested mount points before their parents
+    volumes.sort_by_key(|volume| std::cmp::Reverse(volume.mount_point.len()));
+
+    for volume in volumes {
+        info!(mount_point = %volume.mount_point, source = %volume.source, "Releasing volume");
+        match release_volume(&mut release, &volume) {
+            Ok(()) => release.released.push(volume),
+            Err(reason) => {
+                warn!(mount_point = %volume.mount_point, reason = %reason, "Could not release volume");
+                release.failed.push((volume, reason));
+            }
+        }
+    }
+
+    if release.all_released() {
+        release.disk_offline = take_offline(device_path);
+    }
+
+    release
+}
+
+#[cfg(target_os = "linux")]
+fn release_volume(_release: &mut VolumeRelease, volume: &MountedVolume) -> Result<(), String> {
+    let output = Command::new("umount")
+        .arg(&volume.mount_point)
+        .output()
+        .map_err(|e| e.to_string())?;
+
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(String::from_utf8_lossy(&output.stderr).trim().to_string())
+    }
+}
+
+#[cfg(windows)]
+fn release_volume(release: &mut VolumeRelease, volume: &MountedVolume) -> Result<(), String> {
+    // \\.\E: from a mount point such as E:\
+    let volume_path = format!(r"\\.\{}", volume.mount_point.trim_end_matches('\\'));
+    let volume_path_wide: Vec<u16> = volume_path.encode_utf16().chain(std::iter::once(0)).collect();
+
+    unsafe {
+        let handle = CreateFileW(
+            PCWSTR::from_raw(volume_path_wide.as_ptr()),
+            GENERIC_READ.0 | GENERIC_WRITE.0,
+            FILE_SHARE_READ | FILE_SHARE_WRITE,
+            None,
+            OPEN_EXISTING,
+            FILE_ATTRIBUTE_NORMAL,
+            None,
+        ).map_err(|e| format!("Failed to open volume: {}", e))?;
+
+        let mut bytes_returned = 0u32;
+        if let Err(e) = DeviceIoControl(handle, FSCTL_LOCK_VOLUME, None, 0, None, 0, Some(&mut bytes_returned), None) {
+            let _ = CloseHandle(handle);
+            return Err(format!("Volume is in use and cannot be locked: {}", e));
+        }
+
+        if let Err(e) = DeviceIoControl(handle, FSCTL_DISMOUNT_VOLUME, None, 0, None, 0, Some(&mut bytes_returned), None) {
+            let _ = CloseHandle(handle);
+            return Err(format!("Failed to dismount volume: {}", e));
+        }
+
+        release.locks.push(handle);
+    }
+
+    Ok(())
+}
+
+#[cfg(not(any(target_os = "linux", windows)))]
+fn release_volume(_release: &mut VolumeRelease, _volume: &MountedVolume) -> Result<(), String> {
+    Err("Volume release is not supported on this platform".to_string())
+}
+
+#[cfg(windows)]
+fn take_offline(device_path: &str) -> bool {
+    let Some(disk_number) = device_path.trim_start_matches(r"\\.\").strip_prefix("PhysicalDrive") else {
+        return false;
+    };
+
+    let script = format!("Set-Disk -Number {} -IsOffline $true", disk_number);
+    match Command::new("powershell").args(&["-NoProfile", "-Command", &script]).status() {
+        Ok(status) if status.success() => {
+            info!(disk = disk_number, "Disk taken offline");
+            true
+        }
+        _ => {
+            warn!(disk = disk_number, "Could not take disk offline");
+            false
+        }
+    }
+}
+
+#[cfg(not(windows))]
+fn take_offline(device_path: &str) -> bool {
+    // Linux has no offline state that still permits raw writes; flush the
+    // page cache instead so no stale buffers are written back over the wipe
+    let _ = Command::new("blockdev")
+        .args(["--flushbufs", device_path])
+        .status();
+    false
+}
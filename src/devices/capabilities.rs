@@ -0,0 +1,170 @@
+//! What a drive offers for sanitization, for the GUI's drive details
+//!
+//! Everything is read from the drive itself: ATA IDENTIFY DEVICE (security
+//! state, sanitize feature set, TRIM, HPA), SCSI REPORT SUPPORTED OPERATION
+//! CODES for SANITIZE, TCG discovery for crypto erase, the block layer for
+//! discard and zones, the partitions for encrypted volumes, and SMART. Each probe is
+//! independent; one that cannot reach the drive leaves its field unknown
+//! rather than failing the whole detection. Detection issues several
+//! commands and reads the start of every partition, so front ends run it
+//! off the UI thread and keep the result.
+
+use chrono::{DateTime, Local};
+
+use crate::ata_commands::AtaInterface;
+use crate::encryption::{detect_encryption, EncryptionStatus};
+use crate::freeze::FreezeStatus;
+use crate::scsi_commands::{SanitizeAction, ScsiInterface};
+use crate::smart::{read_device_health, DeviceHealth};
+use crate::zoned::{detect_zoned_model, ZonedModel};
+
+/// ATA Security feature set state, from IDENTIFY DEVICE word 128
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AtaSecurity {
+    pub enabled: bool,
+    pub locked: bool,
+    pub frozen: bool,
+    pub enhanced_erase: bool,
+}
+
+/// Sanitize operations the drive accepts
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct SanitizeSupport {
+    pub block_erase: bool,
+    pub crypto_erase: bool,
+    pub overwrite: bool,
+}
+
+impl SanitizeSupport {
+    pub fn any(&self) -> bool {
+        self.block_erase || self.crypto_erase || self.overwrite
+    }
+
+    pub fn description(&self) -> String {
+        let actions: Vec<&str> = [
+            (self.block_erase, "block erase"),
+            (self.crypto_erase, "crypto erase"),
+            (self.overwrite, "overwrite"),
+        ]
+        .iter()
+        .filter(|(supported, _)| *supported)
+        .map(|(_, name)| *name)
+        .collect();
+        if actions.is_empty() { "Not supported".to_string() } else { actions.join(", ") }
+    }
+}
+
+/// Drive capabilities; `None` where the drive could not be asked
+#[derive(Debug, Clone)]
+pub struct DriveCapabilities {
+    pub device_path: String,
+    pub model: Option<String>,
+    pub serial: Option<String>,
+    /// `None` for drives without the ATA Security feature set
+    pub ata_security: Option<AtaSecurity>,
+    pub freeze_status: FreezeStatus,
+    /// Sectors hidden behind a Host Protected Area
+    pub hpa_hidden_sectors: Option<u64>,
+    /// Device Configuration Overlay feature set supported (IDENTIFY word 83 bit 11)
+    pub dco_supported: Option<bool>,
+    pub trim: bool,
+    pub sanitize: Option<SanitizeSupport>,
+    /// A TCG Opal / Enterprise drive that can replace its media key
+    pub crypto_erase: bool,
+    pub encryption: EncryptionStatus,
+    /// SMR drives and ZNS namespaces; `None` for ordinary devices
+    pub zoned: Option<ZonedModel>,
+    pub health: DeviceHealth,
+    pub detected_at: DateTime<Local>,
+}
+
+/// Probe `device_path`; blocks for as long as the drive takes to answer
+pub fn detect_drive_capabilities(device_path: &str) -> DriveCapabilities {
+    let mut capabilities = DriveCapabilities {
+        device_path: device_path.to_string(),
+        model: None,
+        serial: None,
+        ata_security: None,
+        freeze_status: FreezeStatus::Unknown,
+        hpa_hidden_sectors: None,
+        dco_supported: None,
+        trim: crate::devices::flash::detect_capabilities(device_path).discard,
+        sanitize: None,
+        crypto_erase: false,
+        encryption: EncryptionStatus::Unknown,
+        zoned: detect_zoned_model(device_path),
+        health: read_device_health(device_path),
+        detected_at: Local::now(),
+    };
+
+    if let Some(identify) = AtaInterface::new(device_path).ok().and_then(|ata| {
+        let data = ata.identify_device().ok()?;
+        Some((ata, data))
+    }) {
+        let (ata, data) = identify;
+        let info = ata.parse_identify_data(&data);
+        let words = &data.data;
+        capabilities.model = Some(info.model);
+        capabilities.serial = Some(info.serial);
+        capabilities.freeze_status = FreezeStatus::from_ata(info.security_supported, info.security_frozen);
+        capabilities.ata_security = info.security_supported.then_some(AtaSecurity {
+            enabled: info.security_enabled,
+            locked: info.security_locked,
+            frozen: info.security_frozen,
+            enhanced_erase: words[128] & 0x0020 != 0,
+        });
+        capabilities.dco_supported = Some(words[83] & 0x0800 != 0);
+        // DATA SET MANAGEMENT with TRIM
+        capabilities.trim |= words[169] & 0x0001 != 0;
+        // Word 59: bit 12 sanitize feature set, 13 crypto scramble, 14 overwrite, 15 block erase
+        capabilities.sanitize = Some(if words[59] & 0x1000 != 0 {
+            SanitizeSupport {
+                block_erase: words[59] & 0x8000 != 0,
+                crypto_erase: words[59] & 0x2000 != 0,
+                overwrite: words[59] & 0x4000 != 0,
+            }
+        } else {
+            SanitizeSupport::default()
+        });
+
+        let extended = words[83] & 0x0400 != 0;
+        let user_sectors = if extended {
+            ((words[103] as u64) << 48) | ((words[102] as u64) << 32) | ((words[101] as u64) << 16) | words[100] as u64
+        } else {
+            ((words[61] as u64) << 16) | words[60] as u64
+        };
+        capabilities.hpa_hidden_sectors = ata
+            .read_native_max_address(extended)
+            .ok()
+            .map(|native_max_lba| (native_max_lba + 1).saturating_sub(user_sectors));
+    } else {
+        let scsi = ScsiInterface::new(device_path);
+        let sanitize = SanitizeSupport {
+            block_erase: scsi.supports_sanitize(SanitizeAction::BlockErase),
+            crypto_erase: scsi.supports_sanitize(SanitizeAction::CryptoErase),
+            overwrite: scsi.supports_sanitize(SanitizeAction::Overwrite),
+        };
+        // Devices that answer none of these may not speak SCSI at all
+        capabilities.sanitize = sanitize.any().then_some(sanitize);
+    }
+
+    if let Some(discovery) = crate::opal::detect_opal(device_path) {
+        capabilities.crypto_erase = discovery.supports_crypto_erase();
+    }
+    capabilities.encryption = detect_encryption(device_path).unwrap_or_default();
+
+    capabilities
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sanitize_support_description() {
+        assert_eq!(SanitizeSupport::default().description(), "Not supported");
+        let support = SanitizeSupport { block_erase: true, crypto_erase: false, overwrite: true };
+        assert!(support.any());
+        assert_eq!(support.description(), "block erase, overwrite");
+    }
+}
@@ -0,0 +1,321 @@
+//! eMMC specific erasure methods
+//!
+//! Embedded and industrial boards solder eMMC parts directly to the board.
+//! eMMC implements its own ERASE / TRIM / Secure Erase / SANITIZE commands,
+//! which purge the flash (including blocks the controller has retired from
+//! the visible capacity) far faster than a host-driven overwrite.
+
+use std::io;
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+use std::fs::{File, OpenOptions};
+use std::io::{Write, Seek, SeekFrom};
+use crate::freeze::FreezeStatus;
+use crate::encryption::EncryptionStatus;
+use crate::advanced_wiper::{DeviceInfo, DeviceType, WipingProgress, WipingAlgorithm};
+use crate::devices::{verify_with_policy, DeviceEraser};
+use crate::verification::{ExpectedContent, VerificationPolicy};
+use crate::bad_sectors::BadSectorLog;
+use crate::mmc_commands::{erase_range, EraseArg, ExtCsd, MmcInterface};
+use tracing::{info, info_span, warn};
+
+pub struct EmmcEraser {
+    buffer_size: usize,
+    verify_after_wipe: bool,
+    verification_policy: VerificationPolicy,
+}
+
+impl EmmcEraser {
+    pub fn new() -> Self {
+        Self {
+            buffer_size: 1024 * 1024, // 1MB buffer
+            verify_after_wipe: true,
+            verification_policy: VerificationPolicy::default(),
+        }
+    }
+
+    /// Read the device back according to `verification_policy` after wiping
+    pub fn with_verification_policy(verification_policy: VerificationPolicy) -> Self {
+        Self {
+            verification_policy,
+            ..Self::new()
+        }
+    }
+
+    /// Read EXT_CSD to find which purge commands the part supports
+    pub fn detect_capabilities(&self, device_path: &str) -> io::Result<ExtCsd> {
+        MmcInterface::new(device_path).read_ext_csd()
+    }
+
+    /// ERASE the whole user area, then SANITIZE so every unmapped block is
+    /// physically erased (eMMC 4.5+)
+    pub fn sanitize(
+        &self,
+        device_info: &DeviceInfo,
+        progress_callback: Arc<Mutex<WipingProgress>>,
+    ) -> io::Result<()> {
+        info!("Starting eMMC SANITIZE");
+
+        self.start_hardware_operation("eMMC Sanitize", &progress_callback);
+
+        let mmc = MmcInterface::new(&device_info.device_path);
+        if !mmc.read_ext_csd()?.supports_sanitize {
+            return Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                "SANITIZE not supported on this eMMC part"
+            ));
+        }
+
+        // SANITIZE only reaches unmapped blocks, so unmap the whole device first
+        let (first, last) = erase_range(device_info.size_bytes, device_info.sector_size);
+        mmc.erase(first, last, EraseArg::Erase)?;
+        mmc.sanitize()?;
+
+        self.finish_hardware_operation(device_info, &progress_callback);
+        info!("eMMC SANITIZE completed");
+        Ok(())
+    }
+
+    /// Secure Trim in two steps: mark the range, then purge the marked blocks
+    pub fn secure_trim(
+        &self,
+        device_info: &DeviceInfo,
+        progress_callback: Arc<Mutex<WipingProgress>>,
+    ) -> io::Result<()> {
+        info!("Starting eMMC Secure Trim");
+
+        self.start_hardware_operation("eMMC Secure Trim", &progress_callback);
+
+        let mmc = MmcInterface::new(&device_info.device_path);
+        if !mmc.read_ext_csd()?.supports_secure_trim {
+            return Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                "Secure Trim not supported on this eMMC part"
+            ));
+        }
+
+        let (first, last) = erase_range(device_info.size_bytes, device_info.sector_size);
+        mmc.erase(first, last, EraseArg::SecureTrimStep1)?;
+        mmc.erase(first, last, EraseArg::SecureTrimStep2)?;
+
+        self.finish_hardware_operation(device_info, &progress_callback);
+        info!("eMMC Secure Trim completed");
+        Ok(())
+    }
+
+    /// Secure Erase of every erase group (eMMC 4.4+)
+    pub fn secure_erase(
+        &self,
+        device_info: &DeviceInfo,
+        progress_callback: Arc<Mutex<WipingProgress>>,
+    ) -> io::Result<()> {
+        info!("Starting eMMC Secure Erase");
+
+        self.start_hardware_operation("eMMC Secure Erase", &progress_callback);
+
+        let mmc = MmcInterface::new(&device_info.device_path);
+        if !mmc.read_ext_csd()?.supports_secure_erase {
+            return Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                "Secure Erase not supported on this eMMC part"
+            ));
+        }
+
+        let (first, last) = erase_range(device_info.size_bytes, device_info.sector_size);
+        mmc.erase(first, last, EraseArg::SecureErase)?;
+
+        self.finish_hardware_operation(device_info, &progress_callback);
+        info!("eMMC Secure Erase completed");
+        Ok(())
+    }
+
+    /// Single-pass overwrite fallback for parts reached through a bridge
+    pub fn single_pass_overwrite(
+        &self,
+        device_info: &DeviceInfo,
+        pattern_byte: u8,
+        progress_callback: Arc<Mutex<WipingProgress>>,
+    ) -> io::Result<()> {
+        info!("Starting single-pass overwrite (0x{:02X}) for eMMC", pattern_byte);
+
+        // Update progress
+        if let Ok(mut progress) = progress_callback.lock() {
+            progress.current_pass = 1;
+            progress.total_passes = 1;
+            progress.current_pattern = format!("Overwrite 0x{:02X}", pattern_byte);
+        }
+
+        let start_time = Instant::now();
+        let mut file = OpenOptions::new()
+            .write(true)
+            .open(&device_info.device_path)?;
+
+        let total_size = device_info.size_bytes;
+        let mut bytes_written = 0u64;
+        let chunk = vec![pattern_byte; self.buffer_size];
+
+        file.seek(SeekFrom::Start(0))?;
+
+        while bytes_written < total_size {
+            let remaining = total_size - bytes_written;
+            let write_size = std::cmp::min(chunk.len() as u64, remaining) as usize;
+
+            file.write_all(&chunk[..write_size])?;
+            bytes_written += write_size as u64;
+
+            // Update progress
+            if let Ok(mut progress) = progress_callback.lock() {
+                progress.bytes_processed = bytes_written;
+                progress.total_bytes = total_size;
+
+                let elapsed = start_time.elapsed();
+                if elapsed.as_secs() > 0 {
+                    progress.speed_mbps = (bytes_written as f64) / (1024.0 * 1024.0) / elapsed.as_secs_f64();
+                }
+            }
+        }
+
+        file.sync_all()?;
+        info!("Single-pass overwrite completed for eMMC");
+        Ok(())
+    }
+
+    /// The MMC commands block until the part is done and report no progress
+    fn start_hardware_operation(&self, name: &str, progress_callback: &Arc<Mutex<WipingProgress>>) {
+        if let Ok(mut progress) = progress_callback.lock() {
+            progress.current_pass = 1;
+            progress.total_passes = 1;
+            progress.current_pattern = name.to_string();
+        }
+    }
+
+    fn finish_hardware_operation(&self, device_info: &DeviceInfo, progress_callback: &Arc<Mutex<WipingProgress>>) {
+        if let Ok(mut progress) = progress_callback.lock() {
+            progress.bytes_processed = device_info.size_bytes;
+            progress.total_bytes = device_info.size_bytes;
+        }
+    }
+}
+
+impl Default for EmmcEraser {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl DeviceEraser for EmmcEraser {
+    fn analyze_device(&self, device_path: &str) -> io::Result<DeviceInfo> {
+        info!("Analyzing eMMC device: {}", device_path);
+
+        let ext_csd = self.detect_capabilities(device_path).unwrap_or_default();
+        let size_bytes = if ext_csd.sector_count > 0 {
+            ext_csd.sector_count * 512
+        } else {
+            File::open(device_path)?.metadata()?.len()
+        };
+
+        let device_info = DeviceInfo {
+            device_path: device_path.to_string(),
+            device_type: DeviceType::EMmc,
+            size_bytes,
+            sector_size: 512, // eMMC always addresses 512-byte sectors
+            supports_trim: ext_csd.supports_secure_trim,
+            supports_secure_erase: ext_csd.supports_secure_erase || ext_csd.supports_secure_trim,
+            supports_enhanced_secure_erase: ext_csd.supports_sanitize,
+            supports_crypto_erase: false,
+            supports_opal: false,
+            is_removable: false,
+            vendor: "Unknown".to_string(),
+            model: format!("eMMC (EXT_CSD rev {})", ext_csd.revision),
+            serial: "Unknown".to_string(),
+            device_id: String::new(),
+            freeze_status: FreezeStatus::NotApplicable,
+            encryption_status: EncryptionStatus::Unknown,
+            nvme_namespaces: Vec::new(),
+            zoned: None,
+        };
+
+        info!("eMMC analysis complete: {} ({} bytes, sanitize: {}, secure trim: {})",
+                device_info.model, device_info.size_bytes,
+                ext_csd.supports_sanitize, ext_csd.supports_secure_trim);
+        Ok(device_info)
+    }
+
+    fn erase_device(
+        &self,
+        device_info: &DeviceInfo,
+        algorithm: WipingAlgorithm,
+        progress_callback: Arc<Mutex<WipingProgress>>,
+    ) -> io::Result<()> {
+        let _span = info_span!("erase", device = %device_info.device_path, algorithm = ?algorithm).entered();
+        info!("Starting eMMC erasure with algorithm: {:?}", algorithm);
+
+        match algorithm {
+            WipingAlgorithm::NistClear | WipingAlgorithm::Zeros => self.single_pass_overwrite(device_info, 0x00, progress_callback),
+            WipingAlgorithm::Ones => self.single_pass_overwrite(device_info, 0xFF, progress_callback),
+            _ => {
+                // Purge: prefer SANITIZE, then Secure Erase, then Secure Trim
+                if device_info.supports_enhanced_secure_erase {
+                    info!("Using eMMC SANITIZE as default");
+                    self.sanitize(device_info, progress_callback)
+                } else if device_info.supports_secure_erase {
+                    info!("Using eMMC Secure Erase as default");
+                    self.secure_erase(device_info, progress_callback.clone())
+                        .or_else(|_| self.secure_trim(device_info, progress_callback))
+                } else {
+                    info!("Using single-pass overwrite as fallback for eMMC");
+                    self.single_pass_overwrite(device_info, 0x00, progress_callback)
+                }
+            }
+        }
+    }
+
+    fn verify_erasure(&self, device_info: &DeviceInfo) -> io::Result<bool> {
+        if !self.verify_after_wipe {
+            return Ok(true);
+        }
+
+        info!("Verifying eMMC erasure ({})...", self.verification_policy.description());
+
+        // Erased blocks read back as all zeros or all ones depending on the part
+        let report = verify_with_policy(
+            device_info,
+            &ExpectedContent::UniformFill,
+            &self.verification_policy,
+            &BadSectorLog::new(),
+        )?;
+        if report.passed {
+            info!("eMMC erasure verification passed");
+        } else {
+            warn!("Found residual data during eMMC verification");
+        }
+        Ok(report.passed)
+    }
+
+    fn get_recommended_algorithms(&self) -> Vec<WipingAlgorithm> {
+        vec![
+            WipingAlgorithm::NistPurge, // SANITIZE / Secure Erase / Secure Trim
+            WipingAlgorithm::NistClear, // Host overwrite fallback
+            WipingAlgorithm::Zeros,
+        ]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_erase_range_of_a_part_without_ext_csd() {
+        // A device that does not answer CMD8 is sized from its length
+        let file = tempfile::NamedTempFile::new().unwrap();
+        file.as_file().set_len(4 * 1024 * 1024).unwrap();
+        let device_info = EmmcEraser::new().analyze_device(&file.path().to_string_lossy()).unwrap();
+        assert_eq!(device_info.size_bytes, 4 * 1024 * 1024);
+        assert_eq!(device_info.sector_size, 512);
+        assert!(!device_info.supports_enhanced_secure_erase);
+
+        // Byte addressed, so the last address is the start of the last sector
+        assert_eq!(erase_range(device_info.size_bytes, device_info.sector_size), (0, 4 * 1024 * 1024 - 512));
+    }
+}
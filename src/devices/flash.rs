@@ -0,0 +1,253 @@
+//! Flash media helpers shared by the USB and SD card erasers
+//!
+//! Flash controllers remap every write onto a pool of spare blocks, so a single
+//! overwrite of the visible capacity can leave stale copies in the
+//! over-provisioned area. The native erase commands (SD CMD38, block discard)
+//! reach those blocks through the controller; when they are unavailable, two
+//! full-capacity passes cycle most of the spare pool through the write path.
+//! After an overwrite, a TRIM / Deallocate of the overwritten range lets the
+//! controller erase the stale copies the overwrite retired.
+
+use std::io;
+use serde::{Deserialize, Serialize};
+use crate::mmc_commands::{erase_range, MmcInterface};
+use tracing::{debug, warn};
+
+/// Full-capacity overwrite passes used to reach the over-provisioned area
+pub const OVERPROVISION_PASSES: u32 = 2;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum FlashTechnique {
+    /// SD ERASE_WR_BLK_START / END followed by CMD38 ERASE
+    SdErase,
+    /// BLKDISCARD over the whole device
+    BlockDiscard,
+    /// TRIM / Deallocate of the overwritten range after the last pass
+    Deallocate,
+    /// Full-capacity overwrite passes to push data out of the spare pool
+    FullCapacityPasses(u32),
+}
+
+impl FlashTechnique {
+    pub fn description(&self) -> String {
+        match self {
+            FlashTechnique::SdErase => "SD CMD38 ERASE (whole card)".to_string(),
+            FlashTechnique::BlockDiscard => "Block discard (BLKDISCARD, whole device)".to_string(),
+            FlashTechnique::Deallocate => "TRIM / Deallocate after overwrite".to_string(),
+            FlashTechnique::FullCapacityPasses(passes) => {
+                format!("{} full-capacity overwrite passes (over-provisioning)", passes)
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct FlashCapabilities {
+    /// The controller remaps writes, so overwrites alone may not reach every block
+    pub wear_leveling: bool,
+    /// The card is reachable through the MMC host and accepts SD erase commands
+    pub sd_erase: bool,
+    /// The block layer accepts discard requests for the device
+    pub discard: bool,
+}
+
+/// Probe what the flash device behind `device_path` supports. Without sysfs the
+/// device is assumed to wear-level and to offer no native erase.
+pub fn detect_capabilities(device_path: &str) -> FlashCapabilities {
+    #[cfg(target_os = "linux")]
+    {
+        let name = match std::path::Path::new(device_path).file_name() {
+            Some(name) => name.to_string_lossy().to_string(),
+            None => return FlashCapabilities { wear_leveling: true, ..Default::default() },
+        };
+        let read_attr = |attr: &str| {
+            std::fs::read_to_string(format!("/sys/block/{}/{}", name, attr))
+                .map(|value| value.trim().to_string())
+                .ok()
+        };
+
+        // Card readers that present flash as spinning media are rare; trust "1"
+        let wear_leveling = read_attr("queue/rotational").as_deref() != Some("1");
+        let discard = read_attr("queue/discard_max_bytes")
+            .and_then(|value| value.parse::<u64>().ok())
+            .is_some_and(|max| max > 0);
+        let sd_erase = MmcInterface::new(device_path).is_sd();
+
+        FlashCapabilities { wear_leveling, sd_erase, discard }
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    {
+        let _ = device_path;
+        FlashCapabilities { wear_leveling: true, ..Default::default() }
+    }
+}
+
+#[cfg(target_os = "linux")]
+mod linux {
+    use std::fs::OpenOptions;
+    use std::io;
+    use std::os::unix::io::AsRawFd;
+    use libc::ioctl;
+
+    /// _IO(0x12, 119)
+    const BLKDISCARD: libc::c_ulong = 0x1277;
+
+    pub fn discard(device_path: &str, offset: u64, length: u64) -> io::Result<()> {
+        let device = OpenOptions::new().write(true).open(device_path)?;
+        let range: [u64; 2] = [offset, length];
+        if unsafe { ioctl(device.as_raw_fd(), BLKDISCARD as _, range.as_ptr()) } < 0 {
+            return Err(io::Error::last_os_error());
+        }
+        Ok(())
+    }
+}
+
+#[cfg(windows)]
+mod windows_trim {
+    use std::io;
+    use windows::core::PCWSTR;
+    use windows::Win32::Foundation::{CloseHandle, GENERIC_READ, GENERIC_WRITE};
+    use windows::Win32::Storage::FileSystem::{CreateFileW, FILE_ATTRIBUTE_NORMAL, FILE_SHARE_READ, FILE_SHARE_WRITE, OPEN_EXISTING};
+    use windows::Win32::System::Ioctl::{
+        DeviceDsmAction_Trim, DEVICE_DATA_SET_RANGE, DEVICE_MANAGE_DATA_SET_ATTRIBUTES,
+        IOCTL_STORAGE_MANAGE_DATA_SET_ATTRIBUTES,
+    };
+    use windows::Win32::System::IO::DeviceIoControl;
+
+    /// IOCTL_STORAGE_MANAGE_DATA_SET_ATTRIBUTES input with one range
+    #[repr(C)]
+    struct TrimRequest {
+        attributes: DEVICE_MANAGE_DATA_SET_ATTRIBUTES,
+        range: DEVICE_DATA_SET_RANGE,
+    }
+
+    /// TRIM on ATA drives, UNMAP on SCSI and Deallocate on NVMe, translated by storport
+    pub fn trim(device_path: &str, offset: u64, length: u64) -> io::Result<()> {
+        let path: Vec<u16> = device_path.encode_utf16().chain(std::iter::once(0)).collect();
+        let mut request = TrimRequest {
+            attributes: DEVICE_MANAGE_DATA_SET_ATTRIBUTES {
+                Size: std::mem::size_of::<DEVICE_MANAGE_DATA_SET_ATTRIBUTES>() as u32,
+                Action: DeviceDsmAction_Trim,
+                Flags: 0,
+                ParameterBlockOffset: 0,
+                ParameterBlockLength: 0,
+                DataSetRangesOffset: std::mem::offset_of!(TrimRequest, range) as u32,
+                DataSetRangesLength: std::mem::size_of::<DEVICE_DATA_SET_RANGE>() as u32,
+            },
+            range: DEVICE_DATA_SET_RANGE { StartingOffset: offset as i64, LengthInBytes: length },
+        };
+
+        unsafe {
+            let handle = CreateFileW(
+                PCWSTR::from_raw(path.as_ptr()),
+                GENERIC_READ.0 | GENERIC_WRITE.0,
+                FILE_SHARE_READ | FILE_SHARE_WRITE,
+                None,
+                OPEN_EXISTING,
+                FILE_ATTRIBUTE_NORMAL,
+                None,
+            )
+            .map_err(io::Error::other)?;
+            let mut bytes_returned = 0u32;
+            let result = DeviceIoControl(
+                handle,
+                IOCTL_STORAGE_MANAGE_DATA_SET_ATTRIBUTES,
+                Some(&mut request as *mut TrimRequest as *const _),
+                std::mem::size_of::<TrimRequest>() as u32,
+                None,
+                0,
+                Some(&mut bytes_returned),
+                None,
+            );
+            let _ = CloseHandle(handle);
+            result.map_err(io::Error::other)
+        }
+    }
+}
+
+/// Erase the whole card with CMD32 / CMD33 / CMD38 through the MMC host
+pub fn sd_erase(device_path: &str, size_bytes: u64, sector_size: u32) -> io::Result<()> {
+    let (first, last) = erase_range(size_bytes, sector_size);
+    debug!("Issuing SD CMD38 ERASE on {} (range {}-{})", device_path, first, last);
+
+    MmcInterface::new(device_path).sd_erase(first, last)
+}
+
+/// Discard every block of the device so the controller drops its mappings
+pub fn discard_all(device_path: &str, size_bytes: u64) -> io::Result<()> {
+    debug!("Discarding all {} bytes of {}", size_bytes, device_path);
+
+    #[cfg(target_os = "linux")]
+    {
+        linux::discard(device_path, 0, size_bytes)
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    {
+        Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            "Block discard is not available on this platform"
+        ))
+    }
+}
+
+/// TRIM / Deallocate `length` bytes from `offset`: BLKDISCARD on Linux, which
+/// the kernel sends to NVMe drives as Dataset Management Deallocate, and the
+/// storage TRIM IOCTL on Windows
+pub fn deallocate_range(device_path: &str, offset: u64, length: u64) -> io::Result<()> {
+    debug!("Deallocating {} bytes of {} from byte {}", length, device_path, offset);
+
+    #[cfg(target_os = "linux")]
+    {
+        linux::discard(device_path, offset, length)
+    }
+
+    #[cfg(windows)]
+    {
+        windows_trim::trim(device_path, offset, length)
+    }
+
+    #[cfg(not(any(target_os = "linux", windows)))]
+    {
+        let _ = (offset, length);
+        Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            "TRIM is not available on this platform"
+        ))
+    }
+}
+
+/// Run every native erase the device supports. Returns the techniques that
+/// succeeded; failures are logged and skipped so the overwrite can still run.
+pub fn native_erase(device_path: &str, size_bytes: u64, sector_size: u32, capabilities: &FlashCapabilities) -> Vec<FlashTechnique> {
+    let mut techniques = Vec::new();
+
+    if capabilities.sd_erase {
+        match sd_erase(device_path, size_bytes, sector_size) {
+            Ok(()) => techniques.push(FlashTechnique::SdErase),
+            Err(e) => warn!("SD erase command failed: {}", e),
+        }
+    }
+
+    if capabilities.discard {
+        match discard_all(device_path, size_bytes) {
+            Ok(()) => techniques.push(FlashTechnique::BlockDiscard),
+            Err(e) => warn!("Block discard failed: {}", e),
+        }
+    }
+
+    techniques
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_technique_descriptions() {
+        assert!(FlashTechnique::SdErase.description().contains("CMD38"));
+        assert!(FlashTechnique::Deallocate.description().contains("TRIM"));
+        assert!(FlashTechnique::FullCapacityPasses(OVERPROVISION_PASSES).description().starts_with("2 full-capacity"));
+    }
+}
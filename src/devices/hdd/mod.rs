@@ -8,13 +8,23 @@ use std::sync::{Arc, Mutex};
 use std::time::{Duration, Instant};
 use std::fs::{File, OpenOptions};
 use std::io::{Write, Seek, SeekFrom};
+use crate::freeze::{FreezeMitigation, FreezeStatus};
+use crate::encryption::EncryptionStatus;
 use crate::advanced_wiper::{DeviceInfo, DeviceType, WipingProgress, WipingAlgorithm};
-use crate::devices::DeviceEraser;
+use crate::devices::{verify_with_policy, DeviceEraser};
+use crate::verification::{ExpectedContent, VerificationPolicy};
 use crate::ata_commands::AtaInterface;
+use crate::erase_progress::{ata_erase_estimate, EraseProgressSource, HardwareEraseMonitor};
+use crate::bad_sectors::{write_block_tolerant, BadSectorLog};
+use tracing::{error, info, info_span, warn};
 
 pub struct HddEraser {
     buffer_size: usize,
     verify_after_wipe: bool,
+    verification_policy: VerificationPolicy,
+    /// Skip up to this many unwritable sectors instead of aborting; `None` aborts on the first error
+    max_bad_sectors: Option<u64>,
+    bad_sectors: Mutex<BadSectorLog>,
 }
 
 impl HddEraser {
@@ -22,15 +32,39 @@ impl HddEraser {
         Self {
             buffer_size: 1024 * 1024, // 1MB buffer
             verify_after_wipe: true,
+            verification_policy: VerificationPolicy::default(),
+            max_bad_sectors: None,
+            bad_sectors: Mutex::new(BadSectorLog::new()),
+        }
+    }
+    
+    /// Read the device back according to `verification_policy` after wiping
+    pub fn with_verification_policy(verification_policy: VerificationPolicy) -> Self {
+        Self {
+            verification_policy,
+            ..Self::new()
         }
     }
     
     pub fn with_buffer_size(buffer_size: usize) -> Self {
         Self {
             buffer_size,
-            verify_after_wipe: true,
+            ..Self::new()
         }
     }
+
+    /// Keep overwriting past failing sectors, up to `max_bad_sectors` of them
+    pub fn with_bad_sector_tolerance(max_bad_sectors: u64) -> Self {
+        Self {
+            max_bad_sectors: Some(max_bad_sectors),
+            ..Self::new()
+        }
+    }
+
+    /// Sectors skipped so far by fault-tolerant overwrites
+    pub fn bad_sector_log(&self) -> BadSectorLog {
+        self.bad_sectors.lock().unwrap().clone()
+    }
     
     /// DoD 5220.22-M standard erasure (3-pass)
     pub fn dod_5220_22m_erase(
@@ -38,7 +72,7 @@ impl HddEraser {
         device_info: &DeviceInfo,
         progress_callback: Arc<Mutex<WipingProgress>>,
     ) -> io::Result<()> {
-        println!("🔄 Starting DoD 5220.22-M (3-pass) erasure for HDD");
+        info!("Starting DoD 5220.22-M (3-pass) erasure for HDD");
         
         let patterns = [
             vec![0x00; self.buffer_size], // Pass 1: All zeros
@@ -48,7 +82,8 @@ impl HddEraser {
         
         for (pass, pattern) in patterns.iter().enumerate() {
             let pass_num = pass + 1;
-            println!("🔄 HDD DoD Pass {}/3", pass_num);
+            let _pass = info_span!("pass", pass = pass_num, total_passes = 3).entered();
+            info!("HDD DoD Pass {}/3", pass_num);
             
             // Update progress
             if let Ok(mut progress) = progress_callback.lock() {
@@ -65,7 +100,7 @@ impl HddEraser {
             self.overwrite_device(device_info, pattern, progress_callback.clone())?;
         }
         
-        println!("✅ DoD 5220.22-M erasure completed for HDD");
+        info!("DoD 5220.22-M erasure completed for HDD");
         Ok(())
     }
     
@@ -75,14 +110,15 @@ impl HddEraser {
         device_info: &DeviceInfo,
         progress_callback: Arc<Mutex<WipingProgress>>,
     ) -> io::Result<()> {
-        println!("🔄 Starting Gutmann 35-pass erasure for HDD");
+        info!("Starting Gutmann 35-pass erasure for HDD");
         
         // Gutmann patterns for magnetic drives
         let gutmann_patterns = self.get_gutmann_patterns();
         
         for (pass, pattern_data) in gutmann_patterns.iter().enumerate() {
             let pass_num = pass + 1;
-            println!("🔄 HDD Gutmann Pass {}/35: {}", pass_num, pattern_data.1);
+            let _pass = info_span!("pass", pass = pass_num, total_passes = 35).entered();
+            info!("HDD Gutmann Pass {}/35: {}", pass_num, pattern_data.1);
             
             // Update progress
             if let Ok(mut progress) = progress_callback.lock() {
@@ -95,7 +131,7 @@ impl HddEraser {
             self.overwrite_device(device_info, &pattern, progress_callback.clone())?;
         }
         
-        println!("✅ Gutmann 35-pass erasure completed for HDD");
+        info!("Gutmann 35-pass erasure completed for HDD");
         Ok(())
     }
     
@@ -106,10 +142,11 @@ impl HddEraser {
         passes: u32,
         progress_callback: Arc<Mutex<WipingProgress>>,
     ) -> io::Result<()> {
-        println!("🔄 Starting {}-pass random erasure for HDD", passes);
+        info!("Starting {}-pass random erasure for HDD", passes);
         
         for pass in 1..=passes {
-            println!("🔄 HDD Random Pass {}/{}", pass, passes);
+            let _pass = info_span!("pass", pass, total_passes = passes).entered();
+            info!("HDD Random Pass {}/{}", pass, passes);
             
             // Update progress
             if let Ok(mut progress) = progress_callback.lock() {
@@ -122,7 +159,7 @@ impl HddEraser {
             self.overwrite_device(device_info, &pattern, progress_callback.clone())?;
         }
         
-        println!("✅ {}-pass random erasure completed for HDD", passes);
+        info!("{}-pass random erasure completed for HDD", passes);
         Ok(())
     }
     
@@ -133,7 +170,7 @@ impl HddEraser {
         enhanced: bool,
         progress_callback: Arc<Mutex<WipingProgress>>,
     ) -> io::Result<()> {
-        println!("🔄 Starting ATA Secure Erase for HDD (Enhanced: {})", enhanced);
+        info!("Starting ATA Secure Erase for HDD (Enhanced: {})", enhanced);
         
         // Update progress
         if let Ok(mut progress) = progress_callback.lock() {
@@ -157,13 +194,40 @@ impl HddEraser {
                     ));
                 }
                 
-                // Perform secure erase
-                ata.security_erase(enhanced)?;
-                println!("✅ ATA Secure Erase completed for HDD");
+                // A frozen drive rejects SECURITY ERASE UNIT; try to clear the freeze first
+                if drive_info.security_frozen {
+                    let outcome = FreezeMitigation::new().unfreeze(&device_info.device_path)?;
+                    if !outcome.status.allows_security_commands() {
+                        return Err(io::Error::new(
+                            io::ErrorKind::PermissionDenied,
+                            format!("ATA Secure Erase unavailable: {}", FreezeStatus::Frozen.guidance())
+                        ));
+                    }
+                }
+                
+                // Perform secure erase; the drive reports nothing until it finishes,
+                // so progress is synthesized from its own erase-time estimate
+                let monitor = ata_erase_estimate(&ata, enhanced).map(|estimate| {
+                    HardwareEraseMonitor::for_wiping_progress(
+                        EraseProgressSource::Estimate(estimate),
+                        progress_callback.clone(),
+                        device_info.size_bytes,
+                    )
+                });
+                let result = ata.security_erase(enhanced);
+                drop(monitor);
+                result?;
+
+                if let Ok(mut progress) = progress_callback.lock() {
+                    progress.bytes_processed = device_info.size_bytes;
+                    progress.total_bytes = device_info.size_bytes;
+                    progress.estimated_time_remaining = Duration::ZERO;
+                }
+                info!("ATA Secure Erase completed for HDD");
                 Ok(())
             }
             Err(e) => {
-                println!("❌ ATA interface failed, falling back to software erasure");
+                error!("ATA interface failed, falling back to software erasure");
                 // Fallback to software-based erasure
                 self.dod_5220_22m_erase(device_info, progress_callback)
             }
@@ -191,7 +255,17 @@ impl HddEraser {
             let remaining = total_size - bytes_written;
             let write_size = std::cmp::min(pattern.len() as u64, remaining) as usize;
             
-            file.write_all(&pattern[..write_size])?;
+            if let Err(e) = file.write_all(&pattern[..write_size]) {
+                let Some(max_bad_sectors) = self.max_bad_sectors else {
+                    return Err(e);
+                };
+
+                let mut bad_sectors = self.bad_sectors.lock().unwrap();
+                write_block_tolerant(&mut file, bytes_written, &pattern[..write_size], device_info.sector_size as usize, &mut bad_sectors)?;
+                if bad_sectors.exceeds(max_bad_sectors) {
+                    return Err(io::Error::other(format!("{} sectors could not be overwritten (limit {})", bad_sectors.skipped_sectors(), max_bad_sectors)));
+                }
+            }
             bytes_written += write_size as u64;
             
             // Update progress
@@ -279,7 +353,7 @@ impl HddEraser {
 
 impl DeviceEraser for HddEraser {
     fn analyze_device(&self, device_path: &str) -> io::Result<DeviceInfo> {
-        println!("🔍 Analyzing HDD device: {}", device_path);
+        info!("Analyzing HDD device: {}", device_path);
         
         // Try to get detailed info via ATA interface
         let device_info = match AtaInterface::new(device_path) {
@@ -294,10 +368,16 @@ impl DeviceEraser for HddEraser {
                     supports_secure_erase: drive_info.security_supported,
                     supports_enhanced_secure_erase: drive_info.security_supported,
                     supports_crypto_erase: false, // HDDs typically don't have hardware encryption
+                    supports_opal: false,
                     is_removable: false,
                     vendor: "Unknown".to_string(),
                     model: drive_info.model,
                     serial: drive_info.serial,
+                    device_id: String::new(),
+                    freeze_status: FreezeStatus::from_ata(drive_info.security_supported, drive_info.security_frozen),
+                    encryption_status: EncryptionStatus::Unknown,
+                    nvme_namespaces: Vec::new(),
+                    zoned: None,
                 }
             }
             Err(_) => {
@@ -314,15 +394,21 @@ impl DeviceEraser for HddEraser {
                     supports_secure_erase: false,
                     supports_enhanced_secure_erase: false,
                     supports_crypto_erase: false,
+                    supports_opal: false,
                     is_removable: false,
                     vendor: "Unknown".to_string(),
                     model: "Unknown HDD".to_string(),
                     serial: "Unknown".to_string(),
+                    device_id: String::new(),
+                    freeze_status: FreezeStatus::Unknown,
+                    encryption_status: EncryptionStatus::Unknown,
+                    nvme_namespaces: Vec::new(),
+                    zoned: None,
                 }
             }
         };
         
-        println!("✅ HDD analysis complete: {} ({} bytes)", 
+        info!("HDD analysis complete: {} ({} bytes)", 
                 device_info.model, device_info.size_bytes);
         Ok(device_info)
     }
@@ -333,7 +419,8 @@ impl DeviceEraser for HddEraser {
         algorithm: WipingAlgorithm,
         progress_callback: Arc<Mutex<WipingProgress>>,
     ) -> io::Result<()> {
-        println!("🚀 Starting HDD erasure with algorithm: {:?}", algorithm);
+        let _span = info_span!("erase", device = %device_info.device_path, algorithm = ?algorithm).entered();
+        info!("Starting HDD erasure with algorithm: {:?}", algorithm);
         
         match algorithm {
             WipingAlgorithm::DoD522022M => self.dod_5220_22m_erase(device_info, progress_callback),
@@ -344,7 +431,7 @@ impl DeviceEraser for HddEraser {
             WipingAlgorithm::SevenPass => self.multi_pass_random_erase(device_info, 7, progress_callback),
             _ => {
                 // Default to DoD 5220.22-M for other algorithms
-                println!("ℹ️  Using DoD 5220.22-M as default for HDD");
+                info!("Using DoD 5220.22-M as default for HDD");
                 self.dod_5220_22m_erase(device_info, progress_callback)
             }
         }
@@ -354,31 +441,21 @@ impl DeviceEraser for HddEraser {
         if !self.verify_after_wipe {
             return Ok(true);
         }
-        
-        println!("🔍 Verifying HDD erasure...");
-        
-        let mut file = File::open(&device_info.device_path)?;
-        let mut buffer = vec![0u8; self.buffer_size];
-        let mut total_read = 0u64;
-        let sample_size = std::cmp::min(device_info.size_bytes, 100 * 1024 * 1024); // Sample first 100MB
-        
-        while total_read < sample_size {
-            let bytes_read = std::io::Read::read(&mut file, &mut buffer)?;
-            if bytes_read == 0 {
-                break;
-            }
-            
-            // Check for non-zero bytes (indicating potential data remnants)
-            if buffer[..bytes_read].iter().any(|&b| b != 0) {
-                println!("⚠️  Found non-zero data during verification");
-                return Ok(false);
-            }
-            
-            total_read += bytes_read as u64;
+
+        info!("Verifying HDD erasure ({})...", self.verification_policy.description());
+
+        let report = verify_with_policy(
+            device_info,
+            &ExpectedContent::Pattern(vec![0x00]),
+            &self.verification_policy,
+            &self.bad_sector_log(),
+        )?;
+        if report.passed {
+            info!("HDD erasure verification passed");
+        } else {
+            warn!("Found residual data during HDD verification");
         }
-        
-        println!("✅ HDD erasure verification passed");
-        Ok(true)
+        Ok(report.passed)
     }
     
     fn get_recommended_algorithms(&self) -> Vec<WipingAlgorithm> {
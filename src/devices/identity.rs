@@ -0,0 +1,221 @@
+//! Stable device identity
+//!
+//! Device paths such as `/dev/sdb` or `\\.\PhysicalDrive1` are reassigned
+//! whenever drives are hot-plugged or the bus is re-enumerated. This module
+//! derives a deterministic identifier from the drive serial, WWN and path,
+//! persists it, and correlates re-enumerated drives back to their known id.
+
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use crate::advanced_wiper::DeviceInfo;
+use tracing::{info, warn};
+
+/// Default location of the persistent device registry
+pub const DEFAULT_REGISTRY_PATH: &str = "devices.json";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeviceIdentity {
+    pub device_id: String,
+    pub serial: Option<String>,
+    pub wwn: Option<String>,
+    pub model: String,
+    pub last_path: String,
+    pub first_seen: DateTime<Utc>,
+    pub last_seen: DateTime<Utc>,
+}
+
+/// Known devices keyed by stable id
+#[derive(Debug, Default)]
+pub struct DeviceRegistry {
+    path: Option<PathBuf>,
+    devices: HashMap<String, DeviceIdentity>,
+}
+
+impl DeviceRegistry {
+    pub fn load<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        let devices = if path.exists() {
+            let content = fs::read_to_string(&path)?;
+            let list: Vec<DeviceIdentity> = serde_json::from_str(&content)
+                .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+            list.into_iter().map(|d| (d.device_id.clone(), d)).collect()
+        } else {
+            HashMap::new()
+        };
+
+        Ok(Self { path: Some(path), devices })
+    }
+
+    pub fn save(&self) -> io::Result<()> {
+        let Some(ref path) = self.path else {
+            return Ok(());
+        };
+
+        let mut list: Vec<&DeviceIdentity> = self.devices.values().collect();
+        list.sort_by_key(|identity| identity.first_seen);
+        let content = serde_json::to_string_pretty(&list)
+            .map_err(io::Error::other)?;
+        fs::write(path, content)
+    }
+
+    /// Return the stable id for a drive, correlating it with a known device
+    /// by WWN, then serial, then (for drives without either) by path.
+    pub fn resolve(&mut self, serial: &str, wwn: Option<&str>, model: &str, path: &str) -> String {
+        let serial = normalize(serial);
+        let wwn = wwn.and_then(normalize);
+
+        let existing = self.devices.values()
+            .find(|d| wwn.is_some() && d.wwn == wwn)
+            .or_else(|| self.devices.values().find(|d| serial.is_some() && d.serial == serial))
+            .or_else(|| self.devices.values().find(|d| {
+                serial.is_none() && wwn.is_none() && d.serial.is_none() && d.wwn.is_none() && d.last_path == path
+            }))
+            .map(|d| d.device_id.clone());
+
+        let now = Utc::now();
+        if let Some(device_id) = existing {
+            let device = self.devices.get_mut(&device_id).unwrap();
+            if device.last_path != path {
+                info!("Device {} re-enumerated: {} -> {}", device_id, device.last_path, path);
+                device.last_path = path.to_string();
+            }
+            if device.wwn.is_none() {
+                device.wwn = wwn;
+            }
+            device.last_seen = now;
+            return device_id;
+        }
+
+        let device_id = compute_device_id(serial.as_deref(), wwn.as_deref(), path);
+        self.devices.insert(device_id.clone(), DeviceIdentity {
+            device_id: device_id.clone(),
+            serial,
+            wwn,
+            model: model.to_string(),
+            last_path: path.to_string(),
+            first_seen: now,
+            last_seen: now,
+        });
+        device_id
+    }
+
+    pub fn get(&self, device_id: &str) -> Option<&DeviceIdentity> {
+        self.devices.get(device_id)
+    }
+
+    /// Current path of a known device
+    pub fn path_for(&self, device_id: &str) -> Option<String> {
+        self.devices.get(device_id).map(|d| d.last_path.clone())
+    }
+
+    /// Id of the device last seen at `path`
+    pub fn id_for_path(&self, path: &str) -> Option<String> {
+        self.devices.values()
+            .filter(|d| d.last_path == path)
+            .max_by_key(|d| d.last_seen)
+            .map(|d| d.device_id.clone())
+    }
+}
+
+fn normalize(value: &str) -> Option<String> {
+    let value = value.trim();
+    if value.is_empty() || value.eq_ignore_ascii_case("unknown") {
+        None
+    } else {
+        Some(value.to_string())
+    }
+}
+
+/// Deterministic id: drives without serial or WWN fall back to their path
+pub fn compute_device_id(serial: Option<&str>, wwn: Option<&str>, path: &str) -> String {
+    let mut hasher = Sha256::new();
+    match (serial, wwn) {
+        (None, None) => hasher.update(format!("path:{}", path)),
+        _ => hasher.update(format!("serial:{}|wwn:{}", serial.unwrap_or(""), wwn.unwrap_or(""))),
+    }
+    hex::encode(&hasher.finalize()[..8])
+}
+
+/// World Wide Name reported by the kernel, if any
+pub fn read_wwn(device_path: &str) -> Option<String> {
+    #[cfg(target_os = "linux")]
+    {
+        let name = Path::new(device_path).file_name()?.to_string_lossy().to_string();
+        for candidate in [
+            format!("/sys/block/{}/device/wwid", name),
+            format!("/sys/block/{}/wwid", name),
+        ] {
+            if let Ok(wwid) = fs::read_to_string(&candidate) {
+                return normalize(&wwid);
+            }
+        }
+        None
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    {
+        let _ = device_path;
+        None
+    }
+}
+
+fn registry() -> &'static Mutex<DeviceRegistry> {
+    static REGISTRY: OnceLock<Mutex<DeviceRegistry>> = OnceLock::new();
+    REGISTRY.get_or_init(|| {
+        let registry = DeviceRegistry::load(DEFAULT_REGISTRY_PATH).unwrap_or_else(|e| {
+            warn!("Could not load device registry: {}", e);
+            DeviceRegistry::default()
+        });
+        Mutex::new(registry)
+    })
+}
+
+/// Assign the persisted stable id to an analyzed device
+pub fn resolve_device_id(device_info: &DeviceInfo) -> String {
+    let wwn = read_wwn(&device_info.device_path);
+    let mut registry = registry().lock().unwrap();
+    let device_id = registry.resolve(&device_info.serial, wwn.as_deref(), &device_info.model, &device_info.device_path);
+    if let Err(e) = registry.save() {
+        warn!("Could not save device registry: {}", e);
+    }
+    device_id
+}
+
+/// Look up the current path of a device by its stable id
+pub fn device_path_for_id(device_id: &str) -> Option<String> {
+    registry().lock().unwrap().path_for(device_id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_device_correlated_after_reenumeration() {
+        let mut registry = DeviceRegistry::default();
+        let id = registry.resolve("WD-123", Some("naa.5000c500a1b2c3d4"), "WDC WD10", "/dev/sdb");
+
+        // Same drive re-plugged on another port keeps its id
+        assert_eq!(registry.resolve("WD-123", None, "WDC WD10", "/dev/sdc"), id);
+        assert_eq!(registry.path_for(&id), Some("/dev/sdc".to_string()));
+
+        // A different drive now sitting at the old path gets a new id
+        let other = registry.resolve("ST-999", None, "ST2000", "/dev/sdb");
+        assert_ne!(other, id);
+        assert_eq!(registry.id_for_path("/dev/sdb"), Some(other));
+    }
+
+    #[test]
+    fn test_serial_less_device_uses_path() {
+        let mut registry = DeviceRegistry::default();
+        let id = registry.resolve("Unknown", None, "Generic USB", "/dev/sdd");
+        assert_eq!(registry.resolve("", None, "Generic USB", "/dev/sdd"), id);
+        assert_eq!(id, compute_device_id(None, None, "/dev/sdd"));
+    }
+}
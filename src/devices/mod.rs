@@ -7,12 +7,22 @@
 pub mod hdd;
 pub mod ssd;
 pub mod nvme;
+pub mod sas;
 pub mod usb;
 pub mod sdcard;
+pub mod emmc;
+pub mod flash;
+pub mod identity;
+pub mod capabilities;
+pub mod watcher;
 
 use std::io;
 use std::sync::{Arc, Mutex};
 use crate::advanced_wiper::{DeviceInfo, WipingProgress, WipingAlgorithm};
+use crate::bad_sectors::BadSectorLog;
+use crate::verification::{verify_device, ExpectedContent, VerificationPolicy, VerificationReport};
+use crate::zoned::ZonedModel;
+use tracing::{info, warn};
 
 /// Common interface for all device types
 pub trait DeviceEraser {
@@ -34,6 +44,59 @@ pub trait DeviceEraser {
     fn get_recommended_algorithms(&self) -> Vec<WipingAlgorithm>;
 }
 
+/// Read a wiped device back according to `policy`, shared by the `verify_erasure` implementations
+pub fn verify_with_policy(
+    device_info: &DeviceInfo,
+    expected: &ExpectedContent,
+    policy: &VerificationPolicy,
+    bad_sectors: &BadSectorLog,
+) -> io::Result<VerificationReport> {
+    let mut device = std::fs::File::open(&device_info.device_path)?;
+    let report = verify_device(&mut device, 0, device_info.size_bytes, expected, policy, bad_sectors, |_, _| {})?;
+
+    info!(
+        "Verified {:.2}% of {} in {} regions at {:.1} MB/s ({} failed)",
+        report.coverage * 100.0,
+        device_info.device_path,
+        report.regions.len(),
+        report.throughput_mbps,
+        report.failed_regions().count()
+    );
+
+    Ok(report)
+}
+
+/// The eraser's recommendations for this particular device. Host-managed
+/// zoned devices reject in-place overwrites, so they keep the drive's own
+/// erase commands and get the zone-aware overwrite instead of the rest.
+pub fn recommended_algorithms(device_info: &DeviceInfo, eraser: &dyn DeviceEraser) -> Vec<WipingAlgorithm> {
+    let recommended = eraser.get_recommended_algorithms();
+    if device_info.zoned != Some(ZonedModel::HostManaged) {
+        return recommended;
+    }
+
+    let mut zoned: Vec<WipingAlgorithm> = recommended
+        .into_iter()
+        .filter(|algorithm| {
+            matches!(
+                algorithm,
+                WipingAlgorithm::AtaSecureErase
+                    | WipingAlgorithm::AtaEnhancedSecureErase
+                    | WipingAlgorithm::NvmeSecureErase
+                    | WipingAlgorithm::NvmeCryptoErase
+                    | WipingAlgorithm::NvmeSanitizeBlockErase
+                    | WipingAlgorithm::NvmeSanitizeCryptoErase
+                    | WipingAlgorithm::ScsiSanitizeOverwrite
+                    | WipingAlgorithm::ScsiSanitizeBlockErase
+                    | WipingAlgorithm::ScsiSanitizeCryptoErase
+                    | WipingAlgorithm::ScsiFormatUnit
+            )
+        })
+        .collect();
+    zoned.push(WipingAlgorithm::ZonedOverwrite);
+    zoned
+}
+
 /// Device type detection and factory
 pub struct DeviceFactory;
 
@@ -44,17 +107,65 @@ impl DeviceFactory {
             crate::advanced_wiper::DeviceType::HDD => Box::new(hdd::HddEraser::new()),
             crate::advanced_wiper::DeviceType::SSD => Box::new(ssd::SsdEraser::new()),
             crate::advanced_wiper::DeviceType::NVMe => Box::new(nvme::NvmeEraser::new()),
+            crate::advanced_wiper::DeviceType::SAS => Box::new(sas::SasEraser::new()),
             crate::advanced_wiper::DeviceType::USBDrive => Box::new(usb::UsbEraser::new()),
             crate::advanced_wiper::DeviceType::SDCard => Box::new(sdcard::SdCardEraser::new()),
-            crate::advanced_wiper::DeviceType::MMC | 
-            crate::advanced_wiper::DeviceType::EMmc => Box::new(sdcard::SdCardEraser::new()),
+            crate::advanced_wiper::DeviceType::MMC => Box::new(sdcard::SdCardEraser::new()),
+            crate::advanced_wiper::DeviceType::EMmc => Box::new(emmc::EmmcEraser::new()),
             crate::advanced_wiper::DeviceType::CompactFlash => Box::new(usb::UsbEraser::new()),
+            // Drive commands do not pass through the controller, only overwrites reach the volume
+            crate::advanced_wiper::DeviceType::VirtualDisk => Box::new(hdd::HddEraser::new()),
             crate::advanced_wiper::DeviceType::Other(_) => Box::new(hdd::HddEraser::new()), // Default fallback
         }
     }
     
+    /// Analyze the device currently known by a stable id, wherever it is now attached
+    pub fn analyze_by_id(device_id: &str) -> io::Result<(DeviceInfo, Box<dyn DeviceEraser>)> {
+        let device_path = identity::device_path_for_id(device_id).ok_or_else(|| {
+            io::Error::new(io::ErrorKind::NotFound, format!("Unknown device id: {}", device_id))
+        })?;
+
+        let (device_info, eraser) = Self::analyze_and_create(&device_path)?;
+        if device_info.device_id != device_id {
+            return Err(io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("Device {} is no longer attached at {}", device_id, device_path)
+            ));
+        }
+
+        Ok((device_info, eraser))
+    }
+
     /// Analyze device and return appropriate eraser
     pub fn analyze_and_create(device_path: &str) -> io::Result<(DeviceInfo, Box<dyn DeviceEraser>)> {
+        // SAS drives are identified by their transport rather than by ATA IDENTIFY
+        if crate::scsi_commands::ScsiInterface::new(device_path).is_sas() {
+            let eraser = sas::SasEraser::new();
+            let mut device_info = eraser.analyze_device(device_path)?;
+            Self::finish_analysis(&mut device_info);
+            return Ok((device_info, Box::new(eraser)));
+        }
+        
+        // RAID virtual disks only accept overwrites; the members need the controller
+        if let Some(virtual_disk) = crate::raid::detect_virtual_disk(device_path) {
+            warn!("{}", virtual_disk.guidance());
+            let eraser = hdd::HddEraser::new();
+            let mut device_info = eraser.analyze_device(device_path)?;
+            device_info.device_type = crate::advanced_wiper::DeviceType::VirtualDisk;
+            device_info.vendor = virtual_disk.vendor.as_str().to_string();
+            device_info.model = virtual_disk.model;
+            Self::finish_analysis(&mut device_info);
+            return Ok((device_info, Box::new(eraser)));
+        }
+        
+        // eMMC parts on an MMC host answer EXT_CSD and take MMC erase commands
+        if crate::mmc_commands::MmcInterface::new(device_path).is_emmc() {
+            let eraser = emmc::EmmcEraser::new();
+            let mut device_info = eraser.analyze_device(device_path)?;
+            Self::finish_analysis(&mut device_info);
+            return Ok((device_info, Box::new(eraser)));
+        }
+        
         // First, do a generic analysis to determine device type
         let temp_eraser = hdd::HddEraser::new();
         let device_info = temp_eraser.analyze_device(device_path)?;
@@ -63,8 +174,23 @@ impl DeviceFactory {
         let eraser = Self::create_eraser(&device_info);
         
         // Re-analyze with the specialized eraser for more detailed info
-        let detailed_info = eraser.analyze_device(device_path)?;
+        let mut detailed_info = eraser.analyze_device(device_path)?;
+        Self::finish_analysis(&mut detailed_info);
         
         Ok((detailed_info, eraser))
     }
+
+    /// Details every eraser shares: the stable id, zones and any encrypted volumes
+    fn finish_analysis(device_info: &mut DeviceInfo) {
+        device_info.device_id = identity::resolve_device_id(device_info);
+        device_info.zoned = crate::zoned::detect_zoned_model(&device_info.device_path);
+        if let Some(model) = device_info.zoned {
+            info!("{} device", model.as_str());
+        }
+        device_info.encryption_status = crate::encryption::detect_encryption(&device_info.device_path)
+            .unwrap_or_default();
+        if device_info.encryption_status.is_encrypted() {
+            info!("Encrypted volumes: {}", device_info.encryption_status.description());
+        }
+    }
 }
\ No newline at end of file
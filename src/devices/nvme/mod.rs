@@ -2,6 +2,12 @@
 //! 
 //! NVMe drives use the NVMe protocol and often support advanced
 //! erasure commands including Secure Erase and Cryptographic Erase.
+//! Format NVM erases the namespace being wiped or all of them (see
+//! `NamespaceScope`); Sanitize erases the whole controller.
+
+mod namespace;
+
+pub use namespace::*;
 
 use std::io;
 use std::sync::{Arc, Mutex};
@@ -9,13 +15,38 @@ use std::time::{Duration, Instant};
 use std::fs::{File, OpenOptions};
 use std::io::{Write, Seek, SeekFrom};
 use std::process::Command;
+use crate::freeze::FreezeStatus;
+use crate::encryption::EncryptionStatus;
 use crate::advanced_wiper::{DeviceInfo, DeviceType, WipingProgress, WipingAlgorithm};
-use crate::devices::DeviceEraser;
+use crate::devices::{verify_with_policy, DeviceEraser};
+use crate::verification::{ExpectedContent, VerificationPolicy};
+use crate::bad_sectors::BadSectorLog;
+use crate::erase_progress::{
+    nvme_format_estimate, read_nvme_sanitize_log, EraseProgressSource, HardwareEraseMonitor, SanitizeState,
+};
+use tracing::{debug, error, info, info_span, warn};
+
+/// Sanitize actions, as the Sanitize Action field encodes them
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NvmeSanitizeAction {
+    BlockErase = 2,
+    CryptoErase = 4,
+}
+
+impl NvmeSanitizeAction {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            NvmeSanitizeAction::BlockErase => "NVMe Sanitize Block Erase",
+            NvmeSanitizeAction::CryptoErase => "NVMe Sanitize Crypto Erase",
+        }
+    }
+}
 
 pub struct NvmeEraser {
     buffer_size: usize,
     verify_after_wipe: bool,
-    namespace_id: u32,
+    verification_policy: VerificationPolicy,
+    namespace_scope: NamespaceScope,
 }
 
 impl NvmeEraser {
@@ -23,15 +54,31 @@ impl NvmeEraser {
         Self {
             buffer_size: 4 * 1024 * 1024, // 4MB buffer for NVMe
             verify_after_wipe: true,
-            namespace_id: 1, // Default namespace
+            verification_policy: VerificationPolicy::default(),
+            namespace_scope: NamespaceScope::Device,
+        }
+    }
+    
+    /// Read the device back according to `verification_policy` after wiping
+    pub fn with_verification_policy(verification_policy: VerificationPolicy) -> Self {
+        Self {
+            verification_policy,
+            ..Self::new()
         }
     }
     
     pub fn with_namespace(namespace_id: u32) -> Self {
         Self {
-            buffer_size: 4 * 1024 * 1024,
-            verify_after_wipe: true,
-            namespace_id,
+            namespace_scope: NamespaceScope::Namespace(namespace_id),
+            ..Self::new()
+        }
+    }
+    
+    /// Format every namespace of the controller, not only the one being wiped
+    pub fn with_all_namespaces() -> Self {
+        Self {
+            namespace_scope: NamespaceScope::All,
+            ..Self::new()
         }
     }
     
@@ -41,7 +88,7 @@ impl NvmeEraser {
         device_info: &DeviceInfo,
         progress_callback: Arc<Mutex<WipingProgress>>,
     ) -> io::Result<()> {
-        println!("🔄 Starting NVMe Secure Erase (User Data)");
+        info!("Starting NVMe Secure Erase (User Data)");
         
         // Update progress
         if let Ok(mut progress) = progress_callback.lock() {
@@ -59,13 +106,13 @@ impl NvmeEraser {
         
         // Execute NVMe secure erase command
         // Note: This would typically use nvme-cli or Windows NVMe APIs
-        println!("🔧 Executing NVMe Format with Secure Erase...");
+        debug!("Executing NVMe Format with Secure Erase...");
         
         // Simulate NVMe secure erase (in real implementation, this would use proper NVMe commands)
         let start_time = Instant::now();
         
         // For Windows, we might use StorNVMe or nvme-cli if available
-        let result = self.execute_nvme_format_command(device_info, false);
+        let result = self.execute_monitored_format(device_info, false, progress_callback.clone());
         
         match result {
             Ok(_) => {
@@ -76,11 +123,11 @@ impl NvmeEraser {
                     progress.speed_mbps = (device_info.size_bytes as f64) / (1024.0 * 1024.0) / start_time.elapsed().as_secs_f64();
                 }
                 
-                println!("✅ NVMe Secure Erase completed");
+                info!("NVMe Secure Erase completed");
                 Ok(())
             }
             Err(e) => {
-                println!("❌ NVMe Secure Erase failed: {}", e);
+                error!("NVMe Secure Erase failed: {}", e);
                 Err(e)
             }
         }
@@ -92,7 +139,7 @@ impl NvmeEraser {
         device_info: &DeviceInfo,
         progress_callback: Arc<Mutex<WipingProgress>>,
     ) -> io::Result<()> {
-        println!("🔄 Starting NVMe Cryptographic Erase");
+        info!("Starting NVMe Cryptographic Erase");
         
         // Update progress
         if let Ok(mut progress) = progress_callback.lock() {
@@ -108,11 +155,11 @@ impl NvmeEraser {
             ));
         }
         
-        println!("🔐 Executing NVMe Cryptographic Erase...");
+        info!("Executing NVMe Cryptographic Erase...");
         let start_time = Instant::now();
         
         // Execute cryptographic erase
-        let result = self.execute_nvme_format_command(device_info, true);
+        let result = self.execute_monitored_format(device_info, true, progress_callback.clone());
         
         match result {
             Ok(_) => {
@@ -123,23 +170,79 @@ impl NvmeEraser {
                     progress.speed_mbps = (device_info.size_bytes as f64) / (1024.0 * 1024.0) / start_time.elapsed().as_secs_f64();
                 }
                 
-                println!("✅ NVMe Cryptographic Erase completed");
+                info!("NVMe Cryptographic Erase completed");
                 Ok(())
             }
             Err(e) => {
-                println!("❌ NVMe Cryptographic Erase failed: {}", e);
+                error!("NVMe Cryptographic Erase failed: {}", e);
                 Err(e)
             }
         }
     }
     
+    /// NVMe Sanitize - erases every namespace of the controller, progress is
+    /// polled from the Sanitize Status log
+    pub fn nvme_sanitize(
+        &self,
+        device_info: &DeviceInfo,
+        action: NvmeSanitizeAction,
+        progress_callback: Arc<Mutex<WipingProgress>>,
+    ) -> io::Result<()> {
+        info!("Starting {}", action.as_str());
+        
+        // Update progress
+        if let Ok(mut progress) = progress_callback.lock() {
+            progress.current_pass = 1;
+            progress.total_passes = 1;
+            progress.current_pattern = action.as_str().to_string();
+        }
+        
+        let controller = controller_path(&device_info.device_path).ok_or_else(|| io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("{} is not an NVMe namespace or controller", device_info.device_path)
+        ))?;
+        self.check_controller_wide_erase(&controller)?;
+        if device_info.nvme_namespaces.len() > 1 {
+            let paths: Vec<&str> = device_info.nvme_namespaces.iter().map(|namespace| namespace.path.as_str()).collect();
+            warn!("Sanitize erases every namespace of {}: {}", controller, paths.join(", "));
+        }
+        
+        let output = Command::new("nvme")
+            .args(["sanitize", &controller, &format!("--sanact={}", action as u8)])
+            .output()
+            .map_err(|e| io::Error::new(io::ErrorKind::Unsupported, format!("nvme-cli is needed for Sanitize: {}", e)))?;
+        if !output.status.success() {
+            return Err(io::Error::other(format!(
+                "NVMe sanitize failed: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            )));
+        }
+        
+        // The command returns at once; the controller sanitizes in the background
+        let monitor = HardwareEraseMonitor::for_wiping_progress(
+            EraseProgressSource::NvmeSanitizeLog(controller.clone()),
+            progress_callback.clone(),
+            device_info.size_bytes,
+        );
+        let result = self.wait_for_sanitize(&controller);
+        drop(monitor);
+        result?;
+        
+        if let Ok(mut progress) = progress_callback.lock() {
+            progress.bytes_processed = device_info.size_bytes;
+            progress.total_bytes = device_info.size_bytes;
+        }
+        info!("{} completed", action.as_str());
+        Ok(())
+    }
+    
     /// NVMe Write Zeroes command
     pub fn nvme_write_zeroes(
         &self,
         device_info: &DeviceInfo,
         progress_callback: Arc<Mutex<WipingProgress>>,
     ) -> io::Result<()> {
-        println!("🔄 Starting NVMe Write Zeroes");
+        info!("Starting NVMe Write Zeroes");
         
         // Update progress
         if let Ok(mut progress) = progress_callback.lock() {
@@ -153,7 +256,7 @@ impl NvmeEraser {
         let blocks_per_command = 65536; // Maximum blocks per Write Zeroes command
         let mut blocks_processed = 0u64;
         
-        println!("🔧 Writing zeroes to {} blocks...", total_blocks);
+        debug!("Writing zeroes to {} blocks...", total_blocks);
         
         while blocks_processed < total_blocks {
             let blocks_remaining = total_blocks - blocks_processed;
@@ -188,13 +291,13 @@ impl NvmeEraser {
                     }
                 }
                 Err(e) => {
-                    println!("❌ Write Zeroes failed at block {}: {}", blocks_processed, e);
+                    error!("Write Zeroes failed at block {}: {}", blocks_processed, e);
                     return Err(e);
                 }
             }
         }
         
-        println!("✅ NVMe Write Zeroes completed");
+        info!("NVMe Write Zeroes completed");
         Ok(())
     }
     
@@ -204,7 +307,7 @@ impl NvmeEraser {
         device_info: &DeviceInfo,
         progress_callback: Arc<Mutex<WipingProgress>>,
     ) -> io::Result<()> {
-        println!("🔄 Starting NVMe Deallocate");
+        info!("Starting NVMe Deallocate");
         
         if !device_info.supports_trim {
             return Err(io::Error::new(
@@ -223,7 +326,7 @@ impl NvmeEraser {
         let start_time = Instant::now();
         let total_blocks = device_info.size_bytes / device_info.sector_size as u64;
         
-        println!("🔧 Deallocating {} blocks...", total_blocks);
+        debug!("Deallocating {} blocks...", total_blocks);
         
         // Execute deallocate command for the entire device
         let result = self.execute_deallocate_command(device_info, 0, total_blocks);
@@ -237,11 +340,11 @@ impl NvmeEraser {
                     progress.speed_mbps = (device_info.size_bytes as f64) / (1024.0 * 1024.0) / start_time.elapsed().as_secs_f64();
                 }
                 
-                println!("✅ NVMe Deallocate completed");
+                info!("NVMe Deallocate completed");
                 Ok(())
             }
             Err(e) => {
-                println!("❌ NVMe Deallocate failed: {}", e);
+                error!("NVMe Deallocate failed: {}", e);
                 Err(e)
             }
         }
@@ -253,7 +356,7 @@ impl NvmeEraser {
         device_info: &DeviceInfo,
         progress_callback: Arc<Mutex<WipingProgress>>,
     ) -> io::Result<()> {
-        println!("🔄 Starting single-pass overwrite for NVMe");
+        info!("Starting single-pass overwrite for NVMe");
         
         // Update progress
         if let Ok(mut progress) = progress_callback.lock() {
@@ -265,23 +368,82 @@ impl NvmeEraser {
         let pattern = self.generate_random_pattern(self.buffer_size);
         self.overwrite_device(device_info, &pattern, progress_callback)?;
         
-        println!("✅ Single-pass overwrite completed for NVMe");
+        info!("Single-pass overwrite completed for NVMe");
         Ok(())
     }
     
+    /// Run the format while publishing progress from the controller's erase-time estimate
+    fn execute_monitored_format(
+        &self,
+        device_info: &DeviceInfo,
+        crypto_erase: bool,
+        progress_callback: Arc<Mutex<WipingProgress>>,
+    ) -> io::Result<()> {
+        let monitor = nvme_format_estimate(&device_info.device_path, crypto_erase).map(|estimate| {
+            HardwareEraseMonitor::for_wiping_progress(
+                EraseProgressSource::Estimate(estimate),
+                progress_callback,
+                device_info.size_bytes,
+            )
+        });
+
+        let result = self.execute_nvme_format_command(device_info, crypto_erase);
+        drop(monitor);
+        result
+    }
+
+    /// Poll the Sanitize Status log until the controller finishes
+    fn wait_for_sanitize(&self, controller: &str) -> io::Result<()> {
+        let mut polls = 0;
+        loop {
+            std::thread::sleep(Duration::from_secs(2));
+            polls += 1;
+            match read_nvme_sanitize_log(controller)?.state {
+                SanitizeState::InProgress => continue,
+                SanitizeState::Completed | SanitizeState::CompletedNoDeallocate => return Ok(()),
+                SanitizeState::Failed => {
+                    return Err(io::Error::other(format!("Sanitize of {} failed", controller)));
+                }
+                // Give the controller a few polls to pick the command up
+                SanitizeState::NeverSanitized | SanitizeState::Unknown(_) if polls < 5 => continue,
+                state => {
+                    return Err(io::Error::other(format!("Sanitize of {} did not start ({:?})", controller, state)));
+                }
+            }
+        }
+    }
+    
+    /// Refuse erases that reach past the device being wiped onto a namespace
+    /// holding the running system
+    fn check_controller_wide_erase(&self, controller: &str) -> io::Result<()> {
+        let disks = crate::platform::get_physical_disks().unwrap_or_default();
+        match disks.iter().find(|disk| disk.is_root_disk && disk.controller.as_deref() == Some(controller)) {
+            Some(system) => Err(io::Error::new(
+                io::ErrorKind::PermissionDenied,
+                format!("{} holds the running system and shares controller {}", system.path, controller)
+            )),
+            None => Ok(()),
+        }
+    }
+    
     /// Execute NVMe format command
     fn execute_nvme_format_command(&self, device_info: &DeviceInfo, crypto_erase: bool) -> io::Result<()> {
-        // This is a simplified implementation
-        // In a real implementation, this would use Windows NVMe APIs or nvme-cli
-        
         let erase_type = if crypto_erase { "2" } else { "1" }; // 1 = User Data Erase, 2 = Cryptographic Erase
         
+        // Format is addressed to the controller with the namespace id
+        let controller = controller_path(&device_info.device_path).unwrap_or_else(|| device_info.device_path.clone());
+        let namespace_id = self.namespace_scope.namespace_id(&device_info.device_path);
+        if self.namespace_scope == NamespaceScope::All {
+            self.check_controller_wide_erase(&controller)?;
+            warn!("Formatting every namespace of {}", controller);
+        }
+        
         // Try to use nvme-cli if available
         let output = Command::new("nvme")
             .args(&[
                 "format",
-                &device_info.device_path,
-                "--namespace-id", &self.namespace_id.to_string(),
+                &controller,
+                "--namespace-id", &namespace_id.to_string(),
                 "--ses", erase_type,
             ])
             .output();
@@ -300,7 +462,7 @@ impl NvmeEraser {
             }
             Err(_) => {
                 // Fallback: simulate the operation
-                println!("ℹ️  nvme-cli not available, simulating NVMe format...");
+                info!("nvme-cli not available, simulating NVMe format...");
                 std::thread::sleep(Duration::from_secs(5)); // Simulate format time
                 Ok(())
             }
@@ -338,11 +500,10 @@ impl NvmeEraser {
         start_block: u64,
         num_blocks: u64,
     ) -> io::Result<()> {
-        // This would typically use NVMe Deallocate command
-        // For now, simulate the operation
-        println!("🔧 Deallocating blocks {} to {}", start_block, start_block + num_blocks - 1);
-        std::thread::sleep(Duration::from_millis(100)); // Simulate deallocate time
-        Ok(())
+        // The block layer sends discards to NVMe drives as Dataset Management Deallocate
+        debug!("Deallocating blocks {} to {}", start_block, start_block + num_blocks - 1);
+        let block_size = device_info.sector_size as u64;
+        crate::devices::flash::deallocate_range(&device_info.device_path, start_block * block_size, num_blocks * block_size)
     }
     
     /// Overwrite device with specific pattern (NVMe-optimized)
@@ -430,10 +591,11 @@ impl NvmeEraser {
 
 impl DeviceEraser for NvmeEraser {
     fn analyze_device(&self, device_path: &str) -> io::Result<DeviceInfo> {
-        println!("🔍 Analyzing NVMe device: {}", device_path);
+        info!("Analyzing NVMe device: {}", device_path);
         
         let (supports_secure_erase, supports_crypto_erase, supports_deallocate) = 
             self.detect_nvme_capabilities(device_path);
+        let supports_opal = crate::opal::detect_opal(device_path).is_some();
         
         // Try to get basic device info
         let device_info = match File::open(device_path) {
@@ -448,17 +610,28 @@ impl DeviceEraser for NvmeEraser {
                     supports_secure_erase,
                     supports_enhanced_secure_erase: supports_secure_erase,
                     supports_crypto_erase,
+                    supports_opal,
                     is_removable: false,
                     vendor: "Unknown".to_string(),
                     model: "Unknown NVMe".to_string(),
                     serial: "Unknown".to_string(),
+                    device_id: String::new(),
+                    freeze_status: FreezeStatus::NotApplicable,
+                    encryption_status: EncryptionStatus::Unknown,
+                    nvme_namespaces: controller_path(device_path)
+                        .map(|controller| list_namespaces(&controller))
+                        .unwrap_or_default(),
+                    zoned: None,
                 }
             }
             Err(e) => return Err(e),
         };
         
-        println!("✅ NVMe analysis complete: {} ({} bytes)", 
+        info!("NVMe analysis complete: {} ({} bytes)", 
                 device_info.model, device_info.size_bytes);
+        if device_info.nvme_namespaces.len() > 1 {
+            info!("Controller has {} namespaces", device_info.nvme_namespaces.len());
+        }
         Ok(device_info)
     }
     
@@ -468,12 +641,24 @@ impl DeviceEraser for NvmeEraser {
         algorithm: WipingAlgorithm,
         progress_callback: Arc<Mutex<WipingProgress>>,
     ) -> io::Result<()> {
-        println!("🚀 Starting NVMe erasure with algorithm: {:?}", algorithm);
+        let _span = info_span!("erase", device = %device_info.device_path, algorithm = ?algorithm).entered();
+        info!("Starting NVMe erasure with algorithm: {:?}", algorithm);
         
         match algorithm {
             WipingAlgorithm::NvmeSecureErase => self.nvme_secure_erase(device_info, progress_callback),
             WipingAlgorithm::NvmeCryptoErase => self.nvme_crypto_erase(device_info, progress_callback),
-            WipingAlgorithm::NistClear => self.nvme_write_zeroes(device_info, progress_callback),
+            WipingAlgorithm::NvmeSanitizeBlockErase => self.nvme_sanitize(device_info, NvmeSanitizeAction::BlockErase, progress_callback),
+            WipingAlgorithm::NvmeSanitizeCryptoErase => self.nvme_sanitize(device_info, NvmeSanitizeAction::CryptoErase, progress_callback),
+            WipingAlgorithm::NistClear => {
+                self.nvme_write_zeroes(device_info, progress_callback.clone())?;
+                // Deallocate so the controller erases the blocks the zeroes replaced
+                if device_info.supports_trim
+                    && let Err(e) = self.nvme_deallocate(device_info, progress_callback)
+                {
+                    warn!("Deallocate after NIST Clear failed: {}", e);
+                }
+                Ok(())
+            }
             WipingAlgorithm::Random => self.single_pass_overwrite(device_info, progress_callback),
             WipingAlgorithm::Zeros => self.nvme_write_zeroes(device_info, progress_callback),
             WipingAlgorithm::Ones => {
@@ -483,13 +668,13 @@ impl DeviceEraser for NvmeEraser {
             _ => {
                 // Default to NVMe Secure Erase if supported, otherwise crypto erase
                 if device_info.supports_secure_erase {
-                    println!("ℹ️  Using NVMe Secure Erase as default");
+                    info!("Using NVMe Secure Erase as default");
                     self.nvme_secure_erase(device_info, progress_callback)
                 } else if device_info.supports_crypto_erase {
-                    println!("ℹ️  Using NVMe Crypto Erase as fallback");
+                    info!("Using NVMe Crypto Erase as fallback");
                     self.nvme_crypto_erase(device_info, progress_callback)
                 } else {
-                    println!("ℹ️  Using single-pass overwrite as fallback");
+                    info!("Using single-pass overwrite as fallback");
                     self.single_pass_overwrite(device_info, progress_callback)
                 }
             }
@@ -500,38 +685,28 @@ impl DeviceEraser for NvmeEraser {
         if !self.verify_after_wipe {
             return Ok(true);
         }
-        
-        println!("🔍 Verifying NVMe erasure...");
-        
-        let mut file = File::open(&device_info.device_path)?;
-        let mut buffer = vec![0u8; self.buffer_size];
-        let mut total_read = 0u64;
-        // For NVMe, sample strategically across the device
-        let sample_size = std::cmp::min(device_info.size_bytes, 1024 * 1024 * 1024); // Sample first 1GB
-        
-        while total_read < sample_size {
-            let bytes_read = std::io::Read::read(&mut file, &mut buffer)?;
-            if bytes_read == 0 {
-                break;
-            }
-            
-            // Check for non-zero bytes
-            if buffer[..bytes_read].iter().any(|&b| b != 0) {
-                println!("⚠️  Found non-zero data during NVMe verification");
-                return Ok(false);
-            }
-            
-            total_read += bytes_read as u64;
+
+        info!("Verifying NVMe erasure ({})...", self.verification_policy.description());
+
+        let report = verify_with_policy(
+            device_info,
+            &ExpectedContent::Pattern(vec![0x00]),
+            &self.verification_policy,
+            &BadSectorLog::new(),
+        )?;
+        if report.passed {
+            info!("NVMe erasure verification passed");
+        } else {
+            warn!("Found residual data during NVMe verification");
         }
-        
-        println!("✅ NVMe erasure verification passed");
-        Ok(true)
+        Ok(report.passed)
     }
     
     fn get_recommended_algorithms(&self) -> Vec<WipingAlgorithm> {
         vec![
             WipingAlgorithm::NvmeSecureErase,    // Primary choice for NVMe
             WipingAlgorithm::NvmeCryptoErase,    // For encrypted NVMe drives
+            WipingAlgorithm::NvmeSanitizeCryptoErase, // Whole controller, all namespaces
             WipingAlgorithm::NistClear,          // NIST approved method
             WipingAlgorithm::Random,             // Single-pass fallback
             WipingAlgorithm::Zeros,              // Simple zero fill
@@ -0,0 +1,135 @@
+//! NVMe namespaces
+//!
+//! One NVMe controller (/dev/nvme0) can expose several namespaces, each its
+//! own block device (/dev/nvme0n1, /dev/nvme0n2, ...). Format NVM erases one
+//! namespace, or every namespace when sent to the broadcast namespace id.
+//! Sanitize always erases the whole controller, including namespaces that are
+//! not attached to this host.
+
+/// Namespace id that addresses every namespace of a controller
+pub const NVME_BROADCAST_NSID: u32 = 0xFFFF_FFFF;
+
+/// A namespace of an NVMe controller
+#[derive(Debug, Clone, PartialEq)]
+pub struct NvmeNamespace {
+    pub namespace_id: u32,
+    /// Block device of the namespace, e.g. /dev/nvme0n1
+    pub path: String,
+    pub size_bytes: u64,
+    pub block_size: u32,
+}
+
+/// Namespaces erased by a Format NVM
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum NamespaceScope {
+    /// The namespace of the device being wiped
+    #[default]
+    Device,
+    Namespace(u32),
+    All,
+}
+
+impl NamespaceScope {
+    /// Namespace id to send the command to, for the namespace at `device_path`
+    pub fn namespace_id(&self, device_path: &str) -> u32 {
+        match self {
+            NamespaceScope::Device => namespace_id_of(device_path).unwrap_or(1),
+            NamespaceScope::Namespace(namespace_id) => *namespace_id,
+            NamespaceScope::All => NVME_BROADCAST_NSID,
+        }
+    }
+}
+
+/// Controller and namespace numbers of a namespace block device name:
+/// "nvme0n2" is namespace 2 of controller 0. Partitions (nvme0n1p1) and the
+/// hidden per-path nodes (nvme0c0n1) are not namespaces.
+pub fn parse_namespace_name(name: &str) -> Option<(u32, u32)> {
+    let (controller, namespace) = name.strip_prefix("nvme")?.split_once('n')?;
+    Some((controller.parse().ok()?, namespace.parse().ok()?))
+}
+
+/// The controller of the namespace at `device_path`: /dev/nvme0n1 is on
+/// /dev/nvme0. A controller path is returned as is.
+pub fn controller_path(device_path: &str) -> Option<String> {
+    let (dir, name) = device_path.rsplit_once('/')?;
+    if let Some((controller, _)) = parse_namespace_name(name) {
+        return Some(format!("{}/nvme{}", dir, controller));
+    }
+    let controller = name.strip_prefix("nvme")?;
+    (!controller.is_empty() && controller.bytes().all(|b| b.is_ascii_digit())).then(|| device_path.to_string())
+}
+
+/// Namespace id of the namespace at `device_path`
+pub fn namespace_id_of(device_path: &str) -> Option<u32> {
+    let name = device_path.rsplit('/').next()?;
+    parse_namespace_name(name).map(|(_, namespace_id)| namespace_id)
+}
+
+/// Namespaces of the controller at `controller_path` attached to this host,
+/// by namespace id; empty where namespaces cannot be listed
+pub fn list_namespaces(controller_path: &str) -> Vec<NvmeNamespace> {
+    #[cfg(target_os = "linux")]
+    {
+        let Some(controller) = controller_path.rsplit('/').next().and_then(|name| name.strip_prefix("nvme")) else {
+            return Vec::new();
+        };
+        let Ok(entries) = std::fs::read_dir("/sys/block") else {
+            return Vec::new();
+        };
+        let read = |sys: &str, attribute: &str| -> u64 {
+            std::fs::read_to_string(format!("{}/{}", sys, attribute))
+                .ok()
+                .and_then(|value| value.trim().parse().ok())
+                .unwrap_or(0)
+        };
+
+        let mut namespaces: Vec<NvmeNamespace> = entries
+            .flatten()
+            .filter_map(|entry| {
+                let name = entry.file_name().to_string_lossy().to_string();
+                let (number, namespace_id) = parse_namespace_name(&name)?;
+                if number.to_string() != controller {
+                    return None;
+                }
+                let sys = format!("/sys/block/{}", name);
+                Some(NvmeNamespace {
+                    namespace_id,
+                    path: format!("/dev/{}", name),
+                    size_bytes: read(&sys, "size") * 512,
+                    block_size: read(&sys, "queue/logical_block_size") as u32,
+                })
+            })
+            .collect();
+        namespaces.sort_by_key(|namespace| namespace.namespace_id);
+        namespaces
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    {
+        let _ = controller_path;
+        Vec::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_namespace_paths() {
+        assert_eq!(parse_namespace_name("nvme0n2"), Some((0, 2)));
+        assert_eq!(parse_namespace_name("nvme10n1"), Some((10, 1)));
+        assert_eq!(parse_namespace_name("nvme0n1p1"), None);
+        assert_eq!(parse_namespace_name("nvme0c0n1"), None);
+        assert_eq!(parse_namespace_name("sda"), None);
+
+        assert_eq!(controller_path("/dev/nvme1n3").as_deref(), Some("/dev/nvme1"));
+        assert_eq!(controller_path("/dev/nvme1").as_deref(), Some("/dev/nvme1"));
+        assert_eq!(controller_path("/dev/nvme0n1p2"), None);
+        assert_eq!(controller_path("/dev/sda"), None);
+
+        assert_eq!(NamespaceScope::Device.namespace_id("/dev/nvme0n3"), 3);
+        assert_eq!(NamespaceScope::Namespace(2).namespace_id("/dev/nvme0n3"), 2);
+        assert_eq!(NamespaceScope::All.namespace_id("/dev/nvme0n3"), NVME_BROADCAST_NSID);
+    }
+}
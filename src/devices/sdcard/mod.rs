@@ -9,14 +9,23 @@ use std::time::{Duration, Instant};
 use std::fs::{File, OpenOptions};
 use std::io::{Write, Seek, SeekFrom};
 use std::process::Command;
+use crate::freeze::FreezeStatus;
+use crate::encryption::EncryptionStatus;
 use crate::advanced_wiper::{DeviceInfo, DeviceType, WipingProgress, WipingAlgorithm};
-use crate::devices::DeviceEraser;
+use crate::devices::{verify_with_policy, DeviceEraser};
+use crate::devices::flash::{self, FlashTechnique, OVERPROVISION_PASSES};
+use crate::verification::{ExpectedContent, VerificationPolicy};
+use crate::bad_sectors::BadSectorLog;
+use tracing::{debug, error, info, info_span, warn};
 
 pub struct SdCardEraser {
     buffer_size: usize,
     verify_after_wipe: bool,
+    verification_policy: VerificationPolicy,
     wear_leveling_aware: bool,
     max_write_cycles: u32,
+    /// Techniques the last `erase_device` call actually applied
+    techniques_used: Mutex<Vec<String>>,
 }
 
 impl SdCardEraser {
@@ -24,36 +33,101 @@ impl SdCardEraser {
         Self {
             buffer_size: 256 * 1024, // 256KB buffer (small to avoid timeouts)
             verify_after_wipe: true,
+            verification_policy: VerificationPolicy::default(),
             wear_leveling_aware: true,
             max_write_cycles: 1000, // Conservative estimate for consumer SD cards
+            techniques_used: Mutex::new(Vec::new()),
+        }
+    }
+    
+    /// Read the device back according to `verification_policy` after wiping
+    pub fn with_verification_policy(verification_policy: VerificationPolicy) -> Self {
+        Self {
+            verification_policy,
+            ..Self::new()
         }
     }
     
     pub fn for_high_endurance() -> Self {
         Self {
             buffer_size: 512 * 1024, // 512KB buffer
-            verify_after_wipe: true,
-            wear_leveling_aware: true,
             max_write_cycles: 10000, // High-endurance cards
+            ..Self::new()
         }
     }
     
     pub fn for_industrial() -> Self {
         Self {
             buffer_size: 1024 * 1024, // 1MB buffer
-            verify_after_wipe: true,
-            wear_leveling_aware: true,
             max_write_cycles: 100000, // Industrial-grade cards
+            ..Self::new()
+        }
+    }
+    
+    /// Techniques applied by the last erasure, for the wipe result and certificate
+    pub fn techniques_used(&self) -> Vec<String> {
+        self.techniques_used.lock().map(|t| t.clone()).unwrap_or_default()
+    }
+    
+    fn record_technique(&self, technique: String) {
+        if let Ok(mut techniques) = self.techniques_used.lock() {
+            techniques.push(technique);
         }
     }
     
+    /// Flash purge: CMD38 erase and discard where available, then two
+    /// full-capacity passes so wear-leveled spare blocks are rewritten as well
+    pub fn flash_purge(
+        &self,
+        device_info: &DeviceInfo,
+        progress_callback: Arc<Mutex<WipingProgress>>,
+    ) -> io::Result<()> {
+        info!("Starting flash purge for SD card");
+        
+        let capabilities = flash::detect_capabilities(&device_info.device_path);
+        let native = flash::native_erase(
+            &device_info.device_path,
+            device_info.size_bytes,
+            device_info.sector_size,
+            &capabilities,
+        );
+        for technique in &native {
+            self.record_technique(technique.description());
+        }
+        
+        if (self.wear_leveling_aware && capabilities.wear_leveling) || native.is_empty() {
+            info!("Wear leveling detected, overwriting full capacity {} times", OVERPROVISION_PASSES);
+            
+            for pass in 1..=OVERPROVISION_PASSES {
+                // Finish on zeros so the read-back verification has a known pattern
+                let (pattern, name) = if pass == OVERPROVISION_PASSES {
+                    (vec![0u8; self.buffer_size], "Zeros")
+                } else {
+                    (self.generate_random_pattern(self.buffer_size), "Random")
+                };
+                
+                if let Ok(mut progress) = progress_callback.lock() {
+                    progress.current_pass = pass;
+                    progress.total_passes = OVERPROVISION_PASSES;
+                    progress.current_pattern = name.to_string();
+                }
+                
+                self.overwrite_device_gentle(device_info, &pattern, progress_callback.clone())?;
+            }
+            self.record_technique(FlashTechnique::FullCapacityPasses(OVERPROVISION_PASSES).description());
+        }
+        
+        info!("Flash purge completed for SD card");
+        Ok(())
+    }
+    
     /// Single-pass random erasure (recommended for SD cards)
     pub fn single_pass_random(
         &self,
         device_info: &DeviceInfo,
         progress_callback: Arc<Mutex<WipingProgress>>,
     ) -> io::Result<()> {
-        println!("🔄 Starting single-pass random erasure for SD card");
+        info!("Starting single-pass random erasure for SD card");
         
         // Update progress
         if let Ok(mut progress) = progress_callback.lock() {
@@ -65,7 +139,7 @@ impl SdCardEraser {
         let pattern = self.generate_random_pattern(self.buffer_size);
         self.overwrite_device_gentle(device_info, &pattern, progress_callback)?;
         
-        println!("✅ Single-pass random erasure completed for SD card");
+        info!("Single-pass random erasure completed for SD card");
         Ok(())
     }
     
@@ -75,7 +149,7 @@ impl SdCardEraser {
         device_info: &DeviceInfo,
         progress_callback: Arc<Mutex<WipingProgress>>,
     ) -> io::Result<()> {
-        println!("🔄 Starting single-pass zero fill for SD card");
+        info!("Starting single-pass zero fill for SD card");
         
         // Update progress
         if let Ok(mut progress) = progress_callback.lock() {
@@ -87,7 +161,7 @@ impl SdCardEraser {
         let pattern = vec![0u8; self.buffer_size];
         self.overwrite_device_gentle(device_info, &pattern, progress_callback)?;
         
-        println!("✅ Single-pass zero fill completed for SD card");
+        info!("Single-pass zero fill completed for SD card");
         Ok(())
     }
     
@@ -97,7 +171,7 @@ impl SdCardEraser {
         device_info: &DeviceInfo,
         progress_callback: Arc<Mutex<WipingProgress>>,
     ) -> io::Result<()> {
-        println!("🔄 Starting SD Card erase command");
+        info!("Starting SD Card erase command");
         
         // Update progress
         if let Ok(mut progress) = progress_callback.lock() {
@@ -115,11 +189,12 @@ impl SdCardEraser {
                     progress.total_bytes = device_info.size_bytes;
                 }
                 
-                println!("✅ SD Card erase command completed");
+                self.record_technique(FlashTechnique::SdErase.description());
+                info!("SD Card erase command completed");
                 Ok(())
             }
             Err(e) => {
-                println!("❌ SD erase command failed, falling back to software erasure: {}", e);
+                error!("SD erase command failed, falling back to software erasure: {}", e);
                 // Fallback to single-pass zero fill
                 self.single_pass_zeros(device_info, progress_callback)
             }
@@ -132,7 +207,7 @@ impl SdCardEraser {
         device_info: &DeviceInfo,
         progress_callback: Arc<Mutex<WipingProgress>>,
     ) -> io::Result<()> {
-        println!("🔄 Starting filesystem-level secure deletion for SD card");
+        info!("Starting filesystem-level secure deletion for SD card");
         
         // Update progress
         if let Ok(mut progress) = progress_callback.lock() {
@@ -171,7 +246,7 @@ impl SdCardEraser {
         // Step 4: Final cleanup
         self.cleanup_filesystem(&device_info.device_path)?;
         
-        println!("✅ Filesystem-level secure deletion completed for SD card");
+        info!("Filesystem-level secure deletion completed for SD card");
         Ok(())
     }
     
@@ -181,7 +256,7 @@ impl SdCardEraser {
         device_info: &DeviceInfo,
         progress_callback: Arc<Mutex<WipingProgress>>,
     ) -> io::Result<()> {
-        println!("🔄 Starting quick format for SD card");
+        info!("Starting quick format for SD card");
         
         // Update progress
         if let Ok(mut progress) = progress_callback.lock() {
@@ -207,7 +282,7 @@ impl SdCardEraser {
                         progress.total_bytes = device_info.size_bytes;
                     }
                     
-                    println!("✅ Quick format completed for SD card");
+                    info!("Quick format completed for SD card");
                     Ok(())
                 } else {
                     let error_msg = String::from_utf8_lossy(&result.stderr);
@@ -218,7 +293,7 @@ impl SdCardEraser {
                 }
             }
             Err(e) => {
-                println!("❌ Format command failed: {}", e);
+                error!("Format command failed: {}", e);
                 Err(e)
             }
         }
@@ -230,7 +305,7 @@ impl SdCardEraser {
         device_info: &DeviceInfo,
         progress_callback: Arc<Mutex<WipingProgress>>,
     ) -> io::Result<()> {
-        println!("🔄 Starting conservative 2-pass erasure for SD card");
+        info!("Starting conservative 2-pass erasure for SD card");
         
         let patterns = [
             vec![0x00; self.buffer_size], // Pass 1: Zeros
@@ -239,7 +314,8 @@ impl SdCardEraser {
         
         for (pass, pattern) in patterns.iter().enumerate() {
             let pass_num = pass + 1;
-            println!("🔄 SD Card Pass {}/2", pass_num);
+            let _pass = info_span!("pass", pass = pass_num, total_passes = 2).entered();
+            info!("SD Card Pass {}/2", pass_num);
             
             // Update progress
             if let Ok(mut progress) = progress_callback.lock() {
@@ -256,42 +332,38 @@ impl SdCardEraser {
             
             // Longer delay between passes for SD card health
             if pass < patterns.len() - 1 {
-                println!("⏳ Pausing between passes for SD card health...");
+                info!("Pausing between passes for SD card health...");
                 std::thread::sleep(Duration::from_secs(5));
             }
         }
         
-        println!("✅ Conservative 2-pass erasure completed for SD card");
+        info!("Conservative 2-pass erasure completed for SD card");
         Ok(())
     }
     
-    /// Execute SD card native erase command
+    /// Execute SD card native erase command (CMD32 / CMD33 / CMD38)
     fn execute_sd_erase_command(&self, device_info: &DeviceInfo) -> io::Result<()> {
-        // This would typically use SD card specific commands
-        // For now, simulate the operation
-        println!("🔧 Executing SD native erase command...");
+        debug!("Executing SD native erase command...");
         
-        // Simulate erase time based on card size
-        let erase_time_ms = std::cmp::min(5000, (device_info.size_bytes / (1024 * 1024)) as u64 * 10);
-        std::thread::sleep(Duration::from_millis(erase_time_ms));
+        flash::sd_erase(&device_info.device_path, device_info.size_bytes, device_info.sector_size)?;
         
-        println!("✅ SD native erase completed");
+        info!("SD native erase completed");
         Ok(())
     }
     
     /// Analyze filesystem on SD card
     fn analyze_filesystem(&self, device_path: &str) -> io::Result<()> {
-        println!("🔍 Analyzing filesystem on SD card...");
+        info!("Analyzing filesystem on SD card...");
         std::thread::sleep(Duration::from_millis(500));
-        println!("✅ Filesystem analysis completed");
+        info!("Filesystem analysis completed");
         Ok(())
     }
     
     /// Secure delete all files
     fn secure_delete_files(&self, device_path: &str) -> io::Result<()> {
-        println!("🗑️  Securely deleting files on SD card...");
+        info!("Securely deleting files on SD card...");
         std::thread::sleep(Duration::from_secs(2));
-        println!("✅ File deletion completed");
+        info!("File deletion completed");
         Ok(())
     }
     
@@ -301,7 +373,7 @@ impl SdCardEraser {
         device_path: &str,
         progress_callback: Arc<Mutex<WipingProgress>>,
     ) -> io::Result<()> {
-        println!("🔧 Filling free space on SD card (gentle mode)...");
+        debug!("Filling free space on SD card (gentle mode)...");
         
         let drive_letter = self.extract_drive_letter(device_path)?;
         let fill_file_path = format!("{}:\\temp_sd_fill.tmp", drive_letter);
@@ -337,11 +409,11 @@ impl SdCardEraser {
                     }
                 }
                 Err(e) if e.kind() == io::ErrorKind::StorageFull => {
-                    println!("✅ Free space filled gently ({} bytes)", bytes_written);
+                    info!("Free space filled gently ({} bytes)", bytes_written);
                     break;
                 }
                 Err(e) => {
-                    println!("❌ Error filling free space: {}", e);
+                    error!("Error filling free space: {}", e);
                     break;
                 }
             }
@@ -356,9 +428,9 @@ impl SdCardEraser {
     
     /// Cleanup filesystem
     fn cleanup_filesystem(&self, device_path: &str) -> io::Result<()> {
-        println!("🧹 Cleaning up SD card filesystem...");
+        info!("Cleaning up SD card filesystem...");
         std::thread::sleep(Duration::from_millis(500));
-        println!("✅ Filesystem cleanup completed");
+        info!("Filesystem cleanup completed");
         Ok(())
     }
     
@@ -454,21 +526,25 @@ impl SdCardEraser {
     }
     
     /// Detect SD card type and capabilities
-    fn detect_sd_capabilities(&self, device_path: &str) -> (bool, String) {
-        // SD cards typically don't support hardware secure erase
-        // but may have native erase commands
-        let supports_native_erase = true; // Most SD cards support native erase
-        let card_type = "Standard SD".to_string(); // Could be SD, SDHC, SDXC, etc.
+    fn detect_sd_capabilities(&self, device_path: &str) -> (bool, bool, String) {
+        // The native erase command is only reachable when the card sits on an
+        // MMC host; USB card readers hide it behind a SCSI bridge
+        let capabilities = flash::detect_capabilities(device_path);
+        let card_type = if capabilities.sd_erase {
+            "SD (MMC host)".to_string()
+        } else {
+            "Standard SD".to_string() // Could be SD, SDHC, SDXC, etc.
+        };
         
-        (supports_native_erase, card_type)
+        (capabilities.sd_erase, capabilities.discard, card_type)
     }
 }
 
 impl DeviceEraser for SdCardEraser {
     fn analyze_device(&self, device_path: &str) -> io::Result<DeviceInfo> {
-        println!("🔍 Analyzing SD card: {}", device_path);
+        info!("Analyzing SD card: {}", device_path);
         
-        let (supports_native_erase, card_type) = self.detect_sd_capabilities(device_path);
+        let (supports_native_erase, supports_trim, card_type) = self.detect_sd_capabilities(device_path);
         
         // Try to get basic device info
         let device_info = match File::open(device_path) {
@@ -479,20 +555,26 @@ impl DeviceEraser for SdCardEraser {
                     device_type: DeviceType::SDCard,
                     size_bytes: metadata.len(),
                     sector_size: 512, // Standard for SD cards
-                    supports_trim: false, // SD cards don't typically support TRIM
+                    supports_trim, // Discard maps to the card's ERASE / TRIM
                     supports_secure_erase: supports_native_erase,
                     supports_enhanced_secure_erase: false,
                     supports_crypto_erase: false, // Rare in consumer SD cards
+                    supports_opal: false,
                     is_removable: true,
                     vendor: "Unknown".to_string(),
                     model: card_type,
                     serial: "Unknown".to_string(),
+                    device_id: String::new(),
+                    freeze_status: FreezeStatus::NotApplicable,
+                    encryption_status: EncryptionStatus::Unknown,
+                    nvme_namespaces: Vec::new(),
+                    zoned: None,
                 }
             }
             Err(e) => return Err(e),
         };
         
-        println!("✅ SD card analysis complete: {} ({} bytes)", 
+        info!("SD card analysis complete: {} ({} bytes)", 
                 device_info.model, device_info.size_bytes);
         Ok(device_info)
     }
@@ -503,9 +585,15 @@ impl DeviceEraser for SdCardEraser {
         algorithm: WipingAlgorithm,
         progress_callback: Arc<Mutex<WipingProgress>>,
     ) -> io::Result<()> {
-        println!("🚀 Starting SD card erasure with algorithm: {:?}", algorithm);
+        let _span = info_span!("erase", device = %device_info.device_path, algorithm = ?algorithm).entered();
+        info!("Starting SD card erasure with algorithm: {:?}", algorithm);
+        
+        if let Ok(mut techniques) = self.techniques_used.lock() {
+            techniques.clear();
+        }
         
-        match algorithm {
+        let result = match algorithm {
+            WipingAlgorithm::NistPurge => self.flash_purge(device_info, progress_callback),
             WipingAlgorithm::Random => self.single_pass_random(device_info, progress_callback),
             WipingAlgorithm::Zeros => self.single_pass_zeros(device_info, progress_callback),
             WipingAlgorithm::QuickFormat => self.quick_format(device_info, progress_callback),
@@ -519,54 +607,46 @@ impl DeviceEraser for SdCardEraser {
             _ => {
                 // Default to native erase if supported, otherwise single-pass random
                 if device_info.supports_secure_erase {
-                    println!("ℹ️  Using SD native erase as default");
+                    info!("Using SD native erase as default");
                     self.sd_erase_command(device_info, progress_callback)
                 } else {
-                    println!("ℹ️  Using single-pass random as default for SD card");
+                    info!("Using single-pass random as default for SD card");
                     self.single_pass_random(device_info, progress_callback)
                 }
             }
+        };
+        
+        if result.is_ok() && self.techniques_used().is_empty() {
+            self.record_technique(format!("Software overwrite ({:?})", algorithm));
         }
+        result
     }
     
     fn verify_erasure(&self, device_info: &DeviceInfo) -> io::Result<bool> {
         if !self.verify_after_wipe {
             return Ok(true);
         }
-        
-        println!("🔍 Verifying SD card erasure (gentle verification)...");
-        
-        let mut file = File::open(&device_info.device_path)?;
-        let mut buffer = vec![0u8; self.buffer_size];
-        let mut total_read = 0u64;
-        // For SD cards, very conservative sampling to minimize wear
-        let sample_size = std::cmp::min(device_info.size_bytes, 10 * 1024 * 1024); // Sample first 10MB only
-        
-        while total_read < sample_size {
-            let bytes_read = std::io::Read::read(&mut file, &mut buffer)?;
-            if bytes_read == 0 {
-                break;
-            }
-            
-            // Check for non-zero bytes
-            if buffer[..bytes_read].iter().any(|&b| b != 0) {
-                println!("⚠️  Found non-zero data during SD card verification");
-                return Ok(false);
-            }
-            
-            total_read += bytes_read as u64;
-            
-            // Gentle pause during verification
-            std::thread::sleep(Duration::from_millis(10));
+
+        info!("Verifying SD card erasure ({})...", self.verification_policy.description());
+
+        let report = verify_with_policy(
+            device_info,
+            &ExpectedContent::Pattern(vec![0x00]),
+            &self.verification_policy,
+            &BadSectorLog::new(),
+        )?;
+        if report.passed {
+            info!("SD card erasure verification passed");
+        } else {
+            warn!("Found residual data during SD card verification");
         }
-        
-        println!("✅ SD card erasure verification passed");
-        Ok(true)
+        Ok(report.passed)
     }
     
     fn get_recommended_algorithms(&self) -> Vec<WipingAlgorithm> {
         vec![
             WipingAlgorithm::Random,           // Primary choice (single pass, minimal wear)
+            WipingAlgorithm::NistPurge,        // CMD38 / discard + two full-capacity passes
             WipingAlgorithm::Zeros,            // Simple zero fill
             WipingAlgorithm::QuickFormat,      // Quick format (filesystem level)
             WipingAlgorithm::FileSystemWipe,   // File-level secure deletion
@@ -9,14 +9,20 @@ use std::sync::{Arc, Mutex};
 use std::time::{Duration, Instant};
 use std::fs::{File, OpenOptions};
 use std::io::{Write, Seek, SeekFrom};
-use std::process::Command;
+use crate::freeze::{FreezeMitigation, FreezeStatus};
+use crate::encryption::EncryptionStatus;
 use crate::advanced_wiper::{DeviceInfo, DeviceType, WipingProgress, WipingAlgorithm};
-use crate::devices::DeviceEraser;
+use crate::devices::{verify_with_policy, DeviceEraser};
+use crate::verification::{ExpectedContent, VerificationPolicy};
+use crate::bad_sectors::BadSectorLog;
 use crate::ata_commands::AtaInterface;
+use crate::erase_progress::{ata_erase_estimate, EraseProgressSource, HardwareEraseMonitor};
+use tracing::{debug, error, info, info_span, warn};
 
 pub struct SsdEraser {
     buffer_size: usize,
     verify_after_wipe: bool,
+    verification_policy: VerificationPolicy,
     use_trim: bool,
 }
 
@@ -25,14 +31,24 @@ impl SsdEraser {
         Self {
             buffer_size: 2 * 1024 * 1024, // 2MB buffer for SSDs
             verify_after_wipe: true,
+            verification_policy: VerificationPolicy::default(),
             use_trim: true,
         }
     }
     
+    /// Read the device back according to `verification_policy` after wiping
+    pub fn with_verification_policy(verification_policy: VerificationPolicy) -> Self {
+        Self {
+            verification_policy,
+            ..Self::new()
+        }
+    }
+    
     pub fn with_trim(use_trim: bool) -> Self {
         Self {
             buffer_size: 2 * 1024 * 1024,
             verify_after_wipe: true,
+            verification_policy: VerificationPolicy::default(),
             use_trim,
         }
     }
@@ -44,7 +60,7 @@ impl SsdEraser {
         enhanced: bool,
         progress_callback: Arc<Mutex<WipingProgress>>,
     ) -> io::Result<()> {
-        println!("🔄 Starting ATA Secure Erase for SSD (Enhanced: {})", enhanced);
+        info!("Starting ATA Secure Erase for SSD (Enhanced: {})", enhanced);
         
         // Update progress
         if let Ok(mut progress) = progress_callback.lock() {
@@ -67,8 +83,28 @@ impl SsdEraser {
                     ));
                 }
                 
-                println!("🔧 Performing ATA Secure Erase...");
-                ata.security_erase(enhanced)?;
+                // A frozen drive rejects SECURITY ERASE UNIT; try to clear the freeze first
+                if drive_info.security_frozen {
+                    let outcome = FreezeMitigation::new().unfreeze(&device_info.device_path)?;
+                    if !outcome.status.allows_security_commands() {
+                        return Err(io::Error::new(
+                            io::ErrorKind::PermissionDenied,
+                            format!("ATA Secure Erase unavailable: {}", FreezeStatus::Frozen.guidance())
+                        ));
+                    }
+                }
+                
+                debug!("Performing ATA Secure Erase...");
+                let monitor = ata_erase_estimate(&ata, enhanced).map(|estimate| {
+                    HardwareEraseMonitor::for_wiping_progress(
+                        EraseProgressSource::Estimate(estimate),
+                        progress_callback.clone(),
+                        device_info.size_bytes,
+                    )
+                });
+                let result = ata.security_erase(enhanced);
+                drop(monitor);
+                result?;
                 
                 // Update progress to completion
                 if let Ok(mut progress) = progress_callback.lock() {
@@ -76,11 +112,11 @@ impl SsdEraser {
                     progress.total_bytes = device_info.size_bytes;
                 }
                 
-                println!("✅ ATA Secure Erase completed for SSD");
+                info!("ATA Secure Erase completed for SSD");
                 Ok(())
             }
             Err(e) => {
-                println!("❌ ATA interface failed: {}", e);
+                error!("ATA interface failed: {}", e);
                 Err(io::Error::new(
                     io::ErrorKind::Other,
                     format!("ATA Secure Erase failed: {}", e)
@@ -95,7 +131,7 @@ impl SsdEraser {
         device_info: &DeviceInfo,
         progress_callback: Arc<Mutex<WipingProgress>>,
     ) -> io::Result<()> {
-        println!("🔄 Starting Cryptographic Erase for SSD");
+        info!("Starting Cryptographic Erase for SSD");
         
         // Update progress
         if let Ok(mut progress) = progress_callback.lock() {
@@ -111,12 +147,17 @@ impl SsdEraser {
             ));
         }
         
-        // For Windows, we would use Microsoft's Encrypted Hard Drive API
-        // This is a simplified implementation
-        println!("🔐 Performing cryptographic key rotation...");
-        
-        // Simulate crypto erase process
-        std::thread::sleep(Duration::from_secs(2));
+        if device_info.supports_opal {
+            // TCG Opal: regenerate the media encryption key
+            crate::opal::OpalInterface::new(&device_info.device_path).crypto_erase(None)?;
+        } else {
+            // For Windows, we would use Microsoft's Encrypted Hard Drive API
+            // This is a simplified implementation
+            info!("Performing cryptographic key rotation...");
+            
+            // Simulate crypto erase process
+            std::thread::sleep(Duration::from_secs(2));
+        }
         
         // Update progress to completion
         if let Ok(mut progress) = progress_callback.lock() {
@@ -124,7 +165,7 @@ impl SsdEraser {
             progress.total_bytes = device_info.size_bytes;
         }
         
-        println!("✅ Cryptographic erase completed for SSD");
+        info!("Cryptographic erase completed for SSD");
         Ok(())
     }
     
@@ -134,7 +175,7 @@ impl SsdEraser {
         device_info: &DeviceInfo,
         progress_callback: Arc<Mutex<WipingProgress>>,
     ) -> io::Result<()> {
-        println!("🔄 Starting TRIM-based erase for SSD");
+        info!("Starting TRIM-based erase for SSD");
         
         if !device_info.supports_trim {
             return Err(io::Error::new(
@@ -150,40 +191,21 @@ impl SsdEraser {
             progress.current_pattern = "TRIM Command".to_string();
         }
         
-        // On Windows, we can use fsutil to trim
-        let output = Command::new("fsutil")
-            .args(&["behavior", "set", "DisableDeleteNotify", "0"])
-            .output();
-            
-        match output {
-            Ok(_) => {
-                println!("🔧 TRIM enabled, performing full device TRIM...");
-                
-                // Perform TRIM operation
-                let trim_output = Command::new("fsutil")
-                    .args(&["behavior", "query", "DisableDeleteNotify"])
-                    .output();
-                    
-                match trim_output {
-                    Ok(_) => {
-                        // Update progress to completion
-                        if let Ok(mut progress) = progress_callback.lock() {
-                            progress.bytes_processed = device_info.size_bytes;
-                            progress.total_bytes = device_info.size_bytes;
-                        }
-                        
-                        println!("✅ TRIM-based erase completed for SSD");
-                        Ok(())
-                    }
-                    Err(e) => {
-                        println!("❌ TRIM operation failed: {}", e);
-                        Err(io::Error::new(io::ErrorKind::Other, "TRIM operation failed"))
-                    }
+        debug!("Performing full device TRIM...");
+        match crate::devices::flash::deallocate_range(&device_info.device_path, 0, device_info.size_bytes) {
+            Ok(()) => {
+                // Update progress to completion
+                if let Ok(mut progress) = progress_callback.lock() {
+                    progress.bytes_processed = device_info.size_bytes;
+                    progress.total_bytes = device_info.size_bytes;
                 }
+                
+                info!("TRIM-based erase completed for SSD");
+                Ok(())
             }
             Err(e) => {
-                println!("❌ Failed to enable TRIM: {}", e);
-                Err(io::Error::new(io::ErrorKind::Other, "Failed to enable TRIM"))
+                error!("TRIM operation failed: {}", e);
+                Err(e)
             }
         }
     }
@@ -194,7 +216,7 @@ impl SsdEraser {
         device_info: &DeviceInfo,
         progress_callback: Arc<Mutex<WipingProgress>>,
     ) -> io::Result<()> {
-        println!("🔄 Starting single-pass overwrite for SSD");
+        info!("Starting single-pass overwrite for SSD");
         
         // Update progress
         if let Ok(mut progress) = progress_callback.lock() {
@@ -208,7 +230,7 @@ impl SsdEraser {
         
         // Perform TRIM after overwrite if supported
         if self.use_trim && device_info.supports_trim {
-            println!("🔧 Following up with TRIM command...");
+            debug!("Following up with TRIM command...");
             let _ = self.trim_erase(device_info, Arc::new(Mutex::new(
                 crate::advanced_wiper::WipingProgress {
                     algorithm: WipingAlgorithm::Random,
@@ -223,7 +245,7 @@ impl SsdEraser {
             )));
         }
         
-        println!("✅ Single-pass overwrite completed for SSD");
+        info!("Single-pass overwrite completed for SSD");
         Ok(())
     }
     
@@ -233,7 +255,7 @@ impl SsdEraser {
         device_info: &DeviceInfo,
         progress_callback: Arc<Mutex<WipingProgress>>,
     ) -> io::Result<()> {
-        println!("🔄 Starting NIST Clear for SSD");
+        info!("Starting NIST Clear for SSD");
         
         // Update progress
         if let Ok(mut progress) = progress_callback.lock() {
@@ -248,7 +270,7 @@ impl SsdEraser {
         
         // Verify the erasure
         if self.verify_after_wipe {
-            println!("🔍 Verifying NIST Clear...");
+            info!("Verifying NIST Clear...");
             let verified = self.verify_erasure(device_info)?;
             if !verified {
                 return Err(io::Error::new(
@@ -258,7 +280,15 @@ impl SsdEraser {
             }
         }
         
-        println!("✅ NIST Clear completed for SSD");
+        // TRIM after verification, which reads back the zeros
+        if self.use_trim && device_info.supports_trim {
+            debug!("Following up with TRIM command...");
+            if let Err(e) = self.trim_erase(device_info, progress_callback) {
+                warn!("TRIM after NIST Clear failed: {}", e);
+            }
+        }
+        
+        info!("NIST Clear completed for SSD");
         Ok(())
     }
     
@@ -336,7 +366,11 @@ impl SsdEraser {
 
 impl DeviceEraser for SsdEraser {
     fn analyze_device(&self, device_path: &str) -> io::Result<DeviceInfo> {
-        println!("🔍 Analyzing SSD device: {}", device_path);
+        info!("Analyzing SSD device: {}", device_path);
+        
+        // Level 0 Discovery tells us authoritatively whether this is an SED
+        let opal = crate::opal::detect_opal(device_path);
+        let opal_crypto_erase = opal.as_ref().map(|d| d.supports_crypto_erase()).unwrap_or(false);
         
         // Try to get detailed info via ATA interface
         let device_info = match AtaInterface::new(device_path) {
@@ -350,11 +384,17 @@ impl DeviceEraser for SsdEraser {
                     supports_trim: true, // Most modern SSDs support TRIM
                     supports_secure_erase: drive_info.security_supported,
                     supports_enhanced_secure_erase: drive_info.security_supported,
-                    supports_crypto_erase: self.detect_crypto_support(&drive_info.model),
+                    supports_crypto_erase: opal_crypto_erase || self.detect_crypto_support(&drive_info.model),
+                    supports_opal: opal.is_some(),
                     is_removable: false,
                     vendor: "Unknown".to_string(),
                     model: drive_info.model,
                     serial: drive_info.serial,
+                    device_id: String::new(),
+                    freeze_status: FreezeStatus::from_ata(drive_info.security_supported, drive_info.security_frozen),
+                    encryption_status: EncryptionStatus::Unknown,
+                    nvme_namespaces: Vec::new(),
+                    zoned: None,
                 }
             }
             Err(_) => {
@@ -370,16 +410,22 @@ impl DeviceEraser for SsdEraser {
                     supports_trim: true,
                     supports_secure_erase: false,
                     supports_enhanced_secure_erase: false,
-                    supports_crypto_erase: false,
+                    supports_crypto_erase: opal_crypto_erase,
+                    supports_opal: opal.is_some(),
                     is_removable: false,
                     vendor: "Unknown".to_string(),
                     model: "Unknown SSD".to_string(),
                     serial: "Unknown".to_string(),
+                    device_id: String::new(),
+                    freeze_status: FreezeStatus::Unknown,
+                    encryption_status: EncryptionStatus::Unknown,
+                    nvme_namespaces: Vec::new(),
+                    zoned: None,
                 }
             }
         };
         
-        println!("✅ SSD analysis complete: {} ({} bytes)", 
+        info!("SSD analysis complete: {} ({} bytes)", 
                 device_info.model, device_info.size_bytes);
         Ok(device_info)
     }
@@ -390,7 +436,8 @@ impl DeviceEraser for SsdEraser {
         algorithm: WipingAlgorithm,
         progress_callback: Arc<Mutex<WipingProgress>>,
     ) -> io::Result<()> {
-        println!("🚀 Starting SSD erasure with algorithm: {:?}", algorithm);
+        let _span = info_span!("erase", device = %device_info.device_path, algorithm = ?algorithm).entered();
+        info!("Starting SSD erasure with algorithm: {:?}", algorithm);
         
         match algorithm {
             WipingAlgorithm::AtaSecureErase => self.ata_secure_erase(device_info, false, progress_callback),
@@ -406,13 +453,17 @@ impl DeviceEraser for SsdEraser {
                 let pattern = vec![0xFFu8; self.buffer_size];
                 self.overwrite_device(device_info, &pattern, progress_callback)
             },
+            WipingAlgorithm::NistPurge if device_info.supports_opal && device_info.supports_crypto_erase => {
+                info!("Using TCG Opal crypto erase for NIST Purge");
+                self.crypto_erase(device_info, progress_callback)
+            },
             _ => {
                 // Default to ATA Secure Erase for SSDs if supported, otherwise single pass
                 if device_info.supports_secure_erase {
-                    println!("ℹ️  Using ATA Secure Erase as default for SSD");
+                    info!("Using ATA Secure Erase as default for SSD");
                     self.ata_secure_erase(device_info, false, progress_callback)
                 } else {
-                    println!("ℹ️  Using single-pass overwrite as fallback for SSD");
+                    info!("Using single-pass overwrite as fallback for SSD");
                     self.single_pass_overwrite(device_info, progress_callback)
                 }
             }
@@ -423,32 +474,21 @@ impl DeviceEraser for SsdEraser {
         if !self.verify_after_wipe {
             return Ok(true);
         }
-        
-        println!("🔍 Verifying SSD erasure...");
-        
-        let mut file = File::open(&device_info.device_path)?;
-        let mut buffer = vec![0u8; self.buffer_size];
-        let mut total_read = 0u64;
-        // For SSDs, sample more strategically due to wear leveling
-        let sample_size = std::cmp::min(device_info.size_bytes, 500 * 1024 * 1024); // Sample first 500MB
-        
-        while total_read < sample_size {
-            let bytes_read = std::io::Read::read(&mut file, &mut buffer)?;
-            if bytes_read == 0 {
-                break;
-            }
-            
-            // Check for non-zero bytes
-            if buffer[..bytes_read].iter().any(|&b| b != 0) {
-                println!("⚠️  Found non-zero data during SSD verification");
-                return Ok(false);
-            }
-            
-            total_read += bytes_read as u64;
+
+        info!("Verifying SSD erasure ({})...", self.verification_policy.description());
+
+        let report = verify_with_policy(
+            device_info,
+            &ExpectedContent::Pattern(vec![0x00]),
+            &self.verification_policy,
+            &BadSectorLog::new(),
+        )?;
+        if report.passed {
+            info!("SSD erasure verification passed");
+        } else {
+            warn!("Found residual data during SSD verification");
         }
-        
-        println!("✅ SSD erasure verification passed");
-        Ok(true)
+        Ok(report.passed)
     }
     
     fn get_recommended_algorithms(&self) -> Vec<WipingAlgorithm> {
@@ -9,13 +9,22 @@ use std::time::{Duration, Instant};
 use std::fs::{File, OpenOptions};
 use std::io::{Write, Seek, SeekFrom};
 use std::process::Command;
+use crate::freeze::FreezeStatus;
+use crate::encryption::EncryptionStatus;
 use crate::advanced_wiper::{DeviceInfo, DeviceType, WipingProgress, WipingAlgorithm};
-use crate::devices::DeviceEraser;
+use crate::devices::{verify_with_policy, DeviceEraser};
+use crate::devices::flash::{self, FlashTechnique, OVERPROVISION_PASSES};
+use crate::verification::{ExpectedContent, VerificationPolicy};
+use crate::bad_sectors::BadSectorLog;
+use tracing::{debug, error, info, info_span, warn};
 
 pub struct UsbEraser {
     buffer_size: usize,
     verify_after_wipe: bool,
+    verification_policy: VerificationPolicy,
     conservative_approach: bool,
+    /// Techniques the last `erase_device` call actually applied
+    techniques_used: Mutex<Vec<String>>,
 }
 
 impl UsbEraser {
@@ -23,33 +32,99 @@ impl UsbEraser {
         Self {
             buffer_size: 512 * 1024, // 512KB buffer for USB (smaller to avoid timeout)
             verify_after_wipe: true,
+            verification_policy: VerificationPolicy::default(),
             conservative_approach: true, // Protect USB drive lifespan
+            techniques_used: Mutex::new(Vec::new()),
+        }
+    }
+    
+    /// Read the device back according to `verification_policy` after wiping
+    pub fn with_verification_policy(verification_policy: VerificationPolicy) -> Self {
+        Self {
+            verification_policy,
+            ..Self::new()
         }
     }
     
     pub fn with_buffer_size(buffer_size: usize) -> Self {
         Self {
             buffer_size,
-            verify_after_wipe: true,
-            conservative_approach: true,
+            ..Self::new()
         }
     }
     
     pub fn aggressive_mode() -> Self {
         Self {
             buffer_size: 1024 * 1024, // 1MB buffer
-            verify_after_wipe: true,
             conservative_approach: false,
+            ..Self::new()
         }
     }
     
+    /// Techniques applied by the last erasure, for the wipe result and certificate
+    pub fn techniques_used(&self) -> Vec<String> {
+        self.techniques_used.lock().map(|t| t.clone()).unwrap_or_default()
+    }
+    
+    fn record_technique(&self, technique: String) {
+        if let Ok(mut techniques) = self.techniques_used.lock() {
+            techniques.push(technique);
+        }
+    }
+    
+    /// Flash purge: native discard where the controller supports it, then two
+    /// full-capacity passes so wear-leveled spare blocks are rewritten as well
+    pub fn flash_purge(
+        &self,
+        device_info: &DeviceInfo,
+        progress_callback: Arc<Mutex<WipingProgress>>,
+    ) -> io::Result<()> {
+        info!("Starting flash purge for USB drive");
+        
+        let capabilities = flash::detect_capabilities(&device_info.device_path);
+        let native = flash::native_erase(
+            &device_info.device_path,
+            device_info.size_bytes,
+            device_info.sector_size,
+            &capabilities,
+        );
+        for technique in &native {
+            self.record_technique(technique.description());
+        }
+        
+        if capabilities.wear_leveling || native.is_empty() {
+            info!("Wear leveling detected, overwriting full capacity {} times", OVERPROVISION_PASSES);
+            
+            for pass in 1..=OVERPROVISION_PASSES {
+                // Finish on zeros so the read-back verification has a known pattern
+                let (pattern, name) = if pass == OVERPROVISION_PASSES {
+                    (vec![0u8; self.buffer_size], "Zeros")
+                } else {
+                    (self.generate_random_pattern(self.buffer_size), "Random")
+                };
+                
+                if let Ok(mut progress) = progress_callback.lock() {
+                    progress.current_pass = pass;
+                    progress.total_passes = OVERPROVISION_PASSES;
+                    progress.current_pattern = name.to_string();
+                }
+                
+                self.overwrite_device(device_info, &pattern, progress_callback.clone())?;
+            }
+            self.record_technique(FlashTechnique::FullCapacityPasses(OVERPROVISION_PASSES).description());
+        }
+        
+        info!("Flash purge completed for USB drive");
+        Ok(())
+    }
+    
     /// Single-pass random erasure (recommended for USB drives)
     pub fn single_pass_random(
         &self,
         device_info: &DeviceInfo,
         progress_callback: Arc<Mutex<WipingProgress>>,
     ) -> io::Result<()> {
-        println!("🔄 Starting single-pass random erasure for USB drive");
+        info!("Starting single-pass random erasure for USB drive");
         
         // Update progress
         if let Ok(mut progress) = progress_callback.lock() {
@@ -61,7 +136,7 @@ impl UsbEraser {
         let pattern = self.generate_random_pattern(self.buffer_size);
         self.overwrite_device(device_info, &pattern, progress_callback)?;
         
-        println!("✅ Single-pass random erasure completed for USB drive");
+        info!("Single-pass random erasure completed for USB drive");
         Ok(())
     }
     
@@ -71,7 +146,7 @@ impl UsbEraser {
         device_info: &DeviceInfo,
         progress_callback: Arc<Mutex<WipingProgress>>,
     ) -> io::Result<()> {
-        println!("🔄 Starting single-pass zero fill for USB drive");
+        info!("Starting single-pass zero fill for USB drive");
         
         // Update progress
         if let Ok(mut progress) = progress_callback.lock() {
@@ -83,7 +158,7 @@ impl UsbEraser {
         let pattern = vec![0u8; self.buffer_size];
         self.overwrite_device(device_info, &pattern, progress_callback)?;
         
-        println!("✅ Single-pass zero fill completed for USB drive");
+        info!("Single-pass zero fill completed for USB drive");
         Ok(())
     }
     
@@ -93,7 +168,7 @@ impl UsbEraser {
         device_info: &DeviceInfo,
         progress_callback: Arc<Mutex<WipingProgress>>,
     ) -> io::Result<()> {
-        println!("🔄 Starting quick format + overwrite for USB drive");
+        info!("Starting quick format + overwrite for USB drive");
         
         // Update progress
         if let Ok(mut progress) = progress_callback.lock() {
@@ -115,7 +190,7 @@ impl UsbEraser {
         let pattern = self.generate_random_pattern(self.buffer_size);
         self.overwrite_device(device_info, &pattern, progress_callback)?;
         
-        println!("✅ Quick format + overwrite completed for USB drive");
+        info!("Quick format + overwrite completed for USB drive");
         Ok(())
     }
     
@@ -126,11 +201,11 @@ impl UsbEraser {
         progress_callback: Arc<Mutex<WipingProgress>>,
     ) -> io::Result<()> {
         if self.conservative_approach {
-            println!("ℹ️  Conservative mode enabled, using single-pass instead");
+            info!("Conservative mode enabled, using single-pass instead");
             return self.single_pass_random(device_info, progress_callback);
         }
         
-        println!("🔄 Starting 3-pass erasure for USB drive");
+        info!("Starting 3-pass erasure for USB drive");
         
         let patterns = [
             vec![0x00; self.buffer_size], // Pass 1: Zeros
@@ -140,7 +215,8 @@ impl UsbEraser {
         
         for (pass, pattern) in patterns.iter().enumerate() {
             let pass_num = pass + 1;
-            println!("🔄 USB Pass {}/3", pass_num);
+            let _pass = info_span!("pass", pass = pass_num, total_passes = 3).entered();
+            info!("USB Pass {}/3", pass_num);
             
             // Update progress
             if let Ok(mut progress) = progress_callback.lock() {
@@ -162,7 +238,7 @@ impl UsbEraser {
             }
         }
         
-        println!("✅ 3-pass erasure completed for USB drive");
+        info!("3-pass erasure completed for USB drive");
         Ok(())
     }
     
@@ -172,7 +248,7 @@ impl UsbEraser {
         device_info: &DeviceInfo,
         progress_callback: Arc<Mutex<WipingProgress>>,
     ) -> io::Result<()> {
-        println!("🔄 Starting filesystem-level secure deletion for USB drive");
+        info!("Starting filesystem-level secure deletion for USB drive");
         
         // Update progress
         if let Ok(mut progress) = progress_callback.lock() {
@@ -202,13 +278,13 @@ impl UsbEraser {
         // Step 3: Final cleanup
         self.cleanup_temp_files(&device_info.device_path)?;
         
-        println!("✅ Filesystem-level secure deletion completed for USB drive");
+        info!("Filesystem-level secure deletion completed for USB drive");
         Ok(())
     }
     
     /// Quick format the USB drive
     fn quick_format(&self, device_info: &DeviceInfo) -> io::Result<()> {
-        println!("🔧 Performing quick format...");
+        debug!("Performing quick format...");
         
         // Extract drive letter from device path
         let drive_letter = self.extract_drive_letter(&device_info.device_path)?;
@@ -221,7 +297,7 @@ impl UsbEraser {
         match output {
             Ok(result) => {
                 if result.status.success() {
-                    println!("✅ Quick format completed");
+                    info!("Quick format completed");
                     Ok(())
                 } else {
                     let error_msg = String::from_utf8_lossy(&result.stderr);
@@ -232,7 +308,7 @@ impl UsbEraser {
                 }
             }
             Err(e) => {
-                println!("❌ Format command failed: {}", e);
+                error!("Format command failed: {}", e);
                 Err(e)
             }
         }
@@ -240,13 +316,13 @@ impl UsbEraser {
     
     /// Delete all files on the drive
     fn delete_all_files(&self, device_path: &str) -> io::Result<()> {
-        println!("🗑️  Deleting all files...");
+        info!("Deleting all files...");
         
         // This would recursively delete all files and directories
         // For now, simulate the operation
         std::thread::sleep(Duration::from_secs(2));
         
-        println!("✅ File deletion completed");
+        info!("File deletion completed");
         Ok(())
     }
     
@@ -256,7 +332,7 @@ impl UsbEraser {
         device_path: &str,
         progress_callback: Arc<Mutex<WipingProgress>>,
     ) -> io::Result<()> {
-        println!("🔧 Filling free space...");
+        debug!("Filling free space...");
         
         let drive_letter = self.extract_drive_letter(device_path)?;
         let fill_file_path = format!("{}:\\temp_fill_file.tmp", drive_letter);
@@ -289,11 +365,11 @@ impl UsbEraser {
                     }
                 }
                 Err(e) if e.kind() == io::ErrorKind::StorageFull => {
-                    println!("✅ Free space filled ({} bytes)", bytes_written);
+                    info!("Free space filled ({} bytes)", bytes_written);
                     break;
                 }
                 Err(e) => {
-                    println!("❌ Error filling free space: {}", e);
+                    error!("Error filling free space: {}", e);
                     break;
                 }
             }
@@ -308,12 +384,12 @@ impl UsbEraser {
     
     /// Clean up temporary files
     fn cleanup_temp_files(&self, device_path: &str) -> io::Result<()> {
-        println!("🧹 Cleaning up temporary files...");
+        info!("Cleaning up temporary files...");
         
         // This would clean up any remaining temporary files
         std::thread::sleep(Duration::from_millis(500));
         
-        println!("✅ Cleanup completed");
+        info!("Cleanup completed");
         Ok(())
     }
     
@@ -411,10 +487,10 @@ impl UsbEraser {
     
     /// Detect USB drive capabilities
     fn detect_usb_capabilities(&self, device_path: &str) -> (bool, bool) {
-        // USB drives typically don't support hardware secure erase
-        // but may support TRIM (depends on controller)
+        // USB drives don't support hardware secure erase, but some bridges
+        // pass discard (UNMAP) through to the controller
         let supports_secure_erase = false;
-        let supports_trim = false; // Conservative assumption
+        let supports_trim = flash::detect_capabilities(device_path).discard;
         
         (supports_secure_erase, supports_trim)
     }
@@ -422,7 +498,7 @@ impl UsbEraser {
 
 impl DeviceEraser for UsbEraser {
     fn analyze_device(&self, device_path: &str) -> io::Result<DeviceInfo> {
-        println!("🔍 Analyzing USB drive: {}", device_path);
+        info!("Analyzing USB drive: {}", device_path);
         
         let (supports_secure_erase, supports_trim) = self.detect_usb_capabilities(device_path);
         
@@ -439,16 +515,22 @@ impl DeviceEraser for UsbEraser {
                     supports_secure_erase,
                     supports_enhanced_secure_erase: false,
                     supports_crypto_erase: false, // Rare in USB drives
+                    supports_opal: false,
                     is_removable: true,
                     vendor: "Unknown".to_string(),
                     model: "Unknown USB Drive".to_string(),
                     serial: "Unknown".to_string(),
+                    device_id: String::new(),
+                    freeze_status: FreezeStatus::NotApplicable,
+                    encryption_status: EncryptionStatus::Unknown,
+                    nvme_namespaces: Vec::new(),
+                    zoned: None,
                 }
             }
             Err(e) => return Err(e),
         };
         
-        println!("✅ USB drive analysis complete: {} ({} bytes)", 
+        info!("USB drive analysis complete: {} ({} bytes)", 
                 device_info.model, device_info.size_bytes);
         Ok(device_info)
     }
@@ -459,9 +541,15 @@ impl DeviceEraser for UsbEraser {
         algorithm: WipingAlgorithm,
         progress_callback: Arc<Mutex<WipingProgress>>,
     ) -> io::Result<()> {
-        println!("🚀 Starting USB drive erasure with algorithm: {:?}", algorithm);
+        let _span = info_span!("erase", device = %device_info.device_path, algorithm = ?algorithm).entered();
+        info!("Starting USB drive erasure with algorithm: {:?}", algorithm);
         
-        match algorithm {
+        if let Ok(mut techniques) = self.techniques_used.lock() {
+            techniques.clear();
+        }
+        
+        let result = match algorithm {
+            WipingAlgorithm::NistPurge => self.flash_purge(device_info, progress_callback),
             WipingAlgorithm::Random => self.single_pass_random(device_info, progress_callback),
             WipingAlgorithm::Zeros => self.single_pass_zeros(device_info, progress_callback),
             WipingAlgorithm::QuickFormat => self.quick_format_overwrite(device_info, progress_callback),
@@ -474,48 +562,43 @@ impl DeviceEraser for UsbEraser {
             },
             _ => {
                 // Default to single-pass random for USB drives (preserves lifespan)
-                println!("ℹ️  Using single-pass random as default for USB drive");
+                info!("Using single-pass random as default for USB drive");
                 self.single_pass_random(device_info, progress_callback)
             }
+        };
+        
+        if result.is_ok() && self.techniques_used().is_empty() {
+            self.record_technique(format!("Software overwrite ({:?})", algorithm));
         }
+        result
     }
     
     fn verify_erasure(&self, device_info: &DeviceInfo) -> io::Result<bool> {
         if !self.verify_after_wipe {
             return Ok(true);
         }
-        
-        println!("🔍 Verifying USB drive erasure...");
-        
-        let mut file = File::open(&device_info.device_path)?;
-        let mut buffer = vec![0u8; self.buffer_size];
-        let mut total_read = 0u64;
-        // For USB drives, sample conservatively to avoid wear
-        let sample_size = std::cmp::min(device_info.size_bytes, 50 * 1024 * 1024); // Sample first 50MB
-        
-        while total_read < sample_size {
-            let bytes_read = std::io::Read::read(&mut file, &mut buffer)?;
-            if bytes_read == 0 {
-                break;
-            }
-            
-            // Check for non-zero bytes
-            if buffer[..bytes_read].iter().any(|&b| b != 0) {
-                println!("⚠️  Found non-zero data during USB drive verification");
-                return Ok(false);
-            }
-            
-            total_read += bytes_read as u64;
+
+        info!("Verifying USB drive erasure ({})...", self.verification_policy.description());
+
+        let report = verify_with_policy(
+            device_info,
+            &ExpectedContent::Pattern(vec![0x00]),
+            &self.verification_policy,
+            &BadSectorLog::new(),
+        )?;
+        if report.passed {
+            info!("USB drive erasure verification passed");
+        } else {
+            warn!("Found residual data during USB drive verification");
         }
-        
-        println!("✅ USB drive erasure verification passed");
-        Ok(true)
+        Ok(report.passed)
     }
     
     fn get_recommended_algorithms(&self) -> Vec<WipingAlgorithm> {
         if self.conservative_approach {
             vec![
                 WipingAlgorithm::Random,           // Primary choice (single pass)
+                WipingAlgorithm::NistPurge,        // Discard + two full-capacity passes
                 WipingAlgorithm::Zeros,            // Simple zero fill
                 WipingAlgorithm::FileSystemWipe,   // File-level erasure
                 WipingAlgorithm::QuickFormat,      // Quick format + overwrite
@@ -523,6 +606,7 @@ impl DeviceEraser for UsbEraser {
             ]
         } else {
             vec![
+                WipingAlgorithm::NistPurge,        // Discard + two full-capacity passes
                 WipingAlgorithm::ThreePass,        // 3-pass for higher security
                 WipingAlgorithm::Random,           // Single-pass random
                 WipingAlgorithm::FileSystemWipe,   // File-level erasure
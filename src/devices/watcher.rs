@@ -0,0 +1,285 @@
+//! Hot-plug device monitoring
+//!
+//! A background thread keeps a snapshot of attached drives and publishes
+//! `DeviceEvent`s to every subscriber whenever a drive appears, disappears or
+//! changes. On Linux the thread sleeps on the kernel uevent netlink socket and
+//! rescans when a block device event arrives; elsewhere it rescans on a fixed
+//! interval.
+
+use std::collections::HashMap;
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+use crate::platform::{get_system_drives, DriveInfo};
+use tracing::{info, warn};
+
+/// Rescan interval when no kernel notification source is available
+pub const DEFAULT_POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct DeviceSnapshot {
+    pub path: String,
+    pub label: String,
+    pub drive_type: String,
+    pub total_space: u64,
+}
+
+impl From<&DriveInfo> for DeviceSnapshot {
+    fn from(drive: &DriveInfo) -> Self {
+        Self {
+            path: drive.path.clone(),
+            label: drive.label.clone(),
+            drive_type: drive.drive_type.clone(),
+            total_space: drive.total_space,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum DeviceEvent {
+    Added(DeviceSnapshot),
+    Removed(DeviceSnapshot),
+    Changed { old: DeviceSnapshot, new: DeviceSnapshot },
+}
+
+/// Compare two scans and produce the events that turn `old` into `new`
+pub fn diff_snapshots(old: &HashMap<String, DeviceSnapshot>, new: &HashMap<String, DeviceSnapshot>) -> Vec<DeviceEvent> {
+    let mut events = Vec::new();
+
+    for (path, snapshot) in new {
+        match old.get(path) {
+            None => events.push(DeviceEvent::Added(snapshot.clone())),
+            Some(previous) if previous != snapshot => events.push(DeviceEvent::Changed {
+                old: previous.clone(),
+                new: snapshot.clone(),
+            }),
+            Some(_) => {}
+        }
+    }
+
+    for (path, snapshot) in old {
+        if !new.contains_key(path) {
+            events.push(DeviceEvent::Removed(snapshot.clone()));
+        }
+    }
+
+    events
+}
+
+pub struct DeviceWatcher {
+    subscribers: Arc<Mutex<Vec<Sender<DeviceEvent>>>>,
+    poll_interval: Duration,
+    started: bool,
+}
+
+impl DeviceWatcher {
+    pub fn new() -> Self {
+        Self {
+            subscribers: Arc::new(Mutex::new(Vec::new())),
+            poll_interval: DEFAULT_POLL_INTERVAL,
+            started: false,
+        }
+    }
+
+    pub fn with_poll_interval(poll_interval: Duration) -> Self {
+        Self {
+            poll_interval,
+            ..Self::new()
+        }
+    }
+
+    /// Register a new listener; the monitoring thread is started on first use
+    pub fn subscribe(&mut self) -> Receiver<DeviceEvent> {
+        let (tx, rx) = mpsc::channel();
+        self.subscribers.lock().unwrap().push(tx);
+
+        if !self.started {
+            self.start();
+        }
+
+        rx
+    }
+
+    fn start(&mut self) {
+        self.started = true;
+        let subscribers = self.subscribers.clone();
+        let poll_interval = self.poll_interval;
+
+        thread::spawn(move || {
+            let mut known = scan();
+            let mut notifier = UeventNotifier::open(poll_interval);
+
+            info!("Device watcher started ({} drives)", known.len());
+
+            loop {
+                notifier.wait();
+
+                let current = scan();
+                let events = diff_snapshots(&known, &current);
+                known = current;
+
+                if events.is_empty() {
+                    continue;
+                }
+
+                let mut subscribers = subscribers.lock().unwrap();
+                for event in events {
+                    match &event {
+                        DeviceEvent::Added(d) => info!("Device added: {}", d.path),
+                        DeviceEvent::Removed(d) => info!("Device removed: {}", d.path),
+                        DeviceEvent::Changed { new, .. } => info!("Device changed: {}", new.path),
+                    }
+                    // Drop subscribers whose receiver has gone away
+                    subscribers.retain(|tx| tx.send(event.clone()).is_ok());
+                }
+
+                if subscribers.is_empty() {
+                    info!("Device watcher stopped (no subscribers)");
+                    break;
+                }
+            }
+        });
+    }
+}
+
+impl Default for DeviceWatcher {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn scan() -> HashMap<String, DeviceSnapshot> {
+    match get_system_drives() {
+        Ok(drives) => drives.iter().map(|d| (d.path.clone(), DeviceSnapshot::from(d))).collect(),
+        Err(e) => {
+            warn!("Device scan failed: {}", e);
+            HashMap::new()
+        }
+    }
+}
+
+/// Blocks until the kernel reports a block device uevent or the poll interval elapses
+struct UeventNotifier {
+    poll_interval: Duration,
+    #[cfg(target_os = "linux")]
+    socket: Option<std::os::fd::OwnedFd>,
+}
+
+impl UeventNotifier {
+    #[cfg(target_os = "linux")]
+    fn open(poll_interval: Duration) -> Self {
+        use std::os::fd::FromRawFd;
+
+        let socket = unsafe {
+            let fd = libc::socket(libc::AF_NETLINK, libc::SOCK_DGRAM | libc::SOCK_CLOEXEC, libc::NETLINK_KOBJECT_UEVENT);
+            if fd < 0 {
+                None
+            } else {
+                let fd = std::os::fd::OwnedFd::from_raw_fd(fd);
+                let mut addr: libc::sockaddr_nl = std::mem::zeroed();
+                addr.nl_family = libc::AF_NETLINK as libc::sa_family_t;
+                addr.nl_groups = 1; // kernel uevent multicast group
+
+                let timeout = libc::timeval {
+                    tv_sec: poll_interval.as_secs() as libc::time_t,
+                    tv_usec: poll_interval.subsec_micros() as libc::suseconds_t,
+                };
+
+                use std::os::fd::AsRawFd;
+                let bound = libc::bind(
+                    fd.as_raw_fd(),
+                    &addr as *const libc::sockaddr_nl as *const libc::sockaddr,
+                    std::mem::size_of::<libc::sockaddr_nl>() as libc::socklen_t,
+                ) == 0;
+                let timeout_set = libc::setsockopt(
+                    fd.as_raw_fd(),
+                    libc::SOL_SOCKET,
+                    libc::SO_RCVTIMEO,
+                    &timeout as *const libc::timeval as *const libc::c_void,
+                    std::mem::size_of::<libc::timeval>() as libc::socklen_t,
+                ) == 0;
+
+                if bound && timeout_set { Some(fd) } else { None }
+            }
+        };
+
+        if socket.is_none() {
+            warn!("Kernel uevent socket unavailable, falling back to polling");
+        }
+
+        Self { poll_interval, socket }
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    fn open(poll_interval: Duration) -> Self {
+        Self { poll_interval }
+    }
+
+    #[cfg(target_os = "linux")]
+    fn wait(&mut self) {
+        use std::os::fd::AsRawFd;
+
+        let Some(ref socket) = self.socket else {
+            thread::sleep(self.poll_interval);
+            return;
+        };
+
+        let mut buffer = [0u8; 8192];
+        loop {
+            let received = unsafe {
+                libc::recv(socket.as_raw_fd(), buffer.as_mut_ptr() as *mut libc::c_void, buffer.len(), 0)
+            };
+
+            // Timeout or error: rescan anyway so missed events are still picked up
+            if received <= 0 {
+                return;
+            }
+
+            // Messages are NUL-separated KEY=VALUE pairs; only block devices matter
+            let message = &buffer[..received as usize];
+            if message.split(|&b| b == 0).any(|field| field == b"SUBSYSTEM=block") {
+                // Give the kernel a moment to finish creating partitions and mounts
+                thread::sleep(Duration::from_millis(500));
+                return;
+            }
+        }
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    fn wait(&mut self) {
+        thread::sleep(self.poll_interval);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn snapshot(path: &str, total_space: u64) -> DeviceSnapshot {
+        DeviceSnapshot {
+            path: path.to_string(),
+            label: "Disk".to_string(),
+            drive_type: "Removable".to_string(),
+            total_space,
+        }
+    }
+
+    #[test]
+    fn test_diff_snapshots() {
+        let old: HashMap<_, _> = [("/dev/sdb", 100), ("/dev/sdc", 200)]
+            .iter().map(|&(p, s)| (p.to_string(), snapshot(p, s))).collect();
+        let new: HashMap<_, _> = [("/dev/sdc", 250), ("/dev/sdd", 300)]
+            .iter().map(|&(p, s)| (p.to_string(), snapshot(p, s))).collect();
+
+        let events = diff_snapshots(&old, &new);
+        assert_eq!(events.len(), 3);
+        assert!(events.contains(&DeviceEvent::Added(snapshot("/dev/sdd", 300))));
+        assert!(events.contains(&DeviceEvent::Removed(snapshot("/dev/sdb", 100))));
+        assert!(events.contains(&DeviceEvent::Changed {
+            old: snapshot("/dev/sdc", 200),
+            new: snapshot("/dev/sdc", 250),
+        }));
+        assert!(diff_snapshots(&new, &new).is_empty());
+    }
+}
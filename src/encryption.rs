@@ -0,0 +1,426 @@
+//! Software-encrypted volume awareness
+//!
+//! Finds BitLocker, LUKS and FileVault volumes on a device and can purge them
+//! by destroying their key material. Every key slot, protector and keybag of
+//! these formats lives in a small metadata region; once all copies of it are
+//! overwritten the volume key cannot be recovered and the remaining ciphertext
+//! is unreadable, which takes seconds instead of a full overwrite.
+//!
+//! Key material escrowed elsewhere (BitLocker recovery keys in Active Directory
+//! or a Microsoft account, LUKS header backups, FileVault institutional keys)
+//! is out of reach and must be revoked separately.
+
+use std::fs::{File, OpenOptions};
+use std::io::{self, Read, Seek, SeekFrom, Write};
+use tracing::{info, warn};
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use crate::partitions::read_partition_table;
+
+const LUKS_MAGIC: &[u8] = b"LUKS\xba\xbe";
+const BITLOCKER_SIGNATURE: &[u8] = b"-FVE-FS-";
+const APFS_MAGIC: &[u8] = b"NXSB";
+const CORESTORAGE_SIGNATURE: &[u8] = b"CS";
+/// Bytes read from the start of each volume to identify it
+const PROBE_BYTES: usize = 4096;
+/// LUKS2 headers (both copies plus the JSON area) are at most 4 MiB each
+const LUKS2_MAX_HEADER: u64 = 4 * 1024 * 1024;
+/// cryptsetup defaults, used when the header does not state the data offset
+const LUKS1_DEFAULT_PAYLOAD: u64 = 2 * 1024 * 1024;
+const LUKS2_DEFAULT_DATA_OFFSET: u64 = 16 * 1024 * 1024;
+/// Each BitLocker FVE metadata block, with its key protectors
+const BITLOCKER_METADATA_BYTES: u64 = 64 * 1024;
+/// Writes are widened to this boundary so raw devices accept them
+const WRITE_ALIGNMENT: u64 = 4096;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum EncryptionScheme {
+    Luks1,
+    Luks2,
+    BitLocker,
+    /// APFS container with a keybag (FileVault or hardware-encrypted volumes)
+    FileVaultApfs,
+    /// FileVault 2 on a Core Storage logical volume (macOS 10.7 - 10.12)
+    FileVaultCoreStorage,
+}
+
+impl EncryptionScheme {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            EncryptionScheme::Luks1 => "LUKS1",
+            EncryptionScheme::Luks2 => "LUKS2",
+            EncryptionScheme::BitLocker => "BitLocker",
+            EncryptionScheme::FileVaultApfs => "FileVault (APFS)",
+            EncryptionScheme::FileVaultCoreStorage => "FileVault (Core Storage)",
+        }
+    }
+}
+
+/// A byte range holding key material or encryption metadata
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct KeyRegion {
+    /// Relative to the start of the device
+    pub offset: u64,
+    pub length: u64,
+    pub description: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EncryptedVolume {
+    pub scheme: EncryptionScheme,
+    /// Start of the volume on the device
+    pub volume_offset: u64,
+    /// Partition number, or None for a volume on the whole device
+    pub partition: Option<u32>,
+    /// Every copy of the key material; empty when its location is not known
+    pub key_regions: Vec<KeyRegion>,
+}
+
+impl EncryptedVolume {
+    pub fn supports_header_destruction(&self) -> bool {
+        !self.key_regions.is_empty()
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub enum EncryptionStatus {
+    /// The device has not been inspected
+    #[default]
+    Unknown,
+    Unencrypted,
+    Encrypted(Vec<EncryptedVolume>),
+}
+
+impl EncryptionStatus {
+    pub fn is_encrypted(&self) -> bool {
+        matches!(self, EncryptionStatus::Encrypted(_))
+    }
+
+    pub fn volumes(&self) -> &[EncryptedVolume] {
+        match self {
+            EncryptionStatus::Encrypted(volumes) => volumes,
+            _ => &[],
+        }
+    }
+
+    /// Crypto-erase by header destruction is possible for at least one volume
+    pub fn supports_header_destruction(&self) -> bool {
+        self.volumes().iter().any(EncryptedVolume::supports_header_destruction)
+    }
+
+    pub fn description(&self) -> String {
+        match self {
+            EncryptionStatus::Unknown => "Unknown".to_string(),
+            EncryptionStatus::Unencrypted => "Not encrypted".to_string(),
+            EncryptionStatus::Encrypted(volumes) => volumes
+                .iter()
+                .map(|v| match v.partition {
+                    Some(number) => format!("{} (partition {})", v.scheme.as_str(), number),
+                    None => v.scheme.as_str().to_string(),
+                })
+                .collect::<Vec<_>>()
+                .join(", "),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HeaderDestructionReport {
+    pub device_path: String,
+    pub volumes_destroyed: Vec<EncryptedVolume>,
+    pub bytes_overwritten: u64,
+    /// Every region read back as zeros and no destroyed volume is recognised any more
+    pub verified: bool,
+    pub notes: Vec<String>,
+}
+
+/// Identify an encrypted volume from the first bytes of `header`. LUKS2 needs
+/// the whole binary header plus JSON area to locate its data segment.
+pub fn identify_volume(header: &[u8]) -> Option<(EncryptionScheme, Vec<KeyRegion>)> {
+    if header.starts_with(LUKS_MAGIC) && header.len() >= 8 {
+        let version = u16::from_be_bytes([header[6], header[7]]);
+        return match version {
+            1 => Some((EncryptionScheme::Luks1, luks1_regions(header))),
+            2 => Some((EncryptionScheme::Luks2, luks2_regions(header))),
+            _ => None,
+        };
+    }
+
+    if header.get(3..11) == Some(BITLOCKER_SIGNATURE) {
+        return Some((EncryptionScheme::BitLocker, bitlocker_regions(header)));
+    }
+
+    if header.get(32..36) == Some(APFS_MAGIC) {
+        return apfs_regions(header).map(|regions| (EncryptionScheme::FileVaultApfs, regions));
+    }
+
+    // Core Storage physical volume header; the encrypted context sits in metadata
+    // blocks scattered over the volume, so only detection is supported
+    if header.get(88..90) == Some(CORESTORAGE_SIGNATURE) && header.get(80..82) == Some(&[1, 0]) {
+        return Some((EncryptionScheme::FileVaultCoreStorage, Vec::new()));
+    }
+
+    None
+}
+
+/// Header, key slots and anti-forensic stripes all precede the payload
+fn luks1_regions(header: &[u8]) -> Vec<KeyRegion> {
+    let payload_sectors = header.get(104..108).map_or(0, |b| u32::from_be_bytes(b.try_into().unwrap()) as u64);
+    let length = if payload_sectors > 0 { payload_sectors * 512 } else { LUKS1_DEFAULT_PAYLOAD };
+    vec![KeyRegion { offset: 0, length, description: "LUKS1 header and key slots".to_string() }]
+}
+
+/// Both header copies and the key slot area precede the first data segment
+fn luks2_regions(header: &[u8]) -> Vec<KeyRegion> {
+    let header_size = header.get(8..16).map_or(0, |b| u64::from_be_bytes(b.try_into().unwrap()));
+    let json_end = (header_size as usize).min(header.len());
+    let data_offset = header
+        .get(4096..json_end)
+        .map(|json| String::from_utf8_lossy(json).trim_end_matches('\0').to_string())
+        .and_then(|json| serde_json::from_str::<Value>(&json).ok())
+        .and_then(|config| {
+            config["segments"]
+                .as_object()?
+                .values()
+                .filter_map(|segment| segment["offset"].as_str()?.parse::<u64>().ok())
+                .min()
+        })
+        .unwrap_or(LUKS2_DEFAULT_DATA_OFFSET);
+
+    vec![KeyRegion { offset: 0, length: data_offset, description: "LUKS2 headers and key slots".to_string() }]
+}
+
+/// The boot sector and the three FVE metadata blocks holding the key protectors
+fn bitlocker_regions(header: &[u8]) -> Vec<KeyRegion> {
+    let read_u64 = |offset: usize| header.get(offset..offset + 8).map_or(0, |b| u64::from_le_bytes(b.try_into().unwrap()));
+
+    let mut regions = vec![KeyRegion { offset: 0, length: 512, description: "BitLocker boot sector".to_string() }];
+    // Windows 7 and later record three copies; Vista recorded a single one at 56
+    let mut metadata: Vec<u64> = [176, 184, 192].iter().map(|&o| read_u64(o)).filter(|&o| o != 0).collect();
+    if metadata.is_empty() && read_u64(56) != 0 {
+        metadata.push(read_u64(56));
+    }
+    for (index, offset) in metadata.into_iter().enumerate() {
+        regions.push(KeyRegion {
+            offset,
+            length: BITLOCKER_METADATA_BYTES,
+            description: format!("BitLocker FVE metadata block {}", index + 1),
+        });
+    }
+    regions
+}
+
+/// The container superblock and the keybag referenced by `nx_keylocker`; without
+/// a keybag no volume in the container is encrypted
+fn apfs_regions(header: &[u8]) -> Option<Vec<KeyRegion>> {
+    let block_size = header.get(36..40).map(|b| u32::from_le_bytes(b.try_into().unwrap()) as u64)?;
+    let keybag_block = header.get(1296..1304).map(|b| u64::from_le_bytes(b.try_into().unwrap()))?;
+    let keybag_blocks = header.get(1304..1312).map(|b| u64::from_le_bytes(b.try_into().unwrap()))?;
+    if block_size == 0 || keybag_block == 0 || keybag_blocks == 0 {
+        return None;
+    }
+
+    Some(vec![
+        KeyRegion { offset: 0, length: block_size, description: "APFS container superblock".to_string() },
+        KeyRegion {
+            offset: keybag_block * block_size,
+            length: keybag_blocks * block_size,
+            description: "APFS container keybag".to_string(),
+        },
+    ])
+}
+
+fn read_at(device: &mut File, offset: u64, buffer: &mut [u8]) -> io::Result<usize> {
+    device.seek(SeekFrom::Start(offset))?;
+    let mut filled = 0;
+    while filled < buffer.len() {
+        match device.read(&mut buffer[filled..])? {
+            0 => break,
+            n => filled += n,
+        }
+    }
+    Ok(filled)
+}
+
+fn probe_volume(device: &mut File, volume_offset: u64, partition: Option<u32>) -> io::Result<Option<EncryptedVolume>> {
+    let mut header = vec![0u8; PROBE_BYTES];
+    let len = read_at(device, volume_offset, &mut header)?;
+    header.truncate(len);
+
+    // LUKS2 keeps its JSON metadata past the first 4 KiB
+    if header.starts_with(LUKS_MAGIC) && header.get(6..8) == Some(&[0, 2]) {
+        let header_size = u64::from_be_bytes(header[8..16].try_into().unwrap()).min(LUKS2_MAX_HEADER);
+        header.resize(header_size.max(PROBE_BYTES as u64) as usize, 0);
+        let len = read_at(device, volume_offset, &mut header)?;
+        header.truncate(len);
+    }
+
+    Ok(identify_volume(&header).map(|(scheme, regions)| EncryptedVolume {
+        scheme,
+        volume_offset,
+        partition,
+        key_regions: regions
+            .into_iter()
+            .map(|region| KeyRegion { offset: volume_offset + region.offset, ..region })
+            .collect(),
+    }))
+}
+
+/// Look for encrypted volumes on the whole device and on every partition
+pub fn detect_encryption(device_path: &str) -> io::Result<EncryptionStatus> {
+    let mut device = File::open(device_path)?;
+    let total_bytes = device.seek(SeekFrom::End(0))?;
+
+    let mut candidates = vec![(0u64, None)];
+    if let Ok(Some(table)) = read_partition_table(device_path) {
+        candidates.extend(
+            table.partitions.iter().map(|p| (p.byte_range(table.sector_size).0, Some(p.number)))
+        );
+    }
+
+    let mut volumes = Vec::new();
+    for (offset, partition) in candidates.into_iter().filter(|(offset, _)| *offset < total_bytes) {
+        if let Some(volume) = probe_volume(&mut device, offset, partition)? {
+            info!(scheme = volume.scheme.as_str(), device = %device_path, offset, "Encrypted volume found");
+            volumes.push(volume);
+        }
+    }
+
+    Ok(if volumes.is_empty() {
+        EncryptionStatus::Unencrypted
+    } else {
+        EncryptionStatus::Encrypted(volumes)
+    })
+}
+
+fn aligned(region: &KeyRegion, total_bytes: u64) -> (u64, u64) {
+    let start = region.offset / WRITE_ALIGNMENT * WRITE_ALIGNMENT;
+    let end = (region.offset + region.length).div_ceil(WRITE_ALIGNMENT) * WRITE_ALIGNMENT;
+    (start, end.min(total_bytes))
+}
+
+/// Crypto-erase by header destruction: overwrite every copy of the key material
+/// of each encrypted volume with random data and then zeros, and verify that the
+/// regions read back as zeros and the volumes are no longer recognised.
+pub fn destroy_key_material(device_path: &str) -> io::Result<HeaderDestructionReport> {
+    info!(device = %device_path, "Destroying encryption key material");
+
+    let status = detect_encryption(device_path)?;
+    let (destroyable, skipped): (Vec<_>, Vec<_>) = status
+        .volumes()
+        .iter()
+        .cloned()
+        .partition(EncryptedVolume::supports_header_destruction);
+    if destroyable.is_empty() {
+        return Err(io::Error::new(
+            if status.is_encrypted() { io::ErrorKind::Unsupported } else { io::ErrorKind::InvalidInput },
+            format!("No volume on {} supports header destruction ({})", device_path, status.description())
+        ));
+    }
+
+    let mut notes: Vec<String> = skipped
+        .iter()
+        .map(|v| format!("{} at byte {} left intact: key material location unknown", v.scheme.as_str(), v.volume_offset))
+        .collect();
+
+    let mut device = OpenOptions::new().read(true).write(true).open(device_path)?;
+    let total_bytes = device.seek(SeekFrom::End(0))?;
+    let mut bytes_overwritten = 0u64;
+
+    for volume in &destroyable {
+        for region in &volume.key_regions {
+            let (start, end) = aligned(region, total_bytes);
+            if start >= end {
+                continue;
+            }
+            println!("   Overwriting {} ({} bytes at {})", region.description, end - start, start);
+
+            let mut buffer = vec![0u8; (end - start) as usize];
+            rand::thread_rng().fill_bytes(&mut buffer);
+            device.seek(SeekFrom::Start(start))?;
+            device.write_all(&buffer)?;
+            device.sync_all()?;
+
+            buffer.fill(0);
+            device.seek(SeekFrom::Start(start))?;
+            device.write_all(&buffer)?;
+            device.sync_all()?;
+            bytes_overwritten += end - start;
+        }
+    }
+
+    // Verification: every region reads back as zeros and nothing is recognised where the volumes were
+    let mut verified = true;
+    for volume in &destroyable {
+        for region in &volume.key_regions {
+            let (start, end) = aligned(region, total_bytes);
+            let mut buffer = vec![0u8; (end - start) as usize];
+            let len = read_at(&mut device, start, &mut buffer)?;
+            if len != buffer.len() || buffer.iter().any(|&b| b != 0) {
+                verified = false;
+                notes.push(format!("{} did not read back as zeros", region.description));
+            }
+        }
+        if probe_volume(&mut device, volume.volume_offset, volume.partition)?.is_some() {
+            verified = false;
+            notes.push(format!("{} still recognised at byte {}", volume.scheme.as_str(), volume.volume_offset));
+        }
+    }
+
+    if verified {
+        info!(volumes = destroyable.len(), bytes_overwritten, "Key material destroyed");
+    } else {
+        warn!(device = %device_path, "Header destruction could not be verified");
+    }
+
+    Ok(HeaderDestructionReport {
+        device_path: device_path.to_string(),
+        volumes_destroyed: destroyable,
+        bytes_overwritten,
+        verified,
+        notes,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_identify_luks_and_bitlocker() {
+        let mut luks1 = vec![0u8; 512];
+        luks1[0..6].copy_from_slice(LUKS_MAGIC);
+        luks1[6..8].copy_from_slice(&1u16.to_be_bytes());
+        luks1[104..108].copy_from_slice(&4096u32.to_be_bytes());
+        let (scheme, regions) = identify_volume(&luks1).unwrap();
+        assert_eq!(scheme, EncryptionScheme::Luks1);
+        assert_eq!(regions[0].length, 2 * 1024 * 1024);
+
+        let mut bitlocker = vec![0u8; 512];
+        bitlocker[3..11].copy_from_slice(BITLOCKER_SIGNATURE);
+        bitlocker[176..184].copy_from_slice(&0x0210_0000u64.to_le_bytes());
+        bitlocker[184..192].copy_from_slice(&0x1210_0000u64.to_le_bytes());
+        bitlocker[192..200].copy_from_slice(&0x2210_0000u64.to_le_bytes());
+        let (scheme, regions) = identify_volume(&bitlocker).unwrap();
+        assert_eq!(scheme, EncryptionScheme::BitLocker);
+        assert_eq!(regions.len(), 4);
+        assert_eq!(regions[2].offset, 0x1210_0000);
+
+        assert!(identify_volume(&[0u8; 512]).is_none());
+    }
+
+    #[test]
+    fn test_luks2_data_offset_from_json() {
+        let json = br#"{"keyslots":{},"segments":{"0":{"type":"crypt","offset":"8388608","size":"dynamic"}}}"#;
+        let mut header = vec![0u8; 16384];
+        header[0..6].copy_from_slice(LUKS_MAGIC);
+        header[6..8].copy_from_slice(&2u16.to_be_bytes());
+        header[8..16].copy_from_slice(&16384u64.to_be_bytes());
+        header[4096..4096 + json.len()].copy_from_slice(json);
+
+        let (scheme, regions) = identify_volume(&header).unwrap();
+        assert_eq!(scheme, EncryptionScheme::Luks2);
+        assert_eq!(regions[0].length, 8 * 1024 * 1024);
+    }
+}
@@ -0,0 +1,286 @@
+//! Progress reporting for hardware erase commands
+//!
+//! ATA SECURITY ERASE UNIT and NVMe Format/Sanitize run inside the drive and
+//! report nothing until they finish. `HardwareEraseMonitor` runs alongside the
+//! blocking command and synthesizes progress updates, either linearly over the
+//! erase time the drive estimates for itself or, for an NVMe sanitize, from the
+//! Sanitize Status log page (0x81).
+
+use std::io;
+#[cfg(unix)]
+use std::process::Command;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+use tracing::warn;
+use crate::advanced_wiper::WipingProgress;
+use crate::ata_commands::AtaInterface;
+
+/// NVMe Sanitize Status log page identifier
+pub const NVME_LOG_SANITIZE_STATUS: u8 = 0x81;
+const SANITIZE_LOG_SIZE: usize = 512;
+
+/// Interval between synthesized progress updates
+pub const DEFAULT_UPDATE_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Estimated progress never reaches 100% before the command actually returns
+const MAX_ESTIMATED_PERCENTAGE: f64 = 99.0;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SanitizeState {
+    NeverSanitized,
+    Completed,
+    InProgress,
+    Failed,
+    CompletedNoDeallocate,
+    Unknown(u8),
+}
+
+/// Decoded NVMe Sanitize Status log page
+#[derive(Debug, Clone, PartialEq)]
+pub struct NvmeSanitizeStatus {
+    pub state: SanitizeState,
+    /// Completion of the most recent sanitize operation, 0-100
+    pub percentage: f64,
+    pub estimated_overwrite: Option<Duration>,
+    pub estimated_block_erase: Option<Duration>,
+    pub estimated_crypto_erase: Option<Duration>,
+}
+
+pub fn parse_nvme_sanitize_log(data: &[u8]) -> io::Result<NvmeSanitizeStatus> {
+    if data.len() < 20 {
+        return Err(io::Error::new(io::ErrorKind::InvalidData, "NVMe sanitize status log too short"));
+    }
+
+    // SPROG: fraction complete, numerator over 65536
+    let sprog = u16::from_le_bytes([data[0], data[1]]);
+    let sstat = u16::from_le_bytes([data[2], data[3]]);
+
+    let state = match sstat & 0x07 {
+        0 => SanitizeState::NeverSanitized,
+        1 => SanitizeState::Completed,
+        2 => SanitizeState::InProgress,
+        3 => SanitizeState::Failed,
+        4 => SanitizeState::CompletedNoDeallocate,
+        other => SanitizeState::Unknown(other as u8),
+    };
+
+    let percentage = match state {
+        SanitizeState::InProgress => sprog as f64 * 100.0 / 65536.0,
+        SanitizeState::Completed | SanitizeState::CompletedNoDeallocate => 100.0,
+        _ => 0.0,
+    };
+
+    // Estimated times in seconds; all ones means the controller gives no estimate
+    let estimate = |offset: usize| {
+        let seconds = u32::from_le_bytes([data[offset], data[offset + 1], data[offset + 2], data[offset + 3]]);
+        if seconds == u32::MAX { None } else { Some(Duration::from_secs(seconds as u64)) }
+    };
+
+    Ok(NvmeSanitizeStatus {
+        state,
+        percentage,
+        estimated_overwrite: estimate(8),
+        estimated_block_erase: estimate(12),
+        estimated_crypto_erase: estimate(16),
+    })
+}
+
+/// Read the Sanitize Status log page through nvme-cli
+pub fn read_nvme_sanitize_log(device_path: &str) -> io::Result<NvmeSanitizeStatus> {
+    #[cfg(unix)]
+    {
+        let output = Command::new("nvme")
+            .args([
+                "get-log",
+                device_path,
+                &format!("--log-id={}", NVME_LOG_SANITIZE_STATUS),
+                &format!("--log-len={}", SANITIZE_LOG_SIZE),
+                "--raw-binary",
+            ])
+            .output()?;
+
+        if !output.status.success() {
+            return Err(io::Error::other(format!("NVMe sanitize log read failed: {}", String::from_utf8_lossy(&output.stderr).trim())));
+        }
+
+        parse_nvme_sanitize_log(&output.stdout)
+    }
+
+    #[cfg(not(unix))]
+    {
+        let _ = device_path;
+        Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            "NVMe log page pass-through is not available on this platform"
+        ))
+    }
+}
+
+/// The drive's own estimate for ATA SECURITY ERASE UNIT, from IDENTIFY words 89/90
+pub fn ata_erase_estimate(ata: &AtaInterface, enhanced: bool) -> Option<Duration> {
+    let identify_data = ata.identify_device().ok()?;
+    ata.security_erase_estimate(&identify_data, enhanced)
+}
+
+/// The controller's estimate for an NVMe Format with the given Secure Erase Setting
+pub fn nvme_format_estimate(device_path: &str, crypto_erase: bool) -> Option<Duration> {
+    let status = read_nvme_sanitize_log(device_path).ok()?;
+    if crypto_erase { status.estimated_crypto_erase } else { status.estimated_block_erase }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct EraseProgress {
+    /// 0-100
+    pub percentage: f64,
+    pub elapsed: Duration,
+    /// `None` when there is no estimate or the estimate has already been exceeded
+    pub remaining: Option<Duration>,
+}
+
+/// Linear progress over an expected duration, held below 100% until the drive is done
+pub fn estimate_progress(elapsed: Duration, expected: Duration) -> EraseProgress {
+    let fraction = if expected.is_zero() { 1.0 } else { elapsed.as_secs_f64() / expected.as_secs_f64() };
+
+    EraseProgress {
+        percentage: (fraction * 100.0).min(MAX_ESTIMATED_PERCENTAGE),
+        elapsed,
+        remaining: expected.checked_sub(elapsed).filter(|r| !r.is_zero()),
+    }
+}
+
+pub enum EraseProgressSource {
+    /// Linear progress over the erase time the drive reported
+    Estimate(Duration),
+    /// Poll the Sanitize Status log of an NVMe device running SANITIZE
+    NvmeSanitizeLog(String),
+}
+
+/// Background thread publishing progress while a hardware erase command blocks
+pub struct HardwareEraseMonitor {
+    stop: Arc<AtomicBool>,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl HardwareEraseMonitor {
+    pub fn start<F>(source: EraseProgressSource, interval: Duration, mut on_progress: F) -> Self
+    where
+        F: FnMut(EraseProgress) + Send + 'static,
+    {
+        let stop = Arc::new(AtomicBool::new(false));
+        let stop_flag = stop.clone();
+
+        let handle = thread::spawn(move || {
+            let start_time = Instant::now();
+
+            while !stop_flag.load(Ordering::Relaxed) {
+                let elapsed = start_time.elapsed();
+                let progress = match &source {
+                    EraseProgressSource::Estimate(expected) => Some(estimate_progress(elapsed, *expected)),
+                    EraseProgressSource::NvmeSanitizeLog(device_path) => {
+                        match read_nvme_sanitize_log(device_path) {
+                            Ok(status) => Some(sanitize_progress(&status, elapsed)),
+                            Err(e) => {
+                                warn!(error = %e, "Sanitize status unavailable");
+                                None
+                            }
+                        }
+                    }
+                };
+
+                if let Some(progress) = progress {
+                    on_progress(progress);
+                }
+
+                // Sleep in short steps so dropping the monitor returns promptly
+                let wake_time = Instant::now() + interval;
+                while Instant::now() < wake_time && !stop_flag.load(Ordering::Relaxed) {
+                    thread::sleep(Duration::from_millis(100));
+                }
+            }
+        });
+
+        Self {
+            stop,
+            handle: Some(handle),
+        }
+    }
+
+    /// Mirror the synthesized progress into a device eraser's shared progress
+    pub fn for_wiping_progress(
+        source: EraseProgressSource,
+        progress_callback: Arc<Mutex<WipingProgress>>,
+        total_bytes: u64,
+    ) -> Self {
+        Self::start(source, DEFAULT_UPDATE_INTERVAL, move |update| {
+            if let Ok(mut progress) = progress_callback.lock() {
+                progress.total_bytes = total_bytes;
+                progress.bytes_processed = (total_bytes as f64 * update.percentage / 100.0) as u64;
+                progress.estimated_time_remaining = update.remaining.unwrap_or_default();
+            }
+        })
+    }
+}
+
+/// Dropping the monitor stops it; do so once the erase command has returned
+impl Drop for HardwareEraseMonitor {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+fn sanitize_progress(status: &NvmeSanitizeStatus, elapsed: Duration) -> EraseProgress {
+    // Extrapolate the remaining time from the rate observed so far
+    let remaining = if status.state == SanitizeState::InProgress && status.percentage > 0.0 {
+        let total = elapsed.as_secs_f64() * 100.0 / status.percentage;
+        Some(Duration::from_secs_f64((total - elapsed.as_secs_f64()).max(0.0)))
+    } else {
+        None
+    };
+
+    EraseProgress {
+        percentage: status.percentage,
+        elapsed,
+        remaining,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_nvme_sanitize_log() {
+        let mut log = vec![0u8; SANITIZE_LOG_SIZE];
+        log[0..2].copy_from_slice(&0x4000u16.to_le_bytes()); // 25%
+        log[2..4].copy_from_slice(&2u16.to_le_bytes());
+        log[8..12].copy_from_slice(&u32::MAX.to_le_bytes());
+        log[12..16].copy_from_slice(&120u32.to_le_bytes());
+        log[16..20].copy_from_slice(&5u32.to_le_bytes());
+
+        let status = parse_nvme_sanitize_log(&log).unwrap();
+        assert_eq!(status.state, SanitizeState::InProgress);
+        assert_eq!(status.percentage, 25.0);
+        assert_eq!(status.estimated_overwrite, None);
+        assert_eq!(status.estimated_block_erase, Some(Duration::from_secs(120)));
+        assert_eq!(status.estimated_crypto_erase, Some(Duration::from_secs(5)));
+
+        let progress = sanitize_progress(&status, Duration::from_secs(30));
+        assert_eq!(progress.remaining, Some(Duration::from_secs(90)));
+    }
+
+    #[test]
+    fn test_estimate_progress() {
+        let progress = estimate_progress(Duration::from_secs(30), Duration::from_secs(120));
+        assert_eq!(progress.percentage, 25.0);
+        assert_eq!(progress.remaining, Some(Duration::from_secs(90)));
+
+        let overdue = estimate_progress(Duration::from_secs(200), Duration::from_secs(120));
+        assert_eq!(overdue.percentage, MAX_ESTIMATED_PERCENTAGE);
+        assert_eq!(overdue.remaining, None);
+    }
+}
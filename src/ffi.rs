@@ -0,0 +1,520 @@
+//! C ABI for embedding the wipe engine in C, C++ and C# front ends
+//!
+//! Built with the `ffi` feature, which also writes `include/hdd_tool.h`
+//! with cbindgen. Link against the cdylib produced by
+//! `cargo rustc --release --lib --features ffi --crate-type cdylib`.
+//!
+//! Conventions:
+//!
+//! - Structured data crosses the boundary as UTF-8 JSON strings.
+//! - Strings returned by the library are owned by the caller and released
+//!   with `safewipe_string_free`; strings passed in stay owned by the caller.
+//! - Functions returning a pointer return NULL on failure, functions returning
+//!   a `SafewipeStatus` return a non-zero status. `safewipe_last_error` then
+//!   describes the failure of the last call on the same thread, and
+//!   `safewipe_last_error_code` says what kind of failure it was.
+//! - Panics never unwind into the caller; they are reported as
+//!   `SAFEWIPE_STATUS_PANICKED` or NULL.
+//! - A `SafewipeClient` may be used from several threads at once. Wipes run
+//!   on threads of their own: `safewipe_start_wipe` returns immediately and
+//!   `safewipe_poll_progress` reports how the wipe is doing.
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::ffi::{c_char, CStr, CString};
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::ptr;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::core::{
+    ComplianceProfile, SafeWipeErrorCode, SanitizationStandard, TargetType, WipeConfiguration, WipeEngine, WipeErrorCode,
+    WipePriority, WipeProgress, WipeResult, WipeScope,
+};
+use crate::security_state::SecurityStateAction;
+
+/// Outcome of a call that does not return a pointer
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SafewipeStatus {
+    Ok = 0,
+    /// A pointer was NULL, a string was not UTF-8 or JSON did not parse
+    InvalidArgument = 1,
+    /// Log in with `safewipe_login` or `safewipe_login_token` first
+    NotAuthenticated = 2,
+    NotFound = 3,
+    /// The engine refused or failed the operation
+    Failed = 4,
+    /// The library panicked; the client should be freed and recreated
+    Panicked = 5,
+}
+
+/// Cause of the last failure, for front ends to branch on
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SafewipeErrorCode {
+    /// The last call succeeded
+    None = 0,
+    DeviceNotFound = 1,
+    /// The process lacks the privileges to open or command the device
+    PermissionDenied = 2,
+    NotAuthenticated = 3,
+    /// The drive's security state (locked or frozen) refuses the command
+    SecurityFrozen = 4,
+    /// The device is mounted or in use by another operation
+    Busy = 5,
+    VerificationFailed = 6,
+    Unsupported = 7,
+    Cancelled = 8,
+    /// Refused by the target policy, a role or a missing approval
+    PolicyDenied = 9,
+    InvalidArgument = 10,
+    NotFound = 11,
+    HardwareFailure = 12,
+    Internal = 13,
+}
+
+impl From<SafeWipeErrorCode> for SafewipeErrorCode {
+    fn from(code: SafeWipeErrorCode) -> Self {
+        match code {
+            SafeWipeErrorCode::DeviceNotFound => SafewipeErrorCode::DeviceNotFound,
+            SafeWipeErrorCode::PermissionDenied => SafewipeErrorCode::PermissionDenied,
+            SafeWipeErrorCode::NotAuthenticated => SafewipeErrorCode::NotAuthenticated,
+            SafeWipeErrorCode::SecurityFrozen => SafewipeErrorCode::SecurityFrozen,
+            SafeWipeErrorCode::Busy => SafewipeErrorCode::Busy,
+            SafeWipeErrorCode::VerificationFailed => SafewipeErrorCode::VerificationFailed,
+            SafeWipeErrorCode::Unsupported => SafewipeErrorCode::Unsupported,
+            SafeWipeErrorCode::Cancelled => SafewipeErrorCode::Cancelled,
+            SafeWipeErrorCode::PolicyDenied => SafewipeErrorCode::PolicyDenied,
+            SafeWipeErrorCode::InvalidArgument => SafewipeErrorCode::InvalidArgument,
+            SafeWipeErrorCode::NotFound => SafewipeErrorCode::NotFound,
+            SafeWipeErrorCode::HardwareFailure => SafewipeErrorCode::HardwareFailure,
+            SafeWipeErrorCode::Internal => SafewipeErrorCode::Internal,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+enum JobStatus {
+    Running,
+    Verifying,
+    Completed,
+    Failed,
+    Cancelled,
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct Job {
+    operation_id: Uuid,
+    status: JobStatus,
+    progress: Option<WipeProgress>,
+    result: Option<WipeResult>,
+    certificate_path: Option<String>,
+    error: Option<String>,
+    error_code: Option<SafeWipeErrorCode>,
+}
+
+/// A wipe as passed to `safewipe_start_wipe`
+#[derive(Debug, Deserialize)]
+struct StartWipe {
+    device: String,
+    target_type: TargetType,
+    #[serde(default)]
+    scope: WipeScope,
+    standard: SanitizationStandard,
+    #[serde(default = "default_true")]
+    verify: bool,
+    #[serde(default = "default_true")]
+    certificate: bool,
+    #[serde(default)]
+    profile: Option<ComplianceProfile>,
+    #[serde(default)]
+    confirmation: Option<String>,
+    #[serde(default)]
+    simulate: bool,
+    #[serde(default)]
+    skip_trim: bool,
+    #[serde(default)]
+    security_state: Option<SecurityStateAction>,
+    #[serde(default)]
+    priority: WipePriority,
+}
+
+fn default_true() -> bool { true }
+
+/// Opaque handle to an engine and the wipes started through it
+pub struct SafewipeClient {
+    engine: Arc<WipeEngine>,
+    jobs: Arc<Mutex<HashMap<Uuid, Job>>>,
+}
+
+thread_local! {
+    static LAST_ERROR: RefCell<Option<(SafewipeErrorCode, String)>> = const { RefCell::new(None) };
+}
+
+/// Why a call failed: the status it returns, the cause and the message
+type Failure = (SafewipeStatus, SafewipeErrorCode, String);
+
+fn set_last_error(code: SafewipeErrorCode, message: String) {
+    LAST_ERROR.with(|last| *last.borrow_mut() = Some((code, message)));
+}
+
+/// Run `f`, turning errors and panics into `failed` and the last error
+fn guard<T>(failed: impl Fn(SafewipeStatus) -> T, f: impl FnOnce() -> Result<T, Failure>) -> T {
+    LAST_ERROR.with(|last| *last.borrow_mut() = None);
+    match catch_unwind(AssertUnwindSafe(f)) {
+        Ok(Ok(value)) => value,
+        Ok(Err((status, code, message))) => {
+            set_last_error(code, message);
+            failed(status)
+        }
+        Err(panic) => {
+            let message = panic
+                .downcast_ref::<&str>()
+                .map(|s| s.to_string())
+                .or_else(|| panic.downcast_ref::<String>().cloned())
+                .unwrap_or_else(|| "unknown panic".to_string());
+            set_last_error(SafewipeErrorCode::Internal, format!("Internal error: {}", message));
+            failed(SafewipeStatus::Panicked)
+        }
+    }
+}
+
+fn invalid(message: impl Into<String>) -> Failure {
+    (SafewipeStatus::InvalidArgument, SafewipeErrorCode::InvalidArgument, message.into())
+}
+
+fn engine_error(error: crate::core::WipeError) -> Failure {
+    let status = match error.code {
+        WipeErrorCode::NotAuthenticated => SafewipeStatus::NotAuthenticated,
+        WipeErrorCode::DriveNotFound => SafewipeStatus::NotFound,
+        _ => SafewipeStatus::Failed,
+    };
+    (status, error.error_code().into(), error.to_string())
+}
+
+/// # Safety
+/// `value` must be NULL or a NUL-terminated string
+unsafe fn read_str<'a>(value: *const c_char, name: &str) -> Result<&'a str, Failure> {
+    if value.is_null() {
+        return Err(invalid(format!("{} is NULL", name)));
+    }
+    unsafe { CStr::from_ptr(value) }.to_str().map_err(|_| invalid(format!("{} is not valid UTF-8", name)))
+}
+
+/// # Safety
+/// `client` must be NULL or a pointer returned by `safewipe_client_new`
+unsafe fn client_ref<'a>(client: *const SafewipeClient) -> Result<&'a SafewipeClient, Failure> {
+    unsafe { client.as_ref() }.ok_or_else(|| invalid("client is NULL"))
+}
+
+fn into_c_string(value: String) -> Result<*mut c_char, Failure> {
+    CString::new(value)
+        .map(CString::into_raw)
+        .map_err(|_| (SafewipeStatus::Failed, SafewipeErrorCode::Internal, "String contains a NUL byte".to_string()))
+}
+
+fn to_json<T: Serialize>(value: &T) -> Result<*mut c_char, Failure> {
+    serde_json::to_string(value)
+        .map_err(|e| (SafewipeStatus::Failed, SafewipeErrorCode::Internal, format!("Failed to serialize: {}", e)))
+        .and_then(into_c_string)
+}
+
+fn parse_operation_id(value: &str) -> Result<Uuid, Failure> {
+    Uuid::parse_str(value).map_err(|_| invalid(format!("'{}' is not an operation id", value)))
+}
+
+/// Create a client from a `WipeConfiguration` as JSON. Returns NULL on failure.
+///
+/// # Safety
+/// `config_json` must be NULL or a NUL-terminated string.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn safewipe_client_new(config_json: *const c_char) -> *mut SafewipeClient {
+    guard(|_| ptr::null_mut(), || {
+        let config_json = unsafe { read_str(config_json, "config_json") }?;
+        let config: WipeConfiguration = serde_json::from_str(config_json)
+            .map_err(|e| invalid(format!("Invalid configuration: {}", e)))?;
+        let engine = Arc::new(WipeEngine::new(config).map_err(engine_error)?);
+        let jobs: Arc<Mutex<HashMap<Uuid, Job>>> = Arc::new(Mutex::new(HashMap::new()));
+
+        // Keep the latest progress of each wipe for polling
+        let progress = engine.subscribe_progress();
+        let progress_jobs = Arc::downgrade(&jobs);
+        thread::spawn(move || {
+            while let Ok(update) = progress.recv() {
+                let Some(jobs) = progress_jobs.upgrade() else { break };
+                if let Some(job) = jobs.lock().unwrap().get_mut(&update.request_id) {
+                    if update.verifying && job.status == JobStatus::Running {
+                        job.status = JobStatus::Verifying;
+                    }
+                    job.progress = Some(update);
+                }
+            }
+        });
+
+        Ok(Box::into_raw(Box::new(SafewipeClient { engine, jobs })))
+    })
+}
+
+/// Free a client. Wipes still running finish in the background.
+///
+/// # Safety
+/// `client` must be NULL or a pointer returned by `safewipe_client_new` that
+/// has not been freed, and no other thread may be using it.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn safewipe_client_free(client: *mut SafewipeClient) {
+    if !client.is_null() {
+        let _ = catch_unwind(AssertUnwindSafe(|| drop(unsafe { Box::from_raw(client) })));
+    }
+}
+
+/// Log in as an operator; wipes are recorded under this operator
+///
+/// # Safety
+/// `client` must be a live client, `username` and `password` NUL-terminated strings.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn safewipe_login(
+    client: *const SafewipeClient,
+    username: *const c_char,
+    password: *const c_char,
+) -> SafewipeStatus {
+    guard(|status| status, || {
+        let client = unsafe { client_ref(client) }?;
+        let username = unsafe { read_str(username, "username") }?;
+        let password = unsafe { read_str(password, "password") }?;
+        client.engine.login(username, password).map_err(engine_error)?;
+        Ok(SafewipeStatus::Ok)
+    })
+}
+
+/// Log in with an API token issued by an administrator
+///
+/// # Safety
+/// `client` must be a live client and `token` a NUL-terminated string.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn safewipe_login_token(client: *const SafewipeClient, token: *const c_char) -> SafewipeStatus {
+    guard(|status| status, || {
+        let client = unsafe { client_ref(client) }?;
+        let token = unsafe { read_str(token, "token") }?;
+        client.engine.login_with_token(token).map_err(engine_error)?;
+        Ok(SafewipeStatus::Ok)
+    })
+}
+
+/// Attached disks as a JSON array. Free the result with `safewipe_string_free`.
+///
+/// # Safety
+/// `client` must be a live client.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn safewipe_list_devices_json(client: *const SafewipeClient) -> *mut c_char {
+    guard(|_| ptr::null_mut(), || {
+        let client = unsafe { client_ref(client) }?;
+        let disks = client.engine.scan_devices().map_err(engine_error)?;
+        let devices: Vec<serde_json::Value> = disks
+            .iter()
+            .map(|disk| serde_json::json!({
+                "path": disk.path,
+                "description": disk.description(),
+                "vendor": disk.vendor,
+                "model": disk.model,
+                "serial": disk.serial,
+                "bus_type": disk.bus_type,
+                "kind": disk.kind.as_str(),
+                "virtual": disk.kind.is_virtual(),
+                "size_bytes": disk.size_bytes,
+                "removable": disk.removable,
+                "is_root_disk": disk.is_root_disk,
+                "volumes": disk.volumes,
+            }))
+            .collect();
+        to_json(&devices)
+    })
+}
+
+/// Start a wipe described by `request_json`, e.g.
+/// `{"device":"/dev/sdb","target_type":"HDD","standard":"NIST_SP_800_88_R1"}`.
+/// Returns the operation id; free it with `safewipe_string_free`.
+///
+/// # Safety
+/// `client` must be a live client and `request_json` a NUL-terminated string.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn safewipe_start_wipe(client: *const SafewipeClient, request_json: *const c_char) -> *mut c_char {
+    guard(|_| ptr::null_mut(), || {
+        let client = unsafe { client_ref(client) }?;
+        let body: StartWipe = serde_json::from_str(unsafe { read_str(request_json, "request_json") }?)
+            .map_err(|e| invalid(format!("Invalid wipe request: {}", e)))?;
+        let operator = client.engine.current_operator().ok_or_else(|| {
+            (
+                SafewipeStatus::NotAuthenticated,
+                SafewipeErrorCode::NotAuthenticated,
+                "Log in with safewipe_login or safewipe_login_token first".to_string(),
+            )
+        })?;
+        operator.require_sanitize().map_err(engine_error)?;
+
+        let mut request = WipeEngine::build_request(
+            body.device,
+            body.target_type,
+            body.scope,
+            body.standard,
+            body.verify,
+            body.certificate,
+        );
+        request.profile = body.profile;
+        request.confirmation = body.confirmation;
+        request.simulate = body.simulate;
+        request.skip_trim = body.skip_trim;
+        request.security_state = body.security_state;
+        request.priority = body.priority;
+        let operation_id = request.id;
+
+        client.jobs.lock().unwrap().insert(operation_id, Job {
+            operation_id,
+            status: JobStatus::Running,
+            progress: None,
+            result: None,
+            certificate_path: None,
+            error: None,
+            error_code: None,
+        });
+
+        let engine = client.engine.clone();
+        let jobs = client.jobs.clone();
+        thread::spawn(move || {
+            let outcome = catch_unwind(AssertUnwindSafe(|| engine.execute_request_as(&operator, request)));
+            let mut jobs = jobs.lock().unwrap();
+            let Some(job) = jobs.get_mut(&operation_id) else { return };
+            match outcome {
+                Ok(Ok((result, certificate_path))) => {
+                    job.status = if result.success { JobStatus::Completed } else { JobStatus::Failed };
+                    job.error = result.error_message.clone();
+                    job.result = Some(result);
+                    job.certificate_path = certificate_path;
+                }
+                Ok(Err(e)) => {
+                    job.status = if matches!(e.code, WipeErrorCode::Cancelled) { JobStatus::Cancelled } else { JobStatus::Failed };
+                    job.error = Some(e.to_string());
+                    job.error_code = Some(e.error_code());
+                }
+                Err(_) => {
+                    job.status = JobStatus::Failed;
+                    job.error = Some("Internal error: the wipe panicked".to_string());
+                    job.error_code = Some(SafeWipeErrorCode::Internal);
+                }
+            }
+        });
+
+        into_c_string(operation_id.to_string())
+    })
+}
+
+/// State of a wipe started by this client as JSON: `status` (running,
+/// verifying, completed, failed, cancelled), the latest `progress`, and once finished
+/// `result`, `certificate_path`, `error` and `error_code`. Free it with
+/// `safewipe_string_free`.
+///
+/// # Safety
+/// `client` must be a live client and `operation_id` a NUL-terminated string.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn safewipe_poll_progress(client: *const SafewipeClient, operation_id: *const c_char) -> *mut c_char {
+    guard(|_| ptr::null_mut(), || {
+        let client = unsafe { client_ref(client) }?;
+        let operation_id = parse_operation_id(unsafe { read_str(operation_id, "operation_id") }?)?;
+        let job = client.jobs.lock().unwrap().get(&operation_id).cloned().ok_or_else(|| {
+            (
+                SafewipeStatus::NotFound,
+                SafewipeErrorCode::NotFound,
+                format!("No wipe with id {} was started by this client", operation_id),
+            )
+        })?;
+        to_json(&job)
+    })
+}
+
+/// Ask a running wipe to stop
+///
+/// # Safety
+/// `client` must be a live client and `operation_id` a NUL-terminated string.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn safewipe_cancel(client: *const SafewipeClient, operation_id: *const c_char) -> SafewipeStatus {
+    guard(|status| status, || {
+        let client = unsafe { client_ref(client) }?;
+        let operation_id = parse_operation_id(unsafe { read_str(operation_id, "operation_id") }?)?;
+        client.engine.cancel_wipe(operation_id).map_err(engine_error)?;
+        Ok(SafewipeStatus::Ok)
+    })
+}
+
+/// Description of the last failure on the calling thread, or NULL. Free it
+/// with `safewipe_string_free`.
+#[unsafe(no_mangle)]
+pub extern "C" fn safewipe_last_error() -> *mut c_char {
+    LAST_ERROR
+        .with(|last| last.borrow().clone())
+        .and_then(|(_, message)| CString::new(message.replace('\0', " ")).ok())
+        .map_or(ptr::null_mut(), CString::into_raw)
+}
+
+/// Cause of the last failure on the calling thread, or
+/// `SAFEWIPE_ERROR_CODE_NONE` when the last call succeeded
+#[unsafe(no_mangle)]
+pub extern "C" fn safewipe_last_error_code() -> SafewipeErrorCode {
+    LAST_ERROR.with(|last| last.borrow().as_ref().map_or(SafewipeErrorCode::None, |(code, _)| *code))
+}
+
+/// Free a string returned by the library
+///
+/// # Safety
+/// `value` must be NULL or a string returned by this library that has not been freed.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn safewipe_string_free(value: *mut c_char) {
+    if !value.is_null() {
+        drop(unsafe { CString::from_raw(value) });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn last_error() -> Option<String> {
+        let message = safewipe_last_error();
+        if message.is_null() {
+            return None;
+        }
+        let text = unsafe { CStr::from_ptr(message) }.to_string_lossy().to_string();
+        unsafe { safewipe_string_free(message) };
+        Some(text)
+    }
+
+    #[test]
+    fn test_client_is_shareable_between_threads() {
+        fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<SafewipeClient>();
+    }
+
+    #[test]
+    fn test_invalid_arguments_are_reported_per_thread() {
+        assert!(unsafe { safewipe_client_new(ptr::null()) }.is_null());
+        assert_eq!(last_error().as_deref(), Some("config_json is NULL"));
+        assert_eq!(safewipe_last_error_code(), SafewipeErrorCode::InvalidArgument);
+
+        let config = CString::new("{not json").unwrap();
+        assert!(unsafe { safewipe_client_new(config.as_ptr()) }.is_null());
+        assert!(last_error().unwrap().starts_with("Invalid configuration"));
+
+        // Errors do not leak into other threads, and a successful call clears them
+        assert_eq!(unsafe { safewipe_cancel(ptr::null(), ptr::null()) }, SafewipeStatus::InvalidArgument);
+        thread::spawn(|| assert_eq!(last_error(), None)).join().unwrap();
+        assert!(last_error().is_some());
+
+        let panicked: i32 = guard(|_| -1, || panic!("boom"));
+        assert_eq!(panicked, -1);
+        assert_eq!(last_error().as_deref(), Some("Internal error: boom"));
+        assert_eq!(safewipe_last_error_code(), SafewipeErrorCode::Internal);
+        thread::spawn(|| assert_eq!(safewipe_last_error_code(), SafewipeErrorCode::None)).join().unwrap();
+    }
+}
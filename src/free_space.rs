@@ -0,0 +1,584 @@
+//! Free-space-only wiping
+//!
+//! Overwrites the unallocated space of a mounted filesystem without touching its
+//! files. The volume is filled with random fill files, which are flushed to disk
+//! and deleted again. Per filesystem:
+//! - NTFS: the volume bitmap reports how much free space the fill could not
+//!   claim, and free MFT records are overwritten with small resident files
+//! - FAT: fill files are kept below the 4 GiB FAT32 file size limit
+//! - ext2/3/4: freed blocks are discarded with fstrim afterwards
+//!
+//! Fill files live in a single directory that is removed when the wipe finishes,
+//! fails or is cancelled, and at the start of the next run if a previous one was
+//! killed part way.
+
+use std::fs::{self, File};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Instant;
+use tracing::{info, warn};
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+
+const FILL_DIR_NAME: &str = ".hddtool-free-space-wipe";
+/// Size of each fill file; below the FAT32 limit of 4 GiB - 1
+const FILL_FILE_SIZE: u64 = 1024 * 1024 * 1024;
+const _: () = assert!(FILL_FILE_SIZE < 4 * 1024 * 1024 * 1024 - 1);
+const WRITE_BLOCK_SIZE: usize = 4 * 1024 * 1024;
+/// Small enough for NTFS to store the data resident inside the MFT record
+const MFT_RECORD_FILL_SIZE: usize = 512;
+const MAX_MFT_RECORD_FILES: u64 = 1_000_000;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum FilesystemKind {
+    Ntfs,
+    Fat,
+    ExFat,
+    Ext4,
+    Other(String),
+}
+
+impl FilesystemKind {
+    pub fn from_name(name: &str) -> Self {
+        match name.to_ascii_lowercase().as_str() {
+            "ntfs" | "ntfs3" => FilesystemKind::Ntfs,
+            "fat" | "fat12" | "fat16" | "fat32" | "vfat" | "msdos" => FilesystemKind::Fat,
+            "exfat" => FilesystemKind::ExFat,
+            "ext2" | "ext3" | "ext4" => FilesystemKind::Ext4,
+            other => FilesystemKind::Other(other.to_string()),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct FreeSpaceProgress {
+    pub pass: u32,
+    pub total_passes: u32,
+    pub bytes_written: u64,
+    /// Free space when the pass started
+    pub bytes_total: u64,
+    pub operation: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FreeSpaceReport {
+    pub mount_point: String,
+    pub filesystem: FilesystemKind,
+    pub passes: u32,
+    pub free_bytes_before: u64,
+    /// Total over all passes
+    pub bytes_written: u64,
+    /// Free space left over while the volume was full on the last pass; the
+    /// filesystem keeps some clusters back that no file can claim
+    pub unfilled_bytes: u64,
+    pub mft_records_filled: u64,
+    pub trimmed: bool,
+    pub duration_seconds: f64,
+}
+
+/// Directory holding the fill files; removed again when dropped
+struct FillDirectory {
+    path: PathBuf,
+}
+
+impl FillDirectory {
+    fn create(mount_point: &str) -> io::Result<Self> {
+        let path = Path::new(mount_point).join(FILL_DIR_NAME);
+        if path.exists() {
+            info!(path = %path.display(), "Removing fill files left by an interrupted free space wipe");
+            fs::remove_dir_all(&path)?;
+        }
+        fs::create_dir(&path)?;
+        Ok(Self { path })
+    }
+
+    /// Delete the fill files of a finished pass
+    fn clear(&self) -> io::Result<()> {
+        fs::remove_dir_all(&self.path)?;
+        fs::create_dir(&self.path)
+    }
+}
+
+impl Drop for FillDirectory {
+    fn drop(&mut self) {
+        if let Err(e) = fs::remove_dir_all(&self.path) {
+            warn!(path = %self.path.display(), error = %e, "Could not remove fill directory");
+        }
+    }
+}
+
+fn is_disk_full(error: &io::Error) -> bool {
+    #[cfg(windows)]
+    const DISK_FULL_CODES: &[i32] = &[39, 112]; // ERROR_HANDLE_DISK_FULL, ERROR_DISK_FULL
+    #[cfg(unix)]
+    const DISK_FULL_CODES: &[i32] = &[libc::ENOSPC, libc::EDQUOT, libc::EFBIG];
+    #[cfg(not(any(windows, unix)))]
+    const DISK_FULL_CODES: &[i32] = &[];
+
+    matches!(error.kind(), io::ErrorKind::StorageFull | io::ErrorKind::WriteZero)
+        || error.raw_os_error().is_some_and(|code| DISK_FULL_CODES.contains(&code))
+}
+
+pub struct FreeSpaceWiper {
+    passes: u32,
+    cancelled: Arc<AtomicBool>,
+}
+
+impl FreeSpaceWiper {
+    pub fn new(passes: u32) -> Self {
+        Self {
+            passes: passes.max(1),
+            cancelled: Arc::new(AtomicBool::new(false)),
+        }
+    }
+
+    /// Set the returned flag to stop the wipe; the fill files are removed before it returns
+    pub fn cancel_handle(&self) -> Arc<AtomicBool> {
+        self.cancelled.clone()
+    }
+
+    /// Overwrite the free space of the filesystem mounted at `mount_point`
+    pub fn wipe<F>(&self, mount_point: &str, mut on_progress: F) -> io::Result<FreeSpaceReport>
+    where
+        F: FnMut(FreeSpaceProgress),
+    {
+        let started = Instant::now();
+        let filesystem = platform_fs::filesystem_name(mount_point)
+            .map(|name| FilesystemKind::from_name(&name))
+            .unwrap_or_else(|| FilesystemKind::Other("unknown".to_string()));
+
+        info!(mount_point = %mount_point, filesystem = ?filesystem, passes = self.passes, "Wiping free space");
+
+        let fill_dir = FillDirectory::create(mount_point)?;
+        let free_bytes_before = platform_fs::free_bytes(mount_point)?;
+        let mut report = FreeSpaceReport {
+            mount_point: mount_point.to_string(),
+            filesystem: filesystem.clone(),
+            passes: self.passes,
+            free_bytes_before,
+            bytes_written: 0,
+            unfilled_bytes: 0,
+            mft_records_filled: 0,
+            trimmed: false,
+            duration_seconds: 0.0,
+        };
+
+        for pass in 1..=self.passes {
+            let bytes_total = platform_fs::free_bytes(mount_point)?;
+            report.bytes_written += self.fill_pass(&fill_dir.path, pass, bytes_total, &mut on_progress)?;
+
+            // Measured while the fill files still occupy the volume
+            report.unfilled_bytes = if filesystem == FilesystemKind::Ntfs {
+                platform_fs::ntfs_free_bytes(mount_point).or_else(|_| platform_fs::free_bytes(mount_point))?
+            } else {
+                platform_fs::free_bytes(mount_point)?
+            };
+
+            if filesystem == FilesystemKind::Ntfs && pass == self.passes {
+                on_progress(FreeSpaceProgress {
+                    pass,
+                    total_passes: self.passes,
+                    bytes_written: bytes_total,
+                    bytes_total,
+                    operation: "Overwriting free MFT records".to_string(),
+                });
+                report.mft_records_filled = self.fill_mft_records(&fill_dir.path)?;
+            }
+
+            fill_dir.clear()?;
+            info!(pass, total_passes = self.passes, "Free space pass complete");
+        }
+        drop(fill_dir);
+
+        if filesystem == FilesystemKind::Ext4 {
+            report.trimmed = platform_fs::fstrim(mount_point);
+        }
+
+        report.duration_seconds = started.elapsed().as_secs_f64();
+        info!(
+            mount_point = %mount_point,
+            bytes_written = report.bytes_written,
+            unfilled_bytes = report.unfilled_bytes,
+            "Free space wipe complete"
+        );
+        Ok(report)
+    }
+
+    fn check_cancelled(&self) -> io::Result<()> {
+        if self.cancelled.load(Ordering::Relaxed) {
+            Err(io::Error::new(io::ErrorKind::Interrupted, "Free space wipe cancelled"))
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Write fill files until the volume is full; returns the bytes written
+    fn fill_pass<F>(&self, dir: &Path, pass: u32, bytes_total: u64, on_progress: &mut F) -> io::Result<u64>
+    where
+        F: FnMut(FreeSpaceProgress),
+    {
+        let mut buffer = vec![0u8; WRITE_BLOCK_SIZE];
+        let mut rng = rand::thread_rng();
+        let mut bytes_written = 0u64;
+
+        for file_index in 0u64.. {
+            let path = dir.join(format!("fill_{}.tmp", file_index));
+            let mut file = match File::create(&path) {
+                Ok(file) => file,
+                Err(e) if is_disk_full(&e) => break,
+                Err(e) => return Err(e),
+            };
+
+            let mut disk_full = false;
+            let mut file_bytes = 0u64;
+            while file_bytes < FILL_FILE_SIZE {
+                self.check_cancelled()?;
+                rng.fill_bytes(&mut buffer);
+
+                match file.write_all(&buffer) {
+                    Ok(()) => {
+                        file_bytes += buffer.len() as u64;
+                        bytes_written += buffer.len() as u64;
+                    }
+                    Err(e) if is_disk_full(&e) => {
+                        disk_full = true;
+                        break;
+                    }
+                    Err(e) => return Err(e),
+                }
+
+                on_progress(FreeSpaceProgress {
+                    pass,
+                    total_passes: self.passes,
+                    bytes_written: bytes_written.min(bytes_total),
+                    bytes_total,
+                    operation: format!("Filling free space (pass {}/{})", pass, self.passes),
+                });
+            }
+
+            // Filesystems with delayed allocation would otherwise drop the data
+            // unwritten when the file is deleted
+            match file.sync_all() {
+                Ok(()) => {}
+                Err(e) if is_disk_full(&e) => disk_full = true,
+                Err(e) => return Err(e),
+            }
+
+            if disk_full {
+                break;
+            }
+        }
+
+        Ok(bytes_written)
+    }
+
+    /// Create small files until no more MFT records are free, so records left by
+    /// deleted files are overwritten. Runs while the volume is full so the data
+    /// cannot go to clusters instead.
+    fn fill_mft_records(&self, dir: &Path) -> io::Result<u64> {
+        let mut data = vec![0u8; MFT_RECORD_FILL_SIZE];
+        rand::thread_rng().fill_bytes(&mut data);
+
+        let mft_dir = dir.join("mft");
+        fs::create_dir(&mft_dir)?;
+
+        let mut count = 0u64;
+        while count < MAX_MFT_RECORD_FILES {
+            self.check_cancelled()?;
+            let written = File::create(mft_dir.join(format!("{}.tmp", count)))
+                .and_then(|mut file| file.write_all(&data));
+            if written.is_err() {
+                break;
+            }
+            count += 1;
+        }
+
+        info!(records = count, "Overwrote free MFT records");
+        Ok(count)
+    }
+}
+
+/// Mount points of the filesystems on `device_path`. A directory (such as a
+/// drive root like `E:\`) is returned as is.
+pub fn mount_points_for(device_path: &str) -> Vec<String> {
+    if Path::new(device_path).is_dir() {
+        return vec![device_path.to_string()];
+    }
+    platform_fs::mount_points_for_device(device_path)
+}
+
+#[cfg(unix)]
+mod platform_fs {
+    use std::ffi::CString;
+    use std::io;
+    use tracing::{info, warn};
+
+    fn mounts() -> Vec<(String, String, String)> {
+        std::fs::read_to_string("/proc/self/mounts")
+            .unwrap_or_default()
+            .lines()
+            .filter_map(|line| {
+                let mut fields = line.split_whitespace();
+                let source = fields.next()?.to_string();
+                let mount_point = fields.next()?.replace("\\040", " ");
+                let fs_type = fields.next()?.to_string();
+                Some((source, mount_point, fs_type))
+            })
+            .collect()
+    }
+
+    pub fn filesystem_name(mount_point: &str) -> Option<String> {
+        let target = std::fs::canonicalize(mount_point).ok()?;
+        mounts()
+            .into_iter()
+            .filter(|(_, point, _)| target.starts_with(point))
+            .max_by_key(|(_, point, _)| point.len())
+            .map(|(_, _, fs_type)| fs_type)
+    }
+
+    pub fn mount_points_for_device(device_path: &str) -> Vec<String> {
+        let Ok(device) = std::fs::canonicalize(device_path) else {
+            return Vec::new();
+        };
+        let device = device.to_string_lossy().to_string();
+
+        // Partitions are named after the disk (sda1, nvme0n1p1)
+        mounts()
+            .into_iter()
+            .filter(|(source, _, _)| {
+                std::fs::canonicalize(source)
+                    .map(|s| s.to_string_lossy().starts_with(&device))
+                    .unwrap_or(false)
+            })
+            .map(|(_, mount_point, _)| mount_point)
+            .collect()
+    }
+
+    /// Free bytes including blocks reserved for root, which the wipe runs as
+    pub fn free_bytes(mount_point: &str) -> io::Result<u64> {
+        let path = CString::new(mount_point)
+            .map_err(|_| io::Error::new(io::ErrorKind::InvalidInput, "Invalid mount point"))?;
+        let mut stats: libc::statvfs = unsafe { std::mem::zeroed() };
+        if unsafe { libc::statvfs(path.as_ptr(), &mut stats) } != 0 {
+            return Err(io::Error::last_os_error());
+        }
+        Ok(stats.f_bfree as u64 * stats.f_frsize as u64)
+    }
+
+    pub fn ntfs_free_bytes(mount_point: &str) -> io::Result<u64> {
+        free_bytes(mount_point)
+    }
+
+    /// Discard the blocks the fill files occupied
+    pub fn fstrim(mount_point: &str) -> bool {
+        match std::process::Command::new("fstrim").args(["-v", mount_point]).output() {
+            Ok(output) if output.status.success() => {
+                info!(mount_point = %mount_point, "{}", String::from_utf8_lossy(&output.stdout).trim());
+                true
+            }
+            _ => {
+                warn!(mount_point = %mount_point, "fstrim not available; freed blocks were not discarded");
+                false
+            }
+        }
+    }
+}
+
+#[cfg(windows)]
+mod platform_fs {
+    use std::io;
+    use windows::{
+        core::{HRESULT, PWSTR},
+        Win32::Foundation::{CloseHandle, HANDLE},
+        Win32::Storage::FileSystem::{
+            CreateFileW, GetDiskFreeSpaceExW, GetDiskFreeSpaceW, GetVolumeInformationW,
+            FILE_ATTRIBUTE_NORMAL, FILE_SHARE_READ, FILE_SHARE_WRITE, OPEN_EXISTING,
+        },
+        Win32::System::IO::DeviceIoControl,
+    };
+
+    const FSCTL_GET_VOLUME_BITMAP: u32 = 0x0009_006F;
+    const ERROR_MORE_DATA: u32 = 234;
+    /// Bitmap bytes fetched per call (covers 8M clusters)
+    const BITMAP_CHUNK_SIZE: usize = 1024 * 1024;
+
+    fn wide(value: &str) -> Vec<u16> {
+        value.encode_utf16().chain(std::iter::once(0)).collect()
+    }
+
+    fn root(mount_point: &str) -> String {
+        format!("{}\\", mount_point.trim_end_matches('\\'))
+    }
+
+    pub fn filesystem_name(mount_point: &str) -> Option<String> {
+        let root = wide(&root(mount_point));
+        let mut file_system_buffer = [0u16; 64];
+        unsafe {
+            GetVolumeInformationW(
+                PWSTR::from_raw(root.as_ptr() as *mut u16),
+                None,
+                None,
+                None,
+                None,
+                Some(&mut file_system_buffer),
+            ).ok()?;
+        }
+        Some(String::from_utf16_lossy(&file_system_buffer).trim_end_matches('\0').to_string())
+    }
+
+    pub fn mount_points_for_device(_device_path: &str) -> Vec<String> {
+        // Not resolved from \\.\PhysicalDriveN; pass the drive root (E:\) instead
+        Vec::new()
+    }
+
+    pub fn free_bytes(mount_point: &str) -> io::Result<u64> {
+        let root = wide(&root(mount_point));
+        let mut free_available = 0u64;
+        let mut total = 0u64;
+        let mut total_free = 0u64;
+        unsafe {
+            GetDiskFreeSpaceExW(
+                PWSTR::from_raw(root.as_ptr() as *mut u16),
+                Some(&mut free_available),
+                Some(&mut total),
+                Some(&mut total_free),
+            ).map_err(|e| io::Error::new(io::ErrorKind::Other, format!("Failed to query free space: {}", e)))?;
+        }
+        Ok(total_free)
+    }
+
+    /// Count free clusters in the NTFS volume bitmap
+    pub fn ntfs_free_bytes(mount_point: &str) -> io::Result<u64> {
+        let root_path = wide(&root(mount_point));
+        let mut sectors_per_cluster = 0u32;
+        let mut bytes_per_sector = 0u32;
+        unsafe {
+            GetDiskFreeSpaceW(
+                PWSTR::from_raw(root_path.as_ptr() as *mut u16),
+                Some(&mut sectors_per_cluster),
+                Some(&mut bytes_per_sector),
+                None,
+                None,
+            ).map_err(|e| io::Error::new(io::ErrorKind::Other, format!("Failed to query cluster size: {}", e)))?;
+        }
+
+        let volume = wide(&format!("\\\\.\\{}", mount_point.trim_end_matches('\\')));
+        let handle: HANDLE = unsafe {
+            CreateFileW(
+                PWSTR::from_raw(volume.as_ptr() as *mut u16),
+                0x80000000u32, // GENERIC_READ
+                FILE_SHARE_READ | FILE_SHARE_WRITE,
+                None,
+                OPEN_EXISTING,
+                FILE_ATTRIBUTE_NORMAL,
+                HANDLE::default(),
+            ).map_err(|e| io::Error::new(io::ErrorKind::Other, format!("Failed to open volume: {}", e)))?
+        };
+
+        // Output: StartingLcn (i64), BitmapSize (i64), then one bit per cluster
+        let mut output = vec![0u8; 16 + BITMAP_CHUNK_SIZE];
+        let mut starting_lcn = 0i64;
+        let mut free_clusters = 0u64;
+
+        let result = loop {
+            let input = starting_lcn.to_le_bytes();
+            let mut bytes_returned = 0u32;
+            let call = unsafe {
+                DeviceIoControl(
+                    handle,
+                    FSCTL_GET_VOLUME_BITMAP,
+                    Some(input.as_ptr() as *const _),
+                    input.len() as u32,
+                    Some(output.as_mut_ptr() as *mut _),
+                    output.len() as u32,
+                    Some(&mut bytes_returned),
+                    None,
+                )
+            };
+            let more_data = match call {
+                Ok(()) => false,
+                Err(e) if e.code() == HRESULT::from_win32(ERROR_MORE_DATA) => true,
+                Err(e) => break Err(io::Error::new(io::ErrorKind::Other, format!("Failed to read volume bitmap: {}", e))),
+            };
+
+            let start = i64::from_le_bytes(output[0..8].try_into().unwrap());
+            let remaining_clusters = i64::from_le_bytes(output[8..16].try_into().unwrap()) as u64;
+            let bitmap = &output[16..(bytes_returned as usize).max(16)];
+            let clusters = remaining_clusters.min(bitmap.len() as u64 * 8);
+
+            free_clusters += (0..clusters)
+                .filter(|&bit| bitmap[(bit / 8) as usize] & (1 << (bit % 8)) == 0)
+                .count() as u64;
+
+            if !more_data {
+                break Ok(());
+            }
+            starting_lcn = start + clusters as i64;
+        };
+
+        unsafe {
+            let _ = CloseHandle(handle);
+        }
+        result?;
+        Ok(free_clusters * sectors_per_cluster as u64 * bytes_per_sector as u64)
+    }
+
+    pub fn fstrim(_mount_point: &str) -> bool {
+        false
+    }
+}
+
+#[cfg(not(any(unix, windows)))]
+mod platform_fs {
+    use std::io;
+
+    pub fn filesystem_name(_mount_point: &str) -> Option<String> {
+        None
+    }
+
+    pub fn mount_points_for_device(_device_path: &str) -> Vec<String> {
+        Vec::new()
+    }
+
+    pub fn free_bytes(_mount_point: &str) -> io::Result<u64> {
+        Err(io::Error::new(io::ErrorKind::Unsupported, "Free space query is not available on this platform"))
+    }
+
+    pub fn ntfs_free_bytes(mount_point: &str) -> io::Result<u64> {
+        free_bytes(mount_point)
+    }
+
+    pub fn fstrim(_mount_point: &str) -> bool {
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_filesystem_kind_from_name() {
+        assert_eq!(FilesystemKind::from_name("NTFS"), FilesystemKind::Ntfs);
+        assert_eq!(FilesystemKind::from_name("vfat"), FilesystemKind::Fat);
+        assert_eq!(FilesystemKind::from_name("FAT32"), FilesystemKind::Fat);
+        assert_eq!(FilesystemKind::from_name("ext4"), FilesystemKind::Ext4);
+        assert_eq!(FilesystemKind::from_name("xfs"), FilesystemKind::Other("xfs".to_string()));
+    }
+
+    #[test]
+    fn test_fill_directory_removes_stale_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        fs::create_dir_all(root.join(FILL_DIR_NAME)).unwrap();
+        fs::write(root.join(FILL_DIR_NAME).join("fill_0.tmp"), b"stale").unwrap();
+
+        let fill_dir = FillDirectory::create(root.to_str().unwrap()).unwrap();
+        assert_eq!(fs::read_dir(&fill_dir.path).unwrap().count(), 0);
+        drop(fill_dir);
+
+        assert!(!root.join(FILL_DIR_NAME).exists());
+    }
+}
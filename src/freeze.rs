@@ -0,0 +1,246 @@
+/*!
+ * ATA Security Freeze-Lock Mitigation
+ *
+ * Most BIOSes (and some kernels) issue SECURITY FREEZE LOCK during boot, after
+ * which the drive rejects every ATA security command - including SECURITY ERASE
+ * UNIT - until it next loses power. This module provides:
+ * - Detection of the frozen state from IDENTIFY DEVICE word 128
+ * - Link-level hot-plug emulation (SCSI device delete + host rescan) on Linux
+ * - Suspend-to-RAM / resume, which power-cycles the drive without a reboot
+ * - Re-probing the drive afterwards and clear operator guidance when it stays frozen
+ *
+ * ⚠️ WARNING: Suspend-to-RAM suspends the whole machine. It is only attempted
+ * when explicitly allowed by the caller.
+ */
+
+use serde::{Deserialize, Serialize};
+use std::io;
+use std::time::Duration;
+use tracing::{info, warn};
+use crate::ata_commands::AtaInterface;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum FreezeStatus {
+    /// Security commands are accepted
+    NotFrozen,
+    /// Security commands are rejected until the drive is power-cycled
+    Frozen,
+    /// The drive was frozen and has been unfrozen by a mitigation step
+    Unfrozen,
+    /// The drive does not implement the ATA Security feature set
+    NotApplicable,
+    /// The drive could not be queried
+    Unknown,
+}
+
+impl FreezeStatus {
+    pub fn from_ata(security_supported: bool, security_frozen: bool) -> Self {
+        match (security_supported, security_frozen) {
+            (false, _) => FreezeStatus::NotApplicable,
+            (true, true) => FreezeStatus::Frozen,
+            (true, false) => FreezeStatus::NotFrozen,
+        }
+    }
+
+    /// ATA security commands can be issued right now
+    pub fn allows_security_commands(&self) -> bool {
+        matches!(self, FreezeStatus::NotFrozen | FreezeStatus::Unfrozen)
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            FreezeStatus::NotFrozen => "Not frozen",
+            FreezeStatus::Frozen => "Frozen",
+            FreezeStatus::Unfrozen => "Unfrozen",
+            FreezeStatus::NotApplicable => "Not applicable",
+            FreezeStatus::Unknown => "Unknown",
+        }
+    }
+
+    /// What the operator should do about this state
+    pub fn guidance(&self) -> &'static str {
+        match self {
+            FreezeStatus::NotFrozen | FreezeStatus::Unfrozen => "ATA Secure Erase is available",
+            FreezeStatus::Frozen => {
+                "Drive security is frozen by the BIOS. Suspend and resume the system (sleep/S3), \
+                 or disconnect and reconnect the drive's power or data cable while the system is \
+                 running, then rescan. Drives behind USB bridges or RAID controllers may need to be \
+                 moved to a direct SATA port."
+            }
+            FreezeStatus::NotApplicable => "Drive does not support the ATA Security feature set",
+            FreezeStatus::Unknown => "Security state could not be read; check that the drive is attached via SATA",
+        }
+    }
+}
+
+/// Query the drive's current freeze state
+pub fn detect_freeze_status(device_path: &str) -> FreezeStatus {
+    match AtaInterface::new(device_path).and_then(|ata| ata.get_drive_info()) {
+        Ok(drive_info) => FreezeStatus::from_ata(drive_info.security_supported, drive_info.security_frozen),
+        Err(_) => FreezeStatus::Unknown,
+    }
+}
+
+/// Outcome of an unfreeze attempt; the drive may re-enumerate under a new path
+#[derive(Debug, Clone)]
+pub struct MitigationOutcome {
+    pub status: FreezeStatus,
+    pub device_path: String,
+    pub steps: Vec<String>,
+}
+
+pub struct FreezeMitigation {
+    allow_suspend: bool,
+    suspend_seconds: u32,
+    settle_time: Duration,
+}
+
+impl FreezeMitigation {
+    pub fn new() -> Self {
+        Self {
+            allow_suspend: false,
+            suspend_seconds: 5,
+            settle_time: Duration::from_secs(3),
+        }
+    }
+
+    /// Allow suspending the whole machine to power-cycle the drive
+    pub fn with_suspend(allow_suspend: bool) -> Self {
+        Self {
+            allow_suspend,
+            ..Self::new()
+        }
+    }
+
+    /// Try the available workarounds in order of intrusiveness, re-probing after each
+    pub fn unfreeze(&self, device_path: &str) -> io::Result<MitigationOutcome> {
+        let mut outcome = MitigationOutcome {
+            status: detect_freeze_status(device_path),
+            device_path: device_path.to_string(),
+            steps: Vec::new(),
+        };
+
+        if outcome.status != FreezeStatus::Frozen {
+            return Ok(outcome);
+        }
+
+        info!(device = %device_path, "Drive is security frozen, attempting mitigation");
+
+        #[cfg(target_os = "linux")]
+        {
+            match self.link_reset(&outcome.device_path) {
+                Ok(new_path) => {
+                    outcome.steps.push(format!("SCSI device removed and host rescanned ({})", new_path));
+                    outcome.device_path = new_path;
+                    if self.reprobe(&mut outcome) {
+                        return Ok(outcome);
+                    }
+                }
+                Err(e) => outcome.steps.push(format!("Link reset failed: {}", e)),
+            }
+
+            if self.allow_suspend {
+                match self.suspend_resume() {
+                    Ok(()) => {
+                        outcome.steps.push(format!("System suspended to RAM for {} seconds", self.suspend_seconds));
+                        if self.reprobe(&mut outcome) {
+                            return Ok(outcome);
+                        }
+                    }
+                    Err(e) => outcome.steps.push(format!("Suspend/resume failed: {}", e)),
+                }
+            } else {
+                outcome.steps.push("Suspend/resume skipped (not allowed)".to_string());
+            }
+        }
+
+        #[cfg(not(target_os = "linux"))]
+        {
+            let _ = self.allow_suspend;
+            outcome.steps.push("No automatic unfreeze method is available on this platform".to_string());
+        }
+
+        warn!(guidance = %FreezeStatus::Frozen.guidance(), "Drive is still frozen");
+        Ok(outcome)
+    }
+
+    #[cfg(target_os = "linux")]
+    fn reprobe(&self, outcome: &mut MitigationOutcome) -> bool {
+        std::thread::sleep(self.settle_time);
+
+        outcome.status = match detect_freeze_status(&outcome.device_path) {
+            FreezeStatus::NotFrozen => FreezeStatus::Unfrozen,
+            status => status,
+        };
+
+        if outcome.status == FreezeStatus::Unfrozen {
+            info!(device = %outcome.device_path, "Drive is no longer frozen");
+        }
+        outcome.status == FreezeStatus::Unfrozen
+    }
+
+    /// Emulate a hot-plug: detach the SCSI device and rescan its host. The kernel
+    /// re-probes the drive, which clears a freeze issued by the BIOS on many controllers.
+    #[cfg(target_os = "linux")]
+    fn link_reset(&self, device_path: &str) -> io::Result<String> {
+        use std::fs;
+        use std::path::Path;
+
+        let name = fs::canonicalize(device_path)?
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "Invalid device path"))?;
+
+        // The by-id link follows the drive (model + serial) across re-enumeration
+        let by_id = fs::read_dir("/dev/disk/by-id")?
+            .flatten()
+            .map(|entry| entry.path())
+            .find(|link| {
+                link.file_name().map(|n| n.to_string_lossy().starts_with("ata-")).unwrap_or(false)
+                    && fs::canonicalize(link).map(|t| t.ends_with(&name)).unwrap_or(false)
+            })
+            .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "Drive is not attached through libata"))?;
+
+        let device_link = fs::canonicalize(format!("/sys/block/{}/device", name))?;
+        let host = device_link
+            .ancestors()
+            .filter_map(|p| p.file_name().map(|n| n.to_string_lossy().to_string()))
+            .find(|n| n.starts_with("host"))
+            .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "SCSI host not found"))?;
+
+        fs::write(format!("/sys/block/{}/device/delete", name), "1")?;
+        std::thread::sleep(Duration::from_secs(1));
+        fs::write(format!("/sys/class/scsi_host/{}/scan", host), "- - -")?;
+
+        // Wait for udev to recreate the by-id link
+        for _ in 0..20 {
+            std::thread::sleep(Duration::from_millis(500));
+            if let Ok(target) = fs::canonicalize(&by_id) && Path::new(&target).exists() {
+                return Ok(target.to_string_lossy().to_string());
+            }
+        }
+
+        Err(io::Error::new(io::ErrorKind::TimedOut, "Drive did not reappear after rescan"))
+    }
+
+    #[cfg(target_os = "linux")]
+    fn suspend_resume(&self) -> io::Result<()> {
+        use std::process::Command;
+
+        let output = Command::new("rtcwake")
+            .args(["-m", "mem", "-s", &self.suspend_seconds.to_string()])
+            .output()?;
+
+        if output.status.success() {
+            Ok(())
+        } else {
+            Err(io::Error::other(String::from_utf8_lossy(&output.stderr).trim().to_string()))
+        }
+    }
+}
+
+impl Default for FreezeMitigation {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,151 @@
+//! Post-wipe functional tests
+//!
+//! A drive that is resold needs evidence that it still works, next to the
+//! evidence that it was erased. After the wipe the sanitizer can start the
+//! drive's short self-test and wait for its verdict, or read back every
+//! sector of the wiped area and count the ones that cannot be read. Either
+//! result goes into the wipe result and the certificate. A failed test does
+//! not fail the wipe: the data is gone either way.
+
+use std::io::{self, Read, Seek, SeekFrom};
+use std::time::{Duration, Instant};
+use chrono::Utc;
+
+use crate::security::{FunctionalTest, FunctionalTestKind};
+use crate::smart::{SelfTestStatus, SmartInterface};
+
+const SECTOR_SIZE: u64 = 512;
+
+/// Short self-tests take about two minutes; give up on a drive that is far slower
+pub const SELF_TEST_TIMEOUT: Duration = Duration::from_secs(15 * 60);
+const SELF_TEST_POLL_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Run the drive's short self-test and wait for it, calling `on_progress`
+/// with the percentage done after every poll. Fails when the drive cannot
+/// run a self-test, so the caller can scan its surface instead.
+pub fn run_short_self_test(device_path: &str, mut on_progress: impl FnMut(u8)) -> io::Result<FunctionalTest> {
+    let smart = SmartInterface::new(device_path);
+    smart.start_short_self_test()?;
+    let started = Instant::now();
+
+    loop {
+        std::thread::sleep(SELF_TEST_POLL_INTERVAL);
+        let (passed, detail) = match smart.self_test_status()? {
+            SelfTestStatus::InProgress { percent_complete } if started.elapsed() < SELF_TEST_TIMEOUT => {
+                on_progress(percent_complete);
+                continue;
+            }
+            SelfTestStatus::InProgress { percent_complete } => {
+                (false, format!("Did not finish within {} minutes ({}% done)", SELF_TEST_TIMEOUT.as_secs() / 60, percent_complete))
+            }
+            SelfTestStatus::Passed => (true, "Completed without error".to_string()),
+            SelfTestStatus::Failed(reason) => (false, format!("Failed: {}", reason)),
+        };
+        return Ok(FunctionalTest {
+            kind: FunctionalTestKind::ShortSelfTest,
+            passed,
+            completed_at: Utc::now(),
+            detail,
+            bytes_read: 0,
+            unreadable_sectors: 0,
+        });
+    }
+}
+
+/// Read `length` bytes from `start_offset` in `block_size` reads. A block
+/// that fails is read again sector by sector, so only the unreadable sectors
+/// are counted. `on_progress` gets the bytes read so far; returning `false`
+/// stops the scan with `ErrorKind::Interrupted`.
+pub fn surface_scan<D: Read + Seek>(
+    device: &mut D,
+    start_offset: u64,
+    length: u64,
+    block_size: usize,
+    mut on_progress: impl FnMut(u64) -> bool,
+) -> io::Result<FunctionalTest> {
+    let mut buffer = vec![0u8; block_size.max(SECTOR_SIZE as usize)];
+    let mut unreadable_sectors = 0u64;
+    let mut offset = 0u64;
+
+    while offset < length {
+        let chunk = (length - offset).min(buffer.len() as u64) as usize;
+        let read = device
+            .seek(SeekFrom::Start(start_offset + offset))
+            .and_then(|_| device.read_exact(&mut buffer[..chunk]));
+        if read.is_err() {
+            for sector in (0..chunk as u64).step_by(SECTOR_SIZE as usize) {
+                let size = (chunk as u64 - sector).min(SECTOR_SIZE) as usize;
+                let sector_read = device
+                    .seek(SeekFrom::Start(start_offset + offset + sector))
+                    .and_then(|_| device.read_exact(&mut buffer[..size]));
+                if sector_read.is_err() {
+                    unreadable_sectors += 1;
+                }
+            }
+        }
+        offset += chunk as u64;
+        if !on_progress(offset) {
+            return Err(io::Error::new(io::ErrorKind::Interrupted, "Surface scan cancelled"));
+        }
+    }
+
+    Ok(FunctionalTest {
+        kind: FunctionalTestKind::SurfaceScan,
+        passed: unreadable_sectors == 0,
+        completed_at: Utc::now(),
+        detail: match unreadable_sectors {
+            0 => "Every sector read back".to_string(),
+            count => format!("{} unreadable sectors", count),
+        },
+        bytes_read: length,
+        unreadable_sectors,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    /// A device whose reads fail when they touch `bad_sector`
+    struct FailingDevice {
+        inner: Cursor<Vec<u8>>,
+        bad_sector: u64,
+    }
+
+    impl Read for FailingDevice {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            let start = self.inner.position() / SECTOR_SIZE;
+            let end = (self.inner.position() + buf.len() as u64).div_ceil(SECTOR_SIZE);
+            if (start..end).contains(&self.bad_sector) {
+                return Err(io::Error::other("medium error"));
+            }
+            self.inner.read(buf)
+        }
+    }
+
+    impl Seek for FailingDevice {
+        fn seek(&mut self, position: SeekFrom) -> io::Result<u64> {
+            self.inner.seek(position)
+        }
+    }
+
+    #[test]
+    fn test_surface_scan_counts_unreadable_sectors() {
+        let mut device = FailingDevice { inner: Cursor::new(vec![0u8; 64 * 1024]), bad_sector: 70 };
+        let mut reported = 0;
+        let scan = surface_scan(&mut device, 0, 64 * 1024, 16 * 1024, |bytes| {
+            reported = bytes;
+            true
+        })
+        .unwrap();
+        assert_eq!(reported, 64 * 1024);
+        assert_eq!((scan.passed, scan.unreadable_sectors), (false, 1));
+        assert_eq!(scan.detail, "1 unreadable sectors");
+
+        device.bad_sector = 1000;
+        assert!(surface_scan(&mut device, 0, 64 * 1024, 16 * 1024, |_| true).unwrap().passed);
+        let cancelled = surface_scan(&mut device, 0, 64 * 1024, 16 * 1024, |_| false).unwrap_err();
+        assert_eq!(cancelled.kind(), io::ErrorKind::Interrupted);
+    }
+}
@@ -17,6 +17,9 @@ pub const ATA_SET_MAX_ADDRESS_EXT: u8 = 0x37;
 pub const ATA_SECURITY_ERASE_PREPARE: u8 = 0xF3;
 pub const ATA_SECURITY_ERASE_UNIT: u8 = 0xF4;
 pub const ATA_SANITIZE_DEVICE: u8 = 0xB4;
+pub const ATA_DEVICE_CONFIGURATION: u8 = 0xB1;
+pub const DCO_RESTORE: u8 = 0xC0;
+pub const DCO_IDENTIFY: u8 = 0xC2;
 
 const IOCTL_ATA_PASS_THROUGH: u32 = 0x0004D02C;
 const IOCTL_ATA_PASS_THROUGH_DIRECT: u32 = 0x0004D030;
@@ -287,6 +290,119 @@ impl DriveInterface {
         }
     }
 
+    /// DEVICE CONFIGURATION IDENTIFY - returns the factory maximum LBA, or None
+    /// when the drive does not implement the DCO feature set
+    pub fn dco_identify(&self) -> WipeResult2<Option<u64>> {
+        let identify_data = self.identify_device()?;
+        if identify_data.data[83] & 0x0800 == 0 {
+            return Ok(None);
+        }
+
+        let pass_through = AtaPassThroughEx {
+            length: std::mem::size_of::<AtaPassThroughEx>() as u16,
+            ata_flags: 0x02, // ATA_FLAGS_DATA_IN
+            path_id: 0,
+            target_id: 0,
+            lun: 0,
+            reserved_as_uchar: 0,
+            data_transfer_length: 512,
+            timeout_value: 10,
+            reserved_as_ulong: 0,
+            data_buffer_offset: std::mem::size_of::<AtaPassThroughEx>(),
+            previous_task_file: [0; 8],
+            current_task_file: [DCO_IDENTIFY, 0, 0, 0, 0, 0, ATA_DEVICE_CONFIGURATION, 0],
+        };
+
+        let mut bytes_returned = 0u32;
+        let buffer_size = std::mem::size_of::<AtaPassThroughEx>() + 512;
+        let mut buffer = vec![0u8; buffer_size];
+
+        unsafe {
+            std::ptr::copy_nonoverlapping(
+                &pass_through as *const _ as *const u8,
+                buffer.as_mut_ptr(),
+                std::mem::size_of::<AtaPassThroughEx>(),
+            );
+
+            let result = DeviceIoControl(
+                self.handle,
+                IOCTL_ATA_PASS_THROUGH,
+                Some(buffer.as_ptr() as _),
+                buffer_size as u32,
+                Some(buffer.as_mut_ptr() as _),
+                buffer_size as u32,
+                Some(&mut bytes_returned),
+                None,
+            );
+
+            if result.is_err() {
+                return Err(WipeError {
+                    code: WipeErrorCode::HardwareError,
+                    message: "Failed to execute DEVICE CONFIGURATION IDENTIFY command".to_string(),
+                    sector: None,
+                });
+            }
+        }
+
+        // Words 3-6 of the DCO data hold the factory maximum LBA
+        let data = &buffer[std::mem::size_of::<AtaPassThroughEx>()..];
+        let word = |i: usize| u16::from_le_bytes([data[i * 2], data[i * 2 + 1]]) as u64;
+        let max_lba = (word(6) << 48) | (word(5) << 32) | (word(4) << 16) | word(3);
+
+        Ok(if max_lba > 0 { Some(max_lba) } else { None })
+    }
+
+    /// DEVICE CONFIGURATION RESTORE - permanently removes the DCO overlay
+    pub fn dco_restore(&self) -> WipeResult2<()> {
+        let pass_through = AtaPassThroughEx {
+            length: std::mem::size_of::<AtaPassThroughEx>() as u16,
+            ata_flags: 0x00, // No data transfer
+            path_id: 0,
+            target_id: 0,
+            lun: 0,
+            reserved_as_uchar: 0,
+            data_transfer_length: 0,
+            timeout_value: 30,
+            reserved_as_ulong: 0,
+            data_buffer_offset: std::mem::size_of::<AtaPassThroughEx>(),
+            previous_task_file: [0; 8],
+            current_task_file: [DCO_RESTORE, 0, 0, 0, 0, 0, ATA_DEVICE_CONFIGURATION, 0],
+        };
+
+        let mut bytes_returned = 0u32;
+        let buffer_size = std::mem::size_of::<AtaPassThroughEx>();
+        let mut buffer = vec![0u8; buffer_size];
+
+        unsafe {
+            std::ptr::copy_nonoverlapping(
+                &pass_through as *const _ as *const u8,
+                buffer.as_mut_ptr(),
+                buffer_size,
+            );
+
+            let result = DeviceIoControl(
+                self.handle,
+                IOCTL_ATA_PASS_THROUGH,
+                Some(buffer.as_ptr() as _),
+                buffer_size as u32,
+                Some(buffer.as_mut_ptr() as _),
+                buffer_size as u32,
+                Some(&mut bytes_returned),
+                None,
+            );
+
+            if result.is_ok() {
+                Ok(())
+            } else {
+                Err(WipeError {
+                    code: WipeErrorCode::DCOUnlockFailed,
+                    message: "Failed to execute DEVICE CONFIGURATION RESTORE command".to_string(),
+                    sector: None,
+                })
+            }
+        }
+    }
+
     pub fn parse_drive_geometry(&self, identify_data: &IdentifyDeviceData) -> DriveGeometry {
         let words = &identify_data.data;
         
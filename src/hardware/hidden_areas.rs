@@ -0,0 +1,118 @@
+use tracing::{info, warn};
+
+use crate::core::{HiddenAreaReport, WipeError, WipeErrorCode, WipeResult2};
+use crate::hardware::DriveInterface;
+
+/// User-addressable sector count from IDENTIFY DEVICE, and whether 48-bit commands apply
+fn user_sectors(drive: &DriveInterface) -> WipeResult2<(u64, bool)> {
+    let identify_data = drive.identify_device()?;
+    let words = &identify_data.data;
+    let use_ext = words[83] & 0x0400 != 0;
+
+    let sectors = if use_ext {
+        ((words[103] as u64) << 48) | ((words[102] as u64) << 32) |
+        ((words[101] as u64) << 16) | (words[100] as u64)
+    } else {
+        ((words[61] as u64) << 16) | (words[60] as u64)
+    };
+
+    Ok((sectors, use_ext))
+}
+
+/// Remove the DCO (when allowed) and HPA so the whole native capacity is addressable.
+/// HPA removal is volatile: the drive reverts to its original HPA at the next power cycle.
+pub fn expand_to_native_capacity(drive: &DriveInterface, remove_dco: bool) -> WipeResult2<HiddenAreaReport> {
+    let (original_user_sectors, use_ext) = user_sectors(drive)?;
+    let mut report = HiddenAreaReport {
+        original_user_sectors,
+        ..Default::default()
+    };
+
+    // DCO restore must happen first: it resets the native max address to factory capacity
+    match drive.dco_identify() {
+        Ok(Some(factory_max_lba)) => {
+            report.dco_factory_sectors = Some(factory_max_lba + 1);
+            let native_max_lba = drive.read_native_max_address(use_ext)?;
+
+            if factory_max_lba > native_max_lba {
+                if remove_dco {
+                    info!(from_sectors = native_max_lba + 1, to_sectors = factory_max_lba + 1, "Restoring DCO");
+                    drive.dco_restore()?;
+                    report.dco_restored = true;
+                    report.notes.push("Device Configuration Overlay permanently removed".to_string());
+                } else {
+                    report.notes.push(format!(
+                        "DCO hides {} sectors that were not wiped (DCO removal disabled)",
+                        factory_max_lba - native_max_lba
+                    ));
+                }
+            }
+        }
+        Ok(None) => {}
+        Err(e) => report.notes.push(format!("DCO state could not be read: {}", e.message)),
+    }
+
+    let native_max_lba = drive.read_native_max_address(use_ext)?;
+    report.native_sectors = native_max_lba + 1;
+
+    if report.native_sectors > original_user_sectors {
+        info!(from_sectors = original_user_sectors, to_sectors = report.native_sectors, "Removing HPA");
+        drive.set_max_address(native_max_lba, use_ext).map_err(|e| WipeError {
+            code: WipeErrorCode::HPAUnlockFailed,
+            message: format!("Failed to unlock HPA for hidden area wipe: {}", e.message),
+            sector: None,
+        })?;
+        report.hpa_removed = true;
+    }
+
+    report.expanded_user_sectors = user_sectors(drive)?.0;
+    report.final_user_sectors = report.expanded_user_sectors;
+
+    if report.expanded_user_sectors < report.native_sectors {
+        return Err(WipeError {
+            code: WipeErrorCode::HPAUnlockFailed,
+            message: format!(
+                "Drive still reports {} of {} native sectors after HPA removal",
+                report.expanded_user_sectors, report.native_sectors
+            ),
+            sector: None,
+        });
+    }
+
+    Ok(report)
+}
+
+/// Put the original HPA back after the wipe. Failure is not fatal: drives accept only one
+/// SET MAX ADDRESS per power cycle, and the volatile removal reverts on the next one anyway.
+pub fn restore_hpa(drive: &DriveInterface, report: &mut HiddenAreaReport) {
+    if !report.hpa_removed {
+        return;
+    }
+
+    let use_ext = match user_sectors(drive) {
+        Ok((_, use_ext)) => use_ext,
+        Err(e) => {
+            warn!(error = %e.message, "HPA restore skipped");
+            report.notes.push(format!("HPA restore skipped: {}", e.message));
+            return;
+        }
+    };
+
+    match drive.set_max_address(report.original_user_sectors - 1, use_ext) {
+        Ok(()) => {
+            report.hpa_restored = true;
+            info!(sectors = report.original_user_sectors, "HPA restored");
+        }
+        Err(e) => {
+            warn!(error = %e.message, "HPA could not be restored; it returns at the next power cycle");
+            report.notes.push(format!(
+                "HPA could not be restored immediately ({}); it will return at the next power cycle",
+                e.message
+            ));
+        }
+    }
+
+    if let Ok((sectors, _)) = user_sectors(drive) {
+        report.final_user_sectors = sectors;
+    }
+}
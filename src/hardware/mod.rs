@@ -1,5 +1,9 @@
 pub mod drive_interface;
 pub mod sanitizer;
+pub mod hidden_areas;
+pub mod buffer_tuning;
 
 pub use drive_interface::*;
-pub use sanitizer::*;
\ No newline at end of file
+pub use sanitizer::*;
+pub use hidden_areas::*;
+pub use buffer_tuning::*;
\ No newline at end of file
@@ -1,20 +1,37 @@
 use std::fs::{File, OpenOptions};
-use std::io::{self, Read, Seek, SeekFrom, Write, BufWriter};
+use std::io::{self, Seek, SeekFrom, Write};
 use std::sync::{Arc, Mutex, mpsc};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
 use std::thread;
 use std::time::{Instant, Duration};
 use rayon::prelude::*;
-use rand::{Rng, RngCore};
 use chrono::Utc;
+use tracing::{info, info_span, warn};
 use uuid::Uuid;
 
 use crate::core::{
     SanitizationStandard, TargetType, WipeRequest, WipeProgress, WipeResult, ValidationResult,
     DriveGeometry, SecurityFeatures, WipeError, WipeErrorCode, WipeResult2, WipeConfiguration,
+    BadSectorLog, VerificationReport, WipeScope, EtaEstimator, BufferTuning,
+    LimitsHandle, PauseCheckpoint, Throttle, OperationTimeline, TimelineEventKind, ThermalMonitor, MAX_THROTTLE_SLEEP, PassVerification, OverwritePattern, SimulatedDevice,
+    WrittenPasses, HiddenAreaReport,
 };
 use crate::core::config::{NIST_CLEAR_PATTERNS, NIST_PURGE_PATTERNS, DOD_522022M_PATTERNS, 
-                           VERIFICATION_BLOCK_SIZE, MAX_RETRY_ATTEMPTS, PROGRESS_UPDATE_INTERVAL};
-use crate::hardware::DriveInterface;
+                           MAX_RETRY_ATTEMPTS, PROGRESS_UPDATE_INTERVAL};
+use crate::hardware::{DriveInterface, expand_to_native_capacity, restore_hpa, probe_block_sizes};
+use crate::erase_progress::{EraseProgressSource, HardwareEraseMonitor, DEFAULT_UPDATE_INTERVAL};
+use crate::bad_sectors::write_block_tolerant;
+use crate::verification::{evict_cached, planned_bytes, verify_device, ExpectedContent};
+use crate::partitions::{read_partition_table, PartitionTable};
+use crate::devices::flash::{self, OVERPROVISION_PASSES};
+use crate::pattern_stream::{PatternSeed, PatternStream};
+use crate::reporting::{capture_snapshot, verification_sampling};
+use crate::security::{DriveSnapshot, ErasureEvidence, ForensicImage, FunctionalTest, FunctionalTestKind};
+use crate::functional_test::{run_short_self_test, surface_scan};
+use crate::imaging::{image_device, ImagingConfig};
+use crate::relabel::relabel;
+use crate::security_state::apply_security_state;
+use crate::zoned::{self, ZonedModel};
 
 #[derive(Debug, Clone)]
 pub struct SanitizationProgress {
@@ -28,16 +45,73 @@ pub struct SanitizationProgress {
     pub errors: Vec<String>,
 }
 
+/// What Step 3 uncovered on an ATA drive. Dropping it before Step 8 puts the
+/// original HPA back, so a wipe that fails part-way does not leave the drive
+/// at its native capacity.
+struct HiddenAreaGuard<'a> {
+    drive: &'a DriveInterface,
+    report: Option<HiddenAreaReport>,
+    restore: bool,
+}
+
+impl HiddenAreaGuard<'_> {
+    /// Restore the HPA if configured and hand back the report for the result
+    fn finish(mut self) -> Option<HiddenAreaReport> {
+        let mut report = self.report.take()?;
+        if self.restore {
+            restore_hpa(self.drive, &mut report);
+        }
+        Some(report)
+    }
+}
+
+impl Drop for HiddenAreaGuard<'_> {
+    fn drop(&mut self) {
+        if let Some(ref mut report) = self.report
+            && self.restore
+            && report.hpa_removed
+        {
+            warn!("Wipe ended before completing; restoring the HPA");
+            restore_hpa(self.drive, report);
+        }
+    }
+}
+
+/// The sectors the passes of a wipe write and verify, with the state they
+/// share: sectors that turned out unwritable and the completion estimate
+struct PassContext<'a> {
+    device_path: &'a str,
+    start_sector: u64,
+    sector_count: u64,
+    request_id: Uuid,
+    bad_sectors: BadSectorLog,
+    eta: EtaEstimator,
+}
+
 pub struct SecureSanitizer {
     config: WipeConfiguration,
     progress_sender: Option<mpsc::Sender<WipeProgress>>,
+    cancel_flag: Option<Arc<AtomicBool>>,
+    /// Write size of the passes; `buffer_size` unless the probe found a faster one
+    block_size: AtomicUsize,
+    limits: Arc<LimitsHandle>,
+    /// Image written instead of the target in a simulated wipe
+    simulated_device: Option<SimulatedDevice>,
+    timeline: Arc<OperationTimeline>,
 }
 
 impl SecureSanitizer {
     pub fn new(config: WipeConfiguration) -> Self {
+        let block_size = AtomicUsize::new(config.buffer_size);
+        let limits = Arc::new(LimitsHandle::new(config.operation_limits()));
         Self {
             config,
             progress_sender: None,
+            cancel_flag: None,
+            block_size,
+            limits,
+            simulated_device: None,
+            timeline: Arc::new(OperationTimeline::new()),
         }
     }
 
@@ -46,14 +120,55 @@ impl SecureSanitizer {
         self
     }
 
+    /// Stop between write blocks with `WipeErrorCode::Cancelled` once `flag` is set
+    pub fn with_cancel_flag(mut self, flag: Arc<AtomicBool>) -> Self {
+        self.cancel_flag = Some(flag);
+        self
+    }
+
+    /// Take the rate limit and I/O priority from `limits`, which may change mid-wipe
+    pub fn with_limits(mut self, limits: Arc<LimitsHandle>) -> Self {
+        self.limits = limits;
+        self
+    }
+
+    /// Record what the wipe does in `timeline`
+    pub fn with_timeline(mut self, timeline: Arc<OperationTimeline>) -> Self {
+        self.timeline = timeline;
+        self
+    }
+
+    /// Write every pass to `device` instead of the request's target
+    pub fn with_simulated_device(mut self, device: SimulatedDevice) -> Self {
+        self.simulated_device = Some(device);
+        self
+    }
+
     pub fn execute_wipe(&self, request: WipeRequest) -> WipeResult2<WipeResult> {
         let start_time = Utc::now();
         
-        // Step 1: Open and analyze the target
-        let drive = DriveInterface::new(&request.target_path)?;
-        let identify_data = drive.identify_device()?;
-        let drive_geometry = drive.parse_drive_geometry(&identify_data);
-        let security_features = drive.parse_security_features(&identify_data);
+        // Step 1: Open and analyze the target. A simulated wipe has no drive
+        // to send commands to and writes the image instead.
+        let (drive, drive_geometry, security_features) = match self.simulated_device {
+            Some(ref device) => (None, device.geometry(&request.target_path), device.security_features()),
+            None => {
+                let drive = DriveInterface::new(&request.target_path)?;
+                let identify_data = drive.identify_device()?;
+                let geometry = drive.parse_drive_geometry(&identify_data);
+                let security_features = drive.parse_security_features(&identify_data);
+                (Some(drive), geometry, security_features)
+            }
+        };
+        let device_path = match self.simulated_device {
+            Some(ref device) => device.path().to_string_lossy().to_string(),
+            None => request.target_path.clone(),
+        };
+        self.timeline.record(TimelineEventKind::DeviceOpened {
+            device_path: device_path.clone(),
+            model: drive_geometry.model.clone(),
+            serial: drive_geometry.serial.clone(),
+            total_sectors: drive_geometry.total_sectors,
+        });
 
         // Step 2: Security checks
         if security_features.security_locked {
@@ -64,40 +179,128 @@ impl SecureSanitizer {
             });
         }
 
-        // Step 3: Unlock hidden areas if requested (for HDD targets)
+        // Step 2b: Record what the device holds before anything is written
+        let before = self.snapshot(&device_path, drive.is_some(), "pre-wipe");
+
+        // Step 3: Expand ATA drives to native capacity so HPA/DCO areas are wiped too.
+        // Partial wipes leave the hidden areas alone, like the rest of the device.
         let mut actual_geometry = drive_geometry.clone();
-        if matches!(request.target_type, TargetType::HDD) {
-            if drive_geometry.has_hpa {
-                match drive.unlock_hpa() {
-                    Ok(true) => {
-                        println!("HPA unlocked successfully");
-                        // Re-read geometry after HPA unlock
-                        let new_identify = drive.identify_device()?;
-                        actual_geometry = drive.parse_drive_geometry(&new_identify);
-                    }
-                    Ok(false) => println!("No HPA detected or already unlocked"),
-                    Err(e) => {
-                        println!("Warning: Failed to unlock HPA: {}", e);
-                        return Err(WipeError {
-                            code: WipeErrorCode::HPAUnlockFailed,
-                            message: "Failed to unlock HPA for hidden area wipe".to_string(),
-                            sector: None,
-                        });
-                    }
-                }
+        let mut hidden_areas = None;
+        if let Some(ref drive) = drive
+            && request.scope == WipeScope::WholeDevice
+            && matches!(request.target_type, TargetType::HDD | TargetType::SSD)
+        {
+            let report = expand_to_native_capacity(drive, self.config.remove_dco)?;
+            let expanded = report.hpa_removed || report.dco_restored;
+            // Puts the HPA back on every return from here on, not only after Step 8
+            let guard = hidden_areas.insert(HiddenAreaGuard { drive, report: Some(report), restore: self.config.restore_hpa });
+            if expanded {
+                // Re-read geometry after HPA/DCO removal
+                let new_identify = guard.drive.identify_device()?;
+                actual_geometry = guard.drive.parse_drive_geometry(&new_identify);
             }
         }
 
         // Step 4: Determine target sectors
-        let (start_sector, sector_count) = self.calculate_target_range(&request, &actual_geometry)?;
-        
-        // Step 5: Execute sanitization passes
-        let patterns = self.get_patterns_for_standard(&request.standard, request.passes)?;
+        let (start_sector, sector_count) = self.calculate_target_range(&request, &device_path, &actual_geometry)?;
+
+        // Step 4b: Host-managed zoned drives only take writes in zone order
+        let zoned_overwrite = drive.is_some() && zoned::detect_zoned_model(&device_path) == Some(ZonedModel::HostManaged);
+        if zoned_overwrite {
+            self.check_zoned_overwrite(&request, &device_path)?;
+        }
+
+        // Step 4c: Keep a copy of the target before anything is written to it,
+        // including the block-size probe. A resumed wipe was imaged, if at all,
+        // before the interruption.
+        let forensic_image = match self.config.imaging {
+            Some(_) if request.resume.is_some_and(|resume| resume.passes_completed > 0) => {
+                warn!(device = %device_path, "Not imaging: the first passes ran before the interruption");
+                None
+            }
+            Some(ref config) => Some(self.image_target(config, &device_path, start_sector, sector_count, &request.id)?),
+            None => None,
+        };
+
+        // Step 4d: Pick the write size this device handles fastest; the probe
+        // overwrites the start of the target range
+        let buffer_tuning = if self.config.adaptive_buffer {
+            self.tune_block_size(&device_path, start_sector, sector_count)
+        } else {
+            None
+        };
+
+        // Step 5: Flash media gets its native erase first, and enough full-capacity
+        // passes to cycle the wear-leveling spare pool
+        let mut patterns = request.wipe_spec().pass_patterns();
+        let mut patterns_used = Vec::new();
+        // A resumed wipe skips the passes that reached the device before the
+        // interruption; the native erase went before them
+        let resumed_passes = request.resume.map_or(0, |resume| resume.passes_completed);
+        if drive.is_some()
+            && resumed_passes == 0
+            && request.target_type == TargetType::Flash
+            && request.scope == WipeScope::WholeDevice
+        {
+            let capabilities = flash::detect_capabilities(&request.target_path);
+            let native = flash::native_erase(
+                &request.target_path,
+                actual_geometry.total_sectors * actual_geometry.sector_size,
+                actual_geometry.sector_size as u32,
+                &capabilities,
+            );
+            patterns_used.extend(native.iter().map(|technique| technique.description()));
+
+            if capabilities.wear_leveling {
+                // Extra passes go first so the last pass, which verification checks, is unchanged
+                while (patterns.len() as u32) < OVERPROVISION_PASSES {
+                    patterns.insert(0, OverwritePattern::Random);
+                }
+            }
+        }
+
+        // Step 6: Execute sanitization passes
         let total_passes = patterns.len() as u32;
         let mut sectors_wiped = 0u64;
+        let verify_bytes = if request.verify_erasure {
+            planned_bytes(sector_count * 512, &self.config.verification_policy)
+        } else {
+            0
+        };
+        let mut context = PassContext {
+            device_path: &device_path,
+            start_sector,
+            sector_count,
+            request_id: request.id,
+            bad_sectors: BadSectorLog::new(),
+            eta: EtaEstimator::new(sector_count * 512, total_passes, verify_bytes),
+        };
+        // Held until the wipe returns so verification reads run at the same priority
+        let mut throttle = Throttle::new(&self.limits);
+        if drive.is_some() && self.config.thermal.enabled {
+            throttle = throttle.with_thermal(ThermalMonitor::for_device(self.config.thermal.clone(), &device_path));
+        }
+        // Random passes each write their own stream of this seed
+        let seed = PatternSeed::generate();
+        let mut final_content = None;
+        let mut pass_verifications = Vec::new();
 
+        // The last pass always runs, since verification reads back what it wrote
+        let skipped_passes = resumed_passes.min(total_passes.saturating_sub(1));
         for (pass_num, pattern) in patterns.iter().enumerate() {
+            if (pass_num as u32) < skipped_passes {
+                patterns_used.push(format!("Pass {}: {} (completed before the interruption)", pass_num + 1, pattern.description()));
+                continue;
+            }
+            let _pass = info_span!("pass", pass = pass_num + 1, total_passes, pattern = %pattern.description()).entered();
+            let content = self.pass_content(pattern, &seed, pass_num as u64 + 1);
             let pass_start_time = Instant::now();
+            let sectors_before = sectors_wiped;
+            self.timeline.record(TimelineEventKind::PassStarted {
+                pass: pass_num as u32 + 1,
+                total_passes,
+                pattern: pattern.description(),
+            });
             
             self.send_progress(WipeProgress {
                 request_id: request.id,
@@ -108,25 +311,36 @@ impl SecureSanitizer {
                 percentage: 0.0,
                 current_operation: format!("Pass {} - Overwriting with pattern", pass_num + 1),
                 estimated_completion: None,
+                estimated_completion_earliest: None,
+                estimated_completion_latest: None,
+                verifying: false,
             });
 
-            sectors_wiped += self.execute_pattern_pass(
-                &request.target_path,
-                start_sector,
-                sector_count,
-                pattern,
-                pass_num + 1,
-                total_passes,
-                &request.id,
-            )?;
+            if zoned_overwrite {
+                let reset = self.execute_zoned_pass(&mut context, &content, pass_num + 1, total_passes, &mut throttle)?;
+                patterns_used.push(format!("Pass {}: Zone reset ({} zones)", pass_num + 1, reset));
+                sectors_wiped += sector_count;
+            } else {
+                sectors_wiped += self.execute_pattern_pass(&mut context, &content, pass_num + 1, total_passes, &mut throttle)?;
+            }
 
-            println!("Pass {} completed in {:.2} seconds", 
-                     pass_num + 1, 
-                     pass_start_time.elapsed().as_secs_f64());
+            info!(seconds = pass_start_time.elapsed().as_secs_f64(), "Pass completed");
+            self.timeline.record(TimelineEventKind::PassFinished {
+                pass: pass_num as u32 + 1,
+                seconds: pass_start_time.elapsed().as_secs_f64(),
+                sectors_written: sectors_wiped - sectors_before,
+            });
+            patterns_used.push(format!("Pass {}: {}", pass_num + 1, pattern.description()));
+
+            if self.config.verify_each_pass {
+                pass_verifications.push(self.verify_pass(&context, &content, pass_num as u32 + 1, pattern.description())?);
+            }
+            final_content = Some(content);
         }
 
-        // Step 6: Verification if requested
-        let validation_result = if request.verify_erasure {
+        // Step 7: Verification if requested
+        let (validation_result, verification_report) = if request.verify_erasure {
+            let _verification = info_span!("verification", policy = %self.config.verification_policy.description()).entered();
             self.send_progress(WipeProgress {
                 request_id: request.id,
                 current_pass: total_passes + 1,
@@ -136,19 +350,94 @@ impl SecureSanitizer {
                 percentage: 0.0,
                 current_operation: "Verifying erasure".to_string(),
                 estimated_completion: None,
+                estimated_completion_earliest: None,
+                estimated_completion_latest: None,
+                verifying: true,
             });
 
-            Some(self.verify_erasure(
-                &request.target_path,
-                start_sector,
-                sector_count,
-                patterns.last().unwrap(),
-                &request.id,
-            )?)
+            self.timeline.record(TimelineEventKind::VerificationStarted {
+                policy: self.config.verification_policy.description(),
+            });
+            let (validation, report) = self.verify_erasure(&mut context, final_content.as_ref().unwrap())?;
+            self.timeline.record(TimelineEventKind::VerificationFinished {
+                passed: report.passed,
+                bytes_verified: report.bytes_verified,
+            });
+            if !report.passed {
+                let failed = report.failed_regions().count();
+                let mismatched: u64 = report.failed_regions().map(|region| region.mismatched_bytes).sum();
+                return Err(WipeError {
+                    code: WipeErrorCode::VerificationFailed,
+                    message: format!(
+                        "Verification ({}) failed in {} of {} regions with {} mismatched bytes",
+                        report.policy.description(),
+                        failed,
+                        report.regions.len(),
+                        mismatched
+                    ),
+                    sector: report.failed_sectors.first().copied(),
+                });
+            }
+            (Some(validation), Some(report))
         } else {
-            None
+            (None, None)
+        };
+
+        // Step 7a: An overwrite of flash media retires the old copies of each block
+        // into the spare pool; deallocating the range lets the controller erase them.
+        // Verification reads first, since deallocated blocks no longer read back the pattern.
+        let mut deallocated = false;
+        if drive.is_some()
+            && !request.skip_trim
+            && !zoned_overwrite
+            && total_passes > 0
+            && matches!(request.target_type, TargetType::SSD | TargetType::Flash)
+        {
+            match flash::deallocate_range(&device_path, start_sector * 512, sector_count * 512) {
+                Ok(()) => {
+                    patterns_used.push(flash::FlashTechnique::Deallocate.description());
+                    deallocated = true;
+                }
+                Err(e) => warn!(error = %e, "TRIM / Deallocate after overwrite failed"),
+            }
+        }
+
+        // Step 7b: Show the drive still works, for drives that are resold
+        let functional_test = match self.config.functional_test {
+            Some(kind) => self.run_functional_test(kind, drive.is_some(), &device_path, start_sector, sector_count, &request.id)?,
+            None => None,
+        };
+
+        // Step 7c: Record the device after the wipe, next to the read-back statistics
+        let evidence = match (before, self.snapshot(&device_path, drive.is_some(), "post-wipe")) {
+            (Some(before), Some(after)) => Some(ErasureEvidence {
+                before,
+                after,
+                verification: verification_report.as_ref().map(verification_sampling),
+            }),
+            _ => None,
+        };
+
+        // Step 8: Put the original HPA back if requested
+        let hidden_areas = hidden_areas.and_then(HiddenAreaGuard::finish);
+
+        // Step 9: Leave the drive partitioned, and formatted if asked, for reuse
+        let mut post_processing = match self.config.relabel {
+            Some(ref options) if request.scope == WipeScope::WholeDevice => {
+                let sector_size = if actual_geometry.sector_size == 0 { 512 } else { actual_geometry.sector_size };
+                relabel(&device_path, options, sector_size, drive.is_some())
+            }
+            _ => Vec::new(),
         };
 
+        // Step 10: Leave the drive in the requested ATA security state, last since it may freeze it
+        if let Some(action) = request.security_state
+            && drive.is_some()
+            && request.scope == WipeScope::WholeDevice
+        {
+            post_processing.push(apply_security_state(&device_path, action));
+        }
+
         let completion_time = Utc::now();
         let duration = completion_time.signed_duration_since(start_time);
 
@@ -164,70 +453,294 @@ impl SecureSanitizer {
             error_message: None,
             drive_geometry: actual_geometry,
             security_features,
+            hidden_areas,
+            bad_sectors: context.bad_sectors,
+            verification_report,
+            patterns_used,
+            buffer_tuning,
+            pattern_seed: patterns.contains(&OverwritePattern::Random).then_some(seed),
+            pass_verifications,
+            simulated: self.simulated_device.is_some(),
+            evidence,
+            temperature_samples: throttle.temperature_samples(),
+            throughput: None,
+            functional_test,
+            forensic_image,
+            written_passes: Some(WrittenPasses {
+                start_sector,
+                sector_count,
+                patterns: patterns.clone(),
+                block_size: self.block_size.load(Ordering::Relaxed),
+                deallocated,
+            }),
+            post_processing,
         })
     }
 
-    fn calculate_target_range(&self, request: &WipeRequest, geometry: &DriveGeometry) -> WipeResult2<(u64, u64)> {
-        match &request.target_type {
-            TargetType::HDD | TargetType::SSD | TargetType::Flash | TargetType::Optical | TargetType::Tape => {
-                // For all device types, sanitize the full capacity
-                Ok((0, geometry.total_sectors))
+    /// Image the target range to the configured destination. Any failure
+    /// stops the wipe, so nothing meant to be kept is erased.
+    fn image_target(
+        &self,
+        config: &ImagingConfig,
+        device_path: &str,
+        start_sector: u64,
+        sector_count: u64,
+        request_id: &Uuid,
+    ) -> WipeResult2<ForensicImage> {
+        let progress = |percentage: f64, sectors_processed: u64| WipeProgress {
+            request_id: *request_id,
+            current_pass: 0,
+            total_passes: 1,
+            sectors_processed,
+            total_sectors: sector_count,
+            percentage,
+            current_operation: "Imaging device".to_string(),
+            estimated_completion: None,
+            estimated_completion_earliest: None,
+            estimated_completion_latest: None,
+            verifying: false,
+        };
+
+        self.send_progress(progress(0.0, 0));
+        let mut last_progress_update = Instant::now();
+        let imaged = image_device(
+            device_path,
+            start_sector,
+            sector_count,
+            self.block_size.load(Ordering::Relaxed),
+            config,
+            *request_id,
+            |bytes_imaged| {
+                if last_progress_update.elapsed() > Duration::from_millis(PROGRESS_UPDATE_INTERVAL) {
+                    let percentage = bytes_imaged as f64 / (sector_count * 512).max(1) as f64 * 100.0;
+                    self.send_progress(progress(percentage, bytes_imaged / 512));
+                    last_progress_update = Instant::now();
+                }
+                !self.cancel_flag.as_ref().is_some_and(|flag| flag.load(Ordering::Relaxed))
+            },
+        );
+        match imaged {
+            Ok(manifest) => {
+                info!(
+                    device = %device_path,
+                    location = %manifest.image.location,
+                    sha256 = %manifest.image.source_sha256,
+                    "Device imaged"
+                );
+                if manifest.image.unreadable_sectors > 0 {
+                    warn!(sectors = manifest.image.unreadable_sectors, "Unreadable sectors are zero in the image");
+                }
+                self.timeline.record(TimelineEventKind::ImageWritten {
+                    location: manifest.image.location.clone(),
+                    bytes_imaged: manifest.image.bytes_imaged,
+                });
+                Ok(manifest.image)
             }
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => Err(WipeError {
+                code: WipeErrorCode::Cancelled,
+                message: "Wipe cancelled while imaging the device".to_string(),
+                sector: None,
+            }),
+            Err(e) => Err(WipeError {
+                code: WipeErrorCode::HardwareError,
+                message: format!("Pre-wipe image failed, nothing was erased: {}", e),
+                sector: None,
+            }),
         }
     }
 
-    fn get_patterns_for_standard(&self, standard: &SanitizationStandard, passes: u32) -> WipeResult2<Vec<Vec<u8>>> {
-        match standard {
-            SanitizationStandard::NIST_SP_800_88_R1 => {
-                Ok(vec![vec![0x00; 512]]) // Single zero pass for NIST Clear
+    /// Run the configured functional test; a drive that cannot run its
+    /// self-test gets its surface scanned instead
+    fn run_functional_test(
+        &self,
+        kind: FunctionalTestKind,
+        self_test: bool,
+        device_path: &str,
+        start_sector: u64,
+        sector_count: u64,
+        request_id: &Uuid,
+    ) -> WipeResult2<Option<FunctionalTest>> {
+        let progress = |percentage: f64, sectors_processed: u64, operation: &str| WipeProgress {
+            request_id: *request_id,
+            current_pass: 0,
+            total_passes: 1,
+            sectors_processed,
+            total_sectors: sector_count,
+            percentage,
+            current_operation: operation.to_string(),
+            estimated_completion: None,
+            estimated_completion_earliest: None,
+            estimated_completion_latest: None,
+            verifying: false,
+        };
+
+        if kind == FunctionalTestKind::ShortSelfTest && self_test {
+            self.send_progress(progress(0.0, 0, "Running short self-test"));
+            match run_short_self_test(device_path, |percent| {
+                self.send_progress(progress(percent as f64, 0, "Running short self-test"));
+            }) {
+                Ok(test) => {
+                    info!(detail = %test.detail, "Short self-test finished");
+                    return Ok(Some(test));
+                }
+                Err(e) => warn!(error = %e, "Short self-test unavailable, scanning the surface instead"),
             }
-            SanitizationStandard::DoD_5220_22_M => {
-                let mut patterns = Vec::new();
-                patterns.push(vec![0x00; 512]); // Pass 1: Zeros
-                patterns.push(vec![0xFF; 512]); // Pass 2: Ones
-                patterns.push(self.generate_random_pattern()); // Pass 3: Random
-                Ok(patterns)
+        }
+
+        let mut device = File::open(device_path).map_err(|e| WipeError {
+            code: WipeErrorCode::AccessDenied,
+            message: format!("Failed to open device for surface scan: {}", e),
+            sector: None,
+        })?;
+        let mut last_progress_update = Instant::now();
+        let scan = surface_scan(
+            &mut device,
+            start_sector * 512,
+            sector_count * 512,
+            self.block_size.load(Ordering::Relaxed),
+            |bytes_read| {
+                if last_progress_update.elapsed() > Duration::from_millis(PROGRESS_UPDATE_INTERVAL) {
+                    let percentage = bytes_read as f64 / (sector_count * 512).max(1) as f64 * 100.0;
+                    self.send_progress(progress(percentage, bytes_read / 512, "Scanning surface"));
+                    last_progress_update = Instant::now();
+                }
+                !self.cancel_flag.as_ref().is_some_and(|flag| flag.load(Ordering::Relaxed))
+            },
+        );
+        match scan {
+            Ok(test) => {
+                info!(detail = %test.detail, "Surface scan finished");
+                Ok(Some(test))
             }
-            SanitizationStandard::AFSSI_5020 => {
-                let mut patterns = Vec::new();
-                patterns.push(vec![0x00; 512]); // Pass 1: Zeros
-                patterns.push(vec![0xFF; 512]); // Pass 2: Ones
-                patterns.push(self.generate_random_pattern()); // Pass 3: Random
-                Ok(patterns)
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => Err(WipeError {
+                code: WipeErrorCode::Cancelled,
+                message: "Wipe cancelled during the surface scan".to_string(),
+                sector: None,
+            }),
+            Err(e) => {
+                warn!(error = %e, "Surface scan failed");
+                Ok(None)
             }
-            SanitizationStandard::BSI_2011_VS => {
-                let mut patterns = Vec::new();
-                patterns.push(vec![0x00; 512]); // Pass 1: Zeros
-                patterns.push(vec![0xFF; 512]); // Pass 2: Ones
-                Ok(patterns)
+        }
+    }
+
+    /// A snapshot for the wipe evidence; a device that cannot be sampled
+    /// leaves the evidence out rather than failing the wipe
+    fn snapshot(&self, device_path: &str, read_smart: bool, stage: &str) -> Option<DriveSnapshot> {
+        capture_snapshot(device_path, read_smart)
+            .map_err(|e| warn!(stage, device = %device_path, error = %e, "No device snapshot"))
+            .ok()
+    }
+
+    /// Probe the candidate block sizes at the start of the target range. The
+    /// configured `buffer_size` stays in use if the probe fails.
+    fn tune_block_size(&self, device_path: &str, start_sector: u64, sector_count: u64) -> Option<BufferTuning> {
+        let probed = OpenOptions::new()
+            .write(true)
+            .open(device_path)
+            .and_then(|mut device| probe_block_sizes(&mut device, start_sector * 512, sector_count * 512, |d| d.sync_data()));
+
+        match probed {
+            Ok(tuning) => {
+                info!(block_size = tuning.block_size, throughput_mbps = tuning.throughput_mbps, "Write block size chosen");
+                self.block_size.store(tuning.block_size, Ordering::Relaxed);
+                Some(tuning)
             }
-            SanitizationStandard::NAVSO_P_5239_26 => {
-                let mut patterns = Vec::new();
-                patterns.push(vec![0x01; 512]); // Pass 1: 0x01
-                patterns.push(vec![0x27; 512]); // Pass 2: 0x27
-                patterns.push(vec![0x96; 512]); // Pass 3: 0x96
-                Ok(patterns)
+            Err(e) => {
+                warn!(block_size = self.config.buffer_size, error = %e, "Block size probe failed, using the configured size");
+                None
             }
         }
     }
 
-    fn generate_random_pattern(&self) -> Vec<u8> {
-        let mut rng = rand::thread_rng();
-        let mut pattern = vec![0u8; self.config.buffer_size];
-        rng.fill_bytes(&mut pattern);
-        pattern
+    fn calculate_target_range(&self, request: &WipeRequest, device_path: &str, geometry: &DriveGeometry) -> WipeResult2<(u64, u64)> {
+        if request.scope == WipeScope::WholeDevice {
+            return Ok((0, geometry.total_sectors));
+        }
+
+        let invalid_target = |message: String| WipeError {
+            code: WipeErrorCode::InvalidTarget,
+            message,
+            sector: None,
+        };
+
+        let table = read_partition_table(device_path).map_err(|e| WipeError {
+            code: WipeErrorCode::HardwareError,
+            message: format!("Failed to read partition table: {}", e),
+            sector: None,
+        })?;
+
+        let (start_sector, sector_count) = match &request.scope {
+            WipeScope::WholeDevice => (0, geometry.total_sectors),
+            WipeScope::Partition(selector) => {
+                let table = table.as_ref()
+                    .ok_or_else(|| invalid_target("Device has no partition table".to_string()))?;
+                let partition = table.find(selector)
+                    .ok_or_else(|| invalid_target(format!("{} not found", request.scope.description())))?;
+                let (offset, length) = partition.byte_range(table.sector_size);
+                (offset / 512, length / 512)
+            }
+            WipeScope::LbaRange { start_sector, sector_count } => (*start_sector, *sector_count),
+        };
+
+        if sector_count == 0 || start_sector + sector_count > geometry.total_sectors {
+            return Err(invalid_target(format!(
+                "Sectors {}+{} are outside the device ({} sectors)",
+                start_sector, sector_count, geometry.total_sectors
+            )));
+        }
+
+        // Never touch the partitions the platform boots from
+        if let Some(system) = table.as_ref().and_then(|t| system_partition_overlap(t, start_sector, sector_count)) {
+            return Err(WipeError {
+                code: WipeErrorCode::AccessDenied,
+                message: format!("{} overlaps system partition {}", request.scope.description(), system),
+                sector: Some(start_sector),
+            });
+        }
+
+        info!(
+            scope = %request.scope.description(),
+            first_sector = start_sector,
+            last_sector = start_sector + sector_count - 1,
+            "Wipe range"
+        );
+        Ok((start_sector, sector_count))
+    }
+
+    /// What pass `pass` (1-based) writes and verification expects to read back
+    fn pass_content(&self, pattern: &OverwritePattern, seed: &PatternSeed, pass: u64) -> ExpectedContent {
+        match pattern {
+            OverwritePattern::Fill(bytes) => ExpectedContent::Pattern(self.pattern_buffer(bytes)),
+            OverwritePattern::Random => ExpectedContent::Stream(PatternStream::new(seed, pass)),
+        }
+    }
+
+    /// One write buffer filled with `pattern`; every block of a pass writes this buffer
+    fn pattern_buffer(&self, pattern: &[u8]) -> Vec<u8> {
+        let buffer_size = self.block_size.load(Ordering::Relaxed);
+        if pattern.len() == 1 {
+            vec![pattern[0]; buffer_size]
+        } else if pattern.len() >= buffer_size {
+            pattern[0..buffer_size].to_vec()
+        } else {
+            let repeat_count = buffer_size.div_ceil(pattern.len());
+            pattern.repeat(repeat_count)[0..buffer_size].to_vec()
+        }
     }
 
     fn execute_pattern_pass(
         &self,
-        device_path: &str,
-        start_sector: u64,
-        sector_count: u64,
-        pattern: &[u8],
+        context: &mut PassContext,
+        content: &ExpectedContent,
         pass_num: usize,
         total_passes: u32,
-        request_id: &Uuid,
+        throttle: &mut Throttle,
     ) -> WipeResult2<u64> {
+        let (device_path, start_sector, sector_count) = (context.device_path, context.start_sector, context.sector_count);
+        let request_id = &context.request_id;
+        let bad_sectors = &mut context.bad_sectors;
+        let eta = &mut context.eta;
         let mut device = OpenOptions::new()
             .read(true)
             .write(true)
@@ -240,16 +753,12 @@ impl SecureSanitizer {
 
         let total_bytes = sector_count * 512;
         let mut bytes_written = 0u64;
-        let buffer_size = self.config.buffer_size;
+        let buffer_size = self.block_size.load(Ordering::Relaxed);
         
-        // Create pattern buffer
-        let pattern_buffer = if pattern.len() == 1 {
-            vec![pattern[0]; buffer_size]
-        } else if pattern.len() >= buffer_size {
-            pattern[0..buffer_size].to_vec()
-        } else {
-            let repeat_count = (buffer_size + pattern.len() - 1) / pattern.len();
-            pattern.repeat(repeat_count)[0..buffer_size].to_vec()
+        // Fixed patterns write the same block throughout; streams refill it per block
+        let mut block = match content {
+            ExpectedContent::Pattern(buffer) => buffer.clone(),
+            _ => vec![0u8; buffer_size],
         };
 
         device.seek(SeekFrom::Start(start_sector * 512))
@@ -259,17 +768,64 @@ impl SecureSanitizer {
                 sector: Some(start_sector),
             })?;
 
-        let mut writer = BufWriter::with_capacity(buffer_size, &mut device);
         let start_time = Instant::now();
         let mut last_progress_update = Instant::now();
 
         while bytes_written < total_bytes {
+            if self.cancel_flag.as_ref().is_some_and(|flag| flag.load(Ordering::Relaxed)) {
+                let _ = device.flush();
+                return Err(WipeError {
+                    code: WipeErrorCode::Cancelled,
+                    message: format!("Wipe cancelled during pass {} at sector {}", pass_num, start_sector + bytes_written / 512),
+                    sector: Some(start_sector + bytes_written / 512),
+                });
+            }
+
             let remaining_bytes = total_bytes - bytes_written;
             let write_size = buffer_size.min(remaining_bytes as usize);
+            self.throttle_write(throttle, write_size as u64);
+            self.hold_while_paused(Some(&mut device), || PauseCheckpoint {
+                pass: pass_num as u32,
+                total_passes,
+                bytes_written,
+                next_sector: Some(start_sector + bytes_written / 512),
+                paused_at: Utc::now(),
+            }, || WipeProgress {
+                request_id: *request_id,
+                current_pass: pass_num as u32,
+                total_passes,
+                sectors_processed: bytes_written / 512,
+                total_sectors: sector_count,
+                percentage: (bytes_written as f64 / total_bytes as f64) * 100.0,
+                current_operation: format!("Pass {} - Paused at sector {}", pass_num, start_sector + bytes_written / 512),
+                estimated_completion: None,
+                estimated_completion_earliest: None,
+                estimated_completion_latest: None,
+                verifying: false,
+            })?;
             
-            let write_buffer = &pattern_buffer[0..write_size];
+            if let ExpectedContent::Stream(stream) = content {
+                stream.fill_at(bytes_written, &mut block[0..write_size]);
+            }
+            let write_buffer = &block[0..write_size];
             
-            match writer.write_all(write_buffer) {
+            let write_result = match device.write_all(write_buffer) {
+                Err(e) if self.config.tolerate_bad_sectors => {
+                    warn!(sector = start_sector + bytes_written / 512, error = %e, "Write failed, retrying in smaller blocks");
+                    self.timeline.record(TimelineEventKind::WriteRetried {
+                        sector: start_sector + bytes_written / 512,
+                        error: e.to_string(),
+                    });
+                    self.rewrite_failed_block(&mut device, start_sector * 512 + bytes_written, write_buffer, bad_sectors)
+                }
+                result => result.map_err(|e| WipeError {
+                    code: WipeErrorCode::HardwareError,
+                    message: format!("Write failed at byte {}: {}", bytes_written, e),
+                    sector: Some(start_sector + bytes_written / 512),
+                }),
+            };
+
+            match write_result {
                 Ok(_) => {
                     bytes_written += write_size as u64;
                     
@@ -278,7 +834,7 @@ impl SecureSanitizer {
                         let percentage = (bytes_written as f64 / total_bytes as f64) * 100.0;
                         let sectors_processed = bytes_written / 512;
                         
-                        self.send_progress(WipeProgress {
+                        let mut progress = WipeProgress {
                             request_id: *request_id,
                             current_pass: pass_num as u32,
                             total_passes,
@@ -287,117 +843,414 @@ impl SecureSanitizer {
                             percentage,
                             current_operation: format!("Pass {} - Writing pattern", pass_num),
                             estimated_completion: None,
-                        });
+                            estimated_completion_earliest: None,
+                            estimated_completion_latest: None,
+                            verifying: false,
+                        };
+                        if let Some(estimate) = eta.observe_pass(pass_num as u32, bytes_written) {
+                            estimate.apply(&mut progress);
+                        }
+                        self.send_progress(progress);
                         
                         last_progress_update = Instant::now();
                     }
                 }
-                Err(e) => {
-                    return Err(WipeError {
-                        code: WipeErrorCode::HardwareError,
-                        message: format!("Write failed at byte {}: {}", bytes_written, e),
-                        sector: Some(start_sector + bytes_written / 512),
-                    });
-                }
+                Err(e) => return Err(e),
             }
         }
 
-        writer.flush().map_err(|e| WipeError {
+        // The engine journals the pass as done once the next one starts, so it
+        // has to be on the media, not in the page cache, when this returns
+        device.flush().and_then(|_| device.sync_data()).map_err(|e| WipeError {
             code: WipeErrorCode::HardwareError,
             message: format!("Failed to flush writes: {}", e),
             sector: None,
         })?;
 
-        Ok(sector_count)
+        Ok(sector_count.saturating_sub(bad_sectors.skipped_sectors()))
     }
 
-    fn verify_erasure(
+    /// Whether a host-managed zoned drive can be overwritten zone by zone:
+    /// the whole device, with every zone writable to its end
+    fn check_zoned_overwrite(&self, request: &WipeRequest, device_path: &str) -> WipeResult2<()> {
+        if request.scope != WipeScope::WholeDevice {
+            return Err(WipeError {
+                code: WipeErrorCode::InvalidTarget,
+                message: format!("{} is a host-managed zoned drive; only the whole device can be wiped", device_path),
+                sector: None,
+            });
+        }
+        let zones = zoned::report_zones(device_path).map_err(|e| WipeError {
+            code: WipeErrorCode::HardwareError,
+            message: format!("Failed to report the zones of {}: {}", device_path, e),
+            sector: None,
+        })?;
+        // ZNS zones can end before their address range does, and the rest cannot be written or verified
+        if let Some(zone) = zones.iter().find(|zone| zone.capacity < zone.length) {
+            return Err(WipeError {
+                code: WipeErrorCode::InvalidTarget,
+                message: format!(
+                    "Zone at sector {} holds {} of its {} bytes; use NVMe Format or Sanitize on this namespace",
+                    zone.start / 512,
+                    zone.capacity,
+                    zone.length
+                ),
+                sector: Some(zone.start / 512),
+            });
+        }
+        if let Some(zone) = zones.iter().find(|zone| zone.is_unwritable()) {
+            return Err(WipeError {
+                code: WipeErrorCode::HardwareError,
+                message: format!("Zone at sector {} is read-only or offline and cannot be overwritten", zone.start / 512),
+                sector: Some(zone.start / 512),
+            });
+        }
+        Ok(())
+    }
+
+    /// One pass over a host-managed zoned drive: reset the written zones and
+    /// fill each zone front to back; returns the zones reset
+    fn execute_zoned_pass(
         &self,
-        device_path: &str,
-        start_sector: u64,
-        sector_count: u64,
-        expected_pattern: &[u8],
-        request_id: &Uuid,
-    ) -> WipeResult2<ValidationResult> {
-        let mut device = File::open(device_path)
+        context: &mut PassContext,
+        content: &ExpectedContent,
+        pass_num: usize,
+        total_passes: u32,
+        throttle: &mut Throttle,
+    ) -> WipeResult2<usize> {
+        let device_path = context.device_path;
+        let request_id = &context.request_id;
+        let eta = &mut context.eta;
+        let hardware_error = |message: String| WipeError { code: WipeErrorCode::HardwareError, message, sector: None };
+        // Write pointers move with every pass, so the zones are reported again
+        let zones = zoned::report_zones(device_path)
+            .map_err(|e| hardware_error(format!("Failed to report the zones of {}: {}", device_path, e)))?;
+        let reset = zoned::reset_zones(device_path, &zones)
+            .map_err(|e| hardware_error(format!("Failed to reset the zones of {}: {}", device_path, e)))?;
+
+        let total_bytes: u64 = zones.iter().map(|zone| zone.capacity).sum();
+        let buffer_size = self.block_size.load(Ordering::Relaxed);
+        let mut last_progress_update = Instant::now();
+        let mut cancelled = false;
+
+        zoned::overwrite_zones(
+            device_path,
+            &zones,
+            buffer_size,
+            |offset, block| match content {
+                // The same as a plain pass, which writes the buffer over and over from sector 0
+                ExpectedContent::Pattern(buffer) => {
+                    for (i, byte) in block.iter_mut().enumerate() {
+                        *byte = buffer[((offset + i as u64) % buffer.len() as u64) as usize];
+                    }
+                }
+                ExpectedContent::Stream(stream) => stream.fill_at(offset, block),
+                _ => block.fill(0),
+            },
+            |bytes_written| {
+                if self.cancel_flag.as_ref().is_some_and(|flag| flag.load(Ordering::Relaxed)) {
+                    cancelled = true;
+                    return Err(io::Error::other("cancelled"));
+                }
+                self.throttle_write(throttle, buffer_size as u64);
+                // Zones are written with direct I/O, so a pause has no cached writes to flush
+                self.hold_while_paused(None, || PauseCheckpoint {
+                    pass: pass_num as u32,
+                    total_passes,
+                    bytes_written,
+                    next_sector: None,
+                    paused_at: Utc::now(),
+                }, || WipeProgress {
+                    request_id: *request_id,
+                    current_pass: pass_num as u32,
+                    total_passes,
+                    sectors_processed: bytes_written / 512,
+                    total_sectors: total_bytes / 512,
+                    percentage: bytes_written as f64 / total_bytes.max(1) as f64 * 100.0,
+                    current_operation: format!("Pass {} - Paused", pass_num),
+                    estimated_completion: None,
+                    estimated_completion_earliest: None,
+                    estimated_completion_latest: None,
+                    verifying: false,
+                })
+                .map_err(|e| io::Error::other(e.message))?;
+                if last_progress_update.elapsed() > Duration::from_millis(100) {
+                    let mut progress = WipeProgress {
+                        request_id: *request_id,
+                        current_pass: pass_num as u32,
+                        total_passes,
+                        sectors_processed: bytes_written / 512,
+                        total_sectors: total_bytes / 512,
+                        percentage: bytes_written as f64 / total_bytes.max(1) as f64 * 100.0,
+                        current_operation: format!("Pass {} - Writing zones in order", pass_num),
+                        estimated_completion: None,
+                        estimated_completion_earliest: None,
+                        estimated_completion_latest: None,
+                        verifying: false,
+                    };
+                    if let Some(estimate) = eta.observe_pass(pass_num as u32, bytes_written) {
+                        estimate.apply(&mut progress);
+                    }
+                    self.send_progress(progress);
+                    last_progress_update = Instant::now();
+                }
+                Ok(())
+            },
+        )
+        .map_err(|e| match cancelled {
+            true => WipeError {
+                code: WipeErrorCode::Cancelled,
+                message: format!("Wipe cancelled during pass {}", pass_num),
+                sector: None,
+            },
+            false => hardware_error(format!("Zoned overwrite of {} failed: {}", device_path, e)),
+        })?;
+        Ok(reset)
+    }
+
+    /// Fault-tolerant rewrite of a block whose write failed; gives up once the
+    /// configured bad sector limit is exceeded
+    fn rewrite_failed_block(
+        &self,
+        device: &mut File,
+        offset: u64,
+        data: &[u8],
+        bad_sectors: &mut BadSectorLog,
+    ) -> WipeResult2<()> {
+        write_block_tolerant(device, offset, data, 512, bad_sectors).map_err(|e| WipeError {
+            code: WipeErrorCode::HardwareError,
+            message: format!("Device stopped responding during sector retries: {}", e),
+            sector: Some(offset / 512),
+        })?;
+
+        if bad_sectors.exceeds(self.config.max_bad_sectors) {
+            return Err(WipeError {
+                code: WipeErrorCode::TooManyBadSectors,
+                message: format!(
+                    "{} sectors could not be overwritten (limit {})",
+                    bad_sectors.skipped_sectors(),
+                    self.config.max_bad_sectors
+                ),
+                sector: bad_sectors.ranges.last().map(|r| r.start_sector),
+            });
+        }
+
+        Ok(())
+    }
+
+    /// Read back a sample of the pass just written, from the medium rather than
+    /// the page cache. A mismatch fails the wipe before the next pass hides it.
+    fn verify_pass(
+        &self,
+        context: &PassContext,
+        expected: &ExpectedContent,
+        pass: u32,
+        pattern: String,
+    ) -> WipeResult2<PassVerification> {
+        let (device_path, start_sector, sector_count) = (context.device_path, context.start_sector, context.sector_count);
+        let mut device = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .open(device_path)
             .map_err(|e| WipeError {
                 code: WipeErrorCode::AccessDenied,
-                message: format!("Failed to open device for verification: {}", e),
+                message: format!("Failed to open device for pass read-back: {}", e),
                 sector: None,
             })?;
+        if let Err(e) = evict_cached(&device, start_sector * 512, sector_count * 512) {
+            warn!(pass, error = %e, "Could not bypass the cache for the pass read-back");
+        }
 
-        let total_bytes = sector_count * 512;
-        let sample_rate = self.config.verification_sample_rate;
-        let blocks_to_verify = ((sector_count as f64 * sample_rate) as u64).max(1);
-        let mut failed_sectors = Vec::new();
-        let mut sectors_verified = 0u64;
-        let mut bytes_read = 0u64;
+        let report = verify_device(
+            &mut device,
+            start_sector * 512,
+            sector_count * 512,
+            expected,
+            &self.config.pass_verification_policy,
+            &context.bad_sectors,
+            |_, _| {},
+        ).map_err(|e| WipeError {
+            code: WipeErrorCode::VerificationFailed,
+            message: format!("Pass {} read-back failed: {}", pass, e),
+            sector: None,
+        })?;
 
-        device.seek(SeekFrom::Start(start_sector * 512))
+        let verification = PassVerification::from_report(pass, pattern, &report);
+        info!(
+            pass,
+            bytes_verified = verification.bytes_verified,
+            mismatched_bytes = verification.mismatched_bytes,
+            "Pass read back"
+        );
+        if !verification.passed {
+            return Err(WipeError {
+                code: WipeErrorCode::VerificationFailed,
+                message: format!("Pass {} read-back found {} mismatched bytes", pass, verification.mismatched_bytes),
+                sector: verification.failed_sectors.first().copied(),
+            });
+        }
+        Ok(verification)
+    }
+
+    fn verify_erasure(
+        &self,
+        context: &mut PassContext,
+        expected: &ExpectedContent,
+    ) -> WipeResult2<(ValidationResult, VerificationReport)> {
+        let (device_path, start_sector, sector_count) = (context.device_path, context.start_sector, context.sector_count);
+        let request_id = &context.request_id;
+        let bad_sectors = &context.bad_sectors;
+        let eta = &mut context.eta;
+        let mut device = File::open(device_path)
             .map_err(|e| WipeError {
-                code: WipeErrorCode::HardwareError,
-                message: format!("Failed to seek for verification: {}", e),
-                sector: Some(start_sector),
+                code: WipeErrorCode::AccessDenied,
+                message: format!("Failed to open device for verification: {}", e),
+                sector: None,
             })?;
 
-        let mut buffer = vec![0u8; VERIFICATION_BLOCK_SIZE];
-        let expected_byte = if expected_pattern.len() == 1 { 
-            expected_pattern[0] 
-        } else { 
-            0 // For complex patterns, we'll do a simpler check
-        };
+        let policy = &self.config.verification_policy;
+        let mut last_progress_update = Instant::now();
 
-        while bytes_read < total_bytes && sectors_verified < blocks_to_verify {
-            let read_size = VERIFICATION_BLOCK_SIZE.min((total_bytes - bytes_read) as usize);
-            
-            match device.read_exact(&mut buffer[0..read_size]) {
-                Ok(_) => {
-                    // Verify the pattern
-                    for (i, &byte) in buffer[0..read_size].iter().enumerate() {
-                        if expected_pattern.len() == 1 {
-                            if byte != expected_byte {
-                                let sector = start_sector + (bytes_read + i as u64) / 512;
-                                failed_sectors.push(sector);
-                            }
-                        }
-                        // For complex patterns, additional verification logic would go here
-                    }
-                    
-                    bytes_read += read_size as u64;
-                    sectors_verified += (read_size as u64 + 511) / 512;
-                    
-                    // Update progress
-                    let percentage = (bytes_read as f64 / total_bytes as f64) * 100.0;
-                    self.send_progress(WipeProgress {
+        let report = verify_device(
+            &mut device,
+            start_sector * 512,
+            sector_count * 512,
+            expected,
+            policy,
+            bad_sectors,
+            |bytes_read, planned_bytes| {
+                if last_progress_update.elapsed() > Duration::from_millis(PROGRESS_UPDATE_INTERVAL) {
+                    let mut progress = WipeProgress {
                         request_id: *request_id,
                         current_pass: 0, // Verification pass
                         total_passes: 1,
                         sectors_processed: bytes_read / 512,
-                        total_sectors: sector_count,
-                        percentage,
-                        current_operation: "Verifying erasure".to_string(),
+                        total_sectors: planned_bytes / 512,
+                        percentage: (bytes_read as f64 / planned_bytes as f64) * 100.0,
+                        current_operation: format!("Verifying erasure ({})", policy.description()),
                         estimated_completion: None,
-                    });
-                }
-                Err(e) => {
-                    return Err(WipeError {
-                        code: WipeErrorCode::VerificationFailed,
-                        message: format!("Verification read failed: {}", e),
-                        sector: Some(start_sector + bytes_read / 512),
-                    });
+                        estimated_completion_earliest: None,
+                        estimated_completion_latest: None,
+                        verifying: true,
+                    };
+                    if let Some(estimate) = eta.observe_verification(bytes_read, planned_bytes) {
+                        estimate.apply(&mut progress);
+                    }
+                    self.send_progress(progress);
+                    last_progress_update = Instant::now();
                 }
-            }
-        }
+            },
+        ).map_err(|e| WipeError {
+            code: WipeErrorCode::VerificationFailed,
+            message: format!("Verification failed: {}", e),
+            sector: None,
+        })?;
 
-        let pattern_matches = failed_sectors.is_empty();
-        
-        Ok(ValidationResult {
-            sectors_verified,
-            failed_sectors,
-            pattern_matches,
+        info!(
+            bytes_verified = report.bytes_verified,
+            total_bytes = report.total_bytes,
+            coverage = report.coverage,
+            throughput_mbps = report.throughput_mbps,
+            failed_regions = report.failed_regions().count(),
+            "Verification finished"
+        );
+
+        let validation = ValidationResult {
+            sectors_verified: report.bytes_verified / 512,
+            failed_sectors: report.failed_sectors.clone(),
+            pattern_matches: report.passed,
             checksum_valid: true, // Additional checksum verification could be added
             completion_time: Utc::now(),
-        })
+        };
+
+        Ok((validation, report))
+    }
+
+    /// Keep the progress channel emitting while a hardware erase command blocks.
+    /// Returns `None` when progress reporting is not enabled.
+    pub fn monitor_hardware_erase(
+        &self,
+        request_id: Uuid,
+        total_sectors: u64,
+        operation: &str,
+        source: EraseProgressSource,
+    ) -> Option<HardwareEraseMonitor> {
+        let sender = self.progress_sender.clone()?;
+        let operation = operation.to_string();
+
+        Some(HardwareEraseMonitor::start(source, DEFAULT_UPDATE_INTERVAL, move |progress| {
+            let _ = sender.send(WipeProgress::from_erase_progress(request_id, total_sectors, &operation, &progress));
+        }))
+    }
+
+    /// Hold a write of `bytes` for the rate limits, pauses and drive temperature
+    fn throttle_write(&self, throttle: &mut Throttle, bytes: u64) {
+        let wait = throttle.before_write(bytes);
+        self.record_throttle(throttle);
+        self.throttle_wait(wait);
+        while throttle.thermal_pause() {
+            self.record_throttle(throttle);
+            if self.cancel_flag.as_ref().is_some_and(|flag| flag.load(Ordering::Relaxed)) {
+                return;
+            }
+            thread::sleep(MAX_THROTTLE_SLEEP);
+        }
+    }
+
+    fn record_throttle(&self, throttle: &mut Throttle) {
+        match throttle.limiting_changed() {
+            Some(Some(reason)) => self.timeline.record(TimelineEventKind::ThrottleEngaged { reason }),
+            Some(None) => self.timeline.record(TimelineEventKind::ThrottleReleased),
+            None => {}
+        }
+    }
+
+    /// Sleep off a rate limit in short steps, returning early once cancelled
+    /// or paused; the pause itself is held by `hold_while_paused`
+    fn throttle_wait(&self, wait: Duration) {
+        let until = Instant::now() + wait;
+        loop {
+            let now = Instant::now();
+            if self.cancel_flag.as_ref().is_some_and(|flag| flag.load(Ordering::Relaxed)) || self.limits.is_paused() {
+                return;
+            }
+            if now >= until {
+                return;
+            }
+            thread::sleep((until - now).min(MAX_THROTTLE_SLEEP));
+        }
+    }
+
+    /// At a block boundary of a paused wipe: flush `device` so the blocks
+    /// before the checkpoint are on the media, leave the checkpoint for the
+    /// engine and hold until the wipe is resumed or cancelled
+    fn hold_while_paused(
+        &self,
+        device: Option<&mut File>,
+        checkpoint: impl FnOnce() -> PauseCheckpoint,
+        progress: impl FnOnce() -> WipeProgress,
+    ) -> WipeResult2<()> {
+        if !self.limits.is_paused() {
+            return Ok(());
+        }
+        if let Some(device) = device {
+            device.flush().and_then(|_| device.sync_data()).map_err(|e| WipeError {
+                code: WipeErrorCode::HardwareError,
+                message: format!("Failed to flush writes before pausing: {}", e),
+                sector: None,
+            })?;
+        }
+        let checkpoint = checkpoint();
+        info!(pass = checkpoint.pass, total_passes = checkpoint.total_passes, bytes_written = checkpoint.bytes_written, "Pass holding");
+        self.timeline.record(TimelineEventKind::Paused { checkpoint: checkpoint.clone() });
+        self.limits.set_checkpoint(Some(checkpoint));
+        self.send_progress(progress());
+        while self.limits.is_paused() && !self.cancel_flag.as_ref().is_some_and(|flag| flag.load(Ordering::Relaxed)) {
+            thread::sleep(MAX_THROTTLE_SLEEP);
+        }
+        self.limits.set_checkpoint(None);
+        self.timeline.record(TimelineEventKind::Resumed);
+        Ok(())
     }
 
     fn send_progress(&self, progress: WipeProgress) {
@@ -405,4 +1258,15 @@ impl SecureSanitizer {
             let _ = sender.send(progress);
         }
     }
-}
\ No newline at end of file
+}
+
+/// Number of the first system partition intersecting the 512-byte sector range, if any
+fn system_partition_overlap(table: &PartitionTable, start_sector: u64, sector_count: u64) -> Option<u32> {
+    let end = start_sector + sector_count;
+    table.system_partitions()
+        .find(|partition| {
+            let (offset, length) = partition.byte_range(table.sector_size);
+            offset / 512 < end && start_sector < (offset + length) / 512
+        })
+        .map(|partition| partition.number)
+}
@@ -0,0 +1,226 @@
+//! Translated messages for the CLI, the GUI and printed certificates
+//!
+//! Messages live in Fluent catalogues under `resources/i18n`, one file per
+//! locale, compiled into the binary. `t!("message-id")` or
+//! `t!("message-id", name = value)` formats a message in the current locale,
+//! falling back to `en-US` for messages a catalogue lacks and to the id
+//! itself for unknown ids.
+//!
+//! The locale starts out as the system's (`HDD_TOOL_LOCALE`, then `LC_ALL`,
+//! `LC_MESSAGES` and `LANG`) and is replaced by `WipeConfiguration::locale`
+//! when an engine is configured with one. Only the text shown to people is
+//! translated; JSON output, the audit log and the signed certificate stay
+//! in English so they can be compared and verified anywhere.
+
+use std::cell::Cell;
+use std::sync::{OnceLock, RwLock};
+use tracing::warn;
+
+use fluent_bundle::concurrent::FluentBundle;
+use fluent_bundle::{FluentArgs, FluentResource};
+use unic_langid::LanguageIdentifier;
+
+pub use fluent_bundle::FluentValue;
+
+pub const DEFAULT_LOCALE: &str = "en-US";
+
+/// Locales with a catalogue, and the catalogue source
+const CATALOGUES: &[(&str, &str)] = &[
+    ("en-US", include_str!("../resources/i18n/en-US.ftl")),
+    ("es-ES", include_str!("../resources/i18n/es-ES.ftl")),
+];
+
+struct Catalogue {
+    locale: &'static str,
+    bundle: FluentBundle<FluentResource>,
+}
+
+fn catalogues() -> &'static [Catalogue] {
+    static CATALOGUES_LOADED: OnceLock<Vec<Catalogue>> = OnceLock::new();
+    CATALOGUES_LOADED.get_or_init(|| {
+        CATALOGUES
+            .iter()
+            .map(|&(locale, source)| {
+                let language: LanguageIdentifier = locale.parse().unwrap_or_default();
+                let mut bundle = FluentBundle::new_concurrent(vec![language]);
+                // Bidi isolation marks show up as stray characters in terminals and PDFs
+                bundle.set_use_isolating(false);
+                let resource = FluentResource::try_new(source.to_string()).unwrap_or_else(|(resource, errors)| {
+                    warn!(locale = %locale, errors = ?errors, "Errors in the message catalogue");
+                    resource
+                });
+                if let Err(errors) = bundle.add_resource(resource) {
+                    warn!(locale = %locale, errors = ?errors, "Duplicate messages in the catalogue");
+                }
+                Catalogue { locale, bundle }
+            })
+            .collect()
+    })
+}
+
+thread_local! {
+    /// Locale set by `with_locale` for the calling thread only
+    static THREAD_LOCALE: Cell<Option<&'static str>> = const { Cell::new(None) };
+}
+
+fn current() -> &'static RwLock<&'static str> {
+    static CURRENT: OnceLock<RwLock<&'static str>> = OnceLock::new();
+    CURRENT.get_or_init(|| RwLock::new(negotiate(&system_locale().unwrap_or_default())))
+}
+
+/// The locale asked for by the environment, e.g. "de_DE.UTF-8"
+pub fn system_locale() -> Option<String> {
+    ["HDD_TOOL_LOCALE", "LC_ALL", "LC_MESSAGES", "LANG"]
+        .iter()
+        .filter_map(|name| std::env::var(name).ok())
+        .find(|value| !value.is_empty())
+}
+
+/// Locales that have a catalogue
+pub fn available_locales() -> Vec<&'static str> {
+    CATALOGUES.iter().map(|(locale, _)| *locale).collect()
+}
+
+/// Name of `locale` in its own language, e.g. "Español"
+pub fn locale_name(locale: &str) -> String {
+    catalogues()
+        .iter()
+        .find(|catalogue| catalogue.locale == locale)
+        .and_then(|catalogue| {
+            let pattern = catalogue.bundle.get_message("language-name")?.value()?;
+            Some(catalogue.bundle.format_pattern(pattern, None, &mut Vec::new()).into_owned())
+        })
+        .unwrap_or_else(|| locale.to_string())
+}
+
+/// The catalogue locale closest to `requested`: the same locale, else the
+/// same language, else `en-US`. Accepts POSIX forms such as "es_MX.UTF-8".
+pub fn negotiate(requested: &str) -> &'static str {
+    let requested = requested.split(['.', '@']).next().unwrap_or_default().replace('_', "-");
+    let language = requested.split('-').next().unwrap_or_default();
+    let locales = available_locales();
+    locales
+        .iter()
+        .find(|locale| locale.eq_ignore_ascii_case(&requested))
+        .or_else(|| {
+            locales.iter().find(|locale| {
+                locale.split('-').next().is_some_and(|candidate| candidate.eq_ignore_ascii_case(language))
+            })
+        })
+        .copied()
+        .unwrap_or(DEFAULT_LOCALE)
+}
+
+/// Whether a catalogue speaks the language of `requested`
+pub fn supports(requested: &str) -> bool {
+    let language = |locale: &str| -> String {
+        locale.split(['-', '_', '.', '@']).next().unwrap_or_default().to_ascii_lowercase()
+    };
+    language(negotiate(requested)) == language(requested)
+}
+
+/// Switch every later message to the locale closest to `requested`;
+/// returns the locale picked
+pub fn set_locale(requested: &str) -> &'static str {
+    let locale = negotiate(requested);
+    *current().write().unwrap() = locale;
+    locale
+}
+
+pub fn locale() -> &'static str {
+    THREAD_LOCALE.with(Cell::get).unwrap_or_else(|| *current().read().unwrap())
+}
+
+/// Run `f` with this thread's messages in the locale closest to
+/// `requested`, e.g. to print a certificate in the customer's language
+pub fn with_locale<R>(requested: &str, f: impl FnOnce() -> R) -> R {
+    let previous = THREAD_LOCALE.with(|locale| locale.replace(Some(negotiate(requested))));
+    let result = f();
+    THREAD_LOCALE.with(|locale| locale.set(previous));
+    result
+}
+
+/// Message `id` in the current locale, with `args` filled in
+pub fn translate(id: &str, args: Option<&FluentArgs>) -> String {
+    let catalogues = catalogues();
+    let locale = locale();
+    let preferred = catalogues.iter().find(|catalogue| catalogue.locale == locale);
+    let fallback = catalogues.iter().find(|catalogue| catalogue.locale == DEFAULT_LOCALE);
+
+    for catalogue in preferred.into_iter().chain(fallback) {
+        if let Some(pattern) = catalogue.bundle.get_message(id).and_then(|message| message.value()) {
+            let mut errors = Vec::new();
+            return catalogue.bundle.format_pattern(pattern, args, &mut errors).into_owned();
+        }
+    }
+    id.to_string()
+}
+
+/// Build the arguments of a message; used by `t!`
+pub fn args<'a>(pairs: Vec<(&'a str, FluentValue<'a>)>) -> FluentArgs<'a> {
+    let mut args = FluentArgs::new();
+    for (name, value) in pairs {
+        args.set(name, value);
+    }
+    args
+}
+
+/// `t!("id")` or `t!("id", name = value, ...)`: message `id` in the current
+/// locale. Values are strings or numbers; format anything else first.
+#[macro_export]
+macro_rules! t {
+    ($id:expr) => {
+        $crate::i18n::translate($id, None)
+    };
+    ($id:expr, $($name:ident = $value:expr),+ $(,)?) => {
+        $crate::i18n::translate(
+            $id,
+            Some(&$crate::i18n::args(vec![$((stringify!($name), $crate::i18n::FluentValue::from($value))),+])),
+        )
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_negotiate_locale() {
+        assert_eq!(negotiate("es_MX.UTF-8"), "es-ES");
+        assert_eq!(negotiate("en-us"), "en-US");
+        assert_eq!(negotiate("C"), DEFAULT_LOCALE);
+        assert_eq!(negotiate(""), DEFAULT_LOCALE);
+        assert!(supports("es_MX.UTF-8"));
+        assert!(!supports("fr-FR"));
+    }
+
+    #[test]
+    fn test_with_locale_is_scoped_to_the_call() {
+        let global = locale();
+        assert_eq!(with_locale("es-ES", || translate("cert-yes", None)), "Sí");
+        assert_eq!(locale(), global);
+    }
+
+    #[test]
+    fn test_catalogues_define_the_same_messages() {
+        let ids = |source: &str| -> Vec<String> {
+            let mut ids: Vec<String> = source
+                .lines()
+                .filter(|line| line.chars().next().is_some_and(|c| c.is_ascii_lowercase()))
+                .filter_map(|line| line.split_once(" =").map(|(id, _)| id.trim().to_string()))
+                .collect();
+            ids.sort();
+            ids
+        };
+        let english = ids(CATALOGUES[0].1);
+        assert!(!english.is_empty());
+        for (locale, source) in &CATALOGUES[1..] {
+            assert_eq!(ids(source), english, "{} catalogue differs from en-US", locale);
+        }
+        for catalogue in catalogues() {
+            for id in &english {
+                assert!(catalogue.bundle.has_message(id), "{} cannot parse {}", catalogue.locale, id);
+            }
+        }
+    }
+}
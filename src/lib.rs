@@ -1,7 +1,28 @@
 pub mod sanitization;
 pub mod advanced_wiper;
 pub mod ata_commands;
+pub mod scsi_commands;
+pub mod mmc_commands;
 pub mod hpa_dco;
+pub mod opal;
+pub mod freeze;
+pub mod smart;
+pub mod erase_progress;
+pub mod bad_sectors;
+pub mod verification;
+pub mod imaging;
+pub mod s3;
+pub mod functional_test;
+pub mod relabel;
+pub mod security_state;
+pub mod pattern_stream;
+pub mod residual;
+pub mod partitions;
+pub mod raid;
+pub mod zoned;
+pub mod free_space;
+pub mod secure_delete;
+pub mod encryption;
 pub mod validation;
 pub mod examples;
 pub mod devices;
@@ -12,6 +33,13 @@ pub mod core;
 pub mod hardware;
 pub mod reporting;
 pub mod security;
+pub mod i18n;
 
 #[cfg(feature = "server")]
-pub mod server;
\ No newline at end of file
+pub mod server;
+
+#[cfg(feature = "ffi")]
+pub mod ffi;
+
+#[cfg(feature = "python")]
+pub mod python;
\ No newline at end of file
@@ -1,6 +1,9 @@
 use eframe::egui;
 use std::time::Duration;
 use std::sync::{Arc, Mutex};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::thread::JoinHandle;
 use chrono;
 
 // Platform-specific imports (currently unused)
@@ -14,22 +17,47 @@ use windows::{
 
 mod sanitization;
 mod ata_commands;
+mod scsi_commands;
+mod mmc_commands;
+mod opal;
+mod freeze;
+mod smart;
+mod erase_progress;
+mod bad_sectors;
+mod verification;
+mod pattern_stream;
+mod residual;
+mod partitions;
+mod raid;
+mod zoned;
+mod free_space;
+mod secure_delete;
+mod encryption;
 mod advanced_wiper;
 mod devices;
 mod ui;
 mod platform;
 mod auth;
 mod config;
+mod cli;
+#[cfg(feature = "tui")]
+mod tui;
 
 #[cfg(feature = "server")]
-mod server;
+use hdd_tool::server;
+use hdd_tool::{i18n, t};
+use hdd_tool::core::{DurationEstimate, OperationHistory, TargetType, ThroughputHistory, WriteRate, DEFAULT_HISTORY_PATH, DEFAULT_THROUGHPUT_PATH};
 
 use sanitization::{DataSanitizer, SanitizationProgress};
 use advanced_wiper::{AdvancedWiper, WipingAlgorithm, WipingProgress, DeviceInfo};
-use ui::{SecureTheme, TabWidget, DriveTableWidget, DriveInfo, AdvancedOptionsWidget, show_logo};
+use ui::{SecureTheme, ThemeMode, TabWidget, DriveTableWidget, DriveInfo, AdvancedOptionsWidget, DiskTreeWidget, DiskNode, WipeTarget, show_logo};
 use platform::{get_system_drives, get_device_path_for_sanitization};
 use auth::{AuthSystem, AuthUI, AuthPage};
 use config::AppConfig;
+use smart::HealthStatus;
+use freeze::FreezeStatus;
+use devices::capabilities::{detect_drive_capabilities, DriveCapabilities};
+use devices::watcher::{DeviceEvent, DeviceWatcher};
 
 #[derive(Debug, Clone)]
 struct DiskInfo {
@@ -44,6 +72,44 @@ struct DiskInfo {
     selected: bool,
 }
 
+/// A wipe running on a worker thread and the progress its eraser reports
+struct DriveWipe {
+    progress: Arc<Mutex<WipingProgress>>,
+    worker: JoinHandle<()>,
+    /// Bus or controller the drive sits behind
+    controller: String,
+}
+
+/// A selected drive waiting for a free slot on its controller
+#[derive(Debug, Clone)]
+struct QueuedWipe {
+    drive_index: usize,
+    drive_name: String,
+    drive_path: String,
+    controller: String,
+}
+
+/// How a wipe ended, as its worker thread reports it
+#[derive(Debug, Clone)]
+struct WipeOutcome {
+    drive_index: usize,
+    drive_name: String,
+    drive_path: String,
+    /// Algorithm the wipe ran with
+    method: String,
+    started_at: chrono::DateTime<chrono::Local>,
+    finished_at: chrono::DateTime<chrono::Local>,
+    /// Why the wipe failed, if it did
+    result: Result<(), String>,
+}
+
+/// How the Drives tab lists what can be sanitized
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum DriveView {
+    Volumes,
+    PhysicalDisks,
+}
+
 struct HDDApp {
     disks: Vec<DiskInfo>,
     sanitizer: DataSanitizer,
@@ -55,12 +121,34 @@ struct HDDApp {
     advanced_wiper: AdvancedWiper,
     selected_algorithm: WipingAlgorithm,
     device_analysis: Option<DeviceInfo>,
-    wipe_progress: Arc<Mutex<WipingProgress>>,
+    /// Wipes still running, by drive table row
+    drive_wipes: HashMap<usize, DriveWipe>,
+    /// Selected drives not started yet, in the order they were selected
+    wipe_queue: VecDeque<QueuedWipe>,
+    /// Worker threads send how their wipe ended here
+    wipe_results_tx: Sender<WipeOutcome>,
+    wipe_results: Receiver<WipeOutcome>,
+    /// Wipes of the current run that have ended
+    completed_wipes: Vec<WipeOutcome>,
+    /// Capabilities detected for each device, kept until the next refresh
+    drive_capabilities: HashMap<String, DriveCapabilities>,
+    /// Devices whose detection is still running
+    capability_probes: HashSet<String>,
+    capabilities_tx: Sender<DriveCapabilities>,
+    capabilities_rx: Receiver<DriveCapabilities>,
+    
+    // Hot-plug notifications
+    device_events: Receiver<DeviceEvent>,
+    pending_device_refresh: bool,
     
     // New UI Components
     tab_widget: TabWidget,
     drive_table: DriveTableWidget,
+    disk_tree: DiskTreeWidget,
+    drive_view: DriveView,
     advanced_options: AdvancedOptionsWidget,
+    /// Selected paths the advanced options' duration estimate is for
+    estimated_paths: Vec<String>,
     
     // Authentication System
     auth_system: AuthSystem,
@@ -75,18 +163,12 @@ struct HDDApp {
 
 impl HDDApp {
     fn new() -> Self {
-        let initial_progress = WipingProgress {
-            algorithm: WipingAlgorithm::NistClear,
-            current_pass: 0,
-            total_passes: 1,
-            bytes_processed: 0,
-            total_bytes: 0,
-            current_pattern: "Ready".to_string(),
-            estimated_time_remaining: Duration::from_secs(0),
-            speed_mbps: 0.0,
-        };
-        
         let config = AppConfig::load();
+        if let Some(locale) = &config.locale {
+            i18n::set_locale(locale);
+        }
+        let (wipe_results_tx, wipe_results) = mpsc::channel();
+        let (capabilities_tx, capabilities_rx) = mpsc::channel();
         
         let mut app = Self { 
             disks: Vec::new(),
@@ -98,11 +180,25 @@ impl HDDApp {
             advanced_wiper: AdvancedWiper::new(),
             selected_algorithm: WipingAlgorithm::NistClear,
             device_analysis: None,
-            wipe_progress: Arc::new(Mutex::new(initial_progress)),
+            drive_wipes: HashMap::new(),
+            wipe_queue: VecDeque::new(),
+            wipe_results_tx,
+            wipe_results,
+            completed_wipes: Vec::new(),
+            drive_capabilities: HashMap::new(),
+            capability_probes: HashSet::new(),
+            capabilities_tx,
+            capabilities_rx,
+            
+            device_events: DeviceWatcher::new().subscribe(),
+            pending_device_refresh: false,
             
             tab_widget: TabWidget::new(),
             drive_table: DriveTableWidget::new(),
+            disk_tree: DiskTreeWidget::new(),
+            drive_view: DriveView::Volumes,
             advanced_options: AdvancedOptionsWidget::new(),
+            estimated_paths: Vec::new(),
             
             auth_system: AuthSystem::new(),
             auth_ui: AuthUI::new(),
@@ -123,6 +219,7 @@ impl HDDApp {
     fn refresh_disks(&mut self) {
         self.disks.clear();
         self.drive_table.drives.clear();
+        self.drive_capabilities.clear();
         
         // Use cross-platform drive detection
         match get_system_drives() {
@@ -158,6 +255,21 @@ impl HDDApp {
                 println!("Error getting system drives: {}", e);
             }
         }
+        
+        let disks = match platform::get_physical_disks() {
+            Ok(disks) => disks
+                .into_iter()
+                .map(|disk| DiskNode {
+                    partitions: partitions::read_partition_table(&disk.path).ok().flatten(),
+                    disk,
+                })
+                .collect(),
+            Err(e) => {
+                println!("Error getting physical disks: {}", e);
+                Vec::new()
+            }
+        };
+        self.disk_tree.set_disks(disks);
     }
 
     // Cross-platform disk info is now handled by the platform module
@@ -165,8 +277,12 @@ impl HDDApp {
     fn get_detailed_drive_info(&self, drive_letter: &str) -> (String, bool) {
         use ata_commands::AtaInterface;
         
-        let drive_num = (drive_letter.chars().next().unwrap() as u8).saturating_sub(b'A');
-        let physical_drive_path = format!(r"\\.\PhysicalDrive{}", drive_num);
+        // Drive letters do not map to disk numbers; ask the volume for its disk
+        let volume = format!("{}\\", drive_letter.trim_end_matches('\\'));
+        let physical_drive_path = match platform::physical_disk_for_volume(&volume) {
+            Some(disk) => disk.path,
+            None => return ("Fixed Drive (Physical Disk Unknown)".to_string(), false),
+        };
         
         match AtaInterface::new(&physical_drive_path) {
             Ok(ata) => {
@@ -213,6 +329,78 @@ impl HDDApp {
         format!("{:.2} {}", size, UNITS[unit_index])
     }
     
+    /// Erase-related capabilities in the Details tab
+    fn show_capabilities(ui: &mut egui::Ui, capabilities: &DriveCapabilities) {
+        let yes_no = |value: bool| if value { format!("✅ {}", t!("gui-yes")) } else { format!("❌ {}", t!("gui-no")) };
+        let unknown = format!("❓ {}", t!("gui-cap-not-reported"));
+        
+        ui.add_space(5.0);
+        ui.label(t!("gui-cap-device", value = capabilities.device_path.as_str()));
+        if let (Some(model), Some(serial)) = (&capabilities.model, &capabilities.serial) {
+            ui.label(t!("gui-cap-model", model = model.as_str(), serial = serial.as_str()));
+        }
+        
+        match &capabilities.ata_security {
+            Some(security) => {
+                ui.label(if security.enhanced_erase {
+                    t!("gui-cap-secure-erase-enhanced")
+                } else {
+                    t!("gui-cap-secure-erase")
+                });
+                ui.label(t!(
+                    "gui-cap-security",
+                    password = if security.enabled { t!("gui-cap-password-set") } else { t!("gui-cap-no-password") },
+                    lock = if security.locked { t!("gui-cap-locked") } else { t!("gui-cap-unlocked") },
+                ));
+            }
+            None => { ui.label(t!("gui-cap-secure-erase-unsupported")); }
+        }
+        let freeze_color = match capabilities.freeze_status {
+            FreezeStatus::Frozen => SecureTheme::DANGER_RED,
+            FreezeStatus::NotFrozen | FreezeStatus::Unfrozen => SecureTheme::SUCCESS_GREEN,
+            _ => egui::Color32::GRAY,
+        };
+        ui.colored_label(freeze_color, t!("gui-cap-freeze", value = capabilities.freeze_status.as_str()))
+            .on_hover_text(capabilities.freeze_status.guidance());
+        
+        ui.label(t!("gui-cap-sanitize", value = capabilities.sanitize
+            .map(|sanitize| sanitize.description())
+            .unwrap_or_else(|| unknown.clone())));
+        ui.label(t!("gui-cap-crypto-erase", value = yes_no(capabilities.crypto_erase)));
+        ui.label(t!("gui-cap-trim", value = yes_no(capabilities.trim)));
+        match capabilities.zoned {
+            Some(model) => {
+                ui.colored_label(SecureTheme::WARNING_ORANGE, t!("gui-cap-zoned", value = model.as_str()))
+                    .on_hover_text(t!("gui-cap-zoned-hint"));
+            }
+            None => { ui.label(t!("gui-cap-zoned", value = t!("gui-cap-not-zoned"))); }
+        }
+        
+        match capabilities.hpa_hidden_sectors {
+            Some(0) => { ui.label(t!("gui-cap-hpa-none")); }
+            Some(hidden) => {
+                ui.colored_label(
+                    SecureTheme::WARNING_ORANGE,
+                    t!("gui-cap-hpa-hidden", size = Self::format_bytes(hidden * 512), sectors = hidden),
+                );
+            }
+            None => { ui.label(t!("gui-cap-hpa", value = unknown.clone())); }
+        }
+        ui.label(match capabilities.dco_supported {
+            Some(true) => t!("gui-cap-dco-supported"),
+            Some(false) => t!("gui-cap-dco-unsupported"),
+            None => t!("gui-cap-dco", value = unknown.clone()),
+        });
+        
+        let encryption = capabilities.encryption.description();
+        if capabilities.encryption.is_encrypted() {
+            ui.colored_label(SecureTheme::WARNING_ORANGE, t!("gui-cap-encrypted", value = format!("🔐 {}", encryption)));
+        } else {
+            ui.label(t!("gui-cap-encrypted", value = encryption));
+        }
+        ui.label(t!("gui-cap-detected", time = capabilities.detected_at.format("%H:%M:%S").to_string()));
+    }
+    
     fn handle_erase_request(&mut self) {
         // Check user permissions first
         if let Some(user) = self.auth_system.current_user() {
@@ -231,6 +419,14 @@ impl HDDApp {
             return;
         }
         
+        // Targets picked in the disk view become the selected rows
+        if self.drive_view == DriveView::PhysicalDisks
+            && let Err(message) = self.select_disk_targets()
+        {
+            self.last_error_message = Some(message);
+            return;
+        }
+        
         // Get selected drives
         let selected_drives: Vec<usize> = self.drive_table.drives
             .iter()
@@ -263,6 +459,45 @@ impl HDDApp {
         self.start_real_sanitization();
     }
     
+    /// Select the drive table rows of the disks and volumes picked in the
+    /// disk view, adding rows for targets the volume list does not have
+    fn select_disk_targets(&mut self) -> Result<(), String> {
+        let targets = self.disk_tree.selected.clone();
+        for target in &targets {
+            let node = self.disk_tree.disks.iter().find(|node| match target {
+                WipeTarget::Disk(path) => node.disk.path == *path,
+                WipeTarget::Volume(path) => node.disk.volumes.contains(path),
+            });
+            let system = match target {
+                WipeTarget::Disk(_) => node.is_some_and(|node| node.disk.is_root_disk),
+                WipeTarget::Volume(path) => path == "/" || path.to_uppercase().starts_with("C:"),
+            };
+            if system {
+                return Err(format!("❌ Cannot sanitize {} - it holds the running system!", target.path()));
+            }
+        }
+        
+        for drive in &mut self.drive_table.drives {
+            drive.selected = false;
+        }
+        for target in targets {
+            let path = target.path().to_string();
+            if let Some(drive) = self.drive_table.drives.iter_mut().find(|drive| drive.path == path) {
+                drive.selected = true;
+                continue;
+            }
+            let node = self.disk_tree.disks.iter().find(|node| node.disk.path == path);
+            let (name, size) = match (&target, node) {
+                (WipeTarget::Disk(_), Some(node)) => (node.disk.description(), Self::format_bytes(node.disk.size_bytes)),
+                _ => (format!("Volume {}", path), "-".to_string()),
+            };
+            let mut drive = DriveInfo::new(name, path, size, "-".to_string());
+            drive.selected = true;
+            self.drive_table.add_drive(drive);
+        }
+        Ok(())
+    }
+    
     fn start_real_sanitization(&mut self) {
         // Collect drives to sanitize
         let drives_to_process: Vec<(String, String, usize)> = self.drive_table.drives
@@ -276,18 +511,134 @@ impl HDDApp {
             return;
         }
         
-        // Start the sanitization process for each selected drive
+        self.completed_wipes.clear();
+        self.sanitization_progress = None;
+        
+        // Queue every selected drive; drives sharing a bus wait for each other
         for (drive_path, drive_name, drive_index) in drives_to_process {
-            // Use device-specific sanitization by default, with fallback to traditional method
-            self.start_device_specific_sanitization(&drive_path, &drive_name, drive_index);
+            let controller = self.controller_of(&drive_path);
+            if let Some(drive) = self.drive_table.drives.get_mut(drive_index) {
+                drive.status = format!("Queued ({})", controller);
+            }
+            self.wipe_queue.push_back(QueuedWipe { drive_index, drive_name, drive_path, controller });
+        }
+        self.start_queued_wipes();
+    }
+    
+    /// Estimate how long the selected drives take with the selected
+    /// algorithm, from earlier wipes of their models where there are any;
+    /// shown for the longest of them, and only worked out again when the
+    /// selection changes
+    fn refresh_duration_estimate(&mut self) {
+        let paths: Vec<String> = match self.drive_view {
+            DriveView::PhysicalDisks => self.disk_tree.selected.iter().map(|target| target.path().to_string()).collect(),
+            DriveView::Volumes => self.drive_table.drives.iter().filter(|drive| drive.selected).map(|drive| drive.path.clone()).collect(),
+        };
+        if paths == self.estimated_paths {
+            return;
+        }
+
+        let records = OperationHistory::read_records(DEFAULT_HISTORY_PATH).unwrap_or_default();
+        let throughput = ThroughputHistory::open(DEFAULT_THROUGHPUT_PATH).ok();
+        let passes = algorithm_passes(&self.selected_algorithm);
+        self.advanced_options.estimate = paths
+            .iter()
+            .filter_map(|path| self.physical_disk_of(path))
+            .map(|node| {
+                let target_type = if node.disk.rotational == Some(false) { TargetType::SSD } else { TargetType::HDD };
+                let rate = throughput
+                    .as_ref()
+                    .and_then(|throughput| throughput.write_rate(&node.disk.model, &node.disk.bus_type))
+                    .or_else(|| WriteRate::from_history(&node.disk.model, &records))
+                    .unwrap_or_else(|| WriteRate::nominal(target_type, &node.disk.bus_type));
+                DurationEstimate::overwrite(node.disk.size_bytes, passes, 0, &rate)
+            })
+            .max_by_key(|estimate| estimate.expected_seconds)
+            .map(|estimate| estimate.summary());
+        self.estimated_paths = paths;
+    }
+
+    /// The physical disk behind `drive_path`, which may be the disk itself
+    /// or one of its volumes
+    fn physical_disk_of(&self, drive_path: &str) -> Option<&DiskNode> {
+        self.disk_tree.disks
+            .iter()
+            .find(|node| node.disk.path == drive_path || node.disk.volumes.iter().any(|volume| volume == drive_path))
+    }
+    
+    /// Bus type of the physical disk behind `drive_path`
+    fn controller_of(&self, drive_path: &str) -> String {
+        self.physical_disk_of(drive_path)
+            .map(|node| node.disk.bus_type.clone())
+            .filter(|bus| !bus.is_empty())
+            .unwrap_or_else(|| "Unknown".to_string())
+    }
+    
+    /// Device to probe for the drive table row `index`: its physical disk
+    /// when known, otherwise the path it would be sanitized through
+    fn capability_path(&self, index: usize) -> Option<String> {
+        let drive = self.drive_table.drives.get(index)?;
+        if let Some(node) = self.physical_disk_of(&drive.path) {
+            return Some(node.disk.path.clone());
+        }
+        Some(match self.disks.get(index) {
+            Some(disk_info) => get_device_path_for_sanitization(&platform::DriveInfo {
+                path: disk_info.drive_letter.clone(),
+                label: disk_info.label.clone(),
+                drive_type: disk_info.drive_type.clone(),
+                total_space: disk_info.total_space,
+                free_space: disk_info.free_space,
+            }),
+            None => drive.path.clone(),
+        })
+    }
+    
+    /// Start capability detection for selected drives not probed yet, and
+    /// collect the results of finished probes
+    fn poll_capabilities(&mut self) {
+        while let Ok(capabilities) = self.capabilities_rx.try_recv() {
+            self.capability_probes.remove(&capabilities.device_path);
+            self.drive_capabilities.insert(capabilities.device_path.clone(), capabilities);
         }
         
-        // Begin progress simulation/tracking
-        self.simulate_sanitization_progress();
+        // Probing issues ATA/SCSI commands; leave drives alone while they are wiped
+        if self.sanitization_in_progress {
+            return;
+        }
+        let paths: Vec<String> = (0..self.drive_table.drives.len())
+            .filter(|&index| self.drive_table.drives[index].selected)
+            .filter_map(|index| self.capability_path(index))
+            .collect();
+        for path in paths {
+            if self.drive_capabilities.contains_key(&path) || !self.capability_probes.insert(path.clone()) {
+                continue;
+            }
+            let results = self.capabilities_tx.clone();
+            std::thread::spawn(move || {
+                let _ = results.send(detect_drive_capabilities(&path));
+            });
+        }
+    }
+    
+    /// Start queued wipes, in order, while their controller has fewer than
+    /// `max_wipes_per_controller` running
+    fn start_queued_wipes(&mut self) {
+        let limit = self.config.max_wipes_per_controller.max(1);
+        let mut waiting = VecDeque::new();
+        while let Some(queued) = self.wipe_queue.pop_front() {
+            let running = self.drive_wipes.values().filter(|wipe| wipe.controller == queued.controller).count();
+            if running < limit {
+                // Use device-specific sanitization by default, with fallback to traditional method
+                self.start_device_specific_sanitization(&queued.drive_path, &queued.drive_name, queued.drive_index, queued.controller);
+            } else {
+                waiting.push_back(queued);
+            }
+        }
+        self.wipe_queue = waiting;
     }
     
     /// Enhanced sanitization using device-specific erasers
-    fn start_device_specific_sanitization(&mut self, drive_path: &str, drive_name: &str, drive_index: usize) {
+    fn start_device_specific_sanitization(&mut self, drive_path: &str, drive_name: &str, drive_index: usize, controller: String) {
         // Get the actual device path for sanitization (platform-specific)
         let sanitization_path = if let Some(disk_info) = self.disks.get(drive_index) {
             get_device_path_for_sanitization(&platform::DriveInfo {
@@ -314,86 +665,43 @@ impl HDDApp {
         let sanitization_path_clone = sanitization_path.clone();
         let drive_name_clone = drive_name.to_string();
         let selected_algorithm = self.selected_algorithm.clone();
-        let wipe_progress = Arc::clone(&self.wipe_progress);
+        let wipe_progress = Arc::new(Mutex::new(WipingProgress {
+            algorithm: selected_algorithm.clone(),
+            current_pass: 0,
+            total_passes: 1,
+            bytes_processed: 0,
+            total_bytes: 0,
+            current_pattern: "Analysing device".to_string(),
+            estimated_time_remaining: Duration::from_secs(0),
+            speed_mbps: 0.0,
+        }));
+        let worker_progress = Arc::clone(&wipe_progress);
         
         // Start analysis and sanitization in a separate thread
-        std::thread::spawn(move || {
-            match devices::DeviceFactory::analyze_and_create(&device_path_clone) {
-                Ok((device_info, eraser)) => {
-                    println!("✅ Device analysis complete:");
-                    println!("   Device Type: {:?}", device_info.device_type);
-                    println!("   Model: {}", device_info.model);
-                    println!("   Size: {} bytes", device_info.size_bytes);
-                    println!("   Supports Secure Erase: {}", device_info.supports_secure_erase);
-                    println!("   Supports TRIM: {}", device_info.supports_trim);
-                    
-                    // Get recommended algorithms for this device type
-                    let recommended_algorithms = eraser.get_recommended_algorithms();
-                    println!("🔧 Recommended algorithms: {:?}", recommended_algorithms);
-                    
-                    // Use selected algorithm, or fall back to first recommended
-                    let algorithm_to_use = if recommended_algorithms.contains(&selected_algorithm) {
-                        selected_algorithm
-                    } else {
-                        recommended_algorithms.first().cloned().unwrap_or(WipingAlgorithm::Random)
-                    };
-                    
-                    println!("🚀 Using algorithm: {:?}", algorithm_to_use);
-                    
-                    // Initialize progress
-                    if let Ok(mut progress) = wipe_progress.lock() {
-                        progress.algorithm = algorithm_to_use.clone();
-                        progress.bytes_processed = 0;
-                        progress.total_bytes = device_info.size_bytes;
-                        progress.current_pass = 0;
-                        progress.total_passes = match algorithm_to_use {
-                            WipingAlgorithm::DoD522022M => 3,
-                            WipingAlgorithm::Gutmann => 35,
-                            WipingAlgorithm::SevenPass => 7,
-                            WipingAlgorithm::ThreePass => 3,
-                            WipingAlgorithm::TwoPass => 2,
-                            _ => 1,
-                        };
-                    }
-                    
-                    // Perform device-specific erasure
-                    match eraser.erase_device(&device_info, algorithm_to_use, wipe_progress.clone()) {
-                        Ok(_) => {
-                            println!("✅ Device-specific erasure completed for {}", drive_name_clone);
-                            
-                            // Verify erasure if supported
-                            match eraser.verify_erasure(&device_info) {
-                                Ok(true) => println!("✅ Erasure verification passed for {}", drive_name_clone),
-                                Ok(false) => println!("⚠️  Erasure verification failed for {}", drive_name_clone),
-                                Err(e) => println!("❌ Erasure verification error for {}: {}", drive_name_clone, e),
-                            }
-                        }
-                        Err(e) => {
-                            println!("❌ Device-specific erasure failed for {}: {}", drive_name_clone, e);
-                            println!("🔄 Falling back to traditional file-level sanitization...");
-                            
-                            // Fallback to NIST SP 800-88 disk purge
-                            let sanitizer = DataSanitizer::new();
-                            match sanitizer.nist_purge_entire_disk(&device_path_clone, None) {
-                                Ok(_) => println!("✅ NIST SP 800-88 Purge completed for {}", drive_name_clone),
-                                Err(e) => println!("❌ NIST SP 800-88 Purge also failed for {}: {}", drive_name_clone, e),
-                            }
-                        }
-                    }
-                }
-                Err(e) => {
-                    println!("❌ Device analysis failed for {}: {}", drive_name_clone, e);
-                    println!("🔄 Falling back to traditional file-level sanitization...");
-                    
-                    // Fallback to NIST SP 800-88 disk purge
-                    let sanitizer = DataSanitizer::new();
-                    match sanitizer.nist_purge_entire_disk(&sanitization_path_clone, None) {
-                        Ok(_) => println!("✅ NIST SP 800-88 Purge completed for {}", drive_name_clone),
-                        Err(e) => println!("❌ NIST SP 800-88 Purge also failed for {}: {}", drive_name_clone, e),
-                    }
-                }
-            }
+        let results = self.wipe_results_tx.clone();
+        let outcome_name = drive_name.to_string();
+        let outcome_path = drive_path.to_string();
+        let worker = std::thread::spawn(move || {
+            let started_at = chrono::Local::now();
+            let result = run_device_wipe(
+                &device_path_clone,
+                &sanitization_path_clone,
+                &drive_name_clone,
+                selected_algorithm,
+                Arc::clone(&worker_progress),
+            );
+            let method = worker_progress.lock().map(|progress| format!("{:?}", progress.algorithm)).unwrap_or_default();
+            let _ = results.send(WipeOutcome {
+                drive_index,
+                drive_name: outcome_name,
+                drive_path: outcome_path,
+                method,
+                started_at,
+                finished_at: chrono::Local::now(),
+                result,
+            });
         });
+        self.drive_wipes.insert(drive_index, DriveWipe { progress: wipe_progress, worker, controller });
         
         // Initialize progress tracking for this drive
         let total_bytes = if let Some(drive) = self.drive_table.drives.get(drive_index) {
@@ -411,6 +719,12 @@ impl HDDApp {
                     WipingAlgorithm::AtaSecureErase => "ATA Secure Erase",
                     WipingAlgorithm::NvmeSecureErase => "NVMe Secure Erase",
                     WipingAlgorithm::NvmeCryptoErase => "NVMe Crypto Erase",
+                    WipingAlgorithm::NvmeSanitizeBlockErase => "NVMe Sanitize Block Erase",
+                    WipingAlgorithm::NvmeSanitizeCryptoErase => "NVMe Sanitize Crypto Erase",
+                    WipingAlgorithm::ZonedOverwrite => "Zoned Overwrite",
+                    WipingAlgorithm::ScsiSanitizeCryptoErase => "SCSI Sanitize Crypto Erase",
+                    WipingAlgorithm::ScsiSanitizeBlockErase => "SCSI Sanitize Block Erase",
+                    WipingAlgorithm::CryptoHeaderDestruction => "Encryption Header Destruction",
                     _ => "Optimized",
                 });
         }
@@ -455,76 +769,95 @@ impl HDDApp {
         }
     }
     
-    fn simulate_sanitization_progress(&mut self) {
-        // Collect drive data first to avoid borrowing conflicts
-        let mut drive_updates = Vec::new();
-        let mut total_bytes_all_drives = 0u64;
-        let mut total_processed_all_drives = 0u64;
-        
-        // Start processing for selected drives
-        for (i, drive) in self.drive_table.drives.iter().enumerate() {
-            if drive.selected && drive.progress == 0.0 {
-                // Simulate total bytes based on drive size
-                // Parse size string (e.g., "100 GB" -> bytes)
-                let total_bytes = self.parse_size_to_bytes(&drive.size);
-                drive_updates.push((i, total_bytes, true)); // true = start processing
-            }
-        }
-        
-        // Apply start processing updates
-        for (i, total_bytes, start) in drive_updates {
-            if start {
-                if let Some(drive) = self.drive_table.drives.get_mut(i) {
-                    drive.start_processing(total_bytes);
-                }
+    /// Copy the progress each worker thread reports into its drive's row,
+    /// and finish the rows whose wipe has ended
+    fn poll_wipe_progress(&mut self) {
+        for (&index, wipe) in &self.drive_wipes {
+            let progress = match wipe.progress.lock() {
+                Ok(progress) => progress.clone(),
+                Err(_) => continue,
+            };
+            if let Some(drive) = self.drive_table.drives.get_mut(index) {
+                drive.update_from_wipe(&progress);
             }
         }
         
-        // Update progress for processing drives and calculate overall progress
-        let mut any_in_progress = false;
-        let mut all_completed = true;
+        while let Ok(outcome) = self.wipe_results.try_recv() {
+            if let Some(wipe) = self.drive_wipes.remove(&outcome.drive_index) {
+                let _ = wipe.worker.join();
+            }
+            self.finish_drive(outcome);
+        }
         
-        for drive in &mut self.drive_table.drives {
-            if drive.selected {
-                total_bytes_all_drives += drive.bytes_total;
-                
-                if drive.start_time.is_some() && drive.progress < 1.0 {
-                    // Simulate progress increment (in real implementation, this would come from actual sanitization)
-                    let increment = 1024 * 1024 * 2; // 2MB per update cycle
-                    let new_bytes_processed = (drive.bytes_processed + increment).min(drive.bytes_total);
-                    drive.update_progress(new_bytes_processed);
-                    any_in_progress = true;
-                    
-                    if drive.progress < 1.0 {
-                        all_completed = false;
-                    }
-                }
-                
-                total_processed_all_drives += drive.bytes_processed;
+        // A thread that ended without reporting has panicked
+        let panicked: Vec<usize> = self.drive_wipes
+            .iter()
+            .filter(|(_, wipe)| wipe.worker.is_finished())
+            .map(|(&index, _)| index)
+            .collect();
+        for index in panicked {
+            // Its result may have arrived since the channel was drained
+            if let Ok(outcome) = self.wipe_results.try_recv() {
+                self.drive_wipes.remove(&outcome.drive_index);
+                self.finish_drive(outcome);
+                continue;
+            }
+            if let Some(wipe) = self.drive_wipes.remove(&index) {
+                let method = wipe.progress.lock().map(|progress| format!("{:?}", progress.algorithm)).unwrap_or_default();
+                let drive = self.drive_table.drives.get(index);
+                self.finish_drive(WipeOutcome {
+                    drive_index: index,
+                    drive_name: drive.map(|drive| drive.name.clone()).unwrap_or_default(),
+                    drive_path: drive.map(|drive| drive.path.clone()).unwrap_or_default(),
+                    method,
+                    started_at: chrono::Local::now(),
+                    finished_at: chrono::Local::now(),
+                    result: Err("Wipe thread panicked".to_string()),
+                });
             }
         }
         
-        // Update overall sanitization progress
-        if total_bytes_all_drives > 0 {
-            let overall_percentage = (total_processed_all_drives as f64 / total_bytes_all_drives as f64) * 100.0;
-            
-            let progress = SanitizationProgress {
-                current_pass: if overall_percentage < 33.0 { 1 } else if overall_percentage < 66.0 { 2 } else { 3 },
-                total_passes: 3,
-                percentage: overall_percentage,
-                bytes_processed: total_processed_all_drives,
-                total_bytes: total_bytes_all_drives,
-                estimated_time_remaining: std::time::Duration::from_secs(0),
+        // Finished wipes free their controller for the next drive in line
+        self.start_queued_wipes();
+        
+        // Overall progress across the drives of this run
+        let (mut total_bytes, mut bytes_processed) = (0u64, 0u64);
+        for drive in self.drive_table.drives.iter().filter(|drive| drive.selected && drive.start_time.is_some()) {
+            total_bytes += drive.bytes_total;
+            bytes_processed += drive.bytes_processed;
+        }
+        let current = self.drive_wipes.values().filter_map(|wipe| wipe.progress.lock().ok().map(|p| p.clone())).next();
+        if total_bytes > 0 {
+            self.sanitization_progress = Some(SanitizationProgress {
+                current_pass: current.as_ref().map_or(0, |progress| progress.current_pass),
+                total_passes: current.as_ref().map_or(0, |progress| progress.total_passes),
+                percentage: bytes_processed as f64 / total_bytes as f64 * 100.0,
+                bytes_processed,
+                total_bytes,
+                estimated_time_remaining: current.as_ref().map_or(Duration::ZERO, |progress| progress.estimated_time_remaining),
                 current_operation: "Device-specific sanitization".to_string(),
-            };
-            self.sanitization_progress = Some(progress);
+            });
         }
         
-        // Check if sanitization is complete
-        if all_completed && any_in_progress {
+        if self.sanitization_in_progress && self.drive_wipes.is_empty() && self.wipe_queue.is_empty() {
             self.sanitization_in_progress = false;
-            self.last_error_message = Some("✅ Sanitization completed successfully!".to_string());
+            let failures: Vec<String> = self.completed_wipes
+                .iter()
+                .filter_map(|wipe| wipe.result.as_ref().err().map(|error| format!("{}: {}", wipe.drive_name, error)))
+                .collect();
+            self.last_error_message = Some(if failures.is_empty() {
+                "✅ Sanitization completed successfully!".to_string()
+            } else {
+                format!("❌ Sanitization failed on {} drive(s): {}", failures.len(), failures.join("; "))
+            });
+        }
+    }
+    
+    fn finish_drive(&mut self, outcome: WipeOutcome) {
+        if let Some(drive) = self.drive_table.drives.get_mut(outcome.drive_index) {
+            drive.finish_wipe(outcome.result.as_ref().err().map(String::as_str));
         }
+        self.completed_wipes.push(outcome);
     }
     
     fn parse_size_to_bytes(&self, size_str: &str) -> u64 {
@@ -560,12 +893,17 @@ impl HDDApp {
         report.push_str(&format!("Verification: {}\n", self.advanced_options.verification));
         report.push_str("\n=== SANITIZED DRIVES ===\n");
         
-        for drive in &self.drive_table.drives {
-            if drive.selected && drive.progress >= 1.0 {
-                report.push_str(&format!("✅ {} ({}): Complete\n", drive.name, drive.path));
+        for wipe in &self.completed_wipes {
+            match &wipe.result {
+                Ok(()) => report.push_str(&format!("✅ {} ({}): Complete\n", wipe.drive_name, wipe.drive_path)),
+                Err(error) => report.push_str(&format!("❌ {} ({}): FAILED\n   Error: {}\n", wipe.drive_name, wipe.drive_path, error)),
+            }
+            if let Some(drive) = self.drive_table.drives.get(wipe.drive_index) {
                 report.push_str(&format!("   Size: {}\n", drive.size));
-                report.push_str(&format!("   Status: {}\n", drive.status));
             }
+            report.push_str(&format!("   Algorithm: {}\n", wipe.method));
+            report.push_str(&format!("   Started: {}\n", wipe.started_at.format("%Y-%m-%d %H:%M:%S")));
+            report.push_str(&format!("   Finished: {}\n", wipe.finished_at.format("%Y-%m-%d %H:%M:%S")));
         }
         
         report.push_str("\n=== COMPLIANCE ===\n");
@@ -589,10 +927,199 @@ impl HDDApp {
     }
 }
 
+/// Overwrite passes `algorithm` makes; firmware erases count as one
+fn algorithm_passes(algorithm: &WipingAlgorithm) -> u32 {
+    match algorithm {
+        WipingAlgorithm::DoD522022M => 3,
+        WipingAlgorithm::Gutmann => 35,
+        WipingAlgorithm::SevenPass => 7,
+        WipingAlgorithm::ThreePass => 3,
+        WipingAlgorithm::TwoPass => 2,
+        _ => 1,
+    }
+}
+
+/// Analyse the device and wipe it with `selected_algorithm`, or the eraser's
+/// recommendation, falling back to a NIST purge; returns why the wipe failed
+fn run_device_wipe(
+    device_path: &str,
+    sanitization_path: &str,
+    drive_name: &str,
+    selected_algorithm: WipingAlgorithm,
+    wipe_progress: Arc<Mutex<WipingProgress>>,
+) -> Result<(), String> {
+    match devices::DeviceFactory::analyze_and_create(device_path) {
+        Ok((device_info, eraser)) => {
+            println!("✅ Device analysis complete:");
+            println!("   Device Type: {:?}", device_info.device_type);
+            println!("   Model: {}", device_info.model);
+            println!("   Size: {} bytes", device_info.size_bytes);
+            println!("   Supports Secure Erase: {}", device_info.supports_secure_erase);
+            println!("   Supports TRIM: {}", device_info.supports_trim);
+            
+            // Get recommended algorithms for this device
+            let recommended_algorithms = devices::recommended_algorithms(&device_info, eraser.as_ref());
+            println!("🔧 Recommended algorithms: {:?}", recommended_algorithms);
+            
+            // Use selected algorithm, or fall back to first recommended
+            let header_destruction_possible = selected_algorithm == WipingAlgorithm::CryptoHeaderDestruction
+                && device_info.encryption_status.supports_header_destruction();
+            let algorithm_to_use = if recommended_algorithms.contains(&selected_algorithm) || header_destruction_possible {
+                selected_algorithm
+            } else {
+                recommended_algorithms.first().cloned().unwrap_or(WipingAlgorithm::Random)
+            };
+            
+            println!("🚀 Using algorithm: {:?}", algorithm_to_use);
+            
+            // Initialize progress
+            if let Ok(mut progress) = wipe_progress.lock() {
+                progress.algorithm = algorithm_to_use.clone();
+                progress.bytes_processed = 0;
+                progress.total_bytes = device_info.size_bytes;
+                progress.current_pass = 0;
+                progress.total_passes = algorithm_passes(&algorithm_to_use);
+            }
+            
+            // Header destruction is not device specific and verifies its own regions
+            if algorithm_to_use == WipingAlgorithm::CryptoHeaderDestruction {
+                return match encryption::destroy_key_material(&device_info.device_path) {
+                    Ok(report) if report.verified => {
+                        println!("✅ Key material destroyed on {} ({} volume(s))", drive_name, report.volumes_destroyed.len());
+                        Ok(())
+                    }
+                    Ok(report) => {
+                        println!("⚠️  Header destruction on {} could not be verified: {}", drive_name, report.notes.join("; "));
+                        Err(format!("Header destruction could not be verified: {}", report.notes.join("; ")))
+                    }
+                    Err(e) => {
+                        println!("❌ Header destruction failed for {}: {}", drive_name, e);
+                        Err(format!("Header destruction failed: {}", e))
+                    }
+                };
+            }
+            
+            // Zoned overwrites reset and fill the zones themselves
+            if algorithm_to_use == WipingAlgorithm::ZonedOverwrite {
+                return match run_zoned_overwrite(&device_info.device_path, wipe_progress) {
+                    Ok(bytes_written) => {
+                        println!("✅ Zoned overwrite completed for {} ({} bytes)", drive_name, bytes_written);
+                        Ok(())
+                    }
+                    Err(e) => {
+                        println!("❌ Zoned overwrite failed for {}: {}", drive_name, e);
+                        Err(format!("Zoned overwrite failed: {}", e))
+                    }
+                };
+            }
+            
+            // Perform device-specific erasure
+            match eraser.erase_device(&device_info, algorithm_to_use, wipe_progress.clone()) {
+                Ok(_) => {
+                    println!("✅ Device-specific erasure completed for {}", drive_name);
+                    
+                    // Verify erasure if supported
+                    match eraser.verify_erasure(&device_info) {
+                        Ok(true) => {
+                            println!("✅ Erasure verification passed for {}", drive_name);
+                            Ok(())
+                        }
+                        Ok(false) => {
+                            println!("⚠️  Erasure verification failed for {}", drive_name);
+                            Err("Erasure verification failed".to_string())
+                        }
+                        Err(e) => {
+                            println!("❌ Erasure verification error for {}: {}", drive_name, e);
+                            Err(format!("Erasure verification error: {}", e))
+                        }
+                    }
+                }
+                Err(e) => {
+                    println!("❌ Device-specific erasure failed for {}: {}", drive_name, e);
+                    println!("🔄 Falling back to traditional file-level sanitization...");
+                    
+                    // Fallback to NIST SP 800-88 disk purge
+                    let sanitizer = DataSanitizer::new();
+                    match sanitizer.nist_purge_entire_disk(device_path, Some(report_purge_progress(wipe_progress))) {
+                        Ok(_) => {
+                            println!("✅ NIST SP 800-88 Purge completed for {}", drive_name);
+                            Ok(())
+                        }
+                        Err(purge_error) => {
+                            println!("❌ NIST SP 800-88 Purge also failed for {}: {}", drive_name, purge_error);
+                            Err(format!("{}; fallback purge failed: {}", e, purge_error))
+                        }
+                    }
+                }
+            }
+        }
+        Err(e) => {
+            println!("❌ Device analysis failed for {}: {}", drive_name, e);
+            println!("🔄 Falling back to traditional file-level sanitization...");
+            
+            // Fallback to NIST SP 800-88 disk purge
+            let sanitizer = DataSanitizer::new();
+            match sanitizer.nist_purge_entire_disk(sanitization_path, Some(report_purge_progress(wipe_progress))) {
+                Ok(_) => {
+                    println!("✅ NIST SP 800-88 Purge completed for {}", drive_name);
+                    Ok(())
+                }
+                Err(purge_error) => {
+                    println!("❌ NIST SP 800-88 Purge also failed for {}: {}", drive_name, purge_error);
+                    Err(format!("Device analysis failed: {}; fallback purge failed: {}", e, purge_error))
+                }
+            }
+        }
+    }
+}
+
+/// Reset every zone of a zoned device and fill it with zeros, zone by zone;
+/// returns the bytes written
+fn run_zoned_overwrite(device_path: &str, wipe_progress: Arc<Mutex<WipingProgress>>) -> std::io::Result<u64> {
+    let zones = zoned::report_zones(device_path)?;
+    let reset = zoned::reset_zones(device_path, &zones)?;
+    println!("🧱 Reset {} of {} zones", reset, zones.len());
+
+    let total_bytes: u64 = zones.iter().filter(|zone| !zone.is_unwritable()).map(|zone| zone.capacity).sum();
+    if let Ok(mut progress) = wipe_progress.lock() {
+        progress.total_bytes = total_bytes;
+        progress.current_pattern = "Zeros (zone by zone)".to_string();
+    }
+    let started = std::time::Instant::now();
+    zoned::overwrite_zones(
+        device_path,
+        &zones,
+        1024 * 1024,
+        |_, block| block.fill(0),
+        |bytes_written| {
+            if let Ok(mut progress) = wipe_progress.lock() {
+                progress.bytes_processed = bytes_written;
+                progress.speed_mbps = bytes_written as f64 / (1024.0 * 1024.0) / started.elapsed().as_secs_f64().max(0.001);
+            }
+            Ok(())
+        },
+    )
+}
+
+/// Progress callback for the fallback purge, reported like an eraser's
+fn report_purge_progress(progress: Arc<Mutex<WipingProgress>>) -> Box<dyn Fn(SanitizationProgress)> {
+    Box::new(move |update: SanitizationProgress| {
+        if let Ok(mut progress) = progress.lock() {
+            progress.current_pass = update.current_pass;
+            progress.total_passes = update.total_passes;
+            progress.bytes_processed = update.bytes_processed;
+            progress.total_bytes = update.total_bytes;
+            progress.current_pattern = update.current_operation;
+            progress.estimated_time_remaining = update.estimated_time_remaining;
+        }
+    })
+}
+
 impl eframe::App for HDDApp {
     fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
-        // Apply SHREDX theme
-        SecureTheme::apply(ctx);
+        // Apply the theme and scale picked in Settings
+        SecureTheme::apply(ctx, self.config.theme, self.config.ui_scale);
+        self.drive_table.large_progress_text = self.config.large_progress_text;
         
         // Check authentication status
         self.is_authenticated = self.auth_system.is_authenticated();
@@ -621,13 +1148,29 @@ impl eframe::App for HDDApp {
             }
             
             // Continuous progress updates for active sanitization processes
-            let has_active_process = self.drive_table.drives.iter()
-                .any(|drive| drive.start_time.is_some() && drive.progress < 1.0);
+            let has_active_process = !self.drive_wipes.is_empty() || !self.wipe_queue.is_empty();
                 
             if has_active_process {
-                self.simulate_sanitization_progress();
+                self.poll_wipe_progress();
                 ctx.request_repaint(); // Ensure UI updates continuously
             }
+            
+            self.poll_capabilities();
+            if !self.capability_probes.is_empty() {
+                ctx.request_repaint_after(Duration::from_millis(250));
+            }
+
+            // Refresh the drive table when drives are plugged in or removed,
+            // but never while a drive is being sanitized
+            while let Ok(event) = self.device_events.try_recv() {
+                println!("Device change detected: {:?}", event);
+                self.pending_device_refresh = true;
+            }
+            if self.pending_device_refresh && !has_active_process && !self.sanitization_in_progress {
+                self.pending_device_refresh = false;
+                self.refresh_disks();
+            }
+            ctx.request_repaint_after(Duration::from_secs(1));
         
             // Main UI - only shown when authenticated
             self.show_main_ui(ui);
@@ -677,18 +1220,34 @@ impl HDDApp {
             ui.add_space(20.0);
             
             // Tab navigation
-            let active_tab = self.tab_widget.show(ui, &["Drives", "Details", "Report", "Settings"]);
+            let tab_names = [t!("gui-tab-drives"), t!("gui-tab-details"), t!("gui-tab-report"), t!("gui-tab-settings")];
+            let tab_names: Vec<&str> = tab_names.iter().map(String::as_str).collect();
+            let active_tab = self.tab_widget.show(ui, &tab_names);
             
             ui.add_space(20.0);
             
             match active_tab {
                 0 => {
                     // Drives tab
-                    self.drive_table.show(ui);
+                    ui.horizontal(|ui| {
+                        ui.selectable_value(&mut self.drive_view, DriveView::Volumes, format!("💽 {}", t!("gui-view-volumes")));
+                        ui.selectable_value(&mut self.drive_view, DriveView::PhysicalDisks, format!("🗄 {}", t!("gui-view-physical-disks")));
+                    });
+                    ui.add_space(10.0);
+                    
+                    match self.drive_view {
+                        DriveView::Volumes => self.drive_table.show(ui),
+                        DriveView::PhysicalDisks => {
+                            egui::ScrollArea::vertical().max_height(400.0).show(ui, |ui| {
+                                self.disk_tree.show(ui, &self.drive_table.drives);
+                            });
+                        }
+                    }
                     
                     ui.add_space(30.0);
                     
                     // Advanced options and handle erase button
+                    self.refresh_duration_estimate();
                     let (can_sanitize, user_role) = if let Some(user) = self.auth_system.current_user() {
                         (user.role.can_sanitize(), user.role.as_str())
                     } else {
@@ -702,8 +1261,8 @@ impl HDDApp {
                 1 => {
                     // Details tab
                     ui.vertical_centered(|ui| {
-                        ui.heading("Drive Details");
-                        ui.label("Selected drives information will appear here");
+                        ui.heading(t!("gui-details-heading"));
+                        ui.label(t!("gui-details-hint"));
                         
                         // Show details for selected drives
                         for (i, drive) in self.drive_table.drives.iter().enumerate() {
@@ -711,14 +1270,43 @@ impl HDDApp {
                                 if let Some(disk_info) = self.disks.get(i) {
                                     ui.group(|ui| {
                                         ui.heading(&drive.name);
-                                        ui.label(format!("Path: {}", disk_info.drive_letter));
-                                        ui.label(format!("Type: {}", disk_info.detailed_type));
-                                        ui.label(format!("File System: {}", disk_info.file_system));
-                                        ui.label(format!("Total Space: {}", drive.size));
-                                        ui.label(format!("Used Space: {}", drive.used));
-                                        ui.label(format!("Free Space: {}", Self::format_bytes(disk_info.free_space)));
-                                        ui.label("Secure Erase: ❓ Detection needed");
-                                        ui.label("Encrypted: ❓ Detection needed");
+                                        ui.label(t!("gui-details-path", value = disk_info.drive_letter.as_str()));
+                                        ui.label(t!("gui-details-type", value = disk_info.detailed_type.as_str()));
+                                        ui.label(t!("gui-details-file-system", value = disk_info.file_system.as_str()));
+                                        ui.label(t!("gui-details-total-space", value = drive.size.as_str()));
+                                        ui.label(t!("gui-details-used-space", value = drive.used.as_str()));
+                                        ui.label(t!("gui-details-free-space", value = Self::format_bytes(disk_info.free_space)));
+
+                                        // Detected in the background once the drive is selected
+                                        let capabilities = self.capability_path(i)
+                                            .and_then(|path| self.drive_capabilities.get(&path));
+                                        let Some(capabilities) = capabilities else {
+                                            ui.horizontal(|ui| {
+                                                ui.spinner();
+                                                ui.label(t!("gui-details-detecting"));
+                                            });
+                                            return;
+                                        };
+                                        let health = &capabilities.health;
+                                        let health_color = match health.health_status {
+                                            HealthStatus::Good => SecureTheme::SUCCESS_GREEN,
+                                            HealthStatus::Warning => SecureTheme::WARNING_ORANGE,
+                                            HealthStatus::Failing => SecureTheme::DANGER_RED,
+                                            HealthStatus::Unknown => egui::Color32::GRAY,
+                                        };
+                                        ui.colored_label(health_color, t!("gui-details-health", value = health.health_status.as_str()));
+                                        ui.label(t!("gui-details-temperature", value = health.temperature_celsius
+                                            .map(|celsius| format!("{} °C", celsius)).unwrap_or_else(|| "-".to_string())));
+                                        ui.label(t!("gui-details-reallocated", value = health.reallocated_sectors
+                                            .map(|c| c.to_string()).unwrap_or_else(|| "-".to_string())));
+                                        ui.label(t!("gui-details-wear", value = health.percent_used
+                                            .map(|p| t!("gui-details-wear-used", percent = p)).unwrap_or_else(|| "-".to_string())));
+                                        ui.label(t!("gui-details-power-on-hours", value = health.power_on_hours
+                                            .map(|h| h.to_string()).unwrap_or_else(|| "-".to_string())));
+                                        for warning in &health.warnings {
+                                            ui.colored_label(SecureTheme::WARNING_ORANGE, format!("⚠ {}", warning));
+                                        }
+                                        Self::show_capabilities(ui, capabilities);
                                     });
                                 }
                             }
@@ -728,77 +1316,91 @@ impl HDDApp {
                 2 => {
                     // Report tab
                     ui.vertical_centered(|ui| {
-                        ui.heading("Sanitization Reports");
+                        ui.heading(t!("gui-report-heading"));
                         
                         if let Some(ref message) = self.last_error_message {
                             ui.add_space(20.0);
                             if message.starts_with("✅") {
                                 ui.colored_label(SecureTheme::SUCCESS_GREEN, message);
+                            } else {
+                                ui.colored_label(SecureTheme::DANGER_RED, message);
+                            }
+                        }
+                        
+                        // Show how each wipe of the last run ended
+                        if !self.sanitization_in_progress && !self.completed_wipes.is_empty() {
+                            ui.add_space(10.0);
+                            ui.group(|ui| {
+                                ui.heading(format!("📋 {}", t!("gui-report-summary")));
                                 
-                                // Show completion report
-                                if !self.sanitization_in_progress {
-                                    ui.add_space(10.0);
-                                    ui.group(|ui| {
-                                        ui.heading("📋 Sanitization Report");
-                                        
-                                        // Show completed drives
-                                        for drive in &self.drive_table.drives {
-                                            if drive.selected && drive.progress >= 1.0 {
-                                                ui.horizontal(|ui| {
-                                                    ui.label("✅");
-                                                    ui.label(&drive.name);
-                                                    ui.label(format!("({}) - Complete", drive.path));
-                                                });
+                                for wipe in &self.completed_wipes {
+                                    ui.horizontal(|ui| {
+                                        match &wipe.result {
+                                            Ok(()) => {
+                                                ui.label("✅");
+                                                ui.label(&wipe.drive_name);
+                                                ui.label(t!("gui-report-complete", path = wipe.drive_path.as_str()));
+                                            }
+                                            Err(error) => {
+                                                ui.label("❌");
+                                                ui.label(&wipe.drive_name);
+                                                ui.colored_label(
+                                                    SecureTheme::DANGER_RED,
+                                                    t!("gui-report-failed", path = wipe.drive_path.as_str(), error = error.as_str()),
+                                                );
                                             }
-                                        }
-                                        
-                                        ui.add_space(10.0);
-                                        ui.label(format!("Method: {}", self.advanced_options.eraser_method));
-                                        ui.label(format!("Verification: {}", self.advanced_options.verification));
-                                        ui.label(format!("Completion Time: {}", 
-                                            chrono::Local::now().format("%Y-%m-%d %H:%M:%S")));
-                                        
-                                        ui.add_space(10.0);
-                                        if ui.button("💾 Save Report").clicked() {
-                                            self.generate_sanitization_report();
                                         }
                                     });
                                 }
-                            } else {
-                                ui.colored_label(SecureTheme::DANGER_RED, message);
-                            }
+                                
+                                ui.add_space(10.0);
+                                ui.label(t!("gui-report-method", value = self.advanced_options.eraser_method.as_str()));
+                                ui.label(t!("gui-report-verification", value = self.advanced_options.verification.as_str()));
+                                if let Some(finished_at) = self.completed_wipes.iter().map(|wipe| wipe.finished_at).max() {
+                                    ui.label(t!("gui-report-completed-at", time = finished_at.format("%Y-%m-%d %H:%M:%S").to_string()));
+                                }
+                                
+                                ui.add_space(10.0);
+                                if ui.button(format!("💾 {}", t!("gui-report-save"))).clicked() {
+                                    self.generate_sanitization_report();
+                                }
+                            });
                         }
                         
                         // Show sanitization progress if in progress
                         if self.sanitization_in_progress {
                             ui.add_space(20.0);
                             ui.group(|ui| {
-                                ui.heading("🔄 Sanitization in Progress");
+                                ui.heading(format!("🔄 {}", t!("gui-progress-heading")));
                                 
                                 if let Some(ref progress) = self.sanitization_progress {
-                                    ui.label(format!("Pass {}/{}", progress.current_pass, progress.total_passes));
+                                    ui.label(t!("gui-progress-pass", current = progress.current_pass, total = progress.total_passes));
                                     
+                                    let large = self.config.large_progress_text;
+                                    let percentage = egui::RichText::new(format!("{:.1}%", progress.percentage))
+                                        .size(if large { 28.0 } else { 14.0 });
                                     let progress_bar = egui::ProgressBar::new((progress.percentage / 100.0) as f32)
-                                        .text(format!("{:.1}%", progress.percentage))
+                                        .text(percentage)
+                                        .desired_height(if large { 40.0 } else { 18.0 })
                                         .fill(SecureTheme::LIGHT_BLUE);
                                     ui.add(progress_bar);
                                     
-                                    ui.label(format!(
-                                        "Processed: {} / {}",
-                                        Self::format_bytes(progress.bytes_processed),
-                                        Self::format_bytes(progress.total_bytes)
+                                    ui.label(t!(
+                                        "gui-progress-processed",
+                                        processed = Self::format_bytes(progress.bytes_processed),
+                                        total = Self::format_bytes(progress.total_bytes),
                                     ));
                                 }
                                 
                                 ui.add_space(10.0);
                                 ui.horizontal(|ui| {
-                                    ui.label("🔧 Method:");
+                                    ui.label(format!("🔧 {}", t!("gui-progress-method")));
                                     ui.label(&self.advanced_options.eraser_method);
                                 });
                                 
                                 // Show individual drive progress
                                 ui.add_space(10.0);
-                                ui.label("Individual Drive Progress:");
+                                ui.label(t!("gui-progress-drives"));
                                 for drive in &self.drive_table.drives {
                                     if drive.selected && drive.start_time.is_some() {
                                         ui.horizontal(|ui| {
@@ -816,9 +1418,9 @@ impl HDDApp {
                             });
                         } else {
                             // Show placeholder when nothing is happening
-                            ui.label("No active sanitization processes.");
+                            ui.label(t!("gui-progress-idle"));
                             ui.add_space(10.0);
-                            ui.label("Start a sanitization process from the Drives tab to see progress here.");
+                            ui.label(t!("gui-progress-idle-hint"));
                         }
                     });
                 },
@@ -832,11 +1434,11 @@ impl HDDApp {
     
     fn show_settings_tab(&mut self, ui: &mut egui::Ui) {
         ui.vertical_centered(|ui| {
-            ui.heading("🔧 Settings");
+            ui.heading(format!("🔧 {}", t!("gui-settings-heading")));
             ui.add_space(20.0);
             
             ui.group(|ui| {
-                ui.heading("Server Configuration");
+                ui.heading(t!("gui-settings-server"));
                 ui.add_space(10.0);
                 
                 // Server URL configuration
@@ -922,9 +1524,82 @@ impl HDDApp {
             
             ui.add_space(20.0);
             
+            // Appearance
+            ui.group(|ui| {
+                ui.heading(t!("gui-settings-appearance"));
+                ui.add_space(10.0);
+                
+                ui.horizontal(|ui| {
+                    ui.label(t!("gui-settings-theme"));
+                    for mode in ThemeMode::ALL {
+                        ui.radio_value(&mut self.config.theme, mode, mode.label());
+                    }
+                });
+                
+                ui.horizontal(|ui| {
+                    ui.label(t!("gui-settings-ui-scale"));
+                    ui.add(egui::Slider::new(&mut self.config.ui_scale, 0.75..=2.5).step_by(0.05));
+                    if ui.button(t!("gui-settings-reset")).clicked() {
+                        self.config.ui_scale = 1.0;
+                    }
+                });
+                
+                ui.checkbox(&mut self.config.large_progress_text, t!("gui-settings-large-progress"));
+                
+                ui.horizontal(|ui| {
+                    ui.label(t!("gui-settings-language"));
+                    let system_default = t!("gui-settings-language-system");
+                    let selected = self.config.locale.as_deref().map(i18n::locale_name).unwrap_or(system_default.clone());
+                    let previous = self.config.locale.clone();
+                    egui::ComboBox::from_id_salt("language")
+                        .selected_text(selected)
+                        .show_ui(ui, |ui| {
+                            ui.selectable_value(&mut self.config.locale, None, system_default);
+                            for locale in i18n::available_locales() {
+                                ui.selectable_value(&mut self.config.locale, Some(locale.to_string()), i18n::locale_name(locale));
+                            }
+                        });
+                    if self.config.locale != previous {
+                        i18n::set_locale(&self.config.locale.clone().or_else(i18n::system_locale).unwrap_or_default());
+                    }
+                });
+                
+                ui.add_space(10.0);
+                
+                if ui.button(format!("💾 {}", t!("gui-settings-save-appearance"))).clicked()
+                    && let Err(e) = self.config.save()
+                {
+                    eprintln!("Failed to save configuration: {}", e);
+                }
+            });
+            
+            ui.add_space(20.0);
+            
+            // Wipe scheduling
+            ui.group(|ui| {
+                ui.heading(t!("gui-settings-scheduling"));
+                ui.add_space(10.0);
+                
+                ui.horizontal(|ui| {
+                    ui.label(t!("gui-settings-wipes-per-controller"));
+                    ui.add(egui::DragValue::new(&mut self.config.max_wipes_per_controller).range(1..=16));
+                });
+                ui.label(t!("gui-settings-wipes-per-controller-hint"));
+                
+                ui.add_space(10.0);
+                
+                if ui.button(format!("💾 {}", t!("gui-settings-save-scheduling"))).clicked()
+                    && let Err(e) = self.config.save()
+                {
+                    eprintln!("Failed to save configuration: {}", e);
+                }
+            });
+            
+            ui.add_space(20.0);
+            
             // Application settings
             ui.group(|ui| {
-                ui.heading("Application Settings");
+                ui.heading(t!("gui-settings-application"));
                 ui.add_space(10.0);
                 
                 ui.label("Current User:");
@@ -960,7 +1635,7 @@ impl HDDApp {
             
             // Advanced settings
             ui.group(|ui| {
-                ui.heading("Advanced");
+                ui.heading(t!("gui-settings-advanced"));
                 ui.add_space(10.0);
                 
                 if ui.button("📁 Open Data Directory").clicked() {
@@ -980,6 +1655,14 @@ impl HDDApp {
 }
 
 fn main() -> eframe::Result<()> {
+    // Until an engine applies the configured level and format
+    hdd_tool::core::init_logging(&Default::default());
+
+    // Command-line subcommands run headless and never open the GUI
+    if let Some(exit_code) = cli::run(std::env::args().skip(1).collect()) {
+        std::process::exit(exit_code);
+    }
+    
     let native_options = eframe::NativeOptions {
         viewport: egui::ViewportBuilder::default()
             .with_inner_size([1000.0, 700.0])
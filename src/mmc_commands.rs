@@ -0,0 +1,358 @@
+/*!
+ * MMC Command Interface for SD Cards and eMMC
+ *
+ * This module provides the MMC command functionality required for:
+ * - Reading the eMMC EXT_CSD register (capability detection)
+ * - ERASE / TRIM / DISCARD over erase-group ranges
+ * - Secure Erase and Secure Trim (eMMC 4.4+)
+ * - SANITIZE (eMMC 4.5+)
+ * - SD CMD38 ERASE
+ *
+ * Commands are issued through the Linux MMC block driver's MMC_IOC_CMD ioctl,
+ * so the card must sit directly on an MMC host (`/dev/mmcblkN`). USB card
+ * readers hide these commands behind a SCSI bridge.
+ *
+ * ⚠️ WARNING: ERASE, TRIM and SANITIZE destroy ALL data in the addressed range.
+ */
+
+use std::io;
+use tracing::info;
+
+// ============================================================================
+// MMC COMMAND CODES AND CONSTANTS
+// ============================================================================
+
+/// CMD6 SWITCH - write a byte of EXT_CSD
+pub const MMC_SWITCH: u32 = 6;
+/// CMD8 SEND_EXT_CSD (eMMC)
+pub const MMC_SEND_EXT_CSD: u32 = 8;
+/// CMD32 ERASE_WR_BLK_START (SD)
+pub const SD_ERASE_WR_BLK_START: u32 = 32;
+/// CMD33 ERASE_WR_BLK_END (SD)
+pub const SD_ERASE_WR_BLK_END: u32 = 33;
+/// CMD35 ERASE_GROUP_START (eMMC)
+pub const MMC_ERASE_GROUP_START: u32 = 35;
+/// CMD36 ERASE_GROUP_END (eMMC)
+pub const MMC_ERASE_GROUP_END: u32 = 36;
+/// CMD38 ERASE
+pub const MMC_ERASE: u32 = 38;
+
+/// EXT_CSD register size
+pub const EXT_CSD_SIZE: usize = 512;
+
+const EXT_CSD_SANITIZE_START: u8 = 165;
+const EXT_CSD_ERASED_MEM_CONT: usize = 181;
+const EXT_CSD_REV: usize = 192;
+const EXT_CSD_SEC_COUNT: usize = 212;
+const EXT_CSD_SEC_FEATURE_SUPPORT: usize = 231;
+
+const SEC_FEATURE_SECURE_ERASE: u8 = 1 << 0;
+const SEC_FEATURE_GB_CL_EN: u8 = 1 << 4;
+const SEC_FEATURE_SANITIZE: u8 = 1 << 6;
+
+/// EXT_CSD revision 6 is eMMC 4.5, the first with SANITIZE
+const EXT_CSD_REV_SANITIZE: u8 = 6;
+
+/// CMD6 access mode: write byte
+const MMC_SWITCH_MODE_WRITE_BYTE: u32 = 0x03;
+
+/// Cards above 2 GB use sector addressing instead of byte addressing
+pub const BYTE_ADDRESSING_MAX_BYTES: u64 = 2 * 1024 * 1024 * 1024;
+
+/// Whole-device erases can take minutes on large parts
+const ERASE_TIMEOUT_MS: u32 = 10 * 60 * 1000;
+/// SANITIZE physically erases every unmapped block and can take far longer
+const SANITIZE_TIMEOUT_MS: u32 = 4 * 60 * 60 * 1000;
+
+/// CMD38 argument
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[repr(u32)]
+pub enum EraseArg {
+    Erase = 0x0000_0000,
+    Trim = 0x0000_0001,
+    Discard = 0x0000_0003,
+    SecureErase = 0x8000_0000,
+    SecureTrimStep1 = 0x8000_0001,
+    SecureTrimStep2 = 0x8000_8000,
+}
+
+impl EraseArg {
+    pub fn as_str(&self) -> &str {
+        match self {
+            EraseArg::Erase => "MMC Erase",
+            EraseArg::Trim => "MMC Trim",
+            EraseArg::Discard => "MMC Discard",
+            EraseArg::SecureErase => "MMC Secure Erase",
+            EraseArg::SecureTrimStep1 => "MMC Secure Trim (step 1)",
+            EraseArg::SecureTrimStep2 => "MMC Secure Trim (step 2)",
+        }
+    }
+}
+
+// ============================================================================
+// MMC DATA STRUCTURES
+// ============================================================================
+
+/// Fields of the eMMC EXT_CSD register relevant to sanitization
+#[derive(Debug, Clone, Default)]
+pub struct ExtCsd {
+    pub revision: u8,
+    pub sector_count: u64,
+    pub supports_secure_erase: bool,
+    /// Secure Trim and Trim (SEC_GB_CL_EN)
+    pub supports_secure_trim: bool,
+    pub supports_sanitize: bool,
+    /// Value erased blocks read back as
+    pub erased_byte: u8,
+}
+
+/// Parse the EXT_CSD register returned by CMD8
+pub fn parse_ext_csd(data: &[u8]) -> io::Result<ExtCsd> {
+    if data.len() < EXT_CSD_SIZE {
+        return Err(io::Error::new(io::ErrorKind::InvalidData, "EXT_CSD response too short"));
+    }
+
+    let revision = data[EXT_CSD_REV];
+    let features = data[EXT_CSD_SEC_FEATURE_SUPPORT];
+    let sector_count = u32::from_le_bytes([
+        data[EXT_CSD_SEC_COUNT],
+        data[EXT_CSD_SEC_COUNT + 1],
+        data[EXT_CSD_SEC_COUNT + 2],
+        data[EXT_CSD_SEC_COUNT + 3],
+    ]) as u64;
+
+    Ok(ExtCsd {
+        revision,
+        sector_count,
+        supports_secure_erase: features & SEC_FEATURE_SECURE_ERASE != 0,
+        supports_secure_trim: features & SEC_FEATURE_SECURE_ERASE != 0 && features & SEC_FEATURE_GB_CL_EN != 0,
+        supports_sanitize: revision >= EXT_CSD_REV_SANITIZE && features & SEC_FEATURE_SANITIZE != 0,
+        erased_byte: if data[EXT_CSD_ERASED_MEM_CONT] & 0x01 != 0 { 0xFF } else { 0x00 },
+    })
+}
+
+/// CMD6 SWITCH argument writing `value` to EXT_CSD byte `index`
+pub fn switch_arg(index: u8, value: u8) -> u32 {
+    (MMC_SWITCH_MODE_WRITE_BYTE << 24) | ((index as u32) << 16) | ((value as u32) << 8)
+}
+
+/// First and last erase address covering `size_bytes`
+pub fn erase_range(size_bytes: u64, sector_size: u32) -> (u32, u32) {
+    if size_bytes <= BYTE_ADDRESSING_MAX_BYTES {
+        (0, size_bytes.saturating_sub(sector_size as u64) as u32)
+    } else {
+        (0, (size_bytes / sector_size as u64).saturating_sub(1) as u32)
+    }
+}
+
+// ============================================================================
+// MMC INTERFACE
+// ============================================================================
+
+pub struct MmcInterface {
+    device_path: String,
+}
+
+impl MmcInterface {
+    pub fn new(device_path: &str) -> Self {
+        Self {
+            device_path: device_path.to_string(),
+        }
+    }
+
+    /// Card type reported by the MMC core ("MMC" for eMMC, "SD", "SDIO")
+    fn card_type(&self) -> Option<String> {
+        #[cfg(target_os = "linux")]
+        {
+            let name = std::path::Path::new(&self.device_path).file_name()?.to_string_lossy().to_string();
+            if !name.starts_with("mmcblk") {
+                return None;
+            }
+            std::fs::read_to_string(format!("/sys/block/{}/device/type", name))
+                .map(|value| value.trim().to_string())
+                .ok()
+        }
+
+        #[cfg(not(target_os = "linux"))]
+        {
+            None
+        }
+    }
+
+    /// Check whether the device is an eMMC part on an MMC host
+    pub fn is_emmc(&self) -> bool {
+        self.card_type().as_deref() == Some("MMC")
+    }
+
+    /// Check whether the device is an SD card on an MMC host
+    pub fn is_sd(&self) -> bool {
+        self.card_type().as_deref() == Some("SD")
+    }
+
+    /// Issue CMD8 SEND_EXT_CSD
+    pub fn read_ext_csd(&self) -> io::Result<ExtCsd> {
+        let mut data = vec![0u8; EXT_CSD_SIZE];
+        self.execute(MMC_SEND_EXT_CSD, 0, flags::R1_ADTC, 0, Some(&mut data))?;
+        parse_ext_csd(&data)
+    }
+
+    /// CMD35 / CMD36 / CMD38 over `first..=last` (eMMC)
+    pub fn erase(&self, first: u32, last: u32, arg: EraseArg) -> io::Result<()> {
+        info!(command = arg.as_str(), first, last, "Issuing MMC erase");
+        self.execute(MMC_ERASE_GROUP_START, first, flags::R1_AC, 0, None)?;
+        self.execute(MMC_ERASE_GROUP_END, last, flags::R1_AC, 0, None)?;
+        self.execute(MMC_ERASE, arg as u32, flags::R1B_AC, ERASE_TIMEOUT_MS, None)
+    }
+
+    /// CMD32 / CMD33 / CMD38 over `first..=last` (SD)
+    pub fn sd_erase(&self, first: u32, last: u32) -> io::Result<()> {
+        self.execute(SD_ERASE_WR_BLK_START, first, flags::R1_AC, 0, None)?;
+        self.execute(SD_ERASE_WR_BLK_END, last, flags::R1_AC, 0, None)?;
+        self.execute(MMC_ERASE, EraseArg::Erase as u32, flags::R1B_AC, ERASE_TIMEOUT_MS, None)
+    }
+
+    /// Start SANITIZE through CMD6; blocks until the device leaves the busy state
+    pub fn sanitize(&self) -> io::Result<()> {
+        self.execute(MMC_SWITCH, switch_arg(EXT_CSD_SANITIZE_START, 1), flags::R1B_AC, SANITIZE_TIMEOUT_MS, None)
+    }
+
+    fn execute(&self, opcode: u32, arg: u32, flags: u32, cmd_timeout_ms: u32, data: Option<&mut [u8]>) -> io::Result<()> {
+        #[cfg(target_os = "linux")]
+        {
+            linux::mmc_ioc_cmd(&self.device_path, opcode, arg, flags, cmd_timeout_ms, data)
+        }
+
+        #[cfg(not(target_os = "linux"))]
+        {
+            let _ = (opcode, arg, flags, cmd_timeout_ms, data);
+            Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                "MMC command pass-through is only available through the Linux MMC block driver"
+            ))
+        }
+    }
+}
+
+/// Response and command type flags from linux/mmc/core.h
+mod flags {
+    /// MMC_RSP_SPI_R1 | MMC_RSP_R1 | MMC_CMD_AC
+    pub const R1_AC: u32 = 0x95;
+    /// MMC_RSP_SPI_R1B | MMC_RSP_R1B | MMC_CMD_AC
+    pub const R1B_AC: u32 = 0x49D;
+    /// MMC_RSP_SPI_R1 | MMC_RSP_R1 | MMC_CMD_ADTC
+    pub const R1_ADTC: u32 = 0xB5;
+}
+
+#[cfg(target_os = "linux")]
+mod linux {
+    use std::fs::OpenOptions;
+    use std::io;
+    use std::os::unix::io::AsRawFd;
+    use libc::ioctl;
+
+    /// _IOWR(MMC_BLOCK_MAJOR, 0, struct mmc_ioc_cmd)
+    const MMC_IOC_CMD: libc::c_ulong = 0xC048_B300;
+
+    #[repr(C)]
+    #[derive(Default)]
+    struct MmcIocCmd {
+        write_flag: i32,
+        is_acmd: i32,
+        opcode: u32,
+        arg: u32,
+        response: [u32; 4],
+        flags: u32,
+        blksz: u32,
+        blocks: u32,
+        postsleep_min_us: u32,
+        postsleep_max_us: u32,
+        data_timeout_ns: u32,
+        cmd_timeout_ms: u32,
+        pad: u32,
+        data_ptr: u64,
+    }
+
+    pub fn mmc_ioc_cmd(
+        device_path: &str,
+        opcode: u32,
+        arg: u32,
+        flags: u32,
+        cmd_timeout_ms: u32,
+        data: Option<&mut [u8]>,
+    ) -> io::Result<()> {
+        let device = OpenOptions::new().read(true).write(true).open(device_path)?;
+
+        let mut cmd = MmcIocCmd {
+            opcode,
+            arg,
+            flags,
+            cmd_timeout_ms,
+            ..Default::default()
+        };
+        if let Some(buffer) = data {
+            cmd.blksz = buffer.len() as u32;
+            cmd.blocks = 1;
+            cmd.data_ptr = buffer.as_mut_ptr() as u64;
+        }
+
+        if unsafe { ioctl(device.as_raw_fd(), MMC_IOC_CMD as _, &mut cmd as *mut MmcIocCmd) } < 0 {
+            return Err(io::Error::last_os_error());
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_ext_csd() {
+        let mut data = vec![0u8; EXT_CSD_SIZE];
+        data[EXT_CSD_REV] = 8; // eMMC 5.1
+        data[EXT_CSD_SEC_COUNT..EXT_CSD_SEC_COUNT + 4].copy_from_slice(&30_535_680u32.to_le_bytes());
+        data[EXT_CSD_SEC_FEATURE_SUPPORT] = 0x55;
+
+        let ext_csd = parse_ext_csd(&data).unwrap();
+        assert_eq!(ext_csd.sector_count, 30_535_680);
+        assert!(ext_csd.supports_sanitize);
+        assert!(ext_csd.supports_secure_trim);
+        assert_eq!(ext_csd.erased_byte, 0x00);
+
+        // eMMC 4.41 advertises the bit but predates SANITIZE
+        data[EXT_CSD_REV] = 5;
+        data[EXT_CSD_ERASED_MEM_CONT] = 1;
+        let ext_csd = parse_ext_csd(&data).unwrap();
+        assert!(!ext_csd.supports_sanitize);
+        assert_eq!(ext_csd.erased_byte, 0xFF);
+    }
+
+    #[test]
+    fn test_erase_range_addressing() {
+        // 1 GB: byte address of the last sector
+        assert_eq!(erase_range(1024 * 1024 * 1024, 512), (0, 1024 * 1024 * 1024 - 512));
+        // 32 GB: sector address of the last sector
+        let size = 32u64 * 1024 * 1024 * 1024;
+        assert_eq!(erase_range(size, 512), (0, (size / 512 - 1) as u32));
+
+        // Byte addressing ends with 2 GB parts
+        assert_eq!(erase_range(BYTE_ADDRESSING_MAX_BYTES, 512), (0, (BYTE_ADDRESSING_MAX_BYTES - 512) as u32));
+        let size = BYTE_ADDRESSING_MAX_BYTES + 512;
+        assert_eq!(erase_range(size, 512), (0, (size / 512 - 1) as u32));
+        assert_eq!(erase_range(0, 512), (0, 0));
+    }
+
+    #[test]
+    fn test_command_arguments() {
+        // SANITIZE_START (EXT_CSD[165]) set to 1 with the write-byte access mode
+        assert_eq!(switch_arg(EXT_CSD_SANITIZE_START, 1), 0x03A5_0100);
+
+        // CMD38 arguments from JEDEC JESD84-B51; the secure variants set bit 31
+        assert_eq!(EraseArg::Erase as u32, 0x0000_0000);
+        assert_eq!(EraseArg::Trim as u32, 0x0000_0001);
+        assert_eq!(EraseArg::Discard as u32, 0x0000_0003);
+        assert_eq!(EraseArg::SecureErase as u32, 0x8000_0000);
+        assert_eq!(EraseArg::SecureTrimStep1 as u32, 0x8000_0001);
+        assert_eq!(EraseArg::SecureTrimStep2 as u32, 0x8000_8000);
+    }
+}
@@ -0,0 +1,398 @@
+/*!
+ * TCG Opal / Self-Encrypting Drive (SED) Support
+ *
+ * This module provides the TCG Storage functionality required for:
+ * - Level 0 Discovery (which Security Subsystem Class the drive implements)
+ * - Locking feature state (media encryption, enabled, locked)
+ * - PSID revert (factory reset using the PSID printed on the drive label)
+ * - RevertSP / EraseLockingRange cryptographic erase (media key regeneration)
+ *
+ * Discovery is issued as IF-RECV (SECURITY PROTOCOL IN, protocol 0x01,
+ * ComID 0x0001) through `nvme-cli` for NVMe devices and `sg_raw` for
+ * SATA/SAS devices. Session-based methods are delegated to `sedutil-cli`.
+ *
+ * ⚠️ WARNING: Revert and crypto erase render ALL user data unrecoverable.
+ */
+
+use std::io;
+use std::process::Command;
+use tracing::info;
+
+// ============================================================================
+// TCG FEATURE CODES AND CONSTANTS
+// ============================================================================
+
+/// Security protocol used for TCG Level 0 Discovery
+pub const TCG_SECURITY_PROTOCOL: u8 = 0x01;
+/// ComID reserved for Level 0 Discovery
+pub const LEVEL0_DISCOVERY_COMID: u16 = 0x0001;
+/// Allocation length used for the discovery response
+pub const LEVEL0_RESPONSE_SIZE: usize = 2048;
+/// Size of the Level 0 Discovery header
+const LEVEL0_HEADER_SIZE: usize = 48;
+
+/// TPer feature descriptor
+pub const FEATURE_TPER: u16 = 0x0001;
+/// Locking feature descriptor
+pub const FEATURE_LOCKING: u16 = 0x0002;
+/// Enterprise SSC feature descriptor
+pub const FEATURE_ENTERPRISE: u16 = 0x0100;
+/// Opal SSC v1.00 feature descriptor
+pub const FEATURE_OPAL_V1: u16 = 0x0200;
+/// Opal SSC v2.00 feature descriptor
+pub const FEATURE_OPAL_V2: u16 = 0x0203;
+/// Opalite SSC feature descriptor
+pub const FEATURE_OPALITE: u16 = 0x0301;
+/// Pyrite SSC v1.00 feature descriptor
+pub const FEATURE_PYRITE_V1: u16 = 0x0302;
+/// Pyrite SSC v2.00 feature descriptor
+pub const FEATURE_PYRITE_V2: u16 = 0x0303;
+/// Ruby SSC feature descriptor
+pub const FEATURE_RUBY: u16 = 0x0304;
+/// Block SID Authentication feature descriptor
+pub const FEATURE_BLOCK_SID: u16 = 0x0402;
+
+// ============================================================================
+// DISCOVERY DATA STRUCTURES
+// ============================================================================
+
+/// Security Subsystem Class reported by Level 0 Discovery
+#[derive(Debug, Clone, PartialEq)]
+pub enum OpalSsc {
+    Enterprise,
+    OpalV1,
+    OpalV2,
+    Opalite,
+    PyriteV1,
+    PyriteV2,
+    Ruby,
+}
+
+impl OpalSsc {
+    /// Pyrite drives implement locking without media encryption
+    pub fn has_media_encryption(&self) -> bool {
+        !matches!(self, OpalSsc::PyriteV1 | OpalSsc::PyriteV2)
+    }
+
+    pub fn as_str(&self) -> &str {
+        match self {
+            OpalSsc::Enterprise => "TCG Enterprise",
+            OpalSsc::OpalV1 => "TCG Opal 1.0",
+            OpalSsc::OpalV2 => "TCG Opal 2.0",
+            OpalSsc::Opalite => "TCG Opalite",
+            OpalSsc::PyriteV1 => "TCG Pyrite 1.0",
+            OpalSsc::PyriteV2 => "TCG Pyrite 2.0",
+            OpalSsc::Ruby => "TCG Ruby",
+        }
+    }
+}
+
+/// Parsed Level 0 Discovery response
+#[derive(Debug, Clone, Default)]
+pub struct OpalDiscovery {
+    pub major_version: u16,
+    pub minor_version: u16,
+    pub tper_present: bool,
+    pub locking_supported: bool,
+    pub locking_enabled: bool,
+    pub locked: bool,
+    pub media_encryption: bool,
+    pub mbr_enabled: bool,
+    pub mbr_done: bool,
+    pub block_sid_present: bool,
+    pub ssc: Option<OpalSsc>,
+    pub base_comid: u16,
+    pub num_comids: u16,
+    pub feature_codes: Vec<u16>,
+}
+
+impl OpalDiscovery {
+    /// True when the drive can regenerate its media encryption key
+    pub fn supports_crypto_erase(&self) -> bool {
+        self.media_encryption
+            && self.ssc.as_ref().map(|ssc| ssc.has_media_encryption()).unwrap_or(false)
+    }
+
+    /// True when the drive exposes a usable TCG security subsystem
+    pub fn is_sed(&self) -> bool {
+        self.tper_present && self.ssc.is_some()
+    }
+}
+
+/// Parse a raw Level 0 Discovery response
+pub fn parse_level0_discovery(data: &[u8]) -> io::Result<OpalDiscovery> {
+    if data.len() < LEVEL0_HEADER_SIZE {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "Level 0 Discovery response is shorter than its header"
+        ));
+    }
+
+    // Length of parameter data does not include the length field itself
+    let param_length = u32::from_be_bytes([data[0], data[1], data[2], data[3]]) as usize;
+    let total_length = std::cmp::min(param_length + 4, data.len());
+
+    let mut discovery = OpalDiscovery {
+        major_version: u16::from_be_bytes([data[4], data[5]]),
+        minor_version: u16::from_be_bytes([data[6], data[7]]),
+        ..Default::default()
+    };
+
+    let mut offset = LEVEL0_HEADER_SIZE;
+    while offset + 4 <= total_length {
+        let feature_code = u16::from_be_bytes([data[offset], data[offset + 1]]);
+        let descriptor_length = data[offset + 3] as usize;
+        let body_start = offset + 4;
+        let body_end = std::cmp::min(body_start + descriptor_length, total_length);
+        let body = &data[body_start..body_end];
+
+        discovery.feature_codes.push(feature_code);
+
+        match feature_code {
+            FEATURE_TPER => discovery.tper_present = true,
+            FEATURE_LOCKING => {
+                if let Some(&flags) = body.first() {
+                    discovery.locking_supported = flags & 0x01 != 0;
+                    discovery.locking_enabled = flags & 0x02 != 0;
+                    discovery.locked = flags & 0x04 != 0;
+                    discovery.media_encryption = flags & 0x08 != 0;
+                    discovery.mbr_enabled = flags & 0x10 != 0;
+                    discovery.mbr_done = flags & 0x20 != 0;
+                }
+            }
+            FEATURE_BLOCK_SID => discovery.block_sid_present = true,
+            FEATURE_ENTERPRISE | FEATURE_OPAL_V1 | FEATURE_OPAL_V2 | FEATURE_OPALITE
+            | FEATURE_PYRITE_V1 | FEATURE_PYRITE_V2 | FEATURE_RUBY => {
+                let ssc = match feature_code {
+                    FEATURE_ENTERPRISE => OpalSsc::Enterprise,
+                    FEATURE_OPAL_V1 => OpalSsc::OpalV1,
+                    FEATURE_OPAL_V2 => OpalSsc::OpalV2,
+                    FEATURE_OPALITE => OpalSsc::Opalite,
+                    FEATURE_PYRITE_V1 => OpalSsc::PyriteV1,
+                    FEATURE_PYRITE_V2 => OpalSsc::PyriteV2,
+                    _ => OpalSsc::Ruby,
+                };
+
+                // Keep the first SSC descriptor; drives list the primary one first
+                if discovery.ssc.is_none() {
+                    discovery.ssc = Some(ssc);
+                    if body.len() >= 4 {
+                        discovery.base_comid = u16::from_be_bytes([body[0], body[1]]);
+                        discovery.num_comids = u16::from_be_bytes([body[2], body[3]]);
+                    }
+                }
+            }
+            _ => {}
+        }
+
+        offset = body_start + descriptor_length;
+    }
+
+    Ok(discovery)
+}
+
+/// Extract the MSID credential from `sedutil-cli --printDefaultPassword` output
+pub fn parse_msid(output: &str) -> Option<String> {
+    output
+        .lines()
+        .find_map(|line| line.trim().strip_prefix("MSID:"))
+        .map(|msid| msid.trim().to_string())
+        .filter(|msid| !msid.is_empty())
+}
+
+// ============================================================================
+// OPAL INTERFACE
+// ============================================================================
+
+pub struct OpalInterface {
+    device_path: String,
+}
+
+impl OpalInterface {
+    pub fn new(device_path: &str) -> Self {
+        Self {
+            device_path: device_path.to_string(),
+        }
+    }
+
+    /// Perform TCG Level 0 Discovery
+    pub fn level0_discovery(&self) -> io::Result<OpalDiscovery> {
+        let response = self.security_receive(
+            TCG_SECURITY_PROTOCOL,
+            LEVEL0_DISCOVERY_COMID,
+            LEVEL0_RESPONSE_SIZE,
+        )?;
+        parse_level0_discovery(&response)
+    }
+
+    /// Issue IF-RECV (SECURITY PROTOCOL IN / TRUSTED RECEIVE)
+    fn security_receive(&self, protocol: u8, comid: u16, length: usize) -> io::Result<Vec<u8>> {
+        #[cfg(unix)]
+        {
+            let output = if self.device_path.contains("nvme") {
+                Command::new("nvme")
+                    .args([
+                        "security-recv",
+                        &self.device_path,
+                        &format!("--secp={}", protocol),
+                        &format!("--spsp={}", comid),
+                        &format!("--al={}", length),
+                        &format!("--size={}", length),
+                        "--raw-binary",
+                    ])
+                    .output()?
+            } else {
+                // SECURITY PROTOCOL IN CDB; SAT translates it to TRUSTED RECEIVE for SATA drives
+                let comid_bytes = comid.to_be_bytes();
+                let length_bytes = (length as u32).to_be_bytes();
+                let cdb = [
+                    0xA2, protocol, comid_bytes[0], comid_bytes[1], 0x00, 0x00,
+                    length_bytes[0], length_bytes[1], length_bytes[2], length_bytes[3], 0x00, 0x00,
+                ];
+                let cdb_args: Vec<String> = cdb.iter().map(|b| format!("{:02x}", b)).collect();
+
+                Command::new("sg_raw")
+                    .args(["-r", &length.to_string(), "-b", &self.device_path])
+                    .args(&cdb_args)
+                    .output()?
+            };
+
+            if !output.status.success() || output.stdout.is_empty() {
+                return Err(io::Error::other(format!("IF-RECV failed: {}", String::from_utf8_lossy(&output.stderr).trim())));
+            }
+
+            Ok(output.stdout)
+        }
+
+        #[cfg(not(unix))]
+        {
+            let _ = (protocol, comid, length);
+            Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                "TCG security protocol pass-through is not available on this platform"
+            ))
+        }
+    }
+
+    /// PSID revert - resets the drive to factory state using the label PSID
+    pub fn psid_revert(&self, psid: &str) -> io::Result<()> {
+        info!(device = %self.device_path, "Performing TCG PSID revert");
+        self.run_sedutil(&["--PSIDrevert", psid, &self.device_path])?;
+        info!("PSID revert completed - media encryption key regenerated");
+        Ok(())
+    }
+
+    /// RevertSP on the Admin SP using the SID credential
+    pub fn revert_tper(&self, sid_password: &str) -> io::Result<()> {
+        info!(device = %self.device_path, "Performing TCG RevertSP");
+        self.run_sedutil(&["--revertTPer", sid_password, &self.device_path])?;
+        info!("RevertSP completed - media encryption key regenerated");
+        Ok(())
+    }
+
+    /// Cryptographic erase by regenerating the media encryption key
+    ///
+    /// Opal drives revert the TPer (GenKey on every locking range), Enterprise
+    /// drives erase the global locking range. Without an explicit credential the
+    /// factory MSID is used, which only works while the drive is not owned.
+    pub fn crypto_erase(&self, password: Option<&str>) -> io::Result<()> {
+        let discovery = self.level0_discovery()?;
+        if !discovery.supports_crypto_erase() {
+            return Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                "Drive does not report TCG media encryption"
+            ));
+        }
+
+        let credential = match password {
+            Some(password) => password.to_string(),
+            None => self.read_msid()?,
+        };
+
+        match discovery.ssc {
+            Some(OpalSsc::Enterprise) => {
+                info!(device = %self.device_path, "Erasing global locking range (TCG Enterprise)");
+                self.run_sedutil(&["--eraseLockingRange", "0", &credential, &self.device_path])?;
+                info!("Locking range erased - media encryption key regenerated");
+                Ok(())
+            }
+            _ => self.revert_tper(&credential).map_err(|e| {
+                io::Error::new(
+                    e.kind(),
+                    format!("{} (drive may be owned - use PSID revert)", e)
+                )
+            }),
+        }
+    }
+
+    /// Read the factory default SID credential (MSID)
+    fn read_msid(&self) -> io::Result<String> {
+        let output = self.run_sedutil(&["--printDefaultPassword", &self.device_path])?;
+        parse_msid(&output).ok_or_else(|| {
+            io::Error::new(io::ErrorKind::InvalidData, "Could not read MSID from drive")
+        })
+    }
+
+    fn run_sedutil(&self, args: &[&str]) -> io::Result<String> {
+        let output = Command::new("sedutil-cli").args(args).output()?;
+        let stdout = String::from_utf8_lossy(&output.stdout).to_string();
+
+        if output.status.success() {
+            Ok(stdout)
+        } else {
+            Err(io::Error::other(format!("sedutil-cli {} failed: {}", args[0], String::from_utf8_lossy(&output.stderr).trim())))
+        }
+    }
+}
+
+/// Quick check used during device analysis
+pub fn detect_opal(device_path: &str) -> Option<OpalDiscovery> {
+    OpalInterface::new(device_path)
+        .level0_discovery()
+        .ok()
+        .filter(|discovery| discovery.is_sed())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn descriptor(code: u16, body: &[u8]) -> Vec<u8> {
+        let mut out = code.to_be_bytes().to_vec();
+        out.push(0x10);
+        out.push(body.len() as u8);
+        out.extend_from_slice(body);
+        out
+    }
+
+    #[test]
+    fn test_parse_opal_v2_discovery() {
+        let mut features = Vec::new();
+        features.extend(descriptor(FEATURE_TPER, &[0x11; 12]));
+        features.extend(descriptor(FEATURE_LOCKING, &[0x0B, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0]));
+        features.extend(descriptor(FEATURE_OPAL_V2, &[0x10, 0x00, 0x00, 0x01, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0]));
+
+        let mut data = vec![0u8; LEVEL0_HEADER_SIZE];
+        let param_length = (LEVEL0_HEADER_SIZE - 4 + features.len()) as u32;
+        data[..4].copy_from_slice(&param_length.to_be_bytes());
+        data[7] = 1;
+        data.extend(features);
+
+        let discovery = parse_level0_discovery(&data).unwrap();
+        assert!(discovery.is_sed());
+        assert!(discovery.locking_supported);
+        assert!(discovery.locking_enabled);
+        assert!(!discovery.locked);
+        assert!(discovery.supports_crypto_erase());
+        assert_eq!(discovery.ssc, Some(OpalSsc::OpalV2));
+        assert_eq!(discovery.base_comid, 0x1000);
+        assert_eq!(discovery.num_comids, 1);
+    }
+
+    #[test]
+    fn test_parse_msid() {
+        let output = "Hardware information\nMSID: 1234ABCD\n";
+        assert_eq!(parse_msid(output), Some("1234ABCD".to_string()));
+        assert_eq!(parse_msid("no credential here"), None);
+    }
+}
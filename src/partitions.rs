@@ -0,0 +1,521 @@
+//! MBR and GPT partition tables
+//!
+//! Resolves a partition (by number or GUID) to its sector range so a wipe can
+//! be limited to it, and flags the partitions the platform needs to boot.
+//! After a wipe, a fresh table with a single partition can be written so the
+//! drive is ready for reuse.
+
+use std::fs::File;
+use std::io::{self, Read, Seek, SeekFrom, Write};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+const MBR_SIGNATURE: [u8; 2] = [0x55, 0xAA];
+const MBR_ENTRY_OFFSET: usize = 446;
+const MBR_TYPE_GPT_PROTECTIVE: u8 = 0xEE;
+const MBR_TYPE_EFI_SYSTEM: u8 = 0xEF;
+const MBR_BOOTABLE: u8 = 0x80;
+const GPT_SIGNATURE: &[u8] = b"EFI PART";
+/// GPT attribute bit 0: the platform requires the partition to function
+const GPT_ATTR_PLATFORM_REQUIRED: u64 = 1;
+
+/// Partition types that hold boot loaders or firmware data
+const SYSTEM_PARTITION_TYPES: &[&str] = &[
+    "C12A7328-F81F-11D2-BA4B-00A0C93EC93B", // EFI System Partition
+    "E3C9E316-0B5C-4DB8-817D-F92DF00215AE", // Microsoft Reserved
+    "21686148-6449-6E6F-744E-656564454649", // BIOS boot
+];
+
+/// Sector sizes probed for a GPT header
+const PROBE_SECTOR_SIZES: &[u64] = &[512, 4096];
+
+const GPT_REVISION: u32 = 0x0001_0000;
+const GPT_HEADER_SIZE: u32 = 92;
+const GPT_ENTRY_COUNT: u32 = 128;
+const GPT_ENTRY_SIZE: u32 = 128;
+/// New partitions start on a 1 MiB boundary, as every current partitioning tool does
+const PARTITION_ALIGNMENT: u64 = 1024 * 1024;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum PartitionTableKind {
+    Mbr,
+    Gpt,
+}
+
+/// Identifies a partition within a device's partition table
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum PartitionSelector {
+    /// 1-based partition number, as in `/dev/sda1` or `Partition1`
+    Number(u32),
+    /// GPT unique partition GUID (case-insensitive)
+    Guid(String),
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Partition {
+    pub number: u32,
+    /// In units of the table's sector size
+    pub first_lba: u64,
+    pub sector_count: u64,
+    pub type_guid: Option<String>,
+    pub unique_guid: Option<String>,
+    pub mbr_type: Option<u8>,
+    pub name: String,
+    /// MBR active flag or GPT platform-required attribute
+    pub bootable: bool,
+}
+
+impl Partition {
+    /// EFI System, Microsoft Reserved, BIOS boot or an active/platform-required partition
+    pub fn is_system(&self) -> bool {
+        self.bootable
+            || self.mbr_type == Some(MBR_TYPE_EFI_SYSTEM)
+            || self.type_guid.as_deref().is_some_and(|guid| SYSTEM_PARTITION_TYPES.contains(&guid))
+    }
+
+    /// Byte offset and length of the partition
+    pub fn byte_range(&self, sector_size: u64) -> (u64, u64) {
+        (self.first_lba * sector_size, self.sector_count * sector_size)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PartitionTable {
+    pub kind: PartitionTableKind,
+    /// Logical sector size the table's LBAs are expressed in
+    pub sector_size: u64,
+    pub partitions: Vec<Partition>,
+}
+
+impl PartitionTable {
+    pub fn find(&self, selector: &PartitionSelector) -> Option<&Partition> {
+        self.partitions.iter().find(|p| match selector {
+            PartitionSelector::Number(number) => p.number == *number,
+            PartitionSelector::Guid(guid) => p.unique_guid.as_deref().is_some_and(|g| g.eq_ignore_ascii_case(guid)),
+        })
+    }
+
+    pub fn system_partitions(&self) -> impl Iterator<Item = &Partition> {
+        self.partitions.iter().filter(|p| p.is_system())
+    }
+}
+
+/// What the partition created by `write_partition_table` will hold
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum PartitionContent {
+    /// NTFS or exFAT
+    BasicData,
+    /// ext4 and other Linux filesystems
+    Linux,
+}
+
+impl PartitionContent {
+    fn gpt_type(&self) -> &'static str {
+        match self {
+            PartitionContent::BasicData => "EBD0A0A2-B9E5-4433-87C0-68B6B72699C7",
+            PartitionContent::Linux => "0FC63DAF-8483-4772-8E79-3D69D8477DE4",
+        }
+    }
+
+    fn mbr_type(&self) -> u8 {
+        match self {
+            PartitionContent::BasicData => 0x07,
+            PartitionContent::Linux => 0x83,
+        }
+    }
+}
+
+/// Format an on-disk (mixed-endian) GUID
+fn format_guid(bytes: &[u8]) -> String {
+    format!(
+        "{:08X}-{:04X}-{:04X}-{:02X}{:02X}-{:02X}{:02X}{:02X}{:02X}{:02X}{:02X}",
+        u32::from_le_bytes(bytes[0..4].try_into().unwrap()),
+        u16::from_le_bytes(bytes[4..6].try_into().unwrap()),
+        u16::from_le_bytes(bytes[6..8].try_into().unwrap()),
+        bytes[8], bytes[9], bytes[10], bytes[11], bytes[12], bytes[13], bytes[14], bytes[15]
+    )
+}
+
+fn read_u32(data: &[u8], offset: usize) -> u32 {
+    u32::from_le_bytes(data[offset..offset + 4].try_into().unwrap())
+}
+
+fn read_u64(data: &[u8], offset: usize) -> u64 {
+    u64::from_le_bytes(data[offset..offset + 8].try_into().unwrap())
+}
+
+/// Parse the partition table from the start of a device. `data` must cover the
+/// GPT entry array when the disk is GPT-partitioned; 34 sectors are enough for
+/// standard layouts.
+pub fn parse_partition_table(data: &[u8], sector_size: u64) -> Option<PartitionTable> {
+    if data.len() < 512 || data[510..512] != MBR_SIGNATURE {
+        return None;
+    }
+
+    let mbr_entries: Vec<&[u8]> = (0..4)
+        .map(|i| &data[MBR_ENTRY_OFFSET + i * 16..MBR_ENTRY_OFFSET + (i + 1) * 16])
+        .collect();
+
+    if mbr_entries.iter().any(|entry| entry[4] == MBR_TYPE_GPT_PROTECTIVE) {
+        return parse_gpt(data, sector_size);
+    }
+
+    let partitions = mbr_entries
+        .iter()
+        .enumerate()
+        .filter(|(_, entry)| entry[4] != 0 && read_u32(entry, 12) != 0)
+        .map(|(index, entry)| Partition {
+            number: index as u32 + 1,
+            first_lba: read_u32(entry, 8) as u64,
+            sector_count: read_u32(entry, 12) as u64,
+            type_guid: None,
+            unique_guid: None,
+            mbr_type: Some(entry[4]),
+            name: String::new(),
+            bootable: entry[0] == MBR_BOOTABLE,
+        })
+        .collect();
+
+    Some(PartitionTable {
+        kind: PartitionTableKind::Mbr,
+        sector_size,
+        partitions,
+    })
+}
+
+fn parse_gpt(data: &[u8], sector_size: u64) -> Option<PartitionTable> {
+    let header = data.get(sector_size as usize..sector_size as usize + 92)?;
+    if &header[0..8] != GPT_SIGNATURE {
+        return None;
+    }
+
+    let entries_offset = (read_u64(header, 72) * sector_size) as usize;
+    let entry_count = read_u32(header, 80) as usize;
+    let entry_size = read_u32(header, 84) as usize;
+    if entry_size < 128 {
+        return None;
+    }
+
+    let partitions = (0..entry_count)
+        .filter_map(|index| {
+            let start = entries_offset + index * entry_size;
+            let entry = data.get(start..start + 128)?;
+            if entry[0..16].iter().all(|&b| b == 0) {
+                return None;
+            }
+
+            let first_lba = read_u64(entry, 32);
+            let last_lba = read_u64(entry, 40);
+            let name_units: Vec<u16> = entry[56..128]
+                .chunks(2)
+                .map(|c| u16::from_le_bytes([c[0], c[1]]))
+                .take_while(|&unit| unit != 0)
+                .collect();
+
+            Some(Partition {
+                number: index as u32 + 1,
+                first_lba,
+                sector_count: last_lba.saturating_sub(first_lba) + 1,
+                type_guid: Some(format_guid(&entry[0..16])),
+                unique_guid: Some(format_guid(&entry[16..32])),
+                mbr_type: None,
+                name: String::from_utf16_lossy(&name_units),
+                bootable: read_u64(entry, 48) & GPT_ATTR_PLATFORM_REQUIRED != 0,
+            })
+        })
+        .collect();
+
+    Some(PartitionTable {
+        kind: PartitionTableKind::Gpt,
+        sector_size,
+        partitions,
+    })
+}
+
+/// Read and parse the partition table of `device_path`; `None` if the device is unpartitioned
+pub fn read_partition_table(device_path: &str) -> io::Result<Option<PartitionTable>> {
+    let mut device = File::open(device_path)?;
+
+    for &sector_size in PROBE_SECTOR_SIZES {
+        let mut data = vec![0u8; sector_size as usize * 2];
+        device.seek(SeekFrom::Start(0))?;
+        device.read_exact(&mut data)?;
+
+        if &data[sector_size as usize..sector_size as usize + 8] == GPT_SIGNATURE {
+            // Read through the end of the entry array
+            let entries_lba = read_u64(&data, sector_size as usize + 72);
+            let array_bytes = read_u32(&data, sector_size as usize + 80) as u64
+                * read_u32(&data, sector_size as usize + 84) as u64;
+            let mut full = vec![0u8; (entries_lba * sector_size + array_bytes) as usize];
+            device.seek(SeekFrom::Start(0))?;
+            device.read_exact(&mut full)?;
+            return Ok(parse_partition_table(&full, sector_size));
+        }
+    }
+
+    let mut mbr = vec![0u8; 512];
+    device.seek(SeekFrom::Start(0))?;
+    device.read_exact(&mut mbr)?;
+    Ok(parse_partition_table(&mbr, 512))
+}
+
+/// CRC-32 (IEEE) as used by GPT headers and entry arrays
+fn crc32(data: &[u8]) -> u32 {
+    let mut crc = 0xFFFF_FFFFu32;
+    for &byte in data {
+        crc ^= byte as u32;
+        for _ in 0..8 {
+            crc = if crc & 1 != 0 { (crc >> 1) ^ 0xEDB8_8320 } else { crc >> 1 };
+        }
+    }
+    !crc
+}
+
+fn write_at<D: Write + Seek>(device: &mut D, offset: u64, data: &[u8]) -> io::Result<()> {
+    device.seek(SeekFrom::Start(offset))?;
+    device.write_all(data)
+}
+
+/// Write an empty `kind` partition table over a device of `total_sectors`,
+/// with a single partition spanning the usable space when `partition` is set.
+/// Returns the partition written.
+pub fn write_partition_table<D: Write + Seek>(
+    device: &mut D,
+    kind: PartitionTableKind,
+    sector_size: u64,
+    total_sectors: u64,
+    partition: Option<PartitionContent>,
+) -> io::Result<Option<Partition>> {
+    let first_lba = PARTITION_ALIGNMENT / sector_size;
+    if total_sectors <= first_lba * 2 {
+        return Err(io::Error::new(io::ErrorKind::InvalidInput, "Device is too small to partition"));
+    }
+    match kind {
+        PartitionTableKind::Mbr => write_mbr(device, sector_size, total_sectors, first_lba, partition),
+        PartitionTableKind::Gpt => write_gpt(device, sector_size, total_sectors, first_lba, partition),
+    }
+}
+
+fn mbr_entry(entry: &mut [u8], partition_type: u8, first_lba: u64, sector_count: u64) {
+    // CHS addresses are unused; FE FF FF tells the firmware to use the LBA fields
+    entry[1..4].copy_from_slice(&[0xFE, 0xFF, 0xFF]);
+    entry[4] = partition_type;
+    entry[5..8].copy_from_slice(&[0xFE, 0xFF, 0xFF]);
+    entry[8..12].copy_from_slice(&(first_lba as u32).to_le_bytes());
+    entry[12..16].copy_from_slice(&(sector_count.min(u32::MAX as u64) as u32).to_le_bytes());
+}
+
+fn write_mbr<D: Write + Seek>(
+    device: &mut D,
+    sector_size: u64,
+    total_sectors: u64,
+    first_lba: u64,
+    partition: Option<PartitionContent>,
+) -> io::Result<Option<Partition>> {
+    if total_sectors > u32::MAX as u64 {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "An MBR cannot address this device; use a GPT instead",
+        ));
+    }
+
+    let mut sector = vec![0u8; sector_size as usize];
+    sector[440..444].copy_from_slice(&rand::random::<u32>().to_le_bytes());
+    sector[510..512].copy_from_slice(&MBR_SIGNATURE);
+    let created = partition.map(|content| {
+        let sector_count = total_sectors - first_lba;
+        mbr_entry(&mut sector[MBR_ENTRY_OFFSET..MBR_ENTRY_OFFSET + 16], content.mbr_type(), first_lba, sector_count);
+        Partition {
+            number: 1,
+            first_lba,
+            sector_count,
+            type_guid: None,
+            unique_guid: None,
+            mbr_type: Some(content.mbr_type()),
+            name: String::new(),
+            bootable: false,
+        }
+    });
+
+    write_at(device, 0, &sector)?;
+    device.flush()?;
+    Ok(created)
+}
+
+fn gpt_header(
+    sector_size: u64,
+    my_lba: u64,
+    alternate_lba: u64,
+    entries_lba: u64,
+    usable: (u64, u64),
+    disk_guid: &Uuid,
+    entries_crc: u32,
+) -> Vec<u8> {
+    let mut header = vec![0u8; sector_size as usize];
+    header[0..8].copy_from_slice(GPT_SIGNATURE);
+    header[8..12].copy_from_slice(&GPT_REVISION.to_le_bytes());
+    header[12..16].copy_from_slice(&GPT_HEADER_SIZE.to_le_bytes());
+    header[24..32].copy_from_slice(&my_lba.to_le_bytes());
+    header[32..40].copy_from_slice(&alternate_lba.to_le_bytes());
+    header[40..48].copy_from_slice(&usable.0.to_le_bytes());
+    header[48..56].copy_from_slice(&usable.1.to_le_bytes());
+    header[56..72].copy_from_slice(&disk_guid.to_bytes_le());
+    header[72..80].copy_from_slice(&entries_lba.to_le_bytes());
+    header[80..84].copy_from_slice(&GPT_ENTRY_COUNT.to_le_bytes());
+    header[84..88].copy_from_slice(&GPT_ENTRY_SIZE.to_le_bytes());
+    header[88..92].copy_from_slice(&entries_crc.to_le_bytes());
+    let crc = crc32(&header[..GPT_HEADER_SIZE as usize]);
+    header[16..20].copy_from_slice(&crc.to_le_bytes());
+    header
+}
+
+fn write_gpt<D: Write + Seek>(
+    device: &mut D,
+    sector_size: u64,
+    total_sectors: u64,
+    first_lba: u64,
+    partition: Option<PartitionContent>,
+) -> io::Result<Option<Partition>> {
+    let array_sectors = (GPT_ENTRY_COUNT * GPT_ENTRY_SIZE) as u64 / sector_size;
+    let last_lba = total_sectors - 1;
+    let usable = (2 + array_sectors, last_lba - 1 - array_sectors);
+
+    let mut entries = vec![0u8; (array_sectors * sector_size) as usize];
+    let created = partition.map(|content| {
+        let unique_guid = Uuid::new_v4();
+        let entry = &mut entries[0..GPT_ENTRY_SIZE as usize];
+        entry[0..16].copy_from_slice(&Uuid::parse_str(content.gpt_type()).unwrap().to_bytes_le());
+        entry[16..32].copy_from_slice(&unique_guid.to_bytes_le());
+        entry[32..40].copy_from_slice(&first_lba.to_le_bytes());
+        entry[40..48].copy_from_slice(&usable.1.to_le_bytes());
+        Partition {
+            number: 1,
+            first_lba,
+            sector_count: usable.1 - first_lba + 1,
+            type_guid: Some(content.gpt_type().to_string()),
+            unique_guid: Some(format_guid(&entry[16..32])),
+            mbr_type: None,
+            name: String::new(),
+            bootable: false,
+        }
+    });
+    let entries_crc = crc32(&entries[..(GPT_ENTRY_COUNT * GPT_ENTRY_SIZE) as usize]);
+
+    // Protective MBR covering the whole disk, so MBR-only tools see it in use
+    let mut mbr = vec![0u8; sector_size as usize];
+    mbr[510..512].copy_from_slice(&MBR_SIGNATURE);
+    mbr_entry(&mut mbr[MBR_ENTRY_OFFSET..MBR_ENTRY_OFFSET + 16], MBR_TYPE_GPT_PROTECTIVE, 1, last_lba);
+
+    let disk_guid = Uuid::new_v4();
+    let backup_entries_lba = last_lba - array_sectors;
+    let primary = gpt_header(sector_size, 1, last_lba, 2, usable, &disk_guid, entries_crc);
+    let backup = gpt_header(sector_size, last_lba, 1, backup_entries_lba, usable, &disk_guid, entries_crc);
+
+    write_at(device, 0, &mbr)?;
+    write_at(device, sector_size, &primary)?;
+    write_at(device, 2 * sector_size, &entries)?;
+    write_at(device, backup_entries_lba * sector_size, &entries)?;
+    write_at(device, last_lba * sector_size, &backup)?;
+    device.flush()?;
+    Ok(created)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_mbr() {
+        let mut data = vec![0u8; 512];
+        data[510..512].copy_from_slice(&MBR_SIGNATURE);
+        let entry = &mut data[MBR_ENTRY_OFFSET..MBR_ENTRY_OFFSET + 16];
+        entry[0] = MBR_BOOTABLE;
+        entry[4] = 0x07;
+        entry[8..12].copy_from_slice(&2048u32.to_le_bytes());
+        entry[12..16].copy_from_slice(&1024000u32.to_le_bytes());
+        let entry = &mut data[MBR_ENTRY_OFFSET + 16..MBR_ENTRY_OFFSET + 32];
+        entry[4] = 0x83;
+        entry[8..12].copy_from_slice(&1026048u32.to_le_bytes());
+        entry[12..16].copy_from_slice(&4096u32.to_le_bytes());
+
+        let table = parse_partition_table(&data, 512).unwrap();
+        assert_eq!(table.kind, PartitionTableKind::Mbr);
+        assert_eq!(table.partitions.len(), 2);
+        assert!(table.partitions[0].is_system());
+        assert!(!table.partitions[1].is_system());
+        assert_eq!(table.find(&PartitionSelector::Number(2)).unwrap().byte_range(512), (1026048 * 512, 4096 * 512));
+    }
+
+    #[test]
+    fn test_parse_gpt() {
+        let mut data = vec![0u8; 34 * 512];
+        data[510..512].copy_from_slice(&MBR_SIGNATURE);
+        data[MBR_ENTRY_OFFSET + 4] = MBR_TYPE_GPT_PROTECTIVE;
+
+        let header = &mut data[512..612];
+        header[0..8].copy_from_slice(GPT_SIGNATURE);
+        header[72..80].copy_from_slice(&2u64.to_le_bytes());
+        header[80..84].copy_from_slice(&128u32.to_le_bytes());
+        header[84..88].copy_from_slice(&128u32.to_le_bytes());
+
+        // EFI System Partition, then a basic data partition
+        let esp_type = [0x28, 0x73, 0x2A, 0xC1, 0x1F, 0xF8, 0xD2, 0x11, 0xBA, 0x4B, 0x00, 0xA0, 0xC9, 0x3E, 0xC9, 0x3B];
+        let entries = [(esp_type, 2048u64, 206847u64), ([0xA2; 16], 206848, 409599)];
+        for (index, (type_guid, first, last)) in entries.iter().enumerate() {
+            let entry = &mut data[1024 + index * 128..1024 + (index + 1) * 128];
+            entry[0..16].copy_from_slice(type_guid);
+            entry[16..32].copy_from_slice(&[index as u8 + 1; 16]);
+            entry[32..40].copy_from_slice(&first.to_le_bytes());
+            entry[40..48].copy_from_slice(&last.to_le_bytes());
+            entry[56] = b'P';
+        }
+
+        let table = parse_partition_table(&data, 512).unwrap();
+        assert_eq!(table.kind, PartitionTableKind::Gpt);
+        assert_eq!(table.partitions.len(), 2);
+        assert_eq!(table.partitions[0].type_guid.as_deref(), Some(SYSTEM_PARTITION_TYPES[0]));
+        assert_eq!(table.system_partitions().count(), 1);
+        assert_eq!(table.partitions[1].sector_count, 202752);
+        assert_eq!(table.partitions[1].name, "P");
+
+        let guid = table.partitions[1].unique_guid.clone().unwrap().to_lowercase();
+        assert_eq!(table.find(&PartitionSelector::Guid(guid)).unwrap().number, 2);
+    }
+
+    #[test]
+    fn test_written_tables_parse_back() {
+        let total_sectors = 8192u64;
+        let mut device = io::Cursor::new(vec![0xA5u8; (total_sectors * 512) as usize]);
+        let created = write_partition_table(&mut device, PartitionTableKind::Gpt, 512, total_sectors, Some(PartitionContent::BasicData))
+            .unwrap()
+            .unwrap();
+        let data = device.get_ref();
+
+        let table = parse_partition_table(data, 512).unwrap();
+        assert_eq!(table.kind, PartitionTableKind::Gpt);
+        assert_eq!(table.partitions, vec![created.clone()]);
+        assert_eq!((created.first_lba, created.sector_count), (2048, total_sectors - 33 - 2048));
+        assert_eq!(created.type_guid.as_deref(), Some("EBD0A0A2-B9E5-4433-87C0-68B6B72699C7"));
+
+        // Both headers carry valid checksums, and the backup points back at the primary
+        assert_eq!(crc32(b"123456789"), 0xCBF4_3926);
+        for lba in [1, total_sectors - 1] {
+            let mut header = data[(lba * 512) as usize..(lba * 512) as usize + 92].to_vec();
+            let stored = read_u32(&header, 16);
+            header[16..20].fill(0);
+            assert_eq!(crc32(&header), stored);
+            assert_eq!(read_u32(&header, 88), crc32(&data[1024..1024 + 128 * 128]));
+        }
+        assert_eq!(read_u64(data, (total_sectors - 1) as usize * 512 + 32), 1);
+
+        let mut device = io::Cursor::new(vec![0xA5u8; (total_sectors * 512) as usize]);
+        write_partition_table(&mut device, PartitionTableKind::Mbr, 512, total_sectors, Some(PartitionContent::Linux)).unwrap();
+        let table = parse_partition_table(device.get_ref(), 512).unwrap();
+        assert_eq!(table.kind, PartitionTableKind::Mbr);
+        assert_eq!(table.partitions[0].mbr_type, Some(0x83));
+        assert_eq!((table.partitions[0].first_lba, table.partitions[0].sector_count), (2048, total_sectors - 2048));
+
+        let mut device = io::Cursor::new(vec![0u8; 512 * 1024]);
+        assert!(write_partition_table(&mut device, PartitionTableKind::Mbr, 512, 1024, None).is_err());
+    }
+}
@@ -0,0 +1,198 @@
+//! Reproducible cryptographic overwrite patterns
+//!
+//! Random passes write a ChaCha20 keystream instead of one random buffer
+//! repeated across the device. The stream is addressable by byte offset, so
+//! verification can regenerate exactly what a pass wrote at any sampled
+//! region and compare it byte for byte instead of only checking entropy.
+//!
+//! Each wipe draws a fresh 256-bit seed from the operating system; every pass
+//! uses its own stream of that seed. When seed capture is enabled the seed is
+//! sealed with ChaCha20-Poly1305 under a local key and kept with the operation
+//! record, so the written data can be re-verified later.
+
+use std::fmt;
+use std::fs;
+use std::io::{self, Write};
+use std::path::Path;
+use rand::RngCore;
+use rand::SeedableRng;
+use rand_chacha::ChaCha20Rng;
+use ring::aead::{Aad, LessSafeKey, Nonce, UnboundKey, CHACHA20_POLY1305, NONCE_LEN};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+pub const DEFAULT_SEED_KEY_PATH: &str = "certificates/pattern_seed.key";
+
+/// 256-bit seed of the pattern streams of one wipe
+#[derive(Clone, PartialEq, Eq)]
+pub struct PatternSeed([u8; 32]);
+
+impl PatternSeed {
+    pub fn generate() -> Self {
+        let mut seed = [0u8; 32];
+        rand::rngs::OsRng.fill_bytes(&mut seed);
+        Self(seed)
+    }
+
+    pub fn from_bytes(bytes: [u8; 32]) -> Self {
+        Self(bytes)
+    }
+
+    pub fn as_bytes(&self) -> &[u8; 32] {
+        &self.0
+    }
+}
+
+/// Never print the seed itself
+impl fmt::Debug for PatternSeed {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("PatternSeed(..)")
+    }
+}
+
+impl Drop for PatternSeed {
+    fn drop(&mut self) {
+        self.0.iter_mut().for_each(|byte| unsafe { std::ptr::write_volatile(byte, 0) });
+    }
+}
+
+/// ChaCha20 keystream of one pass, addressable by byte offset
+#[derive(Clone, PartialEq)]
+pub struct PatternStream {
+    seed: PatternSeed,
+    stream: u64,
+}
+
+impl PatternStream {
+    pub fn new(seed: &PatternSeed, stream: u64) -> Self {
+        Self { seed: seed.clone(), stream }
+    }
+
+    /// Fill `buffer` with the stream bytes starting at `offset`
+    pub fn fill_at(&self, offset: u64, buffer: &mut [u8]) {
+        let mut rng = ChaCha20Rng::from_seed(*self.seed.as_bytes());
+        rng.set_stream(self.stream);
+        // The generator is positioned in 32-bit words
+        rng.set_word_pos((offset / 4) as u128);
+
+        let skip = (offset % 4) as usize;
+        if skip == 0 {
+            rng.fill_bytes(buffer);
+        } else {
+            let mut unaligned = vec![0u8; buffer.len() + skip];
+            rng.fill_bytes(&mut unaligned);
+            buffer.copy_from_slice(&unaligned[skip..]);
+        }
+    }
+}
+
+impl fmt::Debug for PatternStream {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PatternStream").field("stream", &self.stream).finish()
+    }
+}
+
+/// A seed encrypted for storage; the operation id is authenticated with it
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SealedSeed {
+    pub nonce: String,
+    pub ciphertext: String,
+}
+
+/// Encrypts pattern seeds under a key kept next to the certificate authority
+pub struct SeedVault {
+    key: LessSafeKey,
+}
+
+impl SeedVault {
+    /// Load the key at `path`, creating it on first use
+    pub fn open<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        let path = path.as_ref();
+        let key_bytes = if path.exists() {
+            hex::decode(fs::read_to_string(path)?.trim())
+                .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, format!("Invalid seed key: {}", e)))?
+        } else {
+            let mut key = vec![0u8; 32];
+            rand::rngs::OsRng.fill_bytes(&mut key);
+            if let Some(parent) = path.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            // Created owner-only, so the key is never readable by others, not even briefly
+            let mut options = fs::OpenOptions::new();
+            options.write(true).create_new(true);
+            #[cfg(unix)]
+            {
+                use std::os::unix::fs::OpenOptionsExt;
+                options.mode(0o600);
+            }
+            options.open(path)?.write_all(hex::encode(&key).as_bytes())?;
+            key
+        };
+
+        let key = UnboundKey::new(&CHACHA20_POLY1305, &key_bytes)
+            .map_err(|_| io::Error::new(io::ErrorKind::InvalidData, "Seed key must be 32 bytes"))?;
+        Ok(Self { key: LessSafeKey::new(key) })
+    }
+
+    pub fn seal(&self, seed: &PatternSeed, operation_id: Uuid) -> io::Result<SealedSeed> {
+        let mut nonce = [0u8; NONCE_LEN];
+        rand::rngs::OsRng.fill_bytes(&mut nonce);
+
+        let mut in_out = seed.as_bytes().to_vec();
+        self.key
+            .seal_in_place_append_tag(Nonce::assume_unique_for_key(nonce), Aad::from(operation_id.as_bytes()), &mut in_out)
+            .map_err(|_| io::Error::other("Failed to encrypt pattern seed"))?;
+
+        Ok(SealedSeed { nonce: hex::encode(nonce), ciphertext: hex::encode(in_out) })
+    }
+
+    pub fn unseal(&self, sealed: &SealedSeed, operation_id: Uuid) -> io::Result<PatternSeed> {
+        let invalid = || io::Error::new(io::ErrorKind::InvalidData, "Pattern seed cannot be decrypted with this key");
+        let nonce: [u8; NONCE_LEN] = hex::decode(&sealed.nonce).ok()
+            .and_then(|bytes| bytes.try_into().ok())
+            .ok_or_else(invalid)?;
+        let mut in_out = hex::decode(&sealed.ciphertext).map_err(|_| invalid())?;
+
+        let plaintext = self.key
+            .open_in_place(Nonce::assume_unique_for_key(nonce), Aad::from(operation_id.as_bytes()), &mut in_out)
+            .map_err(|_| invalid())?;
+        let seed: [u8; 32] = plaintext.try_into().map_err(|_| invalid())?;
+        Ok(PatternSeed::from_bytes(seed))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stream_is_addressable() {
+        let seed = PatternSeed::generate();
+        let stream = PatternStream::new(&seed, 3);
+
+        let mut whole = vec![0u8; 4096];
+        stream.fill_at(0, &mut whole);
+        let mut part = vec![0u8; 1001];
+        stream.fill_at(1027, &mut part);
+        assert_eq!(&whole[1027..2028], &part[..]);
+
+        let mut other_pass = vec![0u8; 4096];
+        PatternStream::new(&seed, 4).fill_at(0, &mut other_pass);
+        assert_ne!(whole, other_pass);
+    }
+
+    #[test]
+    fn test_sealed_seed_roundtrip() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("seed_key");
+        let vault = SeedVault::open(&path).unwrap();
+        let seed = PatternSeed::generate();
+        let operation_id = Uuid::new_v4();
+
+        let sealed = vault.seal(&seed, operation_id).unwrap();
+        assert!(!sealed.ciphertext.contains(&hex::encode(seed.as_bytes())));
+        assert_eq!(SeedVault::open(&path).unwrap().unseal(&sealed, operation_id).unwrap(), seed);
+        // Bound to the operation it was sealed for
+        assert!(vault.unseal(&sealed, Uuid::new_v4()).is_err());
+    }
+}
@@ -13,6 +13,240 @@ pub struct DriveInfo {
     pub free_space: u64,
 }
 
+/// A whole physical disk, including disks with no partitions or volumes
+#[derive(Debug, Clone, Default)]
+pub struct PhysicalDisk {
+    /// Raw device path, e.g. \\.\PhysicalDrive1
+    pub path: String,
+    /// N in PhysicalDriveN
+    pub disk_number: u32,
+    pub vendor: String,
+    pub model: String,
+    pub serial: String,
+    pub firmware: String,
+    pub bus_type: String,
+    /// None when the device does not report a seek penalty
+    pub rotational: Option<bool>,
+    pub removable: bool,
+    pub size_bytes: u64,
+    /// Volumes with at least one extent on this disk: drive letters such as "C:\"
+    /// on Windows, mount points of the disk, its partitions and mappings on Linux
+    pub volumes: Vec<String>,
+    pub kind: DiskKind,
+    /// World Wide Name, when the device reports one
+    pub wwn: Option<String>,
+    /// Active I/O scheduler (Linux)
+    pub scheduler: Option<String>,
+    /// The root filesystem lives on this disk
+    pub is_root_disk: bool,
+    /// Devices underneath a mapping or loop device
+    pub backing_devices: Vec<String>,
+    /// Controller of an NVMe namespace, e.g. /dev/nvme0; namespaces sharing
+    /// a controller are listed as its children
+    pub controller: Option<String>,
+    pub namespace_id: Option<u32>,
+    pub capabilities: VendorCapabilities,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum DiskKind {
+    #[default]
+    Physical,
+    NvmeNamespace,
+    Loop,
+    DmCrypt,
+    Multipath,
+    DeviceMapper,
+    SoftwareRaid,
+    /// File registered with `WipeEngine::create_test_device`
+    TestDevice,
+}
+
+impl DiskKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DiskKind::Physical => "Physical disk",
+            DiskKind::NvmeNamespace => "NVMe namespace",
+            DiskKind::Loop => "Loop device",
+            DiskKind::DmCrypt => "dm-crypt mapping",
+            DiskKind::Multipath => "Multipath device",
+            DiskKind::DeviceMapper => "Device-mapper volume",
+            DiskKind::SoftwareRaid => "Software RAID",
+            DiskKind::TestDevice => "File-backed test device",
+        }
+    }
+
+    /// Mappings and loop devices are views of other devices; wiping them does
+    /// not reach the underlying media
+    pub fn is_virtual(&self) -> bool {
+        !matches!(self, DiskKind::Physical | DiskKind::NvmeNamespace)
+    }
+}
+
+/// Limits and features the device advertises to the block layer
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct VendorCapabilities {
+    pub discard: bool,
+    pub discard_max_bytes: u64,
+    pub write_zeroes: bool,
+    pub logical_block_size: u32,
+    pub physical_block_size: u32,
+    /// TCG security subsystem from Level 0 Discovery, for self-encrypting drives
+    pub opal: Option<crate::opal::OpalSsc>,
+}
+
+impl PhysicalDisk {
+    /// "Samsung SSD 870 EVO (SATA SSD)"
+    pub fn description(&self) -> String {
+        let media = match self.rotational {
+            Some(true) => " HDD",
+            Some(false) => " SSD",
+            None => "",
+        };
+        let name = if self.model.is_empty() { "Disk" } else { self.model.as_str() };
+        format!("{} ({}{})", name, self.bus_type, media)
+    }
+}
+
+/// Name the physical disk behind each listed volume and add disks that have no volumes
+fn merge_physical_disks(drives: &mut Vec<DriveInfo>, disks: Vec<PhysicalDisk>) {
+    let mut annotated = vec![false; drives.len()];
+
+    for disk in disks {
+        let mut has_volume = false;
+        for (index, drive) in drives.iter_mut().enumerate() {
+            if !disk.volumes.contains(&drive.path) {
+                continue;
+            }
+            has_volume = true;
+            // A filesystem on a mapping is listed under both the mapping and its disk
+            if !annotated[index] {
+                drive.drive_type = format!("{} on {}", drive.drive_type, disk.description());
+                annotated[index] = true;
+            }
+        }
+
+        if !has_volume && !drives.iter().any(|d| d.path == disk.path) {
+            let name = if disk.model.is_empty() { disk.kind.as_str() } else { disk.model.as_str() };
+            drives.push(DriveInfo {
+                path: disk.path.clone(),
+                label: format!("{} - {} (no volumes)", disk.path, name),
+                drive_type: disk.description(),
+                total_space: disk.size_bytes,
+                free_space: 0,
+            });
+        }
+    }
+}
+
+/// The active scheduler from `queue/scheduler`, e.g. "none [mq-deadline] kyber"
+pub fn parse_scheduler(content: &str) -> Option<String> {
+    let start = content.find('[')?;
+    let end = content[start..].find(']')? + start;
+    Some(content[start + 1..end].to_string())
+}
+
+/// `E:KEY=value` properties from a udev database entry (/run/udev/data/bMAJ:MIN)
+pub fn parse_udev_properties(content: &str) -> std::collections::HashMap<String, String> {
+    content
+        .lines()
+        .filter_map(|line| line.strip_prefix("E:"))
+        .filter_map(|property| property.split_once('='))
+        .map(|(key, value)| (key.to_string(), value.to_string()))
+        .collect()
+}
+
+/// Classify a device-mapper node by the target prefix of its `dm/uuid`
+pub fn dm_kind(uuid: &str) -> DiskKind {
+    if uuid.starts_with("CRYPT-") {
+        DiskKind::DmCrypt
+    } else if uuid.starts_with("mpath-") {
+        DiskKind::Multipath
+    } else {
+        DiskKind::DeviceMapper
+    }
+}
+
+/// Fields of a STORAGE_DEVICE_DESCRIPTOR returned by IOCTL_STORAGE_QUERY_PROPERTY
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct StorageDescriptor {
+    pub vendor: String,
+    pub product: String,
+    pub revision: String,
+    pub serial: String,
+    pub bus_type: u32,
+    pub removable: bool,
+}
+
+/// Parse a raw STORAGE_DEVICE_DESCRIPTOR; the strings are NUL-terminated ASCII
+/// at the offsets given in the header (0 means absent)
+pub fn parse_storage_descriptor(buffer: &[u8]) -> Option<StorageDescriptor> {
+    if buffer.len() < 36 {
+        return None;
+    }
+    let read_u32 = |offset: usize| u32::from_le_bytes(buffer[offset..offset + 4].try_into().unwrap());
+    let read_string = |header: usize| {
+        let offset = read_u32(header) as usize;
+        if offset == 0 || offset >= buffer.len() {
+            return String::new();
+        }
+        let end = buffer[offset..].iter().position(|&b| b == 0).map_or(buffer.len(), |n| offset + n);
+        String::from_utf8_lossy(&buffer[offset..end]).trim().to_string()
+    };
+
+    Some(StorageDescriptor {
+        removable: buffer[10] != 0,
+        vendor: read_string(12),
+        product: read_string(16),
+        revision: read_string(20),
+        serial: read_string(24),
+        bus_type: read_u32(28),
+    })
+}
+
+/// Disk numbers holding the extents of a volume, from a raw VOLUME_DISK_EXTENTS
+pub fn parse_volume_disk_extents(buffer: &[u8]) -> Vec<u32> {
+    if buffer.len() < 8 {
+        return Vec::new();
+    }
+    let count = u32::from_le_bytes(buffer[0..4].try_into().unwrap()) as usize;
+
+    // Each DISK_EXTENT is 24 bytes (DiskNumber, padding, StartingOffset, ExtentLength)
+    let mut disks: Vec<u32> = (0..count)
+        .map(|i| 8 + i * 24)
+        .filter(|&offset| offset + 4 <= buffer.len())
+        .map(|offset| u32::from_le_bytes(buffer[offset..offset + 4].try_into().unwrap()))
+        .collect();
+    disks.dedup();
+    disks
+}
+
+/// Name of a Windows STORAGE_BUS_TYPE value
+pub fn bus_type_name(bus_type: u32) -> &'static str {
+    match bus_type {
+        0x01 => "SCSI",
+        0x02 => "ATAPI",
+        0x03 => "ATA",
+        0x04 => "IEEE 1394",
+        0x05 => "SSA",
+        0x06 => "Fibre Channel",
+        0x07 => "USB",
+        0x08 => "RAID",
+        0x09 => "iSCSI",
+        0x0A => "SAS",
+        0x0B => "SATA",
+        0x0C => "SD",
+        0x0D => "MMC",
+        0x0E => "Virtual",
+        0x0F => "File Backed Virtual",
+        0x10 => "Storage Spaces",
+        0x11 => "NVMe",
+        0x12 => "SCM",
+        0x13 => "UFS",
+        _ => "Unknown",
+    }
+}
+
 #[cfg(windows)]
 pub mod windows_impl {
     use super::*;
@@ -20,9 +254,10 @@ pub mod windows_impl {
         core::PWSTR,
         Win32::Storage::FileSystem::{
             GetDiskFreeSpaceExW, GetDriveTypeW, GetLogicalDrives, GetVolumeInformationW,
-            CreateFileW, FILE_ATTRIBUTE_NORMAL, FILE_SHARE_READ, OPEN_EXISTING,
+            CreateFileW, FILE_ATTRIBUTE_NORMAL, FILE_SHARE_READ, FILE_SHARE_WRITE, OPEN_EXISTING,
         },
         Win32::Foundation::{CloseHandle, HANDLE},
+        Win32::System::IO::DeviceIoControl,
     };
     
     // Drive type constants
@@ -33,6 +268,15 @@ pub mod windows_impl {
     const DRIVE_CDROM: u32 = 5;
     const DRIVE_RAMDISK: u32 = 6;
 
+    const IOCTL_STORAGE_QUERY_PROPERTY: u32 = 0x002D_1400;
+    const IOCTL_DISK_GET_LENGTH_INFO: u32 = 0x0007_405C;
+    const IOCTL_VOLUME_GET_VOLUME_DISK_EXTENTS: u32 = 0x0056_0000;
+    const STORAGE_DEVICE_PROPERTY: u32 = 0;
+    const STORAGE_DEVICE_SEEK_PENALTY_PROPERTY: u32 = 7;
+    const PROPERTY_STANDARD_QUERY: u32 = 0;
+    /// PhysicalDrive numbers probed; numbering can have gaps after hot-removal
+    const MAX_PHYSICAL_DRIVES: u32 = 64;
+
     pub fn get_drives() -> io::Result<Vec<DriveInfo>> {
         let mut drives = Vec::new();
         
@@ -111,17 +355,133 @@ pub mod windows_impl {
                 }
             }
         }
+
+        merge_physical_disks(&mut drives, get_physical_disks().unwrap_or_default());
         
         Ok(drives)
     }
+
+    /// Enumerate \\.\PhysicalDriveN with their storage descriptors and volumes
+    pub fn get_physical_disks() -> io::Result<Vec<PhysicalDisk>> {
+        let volume_map = volume_disk_map();
+        let system_drive = format!("{}\\", std::env::var("SystemDrive").unwrap_or_else(|_| "C:".to_string()));
+        let mut disks = Vec::new();
+
+        for disk_number in 0..MAX_PHYSICAL_DRIVES {
+            let path = format!("\\\\.\\PhysicalDrive{}", disk_number);
+            // Zero access rights are enough for property queries and need no elevation
+            let Some(handle) = open_device(&path, 0) else {
+                continue;
+            };
+
+            let descriptor = query_property(handle, STORAGE_DEVICE_PROPERTY, 1024)
+                .and_then(|buffer| parse_storage_descriptor(&buffer))
+                .unwrap_or_default();
+            // DEVICE_SEEK_PENALTY_DESCRIPTOR: Version, Size, IncursSeekPenalty
+            let rotational = query_property(handle, STORAGE_DEVICE_SEEK_PENALTY_PROPERTY, 12)
+                .filter(|buffer| buffer.len() >= 9)
+                .map(|buffer| buffer[8] != 0);
+            let size_bytes = ioctl(handle, IOCTL_DISK_GET_LENGTH_INFO, &[], 8)
+                .filter(|buffer| buffer.len() >= 8)
+                .map(|buffer| i64::from_le_bytes(buffer[0..8].try_into().unwrap()) as u64)
+                .unwrap_or(0);
+
+            unsafe {
+                let _ = CloseHandle(handle);
+            }
+
+            let volumes: Vec<String> = volume_map
+                .iter()
+                .filter(|(_, numbers)| numbers.contains(&disk_number))
+                .map(|(volume, _)| volume.clone())
+                .collect();
+
+            disks.push(PhysicalDisk {
+                path,
+                disk_number,
+                vendor: descriptor.vendor,
+                model: descriptor.product,
+                serial: descriptor.serial,
+                firmware: descriptor.revision,
+                bus_type: bus_type_name(descriptor.bus_type).to_string(),
+                rotational,
+                removable: descriptor.removable,
+                size_bytes,
+                is_root_disk: volumes.iter().any(|v| v.eq_ignore_ascii_case(&system_drive)),
+                volumes,
+                ..Default::default()
+            });
+        }
+
+        Ok(disks)
+    }
+
+    /// Disk numbers behind every drive letter; spanned volumes list several disks
+    fn volume_disk_map() -> Vec<(String, Vec<u32>)> {
+        let logical_drives = unsafe { GetLogicalDrives() };
+
+        (0..26u8)
+            .filter(|i| logical_drives & (1 << i) != 0)
+            .filter_map(|i| {
+                let letter = (b'A' + i) as char;
+                let handle = open_device(&format!("\\\\.\\{}:", letter), 0)?;
+                let extents = ioctl(handle, IOCTL_VOLUME_GET_VOLUME_DISK_EXTENTS, &[], 8 + 24 * 32);
+                unsafe {
+                    let _ = CloseHandle(handle);
+                }
+                let numbers = parse_volume_disk_extents(&extents?);
+                (!numbers.is_empty()).then(|| (format!("{}:\\", letter), numbers))
+            })
+            .collect()
+    }
+
+    fn open_device(path: &str, access: u32) -> Option<HANDLE> {
+        let path_wide: Vec<u16> = path.encode_utf16().chain(std::iter::once(0)).collect();
+        unsafe {
+            CreateFileW(
+                PWSTR::from_raw(path_wide.as_ptr() as *mut u16),
+                access,
+                FILE_SHARE_READ | FILE_SHARE_WRITE,
+                None,
+                OPEN_EXISTING,
+                FILE_ATTRIBUTE_NORMAL,
+                HANDLE::default(),
+            ).ok()
+        }
+    }
+
+    /// IOCTL_STORAGE_QUERY_PROPERTY with a STORAGE_PROPERTY_QUERY for `property_id`
+    fn query_property(handle: HANDLE, property_id: u32, output_size: usize) -> Option<Vec<u8>> {
+        let mut query = [0u8; 12];
+        query[0..4].copy_from_slice(&property_id.to_le_bytes());
+        query[4..8].copy_from_slice(&PROPERTY_STANDARD_QUERY.to_le_bytes());
+        ioctl(handle, IOCTL_STORAGE_QUERY_PROPERTY, &query, output_size)
+    }
+
+    fn ioctl(handle: HANDLE, code: u32, input: &[u8], output_size: usize) -> Option<Vec<u8>> {
+        let mut output = vec![0u8; output_size];
+        let mut bytes_returned = 0u32;
+        unsafe {
+            DeviceIoControl(
+                handle,
+                code,
+                (!input.is_empty()).then(|| input.as_ptr() as *const _),
+                input.len() as u32,
+                Some(output.as_mut_ptr() as *mut _),
+                output.len() as u32,
+                Some(&mut bytes_returned),
+                None,
+            ).ok()?;
+        }
+        output.truncate(bytes_returned as usize);
+        Some(output)
+    }
 }
 
 #[cfg(unix)]
 pub mod unix_impl {
     use super::*;
     use std::fs;
-    use std::ffi::{CString, OsStr};
-    use std::os::unix::ffi::OsStrExt;
     use sysinfo::{System, SystemExt, DiskExt};
 
     pub fn get_drives() -> io::Result<Vec<DriveInfo>> {
@@ -153,29 +513,8 @@ pub mod unix_impl {
             });
         }
         
-        // Also check common device paths for unmounted drives
-        let device_paths = [
-            "/dev/sda", "/dev/sdb", "/dev/sdc", "/dev/sdd",
-            "/dev/nvme0n1", "/dev/nvme1n1",
-            "/dev/mmcblk0", "/dev/mmcblk1",
-        ];
-        
-        for &dev_path in &device_paths {
-            if Path::new(dev_path).exists() {
-                // Check if this device is already mounted
-                let already_mounted = drives.iter().any(|d| d.path.contains(&dev_path[5..]));
-                
-                if !already_mounted {
-                    drives.push(DriveInfo {
-                        path: dev_path.to_string(),
-                        label: format!("Unmounted Device ({})", dev_path),
-                        drive_type: determine_drive_type_from_path(dev_path),
-                        total_space: get_device_size(dev_path).unwrap_or(0),
-                        free_space: 0, // Unmounted, so no free space info
-                    });
-                }
-            }
-        }
+        // Unpartitioned and unmounted disks, namespaces and mappings come from sysfs
+        merge_physical_disks(&mut drives, get_physical_disks().unwrap_or_default());
         
         Ok(drives)
     }
@@ -192,25 +531,192 @@ pub mod unix_impl {
         }
     }
     
-    fn determine_drive_type_from_path(device_path: &str) -> String {
-        if device_path.contains("nvme") {
-            "NVMe SSD".to_string()
-        } else if device_path.contains("mmcblk") {
-            "SD Card/eMMC".to_string()
-        } else if device_path.starts_with("/dev/sd") {
-            "SATA Drive (HDD/SSD)".to_string()
+    /// Enumerate block devices from sysfs, enriched with the udev database and
+    /// /proc/mounts. Covers SCSI/SATA disks, NVMe namespaces, MMC, loop devices,
+    /// dm-crypt, multipath and software RAID.
+    pub fn get_physical_disks() -> io::Result<Vec<PhysicalDisk>> {
+        let mounts = read_mounts();
+        let root_device = mounts.iter().find(|(_, mount)| mount == "/").map(|(name, _)| name.clone());
+        let root_disks = root_device.map(|name| ancestors(&name)).unwrap_or_default();
+
+        let mut names: Vec<String> = fs::read_dir("/sys/block")?
+            .filter_map(|entry| entry.ok())
+            .map(|entry| entry.file_name().to_string_lossy().to_string())
+            .filter(|name| is_listed_block_device(name))
+            .collect();
+        names.sort();
+
+        let mut disks = Vec::new();
+        for name in names {
+            let sys = format!("/sys/block/{}", name);
+            let size_bytes = read_sys(&sys, "size").and_then(|s| s.parse::<u64>().ok()).unwrap_or(0) * 512;
+            // Unattached loop devices report no size
+            if size_bytes == 0 {
+                continue;
+            }
+
+            let udev = read_sys(&sys, "dev")
+                .and_then(|dev| fs::read_to_string(format!("/run/udev/data/b{}", dev)).ok())
+                .map(|content| parse_udev_properties(&content))
+                .unwrap_or_default();
+            let udev_value = |key: &str| udev.get(key).map(|v| v.trim().to_string()).filter(|v| !v.is_empty());
+
+            let (kind, path, mut backing_devices) = if name.starts_with("dm-") {
+                let uuid = read_sys(&sys, "dm/uuid").unwrap_or_default();
+                let dm_name = read_sys(&sys, "dm/name").unwrap_or_else(|| name.clone());
+                (dm_kind(&uuid), format!("/dev/mapper/{}", dm_name), list_dir(&format!("{}/slaves", sys)))
+            } else if name.starts_with("loop") {
+                let backing = read_sys(&sys, "loop/backing_file").into_iter().collect();
+                (DiskKind::Loop, format!("/dev/{}", name), backing)
+            } else if name.starts_with("md") {
+                (DiskKind::SoftwareRaid, format!("/dev/{}", name), list_dir(&format!("{}/slaves", sys)))
+            } else if name.starts_with("nvme") {
+                (DiskKind::NvmeNamespace, format!("/dev/{}", name), Vec::new())
+            } else {
+                (DiskKind::Physical, format!("/dev/{}", name), Vec::new())
+            };
+            for device in backing_devices.iter_mut().filter(|d| !d.starts_with('/')) {
+                *device = format!("/dev/{}", device);
+            }
+
+            let volumes = mounts
+                .iter()
+                .filter(|(source, _)| ancestors(source).contains(&name))
+                .map(|(_, mount)| mount.clone())
+                .collect();
+
+            disks.push(PhysicalDisk {
+                path: path.clone(),
+                disk_number: disks.len() as u32,
+                vendor: udev_value("ID_VENDOR").or_else(|| read_sys(&sys, "device/vendor")).unwrap_or_default(),
+                model: udev_value("ID_MODEL")
+                    .map(|model| model.replace('_', " "))
+                    .or_else(|| read_sys(&sys, "device/model"))
+                    .unwrap_or_default(),
+                serial: udev_value("ID_SERIAL_SHORT").or_else(|| read_sys(&sys, "device/serial")).unwrap_or_default(),
+                firmware: udev_value("ID_REVISION")
+                    .or_else(|| read_sys(&sys, "device/firmware_rev"))
+                    .or_else(|| read_sys(&sys, "device/rev"))
+                    .unwrap_or_default(),
+                bus_type: bus_type(&name, udev_value("ID_BUS").as_deref()).to_string(),
+                rotational: read_sys(&sys, "queue/rotational").map(|r| r == "1"),
+                removable: read_sys(&sys, "removable").as_deref() == Some("1"),
+                size_bytes,
+                volumes,
+                kind,
+                wwn: udev_value("ID_WWN").or_else(|| crate::devices::identity::read_wwn(&path)),
+                scheduler: read_sys(&sys, "queue/scheduler").and_then(|s| parse_scheduler(&s)),
+                is_root_disk: root_disks.contains(&name),
+                backing_devices,
+                controller: crate::devices::nvme::controller_path(&path).filter(|_| kind == DiskKind::NvmeNamespace),
+                namespace_id: crate::devices::nvme::namespace_id_of(&path),
+                capabilities: VendorCapabilities {
+                    discard_max_bytes: read_u64(&sys, "queue/discard_max_bytes"),
+                    discard: read_u64(&sys, "queue/discard_max_bytes") > 0,
+                    write_zeroes: read_u64(&sys, "queue/write_zeroes_max_bytes") > 0,
+                    logical_block_size: read_u64(&sys, "queue/logical_block_size") as u32,
+                    physical_block_size: read_u64(&sys, "queue/physical_block_size") as u32,
+                    // Mappings pass no security protocol commands through
+                    opal: if kind.is_virtual() {
+                        None
+                    } else {
+                        crate::opal::detect_opal(&path).and_then(|discovery| discovery.ssc)
+                    },
+                },
+            });
+        }
+
+        Ok(disks)
+    }
+
+    /// RAM disks, zram swap, floppies and the hidden per-controller NVMe path
+    /// nodes (nvme0c0n1) are not wipe targets
+    fn is_listed_block_device(name: &str) -> bool {
+        let hidden_nvme_path = name.starts_with("nvme")
+            && name[4..].split_once('c').is_some_and(|(_, rest)| rest.contains('n'));
+        !(name.starts_with("ram") || name.starts_with("zram") || name.starts_with("fd") || hidden_nvme_path)
+    }
+
+    fn bus_type(name: &str, udev_bus: Option<&str>) -> &'static str {
+        if name.starts_with("nvme") {
+            "NVMe"
+        } else if name.starts_with("mmcblk") {
+            "MMC"
+        } else if name.starts_with("loop") {
+            "File Backed Virtual"
+        } else if name.starts_with("dm-") || name.starts_with("md") {
+            "Virtual"
+        } else if name.starts_with("vd") {
+            "Virtio"
         } else {
-            "Unknown Drive".to_string()
+            match udev_bus {
+                Some("usb") => "USB",
+                Some("ata") => "SATA",
+                Some("ieee1394") => "IEEE 1394",
+                _ => "SCSI",
+            }
         }
     }
-    
-    fn get_device_size(device_path: &str) -> io::Result<u64> {
-        use std::fs::File;
-        use std::io::{Seek, SeekFrom};
-        
-        let mut file = File::open(device_path)?;
-        let size = file.seek(SeekFrom::End(0))?;
-        Ok(size)
+
+    /// (kernel block device name, mount point) for every block-backed mount
+    fn read_mounts() -> Vec<(String, String)> {
+        fs::read_to_string("/proc/mounts")
+            .unwrap_or_default()
+            .lines()
+            .filter_map(|line| {
+                let mut fields = line.split_whitespace();
+                let source = fields.next()?;
+                let mount = fields.next()?.replace("\\040", " ");
+                if !source.starts_with("/dev/") {
+                    return None;
+                }
+                // /dev/mapper/root and /dev/disk/by-uuid/... are symlinks to the kernel node
+                let resolved = fs::canonicalize(source).ok()?;
+                Some((resolved.file_name()?.to_string_lossy().to_string(), mount))
+            })
+            .collect()
+    }
+
+    /// The device itself, the disk holding it when it is a partition, and every
+    /// device underneath it when it is a mapping
+    fn ancestors(name: &str) -> Vec<String> {
+        let mut result = vec![name.to_string()];
+        let class = format!("/sys/class/block/{}", name);
+
+        if Path::new(&class).join("partition").exists()
+            && let Some(parent) = fs::canonicalize(&class).ok().and_then(|p| {
+                p.parent().and_then(|parent| parent.file_name()).map(|n| n.to_string_lossy().to_string())
+            })
+        {
+            result.extend(ancestors(&parent));
+        }
+        for slave in list_dir(&format!("{}/slaves", class)) {
+            result.extend(ancestors(&slave));
+        }
+
+        result
+    }
+
+    fn list_dir(path: &str) -> Vec<String> {
+        fs::read_dir(path)
+            .map(|entries| {
+                entries
+                    .filter_map(|entry| entry.ok())
+                    .map(|entry| entry.file_name().to_string_lossy().to_string())
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    fn read_sys(sys: &str, attribute: &str) -> Option<String> {
+        fs::read_to_string(format!("{}/{}", sys, attribute))
+            .ok()
+            .map(|value| value.trim().to_string())
+            .filter(|value| !value.is_empty())
+    }
+
+    fn read_u64(sys: &str, attribute: &str) -> u64 {
+        read_sys(sys, attribute).and_then(|value| value.parse().ok()).unwrap_or(0)
     }
 }
 
@@ -229,6 +735,29 @@ pub fn get_system_drives() -> io::Result<Vec<DriveInfo>> {
     ));
 }
 
+/// Every physical disk attached to the system, whether or not it holds volumes
+pub fn get_physical_disks() -> io::Result<Vec<PhysicalDisk>> {
+    #[cfg(windows)]
+    return windows_impl::get_physical_disks();
+
+    #[cfg(unix)]
+    return unix_impl::get_physical_disks();
+
+    #[cfg(not(any(windows, unix)))]
+    return Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        "Platform not supported"
+    ));
+}
+
+/// The physical disk holding the volume mounted at `volume` (e.g. "E:\")
+pub fn physical_disk_for_volume(volume: &str) -> Option<PhysicalDisk> {
+    get_physical_disks()
+        .ok()?
+        .into_iter()
+        .find(|disk| disk.volumes.iter().any(|v| v.eq_ignore_ascii_case(volume)))
+}
+
 // Cross-platform device access functions
 pub fn can_access_device_directly(device_path: &str) -> bool {
     #[cfg(windows)]
@@ -307,4 +836,55 @@ pub fn get_device_path_for_sanitization(drive_info: &DriveInfo) -> String {
     
     #[cfg(not(any(windows, unix)))]
     drive_info.path.clone()
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_storage_descriptor() {
+        let mut buffer = vec![0u8; 96];
+        buffer[10] = 1; // RemovableMedia
+        buffer[16..20].copy_from_slice(&40u32.to_le_bytes()); // ProductIdOffset
+        buffer[20..24].copy_from_slice(&64u32.to_le_bytes()); // ProductRevisionOffset
+        buffer[24..28].copy_from_slice(&72u32.to_le_bytes()); // SerialNumberOffset
+        buffer[28..32].copy_from_slice(&0x0Bu32.to_le_bytes()); // BusTypeSata
+        buffer[40..58].copy_from_slice(b"Samsung SSD 870   ");
+        buffer[64..70].copy_from_slice(b"SVT01B");
+        buffer[72..87].copy_from_slice(b"S5Y1NX0R123456 ");
+
+        let descriptor = parse_storage_descriptor(&buffer).unwrap();
+        assert!(descriptor.removable);
+        assert_eq!(descriptor.vendor, "");
+        assert_eq!(descriptor.product, "Samsung SSD 870");
+        assert_eq!(descriptor.revision, "SVT01B");
+        assert_eq!(descriptor.serial, "S5Y1NX0R123456");
+        assert_eq!(bus_type_name(descriptor.bus_type), "SATA");
+        assert!(parse_storage_descriptor(&buffer[..20]).is_none());
+    }
+
+    #[test]
+    fn test_parse_volume_disk_extents() {
+        let mut buffer = vec![0u8; 8 + 24 * 2];
+        buffer[0..4].copy_from_slice(&2u32.to_le_bytes());
+        buffer[8..12].copy_from_slice(&1u32.to_le_bytes());
+        buffer[32..36].copy_from_slice(&3u32.to_le_bytes());
+        assert_eq!(parse_volume_disk_extents(&buffer), vec![1, 3]);
+        assert!(parse_volume_disk_extents(&[]).is_empty());
+    }
+
+    #[test]
+    fn test_linux_device_metadata_parsing() {
+        assert_eq!(parse_scheduler("none [mq-deadline] kyber bfq\n"), Some("mq-deadline".to_string()));
+        assert_eq!(parse_scheduler("none"), None);
+
+        let udev = parse_udev_properties("S:disk/by-id/ata-X\nE:ID_BUS=ata\nE:ID_SERIAL_SHORT=S5Y1NX0R\nE:ID_WWN=0x5002538e\n");
+        assert_eq!(udev.get("ID_BUS").map(String::as_str), Some("ata"));
+        assert_eq!(udev.get("ID_SERIAL_SHORT").map(String::as_str), Some("S5Y1NX0R"));
+        assert!(!udev.contains_key("disk/by-id/ata-X"));
+
+        assert_eq!(dm_kind("CRYPT-LUKS2-1a2b3c-luks-root"), DiskKind::DmCrypt);
+        assert_eq!(dm_kind("mpath-3600508b400105e210000900000490000"), DiskKind::Multipath);
+        assert_eq!(dm_kind("LVM-abc"), DiskKind::DeviceMapper);
+    }
+}
@@ -0,0 +1,355 @@
+//! Python bindings, published as the `safewipe` package
+//!
+//! Built with the `python` feature by maturin from `pyproject.toml`:
+//!
+//! ```text
+//! maturin develop --release        # into the active virtualenv
+//! maturin build --release          # a wheel in target/wheels
+//! ```
+//!
+//! ```python
+//! import safewipe
+//!
+//! client = safewipe.Client("config.json")
+//! client.login("operator", "secret")
+//! for disk in client.scan():
+//!     print(disk["path"], disk["model"])
+//! print(client.preflight("/dev/sdb")["issues"])
+//! outcome = client.start_wipe("/dev/sdb", standard="NIST_SP_800_88_R1",
+//!                             progress=lambda p: print(p["percentage"]))
+//! report = client.report(outcome["certificate_path"])
+//! ```
+//!
+//! Structured values cross into Python as dicts and lists shaped like the
+//! engine's JSON. Engine errors are raised as `safewipe.WipeError` with the
+//! error code and message as its arguments. Wipes block the calling thread
+//! but release the GIL; progress callbacks run on the calling thread, and
+//! Ctrl+C or an exception raised by the callback cancels the wipe.
+
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::sync::mpsc::RecvTimeoutError;
+use std::thread;
+use std::time::Duration;
+use pyo3::create_exception;
+use pyo3::exceptions::{PyException, PyValueError};
+use pyo3::prelude::*;
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use uuid::Uuid;
+
+use crate::core::{HistoryFilter, WipeConfiguration, WipeEngine, WipeScope};
+
+create_exception!(safewipe, WipeError, PyException, "An error reported by the wipe engine: (code, message)");
+
+fn wipe_error(error: crate::core::WipeError) -> PyErr {
+    WipeError::new_err((format!("{:?}", error.code), error.message))
+}
+
+/// Convert through JSON so Python sees the same shapes as REST clients
+fn to_py<T: Serialize>(py: Python<'_>, value: &T) -> PyResult<PyObject> {
+    let text = serde_json::to_string(value).map_err(|e| PyValueError::new_err(e.to_string()))?;
+    Ok(py.import("json")?.call_method1("loads", (text,))?.unbind())
+}
+
+/// Enum values such as "HDD" or "nist-800-88-clear", spelled as in JSON
+fn parse_name<T: DeserializeOwned>(value: &str, name: &str) -> PyResult<T> {
+    serde_json::from_value(serde_json::Value::String(value.to_string()))
+        .map_err(|_| PyValueError::new_err(format!("Invalid {}: '{}'", name, value)))
+}
+
+fn from_py<T: DeserializeOwned>(value: &Bound<'_, PyAny>, name: &str) -> PyResult<T> {
+    let text: String = value.py().import("json")?.call_method1("dumps", (value,))?.extract()?;
+    serde_json::from_str(&text).map_err(|e| PyValueError::new_err(format!("Invalid {}: {}", name, e)))
+}
+
+/// The signed certificate and audit report written next to a PDF certificate
+fn report_paths(certificate_path: &str) -> (String, String) {
+    let pdf = Path::new(certificate_path);
+    let file_name = pdf.file_name().map(|name| name.to_string_lossy().to_string()).unwrap_or_default();
+    let signed = pdf.with_extension("json");
+    let audit = pdf.with_file_name(file_name.replacen("certificate_", "audit_", 1)).with_extension("json");
+    (signed.to_string_lossy().to_string(), audit.to_string_lossy().to_string())
+}
+
+fn read_json(path: &str) -> PyResult<serde_json::Value> {
+    let content = std::fs::read_to_string(path)
+        .map_err(|e| WipeError::new_err(("UnknownError".to_string(), format!("Failed to read {}: {}", path, e))))?;
+    serde_json::from_str(&content).map_err(|e| PyValueError::new_err(format!("{}: {}", path, e)))
+}
+
+/// A wipe engine, configured from a JSON `WipeConfiguration` file
+#[pyclass(name = "Client", module = "safewipe")]
+pub struct PyClient {
+    engine: Arc<WipeEngine>,
+}
+
+#[pymethods]
+impl PyClient {
+    #[new]
+    fn new(config_path: &str) -> PyResult<Self> {
+        let content = std::fs::read_to_string(config_path)
+            .map_err(|e| PyValueError::new_err(format!("Failed to read {}: {}", config_path, e)))?;
+        let config: WipeConfiguration = serde_json::from_str(&content)
+            .map_err(|e| PyValueError::new_err(format!("Invalid configuration {}: {}", config_path, e)))?;
+        let engine = WipeEngine::new(config).map_err(wipe_error)?;
+        Ok(Self { engine: Arc::new(engine) })
+    }
+
+    /// Log in as an operator; wipes are recorded under this operator
+    fn login(&self, username: &str, password: &str) -> PyResult<()> {
+        self.engine.login(username, password).map(|_| ()).map_err(wipe_error)
+    }
+
+    /// Log in with an API token issued by an administrator
+    fn login_token(&self, token: &str) -> PyResult<()> {
+        self.engine.login_with_token(token).map(|_| ()).map_err(wipe_error)
+    }
+
+    /// Attached disks, test devices included
+    fn scan(&self, py: Python<'_>) -> PyResult<PyObject> {
+        let disks = py.allow_threads(|| self.engine.scan_devices()).map_err(wipe_error)?;
+        let disks: Vec<serde_json::Value> = disks
+            .iter()
+            .map(|disk| serde_json::json!({
+                "path": disk.path,
+                "description": disk.description(),
+                "vendor": disk.vendor,
+                "model": disk.model,
+                "serial": disk.serial,
+                "bus_type": disk.bus_type,
+                "kind": disk.kind.as_str(),
+                "virtual": disk.kind.is_virtual(),
+                "size_bytes": disk.size_bytes,
+                "removable": disk.removable,
+                "is_root_disk": disk.is_root_disk,
+                "volumes": disk.volumes,
+            }))
+            .collect();
+        to_py(py, &disks)
+    }
+
+    /// Dry-run every safety check for wiping `device`
+    #[pyo3(signature = (device, target_type = "HDD", standard = "NIST_SP_800_88_R1"))]
+    fn preflight(&self, py: Python<'_>, device: &str, target_type: &str, standard: &str) -> PyResult<PyObject> {
+        let target_type = parse_name(target_type, "target_type")?;
+        let standard = parse_name(standard, "standard")?;
+        let report = py.allow_threads(|| self.engine.preflight(device, target_type, standard));
+        to_py(py, &report)
+    }
+
+    /// Wipe `device`, blocking until the wipe ends. `progress` is called with
+    /// each progress update. Returns the operation id, result and certificate path.
+    #[pyo3(signature = (
+        device,
+        target_type = "HDD",
+        standard = "NIST_SP_800_88_R1",
+        *,
+        scope = None,
+        verify = true,
+        certificate = true,
+        profile = None,
+        confirmation = None,
+        simulate = false,
+        skip_trim = false,
+        security_state = None,
+        priority = "Normal",
+        progress = None,
+    ))]
+    #[allow(clippy::too_many_arguments)]
+    fn start_wipe(
+        &self,
+        py: Python<'_>,
+        device: String,
+        target_type: &str,
+        standard: &str,
+        scope: Option<&Bound<'_, PyAny>>,
+        verify: bool,
+        certificate: bool,
+        profile: Option<&str>,
+        confirmation: Option<String>,
+        simulate: bool,
+        skip_trim: bool,
+        security_state: Option<&str>,
+        priority: &str,
+        progress: Option<PyObject>,
+    ) -> PyResult<PyObject> {
+        let operator = self.engine.current_operator().ok_or_else(|| {
+            wipe_error(crate::core::WipeError {
+                code: crate::core::WipeErrorCode::NotAuthenticated,
+                message: "Log in with login() or login_token() first".to_string(),
+                sector: None,
+            })
+        })?;
+        let scope: WipeScope = match scope {
+            Some(scope) => from_py(scope, "scope")?,
+            None => WipeScope::WholeDevice,
+        };
+        let mut request = WipeEngine::build_request(
+            device,
+            parse_name(target_type, "target_type")?,
+            scope,
+            parse_name(standard, "standard")?,
+            verify,
+            certificate,
+        );
+        if let Some(profile) = profile {
+            request.profile = Some(parse_name(profile, "profile")?);
+        }
+        request.confirmation = confirmation;
+        request.simulate = simulate;
+        request.skip_trim = skip_trim;
+        if let Some(security_state) = security_state {
+            request.security_state = Some(parse_name(security_state, "security_state")?);
+        }
+        request.priority = parse_name(priority, "priority")?;
+        let operation_id = request.id;
+
+        let updates = Mutex::new(self.engine.subscribe_progress());
+        let engine = self.engine.clone();
+        let wipe_operator = operator.clone();
+        let worker = thread::spawn(move || engine.execute_request_as(&wipe_operator, request));
+
+        // Deliver progress until the wipe ends; an interrupt or a failing
+        // callback cancels the wipe and is raised once it has stopped
+        let mut interrupted: Option<PyErr> = None;
+        loop {
+            let update = py.allow_threads(|| updates.lock().unwrap().recv_timeout(Duration::from_millis(200)));
+            match update {
+                Ok(update) if update.request_id == operation_id && interrupted.is_none() => {
+                    if let Some(ref callback) = progress
+                        && let Err(e) = to_py(py, &update).and_then(|update| callback.call1(py, (update,)))
+                    {
+                        interrupted = Some(e);
+                        let _ = self.engine.cancel_wipe_as(&operator, operation_id);
+                    }
+                }
+                Ok(_) => {}
+                Err(RecvTimeoutError::Timeout) if !worker.is_finished() => {
+                    if interrupted.is_none()
+                        && let Err(e) = py.check_signals()
+                    {
+                        interrupted = Some(e);
+                        let _ = self.engine.cancel_wipe_as(&operator, operation_id);
+                    }
+                }
+                Err(_) => break,
+            }
+        }
+
+        let outcome = py
+            .allow_threads(|| worker.join())
+            .map_err(|_| PyException::new_err("The wipe thread panicked"))?;
+        if let Some(e) = interrupted {
+            return Err(e);
+        }
+        let (result, certificate_path) = outcome.map_err(wipe_error)?;
+        to_py(py, &serde_json::json!({
+            "operation_id": operation_id,
+            "result": result,
+            "certificate_path": certificate_path,
+        }))
+    }
+
+    /// Ask a running wipe to stop
+    fn cancel(&self, operation_id: &str) -> PyResult<()> {
+        let operation_id = Uuid::parse_str(operation_id).map_err(|e| PyValueError::new_err(e.to_string()))?;
+        self.engine.cancel_wipe(operation_id).map_err(wipe_error)
+    }
+
+    /// Pause a running wipe at its next block boundary, with its writes
+    /// flushed; returns where it stopped
+    fn pause_operation(&self, py: Python<'_>, operation_id: &str) -> PyResult<PyObject> {
+        let operation_id = Uuid::parse_str(operation_id).map_err(|e| PyValueError::new_err(e.to_string()))?;
+        let checkpoint = py.allow_threads(|| self.engine.pause_operation(operation_id)).map_err(wipe_error)?;
+        to_py(py, &checkpoint)
+    }
+
+    fn resume_operation(&self, operation_id: &str) -> PyResult<()> {
+        let operation_id = Uuid::parse_str(operation_id).map_err(|e| PyValueError::new_err(e.to_string()))?;
+        self.engine.resume_operation(operation_id).map_err(wipe_error)
+    }
+
+    /// The history record of one operation, or None
+    fn operation(&self, py: Python<'_>, operation_id: &str) -> PyResult<PyObject> {
+        let operation_id = Uuid::parse_str(operation_id).map_err(|e| PyValueError::new_err(e.to_string()))?;
+        let record = self
+            .engine
+            .list_operations_history(&HistoryFilter::default())
+            .into_iter()
+            .find(|record| record.operation_id == operation_id);
+        to_py(py, &record)
+    }
+
+    /// What happened during an operation, oldest first: passes, retried
+    /// writes, throttling, pauses, verification and how it ended
+    fn operation_events(&self, py: Python<'_>, operation_id: &str) -> PyResult<PyObject> {
+        let operation_id = Uuid::parse_str(operation_id).map_err(|e| PyValueError::new_err(e.to_string()))?;
+        to_py(py, &self.engine.get_operation_events(operation_id).map_err(wipe_error)?)
+    }
+
+    /// Contents of the report backup directory, with files that no longer
+    /// match its manifest
+    fn backup_status(&self, py: Python<'_>) -> PyResult<PyObject> {
+        to_py(py, &self.engine.get_backup_status().map_err(wipe_error)?)
+    }
+
+    /// Past operations, newest first
+    #[pyo3(signature = (device = None, limit = None))]
+    fn history(&self, py: Python<'_>, device: Option<String>, limit: Option<usize>) -> PyResult<PyObject> {
+        let filter = HistoryFilter { target_path: device, limit, ..HistoryFilter::default() };
+        to_py(py, &self.engine.list_operations_history(&filter))
+    }
+
+    /// Certificates of past wipes, newest first
+    #[pyo3(signature = (device = None, serial = None, limit = None))]
+    fn list_reports(
+        &self,
+        py: Python<'_>,
+        device: Option<String>,
+        serial: Option<String>,
+        limit: Option<usize>,
+    ) -> PyResult<PyObject> {
+        let filter = HistoryFilter { target_path: device, serial, limit, ..HistoryFilter::default() };
+        to_py(py, &self.engine.list_reports(&filter))
+    }
+
+    /// One certificate from `list_reports` with the operation it was issued for
+    fn open_report(&self, py: Python<'_>, report_id: &str) -> PyResult<PyObject> {
+        to_py(py, &self.engine.open_report(report_id).map_err(wipe_error)?)
+    }
+
+    /// The signed certificate, audit report and verification verdict of a
+    /// wipe, from the certificate path `start_wipe` returned
+    fn report(&self, py: Python<'_>, certificate_path: &str) -> PyResult<PyObject> {
+        let (signed, audit) = report_paths(certificate_path);
+        let verdict = self.engine.verify_certificate(&signed).map_err(wipe_error)?;
+        to_py(py, &serde_json::json!({
+            "certificate": read_json(&signed)?,
+            "audit": read_json(&audit).ok(),
+            "verdict": verdict,
+            "pdf_path": certificate_path,
+        }))
+    }
+}
+
+#[pymodule]
+#[pyo3(name = "_native")]
+fn safewipe_native(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_class::<PyClient>()?;
+    m.add("WipeError", m.py().get_type::<WipeError>())?;
+    m.add("__version__", env!("CARGO_PKG_VERSION"))?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_report_paths_sit_next_to_the_pdf() {
+        let (signed, audit) = report_paths("reports/certificate_WD-123_20250101_120000.pdf");
+        assert_eq!(signed, "reports/certificate_WD-123_20250101_120000.json");
+        assert_eq!(audit, "reports/audit_WD-123_20250101_120000.json");
+    }
+}
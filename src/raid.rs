@@ -0,0 +1,303 @@
+//! Hardware RAID awareness
+//!
+//! Drives behind a hardware RAID controller (Broadcom MegaRAID, Dell PERC, HPE
+//! Smart Array) are hidden from the host; the operating system only sees the
+//! virtual disk the controller builds from them. Overwriting a virtual disk
+//! leaves parity, hot spares, controller metadata and remapped sectors on the
+//! member drives untouched, so it does not sanitize them.
+//!
+//! This module recognises virtual disks, lists their member drives through the
+//! controller CLI (`storcli` / `perccli`), and can ask the controller to erase a
+//! member drive once it has been released from its virtual disk.
+
+use std::io;
+use std::process::Command;
+use tracing::info;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// Controller CLIs tried in order; PERC controllers ship a rebranded storcli
+const CONTROLLER_TOOLS: &[&str] = &["storcli64", "storcli", "perccli64", "perccli"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum RaidVendor {
+    MegaRaid,
+    Perc,
+    SmartArray,
+    Adaptec,
+}
+
+impl RaidVendor {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RaidVendor::MegaRaid => "Broadcom MegaRAID",
+            RaidVendor::Perc => "Dell PERC",
+            RaidVendor::SmartArray => "HPE Smart Array",
+            RaidVendor::Adaptec => "Adaptec / Microchip",
+        }
+    }
+}
+
+/// A physical drive behind a virtual disk, as reported by the controller
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct RaidMember {
+    pub controller: u32,
+    /// Enclosure id and slot, e.g. "252:0"
+    pub enclosure_slot: String,
+    pub device_id: Option<u32>,
+    pub model: String,
+    pub size: String,
+    pub media: String,
+    pub state: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VirtualDiskInfo {
+    pub device_path: String,
+    pub vendor: RaidVendor,
+    pub model: String,
+    /// Controller and virtual drive number, when the controller CLI is available
+    pub controller: Option<u32>,
+    pub virtual_drive: Option<u32>,
+    pub raid_level: Option<String>,
+    pub members: Vec<RaidMember>,
+}
+
+impl VirtualDiskInfo {
+    /// Operator guidance for sanitizing the member drives
+    pub fn guidance(&self) -> String {
+        match self.vendor {
+            RaidVendor::MegaRaid | RaidVendor::Perc => format!(
+                "Delete the virtual disk and erase each member through the controller \
+                 (storcli /cX/eY/sZ start erase), or switch the controller to HBA/JBOD mode \
+                 and wipe the members individually{}",
+                if self.members.is_empty() { "; install storcli/perccli to list the members" } else { "" }
+            ),
+            RaidVendor::SmartArray => "Delete the logical drive with ssacli and enable HBA mode, \
+                 then wipe each physical drive individually".to_string(),
+            RaidVendor::Adaptec => "Delete the logical device with arcconf and expose the drives \
+                 as raw devices, then wipe each one individually".to_string(),
+        }
+    }
+}
+
+/// Controller-side erase offered by MegaRAID / PERC for unconfigured drives
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum ControllerEraseMode {
+    /// One pass
+    Simple,
+    /// Three passes
+    Normal,
+    /// Nine passes
+    Thorough,
+}
+
+impl ControllerEraseMode {
+    fn as_arg(&self) -> &'static str {
+        match self {
+            ControllerEraseMode::Simple => "simple",
+            ControllerEraseMode::Normal => "normal",
+            ControllerEraseMode::Thorough => "thorough",
+        }
+    }
+}
+
+/// Recognise a RAID virtual disk from its SCSI INQUIRY vendor and product strings
+pub fn identify_raid_vendor(vendor: &str, model: &str) -> Option<RaidVendor> {
+    let vendor = vendor.trim().to_uppercase();
+    let model = model.trim().to_uppercase();
+
+    if model.contains("PERC") {
+        Some(RaidVendor::Perc)
+    } else if matches!(vendor.as_str(), "LSI" | "AVAGO" | "BROADCOM" | "MEGARAID")
+        || model.starts_with("MR9") || model.contains("MEGARAID")
+    {
+        Some(RaidVendor::MegaRaid)
+    } else if model.contains("LOGICAL VOLUME") {
+        Some(RaidVendor::SmartArray)
+    } else if vendor == "ADAPTEC" || vendor == "ASR" {
+        Some(RaidVendor::Adaptec)
+    } else {
+        None
+    }
+}
+
+/// Return the virtual disk details when `device_path` is a hardware RAID volume
+pub fn detect_virtual_disk(device_path: &str) -> Option<VirtualDiskInfo> {
+    let (vendor_id, model) = device_identity(device_path)?;
+    let vendor = identify_raid_vendor(&vendor_id, &model)?;
+
+    info!(device = %device_path, vendor = vendor.as_str(), model = %model, "RAID virtual disk");
+
+    let mut info = VirtualDiskInfo {
+        device_path: device_path.to_string(),
+        vendor,
+        model,
+        controller: None,
+        virtual_drive: None,
+        raid_level: None,
+        members: Vec::new(),
+    };
+
+    if matches!(vendor, RaidVendor::MegaRaid | RaidVendor::Perc)
+        && let Some(output) = run_controller_tool(&["/call/vall", "show", "all", "J"])
+        && let Some(found) = parse_storcli_virtual_disks(&output)
+            .into_iter()
+            .find(|vd| same_device(&vd.device_path, device_path))
+    {
+        info.controller = found.controller;
+        info.virtual_drive = found.virtual_drive;
+        info.raid_level = found.raid_level;
+        info.members = found.members;
+        println!("   {} member drive(s) behind {}", info.members.len(), device_path);
+    }
+
+    Some(info)
+}
+
+/// Ask the controller to erase a member drive. The drive must first be removed
+/// from its virtual disk (state "UGood"); the erase runs in the background.
+pub fn erase_member(member: &RaidMember, mode: ControllerEraseMode) -> io::Result<()> {
+    let target = format!("/c{}/e{}", member.controller, member.enclosure_slot.replace(':', "/s"));
+    info!(mode = mode.as_arg(), target = %target, "Starting controller erase");
+
+    let output = run_controller_tool(&[&target, "start", "erase", mode.as_arg(), "J"]).ok_or_else(|| {
+        io::Error::new(io::ErrorKind::NotFound, "storcli / perccli not found")
+    })?;
+
+    let json: Value = serde_json::from_str(&output)
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, format!("Unexpected controller output: {}", e)))?;
+    let status = &json["Controllers"][0]["Command Status"];
+    if status["Status"].as_str() != Some("Success") {
+        let detail = status["Detailed Status"][0]["ErrMsg"]
+            .as_str()
+            .or_else(|| status["Description"].as_str())
+            .unwrap_or("unknown error");
+        return Err(io::Error::other(format!("Controller refused to erase {}: {}", target, detail)));
+    }
+
+    info!(target = %target, "Controller erase started");
+    Ok(())
+}
+
+/// Parse `storcli /call/vall show all J` into virtual disks with their members
+pub fn parse_storcli_virtual_disks(output: &str) -> Vec<VirtualDiskInfo> {
+    let Ok(json) = serde_json::from_str::<Value>(output) else {
+        return Vec::new();
+    };
+    let mut disks = Vec::new();
+
+    for controller in json["Controllers"].as_array().into_iter().flatten() {
+        let controller_id = controller["Command Status"]["Controller"].as_u64().map(|c| c as u32);
+        let Some(response) = controller["Response Data"].as_object() else {
+            continue;
+        };
+
+        // Keys look like "/c0/v1", "PDs for VD 1" and "VD1 Properties"
+        for (key, value) in response {
+            let Some(vd) = key.split_once("/v").and_then(|(_, v)| v.parse::<u32>().ok()) else {
+                continue;
+            };
+
+            let properties = &response[&format!("VD{} Properties", vd)];
+            let members = response
+                .get(&format!("PDs for VD {}", vd))
+                .and_then(Value::as_array)
+                .into_iter()
+                .flatten()
+                .map(|pd| RaidMember {
+                    controller: controller_id.unwrap_or(0),
+                    enclosure_slot: pd["EID:Slt"].as_str().unwrap_or_default().to_string(),
+                    device_id: pd["DID"].as_u64().map(|d| d as u32),
+                    model: pd["Model"].as_str().unwrap_or_default().trim().to_string(),
+                    size: pd["Size"].as_str().unwrap_or_default().to_string(),
+                    media: pd["Med"].as_str().unwrap_or_default().to_string(),
+                    state: pd["State"].as_str().unwrap_or_default().to_string(),
+                })
+                .collect();
+
+            disks.push(VirtualDiskInfo {
+                device_path: properties["OS Drive Name"].as_str().unwrap_or_default().to_string(),
+                vendor: RaidVendor::MegaRaid,
+                model: value[0]["Name"].as_str().unwrap_or_default().to_string(),
+                controller: controller_id,
+                virtual_drive: Some(vd),
+                raid_level: value[0]["TYPE"].as_str().map(str::to_string),
+                members,
+            });
+        }
+    }
+
+    disks
+}
+
+fn run_controller_tool(args: &[&str]) -> Option<String> {
+    CONTROLLER_TOOLS.iter().find_map(|tool| {
+        Command::new(tool)
+            .args(args)
+            .output()
+            .ok()
+            .map(|output| String::from_utf8_lossy(&output.stdout).to_string())
+            .filter(|stdout| !stdout.trim().is_empty())
+    })
+}
+
+fn same_device(reported: &str, device_path: &str) -> bool {
+    !reported.is_empty()
+        && (reported == device_path
+            || std::fs::canonicalize(device_path).is_ok_and(|path| path.to_string_lossy() == reported))
+}
+
+/// SCSI INQUIRY vendor and product, from sysfs where available
+fn device_identity(device_path: &str) -> Option<(String, String)> {
+    #[cfg(target_os = "linux")]
+    {
+        let name = std::path::Path::new(device_path).file_name()?.to_string_lossy().to_string();
+        let read_attr = |attr: &str| std::fs::read_to_string(format!("/sys/block/{}/device/{}", name, attr)).ok();
+        if let (Some(vendor), Some(model)) = (read_attr("vendor"), read_attr("model")) {
+            return Some((vendor.trim().to_string(), model.trim().to_string()));
+        }
+    }
+
+    crate::scsi_commands::ScsiInterface::new(device_path)
+        .inquiry()
+        .ok()
+        .map(|inquiry| (inquiry.vendor, inquiry.product))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_identify_raid_vendor() {
+        assert_eq!(identify_raid_vendor("DELL", "PERC H730P Mini"), Some(RaidVendor::Perc));
+        assert_eq!(identify_raid_vendor("AVAGO", "MR9361-8i"), Some(RaidVendor::MegaRaid));
+        assert_eq!(identify_raid_vendor("HP", "LOGICAL VOLUME"), Some(RaidVendor::SmartArray));
+        assert_eq!(identify_raid_vendor("ATA", "Samsung SSD 870"), None);
+    }
+
+    #[test]
+    fn test_parse_storcli_virtual_disks() {
+        let output = r#"{"Controllers":[{
+            "Command Status":{"Controller":0,"Status":"Success"},
+            "Response Data":{
+                "/c0/v0":[{"DG/VD":"0/0","TYPE":"RAID1","State":"Optl","Name":"data"}],
+                "PDs for VD 0":[
+                    {"EID:Slt":"252:0","DID":8,"State":"Onln","Size":"931.0 GB","Med":"HDD","Model":"ST1000NM0033    "},
+                    {"EID:Slt":"252:1","DID":9,"State":"Onln","Size":"931.0 GB","Med":"HDD","Model":"ST1000NM0033    "}
+                ],
+                "VD0 Properties":{"OS Drive Name":"/dev/sdb"}
+            }
+        }]}"#;
+
+        let disks = parse_storcli_virtual_disks(output);
+        assert_eq!(disks.len(), 1);
+        assert_eq!(disks[0].device_path, "/dev/sdb");
+        assert_eq!(disks[0].raid_level.as_deref(), Some("RAID1"));
+        assert_eq!(disks[0].members.len(), 2);
+        assert_eq!(disks[0].members[1].enclosure_slot, "252:1");
+        assert_eq!(disks[0].members[1].device_id, Some(9));
+        assert_eq!(disks[0].members[0].model, "ST1000NM0033");
+    }
+}
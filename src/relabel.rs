@@ -0,0 +1,189 @@
+//! Preparing a wiped drive for reuse
+//!
+//! Drives that go straight back into service need a partition table before
+//! anything can use them. After a whole-device wipe the sanitizer can write a
+//! fresh GPT or MBR, create one partition over the usable space and
+//! quick-format it with the system's own tools. Every step is recorded in the
+//! wipe result; a step that fails is reported but does not fail the wipe.
+
+use std::fs::OpenOptions;
+use std::io::{Seek, SeekFrom};
+#[cfg(any(target_os = "linux", windows))]
+use std::process::Command;
+#[cfg(target_os = "linux")]
+use std::time::{Duration, Instant};
+use tracing::{info, warn};
+use serde::{Deserialize, Serialize};
+
+use crate::core::PostProcessingStep;
+use crate::partitions::{write_partition_table, PartitionContent, PartitionTableKind};
+
+/// How long to wait for the system to create the new partition's device node
+#[cfg(target_os = "linux")]
+const PARTITION_NODE_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RelabelOptions {
+    pub table: PartitionTableKind,
+    /// Create a single partition over the usable space; otherwise the table is left empty
+    #[serde(default = "default_true")]
+    pub create_partition: bool,
+    /// Quick-format the partition
+    #[serde(default)]
+    pub filesystem: Option<Filesystem>,
+    #[serde(default)]
+    pub label: Option<String>,
+}
+
+fn default_true() -> bool { true }
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum Filesystem {
+    Ntfs,
+    ExFat,
+    Ext4,
+}
+
+impl Filesystem {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Filesystem::Ntfs => "NTFS",
+            Filesystem::ExFat => "exFAT",
+            Filesystem::Ext4 => "ext4",
+        }
+    }
+
+    fn partition_content(&self) -> PartitionContent {
+        match self {
+            Filesystem::Ntfs | Filesystem::ExFat => PartitionContent::BasicData,
+            Filesystem::Ext4 => PartitionContent::Linux,
+        }
+    }
+}
+
+/// Write the table and partition described by `options` to `device_path`,
+/// then format the partition when `format` is set. Simulated wipes pass
+/// `false`: their image is not a disk the system tools can format.
+pub fn relabel(device_path: &str, options: &RelabelOptions, sector_size: u64, format: bool) -> Vec<PostProcessingStep> {
+    let table_name = match options.table {
+        PartitionTableKind::Gpt => "GPT",
+        PartitionTableKind::Mbr => "MBR",
+    };
+    let content = options.filesystem.map_or(PartitionContent::BasicData, |fs| fs.partition_content());
+
+    let written = OpenOptions::new()
+        .read(true)
+        .write(true)
+        .open(device_path)
+        .and_then(|mut device| {
+            let total_sectors = device.seek(SeekFrom::End(0))? / sector_size;
+            write_partition_table(&mut device, options.table, sector_size, total_sectors, options.create_partition.then_some(content))
+        });
+    let (success, detail) = match &written {
+        Ok(Some(partition)) => (
+            true,
+            format!("Partition 1: sectors {}-{}", partition.first_lba, partition.first_lba + partition.sector_count - 1),
+        ),
+        Ok(None) => (true, "Empty partition table".to_string()),
+        Err(e) => (false, e.to_string()),
+    };
+    let mut steps = vec![PostProcessingStep { step: format!("Write {}", table_name), success, detail }];
+
+    if let (Ok(Some(_)), Some(filesystem), true) = (&written, options.filesystem, format) {
+        let label = options.label.as_deref().unwrap_or("");
+        let (success, detail) = match format_partition(device_path, filesystem, label) {
+            Ok(detail) => (true, detail),
+            Err(detail) => (false, detail),
+        };
+        steps.push(PostProcessingStep {
+            step: format!("Format {}", filesystem.name()),
+            success,
+            detail,
+        });
+    }
+
+    for step in &steps {
+        if step.success {
+            info!(step = %step.step, detail = %step.detail, "Post-processing step done");
+        } else {
+            warn!(step = %step.step, detail = %step.detail, "Post-processing step failed");
+        }
+    }
+    steps
+}
+
+/// Path of the first partition of `device_path`: sdb → sdb1, nvme0n1 → nvme0n1p1
+#[cfg(target_os = "linux")]
+fn first_partition_path(device_path: &str) -> String {
+    if device_path.ends_with(|c: char| c.is_ascii_digit()) {
+        format!("{}p1", device_path)
+    } else {
+        format!("{}1", device_path)
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn format_partition(device_path: &str, filesystem: Filesystem, label: &str) -> Result<String, String> {
+    // Have the kernel pick up the new table, then wait for udev to create the node
+    let _ = Command::new("blockdev").args(["--rereadpt", device_path]).status();
+    let partition = first_partition_path(device_path);
+    let started = Instant::now();
+    while !std::path::Path::new(&partition).exists() {
+        if started.elapsed() > PARTITION_NODE_TIMEOUT {
+            return Err(format!("{} did not appear after re-reading the partition table", partition));
+        }
+        std::thread::sleep(Duration::from_millis(250));
+    }
+
+    let (tool, mut args) = match filesystem {
+        Filesystem::Ntfs => ("mkfs.ntfs", vec!["--quick", "--force"]),
+        Filesystem::ExFat => ("mkfs.exfat", vec![]),
+        Filesystem::Ext4 => ("mkfs.ext4", vec!["-F"]),
+    };
+    if !label.is_empty() {
+        args.extend(["-L", label]);
+    }
+    args.push(&partition);
+
+    let output = Command::new(tool)
+        .args(&args)
+        .output()
+        .map_err(|e| format!("Failed to run {}: {}", tool, e))?;
+    if !output.status.success() {
+        return Err(format!("{} failed: {}", tool, String::from_utf8_lossy(&output.stderr).trim()));
+    }
+    Ok(format!("Quick-formatted {}", partition))
+}
+
+#[cfg(windows)]
+fn format_partition(device_path: &str, filesystem: Filesystem, label: &str) -> Result<String, String> {
+    let Some(disk_number) = device_path.trim_start_matches(r"\\.\").strip_prefix("PhysicalDrive") else {
+        return Err(format!("{} is not a physical drive", device_path));
+    };
+    let file_system = match filesystem {
+        Filesystem::Ntfs => "NTFS",
+        Filesystem::ExFat => "exFAT",
+        Filesystem::Ext4 => return Err("Windows cannot format ext4".to_string()),
+    };
+
+    let script = format!(
+        "Update-Disk -Number {0}; Get-Partition -DiskNumber {0} -PartitionNumber 1 | \
+         Format-Volume -FileSystem {1} -NewFileSystemLabel '{2}' -Confirm:$false | Out-Null",
+        disk_number,
+        file_system,
+        label.replace('\'', "''")
+    );
+    let output = Command::new("powershell")
+        .args(["-NoProfile", "-Command", &script])
+        .output()
+        .map_err(|e| format!("Failed to run PowerShell: {}", e))?;
+    if !output.status.success() {
+        return Err(format!("Format-Volume failed: {}", String::from_utf8_lossy(&output.stderr).trim()));
+    }
+    Ok(format!("Quick-formatted partition 1 of disk {}", disk_number))
+}
+
+#[cfg(not(any(target_os = "linux", windows)))]
+fn format_partition(_device_path: &str, _filesystem: Filesystem, _label: &str) -> Result<String, String> {
+    Err("Formatting is not supported on this platform".to_string())
+}
@@ -0,0 +1,115 @@
+//! Drive snapshots taken around a wipe
+//!
+//! Before the first pass and after verification the sanitizer samples the
+//! device it writes: partition table, filesystem and encryption signatures,
+//! sample entropy (see `crate::residual`) and SMART health. The two
+//! snapshots and the verification read-back statistics go into the wipe
+//! result and the signed certificate, so a customer can see the drive held
+//! an NTFS volume before and only zeros after. Snapshots only read.
+
+use chrono::Utc;
+use std::io;
+
+use crate::partitions::{read_partition_table, PartitionTableKind};
+use crate::residual::analyze_residual_data;
+use crate::security::{DriveSnapshot, PartitionSummary, SignatureFound, SmartSummary, VerificationSampling};
+use crate::smart::{read_device_health, HealthStatus};
+use crate::verification::VerificationReport;
+
+/// Sample `device_path` as it is now; SMART is read only when `read_smart`
+/// is set, since image files have none
+pub fn capture_snapshot(device_path: &str, read_smart: bool) -> io::Result<DriveSnapshot> {
+    let residual = analyze_residual_data(device_path)?;
+    let table = read_partition_table(device_path).ok().flatten();
+
+    let smart = read_smart
+        .then(|| read_device_health(device_path))
+        .filter(|health| health.health_status != HealthStatus::Unknown)
+        .map(|health| SmartSummary {
+            health: health.health_status.as_str().to_string(),
+            power_on_hours: health.power_on_hours,
+            reallocated_sectors: health.reallocated_sectors,
+            pending_sectors: health.pending_sectors,
+            percent_used: health.percent_used,
+        });
+
+    Ok(DriveSnapshot {
+        captured_at: Utc::now(),
+        partition_table: table.as_ref().map(|table| match table.kind {
+            PartitionTableKind::Mbr => "MBR".to_string(),
+            PartitionTableKind::Gpt => "GPT".to_string(),
+        }),
+        partitions: table
+            .iter()
+            .flat_map(|table| table.partitions.iter().map(move |partition| (table.sector_size, partition)))
+            .map(|(sector_size, partition)| {
+                let (offset, size_bytes) = partition.byte_range(sector_size);
+                PartitionSummary { number: partition.number, offset, size_bytes, name: partition.name.clone() }
+            })
+            .collect(),
+        // The partition table is listed on its own
+        signatures: residual
+            .signatures
+            .iter()
+            .filter(|signature| !signature.name.ends_with("partition table"))
+            .map(|signature| SignatureFound {
+                name: signature.name.clone(),
+                offset: signature.offset,
+                encrypted: signature.encrypted,
+            })
+            .collect(),
+        samples: residual.samples,
+        bytes_sampled: residual.bytes_sampled,
+        zero_fraction: residual.zero_fraction,
+        uniform_fraction: residual.uniform_fraction,
+        high_entropy_fraction: residual.high_entropy_fraction,
+        structured_fraction: residual.structured_fraction,
+        mean_entropy: residual.mean_entropy,
+        classification: residual.classification.as_str().to_string(),
+        smart,
+    })
+}
+
+/// Read-back statistics of a verification, for the certificate
+pub fn verification_sampling(report: &VerificationReport) -> VerificationSampling {
+    let read: Vec<_> = report.regions.iter().filter(|region| region.bytes_read > 0).collect();
+    VerificationSampling {
+        policy: report.policy.description(),
+        bytes_verified: report.bytes_verified,
+        coverage: report.coverage,
+        regions: report.regions.len() as u64,
+        failed_regions: report.failed_regions().count() as u64,
+        mean_entropy: if read.is_empty() {
+            0.0
+        } else {
+            read.iter().map(|region| region.entropy).sum::<f64>() / read.len() as f64
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn test_snapshot_sees_the_wipe() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("evidence.img");
+        let mut image = vec![0x5Au8; 4 * 1024 * 1024];
+        image[3..11].copy_from_slice(b"NTFS    ");
+        fs::write(&path, &image).unwrap();
+        let device = path.to_string_lossy().into_owned();
+
+        let before = capture_snapshot(&device, false).unwrap();
+        assert_eq!(before.signatures.len(), 1);
+        assert_eq!(before.signatures[0].name, "NTFS");
+        assert!(before.smart.is_none());
+
+        fs::write(&path, vec![0u8; image.len()]).unwrap();
+        let after = capture_snapshot(&device, false).unwrap();
+        assert!(after.signatures.is_empty());
+        assert_eq!(after.zero_fraction, 1.0);
+        assert_eq!(after.classification, "Blank");
+    }
+}
@@ -1,6 +1,8 @@
 // Reporting module
 pub mod audit;
 pub mod compliance;
+pub mod evidence;
 
 pub use audit::*;
-pub use compliance::*;
\ No newline at end of file
+pub use compliance::*;
+pub use evidence::*;
\ No newline at end of file
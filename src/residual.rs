@@ -0,0 +1,282 @@
+//! Pre-wipe residual data analysis
+//!
+//! Samples a drive and reports whether it already looks sanitized, holds
+//! encrypted data, or still contains user data. Intended for triaging a batch
+//! of drives before committing hours to wiping each one.
+
+use std::fs::File;
+use std::io::{self, Read, Seek, SeekFrom};
+use tracing::info;
+use serde::{Deserialize, Serialize};
+use crate::verification::shannon_entropy;
+use crate::partitions::{read_partition_table, PartitionTableKind};
+
+/// Number of evenly spaced samples taken across the drive
+const SAMPLE_COUNT: u64 = 256;
+/// Bytes read per sample
+const SAMPLE_BYTES: u64 = 64 * 1024;
+/// Samples are aligned to this boundary so they never straddle a physical sector
+const SAMPLE_ALIGNMENT: u64 = 4096;
+/// Bytes read from the start of the drive and of each partition for signature detection
+const HEADER_BYTES: usize = 128 * 1024;
+/// Random or encrypted data scores close to 8 bits per byte
+const HIGH_ENTROPY_THRESHOLD: f64 = 7.9;
+/// Fraction of samples that must agree for the drive to be classified as blank or random
+const CLASSIFICATION_THRESHOLD: f64 = 0.95;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum ResidualClassification {
+    /// Zeros or a uniform fill throughout; the drive appears already sanitized
+    Blank,
+    /// Random-looking data with no readable structure: a random overwrite or an
+    /// encrypted volume without a recognizable header
+    HighEntropy,
+    /// An encrypted container header was found
+    Encrypted,
+    /// Filesystems or structured data are present
+    UserData,
+    /// The samples disagree and no signature was found
+    Indeterminate,
+}
+
+impl ResidualClassification {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ResidualClassification::Blank => "Blank",
+            ResidualClassification::HighEntropy => "High entropy",
+            ResidualClassification::Encrypted => "Encrypted",
+            ResidualClassification::UserData => "Contains user data",
+            ResidualClassification::Indeterminate => "Indeterminate",
+        }
+    }
+}
+
+/// A filesystem, encryption header or partition table found on the drive
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DataSignature {
+    pub name: String,
+    pub offset: u64,
+    pub encrypted: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResidualDataReport {
+    pub device_path: String,
+    pub total_bytes: u64,
+    pub bytes_sampled: u64,
+    pub samples: u64,
+    /// Fractions of samples that were all zeros, a uniform non-zero fill,
+    /// high entropy, or structured data; they sum to 1.0
+    pub zero_fraction: f64,
+    pub uniform_fraction: f64,
+    pub high_entropy_fraction: f64,
+    pub structured_fraction: f64,
+    /// Mean Shannon entropy of the samples in bits per byte
+    pub mean_entropy: f64,
+    pub signatures: Vec<DataSignature>,
+    pub classification: ResidualClassification,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum SampleKind {
+    Zero,
+    Uniform,
+    HighEntropy,
+    Structured,
+}
+
+fn classify_sample(data: &[u8]) -> (SampleKind, f64) {
+    let mut histogram = [0u64; 256];
+    for &byte in data {
+        histogram[byte as usize] += 1;
+    }
+    let entropy = shannon_entropy(&histogram);
+
+    let kind = match data.first() {
+        Some(&first) if histogram[first as usize] == data.len() as u64 => {
+            if first == 0 { SampleKind::Zero } else { SampleKind::Uniform }
+        }
+        _ if entropy >= HIGH_ENTROPY_THRESHOLD => SampleKind::HighEntropy,
+        _ => SampleKind::Structured,
+    };
+
+    (kind, entropy)
+}
+
+/// (name, offset within the header, magic, encrypted)
+const SIGNATURES: &[(&str, usize, &[u8], bool)] = &[
+    ("LUKS", 0, b"LUKS\xba\xbe", true),
+    ("BitLocker", 3, b"-FVE-FS-", true),
+    ("NTFS", 3, b"NTFS    ", false),
+    ("exFAT", 3, b"EXFAT   ", false),
+    ("FAT32", 82, b"FAT32   ", false),
+    ("FAT12/16", 54, b"FAT1", false),
+    ("XFS", 0, b"XFSB", false),
+    ("APFS", 32, b"NXSB", false),
+    ("HFS+", 1024, b"H+", false),
+    ("ext2/3/4", 1080, &[0x53, 0xEF], false),
+    ("Btrfs", 0x10040, b"_BHRfS_M", false),
+];
+
+/// Find filesystem and encryption signatures in a header read at `base_offset`
+pub fn detect_signatures(header: &[u8], base_offset: u64) -> Vec<DataSignature> {
+    SIGNATURES
+        .iter()
+        .filter(|(_, offset, magic, _)| header.get(*offset..offset + magic.len()) == Some(*magic))
+        .map(|(name, _, _, encrypted)| DataSignature {
+            name: name.to_string(),
+            offset: base_offset,
+            encrypted: *encrypted,
+        })
+        .collect()
+}
+
+fn read_at(device: &mut File, offset: u64, buffer: &mut [u8]) -> io::Result<usize> {
+    device.seek(SeekFrom::Start(offset))?;
+    let mut filled = 0;
+    while filled < buffer.len() {
+        match device.read(&mut buffer[filled..])? {
+            0 => break,
+            n => filled += n,
+        }
+    }
+    Ok(filled)
+}
+
+/// Sample `device_path` and classify what it holds. Only reads; nothing is written.
+pub fn analyze_residual_data(device_path: &str) -> io::Result<ResidualDataReport> {
+    info!(device = %device_path, "Analyzing residual data");
+
+    let mut device = File::open(device_path)?;
+    let total_bytes = device.seek(SeekFrom::End(0))?;
+
+    // Signatures: whole-device header, then the start of every partition
+    let mut header = vec![0u8; HEADER_BYTES];
+    let header_len = read_at(&mut device, 0, &mut header)?;
+    header.truncate(header_len);
+
+    let mut signatures = detect_signatures(&header, 0);
+    let mut partitions = Vec::new();
+    if let Ok(Some(table)) = read_partition_table(device_path) {
+        let kind = match table.kind {
+            PartitionTableKind::Mbr => "MBR",
+            PartitionTableKind::Gpt => "GPT",
+        };
+        signatures.push(DataSignature {
+            name: format!("{} partition table", kind),
+            offset: 0,
+            encrypted: false,
+        });
+        partitions = table.partitions.iter().map(|p| p.byte_range(table.sector_size).0).collect();
+    }
+    for offset in partitions.into_iter().filter(|&o| o < total_bytes) {
+        let mut partition_header = vec![0u8; HEADER_BYTES];
+        let len = read_at(&mut device, offset, &mut partition_header)?;
+        signatures.extend(detect_signatures(&partition_header[..len], offset));
+    }
+
+    // Entropy: evenly spaced samples across the whole drive
+    let sample_count = SAMPLE_COUNT.min(total_bytes / SAMPLE_BYTES).max(1);
+    let stride = total_bytes / sample_count;
+    let mut buffer = vec![0u8; SAMPLE_BYTES as usize];
+    let mut counts = [0u64; 4];
+    let mut entropy_sum = 0.0;
+    let mut bytes_sampled = 0u64;
+    let mut samples = 0u64;
+
+    for index in 0..sample_count {
+        let offset = (index * stride) / SAMPLE_ALIGNMENT * SAMPLE_ALIGNMENT;
+        let len = read_at(&mut device, offset, &mut buffer)?;
+        if len == 0 {
+            continue;
+        }
+
+        let (kind, entropy) = classify_sample(&buffer[..len]);
+        counts[kind as usize] += 1;
+        entropy_sum += entropy;
+        bytes_sampled += len as u64;
+        samples += 1;
+    }
+
+    let fraction = |kind: SampleKind| {
+        if samples == 0 { 0.0 } else { counts[kind as usize] as f64 / samples as f64 }
+    };
+
+    let mut report = ResidualDataReport {
+        device_path: device_path.to_string(),
+        total_bytes,
+        bytes_sampled,
+        samples,
+        zero_fraction: fraction(SampleKind::Zero),
+        uniform_fraction: fraction(SampleKind::Uniform),
+        high_entropy_fraction: fraction(SampleKind::HighEntropy),
+        structured_fraction: fraction(SampleKind::Structured),
+        mean_entropy: if samples == 0 { 0.0 } else { entropy_sum / samples as f64 },
+        signatures,
+        classification: ResidualClassification::Indeterminate,
+    };
+    report.classification = classify(&report);
+
+    info!(
+        device = %device_path,
+        classification = report.classification.as_str(),
+        zero_fraction = report.zero_fraction,
+        uniform_fraction = report.uniform_fraction,
+        high_entropy_fraction = report.high_entropy_fraction,
+        structured_fraction = report.structured_fraction,
+        mean_entropy = report.mean_entropy,
+        "Residual data analysed"
+    );
+
+    Ok(report)
+}
+
+fn classify(report: &ResidualDataReport) -> ResidualClassification {
+    if report.signatures.iter().any(|s| s.encrypted) {
+        return ResidualClassification::Encrypted;
+    }
+    // A partition table alone is not user data, but a filesystem is
+    if report.signatures.iter().any(|s| !s.name.ends_with("partition table")) {
+        return ResidualClassification::UserData;
+    }
+
+    if report.zero_fraction + report.uniform_fraction >= CLASSIFICATION_THRESHOLD {
+        ResidualClassification::Blank
+    } else if report.high_entropy_fraction >= CLASSIFICATION_THRESHOLD {
+        ResidualClassification::HighEntropy
+    } else if report.structured_fraction > 1.0 - CLASSIFICATION_THRESHOLD {
+        ResidualClassification::UserData
+    } else {
+        ResidualClassification::Indeterminate
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_classify_sample() {
+        assert_eq!(classify_sample(&[0u8; 4096]).0, SampleKind::Zero);
+        assert_eq!(classify_sample(&[0xFFu8; 4096]).0, SampleKind::Uniform);
+        assert_eq!(classify_sample(b"The quick brown fox jumps over the lazy dog").0, SampleKind::Structured);
+
+        let random: Vec<u8> = (0..65536).map(|_| rand::random::<u8>()).collect();
+        assert_eq!(classify_sample(&random).0, SampleKind::HighEntropy);
+    }
+
+    #[test]
+    fn test_detect_signatures() {
+        let mut boot_sector = vec![0u8; 512];
+        boot_sector[3..11].copy_from_slice(b"NTFS    ");
+        let signatures = detect_signatures(&boot_sector, 2048 * 512);
+        assert_eq!(signatures.len(), 1);
+        assert_eq!(signatures[0].name, "NTFS");
+        assert_eq!(signatures[0].offset, 2048 * 512);
+        assert!(!signatures[0].encrypted);
+
+        let mut luks_header = vec![0u8; 512];
+        luks_header[0..6].copy_from_slice(b"LUKS\xba\xbe");
+        assert!(detect_signatures(&luks_header, 0)[0].encrypted);
+    }
+}
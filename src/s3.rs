@@ -0,0 +1,274 @@
+//! S3 uploads of images, certificates and reports
+//!
+//! Just what the tool writes: single PUTs for small files and multipart
+//! uploads for streams of unknown length. Requests are signed with AWS
+//! Signature Version 4 and addressed path-style, so S3-compatible servers
+//! such as MinIO and Ceph work as well. The client needs the "s3" feature;
+//! `S3Location` is always available so configurations load in any build.
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct S3Location {
+    /// e.g. "https://s3.eu-west-1.amazonaws.com" or the URL of a MinIO server
+    pub endpoint: String,
+    pub bucket: String,
+    pub region: String,
+    /// Key prefix objects are written under
+    #[serde(default)]
+    pub prefix: String,
+    /// Environment variables holding the credentials, so they stay out of the configuration
+    pub access_key_env: String,
+    pub secret_key_env: String,
+}
+
+impl S3Location {
+    /// Key of the object `name` under the prefix
+    pub fn key(&self, name: &str) -> String {
+        match self.prefix.trim_matches('/') {
+            "" => name.to_string(),
+            prefix => format!("{}/{}", prefix, name),
+        }
+    }
+
+    /// `s3://bucket/key`, as recorded in manifests and operation records
+    pub fn url(&self, key: &str) -> String {
+        format!("s3://{}/{}", self.bucket, key)
+    }
+}
+
+#[cfg(feature = "s3")]
+pub use client::{Bucket, MultipartUpload};
+
+#[cfg(feature = "s3")]
+mod client {
+    use std::io::{self, Write};
+    use std::time::Duration;
+    use chrono::Utc;
+    use reqwest::Method;
+    use reqwest::blocking::{Client, Response};
+    use ring::hmac;
+    use sha2::{Digest, Sha256};
+
+    use super::S3Location;
+
+    /// Parts must be at least 5 MiB, except the last
+    const PART_SIZE: usize = 16 * 1024 * 1024;
+    const REQUEST_TIMEOUT: Duration = Duration::from_secs(300);
+
+    /// A bucket with the credentials read from the environment
+    pub struct Bucket {
+        client: Client,
+        location: S3Location,
+        access_key: String,
+        secret_key: String,
+    }
+
+    impl Bucket {
+        pub fn connect(location: &S3Location) -> io::Result<Self> {
+            let credential = |var: &str| {
+                std::env::var(var).map_err(|_| io::Error::new(io::ErrorKind::NotFound, format!("{} is not set", var)))
+            };
+            Ok(Self {
+                client: Client::builder().timeout(REQUEST_TIMEOUT).build().map_err(io::Error::other)?,
+                access_key: credential(&location.access_key_env)?,
+                secret_key: credential(&location.secret_key_env)?,
+                location: S3Location { endpoint: location.endpoint.trim_end_matches('/').to_string(), ..location.clone() },
+            })
+        }
+
+        /// Store `body` as `key` in one request
+        pub fn put_object(&self, key: &str, body: Vec<u8>) -> io::Result<()> {
+            self.send(key, Method::PUT, &[], body).map(|_| ())
+        }
+
+        fn send(&self, key: &str, method: Method, query: &[(&str, &str)], body: Vec<u8>) -> io::Result<Response> {
+            let endpoint = reqwest::Url::parse(&self.location.endpoint).map_err(io::Error::other)?;
+            let host = match (endpoint.host_str(), endpoint.port()) {
+                (Some(host), Some(port)) => format!("{}:{}", host, port),
+                (Some(host), None) => host.to_string(),
+                (None, _) => return Err(io::Error::other(format!("{} has no host", self.location.endpoint))),
+            };
+            let path = uri_encode(&format!("/{}/{}", self.location.bucket, key), false);
+            let mut pairs: Vec<(String, String)> =
+                query.iter().map(|(k, v)| (uri_encode(k, true), uri_encode(v, true))).collect();
+            pairs.sort();
+            let canonical_query = pairs.iter().map(|(k, v)| format!("{}={}", k, v)).collect::<Vec<_>>().join("&");
+
+            let amz_date = Utc::now().format("%Y%m%dT%H%M%SZ").to_string();
+            let payload_hash = hex::encode(Sha256::digest(&body));
+            let authorization = self.authorization(method.as_str(), &path, &canonical_query, &host, &payload_hash, &amz_date);
+
+            let url = match canonical_query.as_str() {
+                "" => format!("{}{}", self.location.endpoint, path),
+                query => format!("{}{}?{}", self.location.endpoint, path, query),
+            };
+            let response = self
+                .client
+                .request(method, url)
+                .header("x-amz-date", amz_date)
+                .header("x-amz-content-sha256", payload_hash)
+                .header("authorization", authorization)
+                .body(body)
+                .send()
+                .map_err(io::Error::other)?;
+            if !response.status().is_success() {
+                let status = response.status();
+                let text = response.text().unwrap_or_default();
+                return Err(io::Error::other(format!(
+                    "S3 returned {}: {}",
+                    status,
+                    xml_value(&text, "Message").unwrap_or(&text)
+                )));
+            }
+            Ok(response)
+        }
+
+        /// The `Authorization` header of a request signed with Signature Version 4
+        fn authorization(
+            &self,
+            method: &str,
+            path: &str,
+            canonical_query: &str,
+            host: &str,
+            payload_hash: &str,
+            amz_date: &str,
+        ) -> String {
+            const SIGNED_HEADERS: &str = "host;x-amz-content-sha256;x-amz-date";
+            let canonical_request = format!(
+                "{}\n{}\n{}\nhost:{}\nx-amz-content-sha256:{}\nx-amz-date:{}\n\n{}\n{}",
+                method, path, canonical_query, host, payload_hash, amz_date, SIGNED_HEADERS, payload_hash
+            );
+            let date = &amz_date[..8];
+            let scope = format!("{}/{}/s3/aws4_request", date, self.location.region);
+            let string_to_sign = format!(
+                "AWS4-HMAC-SHA256\n{}\n{}\n{}",
+                amz_date,
+                scope,
+                hex::encode(Sha256::digest(canonical_request.as_bytes()))
+            );
+
+            let sign = |key: &[u8], data: &str| hmac::sign(&hmac::Key::new(hmac::HMAC_SHA256, key), data.as_bytes());
+            let mut key = sign(format!("AWS4{}", self.secret_key).as_bytes(), date);
+            for part in [self.location.region.as_str(), "s3", "aws4_request"] {
+                key = sign(key.as_ref(), part);
+            }
+            let signature = hex::encode(sign(key.as_ref(), &string_to_sign).as_ref());
+            format!(
+                "AWS4-HMAC-SHA256 Credential={}/{}, SignedHeaders={}, Signature={}",
+                self.access_key, scope, SIGNED_HEADERS, signature
+            )
+        }
+    }
+
+    /// An object written in parts as it is produced. Dropping the upload
+    /// before `complete` aborts it.
+    pub struct MultipartUpload {
+        bucket: Bucket,
+        key: String,
+        upload_id: String,
+        part: Vec<u8>,
+        etags: Vec<String>,
+        completed: bool,
+    }
+
+    impl MultipartUpload {
+        pub fn start(bucket: Bucket, key: String) -> io::Result<Self> {
+            let response = bucket.send(&key, Method::POST, &[("uploads", "")], Vec::new())?;
+            let body = response.text().map_err(io::Error::other)?;
+            let upload_id = xml_value(&body, "UploadId")
+                .ok_or_else(|| io::Error::other("S3 did not return an upload id"))?
+                .to_string();
+            Ok(Self { bucket, key, upload_id, part: Vec::with_capacity(PART_SIZE), etags: Vec::new(), completed: false })
+        }
+
+        /// `s3://bucket/key` of the object being written
+        pub fn url(&self) -> String {
+            self.bucket.location.url(&self.key)
+        }
+
+        /// Upload what is buffered and assemble the object
+        pub fn complete(&mut self) -> io::Result<()> {
+            if !self.part.is_empty() || self.etags.is_empty() {
+                self.upload_part()?;
+            }
+            let parts: String = self
+                .etags
+                .iter()
+                .enumerate()
+                .map(|(i, etag)| format!("<Part><PartNumber>{}</PartNumber><ETag>{}</ETag></Part>", i + 1, etag))
+                .collect();
+            let body = format!("<CompleteMultipartUpload>{}</CompleteMultipartUpload>", parts).into_bytes();
+            let query = [("uploadId", self.upload_id.as_str())];
+            let response = self.bucket.send(&self.key, Method::POST, &query, body)?;
+            // Completion can fail after the 200 status line was sent
+            let text = response.text().map_err(io::Error::other)?;
+            if text.contains("<Error>") {
+                return Err(io::Error::other(format!(
+                    "S3 could not complete the upload: {}",
+                    xml_value(&text, "Message").unwrap_or(&text)
+                )));
+            }
+            self.completed = true;
+            Ok(())
+        }
+
+        fn upload_part(&mut self) -> io::Result<()> {
+            let body = std::mem::replace(&mut self.part, Vec::with_capacity(PART_SIZE));
+            let number = (self.etags.len() + 1).to_string();
+            let query = [("partNumber", number.as_str()), ("uploadId", self.upload_id.as_str())];
+            let response = self.bucket.send(&self.key, Method::PUT, &query, body)?;
+            let etag = response
+                .headers()
+                .get("etag")
+                .and_then(|etag| etag.to_str().ok())
+                .ok_or_else(|| io::Error::other(format!("S3 returned no ETag for part {}", number)))?;
+            self.etags.push(etag.to_string());
+            Ok(())
+        }
+    }
+
+    impl Write for MultipartUpload {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            let taken = buf.len().min(PART_SIZE - self.part.len());
+            self.part.extend_from_slice(&buf[..taken]);
+            if self.part.len() == PART_SIZE {
+                self.upload_part()?;
+            }
+            Ok(taken)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    impl Drop for MultipartUpload {
+        fn drop(&mut self) {
+            // Parts of an abandoned upload are billed until it is aborted
+            if !self.completed {
+                let query = [("uploadId", self.upload_id.as_str())];
+                let _ = self.bucket.send(&self.key, Method::DELETE, &query, Vec::new());
+            }
+        }
+    }
+
+    /// Percent-encode everything but the unreserved characters (and `/` unless `encode_slash`)
+    fn uri_encode(value: &str, encode_slash: bool) -> String {
+        let mut encoded = String::with_capacity(value.len());
+        for byte in value.bytes() {
+            match byte {
+                b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => encoded.push(byte as char),
+                b'/' if !encode_slash => encoded.push('/'),
+                _ => encoded.push_str(&format!("%{:02X}", byte)),
+            }
+        }
+        encoded
+    }
+
+    fn xml_value<'a>(xml: &'a str, tag: &str) -> Option<&'a str> {
+        let start = xml.find(&format!("<{}>", tag))? + tag.len() + 2;
+        let end = xml[start..].find(&format!("</{}>", tag))?;
+        Some(&xml[start..start + end])
+    }
+}
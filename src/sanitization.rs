@@ -6,6 +6,7 @@ use std::thread;
 use std::time::Instant;
 use rand::Rng;
 use rayon::prelude::*;
+use tracing::{debug, error, info, warn};
 // use crate::hpa_dco::{HpaDcoDetector, ComprehensiveDriveInfo}; // Temporarily disabled
 
 #[derive(Debug, Clone)]
@@ -15,6 +16,7 @@ pub enum SanitizationMethod {
     SecureErase,        // ATA Secure Erase (Normal)
     EnhancedSecureErase, // ATA Secure Erase (Enhanced)
     ComprehensiveClean, // Full HPA/DCO detection and removal + sanitization
+    FreeSpaceOnly,      // Overwrite unallocated space of mounted filesystems, keep files
 }
 
 #[derive(Debug, Clone)]
@@ -134,10 +136,10 @@ impl DataSanitizer {
     ) -> io::Result<String> {  // Changed return type to String temporarily
         let _path = device_path.as_ref();
         
-        println!("🔍 Starting comprehensive drive analysis...");
+        info!("Starting comprehensive drive analysis...");
         
         // Temporarily disabled HPA/DCO detection - would require additional module
-        println!("📊 Comprehensive clean temporarily using standard purge method");
+        info!("Comprehensive clean temporarily using standard purge method");
         
         self.purge(device_path, progress_callback)?;
         
@@ -153,9 +155,9 @@ impl DataSanitizer {
     ) -> io::Result<()> {
         let device_path = device_path.as_ref();
         
-        println!("🚨 CRITICAL: Starting NIST SP 800-88 PURGE operation on ENTIRE DISK");
-        println!("📝 This will PERMANENTLY DESTROY ALL DATA on {}", device_path.display());
-        println!("🔒 Data will be UNRECOVERABLE after this operation");
+        warn!("CRITICAL: Starting NIST SP 800-88 PURGE operation on ENTIRE DISK");
+        info!("This will PERMANENTLY DESTROY ALL DATA on {}", device_path.display());
+        info!("Data will be UNRECOVERABLE after this operation");
         
         // Try to open device for direct access
         let device_file = match std::fs::OpenOptions::new()
@@ -164,8 +166,8 @@ impl DataSanitizer {
             .open(device_path) {
             Ok(file) => file,
             Err(e) => {
-                println!("❌ Cannot access device directly: {}", e);
-                println!("🔄 Falling back to file-system level sanitization");
+                error!("Cannot access device directly: {}", e);
+                info!("Falling back to file-system level sanitization");
                 return self.sanitize_files_and_free_space_fallback(device_path, 3, progress_callback);
             }
         };
@@ -174,12 +176,12 @@ impl DataSanitizer {
         let device_size = match device_file.metadata() {
             Ok(metadata) => metadata.len(),
             Err(e) => {
-                println!("❌ Cannot determine device size: {}", e);
+                error!("Cannot determine device size: {}", e);
                 return Err(e);
             }
         };
         
-        println!("📊 Device size: {:.2} GB ({} bytes)", 
+        info!("Device size: {:.2} GB ({} bytes)", 
                 device_size as f64 / (1024.0 * 1024.0 * 1024.0), device_size);
         
         // NIST SP 800-88 Purge Method: Multiple passes with different patterns
@@ -190,7 +192,7 @@ impl DataSanitizer {
         ];
         
         for (pass_num, (pass_name, pattern)) in purge_passes.iter().enumerate() {
-            println!("🔄 Starting {}", pass_name);
+            info!("Starting {}", pass_name);
             
             if let Some(ref callback) = progress_callback {
                 callback(SanitizationProgress {
@@ -207,38 +209,38 @@ impl DataSanitizer {
             // Perform the pass
             match self.overwrite_entire_device(&device_file, device_size, pattern, 
                                                                                            (pass_num + 1) as u32, 3, progress_callback.as_ref()) {
-                Ok(_) => println!("✅ {} completed", pass_name),
+                Ok(_) => info!("{} completed", pass_name),
                 Err(e) => {
-                    println!("❌ {} failed: {}", pass_name, e);
+                    error!("{} failed: {}", pass_name, e);
                     return Err(e);
                 }
             }
         }
         
         // Final verification pass (read-only)
-        println!("🔍 Performing final verification...");
+        info!("Performing final verification...");
         match self.verify_disk_sanitization(&device_file, device_size) {
-            Ok(true) => println!("✅ NIST SP 800-88 Purge verification PASSED"),
+            Ok(true) => info!("NIST SP 800-88 Purge verification PASSED"),
             Ok(false) => {
-                println!("⚠️  Verification found potential data remnants");
-                println!("🔄 Performing additional sanitization pass...");
+                warn!("Verification found potential data remnants");
+                info!("Performing additional sanitization pass...");
                 
                 // Additional security pass
                 if let Err(e) = self.overwrite_entire_device(&device_file, device_size, 
                                                            &SanitizationPattern::Random, 4, 4, 
                                                            progress_callback.as_ref()) {
-                    println!("❌ Additional sanitization pass failed: {}", e);
+                    error!("Additional sanitization pass failed: {}", e);
                     return Err(e);
                 }
             },
             Err(e) => {
-                println!("❌ Verification failed: {}", e);
+                error!("Verification failed: {}", e);
                 return Err(e);
             }
         }
         
-        println!("🎯 NIST SP 800-88 PURGE operation completed successfully");
-        println!("🔒 All data has been permanently destroyed and is unrecoverable");
+        info!("NIST SP 800-88 PURGE operation completed successfully");
+        info!("All data has been permanently destroyed and is unrecoverable");
         
         // Generate compliance report
         self.generate_nist_compliance_report(device_path, device_size)?;
@@ -246,6 +248,32 @@ impl DataSanitizer {
         Ok(())
     }
     
+    /// Overwrite only the free space of the filesystems on `device_path` (a device
+    /// or a mount point), leaving existing files intact
+    pub fn free_space_only<P: AsRef<Path>>(&self, device_path: P, passes: u32) -> io::Result<()> {
+        let device_path = device_path.as_ref().to_string_lossy().to_string();
+        let mount_points = crate::free_space::mount_points_for(&device_path);
+        if mount_points.is_empty() {
+            return Err(io::Error::new(io::ErrorKind::NotFound,
+                format!("No mounted filesystem found on {}", device_path)));
+        }
+
+        let wiper = crate::free_space::FreeSpaceWiper::new(passes);
+        for mount_point in mount_points {
+            let mut last_reported = 0u64;
+            wiper.wipe(&mount_point, |progress| {
+                // One line per GiB written
+                if progress.bytes_written >= last_reported + 1024 * 1024 * 1024 {
+                    last_reported = progress.bytes_written;
+                    info!("Progress: Pass {}/{} - {:.2} / {:.2} GB", progress.pass, progress.total_passes,
+                             progress.bytes_written as f64 / (1024.0 * 1024.0 * 1024.0),
+                             progress.bytes_total as f64 / (1024.0 * 1024.0 * 1024.0));
+                }
+            })?;
+        }
+        Ok(())
+    }
+
     /// Fallback method that calls the original file-level sanitization
     pub fn sanitize_files_and_free_space_fallback<P: AsRef<Path>>(
         &self,
@@ -253,7 +281,7 @@ impl DataSanitizer {
         passes: u32,
         progress_callback: Option<Box<dyn Fn(SanitizationProgress)>>,
     ) -> io::Result<()> {
-        println!("🔄 Using file-system level sanitization as fallback");
+        info!("Using file-system level sanitization as fallback");
         self.sanitize_files_and_free_space(drive_root, passes, None)
     }
 
@@ -267,7 +295,7 @@ impl DataSanitizer {
     ) -> io::Result<()> {
         let drive_path = drive_root.as_ref();
         
-        println!("🔧 Starting file-level sanitization on {}", drive_path.display());
+        debug!("Starting file-level sanitization on {}", drive_path.display());
         
         // Check if the drive path exists and is accessible
         if !drive_path.exists() {
@@ -281,42 +309,42 @@ impl DataSanitizer {
         }
         
         // Step 1: Overwrite all existing files
-        println!("🗂️  Phase 1: Overwriting all existing files...");
+        info!("Phase 1: Overwriting all existing files...");
         match self.overwrite_all_files(drive_path, passes) {
-            Ok(_) => println!("✅ File overwriting completed"),
+            Ok(_) => info!("File overwriting completed"),
             Err(e) => {
-                println!("❌ File overwriting failed: {}", e);
+                error!("File overwriting failed: {}", e);
                 return Err(e);
             }
         }
         
         // Step 2: Fill free space with random data
-        println!("💾 Phase 2: Filling free space with random data...");
+        info!("Phase 2: Filling free space with random data...");
         match self.fill_free_space(drive_path, passes) {
-            Ok(_) => println!("✅ Free space filling completed"),
+            Ok(_) => info!("Free space filling completed"),
             Err(e) => {
-                println!("❌ Free space filling failed: {}", e);
+                error!("Free space filling failed: {}", e);
                 return Err(e);
             }
         }
         
-        println!("✅ File-level sanitization completed");
+        info!("File-level sanitization completed");
         Ok(())
     }
 
     /// Recursively overwrite all files in a directory
     fn overwrite_all_files(&self, dir: &Path, passes: u32) -> io::Result<()> {
         if !dir.is_dir() {
-            println!("❌ Path is not a directory: {}", dir.display());
+            error!("Path is not a directory: {}", dir.display());
             return Ok(());
         }
 
-        println!("🔍 Scanning directory: {}", dir.display());
+        info!("Scanning directory: {}", dir.display());
         
         let entries = match read_dir(dir) {
             Ok(entries) => entries,
             Err(e) => {
-                println!("❌ Failed to read directory {}: {}", dir.display(), e);
+                error!("Failed to read directory {}: {}", dir.display(), e);
                 return Err(e);
             }
         };
@@ -328,7 +356,7 @@ impl DataSanitizer {
             let entry = match entry {
                 Ok(entry) => entry,
                 Err(e) => {
-                    println!("❌ Failed to read directory entry: {}", e);
+                    error!("Failed to read directory entry: {}", e);
                     continue;
                 }
             };
@@ -337,33 +365,33 @@ impl DataSanitizer {
 
             if path.is_dir() {
                 dir_count += 1;
-                println!("📁 Processing subdirectory: {}", path.display());
+                debug!("Processing subdirectory: {}", path.display());
                 // Recursively process subdirectories
                 if let Err(e) = self.overwrite_all_files(&path, passes) {
-                    println!("❌ Failed to process subdirectory {}: {}", path.display(), e);
+                    error!("Failed to process subdirectory {}: {}", path.display(), e);
                 }
             } else if path.is_file() {
                 file_count += 1;
-                println!("📄 Found file: {}", path.display());
+                debug!("Found file: {}", path.display());
                 
                 // Overwrite the file multiple times
                 for pass in 1..=passes {
-                    println!("  🔄 Pass {}/{}: Overwriting {}", pass, passes, path.display());
+                    info!("Pass {}/{}: Overwriting {}", pass, passes, path.display());
                     if let Err(e) = self.overwrite_single_file(&path) {
-                        println!("  ❌ Failed to overwrite {}: {}", path.display(), e);
+                        error!("Failed to overwrite {}: {}", path.display(), e);
                         continue;
                     }
                 }
                 
                 // Delete the file after overwriting
                 match remove_file(&path) {
-                    Ok(_) => println!("  ✅ Deleted: {}", path.display()),
-                    Err(e) => println!("  ❌ Failed to delete {}: {}", path.display(), e),
+                    Ok(_) => info!("Deleted: {}", path.display()),
+                    Err(e) => error!("Failed to delete {}: {}", path.display(), e),
                 }
             }
         }
         
-        println!("📊 Directory scan complete: {} files, {} subdirectories processed", file_count, dir_count);
+        info!("Directory scan complete: {} files, {} subdirectories processed", file_count, dir_count);
         Ok(())
     }
 
@@ -413,7 +441,7 @@ impl DataSanitizer {
         let start_time = Instant::now();
         
         for pass in 1..=passes {
-            println!("🚀 Pass {}/{}: Optimized free space filling on {}", pass, passes, drive_path.display());
+            info!("Pass {}/{}: Optimized free space filling on {}", pass, passes, drive_path.display());
             
             // Create a temporary directory for our fill files
             let temp_dir = drive_path.join("__sanitize_temp__");
@@ -467,14 +495,14 @@ impl DataSanitizer {
             }).collect();
             
             let total_files: usize = results.iter().sum();
-            println!("    ✅ Created {} fill files in {:.2}s", total_files, start_time.elapsed().as_secs_f64());
+            info!("Created {} fill files in {:.2}s", total_files, start_time.elapsed().as_secs_f64());
 
             // Clean up temporary files (also parallelized)
             if temp_dir.exists() {
                 let _ = std::fs::remove_dir_all(&temp_dir);
             }
         }
-        println!("🎯 Free space filling completed in {:.2}s", start_time.elapsed().as_secs_f64());
+        info!("Free space filling completed in {:.2}s", start_time.elapsed().as_secs_f64());
         Ok(())
     }
 
@@ -506,7 +534,7 @@ impl DataSanitizer {
         
         let total_passes = patterns.len() as u32;
         
-        println!("🚀 Starting optimized sanitization (Target size: {:.2} GB)", 
+        info!("Starting optimized sanitization (Target size: {:.2} GB)", 
                 device_size as f64 / (1024.0 * 1024.0 * 1024.0));
         
         // Open device with optimized flags
@@ -519,7 +547,7 @@ impl DataSanitizer {
             let current_pass = (pass_num + 1) as u32;
             let pass_start = Instant::now();
             
-            println!("📝 Pass {}/{}: {:?}", current_pass, total_passes, pattern);
+            info!("Pass {}/{}: {:?}", current_pass, total_passes, pattern);
             
             // Use optimized writing strategy
             if device_size > CHUNK_SIZE as u64 && self.thread_count > 1 {
@@ -530,10 +558,10 @@ impl DataSanitizer {
                 self.sanitize_device_sequential(&mut device, device_size, pattern, current_pass, total_passes, &progress_callback)?;
             }
             
-            println!("✅ Pass {} completed in {:.2}s", current_pass, pass_start.elapsed().as_secs_f64());
+            info!("Pass {} completed in {:.2}s", current_pass, pass_start.elapsed().as_secs_f64());
         }
         
-        println!("🎯 Total sanitization completed in {:.2}s", start_time.elapsed().as_secs_f64());
+        info!("Total sanitization completed in {:.2}s", start_time.elapsed().as_secs_f64());
         Ok(())
     }
 
@@ -606,7 +634,7 @@ impl DataSanitizer {
         total_passes: u32,
         progress_callback: &Option<Box<dyn Fn(SanitizationProgress)>>,
     ) -> io::Result<()> {
-        println!("🔄 Using parallel processing with {} threads", self.thread_count);
+        info!("Using parallel processing with {} threads", self.thread_count);
         
         // Calculate optimal chunk distribution
         let chunks_count = (device_size + CHUNK_SIZE as u64 - 1) / CHUNK_SIZE as u64;
@@ -803,7 +831,7 @@ impl DataSanitizer {
         // Seek to beginning of device
         file.seek(SeekFrom::Start(0))?;
         
-        println!("📝 Pass {}/{}: Writing pattern to {} bytes in {} chunks", 
+        info!("Pass {}/{}: Writing pattern to {} bytes in {} chunks", 
                 current_pass, total_passes, device_size, 
                 (device_size + chunk_size as u64 - 1) / chunk_size as u64);
         
@@ -838,7 +866,7 @@ impl DataSanitizer {
                             std::time::Duration::from_secs(0)
                         };
                         
-                        println!("📊 Pass {}/{}: {:.1}% complete - {:.2} GB processed - {:.1} MB/s - ETA: {:?}", 
+                        info!("Pass {}/{}: {:.1}% complete - {:.2} GB processed - {:.1} MB/s - ETA: {:?}", 
                                 current_pass, total_passes, percentage, 
                                 bytes_written as f64 / (1024.0 * 1024.0 * 1024.0),
                                 speed_mbps, eta);
@@ -857,7 +885,7 @@ impl DataSanitizer {
                     }
                 }
                 Err(e) => {
-                    println!("❌ Write failed at byte {}: {}", bytes_written, e);
+                    error!("Write failed at byte {}: {}", bytes_written, e);
                     return Err(e);
                 }
             }
@@ -866,7 +894,7 @@ impl DataSanitizer {
         // Final sync to ensure all data is written to disk
         file.sync_all()?;
         
-        println!("✅ Pass {}/{} completed: {} bytes overwritten", 
+        info!("Pass {}/{} completed: {} bytes overwritten", 
                 current_pass, total_passes, bytes_written);
         
         Ok(())
@@ -882,7 +910,7 @@ impl DataSanitizer {
         let mut buffer = vec![0u8; sample_size];
         let mut suspicious_patterns = 0;
         
-        println!("🔍 Verifying sanitization by sampling {} random locations...", verification_samples);
+        info!("Verifying sanitization by sampling {} random locations...", verification_samples);
         
         use rand::Rng;
         let mut rng = rand::thread_rng();
@@ -900,14 +928,14 @@ impl DataSanitizer {
                     if self.contains_suspicious_patterns(&buffer) {
                         suspicious_patterns += 1;
                         if suspicious_patterns > 10 { // Allow some tolerance for normal random data
-                            println!("⚠️  Verification failed: Found {} suspicious patterns in {} samples", 
+                            warn!("Verification failed: Found {} suspicious patterns in {} samples", 
                                     suspicious_patterns, i + 1);
                             return Ok(false);
                         }
                     }
                 }
                 Err(e) => {
-                    println!("❌ Read verification failed at position {}: {}", position, e);
+                    error!("Read verification failed at position {}: {}", position, e);
                     // Don't fail verification for read errors near end of device
                     if position < device_size - sample_size as u64 {
                         return Err(e);
@@ -917,12 +945,12 @@ impl DataSanitizer {
             
             // Progress update every 100 samples
             if (i + 1) % 100 == 0 {
-                println!("🔍 Verification progress: {}/{} samples checked, {} suspicious patterns found", 
+                info!("Verification progress: {}/{} samples checked, {} suspicious patterns found", 
                         i + 1, verification_samples, suspicious_patterns);
             }
         }
         
-        println!("✅ Verification completed: {}/{} samples checked, {} suspicious patterns found", 
+        info!("Verification completed: {}/{} samples checked, {} suspicious patterns found", 
                 verification_samples, verification_samples, suspicious_patterns);
         
         // Pass verification if we found very few suspicious patterns
@@ -1031,7 +1059,7 @@ impl DataSanitizer {
         writeln!(report_file, "Report saved as: {}", report_filename)?;
         writeln!(report_file, "================================================")?;
         
-        println!("📋 NIST SP 800-88 compliance report generated: {}", report_filename);
+        info!("NIST SP 800-88 compliance report generated: {}", report_filename);
         
         Ok(())
     }
@@ -1039,86 +1067,19 @@ impl DataSanitizer {
 
 /// SSD-specific sanitization using ATA Secure Erase (cross-platform)
 pub mod ssd_sanitization {
-    #[cfg(windows)]
-    use windows::{
-        core::PWSTR,
-        Win32::{
-            Foundation::{CloseHandle, HANDLE},
-            Storage::FileSystem::{CreateFileW, FILE_ATTRIBUTE_NORMAL, FILE_SHARE_NONE, OPEN_EXISTING},
-        },
-    };
+    use crate::ata_commands::AtaInterface;
+    use tracing::{debug, info};
 
     pub fn secure_erase_ssd(drive_path: &str) -> Result<(), Box<dyn std::error::Error>> {
-        #[cfg(windows)]
-        {
-        unsafe {
-            let drive_path_wide: Vec<u16> = drive_path.encode_utf16().chain(std::iter::once(0)).collect();
-            let drive_path_pwstr = PWSTR::from_raw(drive_path_wide.as_ptr() as *mut u16);
+        debug!("Attempting SSD secure erase using native ATA commands...");
 
-            let handle = CreateFileW(
-                drive_path_pwstr,
-                0x40000000u32, // GENERIC_WRITE
-                FILE_SHARE_NONE,             // No sharing
-                None,
-                OPEN_EXISTING,
-                FILE_ATTRIBUTE_NORMAL,
-                HANDLE::default(),
-            )?;
+        // Issues SECURITY SET PASSWORD, ERASE PREPARE and ERASE UNIT through the
+        // platform pass-through interface and waits for the drive to finish
+        let ata = AtaInterface::new(drive_path)?;
+        ata.security_erase(false)?;
 
-            // This is a simplified example - real implementation would need:
-            // 1. Check if drive supports secure erase
-            // 2. Issue SECURITY SET PASSWORD command
-            // 3. Issue SECURITY ERASE UNIT command
-            // 4. Verify completion
-
-            CloseHandle(handle)?;
-            Ok(())
-        }
-        }
-        
-        #[cfg(unix)]
-        {
-            // On Linux, use hdparm for SSD secure erase
-            use std::process::Command;
-            
-            println!("🔧 Attempting SSD secure erase using hdparm...");
-            
-            // First, check if the device supports secure erase
-            let output = Command::new("hdparm")
-                .arg("-I")
-                .arg(drive_path)
-                .output()?;
-                
-            let output_str = String::from_utf8_lossy(&output.stdout);
-            if !output_str.contains("Security") {
-                return Err("Drive does not support ATA security features".into());
-            }
-            
-            // Set password and perform secure erase
-            let _result = Command::new("hdparm")
-                .arg("--user-master")
-                .arg("u")
-                .arg("--security-set-pass")
-                .arg("p")
-                .arg(drive_path)
-                .status()?;
-                
-            let _result = Command::new("hdparm")
-                .arg("--user-master") 
-                .arg("u")
-                .arg("--security-erase")
-                .arg("p")
-                .arg(drive_path)
-                .status()?;
-            
-            println!("✅ SSD secure erase completed");
-            Ok(())
-        }
-        
-        #[cfg(not(any(windows, unix)))]
-        {
-            Err("Platform not supported for SSD secure erase".into())
-        }
+        info!("SSD secure erase completed");
+        Ok(())
     }
 }
 
@@ -1133,6 +1094,7 @@ pub fn sanitize_device_with_size<P: AsRef<Path>>(
     let device_size = size_in_sectors * 512; // Convert sectors to bytes
     
     let patterns = match method {
+        SanitizationMethod::FreeSpaceOnly => return sanitizer.free_space_only(device_path, 1),
         SanitizationMethod::Clear => vec![SanitizationPattern::Zeros],
         SanitizationMethod::Purge => vec![
             SanitizationPattern::Random,
@@ -1151,11 +1113,11 @@ pub fn sanitize_device_with_size<P: AsRef<Path>>(
         ],
     };
     
-    println!("📝 Starting sanitization of {:.2} GB using native capacity", 
+    info!("Starting sanitization of {:.2} GB using native capacity", 
              device_size as f64 / (1024.0 * 1024.0 * 1024.0));
     
     let progress_callback = Some(Box::new(|progress: SanitizationProgress| {
-        println!("Progress: {:.1}% - Pass {}/{} - {:.2} GB processed", 
+        info!("Progress: {:.1}% - Pass {}/{} - {:.2} GB processed", 
                 progress.percentage, 
                 progress.current_pass, 
                 progress.total_passes,
@@ -0,0 +1,426 @@
+/*!
+ * SCSI Command Interface for SAS / Enterprise Drives
+ *
+ * This module provides the SCSI command functionality required for:
+ * - Device identification (INQUIRY, READ CAPACITY(16))
+ * - SANITIZE (overwrite, block erase, cryptographic erase)
+ * - FORMAT UNIT
+ * - SECURITY PROTOCOL IN / OUT
+ * - Progress polling through REQUEST SENSE
+ *
+ * Commands are issued through SG_IO using the `sg_raw` utility from sg3_utils.
+ *
+ * ⚠️ WARNING: SANITIZE and FORMAT UNIT destroy ALL data on the target device.
+ */
+
+use std::io;
+use std::process::Command;
+use std::time::Duration;
+
+// ============================================================================
+// SCSI OPERATION CODES AND CONSTANTS
+// ============================================================================
+
+/// SCSI REQUEST SENSE command (0x03)
+pub const SCSI_REQUEST_SENSE: u8 = 0x03;
+/// SCSI FORMAT UNIT command (0x04)
+pub const SCSI_FORMAT_UNIT: u8 = 0x04;
+/// SCSI INQUIRY command (0x12)
+pub const SCSI_INQUIRY: u8 = 0x12;
+/// SCSI SANITIZE command (0x48)
+pub const SCSI_SANITIZE: u8 = 0x48;
+/// SCSI SERVICE ACTION IN(16) command (0x9E) - READ CAPACITY(16) service action 0x10
+pub const SCSI_SERVICE_ACTION_IN_16: u8 = 0x9E;
+/// SCSI SECURITY PROTOCOL IN command (0xA2)
+pub const SCSI_SECURITY_PROTOCOL_IN: u8 = 0xA2;
+/// SCSI SECURITY PROTOCOL OUT command (0xB5)
+pub const SCSI_SECURITY_PROTOCOL_OUT: u8 = 0xB5;
+
+/// READ CAPACITY(16) service action
+const READ_CAPACITY_16_SA: u8 = 0x10;
+/// Sense key NOT READY - returned while a sanitize/format is in progress
+const SENSE_KEY_NOT_READY: u8 = 0x02;
+/// Sense key RECOVERED ERROR - the command succeeded after a retry
+const SENSE_KEY_RECOVERED_ERROR: u8 = 0x01;
+/// ASC LOGICAL UNIT NOT READY, with ASCQ 0x04 (format) or 0x1B (sanitize) in progress
+const ASC_NOT_READY: u8 = 0x04;
+const ASCQ_FORMAT_IN_PROGRESS: u8 = 0x04;
+const ASCQ_SANITIZE_IN_PROGRESS: u8 = 0x1B;
+/// Default command timeout for short commands (seconds)
+const DEFAULT_TIMEOUT_SECS: u64 = 30;
+
+/// SANITIZE service actions
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SanitizeAction {
+    Overwrite = 0x01,
+    BlockErase = 0x02,
+    CryptoErase = 0x03,
+    ExitFailureMode = 0x1F,
+}
+
+impl SanitizeAction {
+    pub fn as_str(&self) -> &str {
+        match self {
+            SanitizeAction::Overwrite => "SCSI Sanitize Overwrite",
+            SanitizeAction::BlockErase => "SCSI Sanitize Block Erase",
+            SanitizeAction::CryptoErase => "SCSI Sanitize Crypto Erase",
+            SanitizeAction::ExitFailureMode => "SCSI Sanitize Exit Failure Mode",
+        }
+    }
+}
+
+// ============================================================================
+// SCSI DATA STRUCTURES
+// ============================================================================
+
+/// Standard INQUIRY data
+#[derive(Debug, Clone, Default)]
+pub struct InquiryData {
+    pub peripheral_device_type: u8,
+    pub removable: bool,
+    pub vendor: String,
+    pub product: String,
+    pub revision: String,
+}
+
+/// READ CAPACITY(16) data
+#[derive(Debug, Clone, Default)]
+pub struct CapacityData {
+    pub total_blocks: u64,
+    pub block_size: u32,
+    pub protection_enabled: bool,
+}
+
+impl CapacityData {
+    pub fn total_bytes(&self) -> u64 {
+        self.total_blocks * self.block_size as u64
+    }
+}
+
+/// Parse standard INQUIRY response
+pub fn parse_inquiry(data: &[u8]) -> io::Result<InquiryData> {
+    if data.len() < 36 {
+        return Err(io::Error::new(io::ErrorKind::InvalidData, "INQUIRY response too short"));
+    }
+
+    let ascii = |bytes: &[u8]| String::from_utf8_lossy(bytes).trim().to_string();
+
+    Ok(InquiryData {
+        peripheral_device_type: data[0] & 0x1F,
+        removable: data[1] & 0x80 != 0,
+        vendor: ascii(&data[8..16]),
+        product: ascii(&data[16..32]),
+        revision: ascii(&data[32..36]),
+    })
+}
+
+/// Parse READ CAPACITY(16) response
+pub fn parse_read_capacity_16(data: &[u8]) -> io::Result<CapacityData> {
+    if data.len() < 13 {
+        return Err(io::Error::new(io::ErrorKind::InvalidData, "READ CAPACITY(16) response too short"));
+    }
+
+    let mut lba_bytes = [0u8; 8];
+    lba_bytes.copy_from_slice(&data[0..8]);
+
+    Ok(CapacityData {
+        // Returned value is the last LBA, not the block count
+        total_blocks: u64::from_be_bytes(lba_bytes) + 1,
+        block_size: u32::from_be_bytes([data[8], data[9], data[10], data[11]]),
+        protection_enabled: data[12] & 0x01 != 0,
+    })
+}
+
+/// What REQUEST SENSE says about a background sanitize or format
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SenseStatus {
+    /// Still running, with progress (0.0 - 1.0) when the device reports it
+    InProgress(Option<f64>),
+    /// No pending sense: the operation finished
+    Ready,
+    /// The operation failed; the drive may need SANITIZE EXIT FAILURE MODE or a new format
+    Failed { sense_key: u8, asc: u8, ascq: u8 },
+}
+
+/// Decode fixed- or descriptor-format sense data returned by REQUEST SENSE
+pub fn parse_sense_status(sense: &[u8]) -> SenseStatus {
+    let response_code = sense.first().map_or(0, |code| code & 0x7F);
+    let (sense_key, asc, ascq, progress) = match response_code {
+        0x70 | 0x71 if sense.len() >= 14 => {
+            let progress = (sense.len() >= 18 && sense[15] & 0x80 != 0)
+                .then(|| u16::from_be_bytes([sense[16], sense[17]]) as f64 / 65536.0);
+            (sense[2] & 0x0F, sense[12], sense[13], progress)
+        }
+        0x72 | 0x73 if sense.len() >= 4 => (sense[1] & 0x0F, sense[2], sense[3], descriptor_progress(sense)),
+        // No sense data at all
+        _ => return SenseStatus::Ready,
+    };
+
+    match (sense_key, asc, ascq) {
+        (0x00, _, _) | (SENSE_KEY_RECOVERED_ERROR, _, _) => SenseStatus::Ready,
+        (SENSE_KEY_NOT_READY, ASC_NOT_READY, ASCQ_FORMAT_IN_PROGRESS | ASCQ_SANITIZE_IN_PROGRESS) => {
+            SenseStatus::InProgress(progress)
+        }
+        (SENSE_KEY_NOT_READY, _, _) if progress.is_some() => SenseStatus::InProgress(progress),
+        _ => SenseStatus::Failed { sense_key, asc, ascq },
+    }
+}
+
+/// Progress from the sense-key-specific descriptor (type 0x02) of descriptor-format sense data
+fn descriptor_progress(sense: &[u8]) -> Option<f64> {
+    let additional_length = sense.get(7).map_or(0, |length| *length as usize);
+    let mut descriptors = sense.get(8..(8 + additional_length).min(sense.len()))?;
+    while descriptors.len() >= 2 {
+        let length = 2 + descriptors[1] as usize;
+        if descriptors[0] == 0x02 && descriptors.len() >= 7 && descriptors[4] & 0x80 != 0 {
+            return Some(u16::from_be_bytes([descriptors[5], descriptors[6]]) as f64 / 65536.0);
+        }
+        descriptors = descriptors.get(length..)?;
+    }
+    None
+}
+
+// ============================================================================
+// SCSI INTERFACE
+// ============================================================================
+
+pub struct ScsiInterface {
+    device_path: String,
+}
+
+impl ScsiInterface {
+    pub fn new(device_path: &str) -> Self {
+        Self {
+            device_path: device_path.to_string(),
+        }
+    }
+
+    /// Check whether the device sits behind a SAS transport
+    pub fn is_sas(&self) -> bool {
+        #[cfg(target_os = "linux")]
+        {
+            let name = match std::path::Path::new(&self.device_path).file_name() {
+                Some(name) => name.to_string_lossy().to_string(),
+                None => return false,
+            };
+
+            if std::path::Path::new(&format!("/sys/block/{}/device/sas_address", name)).exists() {
+                return true;
+            }
+
+            std::fs::canonicalize(format!("/sys/block/{}", name))
+                .map(|path| path.to_string_lossy().contains("/end_device-"))
+                .unwrap_or(false)
+        }
+
+        #[cfg(not(target_os = "linux"))]
+        {
+            false
+        }
+    }
+
+    /// Issue INQUIRY
+    pub fn inquiry(&self) -> io::Result<InquiryData> {
+        let cdb = [SCSI_INQUIRY, 0x00, 0x00, 0x00, 0x60, 0x00];
+        let data = self.execute_read(&cdb, 96, DEFAULT_TIMEOUT_SECS)?;
+        parse_inquiry(&data)
+    }
+
+    /// Issue READ CAPACITY(16)
+    pub fn read_capacity(&self) -> io::Result<CapacityData> {
+        let cdb = [
+            SCSI_SERVICE_ACTION_IN_16, READ_CAPACITY_16_SA,
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0x00, 0x00, 0x00, 0x20, 0x00, 0x00,
+        ];
+        let data = self.execute_read(&cdb, 32, DEFAULT_TIMEOUT_SECS)?;
+        parse_read_capacity_16(&data)
+    }
+
+    /// Check whether a SANITIZE service action is supported (REPORT SUPPORTED OPERATION CODES)
+    pub fn supports_sanitize(&self, action: SanitizeAction) -> bool {
+        // MAINTENANCE IN / REPORT SUPPORTED OPERATION CODES, reporting options 010b
+        let cdb = [
+            0xA3, 0x0C, 0x02, SCSI_SANITIZE, 0x00, action as u8,
+            0x00, 0x00, 0x00, 0x40, 0x00, 0x00,
+        ];
+
+        match self.execute_read(&cdb, 64, DEFAULT_TIMEOUT_SECS) {
+            // SUPPORT field 011b = supported in conformance with the standard
+            Ok(data) => data.len() > 1 && data[1] & 0x07 == 0x03,
+            Err(_) => false,
+        }
+    }
+
+    /// Start SANITIZE in immediate mode
+    pub fn sanitize(&self, action: SanitizeAction) -> io::Result<()> {
+        if action == SanitizeAction::Overwrite {
+            // Overwrite needs a parameter list: 1 pass, 4-byte zero pattern
+            let parameters = [0x01, 0x00, 0x00, 0x04, 0x00, 0x00, 0x00, 0x00];
+            let cdb = [SCSI_SANITIZE, 0x80 | action as u8, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x08, 0x00];
+            return self.execute_write(&cdb, &parameters, DEFAULT_TIMEOUT_SECS);
+        }
+
+        // IMMED=1 so the command returns and progress is polled through REQUEST SENSE
+        let cdb = [SCSI_SANITIZE, 0x80 | action as u8, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00];
+        self.execute_no_data(&cdb, DEFAULT_TIMEOUT_SECS)
+    }
+
+    /// FORMAT UNIT with default parameters
+    pub fn format_unit(&self) -> io::Result<()> {
+        // FMTDATA=1 with a short header that sets IMMED so the call does not block
+        // for hours; the drive only honours IMMED when FOV is set as well
+        let parameters = [0x00, 0x80 | 0x02, 0x00, 0x00];
+        let cdb = [SCSI_FORMAT_UNIT, 0x10, 0x00, 0x00, 0x00, 0x00];
+        self.execute_write(&cdb, &parameters, DEFAULT_TIMEOUT_SECS)
+    }
+
+    /// REQUEST SENSE - whether a sanitize/format is running, done or failed
+    pub fn poll_progress(&self) -> io::Result<SenseStatus> {
+        let cdb = [SCSI_REQUEST_SENSE, 0x00, 0x00, 0x00, 0x20, 0x00];
+        let sense = self.execute_read(&cdb, 32, DEFAULT_TIMEOUT_SECS)?;
+        Ok(parse_sense_status(&sense))
+    }
+
+    /// Block until a background sanitize/format completes, reporting progress;
+    /// fails when the drive reports that the operation failed
+    pub fn wait_for_completion<F: FnMut(f64)>(&self, mut on_progress: F) -> io::Result<()> {
+        loop {
+            match self.poll_progress()? {
+                SenseStatus::InProgress(progress) => {
+                    if let Some(progress) = progress {
+                        on_progress(progress);
+                    }
+                    std::thread::sleep(Duration::from_secs(5));
+                }
+                SenseStatus::Ready => {
+                    on_progress(1.0);
+                    return Ok(());
+                }
+                SenseStatus::Failed { sense_key, asc, ascq } => {
+                    return Err(io::Error::other(format!(
+                        "Drive reported the operation failed (sense key 0x{:02X}, ASC 0x{:02X}, ASCQ 0x{:02X})",
+                        sense_key, asc, ascq
+                    )));
+                }
+            }
+        }
+    }
+
+    /// SECURITY PROTOCOL IN
+    pub fn security_protocol_in(&self, protocol: u8, sp_specific: u16, length: u32) -> io::Result<Vec<u8>> {
+        let sp = sp_specific.to_be_bytes();
+        let len = length.to_be_bytes();
+        let cdb = [
+            SCSI_SECURITY_PROTOCOL_IN, protocol, sp[0], sp[1], 0x00, 0x00,
+            len[0], len[1], len[2], len[3], 0x00, 0x00,
+        ];
+        self.execute_read(&cdb, length as usize, DEFAULT_TIMEOUT_SECS)
+    }
+
+    /// SECURITY PROTOCOL OUT
+    pub fn security_protocol_out(&self, protocol: u8, sp_specific: u16, data: &[u8]) -> io::Result<()> {
+        let sp = sp_specific.to_be_bytes();
+        let len = (data.len() as u32).to_be_bytes();
+        let cdb = [
+            SCSI_SECURITY_PROTOCOL_OUT, protocol, sp[0], sp[1], 0x00, 0x00,
+            len[0], len[1], len[2], len[3], 0x00, 0x00,
+        ];
+        self.execute_write(&cdb, data, DEFAULT_TIMEOUT_SECS)
+    }
+
+    fn execute_read(&self, cdb: &[u8], length: usize, timeout_secs: u64) -> io::Result<Vec<u8>> {
+        self.sg_raw(&["-r", &length.to_string(), "-b"], cdb, timeout_secs)
+    }
+
+    fn execute_write(&self, cdb: &[u8], data: &[u8], timeout_secs: u64) -> io::Result<()> {
+        // One file per call, so concurrent commands do not overwrite each other's parameters
+        let parameter_file = std::env::temp_dir().join(format!("hdd_tool_scsi_{}.bin", uuid::Uuid::new_v4()));
+        std::fs::write(&parameter_file, data)?;
+
+        let parameter_path = parameter_file.to_string_lossy().to_string();
+        let result = self.sg_raw(
+            &["-s", &data.len().to_string(), "-i", &parameter_path],
+            cdb,
+            timeout_secs,
+        );
+
+        let _ = std::fs::remove_file(&parameter_file);
+        result.map(|_| ())
+    }
+
+    fn execute_no_data(&self, cdb: &[u8], timeout_secs: u64) -> io::Result<()> {
+        self.sg_raw(&[], cdb, timeout_secs).map(|_| ())
+    }
+
+    fn sg_raw(&self, options: &[&str], cdb: &[u8], timeout_secs: u64) -> io::Result<Vec<u8>> {
+        #[cfg(unix)]
+        {
+            let cdb_args: Vec<String> = cdb.iter().map(|b| format!("{:02x}", b)).collect();
+            let output = Command::new("sg_raw")
+                .args(options)
+                .args(["-t", &timeout_secs.to_string(), &self.device_path])
+                .args(&cdb_args)
+                .output()?;
+
+            if output.status.success() {
+                Ok(output.stdout)
+            } else {
+                Err(io::Error::other(format!(
+                    "SCSI command 0x{:02X} failed: {}",
+                    cdb[0],
+                    String::from_utf8_lossy(&output.stderr).trim()
+                )))
+            }
+        }
+
+        #[cfg(not(unix))]
+        {
+            let _ = (options, cdb, timeout_secs);
+            Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                "SCSI pass-through is not available on this platform"
+            ))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fixed_sense(sense_key: u8, asc: u8, ascq: u8, progress: Option<u16>) -> Vec<u8> {
+        let mut sense = vec![0u8; 18];
+        sense[0] = 0x70;
+        sense[2] = sense_key;
+        sense[7] = 10;
+        sense[12] = asc;
+        sense[13] = ascq;
+        if let Some(progress) = progress {
+            sense[15] = 0x80;
+            sense[16..18].copy_from_slice(&progress.to_be_bytes());
+        }
+        sense
+    }
+
+    #[test]
+    fn test_sense_tells_failure_from_completion() {
+        assert_eq!(
+            parse_sense_status(&fixed_sense(SENSE_KEY_NOT_READY, ASC_NOT_READY, ASCQ_SANITIZE_IN_PROGRESS, Some(0x8000))),
+            SenseStatus::InProgress(Some(0.5))
+        );
+        assert_eq!(parse_sense_status(&fixed_sense(0x00, 0x00, 0x00, None)), SenseStatus::Ready);
+        assert_eq!(parse_sense_status(&[]), SenseStatus::Ready);
+        // MEDIUM ERROR, SANITIZE COMMAND FAILED
+        assert_eq!(
+            parse_sense_status(&fixed_sense(0x03, 0x31, 0x03, None)),
+            SenseStatus::Failed { sense_key: 0x03, asc: 0x31, ascq: 0x03 }
+        );
+        // NOT READY, FORMAT COMMAND FAILED is not progress
+        assert!(matches!(parse_sense_status(&fixed_sense(SENSE_KEY_NOT_READY, 0x31, 0x01, None)), SenseStatus::Failed { .. }));
+
+        // Descriptor format with a progress indication descriptor
+        let descriptor = [0x72, 0x02, 0x04, 0x04, 0, 0, 0, 8, 0x02, 0x06, 0, 0, 0x80, 0x40, 0x00, 0];
+        assert_eq!(parse_sense_status(&descriptor), SenseStatus::InProgress(Some(0.25)));
+    }
+}
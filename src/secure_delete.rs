@@ -0,0 +1,251 @@
+//! Targeted secure deletion of files and folders
+//!
+//! For users who only need to destroy specific files rather than a whole disk.
+//! Each file is overwritten in place, renamed to a random name, truncated and
+//! then deleted, so neither its contents nor its name survive in the directory
+//! entry. Directories are shredded recursively and removed.
+//!
+//! Overwriting in place only reaches the original blocks on filesystems that
+//! update data where it lies (NTFS, FAT, ext4 without data journaling). On SSDs
+//! and copy-on-write filesystems earlier copies may remain; use a device-level
+//! purge there.
+
+use std::fs::{self, OpenOptions};
+use std::io::{self, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use tracing::{error, info};
+use rand::{Rng, RngCore};
+use rand::distributions::Alphanumeric;
+use serde::{Deserialize, Serialize};
+
+const WRITE_BLOCK_SIZE: usize = 1024 * 1024;
+/// Allocation unit assumed when the filesystem does not report one
+const DEFAULT_BLOCK_SIZE: u64 = 4096;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum ShredPattern {
+    Zeros,
+    Ones,
+    Random,
+    Byte(u8),
+}
+
+impl ShredPattern {
+    /// Accepts `zeros`, `ones`, `random` or a byte value such as `0xAA`
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.to_ascii_lowercase().as_str() {
+            "zeros" | "zero" => Some(ShredPattern::Zeros),
+            "ones" | "one" => Some(ShredPattern::Ones),
+            "random" => Some(ShredPattern::Random),
+            other => {
+                let hex = other.strip_prefix("0x")?;
+                u8::from_str_radix(hex, 16).ok().map(ShredPattern::Byte)
+            }
+        }
+    }
+
+    fn fill(&self, buffer: &mut [u8]) {
+        match self {
+            ShredPattern::Zeros => buffer.fill(0x00),
+            ShredPattern::Ones => buffer.fill(0xFF),
+            ShredPattern::Byte(byte) => buffer.fill(*byte),
+            ShredPattern::Random => rand::thread_rng().fill_bytes(buffer),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ShredOptions {
+    pub pattern: ShredPattern,
+    pub passes: u32,
+    /// Also overwrite the slack between the end of the file and the end of its
+    /// last allocation unit (cluster / block)
+    pub wipe_slack: bool,
+}
+
+impl ShredOptions {
+    pub fn new(pattern: ShredPattern, passes: u32) -> Self {
+        Self {
+            pattern,
+            passes: passes.max(1),
+            wipe_slack: true,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ShredReport {
+    pub files_shredded: u64,
+    pub directories_removed: u64,
+    pub bytes_overwritten: u64,
+    /// Paths that could not be shredded, with the reason
+    pub failures: Vec<(String, String)>,
+}
+
+impl ShredReport {
+    pub fn success(&self) -> bool {
+        self.failures.is_empty()
+    }
+}
+
+#[cfg(unix)]
+fn allocation_unit(metadata: &fs::Metadata) -> u64 {
+    use std::os::unix::fs::MetadataExt;
+    match metadata.blksize() {
+        0 => DEFAULT_BLOCK_SIZE,
+        size => size,
+    }
+}
+
+#[cfg(not(unix))]
+fn allocation_unit(_metadata: &fs::Metadata) -> u64 {
+    DEFAULT_BLOCK_SIZE
+}
+
+/// Rename `path` to a random name of the same length in the same directory
+fn obscure_name(path: &Path) -> io::Result<PathBuf> {
+    let length = path.file_name().map(|n| n.len()).unwrap_or(8).max(1);
+    let name: String = rand::thread_rng()
+        .sample_iter(&Alphanumeric)
+        .take(length)
+        .map(char::from)
+        .collect();
+
+    let renamed = path.with_file_name(name);
+    fs::rename(path, &renamed)?;
+    Ok(renamed)
+}
+
+/// Overwrite, rename, truncate and delete a single file. Returns the bytes overwritten.
+pub fn shred_file(path: &Path, options: &ShredOptions) -> io::Result<u64> {
+    let metadata = fs::symlink_metadata(path)?;
+    if metadata.file_type().is_symlink() {
+        // Never follow links out of the selected tree; only the link itself goes
+        fs::remove_file(path)?;
+        return Ok(0);
+    }
+
+    let length = metadata.len();
+    let unit = allocation_unit(&metadata);
+    let overwrite_length = if options.wipe_slack {
+        length.div_ceil(unit) * unit
+    } else {
+        length
+    };
+
+    let mut file = OpenOptions::new().write(true).open(path)?;
+    let mut buffer = vec![0u8; WRITE_BLOCK_SIZE];
+    let mut bytes_overwritten = 0u64;
+
+    for _ in 0..options.passes {
+        file.seek(SeekFrom::Start(0))?;
+        let mut position = 0u64;
+        while position < overwrite_length {
+            let chunk = (overwrite_length - position).min(WRITE_BLOCK_SIZE as u64) as usize;
+            options.pattern.fill(&mut buffer[..chunk]);
+            file.write_all(&buffer[..chunk])?;
+            position += chunk as u64;
+        }
+        file.sync_all()?;
+        bytes_overwritten += overwrite_length;
+    }
+
+    file.set_len(0)?;
+    file.sync_all()?;
+    drop(file);
+
+    let renamed = obscure_name(path)?;
+    fs::remove_file(&renamed)?;
+    Ok(bytes_overwritten)
+}
+
+fn shred_path(path: &Path, options: &ShredOptions, report: &mut ShredReport) {
+    let metadata = match fs::symlink_metadata(path) {
+        Ok(metadata) => metadata,
+        Err(e) => {
+            report.failures.push((path.display().to_string(), e.to_string()));
+            return;
+        }
+    };
+
+    if metadata.is_dir() {
+        match fs::read_dir(path) {
+            Ok(entries) => {
+                for entry in entries.flatten() {
+                    shred_path(&entry.path(), options, report);
+                }
+            }
+            Err(e) => {
+                report.failures.push((path.display().to_string(), e.to_string()));
+                return;
+            }
+        }
+
+        match obscure_name(path).and_then(|renamed| fs::remove_dir(&renamed)) {
+            Ok(()) => report.directories_removed += 1,
+            Err(e) => report.failures.push((path.display().to_string(), e.to_string())),
+        }
+    } else {
+        match shred_file(path, options) {
+            Ok(bytes) => {
+                report.files_shredded += 1;
+                report.bytes_overwritten += bytes;
+            }
+            Err(e) => {
+                error!(path = %path.display(), error = %e, "Failed to shred");
+                report.failures.push((path.display().to_string(), e.to_string()));
+            }
+        }
+    }
+}
+
+/// Shred every file and directory in `paths`. Failures are collected in the
+/// report rather than stopping the remaining paths.
+pub fn secure_delete_paths<P: AsRef<Path>>(paths: &[P], options: &ShredOptions) -> ShredReport {
+    let mut report = ShredReport::default();
+
+    for path in paths {
+        let path = path.as_ref();
+        info!(path = %path.display(), passes = options.passes, pattern = ?options.pattern, "Shredding");
+        shred_path(path, options, &mut report);
+    }
+
+    info!(
+        files = report.files_shredded,
+        directories = report.directories_removed,
+        bytes_overwritten = report.bytes_overwritten,
+        failures = report.failures.len(),
+        "Shredding finished"
+    );
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_shred_pattern_parse() {
+        assert_eq!(ShredPattern::parse("zeros"), Some(ShredPattern::Zeros));
+        assert_eq!(ShredPattern::parse("Random"), Some(ShredPattern::Random));
+        assert_eq!(ShredPattern::parse("0xAA"), Some(ShredPattern::Byte(0xAA)));
+        assert_eq!(ShredPattern::parse("AA"), None);
+    }
+
+    #[test]
+    fn test_secure_delete_directory() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().join("shred");
+        fs::create_dir_all(root.join("nested")).unwrap();
+        fs::write(root.join("secret.txt"), b"confidential").unwrap();
+        fs::write(root.join("nested").join("more.bin"), vec![0x42u8; 10_000]).unwrap();
+
+        let report = secure_delete_paths(&[&root], &ShredOptions::new(ShredPattern::Random, 2));
+
+        assert!(report.success(), "{:?}", report.failures);
+        assert_eq!(report.files_shredded, 2);
+        assert_eq!(report.directories_removed, 2);
+        assert!(report.bytes_overwritten >= 2 * (12 + 10_000));
+        assert!(!root.exists());
+    }
+}
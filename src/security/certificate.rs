@@ -1,70 +1,37 @@
-use rsa::{RsaPrivateKey, RsaPublicKey, pkcs1v15::{SigningKey, VerifyingKey}};
-use rsa::signature::{RandomizedSigner, Verifier, SignatureEncoding};
-use sha2::{Sha256, Digest};
+use rsa::{RsaPrivateKey, RsaPublicKey, pkcs1v15::SigningKey};
+use rsa::signature::{RandomizedSigner, SignatureEncoding};
+use sha2::Sha256;
 use base64::{Engine as _, engine::general_purpose};
 use serde::{Deserialize, Serialize};
-use chrono::{DateTime, Utc};
+use chrono::Utc;
 use uuid::Uuid;
+use ring::rand::SystemRandom;
+use ring::signature::{Ed25519KeyPair, KeyPair};
 use std::fs::{self};
 use std::path::Path;
 
-use crate::core::{WipeResult, WipeRequest, SecurityFeatures, WipeError, WipeErrorCode, WipeResult2};
+use crate::core::{WipeResult, WipeRequest, WipeScope, SecurityFeatures, WipeError, WipeErrorCode, WipeResult2};
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct ErasureCertificate {
-    pub certificate_id: Uuid,
-    pub wipe_request_id: Uuid,
-    pub issued_at: DateTime<Utc>,
-    pub issuer: String,
-    pub organization: String,
-    pub drive_info: DriveInfo,
-    pub wipe_details: WipeDetails,
-    pub verification_details: VerificationDetails,
-    pub compliance_standards: Vec<String>,
-    pub signature: String,
-    pub public_key: String,
-    pub certificate_hash: String,
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct DriveInfo {
-    pub model: String,
-    pub serial_number: String,
-    pub firmware_version: String,
-    pub total_capacity_gb: f64,
-    pub native_capacity_gb: f64,
-    pub had_hpa: bool,
-    pub had_dco: bool,
-    pub security_features: Vec<String>,
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct WipeDetails {
-    pub standard_used: String,
-    pub passes_completed: u32,
-    pub sectors_wiped: u64,
-    pub start_time: DateTime<Utc>,
-    pub completion_time: DateTime<Utc>,
-    pub duration_minutes: u64,
-    pub patterns_used: Vec<String>,
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct VerificationDetails {
-    pub verification_performed: bool,
-    pub sectors_verified: u64,
-    pub verification_sample_rate: f64,
-    pub pattern_verification_passed: bool,
-    pub failed_sectors: u64,
-    pub verification_time: Option<DateTime<Utc>>,
-}
+pub use hdd_tool_certificate::{
+    CERTIFICATE_SCHEMA_VERSION, DriveInfo, DriveSnapshot, ErasureCertificate, ErasureEvidence, ForensicImage,
+    FunctionalTest, FunctionalTestKind, PartitionSummary, RecipeReference, SignatureAlgorithm, SignatureFound,
+    SmartSummary, TemplateReference, VerificationDetails, VerificationSampling, WipeDetails, key_fingerprint,
+};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CertificateAuthority {
     pub name: String,
     pub organization: String,
+    #[serde(default)]
+    pub signature_algorithm: SignatureAlgorithm,
     pub private_key_pem: String,
     pub public_key_pem: String,
+    /// Base64 PKCS#8 document holding the Ed25519 signing key
+    #[serde(default)]
+    pub ed25519_pkcs8: Option<String>,
+    /// Base64 raw Ed25519 public key
+    #[serde(default)]
+    pub ed25519_public_key: Option<String>,
     pub certificate_counter: u64,
 }
 
@@ -99,12 +66,67 @@ impl CertificateAuthority {
         Ok(Self {
             name,
             organization,
+            signature_algorithm: SignatureAlgorithm::RsaPkcs1Sha256,
             private_key_pem: private_key_pem.to_string(),
             public_key_pem: public_key_pem.to_string(),
+            ed25519_pkcs8: None,
+            ed25519_public_key: None,
+            certificate_counter: 0,
+        })
+    }
+
+    /// Create a CA that signs with Ed25519. When `key_path` names an existing
+    /// PKCS#8 file that key is used, otherwise a fresh key pair is generated.
+    pub fn new_ed25519<P: AsRef<Path>>(name: String, organization: String, key_path: Option<P>) -> WipeResult2<Self> {
+        let pkcs8 = match key_path {
+            Some(path) if path.as_ref().exists() => fs::read(path)
+                .map_err(|e| WipeError {
+                    code: WipeErrorCode::UnknownError,
+                    message: format!("Failed to read signing key: {}", e),
+                    sector: None,
+                })?,
+            _ => Ed25519KeyPair::generate_pkcs8(&SystemRandom::new())
+                .map_err(|_| WipeError {
+                    code: WipeErrorCode::UnknownError,
+                    message: "Failed to generate Ed25519 key".to_string(),
+                    sector: None,
+                })?
+                .as_ref()
+                .to_vec(),
+        };
+
+        let key_pair = Ed25519KeyPair::from_pkcs8(&pkcs8)
+            .map_err(|e| WipeError {
+                code: WipeErrorCode::UnknownError,
+                message: format!("Invalid Ed25519 signing key: {}", e),
+                sector: None,
+            })?;
+
+        Ok(Self {
+            name,
+            organization,
+            signature_algorithm: SignatureAlgorithm::Ed25519,
+            private_key_pem: String::new(),
+            public_key_pem: String::new(),
+            ed25519_pkcs8: Some(general_purpose::STANDARD.encode(&pkcs8)),
+            ed25519_public_key: Some(general_purpose::STANDARD.encode(key_pair.public_key().as_ref())),
             certificate_counter: 0,
         })
     }
 
+    /// Public key embedded into issued certificates
+    pub fn public_key(&self) -> String {
+        match self.signature_algorithm {
+            SignatureAlgorithm::RsaPkcs1Sha256 => self.public_key_pem.clone(),
+            SignatureAlgorithm::Ed25519 => self.ed25519_public_key.clone().unwrap_or_default(),
+        }
+    }
+
+    /// SHA-256 fingerprint of the public key, hex encoded
+    pub fn key_fingerprint(&self) -> String {
+        key_fingerprint(&self.public_key())
+    }
+
     pub fn load_from_file<P: AsRef<Path>>(path: P) -> WipeResult2<Self> {
         let content = fs::read_to_string(path)
             .map_err(|e| WipeError {
@@ -141,6 +163,8 @@ impl CertificateAuthority {
         &mut self,
         wipe_request: &WipeRequest,
         wipe_result: &WipeResult,
+        operator: &str,
+        template: Option<TemplateReference>,
     ) -> WipeResult2<ErasureCertificate> {
         self.certificate_counter += 1;
         let certificate_id = Uuid::new_v4();
@@ -159,13 +183,24 @@ impl CertificateAuthority {
 
         // Extract wipe details
         let wipe_details = WipeDetails {
-            standard_used: format!("{:?}", wipe_request.standard),
+            standard_used: match wipe_request.recipe {
+                Some(ref recipe) => format!("Recipe: {}", recipe.name),
+                None => format!("{:?}", wipe_request.standard),
+            },
             passes_completed: wipe_result.passes_completed,
             sectors_wiped: wipe_result.sectors_wiped,
             start_time: wipe_result.start_time,
             completion_time: wipe_result.completion_time,
             duration_minutes: wipe_result.duration_seconds / 60,
-            patterns_used: self.get_pattern_descriptions(&wipe_request.standard),
+            patterns_used: if wipe_result.patterns_used.is_empty() {
+                self.get_pattern_descriptions(&wipe_request.standard)
+            } else {
+                wipe_result.patterns_used.clone()
+            },
+            scope: match wipe_request.scope {
+                WipeScope::WholeDevice => None,
+                ref scope => Some(scope.description()),
+            },
         };
 
         // Extract verification details
@@ -173,7 +208,10 @@ impl CertificateAuthority {
             VerificationDetails {
                 verification_performed: true,
                 sectors_verified: validation.sectors_verified,
-                verification_sample_rate: 0.1, // This should come from config
+                verification_sample_rate: wipe_result.verification_report
+                    .as_ref()
+                    .map(|report| report.coverage)
+                    .unwrap_or(0.0),
                 pattern_verification_passed: validation.pattern_matches,
                 failed_sectors: validation.failed_sectors.len() as u64,
                 verification_time: Some(validation.completion_time),
@@ -191,26 +229,44 @@ impl CertificateAuthority {
 
         // Create certificate (without signature initially)
         let mut certificate = ErasureCertificate {
+            schema_version: CERTIFICATE_SCHEMA_VERSION.to_string(),
             certificate_id,
             wipe_request_id: wipe_request.id,
             issued_at: Utc::now(),
             issuer: self.name.clone(),
             organization: self.organization.clone(),
+            operator: operator.to_string(),
             drive_info,
             wipe_details,
             verification_details,
-            compliance_standards: vec![
-                "NIST SP 800-88 Rev. 1".to_string(),
-                "DoD 5220.22-M".to_string(),
-            ],
+            compliance_standards: match (&wipe_request.recipe, wipe_request.profile) {
+                // A custom recipe attests to its own passes, not to a published standard
+                (Some(_), _) => Vec::new(),
+                (None, Some(profile)) => vec![profile.name().to_string()],
+                (None, None) => vec![
+                    "NIST SP 800-88 Rev. 1".to_string(),
+                    "DoD 5220.22-M".to_string(),
+                ],
+            },
+            compliance_profile: wipe_request.profile.map(|profile| profile.statement()),
+            recipe: wipe_request.recipe.as_ref().map(|recipe| RecipeReference {
+                name: recipe.name.clone(),
+                fingerprint: recipe.fingerprint.clone(),
+            }),
+            template,
+            evidence: wipe_result.evidence.clone(),
+            functional_test: wipe_result.functional_test.clone(),
+            forensic_image: wipe_result.forensic_image.clone(),
+            simulated: wipe_result.simulated,
+            signature_algorithm: self.signature_algorithm,
             signature: String::new(),
-            public_key: self.public_key_pem.clone(),
+            public_key: self.public_key(),
+            key_fingerprint: self.key_fingerprint(),
             certificate_hash: String::new(),
         };
 
         // Calculate hash of certificate content (excluding signature and hash fields)
-        let certificate_content = self.get_certificate_content_for_signing(&certificate)?;
-        let content_hash = self.calculate_hash(&certificate_content);
+        let content_hash = Self::content_hash(&certificate)?;
         certificate.certificate_hash = hex::encode(&content_hash);
 
         // Sign the certificate
@@ -220,40 +276,28 @@ impl CertificateAuthority {
         Ok(certificate)
     }
 
-    fn get_certificate_content_for_signing(&self, cert: &ErasureCertificate) -> WipeResult2<Vec<u8>> {
-        // Create a version of the certificate without signature and hash for signing
-        let signing_content = format!(
-            "{}|{}|{}|{}|{}|{}|{}|{}|{}|{}|{}|{}|{}|{}|{}|{}|{}|{}",
-            cert.certificate_id,
-            cert.wipe_request_id,
-            cert.issued_at.to_rfc3339(),
-            cert.issuer,
-            cert.organization,
-            cert.drive_info.serial_number,
-            cert.drive_info.model,
-            cert.wipe_details.standard_used,
-            cert.wipe_details.passes_completed,
-            cert.wipe_details.sectors_wiped,
-            cert.wipe_details.start_time.to_rfc3339(),
-            cert.wipe_details.completion_time.to_rfc3339(),
-            cert.verification_details.verification_performed,
-            cert.verification_details.sectors_verified,
-            cert.verification_details.pattern_verification_passed,
-            cert.verification_details.failed_sectors,
-            cert.compliance_standards.join(","),
-            cert.public_key
-        );
-
-        Ok(signing_content.into_bytes())
-    }
-
-    fn calculate_hash(&self, data: &[u8]) -> [u8; 32] {
-        let mut hasher = Sha256::new();
-        hasher.update(data);
-        hasher.finalize().into()
+    fn content_hash(cert: &ErasureCertificate) -> WipeResult2<[u8; 32]> {
+        hdd_tool_certificate::content_hash(cert).map_err(certificate_error)
     }
 
     fn sign_data(&self, data: &[u8]) -> WipeResult2<Vec<u8>> {
+        if self.signature_algorithm == SignatureAlgorithm::Ed25519 {
+            let pkcs8 = self.ed25519_pkcs8.as_ref()
+                .and_then(|encoded| general_purpose::STANDARD.decode(encoded).ok())
+                .ok_or_else(|| WipeError {
+                    code: WipeErrorCode::UnknownError,
+                    message: "Ed25519 signing key missing from CA".to_string(),
+                    sector: None,
+                })?;
+            let key_pair = Ed25519KeyPair::from_pkcs8(&pkcs8)
+                .map_err(|e| WipeError {
+                    code: WipeErrorCode::UnknownError,
+                    message: format!("Failed to decode Ed25519 key: {}", e),
+                    sector: None,
+                })?;
+            return Ok(key_pair.sign(data).as_ref().to_vec());
+        }
+
         let private_key = rsa::pkcs8::DecodePrivateKey::from_pkcs8_pem(&self.private_key_pem)
             .map_err(|e| WipeError {
                 code: WipeErrorCode::UnknownError,
@@ -269,43 +313,18 @@ impl CertificateAuthority {
     }
 
     pub fn verify_certificate(&self, certificate: &ErasureCertificate) -> WipeResult2<bool> {
-        // Recreate the content that was signed
-        let certificate_content = self.get_certificate_content_for_signing(certificate)?;
-        let content_hash = self.calculate_hash(&certificate_content);
-
-        // Verify the hash matches
-        let calculated_hash = hex::encode(&content_hash);
-        if calculated_hash != certificate.certificate_hash {
-            return Ok(false);
-        }
-
-        // Verify the signature
-        let signature_bytes = general_purpose::STANDARD.decode(&certificate.signature)
-            .map_err(|e| WipeError {
-                code: WipeErrorCode::UnknownError,
-                message: format!("Failed to decode signature: {}", e),
-                sector: None,
-            })?;
-
-        let public_key: RsaPublicKey = rsa::pkcs8::DecodePublicKey::from_public_key_pem(&certificate.public_key)
-            .map_err(|e| WipeError {
-                code: WipeErrorCode::UnknownError,
-                message: format!("Failed to decode public key: {}", e),
-                sector: None,
-            })?;
+        Ok(Self::verify_hash(certificate)? && Self::verify_signature(certificate)?)
+    }
 
-        let verifying_key = rsa::pkcs1v15::VerifyingKey::<Sha256>::new_unprefixed(public_key);
-        let signature = rsa::pkcs1v15::Signature::try_from(signature_bytes.as_slice())
-            .map_err(|e| WipeError {
-                code: WipeErrorCode::UnknownError,
-                message: format!("Invalid signature format: {}", e),
-                sector: None,
-            })?;
+    /// Check that the recorded certificate hash matches the certificate content
+    pub fn verify_hash(certificate: &ErasureCertificate) -> WipeResult2<bool> {
+        hdd_tool_certificate::verify_hash(certificate).map_err(certificate_error)
+    }
 
-        match verifying_key.verify(&content_hash, &signature) {
-            Ok(_) => Ok(true),
-            Err(_) => Ok(false),
-        }
+    /// Check the signature against the public key embedded in the certificate.
+    /// Needs no CA instance, so certificates can be verified offline.
+    pub fn verify_signature(certificate: &ErasureCertificate) -> WipeResult2<bool> {
+        hdd_tool_certificate::verify_signature(certificate).map_err(certificate_error)
     }
 
     fn format_security_features(&self, features: &SecurityFeatures) -> Vec<String> {
@@ -365,4 +384,19 @@ impl CertificateAuthority {
             }
         }
     }
-}
\ No newline at end of file
+}
+
+fn certificate_error(error: hdd_tool_certificate::CertificateError) -> WipeError {
+    WipeError {
+        code: WipeErrorCode::UnknownError,
+        message: error.message,
+        sector: None,
+    }
+}
+
+/// Canonical JSON encoding of a certificate body: signature and hash are
+/// blanked and object keys sorted, so the same certificate always produces
+/// the same bytes.
+pub fn canonical_json(certificate: &ErasureCertificate) -> WipeResult2<String> {
+    hdd_tool_certificate::canonical_json(certificate).map_err(certificate_error)
+}
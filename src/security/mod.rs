@@ -1,5 +1,9 @@
 pub mod certificate;
 pub mod report_generator;
+pub mod template;
+pub mod verification;
 
 pub use certificate::*;
-pub use report_generator::*;
\ No newline at end of file
+pub use report_generator::*;
+pub use template::*;
+pub use verification::*;
\ No newline at end of file
@@ -1,4 +1,5 @@
-use printpdf::{PdfDocument, Mm, PdfDocumentReference, PdfLayerReference, IndirectFontRef, Color, Rgb};
+use printpdf::{PdfDocument, Mm, PdfLayerReference, IndirectFontRef, Image, ImageTransform};
+use printpdf::image_crate::{DynamicImage, GenericImageView};
 use chrono::{DateTime, Utc};
 use serde_json;
 use std::fs::File;
@@ -6,13 +7,25 @@ use std::io::Write;
 use std::path::Path;
 
 use crate::core::{WipeRequest, WipeResult, WipeError, WipeErrorCode, WipeResult2};
-use crate::security::ErasureCertificate;
+use crate::security::{DriveSnapshot, ErasureCertificate, FunctionalTestKind, LoadedTemplate};
+use crate::t;
 
-pub struct ReportGenerator;
+pub struct ReportGenerator {
+    template: Option<LoadedTemplate>,
+}
 
 impl ReportGenerator {
     pub fn new() -> Self {
-        Self
+        Self { template: None }
+    }
+
+    /// Print certificates with the branding of `template`
+    pub fn with_template(template: Option<LoadedTemplate>) -> Self {
+        Self { template }
+    }
+
+    pub fn template(&self) -> Option<&LoadedTemplate> {
+        self.template.as_ref()
     }
 
     pub fn generate_pdf_report<P: AsRef<Path>>(
@@ -20,7 +33,25 @@ impl ReportGenerator {
         certificate: &ErasureCertificate,
         output_path: P,
     ) -> WipeResult2<()> {
-        let (doc, page1, layer1) = PdfDocument::new("Data Erasure Certificate", Mm(210.0), Mm(297.0), "Layer 1");
+        // Branding only goes on certificates that recorded it when they were signed
+        let template = self.template.as_ref().filter(|template| {
+            certificate.template.as_ref().is_some_and(|recorded| recorded.fingerprint == template.fingerprint)
+        });
+        match template.and_then(|template| template.template.locale.as_deref()) {
+            Some(locale) => crate::i18n::with_locale(locale, || {
+                self.render_pdf(certificate, template, output_path.as_ref())
+            }),
+            None => self.render_pdf(certificate, template, output_path.as_ref()),
+        }
+    }
+
+    fn render_pdf(
+        &self,
+        certificate: &ErasureCertificate,
+        template: Option<&LoadedTemplate>,
+        output_path: &Path,
+    ) -> WipeResult2<()> {
+        let (doc, page1, layer1) = PdfDocument::new(t!("cert-document-title"), Mm(210.0), Mm(297.0), "Layer 1");
         let current_layer = doc.get_page(page1).get_layer(layer1);
 
         // Load fonts
@@ -28,11 +59,15 @@ impl ReportGenerator {
         let font_bold = doc.add_builtin_font(printpdf::BuiltinFont::HelveticaBold)?;
 
         // Title
-        self.add_title(&current_layer, &font_bold, "DATA ERASURE CERTIFICATE", 280.0)?;
+        let title = if certificate.simulated { t!("cert-title-simulated") } else { t!("cert-title") };
+        self.add_title(&current_layer, &font_bold, &title, 280.0)?;
+        if let Some(template) = template {
+            self.add_branding(&current_layer, &font, &font_bold, template)?;
+        }
         
         // Header information
         let mut y_pos = 260.0;
-        y_pos = self.add_header_section(&current_layer, &font, &font_bold, certificate, y_pos)?;
+        y_pos = self.add_header_section(&current_layer, &font, &font_bold, certificate, template, y_pos)?;
         
         // Drive information
         y_pos = self.add_drive_section(&current_layer, &font, &font_bold, certificate, y_pos)?;
@@ -43,11 +78,14 @@ impl ReportGenerator {
         // Verification details
         y_pos = self.add_verification_section(&current_layer, &font, &font_bold, certificate, y_pos)?;
         
+        // Drive state before and after
+        y_pos = self.add_evidence_section(&current_layer, &font, &font_bold, certificate, y_pos)?;
+        
         // Compliance and signature
         y_pos = self.add_compliance_section(&current_layer, &font, &font_bold, certificate, y_pos)?;
         
         // Footer
-        self.add_footer(&current_layer, &font, certificate)?;
+        self.add_footer(&current_layer, &font, certificate, template)?;
 
         // Save the PDF
         doc.save(&mut std::io::BufWriter::new(
@@ -65,6 +103,27 @@ impl ReportGenerator {
         Ok(())
     }
 
+    /// Write the signed certificate itself as JSON; this is the file auditors verify
+    pub fn generate_certificate_json<P: AsRef<Path>>(
+        &self,
+        certificate: &ErasureCertificate,
+        output_path: P,
+    ) -> WipeResult2<()> {
+        let json_content = serde_json::to_string_pretty(certificate)
+            .map_err(|e| WipeError {
+                code: WipeErrorCode::UnknownError,
+                message: format!("Failed to serialize certificate: {}", e),
+                sector: None,
+            })?;
+
+        std::fs::write(output_path, json_content)
+            .map_err(|e| WipeError {
+                code: WipeErrorCode::UnknownError,
+                message: format!("Failed to write certificate: {}", e),
+                sector: None,
+            })
+    }
+
     pub fn generate_json_report<P: AsRef<Path>>(
         &self,
         certificate: &ErasureCertificate,
@@ -107,10 +166,7 @@ impl ReportGenerator {
                 report_version: "1.0".to_string(),
                 generated_at: Utc::now(),
                 generator: "SecureWipe Pro v1.0".to_string(),
-                compliance_standards: vec![
-                    "NIST SP 800-88 Rev. 1".to_string(),
-                    "DoD 5220.22-M".to_string(),
-                ],
+                compliance_standards: certificate.compliance_standards.clone(),
             },
             audit_trail: AuditTrail {
                 original_request: wipe_request.clone(),
@@ -151,28 +207,69 @@ impl ReportGenerator {
         Ok(())
     }
 
+    /// Logo in the top left corner, organization and address under it
+    fn add_branding(
+        &self,
+        layer: &PdfLayerReference,
+        font: &IndirectFontRef,
+        font_bold: &IndirectFontRef,
+        template: &LoadedTemplate,
+    ) -> WipeResult2<()> {
+        if let Some(logo) = &template.logo {
+            // Scale the logo to fit 40 x 20 mm
+            let (width, height) = logo.dimensions();
+            let dpi = (width as f32 * 25.4 / 40.0).max(height as f32 * 25.4 / 20.0);
+            Image::from_dynamic_image(&DynamicImage::ImageRgb8(logo.to_rgb8())).add_to_layer(
+                layer.clone(),
+                ImageTransform {
+                    translate_x: Some(Mm(20.0)),
+                    translate_y: Some(Mm(272.0)),
+                    dpi: Some(dpi),
+                    ..Default::default()
+                },
+            );
+        }
+
+        let mut y_pos = 268.0;
+        if let Some(organization) = &template.template.organization {
+            layer.use_text(organization, 9.0, Mm(20.0), Mm(y_pos), font_bold);
+            y_pos -= 4.0;
+        }
+        for line in &template.template.address {
+            layer.use_text(line, 8.0, Mm(20.0), Mm(y_pos), font);
+            y_pos -= 4.0;
+        }
+        Ok(())
+    }
+
     fn add_header_section(
         &self,
         layer: &PdfLayerReference,
         font: &IndirectFontRef,
         font_bold: &IndirectFontRef,
         certificate: &ErasureCertificate,
+        template: Option<&LoadedTemplate>,
         start_y: f64,
     ) -> WipeResult2<f64> {
         let mut y_pos = start_y - 20.0;
 
-        layer.use_text("CERTIFICATE INFORMATION", 14.0, Mm(20.0), Mm(y_pos as f32), font_bold);
+        layer.use_text(t!("cert-section-certificate"), 14.0, Mm(20.0), Mm(y_pos as f32), font_bold);
         y_pos -= 15.0;
 
-        let header_items = vec![
-            ("Certificate ID:", certificate.certificate_id.to_string()),
-            ("Issue Date:", certificate.issued_at.format("%Y-%m-%d %H:%M:%S UTC").to_string()),
-            ("Issuer:", certificate.issuer.clone()),
-            ("Organization:", certificate.organization.clone()),
+        let mut header_items = vec![
+            (t!("cert-certificate-id"), certificate.certificate_id.to_string()),
+            (t!("cert-issue-date"), certificate.issued_at.format("%Y-%m-%d %H:%M:%S UTC").to_string()),
+            (t!("cert-issuer"), certificate.issuer.clone()),
+            (t!("cert-organization"), certificate.organization.clone()),
+            (t!("cert-operator"), certificate.operator.clone()),
+            (t!("cert-schema-version"), certificate.schema_version.clone()),
         ];
+        if let Some(template) = template {
+            header_items.extend(template.template.fields.iter().map(|field| (format!("{}:", field.label), field.value.clone())));
+        }
 
         for (label, value) in header_items {
-            layer.use_text(label, 10.0, Mm(20.0), Mm(y_pos as f32), font_bold);
+            layer.use_text(&label, 10.0, Mm(20.0), Mm(y_pos as f32), font_bold);
             layer.use_text(&value, 10.0, Mm(70.0), Mm(y_pos as f32), font);
             y_pos -= 12.0;
         }
@@ -190,22 +287,22 @@ impl ReportGenerator {
     ) -> WipeResult2<f64> {
         let mut y_pos = start_y;
 
-        layer.use_text("DRIVE INFORMATION", 14.0, Mm(20.0), Mm(y_pos as f32), font_bold);
+        layer.use_text(t!("cert-section-drive"), 14.0, Mm(20.0), Mm(y_pos as f32), font_bold);
         y_pos -= 15.0;
 
         let drive_items = vec![
-            ("Model:", certificate.drive_info.model.clone()),
-            ("Serial Number:", certificate.drive_info.serial_number.clone()),
-            ("Firmware:", certificate.drive_info.firmware_version.clone()),
-            ("Total Capacity:", format!("{:.2} GB", certificate.drive_info.total_capacity_gb)),
-            ("Native Capacity:", format!("{:.2} GB", certificate.drive_info.native_capacity_gb)),
-            ("HPA Detected:", if certificate.drive_info.had_hpa { "Yes" } else { "No" }.to_string()),
-            ("DCO Detected:", if certificate.drive_info.had_dco { "Yes" } else { "No" }.to_string()),
-            ("Security Features:", certificate.drive_info.security_features.join(", ")),
+            (t!("cert-model"), certificate.drive_info.model.clone()),
+            (t!("cert-serial-number"), certificate.drive_info.serial_number.clone()),
+            (t!("cert-firmware"), certificate.drive_info.firmware_version.clone()),
+            (t!("cert-total-capacity"), format!("{:.2} GB", certificate.drive_info.total_capacity_gb)),
+            (t!("cert-native-capacity"), format!("{:.2} GB", certificate.drive_info.native_capacity_gb)),
+            (t!("cert-hpa-detected"), yes_no(certificate.drive_info.had_hpa)),
+            (t!("cert-dco-detected"), yes_no(certificate.drive_info.had_dco)),
+            (t!("cert-security-features"), certificate.drive_info.security_features.join(", ")),
         ];
 
         for (label, value) in drive_items {
-            layer.use_text(label, 10.0, Mm(20.0), Mm(y_pos as f32), font_bold);
+            layer.use_text(&label, 10.0, Mm(20.0), Mm(y_pos as f32), font_bold);
             layer.use_text(&value, 10.0, Mm(70.0), Mm(y_pos as f32), font);
             y_pos -= 12.0;
         }
@@ -223,21 +320,24 @@ impl ReportGenerator {
     ) -> WipeResult2<f64> {
         let mut y_pos = start_y;
 
-        layer.use_text("SANITIZATION DETAILS", 14.0, Mm(20.0), Mm(y_pos as f32), font_bold);
+        layer.use_text(t!("cert-section-sanitization"), 14.0, Mm(20.0), Mm(y_pos as f32), font_bold);
         y_pos -= 15.0;
 
-        let wipe_items = vec![
-            ("Standard Used:", certificate.wipe_details.standard_used.clone()),
-            ("Passes Completed:", certificate.wipe_details.passes_completed.to_string()),
-            ("Sectors Wiped:", certificate.wipe_details.sectors_wiped.to_string()),
-            ("Start Time:", certificate.wipe_details.start_time.format("%Y-%m-%d %H:%M:%S UTC").to_string()),
-            ("Completion Time:", certificate.wipe_details.completion_time.format("%Y-%m-%d %H:%M:%S UTC").to_string()),
-            ("Duration:", format!("{} minutes", certificate.wipe_details.duration_minutes)),
-            ("Patterns Used:", certificate.wipe_details.patterns_used.join(", ")),
+        let mut wipe_items = vec![
+            (t!("cert-standard-used"), certificate.wipe_details.standard_used.clone()),
+            (t!("cert-passes-completed"), certificate.wipe_details.passes_completed.to_string()),
+            (t!("cert-sectors-wiped"), certificate.wipe_details.sectors_wiped.to_string()),
+            (t!("cert-start-time"), certificate.wipe_details.start_time.format("%Y-%m-%d %H:%M:%S UTC").to_string()),
+            (t!("cert-completion-time"), certificate.wipe_details.completion_time.format("%Y-%m-%d %H:%M:%S UTC").to_string()),
+            (t!("cert-duration"), t!("cert-duration-minutes", minutes = certificate.wipe_details.duration_minutes)),
+            (t!("cert-patterns-used"), certificate.wipe_details.patterns_used.join(", ")),
         ];
+        if certificate.simulated {
+            wipe_items.insert(0, (t!("cert-simulation"), t!("cert-simulation-note")));
+        }
 
         for (label, value) in wipe_items {
-            layer.use_text(label, 10.0, Mm(20.0), Mm(y_pos as f32), font_bold);
+            layer.use_text(&label, 10.0, Mm(20.0), Mm(y_pos as f32), font_bold);
             layer.use_text(&value, 10.0, Mm(70.0), Mm(y_pos as f32), font);
             y_pos -= 12.0;
         }
@@ -255,19 +355,78 @@ impl ReportGenerator {
     ) -> WipeResult2<f64> {
         let mut y_pos = start_y;
 
-        layer.use_text("VERIFICATION DETAILS", 14.0, Mm(20.0), Mm(y_pos as f32), font_bold);
+        layer.use_text(t!("cert-section-verification"), 14.0, Mm(20.0), Mm(y_pos as f32), font_bold);
         y_pos -= 15.0;
 
-        let verification_items = vec![
-            ("Verification Performed:", if certificate.verification_details.verification_performed { "Yes" } else { "No" }.to_string()),
-            ("Sectors Verified:", certificate.verification_details.sectors_verified.to_string()),
-            ("Sample Rate:", format!("{:.1}%", certificate.verification_details.verification_sample_rate * 100.0)),
-            ("Pattern Verification:", if certificate.verification_details.pattern_verification_passed { "PASSED" } else { "FAILED" }.to_string()),
-            ("Failed Sectors:", certificate.verification_details.failed_sectors.to_string()),
+        let mut verification_items = vec![
+            (t!("cert-verification-performed"), yes_no(certificate.verification_details.verification_performed)),
+            (t!("cert-sectors-verified"), certificate.verification_details.sectors_verified.to_string()),
+            (t!("cert-sample-rate"), format!("{:.1}%", certificate.verification_details.verification_sample_rate * 100.0)),
+            (t!("cert-pattern-verification"), if certificate.verification_details.pattern_verification_passed { t!("cert-passed") } else { t!("cert-failed") }),
+            (t!("cert-failed-sectors"), certificate.verification_details.failed_sectors.to_string()),
         ];
+        if let Some(test) = &certificate.functional_test {
+            let kind = match test.kind {
+                FunctionalTestKind::ShortSelfTest => t!("cert-functional-self-test"),
+                FunctionalTestKind::SurfaceScan => t!("cert-functional-surface-scan"),
+            };
+            let verdict = if test.passed { t!("cert-passed") } else { t!("cert-failed") };
+            verification_items.push((
+                t!("cert-functional-test"),
+                t!("cert-functional-test-value", kind = kind, verdict = verdict, detail = test.detail.as_str()),
+            ));
+        }
+        if let Some(image) = &certificate.forensic_image {
+            verification_items.push((t!("cert-forensic-image"), image.location.clone()));
+            verification_items.push((t!("cert-forensic-source-hash"), image.source_sha256.clone()));
+            verification_items.push((t!("cert-forensic-image-hash"), image.image_sha256.clone()));
+        }
 
         for (label, value) in verification_items {
-            layer.use_text(label, 10.0, Mm(20.0), Mm(y_pos as f32), font_bold);
+            layer.use_text(&label, 10.0, Mm(20.0), Mm(y_pos as f32), font_bold);
+            layer.use_text(&value, 10.0, Mm(70.0), Mm(y_pos as f32), font);
+            y_pos -= 12.0;
+        }
+
+        Ok(y_pos - 10.0)
+    }
+
+    fn add_evidence_section(
+        &self,
+        layer: &PdfLayerReference,
+        font: &IndirectFontRef,
+        font_bold: &IndirectFontRef,
+        certificate: &ErasureCertificate,
+        start_y: f64,
+    ) -> WipeResult2<f64> {
+        let Some(evidence) = &certificate.evidence else {
+            return Ok(start_y);
+        };
+        let mut y_pos = start_y;
+
+        layer.use_text(t!("cert-section-evidence"), 14.0, Mm(20.0), Mm(y_pos as f32), font_bold);
+        y_pos -= 15.0;
+
+        let mut evidence_items = Vec::new();
+        for (stage, snapshot) in [(t!("cert-evidence-before"), &evidence.before), (t!("cert-evidence-after"), &evidence.after)] {
+            evidence_items.push((stage, String::new()));
+            evidence_items.extend(snapshot_items(snapshot));
+        }
+        if let Some(verification) = &evidence.verification {
+            evidence_items.push((
+                t!("cert-evidence-read-back"),
+                t!(
+                    "cert-evidence-read-back-value",
+                    coverage = format!("{:.1}", verification.coverage * 100.0),
+                    failed = verification.failed_regions,
+                    regions = verification.regions,
+                    entropy = format!("{:.2}", verification.mean_entropy),
+                ),
+            ));
+        }
+
+        for (label, value) in evidence_items {
+            layer.use_text(&label, 10.0, Mm(20.0), Mm(y_pos as f32), font_bold);
             layer.use_text(&value, 10.0, Mm(70.0), Mm(y_pos as f32), font);
             y_pos -= 12.0;
         }
@@ -285,10 +444,10 @@ impl ReportGenerator {
     ) -> WipeResult2<f64> {
         let mut y_pos = start_y;
 
-        layer.use_text("COMPLIANCE & CERTIFICATION", 14.0, Mm(20.0), Mm(y_pos as f32), font_bold);
+        layer.use_text(t!("cert-section-compliance"), 14.0, Mm(20.0), Mm(y_pos as f32), font_bold);
         y_pos -= 15.0;
 
-        layer.use_text("Compliance Standards:", 10.0, Mm(20.0), Mm(y_pos as f32), font_bold);
+        layer.use_text(t!("cert-compliance-standards"), 10.0, Mm(20.0), Mm(y_pos as f32), font_bold);
         y_pos -= 12.0;
 
         for standard in &certificate.compliance_standards {
@@ -296,16 +455,35 @@ impl ReportGenerator {
             y_pos -= 12.0;
         }
 
+        if let Some(ref profile) = certificate.compliance_profile {
+            layer.use_text(t!("cert-reference"), 10.0, Mm(20.0), Mm(y_pos as f32), font_bold);
+            layer.use_text(&profile.citation, 8.0, Mm(45.0), Mm(y_pos as f32), font);
+            y_pos -= 12.0;
+            for line in wrap_text(&profile.statement, 95) {
+                layer.use_text(&line, 9.0, Mm(20.0), Mm(y_pos as f32), font);
+                y_pos -= 10.0;
+            }
+        }
+
         y_pos -= 10.0;
-        layer.use_text("DIGITAL SIGNATURE", 12.0, Mm(20.0), Mm(y_pos as f32), font_bold);
+        layer.use_text(t!("cert-section-signature"), 12.0, Mm(20.0), Mm(y_pos as f32), font_bold);
+        y_pos -= 15.0;
+
+        layer.use_text(t!("cert-signature-algorithm"), 10.0, Mm(20.0), Mm(y_pos as f32), font_bold);
+        layer.use_text(format!("{:?}", certificate.signature_algorithm), 10.0, Mm(70.0), Mm(y_pos as f32), font);
+        y_pos -= 12.0;
+
+        layer.use_text(t!("cert-key-fingerprint"), 10.0, Mm(20.0), Mm(y_pos as f32), font_bold);
+        y_pos -= 12.0;
+        layer.use_text(&certificate.key_fingerprint, 8.0, Mm(20.0), Mm(y_pos as f32), font);
         y_pos -= 15.0;
 
-        layer.use_text("Certificate Hash:", 10.0, Mm(20.0), Mm(y_pos as f32), font_bold);
+        layer.use_text(t!("cert-certificate-hash"), 10.0, Mm(20.0), Mm(y_pos as f32), font_bold);
         y_pos -= 12.0;
         layer.use_text(&certificate.certificate_hash, 8.0, Mm(20.0), Mm(y_pos as f32), font);
         y_pos -= 15.0;
 
-        layer.use_text("Digital Signature:", 10.0, Mm(20.0), Mm(y_pos as f32), font_bold);
+        layer.use_text(t!("cert-digital-signature"), 10.0, Mm(20.0), Mm(y_pos as f32), font_bold);
         y_pos -= 12.0;
         
         // Truncate signature for display
@@ -324,9 +502,18 @@ impl ReportGenerator {
         layer: &PdfLayerReference,
         font: &IndirectFontRef,
         certificate: &ErasureCertificate,
+        template: Option<&LoadedTemplate>,
     ) -> WipeResult2<()> {
+        let template = template.map(|template| &template.template);
+        if let Some(disclaimer) = template.and_then(|template| template.disclaimer.as_deref()) {
+            let lines = wrap_text(disclaimer, 110);
+            for (index, line) in lines.iter().enumerate() {
+                layer.use_text(line, 8.0, Mm(20.0), Mm(40.0 + 4.0 * (lines.len() - 1 - index) as f32), font);
+            }
+        }
+
         layer.use_text(
-            "This certificate is cryptographically signed and tamper-evident.",
+            t!("cert-footer-signed"),
             8.0,
             Mm(20.0),
             Mm(30.0),
@@ -334,17 +521,79 @@ impl ReportGenerator {
         );
         
         layer.use_text(
-            &format!("Generated on: {}", Utc::now().format("%Y-%m-%d %H:%M:%S UTC")),
+            t!("cert-footer-generated", time = Utc::now().format("%Y-%m-%d %H:%M:%S UTC").to_string()),
             8.0,
             Mm(20.0),
             Mm(20.0),
             font,
         );
 
+        if let Some(footer_text) = template.and_then(|template| template.footer_text.as_deref()) {
+            layer.use_text(footer_text, 8.0, Mm(20.0), Mm(12.0), font);
+        }
+
         Ok(())
     }
 }
 
+/// Label/value rows describing one drive snapshot
+fn snapshot_items(snapshot: &DriveSnapshot) -> Vec<(String, String)> {
+    let none = t!("cert-evidence-none");
+    let mut items = vec![
+        (
+            t!("cert-evidence-partitions"),
+            match &snapshot.partition_table {
+                Some(kind) => t!("cert-evidence-partition-table", kind = kind.as_str(), count = snapshot.partitions.len()),
+                None => none.clone(),
+            },
+        ),
+        (
+            t!("cert-evidence-signatures"),
+            if snapshot.signatures.is_empty() {
+                none.clone()
+            } else {
+                snapshot.signatures.iter().map(|signature| signature.name.as_str()).collect::<Vec<_>>().join(", ")
+            },
+        ),
+        (
+            t!("cert-evidence-content"),
+            t!(
+                "cert-evidence-content-value",
+                classification = snapshot.classification.as_str(),
+                entropy = format!("{:.2}", snapshot.mean_entropy),
+                zero = format!("{:.0}", snapshot.zero_fraction * 100.0),
+            ),
+        ),
+    ];
+    if let Some(smart) = &snapshot.smart {
+        items.push((t!("cert-evidence-smart"), smart.health.clone()));
+    }
+    items
+}
+
+fn yes_no(value: bool) -> String {
+    if value { t!("cert-yes") } else { t!("cert-no") }
+}
+
+/// Break `text` into lines of at most `width` characters at word boundaries
+fn wrap_text(text: &str, width: usize) -> Vec<String> {
+    let mut lines = Vec::new();
+    let mut line = String::new();
+    for word in text.split_whitespace() {
+        if !line.is_empty() && line.len() + 1 + word.len() > width {
+            lines.push(std::mem::take(&mut line));
+        }
+        if !line.is_empty() {
+            line.push(' ');
+        }
+        line.push_str(word);
+    }
+    if !line.is_empty() {
+        lines.push(line);
+    }
+    lines
+}
+
 impl From<printpdf::Error> for WipeError {
     fn from(err: printpdf::Error) -> Self {
         WipeError {
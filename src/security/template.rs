@@ -0,0 +1,210 @@
+//! Organization branding on printed certificates
+//!
+//! `WipeConfiguration::certificate_template` puts a customer's logo, address,
+//! extra fields and disclaimer on the PDF certificate, and can print it in a
+//! fixed language:
+//!
+//! ```json
+//! "certificate_template": {
+//!     "name": "acme",
+//!     "logo_path": "branding/acme.png",
+//!     "organization": "Acme Recycling Ltd",
+//!     "address": ["1 High Street", "London EC1A 1AA"],
+//!     "fields": [{ "label": "Customer", "value": "Contoso" }],
+//!     "disclaimer": "Erased under contract 2024-117.",
+//!     "footer_text": "acme-recycling.example",
+//!     "locale": "es-ES"
+//! }
+//! ```
+//!
+//! Templates are checked when the engine loads its configuration, so a
+//! missing logo or an unknown locale is reported up front rather than on the
+//! first certificate. Certificates record the template's fingerprint, a
+//! SHA-256 of its settings and the logo's bytes, in the signed body; the
+//! fingerprint changes when the logo file does, not when it moves.
+
+use printpdf::image_crate::{self, DynamicImage};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fs;
+
+use crate::core::{WipeError, WipeErrorCode, WipeResult2};
+use crate::security::TemplateReference;
+
+/// Largest logo file accepted
+pub const LOGO_MAX_BYTES: u64 = 5 * 1024 * 1024;
+/// Lines and fields that fit the certificate page
+pub const MAX_ADDRESS_LINES: usize = 4;
+pub const MAX_TEMPLATE_FIELDS: usize = 8;
+pub const MAX_DISCLAIMER_CHARS: usize = 600;
+pub const MAX_FOOTER_CHARS: usize = 120;
+
+/// Branding for printed certificates, as written in the configuration
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct CertificateTemplate {
+    pub name: String,
+    /// PNG, JPEG or BMP image
+    #[serde(default)]
+    pub logo_path: Option<String>,
+    /// Printed above the address, under the logo
+    #[serde(default)]
+    pub organization: Option<String>,
+    #[serde(default)]
+    pub address: Vec<String>,
+    /// Extra label/value rows in the certificate information section
+    #[serde(default)]
+    pub fields: Vec<TemplateField>,
+    #[serde(default)]
+    pub disclaimer: Option<String>,
+    #[serde(default)]
+    pub footer_text: Option<String>,
+    /// Language of the certificate, whatever the engine's locale
+    #[serde(default)]
+    pub locale: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TemplateField {
+    pub label: String,
+    pub value: String,
+}
+
+/// A validated template with its logo decoded
+#[derive(Debug, Clone)]
+pub struct LoadedTemplate {
+    pub template: CertificateTemplate,
+    pub logo: Option<DynamicImage>,
+    /// SHA-256 of the template settings and logo bytes
+    pub fingerprint: String,
+}
+
+impl LoadedTemplate {
+    /// What certificates printed with this template record
+    pub fn reference(&self) -> TemplateReference {
+        TemplateReference {
+            name: self.template.name.clone(),
+            fingerprint: self.fingerprint.clone(),
+        }
+    }
+}
+
+impl CertificateTemplate {
+    /// Check the template and read its logo
+    pub fn load(&self) -> WipeResult2<LoadedTemplate> {
+        let name = self.name.trim();
+        if name.is_empty() {
+            return Err(invalid("Certificate template needs a name".to_string()));
+        }
+        let invalid = |message: String| invalid(format!("Certificate template '{}': {}", name, message));
+
+        if self.address.len() > MAX_ADDRESS_LINES {
+            return Err(invalid(format!("at most {} address lines fit the page", MAX_ADDRESS_LINES)));
+        }
+        if self.fields.len() > MAX_TEMPLATE_FIELDS {
+            return Err(invalid(format!("at most {} fields fit the page", MAX_TEMPLATE_FIELDS)));
+        }
+        if let Some(field) = self.fields.iter().find(|field| field.label.trim().is_empty()) {
+            return Err(invalid(format!("field with value '{}' has no label", field.value)));
+        }
+        if self.disclaimer.as_ref().is_some_and(|text| text.chars().count() > MAX_DISCLAIMER_CHARS) {
+            return Err(invalid(format!("disclaimer is longer than {} characters", MAX_DISCLAIMER_CHARS)));
+        }
+        if self.footer_text.as_ref().is_some_and(|text| text.chars().count() > MAX_FOOTER_CHARS) {
+            return Err(invalid(format!("footer text is longer than {} characters", MAX_FOOTER_CHARS)));
+        }
+        if let Some(locale) = &self.locale
+            && !crate::i18n::supports(locale)
+        {
+            return Err(invalid(format!(
+                "no messages for locale '{}' (available: {})",
+                locale,
+                crate::i18n::available_locales().join(", ")
+            )));
+        }
+
+        let (logo, logo_hash) = match &self.logo_path {
+            Some(path) => {
+                let size = fs::metadata(path).map_err(|e| invalid(format!("cannot read logo {}: {}", path, e)))?.len();
+                if size > LOGO_MAX_BYTES {
+                    return Err(invalid(format!("logo {} is larger than {} MB", path, LOGO_MAX_BYTES / (1024 * 1024))));
+                }
+                let bytes = fs::read(path).map_err(|e| invalid(format!("cannot read logo {}: {}", path, e)))?;
+                let image = image_crate::load_from_memory(&bytes)
+                    .map_err(|e| invalid(format!("logo {} is not a PNG, JPEG or BMP image: {}", path, e)))?;
+                (Some(image), Some(hex::encode(Sha256::digest(&bytes))))
+            }
+            None => (None, None),
+        };
+
+        let canonical = serde_json::json!({
+            "name": name,
+            "logo": logo_hash,
+            "organization": self.organization,
+            "address": self.address,
+            "fields": self.fields,
+            "disclaimer": self.disclaimer,
+            "footer_text": self.footer_text,
+            "locale": self.locale,
+        });
+        Ok(LoadedTemplate {
+            template: self.clone(),
+            logo,
+            fingerprint: hex::encode(Sha256::digest(canonical.to_string().as_bytes())),
+        })
+    }
+}
+
+fn invalid(message: String) -> WipeError {
+    WipeError {
+        code: WipeErrorCode::UnknownError,
+        message,
+        sector: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn template() -> CertificateTemplate {
+        CertificateTemplate {
+            name: "acme".to_string(),
+            organization: Some("Acme Recycling Ltd".to_string()),
+            address: vec!["1 High Street".to_string()],
+            fields: vec![TemplateField { label: "Customer".to_string(), value: "Contoso".to_string() }],
+            disclaimer: Some("Erased under contract 2024-117.".to_string()),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_template_validation() {
+        assert!(template().load().is_ok());
+        assert!(CertificateTemplate { name: " ".to_string(), ..template() }.load().is_err());
+        assert!(CertificateTemplate { locale: Some("xx-XX".to_string()), ..template() }.load().is_err());
+        assert!(CertificateTemplate { locale: Some("es_MX.UTF-8".to_string()), ..template() }.load().is_ok());
+        assert!(CertificateTemplate { logo_path: Some("/nonexistent/logo.png".to_string()), ..template() }.load().is_err());
+        let unlabelled = vec![TemplateField { label: String::new(), value: "x".to_string() }];
+        assert!(CertificateTemplate { fields: unlabelled, ..template() }.load().is_err());
+    }
+
+    #[test]
+    fn test_template_fingerprint_covers_logo_content() {
+        let dir = tempfile::tempdir().unwrap();
+        let logo_path = dir.path().join("logo.png");
+        DynamicImage::new_rgb8(4, 2).save(&logo_path).unwrap();
+        let branded = CertificateTemplate { logo_path: Some(logo_path.to_string_lossy().into_owned()), ..template() };
+
+        let first = branded.load().unwrap();
+        assert!(first.logo.is_some());
+        assert_ne!(first.fingerprint, template().load().unwrap().fingerprint);
+
+        // Moving the logo keeps the fingerprint, changing it does not
+        let moved_path = dir.path().join("moved.png");
+        fs::rename(&logo_path, &moved_path).unwrap();
+        let moved = CertificateTemplate { logo_path: Some(moved_path.to_string_lossy().into_owned()), ..template() };
+        assert_eq!(moved.load().unwrap().fingerprint, first.fingerprint);
+        DynamicImage::new_rgb8(4, 3).save(&moved_path).unwrap();
+        assert_ne!(moved.load().unwrap().fingerprint, first.fingerprint);
+    }
+}
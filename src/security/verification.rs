@@ -0,0 +1,114 @@
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use uuid::Uuid;
+
+use crate::core::{OperationHistory, WipeError, WipeErrorCode, WipeResult2};
+use crate::security::ErasureCertificate;
+
+pub use hdd_tool_certificate::SUPPORTED_SCHEMA_VERSIONS;
+
+/// Outcome of cross-checking a certificate against the local operation history
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum HistoryMatch {
+    /// The wipe operation exists and recorded the same drive serial
+    Matched { operation_id: Uuid },
+    /// The wipe operation exists but was run against a different drive
+    SerialMismatch { recorded_serial: String },
+    /// No wipe operation with this id is in the history database
+    NotFound,
+    /// History was not available for the check
+    Unavailable,
+}
+
+/// Structured verdict returned by certificate verification
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CertificateVerdict {
+    pub certificate_id: Uuid,
+    pub schema_version: String,
+    pub schema_supported: bool,
+    pub hash_valid: bool,
+    pub signature_valid: bool,
+    pub key_fingerprint: String,
+    pub serial_number: String,
+    pub history: HistoryMatch,
+    pub issues: Vec<String>,
+}
+
+impl CertificateVerdict {
+    /// The certificate is authentic; a history mismatch is also treated as a failure
+    pub fn is_valid(&self) -> bool {
+        self.schema_supported
+            && self.hash_valid
+            && self.signature_valid
+            && !matches!(self.history, HistoryMatch::SerialMismatch { .. })
+    }
+}
+
+/// Load a signed JSON certificate and verify it. `history` enables the
+/// cross-check of the embedded drive serial against recorded operations.
+pub fn verify_certificate_file<P: AsRef<Path>>(
+    path: P,
+    history: Option<&OperationHistory>,
+) -> WipeResult2<CertificateVerdict> {
+    let content = std::fs::read_to_string(path)
+        .map_err(|e| WipeError {
+            code: WipeErrorCode::UnknownError,
+            message: format!("Failed to read certificate: {}", e),
+            sector: None,
+        })?;
+
+    let certificate: ErasureCertificate = serde_json::from_str(&content)
+        .map_err(|e| WipeError {
+            code: WipeErrorCode::UnknownError,
+            message: format!("Failed to parse certificate: {}", e),
+            sector: None,
+        })?;
+
+    Ok(verify_certificate(&certificate, history))
+}
+
+pub fn verify_certificate(
+    certificate: &ErasureCertificate,
+    history: Option<&OperationHistory>,
+) -> CertificateVerdict {
+    let verification = hdd_tool_certificate::verify(certificate);
+    let mut issues = verification.issues;
+
+    let serial = &certificate.drive_info.serial_number;
+    let history_match = match history {
+        None => HistoryMatch::Unavailable,
+        Some(history) => match history.get(certificate.wipe_request_id) {
+            None => HistoryMatch::NotFound,
+            Some(record) => match record.result.as_ref().map(|r| &r.drive_geometry.serial) {
+                Some(recorded) if recorded == serial => HistoryMatch::Matched {
+                    operation_id: record.operation_id,
+                },
+                Some(recorded) => HistoryMatch::SerialMismatch {
+                    recorded_serial: recorded.clone(),
+                },
+                None => HistoryMatch::NotFound,
+            },
+        },
+    };
+
+    match &history_match {
+        HistoryMatch::SerialMismatch { recorded_serial } => issues.push(format!(
+            "Drive serial {} does not match recorded serial {}",
+            serial, recorded_serial
+        )),
+        HistoryMatch::NotFound => issues.push("Wipe operation not found in local history".to_string()),
+        _ => {}
+    }
+
+    CertificateVerdict {
+        certificate_id: verification.certificate_id,
+        schema_version: verification.schema_version,
+        schema_supported: verification.schema_supported,
+        hash_valid: verification.hash_valid,
+        signature_valid: verification.signature_valid,
+        key_fingerprint: verification.key_fingerprint,
+        serial_number: verification.serial_number,
+        history: history_match,
+        issues,
+    }
+}
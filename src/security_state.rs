@@ -0,0 +1,102 @@
+//! ATA security state left on a drive after the wipe
+//!
+//! Remotely managed kiosks and similar fleets want every wiped drive handed
+//! back in a known security state. A wipe request can ask for one of three:
+//!
+//! - **Disable**: no user password, so the drive is usable in any machine.
+//! - **Freeze**: SECURITY FREEZE LOCK, so nothing can set a password or erase
+//!   the drive until it is next power-cycled.
+//! - **Brick**: a random user password that is never stored or shown, at
+//!   maximum security, then a freeze lock. ⚠️ This is irreversible: the drive
+//!   locks at its next power-up and no one can unlock it. At most the
+//!   manufacturer's master password, where still known, can erase it again.
+//!
+//! The outcome is recorded as a post-processing step of the wipe. A drive
+//! that cannot reach the requested state does not fail the wipe.
+
+use tracing::{info, warn};
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+
+use crate::ata_commands::{AtaInterface, SECURITY_ERASE_PASSWORD};
+use crate::core::PostProcessingStep;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum SecurityStateAction {
+    /// Remove the user password, if one is set
+    Disable,
+    /// Refuse security commands until the next power cycle
+    Freeze,
+    /// Lock the drive for good with a password nobody knows (irreversible)
+    Brick,
+}
+
+impl SecurityStateAction {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.to_ascii_lowercase().as_str() {
+            "disable" => Some(SecurityStateAction::Disable),
+            "freeze" => Some(SecurityStateAction::Freeze),
+            "brick" => Some(SecurityStateAction::Brick),
+            _ => None,
+        }
+    }
+
+    pub fn step_name(&self) -> &'static str {
+        match self {
+            SecurityStateAction::Disable => "Disable ATA security",
+            SecurityStateAction::Freeze => "Freeze ATA security",
+            SecurityStateAction::Brick => "Lock drive permanently",
+        }
+    }
+}
+
+/// Bring the drive at `device_path` into the state `action` asks for
+pub fn apply_security_state(device_path: &str, action: SecurityStateAction) -> PostProcessingStep {
+    let (success, detail) = match set_state(device_path, action) {
+        Ok(detail) => (true, detail),
+        Err(detail) => (false, detail),
+    };
+    if success {
+        info!(step = action.step_name(), detail = %detail, "Security state applied");
+    } else {
+        warn!(step = action.step_name(), detail = %detail, "Security state not applied");
+    }
+    PostProcessingStep { step: action.step_name().to_string(), success, detail }
+}
+
+fn set_state(device_path: &str, action: SecurityStateAction) -> Result<String, String> {
+    let ata = AtaInterface::new(device_path).map_err(|e| format!("Failed to open drive: {}", e))?;
+    let info = ata.get_drive_info().map_err(|e| e.to_string())?;
+    if !info.security_supported {
+        return Err("Drive does not support the ATA Security feature set".to_string());
+    }
+
+    match action {
+        SecurityStateAction::Freeze if info.security_frozen => Ok("Security was already frozen".to_string()),
+        SecurityStateAction::Freeze => {
+            ata.security_freeze_lock().map_err(|e| e.to_string())?;
+            Ok("Security commands refused until the next power cycle".to_string())
+        }
+        _ if info.security_frozen => Err("Drive security is frozen; power-cycle the drive first".to_string()),
+        SecurityStateAction::Disable if !info.security_enabled => Ok("No user password is set".to_string()),
+        SecurityStateAction::Disable => {
+            // The only password this tool ever leaves behind is the one of an interrupted security erase
+            ata.security_disable_password(SECURITY_ERASE_PASSWORD)
+                .map_err(|_| "A user password of unknown value is set".to_string())?;
+            Ok("User password removed".to_string())
+        }
+        SecurityStateAction::Brick if info.security_enabled => {
+            Err("A user password is already set; disable it before locking the drive".to_string())
+        }
+        SecurityStateAction::Brick => {
+            let mut password = [0u8; 32];
+            rand::thread_rng().fill_bytes(&mut password);
+            let set = ata.security_set_user_password(&password, true);
+            password.fill(0);
+            set.map_err(|e| e.to_string())?;
+            // Nothing may replace the password before the drive powers down
+            ata.security_freeze_lock().map_err(|e| format!("Password set, but freezing failed: {}", e))?;
+            Ok("Random user password set and discarded; the drive locks at its next power-up".to_string())
+        }
+    }
+}
@@ -1,6 +1,9 @@
-use warp::Filter;
+use warp::{Filter, Reply};
 use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tracing::info;
 use uuid::Uuid;
+use crate::core::SafeWipeErrorCode;
 use crate::server::{DatabaseManager, models::*};
 
 pub async fn start_server(database_url: String, port: u16) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
@@ -43,6 +46,20 @@ pub async fn start_server(database_url: String, port: u16) -> Result<(), Box<dyn
         .and(with_db(db.clone()))
         .and_then(get_certificates);
     
+    let search_certs = warp::path!("api" / "certificates" / "search")
+        .and(warp::get())
+        .and(warp::header::<String>("authorization"))
+        .and(warp::query::<CertificateSearchQuery>())
+        .and(with_db(db.clone()))
+        .and_then(search_certificates);
+
+    let export_certs = warp::path!("api" / "certificates" / "export.csv")
+        .and(warp::get())
+        .and(warp::header::<String>("authorization"))
+        .and(warp::query::<CertificateQuery>())
+        .and(with_db(db.clone()))
+        .and_then(export_certificates_csv);
+    
     let get_logs = warp::path("api")
         .and(warp::path("logs"))
         .and(warp::get())
@@ -51,15 +68,90 @@ pub async fn start_server(database_url: String, port: u16) -> Result<(), Box<dyn
         .and(with_db(db.clone()))
         .and_then(get_sanitization_logs);
     
+    // Fleet administration (user token)
+    let register_station = warp::path!("api" / "stations")
+        .and(warp::post())
+        .and(warp::header::<String>("authorization"))
+        .and(warp::body::json())
+        .and(with_db(db.clone()))
+        .and_then(register_station);
+
+    let list_stations = warp::path!("api" / "stations")
+        .and(warp::get())
+        .and(warp::header::<String>("authorization"))
+        .and(with_db(db.clone()))
+        .and_then(list_stations);
+
+    let station_devices = warp::path!("api" / "stations" / Uuid / "devices")
+        .and(warp::get())
+        .and(warp::header::<String>("authorization"))
+        .and(with_db(db.clone()))
+        .and_then(get_station_devices);
+
+    let enqueue_job = warp::path!("api" / "jobs")
+        .and(warp::post())
+        .and(warp::header::<String>("authorization"))
+        .and(warp::body::json())
+        .and(with_db(db.clone()))
+        .and_then(enqueue_job);
+
+    let list_jobs = warp::path!("api" / "jobs")
+        .and(warp::get())
+        .and(warp::header::<String>("authorization"))
+        .and(warp::query::<JobQuery>())
+        .and(with_db(db.clone()))
+        .and_then(list_jobs);
+
+    // Station agent endpoints (station token)
+    let check_in = warp::path!("api" / "station" / "checkin")
+        .and(warp::post())
+        .and(warp::header::<String>("authorization"))
+        .and(warp::body::json())
+        .and(with_db(db.clone()))
+        .and_then(station_check_in);
+
+    let next_job = warp::path!("api" / "station" / "jobs" / "next")
+        .and(warp::get())
+        .and(warp::header::<String>("authorization"))
+        .and(warp::query::<WaitQuery>())
+        .and(with_db(db.clone()))
+        .and_then(next_station_job);
+
+    let job_progress = warp::path!("api" / "station" / "jobs" / Uuid / "progress")
+        .and(warp::post())
+        .and(warp::header::<String>("authorization"))
+        .and(warp::body::json())
+        .and(with_db(db.clone()))
+        .and_then(report_job_progress);
+
+    let job_certificate = warp::path!("api" / "station" / "jobs" / Uuid / "certificate")
+        .and(warp::post())
+        .and(warp::header::<String>("authorization"))
+        .and(warp::body::json())
+        .and(with_db(db.clone()))
+        .and_then(upload_job_certificate);
+
     // Static files for web dashboard
     let static_files = warp::path("dashboard")
         .and(warp::fs::dir("web/"));
     
+    // The archive routes go before `get_certs`, which matches any path under /api/certificates
     let routes = register
         .or(login)
+        .or(search_certs)
+        .or(export_certs)
         .or(submit_cert)
         .or(get_certs)
         .or(get_logs)
+        .or(register_station)
+        .or(list_stations)
+        .or(station_devices)
+        .or(enqueue_job)
+        .or(list_jobs)
+        .or(check_in)
+        .or(next_job)
+        .or(job_progress)
+        .or(job_certificate)
         .or(static_files)
         .with(cors);
     
@@ -70,7 +162,18 @@ pub async fn start_server(database_url: String, port: u16) -> Result<(), Box<dyn
     println!("   POST /api/login - User login");
     println!("   POST /api/certificates - Submit certificate");
     println!("   GET  /api/certificates - Get user certificates");
+    println!("   GET  /api/certificates/search?serial=&operator=&since=&until= - Search the certificate archive");
+    println!("   GET  /api/certificates/export.csv?serial=&operator=&since=&until= - Export matching certificates as CSV");
     println!("   GET  /api/logs - Get sanitization logs");
+    println!("   POST /api/stations - Register a wipe station");
+    println!("   GET  /api/stations - List stations");
+    println!("   GET  /api/stations/{{id}}/devices - Disks a station reported");
+    println!("   POST /api/jobs - Queue a wipe on a station");
+    println!("   GET  /api/jobs?station_id=&status= - List queued and finished jobs");
+    println!("   POST /api/station/checkin - Station check-in with its disks");
+    println!("   GET  /api/station/jobs/next?wait=N - Station pulls its next job");
+    println!("   POST /api/station/jobs/{{id}}/progress - Station reports progress");
+    println!("   POST /api/station/jobs/{{id}}/certificate - Station uploads the certificate");
     
     warp::serve(routes)
         .run(([0, 0, 0, 0], port))
@@ -93,15 +196,75 @@ struct PaginationQuery {
 
 fn default_limit() -> i64 { 50 }
 
+#[derive(serde::Deserialize)]
+struct CertificateSearchQuery {
+    #[serde(flatten)]
+    filter: CertificateQuery,
+    #[serde(default = "default_limit")]
+    limit: i64,
+    #[serde(default)]
+    offset: i64,
+}
+
+#[derive(serde::Deserialize)]
+struct JobQuery {
+    station_id: Option<Uuid>,
+    status: Option<String>,
+    #[serde(default = "default_limit")]
+    limit: i64,
+    #[serde(default)]
+    offset: i64,
+}
+
+#[derive(serde::Deserialize)]
+struct WaitQuery {
+    /// Seconds to hold the request open while no job is queued
+    #[serde(default)]
+    wait: u64,
+}
+
+/// Longest a station may long-poll for its next job
+const MAX_JOB_WAIT_SECS: u64 = 60;
+
+/// How often a long-poll re-checks the queue
+const JOB_POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Why a request failed and the message shown for it
+type Failure = (SafeWipeErrorCode, String);
+
+/// A failure of the database or the server itself
+fn internal(message: String) -> Failure {
+    (SafeWipeErrorCode::Internal, message)
+}
+
 // Extract user ID from Bearer token (simplified - in production use JWT)
-fn extract_user_id(auth_header: &str) -> Result<Uuid, String> {
+fn extract_user_id(auth_header: &str) -> Result<Uuid, Failure> {
     if let Some(token) = auth_header.strip_prefix("Bearer ") {
-        Uuid::parse_str(token).map_err(|_| "Invalid token format".to_string())
+        Uuid::parse_str(token).map_err(|_| (SafeWipeErrorCode::NotAuthenticated, "Invalid token format".to_string()))
     } else {
-        Err("Invalid authorization header".to_string())
+        Err((SafeWipeErrorCode::NotAuthenticated, "Invalid authorization header".to_string()))
+    }
+}
+
+// Resolve a station's Bearer token to its registration
+async fn authenticate_station(auth_header: &str, db: &DatabaseManager) -> Result<Station, Failure> {
+    let token = auth_header
+        .strip_prefix("Bearer ")
+        .ok_or((SafeWipeErrorCode::NotAuthenticated, "Invalid authorization header".to_string()))?;
+    match db.authenticate_station(token.trim()).await {
+        Ok(Some(station)) => Ok(station),
+        Ok(None) => Err((SafeWipeErrorCode::NotAuthenticated, "Unknown station token".to_string())),
+        Err(e) => Err(internal(format!("Station authentication failed: {}", e))),
     }
 }
 
+fn reply<T: serde::Serialize>(result: Result<T, Failure>) -> Result<warp::reply::Json, warp::Rejection> {
+    Ok(match result {
+        Ok(data) => warp::reply::json(&ApiResponse::success(data)),
+        Err((code, e)) => warp::reply::json(&ApiResponse::<()>::error(code, e)),
+    })
+}
+
 async fn register_user(
     req: CreateUserRequest,
     db: Arc<DatabaseManager>,
@@ -116,7 +279,7 @@ async fn register_user(
             Ok(warp::reply::json(&response))
         }
         Err(e) => {
-            let response: ApiResponse<()> = ApiResponse::error(format!("Registration failed: {}", e));
+            let response: ApiResponse<()> = ApiResponse::error(SafeWipeErrorCode::Internal, format!("Registration failed: {}", e));
             Ok(warp::reply::json(&response))
         }
     }
@@ -136,11 +299,11 @@ async fn login_user(
             Ok(warp::reply::json(&response))
         }
         Ok(None) => {
-            let response: ApiResponse<()> = ApiResponse::error("Invalid credentials".to_string());
+            let response: ApiResponse<()> = ApiResponse::error(SafeWipeErrorCode::NotAuthenticated, "Invalid credentials".to_string());
             Ok(warp::reply::json(&response))
         }
         Err(e) => {
-            let response: ApiResponse<()> = ApiResponse::error(format!("Login failed: {}", e));
+            let response: ApiResponse<()> = ApiResponse::error(SafeWipeErrorCode::Internal, format!("Login failed: {}", e));
             Ok(warp::reply::json(&response))
         }
     }
@@ -159,13 +322,13 @@ async fn submit_certificate(
                     Ok(warp::reply::json(&response))
                 }
                 Err(e) => {
-                    let response: ApiResponse<()> = ApiResponse::error(format!("Failed to store certificate: {}", e));
+                    let response: ApiResponse<()> = ApiResponse::error(SafeWipeErrorCode::Internal, format!("Failed to store certificate: {}", e));
                     Ok(warp::reply::json(&response))
                 }
             }
         }
-        Err(e) => {
-            let response: ApiResponse<()> = ApiResponse::error(e);
+        Err((code, e)) => {
+            let response: ApiResponse<()> = ApiResponse::error(code, e);
             Ok(warp::reply::json(&response))
         }
     }
@@ -184,13 +347,13 @@ async fn get_certificates(
                     Ok(warp::reply::json(&response))
                 }
                 Err(e) => {
-                    let response: ApiResponse<()> = ApiResponse::error(format!("Failed to get certificates: {}", e));
+                    let response: ApiResponse<()> = ApiResponse::error(SafeWipeErrorCode::Internal, format!("Failed to get certificates: {}", e));
                     Ok(warp::reply::json(&response))
                 }
             }
         }
-        Err(e) => {
-            let response: ApiResponse<()> = ApiResponse::error(e);
+        Err((code, e)) => {
+            let response: ApiResponse<()> = ApiResponse::error(code, e);
             Ok(warp::reply::json(&response))
         }
     }
@@ -209,14 +372,247 @@ async fn get_sanitization_logs(
                     Ok(warp::reply::json(&response))
                 }
                 Err(e) => {
-                    let response: ApiResponse<()> = ApiResponse::error(format!("Failed to get logs: {}", e));
+                    let response: ApiResponse<()> = ApiResponse::error(SafeWipeErrorCode::Internal, format!("Failed to get logs: {}", e));
                     Ok(warp::reply::json(&response))
                 }
             }
         }
-        Err(e) => {
-            let response: ApiResponse<()> = ApiResponse::error(e);
+        Err((code, e)) => {
+            let response: ApiResponse<()> = ApiResponse::error(code, e);
             Ok(warp::reply::json(&response))
         }
     }
-}
\ No newline at end of file
+}
+
+async fn search_certificates(
+    auth_header: String,
+    query: CertificateSearchQuery,
+    db: Arc<DatabaseManager>,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    reply(match extract_user_id(&auth_header) {
+        Ok(_) => db.search_certificates(&query.filter, Some(query.limit), query.offset).await
+            .map_err(|e| internal(format!("Failed to search certificates: {}", e))),
+        Err(e) => Err(e),
+    })
+}
+
+/// Every certificate matching the filter as CSV, without the certificate bodies
+async fn export_certificates_csv(
+    auth_header: String,
+    query: CertificateQuery,
+    db: Arc<DatabaseManager>,
+) -> Result<warp::reply::Response, warp::Rejection> {
+    let result = match extract_user_id(&auth_header) {
+        Ok(_) => db.search_certificates(&query, None, 0).await
+            .map_err(|e| internal(format!("Failed to export certificates: {}", e))),
+        Err(e) => Err(e),
+    };
+
+    match result {
+        Ok(response) => {
+            info!(certificates = response.total, "Exported certificates as CSV");
+            let csv = certificates_to_csv(&response.certificates);
+            Ok(warp::reply::with_header(
+                warp::reply::with_header(csv, "content-type", "text/csv; charset=utf-8"),
+                "content-disposition",
+                "attachment; filename=\"certificates.csv\"",
+            )
+            .into_response())
+        }
+        Err((code, e)) => Ok(warp::reply::json(&ApiResponse::<()>::error(code, e)).into_response()),
+    }
+}
+
+fn certificates_to_csv(certificates: &[Certificate]) -> String {
+    let mut csv = String::from(
+        "certificate_id,device_serial,device_info,operator,sanitization_method,completed_at,uploaded_at,signature_valid,file_hash\n",
+    );
+    for certificate in certificates {
+        let fields = [
+            certificate.id.to_string(),
+            certificate.device_serial.clone().unwrap_or_default(),
+            certificate.device_info.clone(),
+            certificate.operator.clone().unwrap_or_default(),
+            certificate.sanitization_method.clone(),
+            certificate.completed_at.map(|t| t.to_rfc3339()).unwrap_or_default(),
+            certificate.created_at.to_rfc3339(),
+            certificate.signature_valid.map(|v| v.to_string()).unwrap_or_default(),
+            certificate.file_hash.clone(),
+        ];
+        let row: Vec<String> = fields.iter().map(|field| csv_field(field)).collect();
+        csv.push_str(&row.join(","));
+        csv.push('\n');
+    }
+    csv
+}
+
+/// Quote a CSV field when needed. Fields that a spreadsheet would run as a
+/// formula get a leading apostrophe.
+fn csv_field(value: &str) -> String {
+    let value = if value.starts_with(['=', '+', '-', '@']) {
+        format!("'{}", value)
+    } else {
+        value.to_string()
+    };
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value
+    }
+}
+
+async fn register_station(
+    auth_header: String,
+    req: RegisterStationRequest,
+    db: Arc<DatabaseManager>,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let result = match extract_user_id(&auth_header) {
+        Ok(user_id) => db.register_station(user_id, req).await
+            .map_err(|e| internal(format!("Failed to register station: {}", e))),
+        Err(e) => Err(e),
+    };
+    if let Ok(registration) = &result {
+        info!(station = %registration.station.name, station_id = %registration.station.id, "Station registered");
+    }
+    reply(result)
+}
+
+async fn list_stations(
+    auth_header: String,
+    db: Arc<DatabaseManager>,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    reply(match extract_user_id(&auth_header) {
+        Ok(_) => db.list_stations().await.map_err(|e| internal(format!("Failed to list stations: {}", e))),
+        Err(e) => Err(e),
+    })
+}
+
+async fn get_station_devices(
+    station_id: Uuid,
+    auth_header: String,
+    db: Arc<DatabaseManager>,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    reply(match extract_user_id(&auth_header) {
+        Ok(_) => db.get_station_devices(station_id).await.map_err(|e| internal(format!("Failed to get devices: {}", e))),
+        Err(e) => Err(e),
+    })
+}
+
+async fn enqueue_job(
+    auth_header: String,
+    req: EnqueueJobRequest,
+    db: Arc<DatabaseManager>,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let result = match extract_user_id(&auth_header) {
+        Ok(user_id) => db.enqueue_job(user_id, req).await.map_err(|e| internal(format!("Failed to queue job: {}", e))),
+        Err(e) => Err(e),
+    };
+    if let Ok(job) = &result {
+        info!(job = %job.id, device = %job.device, station_id = %job.station_id, "Job queued");
+    }
+    reply(result)
+}
+
+async fn list_jobs(
+    auth_header: String,
+    query: JobQuery,
+    db: Arc<DatabaseManager>,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    reply(match extract_user_id(&auth_header) {
+        Ok(_) => db.list_jobs(query.station_id, query.status.as_deref(), query.limit, query.offset).await
+            .map_err(|e| internal(format!("Failed to list jobs: {}", e))),
+        Err(e) => Err(e),
+    })
+}
+
+async fn station_check_in(
+    auth_header: String,
+    req: StationCheckIn,
+    db: Arc<DatabaseManager>,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let result = match authenticate_station(&auth_header, &db).await {
+        Ok(station) => db.check_in_station(station.id, &req.devices).await
+            .map(|queued_jobs| CheckInResponse { station_id: station.id, queued_jobs })
+            .map_err(|e| internal(format!("Check-in failed: {}", e))),
+        Err(e) => Err(e),
+    };
+    reply(result)
+}
+
+/// The station's next queued job, or null. With `wait`, the request is held
+/// open until a job is queued or the wait runs out.
+async fn next_station_job(
+    auth_header: String,
+    query: WaitQuery,
+    db: Arc<DatabaseManager>,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let station = match authenticate_station(&auth_header, &db).await {
+        Ok(station) => station,
+        Err(e) => return reply::<Option<FleetJob>>(Err(e)),
+    };
+
+    let deadline = Instant::now() + Duration::from_secs(query.wait.min(MAX_JOB_WAIT_SECS));
+    loop {
+        match db.assign_next_job(station.id).await {
+            Ok(Some(job)) => {
+                info!(job = %job.id, station = %station.name, "Job assigned");
+                return reply(Ok(Some(job)));
+            }
+            Ok(None) if Instant::now() < deadline => tokio::time::sleep(JOB_POLL_INTERVAL).await,
+            Ok(None) => return reply(Ok(None::<FleetJob>)),
+            Err(e) => return reply::<Option<FleetJob>>(Err(internal(format!("Failed to fetch job: {}", e)))),
+        }
+    }
+}
+
+async fn report_job_progress(
+    job_id: Uuid,
+    auth_header: String,
+    update: JobProgressUpdate,
+    db: Arc<DatabaseManager>,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let station = match authenticate_station(&auth_header, &db).await {
+        Ok(station) => station,
+        Err(e) => return reply::<FleetJob>(Err(e)),
+    };
+    if !FleetJob::STATUSES.contains(&update.status.as_str()) || update.status == "queued" {
+        return reply::<FleetJob>(Err((SafeWipeErrorCode::InvalidArgument, format!("Invalid job status '{}'", update.status))));
+    }
+
+    reply(match db.update_job_progress(station.id, job_id, &update).await {
+        Ok(Some(job)) => Ok(job),
+        Ok(None) => Err((SafeWipeErrorCode::NotFound, format!("No open job {} for this station", job_id))),
+        Err(e) => Err(internal(format!("Failed to update job: {}", e))),
+    })
+}
+
+async fn upload_job_certificate(
+    job_id: Uuid,
+    auth_header: String,
+    req: SubmitCertificateRequest,
+    db: Arc<DatabaseManager>,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let station = match authenticate_station(&auth_header, &db).await {
+        Ok(station) => station,
+        Err(e) => return reply::<Certificate>(Err(e)),
+    };
+
+    reply(match db.attach_job_certificate(station.id, job_id, req).await {
+        Ok(Some(certificate)) => Ok(certificate),
+        Ok(None) => Err((SafeWipeErrorCode::NotFound, format!("No job {} for this station", job_id))),
+        Err(e) => Err(internal(format!("Failed to store certificate: {}", e))),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_csv_field_quoting() {
+        assert_eq!(csv_field("WD-123"), "WD-123");
+        assert_eq!(csv_field("Disk, 2TB"), "\"Disk, 2TB\"");
+        assert_eq!(csv_field("say \"hi\""), "\"say \"\"hi\"\"\"");
+        assert_eq!(csv_field("=SUM(A1)"), "'=SUM(A1)");
+    }
+}
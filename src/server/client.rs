@@ -8,6 +8,8 @@ pub struct ServerClient {
     base_url: String,
     token: Option<String>,
     user_id: Option<Uuid>,
+    station_id: Option<Uuid>,
+    station_token: Option<String>,
 }
 
 impl ServerClient {
@@ -17,8 +19,17 @@ impl ServerClient {
             base_url: server_url.to_string(),
             token: None,
             user_id: None,
+            station_id: None,
+            station_token: None,
         }
     }
+
+    /// Act as an already registered station
+    pub fn with_station_credentials(mut self, station_id: Uuid, station_token: &str) -> Self {
+        self.station_id = Some(station_id);
+        self.station_token = Some(station_token.to_string());
+        self
+    }
     
     pub async fn register(&mut self, username: &str, email: &str, password: &str) -> Result<LoginResponse, Box<dyn std::error::Error + Send + Sync>> {
         let req = CreateUserRequest {
@@ -153,4 +164,140 @@ impl ServerClient {
         self.token = None;
         self.user_id = None;
     }
-}
\ No newline at end of file
+
+    // ---- Fleet administration (requires a logged-in user) ----
+
+    /// Register this machine as a wipe station and keep its credentials.
+    /// The token is only returned once; store it for later runs.
+    pub async fn register_station(&mut self, name: &str, hostname: &str) -> Result<StationRegistration, Box<dyn std::error::Error + Send + Sync>> {
+        let token = self.token.as_ref().ok_or("Not logged in")?;
+
+        let req = RegisterStationRequest {
+            name: name.to_string(),
+            hostname: hostname.to_string(),
+            agent_version: env!("CARGO_PKG_VERSION").to_string(),
+        };
+
+        let response = self.client
+            .post(format!("{}/api/stations", self.base_url))
+            .header("Authorization", format!("Bearer {}", token))
+            .json(&req)
+            .send()
+            .await?;
+
+        let registration: StationRegistration = Self::into_data(response.json().await?)?;
+        self.station_id = Some(registration.station.id);
+        self.station_token = Some(registration.station_token.clone());
+        Ok(registration)
+    }
+
+    pub async fn list_stations(&self) -> Result<Vec<Station>, Box<dyn std::error::Error + Send + Sync>> {
+        let token = self.token.as_ref().ok_or("Not logged in")?;
+
+        let response = self.client
+            .get(format!("{}/api/stations", self.base_url))
+            .header("Authorization", format!("Bearer {}", token))
+            .send()
+            .await?;
+
+        Self::into_data(response.json().await?)
+    }
+
+    pub async fn enqueue_job(&self, req: &EnqueueJobRequest) -> Result<FleetJob, Box<dyn std::error::Error + Send + Sync>> {
+        let token = self.token.as_ref().ok_or("Not logged in")?;
+
+        let response = self.client
+            .post(format!("{}/api/jobs", self.base_url))
+            .header("Authorization", format!("Bearer {}", token))
+            .json(req)
+            .send()
+            .await?;
+
+        Self::into_data(response.json().await?)
+    }
+
+    // ---- Station agent (requires station credentials) ----
+
+    /// Report this station's disks and learn how many jobs are waiting
+    pub async fn check_in(&self, devices: Vec<DeviceReport>) -> Result<CheckInResponse, Box<dyn std::error::Error + Send + Sync>> {
+        let token = self.station_token.as_ref().ok_or("Station not registered")?;
+
+        let response = self.client
+            .post(format!("{}/api/station/checkin", self.base_url))
+            .header("Authorization", format!("Bearer {}", token))
+            .json(&StationCheckIn { devices })
+            .send()
+            .await?;
+
+        Self::into_data(response.json().await?)
+    }
+
+    /// Take the next job queued for this station, waiting up to
+    /// `wait_seconds` for one to arrive
+    pub async fn pull_job(&self, wait_seconds: u64) -> Result<Option<FleetJob>, Box<dyn std::error::Error + Send + Sync>> {
+        let token = self.station_token.as_ref().ok_or("Station not registered")?;
+
+        let response = self.client
+            .get(format!("{}/api/station/jobs/next?wait={}", self.base_url, wait_seconds))
+            .header("Authorization", format!("Bearer {}", token))
+            .send()
+            .await?;
+
+        let api_response: ApiResponse<Option<FleetJob>> = response.json().await?;
+        if api_response.success {
+            Ok(api_response.data.flatten())
+        } else {
+            Err(api_response.message.into())
+        }
+    }
+
+    pub async fn push_progress(&self, job_id: Uuid, update: &JobProgressUpdate) -> Result<FleetJob, Box<dyn std::error::Error + Send + Sync>> {
+        let token = self.station_token.as_ref().ok_or("Station not registered")?;
+
+        let response = self.client
+            .post(format!("{}/api/station/jobs/{}/progress", self.base_url, job_id))
+            .header("Authorization", format!("Bearer {}", token))
+            .json(update)
+            .send()
+            .await?;
+
+        Self::into_data(response.json().await?)
+    }
+
+    pub async fn upload_certificate(
+        &self,
+        job_id: Uuid,
+        certificate_data: &str,
+        device_info: &str,
+        sanitization_method: &str,
+    ) -> Result<Certificate, Box<dyn std::error::Error + Send + Sync>> {
+        let token = self.station_token.as_ref().ok_or("Station not registered")?;
+
+        let req = SubmitCertificateRequest {
+            certificate_data: certificate_data.to_string(),
+            device_info: device_info.to_string(),
+            sanitization_method: sanitization_method.to_string(),
+        };
+
+        let response = self.client
+            .post(format!("{}/api/station/jobs/{}/certificate", self.base_url, job_id))
+            .header("Authorization", format!("Bearer {}", token))
+            .json(&req)
+            .send()
+            .await?;
+
+        Self::into_data(response.json().await?)
+    }
+
+    pub fn get_station_id(&self) -> Option<Uuid> {
+        self.station_id
+    }
+
+    fn into_data<T>(api_response: ApiResponse<T>) -> Result<T, Box<dyn std::error::Error + Send + Sync>> {
+        if api_response.success {
+            api_response.data.ok_or("No data in response".into())
+        } else {
+            Err(api_response.message.into())
+        }
+    }
+}
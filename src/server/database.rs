@@ -2,6 +2,7 @@ use sqlx::{PgPool, Row};
 use chrono::Utc;
 use uuid::Uuid;
 use sha2::{Sha256, Digest};
+use rand::RngCore;
 use crate::server::models::*;
 
 pub struct DatabaseManager {
@@ -50,6 +51,17 @@ impl DatabaseManager {
         .execute(&self.pool)
         .await?;
         
+        // Archive metadata read from signed certificates
+        sqlx::query(r#"
+            ALTER TABLE certificates
+                ADD COLUMN IF NOT EXISTS device_serial VARCHAR(255),
+                ADD COLUMN IF NOT EXISTS operator VARCHAR(255),
+                ADD COLUMN IF NOT EXISTS completed_at TIMESTAMPTZ,
+                ADD COLUMN IF NOT EXISTS signature_valid BOOLEAN
+        "#)
+        .execute(&self.pool)
+        .await?;
+        
         // Create sanitization logs table
         sqlx::query(r#"
             CREATE TABLE IF NOT EXISTS sanitization_logs (
@@ -70,13 +82,76 @@ impl DatabaseManager {
         .execute(&self.pool)
         .await?;
         
+        // Create fleet tables: registered stations, their disks and queued wipe jobs
+        sqlx::query(r#"
+            CREATE TABLE IF NOT EXISTS stations (
+                id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+                name VARCHAR(255) NOT NULL,
+                hostname VARCHAR(255) NOT NULL,
+                agent_version VARCHAR(50) NOT NULL,
+                token_hash VARCHAR(255) UNIQUE NOT NULL,
+                registered_by UUID NOT NULL REFERENCES users(id),
+                registered_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+                last_seen TIMESTAMPTZ
+            )
+        "#)
+        .execute(&self.pool)
+        .await?;
+
+        sqlx::query(r#"
+            CREATE TABLE IF NOT EXISTS station_devices (
+                station_id UUID NOT NULL REFERENCES stations(id) ON DELETE CASCADE,
+                device_path VARCHAR(500) NOT NULL,
+                model VARCHAR(255) NOT NULL,
+                serial_number VARCHAR(255) NOT NULL,
+                size_bytes BIGINT NOT NULL,
+                reported_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+                PRIMARY KEY (station_id, device_path)
+            )
+        "#)
+        .execute(&self.pool)
+        .await?;
+
+        sqlx::query(r#"
+            CREATE TABLE IF NOT EXISTS wipe_jobs (
+                id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+                station_id UUID NOT NULL REFERENCES stations(id),
+                device VARCHAR(500) NOT NULL,
+                target_type VARCHAR(50) NOT NULL,
+                standard VARCHAR(100) NOT NULL,
+                scope TEXT NOT NULL,
+                verify BOOLEAN NOT NULL DEFAULT TRUE,
+                status VARCHAR(50) NOT NULL DEFAULT 'queued',
+                progress_percent DOUBLE PRECISION NOT NULL DEFAULT 0,
+                bytes_processed BIGINT NOT NULL DEFAULT 0,
+                message TEXT,
+                operation_id UUID,
+                certificate_id UUID REFERENCES certificates(id),
+                created_by UUID NOT NULL REFERENCES users(id),
+                created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+                assigned_at TIMESTAMPTZ,
+                updated_at TIMESTAMPTZ,
+                completed_at TIMESTAMPTZ
+            )
+        "#)
+        .execute(&self.pool)
+        .await?;
+
         // Create indexes for better performance
         sqlx::query("CREATE INDEX IF NOT EXISTS idx_certificates_user_id ON certificates(user_id)")
             .execute(&self.pool).await?;
+        sqlx::query("CREATE INDEX IF NOT EXISTS idx_certificates_serial ON certificates(device_serial)")
+            .execute(&self.pool).await?;
+        sqlx::query("CREATE INDEX IF NOT EXISTS idx_certificates_operator ON certificates(operator)")
+            .execute(&self.pool).await?;
+        sqlx::query("CREATE INDEX IF NOT EXISTS idx_certificates_completed_at ON certificates(completed_at)")
+            .execute(&self.pool).await?;
         sqlx::query("CREATE INDEX IF NOT EXISTS idx_logs_user_id ON sanitization_logs(user_id)")
             .execute(&self.pool).await?;
         sqlx::query("CREATE INDEX IF NOT EXISTS idx_logs_started_at ON sanitization_logs(started_at)")
             .execute(&self.pool).await?;
+        sqlx::query("CREATE INDEX IF NOT EXISTS idx_jobs_station_status ON wipe_jobs(station_id, status, created_at)")
+            .execute(&self.pool).await?;
         
         Ok(())
     }
@@ -145,20 +220,27 @@ impl DatabaseManager {
             hasher.update(req.certificate_data.as_bytes());
             format!("{:x}", hasher.finalize())
         };
+        let metadata = CertificateMetadata::from_certificate_data(&req.certificate_data);
         
         let certificate = sqlx::query_as!(
             Certificate,
             r#"
-            INSERT INTO certificates (id, user_id, certificate_data, device_info, sanitization_method, file_hash)
-            VALUES ($1, $2, $3, $4, $5, $6)
-            RETURNING id, user_id, certificate_data, device_info, sanitization_method, created_at, file_hash
+            INSERT INTO certificates (id, user_id, certificate_data, device_info, sanitization_method, file_hash,
+                                      device_serial, operator, completed_at, signature_valid)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
+            RETURNING id, user_id, certificate_data, device_info, sanitization_method, created_at, file_hash,
+                      device_serial, operator, completed_at, signature_valid
             "#,
             cert_id,
             user_id,
             req.certificate_data,
             req.device_info,
             req.sanitization_method,
-            file_hash
+            file_hash,
+            metadata.device_serial,
+            metadata.operator,
+            metadata.completed_at,
+            metadata.signature_valid
         )
         .fetch_one(&self.pool)
         .await?;
@@ -170,7 +252,8 @@ impl DatabaseManager {
         let certificates = sqlx::query_as!(
             Certificate,
             r#"
-            SELECT id, user_id, certificate_data, device_info, sanitization_method, created_at, file_hash
+            SELECT id, user_id, certificate_data, device_info, sanitization_method, created_at, file_hash,
+                   device_serial, operator, completed_at, signature_valid
             FROM certificates 
             WHERE user_id = $1
             ORDER BY created_at DESC
@@ -262,4 +345,292 @@ impl DatabaseManager {
             total,
         })
     }
-}
\ No newline at end of file
+
+    // ---- Fleet coordination ----
+
+    /// Register a wipe station. The returned token is what the station
+    /// authenticates with; only its hash is stored.
+    pub async fn register_station(&self, user_id: Uuid, req: RegisterStationRequest) -> Result<StationRegistration, sqlx::Error> {
+        let mut secret = [0u8; 32];
+        rand::thread_rng().fill_bytes(&mut secret);
+        let station_token = hex::encode(secret);
+
+        let station = sqlx::query_as::<_, Station>(&format!(
+            "INSERT INTO stations (id, name, hostname, agent_version, token_hash, registered_by)
+             VALUES ($1, $2, $3, $4, $5, $6)
+             RETURNING {}",
+            STATION_COLUMNS
+        ))
+        .bind(Uuid::new_v4())
+        .bind(&req.name)
+        .bind(&req.hostname)
+        .bind(&req.agent_version)
+        .bind(Self::hash_token(&station_token))
+        .bind(user_id)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(StationRegistration { station, station_token })
+    }
+
+    pub async fn authenticate_station(&self, token: &str) -> Result<Option<Station>, sqlx::Error> {
+        sqlx::query_as::<_, Station>(&format!("SELECT {} FROM stations WHERE token_hash = $1", STATION_COLUMNS))
+            .bind(Self::hash_token(token))
+            .fetch_optional(&self.pool)
+            .await
+    }
+
+    /// Record a station check-in: mark it seen and replace its device list.
+    /// Returns the number of jobs waiting for it.
+    pub async fn check_in_station(&self, station_id: Uuid, devices: &[DeviceReport]) -> Result<i64, sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+
+        sqlx::query("UPDATE stations SET last_seen = NOW() WHERE id = $1")
+            .bind(station_id)
+            .execute(&mut *tx)
+            .await?;
+        sqlx::query("DELETE FROM station_devices WHERE station_id = $1")
+            .bind(station_id)
+            .execute(&mut *tx)
+            .await?;
+        for device in devices {
+            sqlx::query(
+                "INSERT INTO station_devices (station_id, device_path, model, serial_number, size_bytes)
+                 VALUES ($1, $2, $3, $4, $5)
+                 ON CONFLICT (station_id, device_path) DO NOTHING",
+            )
+            .bind(station_id)
+            .bind(&device.device_path)
+            .bind(&device.model)
+            .bind(&device.serial_number)
+            .bind(device.size_bytes)
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        let queued: i64 = sqlx::query("SELECT COUNT(*) AS count FROM wipe_jobs WHERE station_id = $1 AND status = 'queued'")
+            .bind(station_id)
+            .fetch_one(&mut *tx)
+            .await?
+            .get("count");
+
+        tx.commit().await?;
+        Ok(queued)
+    }
+
+    pub async fn list_stations(&self) -> Result<Vec<Station>, sqlx::Error> {
+        sqlx::query_as::<_, Station>(&format!("SELECT {} FROM stations ORDER BY name", STATION_COLUMNS))
+            .fetch_all(&self.pool)
+            .await
+    }
+
+    pub async fn get_station_devices(&self, station_id: Uuid) -> Result<Vec<StationDevice>, sqlx::Error> {
+        sqlx::query_as::<_, StationDevice>(
+            "SELECT station_id, device_path, model, serial_number, size_bytes, reported_at
+             FROM station_devices WHERE station_id = $1 ORDER BY device_path",
+        )
+        .bind(station_id)
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    pub async fn enqueue_job(&self, user_id: Uuid, req: EnqueueJobRequest) -> Result<FleetJob, sqlx::Error> {
+        let scope = serde_json::to_string(&req.scope).map_err(|e| sqlx::Error::Protocol(format!("Invalid scope: {}", e)))?;
+
+        sqlx::query_as::<_, FleetJob>(&format!(
+            "INSERT INTO wipe_jobs (id, station_id, device, target_type, standard, scope, verify, created_by)
+             VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+             RETURNING {}",
+            JOB_COLUMNS
+        ))
+        .bind(Uuid::new_v4())
+        .bind(req.station_id)
+        .bind(&req.device)
+        .bind(variant_name(&req.target_type))
+        .bind(variant_name(&req.standard))
+        .bind(scope)
+        .bind(req.verify)
+        .bind(user_id)
+        .fetch_one(&self.pool)
+        .await
+    }
+
+    /// Hand the oldest queued job of a station to it. Concurrent pulls never
+    /// receive the same job.
+    pub async fn assign_next_job(&self, station_id: Uuid) -> Result<Option<FleetJob>, sqlx::Error> {
+        sqlx::query_as::<_, FleetJob>(&format!(
+            "UPDATE wipe_jobs SET status = 'assigned', assigned_at = NOW(), updated_at = NOW()
+             WHERE id = (
+                 SELECT id FROM wipe_jobs
+                 WHERE station_id = $1 AND status = 'queued'
+                 ORDER BY created_at
+                 LIMIT 1
+                 FOR UPDATE SKIP LOCKED
+             )
+             RETURNING {}",
+            JOB_COLUMNS
+        ))
+        .bind(station_id)
+        .fetch_optional(&self.pool)
+        .await
+    }
+
+    /// Apply a progress report from the station the job belongs to. Returns
+    /// None when the job does not exist, belongs to another station or has
+    /// already finished.
+    pub async fn update_job_progress(&self, station_id: Uuid, job_id: Uuid, update: &JobProgressUpdate) -> Result<Option<FleetJob>, sqlx::Error> {
+        sqlx::query_as::<_, FleetJob>(&format!(
+            "UPDATE wipe_jobs SET
+                 status = $3,
+                 operation_id = COALESCE($4, operation_id),
+                 progress_percent = $5,
+                 bytes_processed = $6,
+                 message = $7,
+                 updated_at = NOW(),
+                 completed_at = CASE WHEN $3 IN ('completed', 'failed', 'cancelled') THEN NOW() ELSE NULL END
+             WHERE id = $1 AND station_id = $2 AND status NOT IN ('completed', 'failed', 'cancelled')
+             RETURNING {}",
+            JOB_COLUMNS
+        ))
+        .bind(job_id)
+        .bind(station_id)
+        .bind(&update.status)
+        .bind(update.operation_id)
+        .bind(update.progress_percent)
+        .bind(update.bytes_processed)
+        .bind(&update.message)
+        .fetch_optional(&self.pool)
+        .await
+    }
+
+    /// Store the certificate a station produced for one of its jobs and link
+    /// it to the job. The certificate is owned by the user who queued the job.
+    pub async fn attach_job_certificate(&self, station_id: Uuid, job_id: Uuid, req: SubmitCertificateRequest) -> Result<Option<Certificate>, sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+
+        let owner: Option<Uuid> = sqlx::query("SELECT created_by FROM wipe_jobs WHERE id = $1 AND station_id = $2 FOR UPDATE")
+            .bind(job_id)
+            .bind(station_id)
+            .fetch_optional(&mut *tx)
+            .await?
+            .map(|row| row.get("created_by"));
+        let Some(owner) = owner else {
+            return Ok(None);
+        };
+
+        let metadata = CertificateMetadata::from_certificate_data(&req.certificate_data);
+        let certificate = sqlx::query_as::<_, Certificate>(&format!(
+            "INSERT INTO certificates (id, user_id, certificate_data, device_info, sanitization_method, file_hash,
+                                       device_serial, operator, completed_at, signature_valid)
+             VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
+             RETURNING {}",
+            CERTIFICATE_COLUMNS
+        ))
+        .bind(Uuid::new_v4())
+        .bind(owner)
+        .bind(&req.certificate_data)
+        .bind(&req.device_info)
+        .bind(&req.sanitization_method)
+        .bind(Self::hash_token(&req.certificate_data))
+        .bind(&metadata.device_serial)
+        .bind(&metadata.operator)
+        .bind(metadata.completed_at)
+        .bind(metadata.signature_valid)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        sqlx::query("UPDATE wipe_jobs SET certificate_id = $2, updated_at = NOW() WHERE id = $1")
+            .bind(job_id)
+            .bind(certificate.id)
+            .execute(&mut *tx)
+            .await?;
+
+        tx.commit().await?;
+        Ok(Some(certificate))
+    }
+
+    /// Jobs across the fleet, newest first, optionally for one station or status
+    pub async fn list_jobs(&self, station_id: Option<Uuid>, status: Option<&str>, limit: i64, offset: i64) -> Result<FleetJobResponse, sqlx::Error> {
+        let filter = "($1::uuid IS NULL OR station_id = $1) AND ($2::varchar IS NULL OR status = $2)";
+
+        let jobs = sqlx::query_as::<_, FleetJob>(&format!(
+            "SELECT {} FROM wipe_jobs WHERE {} ORDER BY created_at DESC LIMIT $3 OFFSET $4",
+            JOB_COLUMNS, filter
+        ))
+        .bind(station_id)
+        .bind(status)
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(&self.pool)
+        .await?;
+
+        let total: i64 = sqlx::query(&format!("SELECT COUNT(*) AS count FROM wipe_jobs WHERE {}", filter))
+            .bind(station_id)
+            .bind(status)
+            .fetch_one(&self.pool)
+            .await?
+            .get("count");
+
+        Ok(FleetJobResponse { jobs, total })
+    }
+
+    // ---- Certificate archive ----
+
+    /// Certificates of every user matching `query`, newest completion first.
+    /// A `limit` of None returns every match.
+    pub async fn search_certificates(&self, query: &CertificateQuery, limit: Option<i64>, offset: i64) -> Result<CertificateResponse, sqlx::Error> {
+        let filter = "($1::varchar[] IS NULL OR device_serial = ANY($1))
+             AND ($2::varchar IS NULL OR operator = $2)
+             AND ($3::timestamptz IS NULL OR completed_at >= $3)
+             AND ($4::timestamptz IS NULL OR completed_at <= $4)";
+        let serials = query.serials();
+
+        let certificates = sqlx::query_as::<_, Certificate>(&format!(
+            "SELECT {} FROM certificates WHERE {}
+             ORDER BY completed_at DESC NULLS LAST, created_at DESC
+             LIMIT $5 OFFSET $6",
+            CERTIFICATE_COLUMNS, filter
+        ))
+        .bind(&serials)
+        .bind(&query.operator)
+        .bind(query.since)
+        .bind(query.until)
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(&self.pool)
+        .await?;
+
+        let total: i64 = sqlx::query(&format!("SELECT COUNT(*) AS count FROM certificates WHERE {}", filter))
+            .bind(&serials)
+            .bind(&query.operator)
+            .bind(query.since)
+            .bind(query.until)
+            .fetch_one(&self.pool)
+            .await?
+            .get("count");
+
+        Ok(CertificateResponse { certificates, total })
+    }
+
+    fn hash_token(token: &str) -> String {
+        let mut hasher = Sha256::new();
+        hasher.update(token.as_bytes());
+        format!("{:x}", hasher.finalize())
+    }
+}
+
+const CERTIFICATE_COLUMNS: &str = "id, user_id, certificate_data, device_info, sanitization_method, created_at, file_hash, \
+    device_serial, operator, completed_at, signature_valid";
+
+const STATION_COLUMNS: &str = "id, name, hostname, agent_version, registered_by, registered_at, last_seen";
+
+const JOB_COLUMNS: &str = "id, station_id, device, target_type, standard, scope, verify, status, progress_percent, \
+    bytes_processed, message, operation_id, certificate_id, created_by, created_at, assigned_at, updated_at, completed_at";
+
+/// Serde name of a unit enum variant, as stored in text columns
+fn variant_name<T: serde::Serialize>(value: &T) -> String {
+    serde_json::to_value(value)
+        .ok()
+        .and_then(|v| v.as_str().map(str::to_string))
+        .unwrap_or_default()
+}
@@ -0,0 +1,814 @@
+//! Remote control API for a wipe station
+//!
+//! Exposes a local `WipeEngine` over HTTP so headless wipe appliances can be
+//! driven remotely: device listing, preflight, starting, approving and cancelling wipes,
+//! live progress as Server-Sent Events and certificate download.
+//!
+//! Progress and status events carry a per-server sequence number, sent as the
+//! SSE event id. The most recent events of each wipe are kept so a client that
+//! connects late, or reconnects with `Last-Event-ID`, is replayed what it
+//! missed instead of only seeing the next update.
+//!
+//! Every request must carry `Authorization: Bearer <token>` with an operator
+//! token issued through `WipeEngine::issue_operator_token`. The token's owner
+//! is the operator recorded in history, the audit log and certificates.
+
+use std::collections::{HashMap, VecDeque};
+use std::convert::Infallible;
+use std::sync::{Arc, Mutex};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use tokio::sync::broadcast;
+use tokio_stream::wrappers::BroadcastStream;
+use tokio_stream::StreamExt;
+use tracing::info;
+use uuid::Uuid;
+use warp::http::StatusCode;
+use warp::sse::Event;
+use warp::{Filter, Rejection, Reply};
+
+use crate::core::{
+    Approval, ComplianceProfile, OperatorIdentity, PreflightReport, SafeWipeErrorCode, SanitizationStandard, TargetType, WipeEngine, WipeError,
+    WipeErrorCode, WipePriority, WipeProgress, WipeResult, WipeScope,
+};
+use crate::platform::PhysicalDisk;
+use crate::security_state::SecurityStateAction;
+use crate::devices::identity::device_path_for_id;
+use crate::server::models::ApiResponse;
+
+/// Progress events buffered per subscriber before slow clients start missing some
+const EVENT_BUFFER: usize = 1024;
+
+/// Events kept per wipe for replay; status transitions are kept before progress
+const REPLAY_LIMIT: usize = 512;
+
+/// Finished wipes whose events are kept for replay; the oldest are dropped first
+const REPLAY_FINISHED_WIPES: usize = 64;
+
+/// Routes listed at startup
+const ENDPOINTS: &[(&str, &str)] = &[
+    ("GET /api/devices", "List attached disks"),
+    ("GET /api/preflight?device=&target_type=&standard=", "Dry-run safety checks"),
+    ("POST /api/wipes", "Start a wipe"),
+    ("GET /api/wipes", "List wipes started through the API"),
+    ("GET /api/wipes/{id}", "Wipe status"),
+    ("DELETE /api/wipes/{id}", "Cancel a wipe"),
+    ("POST /api/wipes/{id}/approve", "Approve a wipe as the second operator"),
+    ("GET /api/approvals", "Wipes waiting for approval"),
+    ("GET /api/wipes/{id}/events", "Progress as Server-Sent Events (honours Last-Event-ID)"),
+    ("GET /api/wipes/{id}/certificate[?format=json]", "Download the certificate"),
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum JobStatus {
+    Running,
+    /// Reading the device back after the last pass
+    Verifying,
+    /// Cancellation was requested; the wipe stops after the block being written
+    Cancelling,
+    Completed,
+    Failed,
+    Cancelled,
+}
+
+impl JobStatus {
+    /// The wipe has ended and its status will not change again
+    pub fn is_finished(self) -> bool {
+        matches!(self, JobStatus::Completed | JobStatus::Failed | JobStatus::Cancelled)
+    }
+}
+
+/// A wipe started through the API
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WipeJob {
+    pub operation_id: Uuid,
+    pub target_path: String,
+    pub operator: String,
+    pub status: JobStatus,
+    pub started_at: DateTime<Utc>,
+    pub finished_at: Option<DateTime<Utc>>,
+    pub progress: Option<WipeProgress>,
+    pub result: Option<WipeResult>,
+    pub certificate_path: Option<String>,
+    pub error: Option<String>,
+    #[serde(default)]
+    pub error_code: Option<SafeWipeErrorCode>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum WipeEvent {
+    Progress(WipeProgress),
+    Status { operation_id: Uuid, status: JobStatus, message: Option<String> },
+}
+
+impl WipeEvent {
+    pub(crate) fn operation_id(&self) -> Uuid {
+        match self {
+            WipeEvent::Progress(progress) => progress.request_id,
+            WipeEvent::Status { operation_id, .. } => *operation_id,
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        match self {
+            WipeEvent::Progress(_) => "progress",
+            WipeEvent::Status { .. } => "status",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SequencedEvent {
+    pub sequence: u64,
+    #[serde(flatten)]
+    pub event: WipeEvent,
+}
+
+impl SequencedEvent {
+    fn to_sse(&self) -> Result<Event, serde_json::Error> {
+        Event::default()
+            .id(self.sequence.to_string())
+            .event(self.event.name())
+            .json_data(self)
+    }
+}
+
+/// Recent events of every wipe, in sequence order
+#[derive(Default)]
+struct ReplayBuffer {
+    next_sequence: u64,
+    events: HashMap<Uuid, VecDeque<SequencedEvent>>,
+    /// Wipes that reached a final status, oldest first
+    finished: VecDeque<Uuid>,
+}
+
+impl ReplayBuffer {
+    fn push(&mut self, event: WipeEvent) -> SequencedEvent {
+        self.next_sequence += 1;
+        let sequenced = SequencedEvent { sequence: self.next_sequence, event };
+        let buffer = self.events.entry(sequenced.event.operation_id()).or_default();
+        buffer.push_back(sequenced.clone());
+        if buffer.len() > REPLAY_LIMIT {
+            let oldest = buffer.iter().position(|e| matches!(e.event, WipeEvent::Progress(_))).unwrap_or(0);
+            buffer.remove(oldest);
+        }
+
+        if let WipeEvent::Status { operation_id, status, .. } = sequenced.event
+            && status.is_finished()
+            && !self.finished.contains(&operation_id)
+        {
+            self.finished.push_back(operation_id);
+            if self.finished.len() > REPLAY_FINISHED_WIPES
+                && let Some(oldest) = self.finished.pop_front()
+            {
+                self.events.remove(&oldest);
+            }
+        }
+        sequenced
+    }
+
+    fn since(&self, operation_id: Uuid, after: u64) -> Vec<SequencedEvent> {
+        self.events
+            .get(&operation_id)
+            .map(|buffer| buffer.iter().filter(|e| e.sequence > after).cloned().collect())
+            .unwrap_or_default()
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct PreflightQuery {
+    pub device: String,
+    pub target_type: TargetType,
+    pub standard: SanitizationStandard,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct StartWipeRequest {
+    /// Stable device id or device path
+    pub device: String,
+    pub target_type: TargetType,
+    #[serde(default)]
+    pub scope: WipeScope,
+    pub standard: SanitizationStandard,
+    #[serde(default = "default_true")]
+    pub verify: bool,
+    #[serde(default = "default_true")]
+    pub certificate: bool,
+    /// Compliance profile to follow instead of `standard`
+    #[serde(default)]
+    pub profile: Option<ComplianceProfile>,
+    /// Name of a recipe registered with the engine
+    #[serde(default)]
+    pub recipe: Option<String>,
+    /// Phrase required by the safety policy
+    #[serde(default)]
+    pub confirmation: Option<String>,
+    /// Operation id chosen by the client, so the confirmation token is known
+    /// and a second operator can approve before the wipe is started
+    #[serde(default)]
+    pub operation_id: Option<Uuid>,
+    /// Write a sparse image of the device's size instead of the device
+    #[serde(default)]
+    pub simulate: bool,
+    /// No TRIM / Deallocate after overwriting SSD and flash media
+    #[serde(default)]
+    pub skip_trim: bool,
+    /// ATA security state to leave the drive in; `Brick` is irreversible
+    #[serde(default)]
+    pub security_state: Option<SecurityStateAction>,
+    /// High lets a firmware erase preempt lower-priority overwrites when so configured
+    #[serde(default)]
+    pub priority: WipePriority,
+}
+
+fn default_true() -> bool { true }
+
+#[derive(Debug, Deserialize)]
+struct CertificateQuery {
+    #[serde(default)]
+    format: Option<String>,
+}
+
+/// Wipes started remotely and their events, shared by every remote front end
+pub(crate) struct StationState {
+    engine: Arc<WipeEngine>,
+    jobs: Mutex<HashMap<Uuid, WipeJob>>,
+    events: broadcast::Sender<SequencedEvent>,
+    replay: Mutex<ReplayBuffer>,
+}
+
+impl StationState {
+    pub(crate) fn new(engine: WipeEngine) -> Arc<Self> {
+        let engine = Arc::new(engine);
+        let (events, _) = broadcast::channel(EVENT_BUFFER);
+        let state = Arc::new(Self {
+            engine: engine.clone(),
+            jobs: Mutex::new(HashMap::new()),
+            events,
+            replay: Mutex::new(ReplayBuffer::default()),
+        });
+
+        // Forward engine progress to the job table and to event subscribers
+        let progress_rx = engine.subscribe_progress();
+        let progress_state = state.clone();
+        std::thread::spawn(move || {
+            while let Ok(progress) = progress_rx.recv() {
+                let started_verifying = match progress_state.jobs.lock().unwrap().get_mut(&progress.request_id) {
+                    Some(job) => {
+                        job.progress = Some(progress.clone());
+                        progress.verifying && job.status == JobStatus::Running
+                    }
+                    None => false,
+                };
+                if started_verifying {
+                    progress_state.update_status(progress.request_id, JobStatus::Verifying, None);
+                }
+                progress_state.publish(WipeEvent::Progress(progress));
+            }
+        });
+
+        state
+    }
+
+    /// Resolve an `Authorization: Bearer <token>` value to an operator
+    pub(crate) fn authenticate(&self, authorization: Option<&str>) -> Result<OperatorIdentity, WipeError> {
+        let token = authorization
+            .and_then(|h| h.strip_prefix("Bearer "))
+            .ok_or_else(|| WipeError {
+                code: WipeErrorCode::NotAuthenticated,
+                message: "Missing bearer token".to_string(),
+                sector: None,
+            })?;
+        self.authenticate_token(token.trim())
+    }
+
+    pub(crate) fn authenticate_token(&self, token: &str) -> Result<OperatorIdentity, WipeError> {
+        self.engine.authenticate_token(token)
+    }
+
+    pub(crate) async fn scan_devices(&self, operator: OperatorIdentity) -> Result<Vec<PhysicalDisk>, WipeError> {
+        let engine = self.engine.clone();
+        tokio::task::spawn_blocking(move || engine.scan_devices_as(Some(&operator)))
+            .await
+            .map_err(task_failed)?
+    }
+
+    pub(crate) async fn preflight(
+        &self,
+        operator: OperatorIdentity,
+        device: String,
+        target_type: TargetType,
+        standard: SanitizationStandard,
+    ) -> Result<PreflightReport, WipeError> {
+        let engine = self.engine.clone();
+        tokio::task::spawn_blocking(move || engine.preflight_as(Some(&operator), &device, target_type, standard))
+            .await
+            .map_err(task_failed)
+    }
+
+    /// Start a wipe in the background and return its operation id
+    pub(crate) fn start_wipe(self: &Arc<Self>, operator: OperatorIdentity, body: StartWipeRequest) -> Result<Uuid, WipeError> {
+        operator.require_sanitize()?;
+
+        let target_path = device_path_for_id(&body.device).unwrap_or_else(|| body.device.clone());
+        // The engine refuses a second claim on the device too; this also covers
+        // wipes accepted here whose task has not claimed the device yet
+        let busy = self.jobs.lock().unwrap().values()
+            .any(|job| matches!(job.status, JobStatus::Running | JobStatus::Verifying | JobStatus::Cancelling) && job.target_path == target_path);
+        if busy {
+            return Err(WipeError {
+                code: WipeErrorCode::OperationInProgress,
+                message: format!("A wipe of {} is already running", target_path),
+                sector: None,
+            });
+        }
+        if let Some(holder) = self.engine.device_lock(&target_path) {
+            return Err(WipeError {
+                code: WipeErrorCode::OperationInProgress,
+                message: format!("{} is in use by operation {}", target_path, holder.operation_id),
+                sector: None,
+            });
+        }
+
+        let mut request = WipeEngine::build_request(
+            target_path.clone(),
+            body.target_type,
+            body.scope,
+            body.standard,
+            body.verify,
+            body.certificate,
+        );
+        request.profile = body.profile;
+        if let Some(ref name) = body.recipe {
+            request.recipe = Some(self.engine.recipe(name)?);
+        }
+        request.confirmation = body.confirmation;
+        request.simulate = body.simulate;
+        request.skip_trim = body.skip_trim;
+        request.security_state = body.security_state;
+        request.priority = body.priority;
+        if let Some(operation_id) = body.operation_id {
+            if self.jobs.lock().unwrap().contains_key(&operation_id) {
+                return Err(WipeError {
+                    code: WipeErrorCode::InvalidTarget,
+                    message: format!("Operation id {} is already in use", operation_id),
+                    sector: None,
+                });
+            }
+            request.id = operation_id;
+        }
+        let operation_id = request.id;
+
+        self.jobs.lock().unwrap().insert(operation_id, WipeJob {
+            operation_id,
+            target_path,
+            operator: operator.display_name(),
+            status: JobStatus::Running,
+            started_at: Utc::now(),
+            finished_at: None,
+            progress: None,
+            result: None,
+            certificate_path: None,
+            error: None,
+            error_code: None,
+        });
+        self.update_status(operation_id, JobStatus::Running, None);
+
+        let engine = self.engine.clone();
+        let wipe = tokio::task::spawn_blocking(move || engine.execute_request_as(&operator, request));
+        let state = self.clone();
+        tokio::spawn(async move {
+            let outcome = wipe.await.unwrap_or_else(|e| Err(task_failed(e)));
+            state.finish(operation_id, outcome);
+        });
+
+        Ok(operation_id)
+    }
+
+    pub(crate) fn job(&self, operation_id: Uuid) -> Result<WipeJob, WipeError> {
+        self.jobs.lock().unwrap().get(&operation_id).cloned()
+            .ok_or_else(|| not_found(format!("No wipe with id {}", operation_id)))
+    }
+
+    /// Every remotely started wipe, newest first
+    pub(crate) fn jobs(&self) -> Vec<WipeJob> {
+        let mut jobs: Vec<WipeJob> = self.jobs.lock().unwrap().values().cloned().collect();
+        jobs.sort_by(|a, b| b.started_at.cmp(&a.started_at));
+        jobs
+    }
+
+    /// Approve a wipe as the second operator; it may not have been started yet
+    pub(crate) fn approve(&self, operator: &OperatorIdentity, operation_id: Uuid) -> Result<Approval, WipeError> {
+        self.engine.approve_operation(operation_id, operator)
+    }
+
+    pub(crate) fn cancel(&self, operator: &OperatorIdentity, operation_id: Uuid) -> Result<(), WipeError> {
+        self.job(operation_id)?;
+        self.engine.cancel_wipe_as(operator, operation_id)?;
+        self.update_status(operation_id, JobStatus::Cancelling, Some(format!("Requested by {}", operator.display_name())));
+        Ok(())
+    }
+
+    /// Current job state, the buffered events after `after`, and a receiver for
+    /// everything published from now on. Subscribing under the replay lock
+    /// ensures no event falls between the replay and the live stream.
+    pub(crate) fn subscribe(
+        &self,
+        operation_id: Uuid,
+        after: u64,
+    ) -> Result<(WipeJob, Vec<SequencedEvent>, broadcast::Receiver<SequencedEvent>), WipeError> {
+        let job = self.job(operation_id)?;
+        let replay = self.replay.lock().unwrap();
+        Ok((job, replay.since(operation_id, after), self.events.subscribe()))
+    }
+
+    /// Path and content type of the certificate of a finished wipe; the signed
+    /// JSON certificate is written next to the PDF under the same name
+    pub(crate) fn certificate_file(&self, operation_id: Uuid, json: bool) -> Result<(String, &'static str), WipeError> {
+        let pdf_path = self.job(operation_id)?
+            .certificate_path
+            .ok_or_else(|| not_found(format!("No certificate for wipe {}", operation_id)))?;
+        Ok(if json {
+            (pdf_path.replace(".pdf", ".json"), "application/json")
+        } else {
+            (pdf_path, "application/pdf")
+        })
+    }
+
+    /// Number the event, keep it for replay and send it to live subscribers.
+    /// Sending under the replay lock keeps live and replayed order identical.
+    fn publish(&self, event: WipeEvent) {
+        let mut replay = self.replay.lock().unwrap();
+        let sequenced = replay.push(event);
+        let _ = self.events.send(sequenced);
+    }
+
+    fn update_status(&self, operation_id: Uuid, status: JobStatus, message: Option<String>) {
+        if let Some(job) = self.jobs.lock().unwrap().get_mut(&operation_id) {
+            job.status = status;
+        }
+        self.publish(WipeEvent::Status { operation_id, status, message });
+    }
+
+    fn finish(&self, operation_id: Uuid, outcome: Result<(WipeResult, Option<String>), WipeError>) {
+        let (status, message) = {
+            let mut jobs = self.jobs.lock().unwrap();
+            let Some(job) = jobs.get_mut(&operation_id) else { return };
+            job.finished_at = Some(Utc::now());
+            match outcome {
+                Ok((result, certificate_path)) => {
+                    job.status = if result.success { JobStatus::Completed } else { JobStatus::Failed };
+                    job.error = result.error_message.clone();
+                    job.result = Some(result);
+                    job.certificate_path = certificate_path;
+                }
+                Err(error) => {
+                    job.status = match error.code {
+                        WipeErrorCode::Cancelled => JobStatus::Cancelled,
+                        _ => JobStatus::Failed,
+                    };
+                    job.error = Some(error.to_string());
+                    job.error_code = Some(error.error_code());
+                }
+            }
+            (job.status, job.error.clone())
+        };
+        self.update_status(operation_id, status, message);
+    }
+}
+
+#[derive(Debug)]
+struct ApiError(WipeError);
+
+impl warp::reject::Reject for ApiError {}
+
+fn reject(error: WipeError) -> Rejection {
+    warp::reject::custom(ApiError(error))
+}
+
+fn status_for(code: &WipeErrorCode) -> StatusCode {
+    match code {
+        WipeErrorCode::NotAuthenticated => StatusCode::UNAUTHORIZED,
+        WipeErrorCode::InsufficientPrivileges | WipeErrorCode::PolicyDenied => StatusCode::FORBIDDEN,
+        WipeErrorCode::DriveNotFound => StatusCode::NOT_FOUND,
+        WipeErrorCode::InvalidTarget => StatusCode::BAD_REQUEST,
+        WipeErrorCode::VolumeInUse | WipeErrorCode::OperationInProgress => StatusCode::CONFLICT,
+        WipeErrorCode::Unsupported => StatusCode::UNPROCESSABLE_ENTITY,
+        _ => StatusCode::INTERNAL_SERVER_ERROR,
+    }
+}
+
+/// A blocking engine call that panicked
+fn task_failed(error: tokio::task::JoinError) -> WipeError {
+    WipeError {
+        code: WipeErrorCode::UnknownError,
+        message: format!("Engine task failed: {}", error),
+        sector: None,
+    }
+}
+
+fn not_found(message: String) -> WipeError {
+    WipeError { code: WipeErrorCode::DriveNotFound, message, sector: None }
+}
+
+/// Serve the engine API on `port` until the process exits
+pub async fn start_engine_server(engine: WipeEngine, port: u16) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    serve_http(StationState::new(engine), port).await
+}
+
+pub(crate) async fn serve_http(state: Arc<StationState>, port: u16) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let devices = warp::path!("api" / "devices")
+        .and(warp::get())
+        .and(with_operator(state.clone()))
+        .and(with_state(state.clone()))
+        .and_then(list_devices);
+
+    let preflight = warp::path!("api" / "preflight")
+        .and(warp::get())
+        .and(with_operator(state.clone()))
+        .and(warp::query::<PreflightQuery>())
+        .and(with_state(state.clone()))
+        .and_then(run_preflight);
+
+    let start = warp::path!("api" / "wipes")
+        .and(warp::post())
+        .and(with_operator(state.clone()))
+        .and(warp::body::json())
+        .and(with_state(state.clone()))
+        .and_then(start_wipe);
+
+    let list = warp::path!("api" / "wipes")
+        .and(warp::get())
+        .and(with_operator(state.clone()))
+        .and(with_state(state.clone()))
+        .and_then(list_wipes);
+
+    let status = warp::path!("api" / "wipes" / Uuid)
+        .and(warp::get())
+        .and(with_operator(state.clone()))
+        .and(with_state(state.clone()))
+        .and_then(wipe_status);
+
+    let cancel = warp::path!("api" / "wipes" / Uuid)
+        .and(warp::delete())
+        .and(with_operator(state.clone()))
+        .and(with_state(state.clone()))
+        .and_then(cancel_wipe);
+
+    let approve = warp::path!("api" / "wipes" / Uuid / "approve")
+        .and(warp::post())
+        .and(with_operator(state.clone()))
+        .and(with_state(state.clone()))
+        .and_then(approve_wipe);
+
+    let pending = warp::path!("api" / "approvals")
+        .and(warp::get())
+        .and(with_operator(state.clone()))
+        .and(with_state(state.clone()))
+        .and_then(pending_approvals);
+
+    let events = warp::path!("api" / "wipes" / Uuid / "events")
+        .and(warp::get())
+        .and(with_operator(state.clone()))
+        .and(warp::header::optional::<u64>("last-event-id"))
+        .and(with_state(state.clone()))
+        .and_then(wipe_events);
+
+    let certificate = warp::path!("api" / "wipes" / Uuid / "certificate")
+        .and(warp::get())
+        .and(with_operator(state.clone()))
+        .and(warp::query::<CertificateQuery>())
+        .and(with_state(state.clone()))
+        .and_then(download_certificate);
+
+    let routes = devices
+        .or(preflight)
+        .or(start)
+        .or(list)
+        .or(status)
+        .or(cancel)
+        .or(approve)
+        .or(pending)
+        .or(events)
+        .or(certificate)
+        .recover(handle_rejection);
+
+    info!(port, "Wipe engine API starting; requests need Authorization: Bearer <operator token>");
+    for (route, description) in ENDPOINTS {
+        info!(route, "{}", description);
+    }
+
+    warp::serve(routes)
+        .run(([0, 0, 0, 0], port))
+        .await;
+
+    Ok(())
+}
+
+fn with_state(state: Arc<StationState>) -> impl Filter<Extract = (Arc<StationState>,), Error = Infallible> + Clone {
+    warp::any().map(move || state.clone())
+}
+
+/// Resolve the bearer token to an operator, rejecting the request otherwise
+fn with_operator(state: Arc<StationState>) -> impl Filter<Extract = (OperatorIdentity,), Error = Rejection> + Clone {
+    warp::header::optional::<String>("authorization")
+        .and(with_state(state))
+        .and_then(|header: Option<String>, state: Arc<StationState>| async move {
+            state.authenticate(header.as_deref()).map_err(reject)
+        })
+}
+
+async fn handle_rejection(rejection: Rejection) -> Result<impl Reply, Infallible> {
+    let (status, response) = if let Some(ApiError(error)) = rejection.find::<ApiError>() {
+        (status_for(&error.code), ApiResponse::<()>::failure(error))
+    } else if rejection.is_not_found() {
+        (StatusCode::NOT_FOUND, ApiResponse::error(SafeWipeErrorCode::NotFound, "Not found".to_string()))
+    } else {
+        (StatusCode::BAD_REQUEST, ApiResponse::error(SafeWipeErrorCode::InvalidArgument, format!("{:?}", rejection)))
+    };
+    Ok(warp::reply::with_status(warp::reply::json(&response), status))
+}
+
+async fn list_devices(operator: OperatorIdentity, state: Arc<StationState>) -> Result<impl Reply, Rejection> {
+    let disks = state.scan_devices(operator).await.map_err(reject)?;
+
+    let devices: Vec<serde_json::Value> = disks
+        .iter()
+        .map(|disk| serde_json::json!({
+            "path": disk.path,
+            "description": disk.description(),
+            "vendor": disk.vendor,
+            "model": disk.model,
+            "serial": disk.serial,
+            "bus_type": disk.bus_type,
+            "kind": disk.kind.as_str(),
+            "virtual": disk.kind.is_virtual(),
+            "size_bytes": disk.size_bytes,
+            "removable": disk.removable,
+            "is_root_disk": disk.is_root_disk,
+            "volumes": disk.volumes,
+            "controller": disk.controller,
+            "namespace_id": disk.namespace_id,
+        }))
+        .collect();
+    Ok(warp::reply::json(&ApiResponse::success(devices)))
+}
+
+async fn run_preflight(
+    operator: OperatorIdentity,
+    query: PreflightQuery,
+    state: Arc<StationState>,
+) -> Result<impl Reply, Rejection> {
+    let report = state
+        .preflight(operator, query.device, query.target_type, query.standard)
+        .await
+        .map_err(reject)?;
+    Ok(warp::reply::json(&ApiResponse::success(report)))
+}
+
+async fn start_wipe(
+    operator: OperatorIdentity,
+    body: StartWipeRequest,
+    state: Arc<StationState>,
+) -> Result<impl Reply, Rejection> {
+    let operation_id = state.start_wipe(operator, body).map_err(reject)?;
+    Ok(warp::reply::with_status(
+        warp::reply::json(&ApiResponse::success(serde_json::json!({ "operation_id": operation_id }))),
+        StatusCode::ACCEPTED,
+    ))
+}
+
+async fn list_wipes(_operator: OperatorIdentity, state: Arc<StationState>) -> Result<impl Reply, Rejection> {
+    Ok(warp::reply::json(&ApiResponse::success(state.jobs())))
+}
+
+async fn wipe_status(operation_id: Uuid, _operator: OperatorIdentity, state: Arc<StationState>) -> Result<impl Reply, Rejection> {
+    let job = state.job(operation_id).map_err(reject)?;
+    Ok(warp::reply::json(&ApiResponse::success(job)))
+}
+
+async fn cancel_wipe(operation_id: Uuid, operator: OperatorIdentity, state: Arc<StationState>) -> Result<impl Reply, Rejection> {
+    state.cancel(&operator, operation_id).map_err(reject)?;
+    Ok(warp::reply::with_status(
+        warp::reply::json(&ApiResponse::success(serde_json::json!({ "operation_id": operation_id }))),
+        StatusCode::ACCEPTED,
+    ))
+}
+
+async fn approve_wipe(operation_id: Uuid, operator: OperatorIdentity, state: Arc<StationState>) -> Result<impl Reply, Rejection> {
+    let approval = state.approve(&operator, operation_id).map_err(reject)?;
+    Ok(warp::reply::json(&ApiResponse::success(approval)))
+}
+
+async fn pending_approvals(_operator: OperatorIdentity, state: Arc<StationState>) -> Result<impl Reply, Rejection> {
+    Ok(warp::reply::json(&ApiResponse::success(state.engine.pending_approvals())))
+}
+
+/// Current job state, then the buffered events the client has not seen, then
+/// live events of the wipe
+async fn wipe_events(
+    operation_id: Uuid,
+    _operator: OperatorIdentity,
+    last_event_id: Option<u64>,
+    state: Arc<StationState>,
+) -> Result<impl Reply, Rejection> {
+    let (job, missed, receiver) = state.subscribe(operation_id, last_event_id.unwrap_or(0)).map_err(reject)?;
+    let replayed_up_to = missed.last().map_or(0, |e| e.sequence);
+
+    let snapshot = tokio_stream::once(Event::default().event("job").json_data(&job));
+    let replayed = tokio_stream::iter(missed.into_iter().map(|event| event.to_sse()));
+    let updates = BroadcastStream::new(receiver)
+        // A lagged receiver has skipped events; ending the stream makes the client
+        // reconnect with Last-Event-ID and be replayed what it missed
+        .take_while(Result::is_ok)
+        .filter_map(move |event| match event {
+            Ok(event) if event.event.operation_id() == operation_id && event.sequence > replayed_up_to => {
+                Some(event.to_sse())
+            }
+            _ => None,
+        });
+
+    let stream = snapshot
+        .chain(replayed)
+        .chain(updates)
+        .filter_map(|event| event.ok().map(Ok::<Event, Infallible>));
+    Ok(warp::sse::reply(warp::sse::keep_alive().stream(stream)))
+}
+
+async fn download_certificate(
+    operation_id: Uuid,
+    _operator: OperatorIdentity,
+    query: CertificateQuery,
+    state: Arc<StationState>,
+) -> Result<impl Reply, Rejection> {
+    let (path, content_type) = state
+        .certificate_file(operation_id, query.format.as_deref() == Some("json"))
+        .map_err(reject)?;
+    let content = tokio::fs::read(&path).await
+        .map_err(|e| reject(not_found(format!("Failed to read {}: {}", path, e))))?;
+
+    let filename = std::path::Path::new(&path)
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default();
+    Ok(warp::reply::with_header(
+        warp::reply::with_header(content, "content-type", content_type),
+        "content-disposition",
+        format!("attachment; filename=\"{}\"", filename),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn progress(operation_id: Uuid, percentage: f64) -> WipeEvent {
+        WipeEvent::Progress(WipeProgress {
+            request_id: operation_id,
+            current_pass: 1,
+            total_passes: 1,
+            sectors_processed: 0,
+            total_sectors: 100,
+            percentage,
+            current_operation: "Pass 1 - Writing pattern".to_string(),
+            estimated_completion: None,
+            estimated_completion_earliest: None,
+            estimated_completion_latest: None,
+            verifying: false,
+        })
+    }
+
+    #[test]
+    fn test_replay_keeps_status_transitions() {
+        let (wipe, other) = (Uuid::new_v4(), Uuid::new_v4());
+        let mut replay = ReplayBuffer::default();
+        replay.push(WipeEvent::Status { operation_id: wipe, status: JobStatus::Running, message: None });
+        for n in 0..REPLAY_LIMIT {
+            replay.push(progress(wipe, n as f64));
+        }
+        let other_event = replay.push(progress(other, 0.0));
+
+        let events = replay.since(wipe, 0);
+        assert_eq!(events.len(), REPLAY_LIMIT);
+        assert!(matches!(events[0].event, WipeEvent::Status { status: JobStatus::Running, .. }));
+        assert!(events.windows(2).all(|pair| pair[0].sequence < pair[1].sequence));
+        assert!(replay.since(wipe, other_event.sequence).is_empty());
+    }
+
+    #[test]
+    fn test_replay_drops_the_oldest_finished_wipes() {
+        let mut replay = ReplayBuffer::default();
+        let wipes: Vec<Uuid> = (0..=REPLAY_FINISHED_WIPES).map(|_| Uuid::new_v4()).collect();
+        let running = Uuid::new_v4();
+        replay.push(progress(running, 50.0));
+        for &wipe in &wipes {
+            replay.push(progress(wipe, 100.0));
+            replay.push(WipeEvent::Status { operation_id: wipe, status: JobStatus::Completed, message: None });
+        }
+
+        assert!(replay.since(wipes[0], 0).is_empty());
+        assert_eq!(replay.since(wipes[1], 0).len(), 2);
+        assert_eq!(replay.since(running, 0).len(), 1);
+    }
+}
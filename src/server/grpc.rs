@@ -0,0 +1,454 @@
+//! gRPC service for fleet controllers
+//!
+//! The `WipeStation` service from `proto/wipe_station.proto`, backed by the
+//! same job table and event replay as the HTTP API, so a wipe started over one
+//! protocol can be watched or cancelled over the other.
+
+use std::net::SocketAddr;
+use std::pin::Pin;
+use std::sync::Arc;
+use tokio_stream::wrappers::BroadcastStream;
+use tokio_stream::{Stream, StreamExt};
+use tonic::{Request, Response, Status};
+use tracing::info;
+use uuid::Uuid;
+
+use crate::core::{
+    OperatorIdentity, SanitizationStandard, TargetType, WipeEngine, WipeError, WipeErrorCode,
+    WipePriority, WipeProgress, WipeScope,
+};
+use crate::partitions::PartitionSelector;
+use crate::server::engine_api::{
+    serve_http, JobStatus, SequencedEvent, StartWipeRequest, StationState, WipeEvent, WipeJob,
+};
+
+pub mod proto {
+    tonic::include_proto!("hdd_tool.station.v1");
+}
+
+use proto::wipe_station_server::{WipeStation, WipeStationServer};
+
+pub struct GrpcStation {
+    state: Arc<StationState>,
+}
+
+impl GrpcStation {
+    fn operator<T>(&self, request: &Request<T>) -> Result<OperatorIdentity, Status> {
+        let authorization = request.metadata().get("authorization").and_then(|value| value.to_str().ok());
+        self.state.authenticate(authorization).map_err(status_from)
+    }
+}
+
+/// Serve the gRPC API on `port` until the process exits
+pub async fn start_grpc_server(engine: WipeEngine, port: u16) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    serve_grpc(StationState::new(engine), port).await
+}
+
+/// Serve the HTTP and gRPC APIs side by side over one engine
+pub async fn start_station_servers(
+    engine: WipeEngine,
+    http_port: u16,
+    grpc_port: u16,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let state = StationState::new(engine);
+    tokio::try_join!(serve_http(state.clone(), http_port), serve_grpc(state, grpc_port))?;
+    Ok(())
+}
+
+async fn serve_grpc(state: Arc<StationState>, port: u16) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let addr = SocketAddr::from(([0, 0, 0, 0], port));
+    info!(port, "Wipe station gRPC service starting");
+
+    tonic::transport::Server::builder()
+        .add_service(WipeStationServer::new(GrpcStation { state }))
+        .serve(addr)
+        .await?;
+    Ok(())
+}
+
+fn status_from(error: WipeError) -> Status {
+    match error.code {
+        WipeErrorCode::NotAuthenticated => Status::unauthenticated(error.message),
+        WipeErrorCode::InsufficientPrivileges | WipeErrorCode::PolicyDenied => Status::permission_denied(error.message),
+        WipeErrorCode::DriveNotFound => Status::not_found(error.message),
+        WipeErrorCode::InvalidTarget => Status::invalid_argument(error.message),
+        WipeErrorCode::VolumeInUse | WipeErrorCode::OperationInProgress | WipeErrorCode::Unsupported => {
+            Status::failed_precondition(error.message)
+        }
+        WipeErrorCode::Cancelled => Status::cancelled(error.message),
+        _ => Status::internal(error.message),
+    }
+}
+
+fn parse_operation_id(value: &str) -> Result<Uuid, Status> {
+    Uuid::parse_str(value).map_err(|_| Status::invalid_argument(format!("Invalid operation id {}", value)))
+}
+
+fn target_type_from(value: proto::TargetType) -> Result<TargetType, Status> {
+    match value {
+        proto::TargetType::Hdd => Ok(TargetType::HDD),
+        proto::TargetType::Ssd => Ok(TargetType::SSD),
+        proto::TargetType::Flash => Ok(TargetType::Flash),
+        proto::TargetType::Optical => Ok(TargetType::Optical),
+        proto::TargetType::Tape => Ok(TargetType::Tape),
+        proto::TargetType::Unspecified => Err(Status::invalid_argument("target_type is required")),
+    }
+}
+
+fn standard_from(value: proto::SanitizationStandard) -> Result<SanitizationStandard, Status> {
+    match value {
+        proto::SanitizationStandard::NistSp80088R1 => Ok(SanitizationStandard::NIST_SP_800_88_R1),
+        proto::SanitizationStandard::Dod522022M => Ok(SanitizationStandard::DoD_5220_22_M),
+        proto::SanitizationStandard::Afssi5020 => Ok(SanitizationStandard::AFSSI_5020),
+        proto::SanitizationStandard::Bsi2011Vs => Ok(SanitizationStandard::BSI_2011_VS),
+        proto::SanitizationStandard::NavsoP523926 => Ok(SanitizationStandard::NAVSO_P_5239_26),
+        proto::SanitizationStandard::Unspecified => Err(Status::invalid_argument("standard is required")),
+    }
+}
+
+fn priority_from(value: proto::WipePriority) -> WipePriority {
+    match value {
+        proto::WipePriority::Low => WipePriority::Low,
+        proto::WipePriority::High => WipePriority::High,
+        proto::WipePriority::Normal | proto::WipePriority::Unspecified => WipePriority::Normal,
+    }
+}
+
+fn job_status_to(status: JobStatus) -> proto::JobStatus {
+    match status {
+        JobStatus::Running => proto::JobStatus::Running,
+        JobStatus::Verifying => proto::JobStatus::Verifying,
+        JobStatus::Cancelling => proto::JobStatus::Cancelling,
+        JobStatus::Completed => proto::JobStatus::Completed,
+        JobStatus::Failed => proto::JobStatus::Failed,
+        JobStatus::Cancelled => proto::JobStatus::Cancelled,
+    }
+}
+
+fn progress_to(progress: &WipeProgress) -> proto::WipeProgress {
+    proto::WipeProgress {
+        current_pass: progress.current_pass,
+        total_passes: progress.total_passes,
+        sectors_processed: progress.sectors_processed,
+        total_sectors: progress.total_sectors,
+        percentage: progress.percentage,
+        current_operation: progress.current_operation.clone(),
+        estimated_completion: progress.estimated_completion.map(|t| t.to_rfc3339()).unwrap_or_default(),
+        estimated_completion_earliest: progress.estimated_completion_earliest.map(|t| t.to_rfc3339()).unwrap_or_default(),
+        estimated_completion_latest: progress.estimated_completion_latest.map(|t| t.to_rfc3339()).unwrap_or_default(),
+    }
+}
+
+fn job_to(job: &WipeJob) -> proto::WipeJob {
+    proto::WipeJob {
+        operation_id: job.operation_id.to_string(),
+        target_path: job.target_path.clone(),
+        operator: job.operator.clone(),
+        status: job_status_to(job.status) as i32,
+        started_at: job.started_at.to_rfc3339(),
+        finished_at: job.finished_at.map(|t| t.to_rfc3339()).unwrap_or_default(),
+        progress: job.progress.as_ref().map(progress_to),
+        success: job.result.as_ref().map_or(false, |r| r.success),
+        sectors_wiped: job.result.as_ref().map_or(0, |r| r.sectors_wiped),
+        has_certificate: job.certificate_path.is_some(),
+        error: job.error.clone().unwrap_or_default(),
+    }
+}
+
+fn event_to(event: &SequencedEvent) -> proto::WipeEvent {
+    let (operation_id, payload) = match &event.event {
+        WipeEvent::Progress(progress) => (progress.request_id, proto::wipe_event::Event::Progress(progress_to(progress))),
+        WipeEvent::Status { operation_id, status, message } => (
+            *operation_id,
+            proto::wipe_event::Event::Status(proto::StatusChange {
+                status: job_status_to(*status) as i32,
+                message: message.clone().unwrap_or_default(),
+            }),
+        ),
+    };
+    proto::WipeEvent {
+        sequence: event.sequence,
+        operation_id: operation_id.to_string(),
+        event: Some(payload),
+    }
+}
+
+#[tonic::async_trait]
+impl WipeStation for GrpcStation {
+    async fn scan_devices(
+        &self,
+        request: Request<proto::ScanDevicesRequest>,
+    ) -> Result<Response<proto::ScanDevicesResponse>, Status> {
+        let operator = self.operator(&request)?;
+        let disks = self.state.scan_devices(operator).await.map_err(status_from)?;
+
+        let devices = disks
+            .iter()
+            .map(|disk| proto::Device {
+                path: disk.path.clone(),
+                description: disk.description(),
+                vendor: disk.vendor.clone(),
+                model: disk.model.clone(),
+                serial: disk.serial.clone(),
+                bus_type: disk.bus_type.clone(),
+                kind: disk.kind.as_str().to_string(),
+                size_bytes: disk.size_bytes,
+                removable: disk.removable,
+                is_root_disk: disk.is_root_disk,
+                volumes: disk.volumes.clone(),
+            })
+            .collect();
+        Ok(Response::new(proto::ScanDevicesResponse { devices }))
+    }
+
+    async fn preflight(
+        &self,
+        request: Request<proto::PreflightRequest>,
+    ) -> Result<Response<proto::PreflightResponse>, Status> {
+        let operator = self.operator(&request)?;
+        let body = request.into_inner();
+        let target_type = target_type_from(body.target_type())?;
+        let standard = standard_from(body.standard())?;
+
+        let report = self.state
+            .preflight(operator, body.device, target_type, standard)
+            .await
+            .map_err(status_from)?;
+
+        Ok(Response::new(proto::PreflightResponse {
+            can_proceed: report.can_proceed(),
+            issues: report
+                .issues
+                .iter()
+                .map(|issue| proto::PreflightIssue {
+                    check: format!("{:?}", issue.check),
+                    severity: format!("{:?}", issue.severity),
+                    message: issue.message.clone(),
+                    remediation: issue.remediation.clone(),
+                })
+                .collect(),
+        }))
+    }
+
+    async fn start_wipe(
+        &self,
+        request: Request<proto::StartWipeRequest>,
+    ) -> Result<Response<proto::StartWipeResponse>, Status> {
+        let operator = self.operator(&request)?;
+        let body = request.into_inner();
+        let target_type = target_type_from(body.target_type())?;
+        let standard = standard_from(body.standard())?;
+        let priority = priority_from(body.priority());
+
+        let scope = match body.scope {
+            None => WipeScope::WholeDevice,
+            Some(proto::start_wipe_request::Scope::PartitionNumber(number)) => {
+                WipeScope::Partition(PartitionSelector::Number(number))
+            }
+            Some(proto::start_wipe_request::Scope::PartitionGuid(guid)) => {
+                WipeScope::Partition(PartitionSelector::Guid(guid))
+            }
+            Some(proto::start_wipe_request::Scope::LbaRange(range)) => WipeScope::LbaRange {
+                start_sector: range.start_sector,
+                sector_count: range.sector_count,
+            },
+        };
+
+        let operation_id = self.state
+            .start_wipe(operator, StartWipeRequest {
+                device: body.device,
+                target_type,
+                standard,
+                scope,
+                verify: body.verify.unwrap_or(true),
+                certificate: body.certificate.unwrap_or(true),
+                profile: None,
+                recipe: None,
+                confirmation: None,
+                operation_id: None,
+                simulate: false,
+                skip_trim: false,
+                security_state: None,
+                priority,
+            })
+            .map_err(status_from)?;
+
+        Ok(Response::new(proto::StartWipeResponse { operation_id: operation_id.to_string() }))
+    }
+
+    async fn cancel_wipe(
+        &self,
+        request: Request<proto::CancelWipeRequest>,
+    ) -> Result<Response<proto::CancelWipeResponse>, Status> {
+        let operator = self.operator(&request)?;
+        let operation_id = parse_operation_id(&request.get_ref().operation_id)?;
+        self.state.cancel(&operator, operation_id).map_err(status_from)?;
+        Ok(Response::new(proto::CancelWipeResponse {}))
+    }
+
+    async fn get_wipe_status(
+        &self,
+        request: Request<proto::GetWipeStatusRequest>,
+    ) -> Result<Response<proto::WipeJob>, Status> {
+        self.operator(&request)?;
+        let operation_id = parse_operation_id(&request.get_ref().operation_id)?;
+        let job = self.state.job(operation_id).map_err(status_from)?;
+        Ok(Response::new(job_to(&job)))
+    }
+
+    type StreamWipeEventsStream = Pin<Box<dyn Stream<Item = Result<proto::WipeEvent, Status>> + Send>>;
+
+    async fn stream_wipe_events(
+        &self,
+        request: Request<proto::StreamWipeEventsRequest>,
+    ) -> Result<Response<Self::StreamWipeEventsStream>, Status> {
+        self.operator(&request)?;
+        let operation_id = parse_operation_id(&request.get_ref().operation_id)?;
+        let (_, missed, receiver) = self.state
+            .subscribe(operation_id, request.get_ref().after_sequence)
+            .map_err(status_from)?;
+        let replayed_up_to = missed.last().map_or(0, |e| e.sequence);
+
+        let replayed = tokio_stream::iter(missed.iter().map(event_to).map(Ok).collect::<Vec<_>>());
+        let updates = BroadcastStream::new(receiver).filter_map(move |event| match event {
+            // Lagged receivers skip ahead; the next event carries the current state
+            Ok(event) if event.event.operation_id() == operation_id && event.sequence > replayed_up_to => {
+                Some(Ok(event_to(&event)))
+            }
+            _ => None,
+        });
+
+        Ok(Response::new(Box::pin(replayed.chain(updates))))
+    }
+
+    async fn fetch_report(
+        &self,
+        request: Request<proto::FetchReportRequest>,
+    ) -> Result<Response<proto::FetchReportResponse>, Status> {
+        self.operator(&request)?;
+        let operation_id = parse_operation_id(&request.get_ref().operation_id)?;
+        let json = request.get_ref().format() == proto::ReportFormat::SignedJson;
+
+        let (path, content_type) = self.state.certificate_file(operation_id, json).map_err(status_from)?;
+        let content = tokio::fs::read(&path)
+            .await
+            .map_err(|e| Status::not_found(format!("Failed to read {}: {}", path, e)))?;
+        let filename = std::path::Path::new(&path)
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_default();
+
+        Ok(Response::new(proto::FetchReportResponse {
+            content,
+            content_type: content_type.to_string(),
+            filename,
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+    use tonic::Code;
+
+    fn error(code: WipeErrorCode) -> WipeError {
+        WipeError { code, message: "refused".to_string(), sector: None }
+    }
+
+    fn job(status: JobStatus) -> WipeJob {
+        WipeJob {
+            operation_id: Uuid::new_v4(),
+            target_path: "/dev/sdb".to_string(),
+            operator: "alice".to_string(),
+            status,
+            started_at: Utc::now(),
+            finished_at: None,
+            progress: None,
+            result: None,
+            certificate_path: None,
+            error: None,
+            error_code: None,
+        }
+    }
+
+    #[test]
+    fn test_job_status_mapping() {
+        let cases = [
+            (JobStatus::Running, proto::JobStatus::Running),
+            (JobStatus::Verifying, proto::JobStatus::Verifying),
+            (JobStatus::Cancelling, proto::JobStatus::Cancelling),
+            (JobStatus::Completed, proto::JobStatus::Completed),
+            (JobStatus::Failed, proto::JobStatus::Failed),
+            (JobStatus::Cancelled, proto::JobStatus::Cancelled),
+        ];
+        for (status, expected) in cases {
+            assert_eq!(job_status_to(status), expected);
+        }
+    }
+
+    #[test]
+    fn test_job_mapping() {
+        let running = job(JobStatus::Running);
+        let message = job_to(&running);
+        assert_eq!(message.operation_id, running.operation_id.to_string());
+        assert_eq!(message.status, proto::JobStatus::Running as i32);
+        assert_eq!(message.finished_at, "");
+        assert!(!message.success && !message.has_certificate);
+
+        let mut failed = job(JobStatus::Failed);
+        failed.finished_at = Some(Utc::now());
+        failed.error = Some("Write failed".to_string());
+        let message = job_to(&failed);
+        assert_eq!(message.status, proto::JobStatus::Failed as i32);
+        assert_eq!(message.finished_at, failed.finished_at.unwrap().to_rfc3339());
+        assert_eq!(message.error, "Write failed");
+    }
+
+    #[test]
+    fn test_status_event_mapping() {
+        let operation_id = Uuid::new_v4();
+        let event = SequencedEvent {
+            sequence: 7,
+            event: WipeEvent::Status { operation_id, status: JobStatus::Cancelled, message: None },
+        };
+
+        let message = event_to(&event);
+        assert_eq!(message.sequence, 7);
+        assert_eq!(message.operation_id, operation_id.to_string());
+        match message.event {
+            Some(proto::wipe_event::Event::Status(change)) => {
+                assert_eq!(change.status, proto::JobStatus::Cancelled as i32);
+                assert_eq!(change.message, "");
+            }
+            other => panic!("expected a status change, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_error_code_mapping() {
+        let cases = [
+            (WipeErrorCode::NotAuthenticated, Code::Unauthenticated),
+            (WipeErrorCode::PolicyDenied, Code::PermissionDenied),
+            (WipeErrorCode::DriveNotFound, Code::NotFound),
+            (WipeErrorCode::InvalidTarget, Code::InvalidArgument),
+            (WipeErrorCode::VolumeInUse, Code::FailedPrecondition),
+            (WipeErrorCode::Cancelled, Code::Cancelled),
+            (WipeErrorCode::HardwareError, Code::Internal),
+        ];
+        for (code, expected) in cases {
+            let status = status_from(error(code));
+            assert_eq!(status.code(), expected);
+            assert_eq!(status.message(), "refused");
+        }
+    }
+
+    #[test]
+    fn test_request_enum_mapping() {
+        assert_eq!(priority_from(proto::WipePriority::Unspecified), WipePriority::Normal);
+        assert_eq!(priority_from(proto::WipePriority::High), WipePriority::High);
+        assert!(matches!(target_type_from(proto::TargetType::Ssd), Ok(TargetType::SSD)));
+        assert_eq!(target_type_from(proto::TargetType::Unspecified).unwrap_err().code(), Code::InvalidArgument);
+        assert_eq!(standard_from(proto::SanitizationStandard::Unspecified).unwrap_err().code(), Code::InvalidArgument);
+    }
+}
@@ -2,8 +2,14 @@ pub mod database;
 pub mod api;
 pub mod client;
 pub mod models;
+pub mod engine_api;
+pub mod station;
+#[cfg(feature = "grpc")]
+pub mod grpc;
 
 pub use database::DatabaseManager;
 pub use api::start_server;
+pub use engine_api::start_engine_server;
 pub use client::ServerClient;
+pub use station::run_station_agent;
 pub use models::*;
\ No newline at end of file
@@ -1,6 +1,9 @@
 use serde::{Deserialize, Serialize};
 use chrono::{DateTime, Utc};
 use uuid::Uuid;
+use crate::core::{SafeWipeErrorCode, SanitizationStandard, TargetType, WipeError, WipePriority, WipeScope};
+use crate::security::{verify_certificate, ErasureCertificate};
+use crate::server::engine_api::StartWipeRequest;
 
 #[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
 pub struct ServerUser {
@@ -22,6 +25,38 @@ pub struct Certificate {
     pub sanitization_method: String,
     pub created_at: DateTime<Utc>,
     pub file_hash: String,        // Hash of the certificate for integrity
+    pub device_serial: Option<String>,
+    pub operator: Option<String>,
+    pub completed_at: Option<DateTime<Utc>>, // Wipe completion time stated in the certificate
+    pub signature_valid: Option<bool>,       // Hash and signature checked on upload; None if not a signed certificate
+}
+
+/// Searchable fields read from an uploaded certificate
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CertificateMetadata {
+    pub device_serial: Option<String>,
+    pub operator: Option<String>,
+    pub completed_at: Option<DateTime<Utc>>,
+    pub signature_valid: Option<bool>,
+}
+
+impl CertificateMetadata {
+    /// Metadata of a signed `ErasureCertificate` in JSON form; empty for
+    /// anything else
+    pub fn from_certificate_data(certificate_data: &str) -> Self {
+        match serde_json::from_str::<ErasureCertificate>(certificate_data) {
+            Ok(certificate) => {
+                let verdict = verify_certificate(&certificate, None);
+                Self {
+                    device_serial: Some(certificate.drive_info.serial_number),
+                    operator: Some(certificate.operator),
+                    completed_at: Some(certificate.wipe_details.completion_time),
+                    signature_valid: Some(verdict.hash_valid && verdict.signature_valid),
+                }
+            }
+            Err(_) => Self::default(),
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
@@ -67,6 +102,25 @@ pub struct SubmitCertificateRequest {
     pub sanitization_method: String,
 }
 
+/// Archive search; unset fields do not filter. `serial` takes a comma
+/// separated list so a whole hardware lot can be pulled at once.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CertificateQuery {
+    pub serial: Option<String>,
+    pub operator: Option<String>,
+    /// Completion time bounds (RFC 3339), inclusive
+    pub since: Option<DateTime<Utc>>,
+    pub until: Option<DateTime<Utc>>,
+}
+
+impl CertificateQuery {
+    pub fn serials(&self) -> Option<Vec<String>> {
+        self.serial.as_ref().map(|list| {
+            list.split(',').map(str::trim).filter(|s| !s.is_empty()).map(str::to_string).collect()
+        })
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct CertificateResponse {
     pub certificates: Vec<Certificate>,
@@ -83,6 +137,9 @@ pub struct SanitizationLogResponse {
 pub struct ApiResponse<T> {
     pub success: bool,
     pub data: Option<T>,
+    /// What went wrong, for clients to branch on; null on success
+    #[serde(default)]
+    pub error_code: Option<SafeWipeErrorCode>,
     pub message: String,
 }
 
@@ -91,15 +148,159 @@ impl<T> ApiResponse<T> {
         Self {
             success: true,
             data: Some(data),
+            error_code: None,
             message: "Success".to_string(),
         }
     }
     
-    pub fn error(message: String) -> Self {
+    pub fn error(code: SafeWipeErrorCode, message: String) -> Self {
         Self {
             success: false,
             data: None,
+            error_code: Some(code),
             message,
         }
     }
-}
\ No newline at end of file
+
+    /// The response for an engine error
+    pub fn failure(error: &WipeError) -> Self {
+        Self::error(error.error_code(), error.message.clone())
+    }
+}
+
+// ---- Fleet coordination: wipe stations and centrally queued jobs ----
+
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct Station {
+    pub id: Uuid,
+    pub name: String,
+    pub hostname: String,
+    pub agent_version: String,
+    pub registered_by: Uuid,
+    pub registered_at: DateTime<Utc>,
+    pub last_seen: Option<DateTime<Utc>>,
+}
+
+/// A disk a station reported at its last check-in
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct StationDevice {
+    pub station_id: Uuid,
+    pub device_path: String,
+    pub model: String,
+    pub serial_number: String,
+    pub size_bytes: i64,
+    pub reported_at: DateTime<Utc>,
+}
+
+/// A wipe queued on the server for one station
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct FleetJob {
+    pub id: Uuid,
+    pub station_id: Uuid,
+    pub device: String,            // Stable device id or device path on the station
+    pub target_type: String,       // TargetType variant name
+    pub standard: String,          // SanitizationStandard variant name
+    pub scope: String,             // JSON serialized WipeScope
+    pub verify: bool,
+    pub status: String,            // "queued", "assigned", "running", "completed", "failed", "cancelled"
+    pub progress_percent: f64,
+    pub bytes_processed: i64,
+    pub message: Option<String>,
+    pub operation_id: Option<Uuid>, // Engine operation id once the station started it
+    pub certificate_id: Option<Uuid>,
+    pub created_by: Uuid,
+    pub created_at: DateTime<Utc>,
+    pub assigned_at: Option<DateTime<Utc>>,
+    pub updated_at: Option<DateTime<Utc>>,
+    pub completed_at: Option<DateTime<Utc>>,
+}
+
+impl FleetJob {
+    pub const STATUSES: [&'static str; 6] = ["queued", "assigned", "running", "completed", "failed", "cancelled"];
+
+    pub fn is_finished(&self) -> bool {
+        matches!(self.status.as_str(), "completed" | "failed" | "cancelled")
+    }
+
+    /// The job as a wipe request for the station's engine
+    pub fn to_wipe_request(&self) -> Result<StartWipeRequest, serde_json::Error> {
+        Ok(StartWipeRequest {
+            device: self.device.clone(),
+            target_type: serde_json::from_value(serde_json::Value::String(self.target_type.clone()))?,
+            scope: serde_json::from_str(&self.scope)?,
+            standard: serde_json::from_value(serde_json::Value::String(self.standard.clone()))?,
+            verify: self.verify,
+            certificate: true,
+            profile: None,
+            recipe: None,
+            confirmation: None,
+            operation_id: None,
+            simulate: false,
+            skip_trim: false,
+            security_state: None,
+            priority: WipePriority::Normal,
+        })
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RegisterStationRequest {
+    pub name: String,
+    pub hostname: String,
+    pub agent_version: String,
+}
+
+/// Returned once at registration; the server only keeps a hash of the token
+#[derive(Debug, Serialize, Deserialize)]
+pub struct StationRegistration {
+    pub station: Station,
+    pub station_token: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeviceReport {
+    pub device_path: String,
+    pub model: String,
+    pub serial_number: String,
+    pub size_bytes: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct StationCheckIn {
+    pub devices: Vec<DeviceReport>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CheckInResponse {
+    pub station_id: Uuid,
+    pub queued_jobs: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct EnqueueJobRequest {
+    pub station_id: Uuid,
+    pub device: String,
+    pub target_type: TargetType,
+    #[serde(default)]
+    pub scope: WipeScope,
+    pub standard: SanitizationStandard,
+    #[serde(default = "default_verify")]
+    pub verify: bool,
+}
+
+fn default_verify() -> bool { true }
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JobProgressUpdate {
+    pub status: String,
+    pub operation_id: Option<Uuid>,
+    pub progress_percent: f64,
+    pub bytes_processed: i64,
+    pub message: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct FleetJobResponse {
+    pub jobs: Vec<FleetJob>,
+    pub total: i64,
+}
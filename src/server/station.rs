@@ -0,0 +1,252 @@
+//! Fleet station agent
+//!
+//! Connects a local `WipeEngine` to the central server: the station checks
+//! in with its attached disks, long-polls for jobs queued for it, runs each
+//! one through the same job table as the remote control API and pushes
+//! progress, the final status and the signed certificate back to the server.
+
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::broadcast::error::RecvError;
+use tracing::{error, info, warn};
+use uuid::Uuid;
+
+use crate::core::{OperatorIdentity, WipeEngine};
+use crate::server::engine_api::{JobStatus, StationState, WipeEvent};
+use crate::server::models::{DeviceReport, FleetJob, JobProgressUpdate};
+use crate::server::ServerClient;
+
+/// How long each job pull waits on the server; also the check-in interval
+const JOB_WAIT_SECS: u64 = 30;
+
+/// Pause before retrying after the server could not be reached
+const RETRY_DELAY: Duration = Duration::from_secs(10);
+
+/// Minimum time between progress pushes for one job
+const PROGRESS_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Run jobs queued for this station until the process exits. `client` must
+/// carry station credentials; wipes run as the owner of `operator_token`.
+pub async fn run_station_agent(
+    engine: WipeEngine,
+    client: ServerClient,
+    operator_token: &str,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let station_id = client.get_station_id().ok_or("Station credentials are required")?;
+    let state = StationState::new(engine);
+    let operator = state.authenticate_token(operator_token)?;
+    let client = Arc::new(client);
+
+    info!(station = %station_id, operator = %operator.display_name(), "Station running jobs");
+
+    loop {
+        let devices = match state.scan_devices(operator.clone()).await {
+            Ok(disks) => disks
+                .into_iter()
+                .map(|disk| DeviceReport {
+                    device_path: disk.path,
+                    model: disk.model,
+                    serial_number: disk.serial,
+                    size_bytes: disk.size_bytes as i64,
+                })
+                .collect(),
+            Err(e) => {
+                warn!(error = %e, "Device scan failed");
+                Vec::new()
+            }
+        };
+
+        if let Err(e) = client.check_in(devices).await {
+            warn!(error = %e, "Check-in failed");
+            tokio::time::sleep(RETRY_DELAY).await;
+            continue;
+        }
+
+        match client.pull_job(JOB_WAIT_SECS).await {
+            Ok(Some(job)) => {
+                info!(job = %job.id, device = %job.device, standard = %job.standard, "Job received");
+                tokio::spawn(run_job(state.clone(), client.clone(), operator.clone(), job));
+            }
+            Ok(None) => {}
+            Err(e) => {
+                warn!(error = %e, "Job pull failed");
+                tokio::time::sleep(RETRY_DELAY).await;
+            }
+        }
+    }
+}
+
+/// Run one job and report its outcome; errors are reported to the server
+async fn run_job(state: Arc<StationState>, client: Arc<ServerClient>, operator: OperatorIdentity, job: FleetJob) {
+    let started = match job.to_wipe_request() {
+        Ok(request) => state.start_wipe(operator, request).map_err(|e| e.to_string()),
+        Err(e) => Err(format!("Invalid job definition: {}", e)),
+    };
+    let operation_id = match started {
+        Ok(operation_id) => operation_id,
+        Err(message) => {
+            error!(job = %job.id, error = %message, "Job could not start");
+            report(&client, job.id, "failed", None, 0.0, 0, Some(message)).await;
+            return;
+        }
+    };
+    report(&client, job.id, "running", Some(operation_id), 0.0, 0, None).await;
+
+    let (status, message) = follow_wipe(&state, &client, job.id, operation_id).await;
+
+    if status == JobStatus::Completed {
+        upload_certificate(&state, &client, &job, operation_id).await;
+    }
+
+    let (percent, bytes) = state
+        .job(operation_id)
+        .ok()
+        .and_then(|wipe| wipe.progress)
+        .map(|p| (p.percentage, (p.sectors_processed * 512) as i64))
+        .unwrap_or((0.0, 0));
+    let status_name = fleet_status(status);
+    report(&client, job.id, status_name, Some(operation_id), percent, bytes, message).await;
+    info!(job = %job.id, status = status_name, "Job finished");
+}
+
+/// The fleet job status reported for a wipe that reached `status`
+fn fleet_status(status: JobStatus) -> &'static str {
+    match status {
+        JobStatus::Completed => "completed",
+        JobStatus::Cancelled => "cancelled",
+        _ => "failed",
+    }
+}
+
+/// Forward progress of a running wipe until it reaches a final status
+async fn follow_wipe(
+    state: &StationState,
+    client: &ServerClient,
+    job_id: Uuid,
+    operation_id: Uuid,
+) -> (JobStatus, Option<String>) {
+    let (_, replay, mut live) = match state.subscribe(operation_id, 0) {
+        Ok(subscription) => subscription,
+        Err(e) => return (JobStatus::Failed, Some(e.to_string())),
+    };
+
+    let mut last_push = Instant::now();
+    let mut last_sequence = 0;
+    let mut pending = replay.into_iter();
+    loop {
+        let event = match pending.next() {
+            Some(event) => event,
+            None => match live.recv().await {
+                Ok(event) => event,
+                Err(RecvError::Lagged(_)) => continue,
+                Err(RecvError::Closed) => return (JobStatus::Failed, Some("Event stream closed".to_string())),
+            },
+        };
+        if event.event.operation_id() != operation_id || event.sequence <= last_sequence {
+            continue;
+        }
+        last_sequence = event.sequence;
+
+        match event.event {
+            WipeEvent::Progress(progress) if last_push.elapsed() >= PROGRESS_INTERVAL => {
+                last_push = Instant::now();
+                let bytes = (progress.sectors_processed * 512) as i64;
+                report(client, job_id, "running", Some(operation_id), progress.percentage, bytes, Some(progress.current_operation)).await;
+            }
+            WipeEvent::Status { status, message, .. } if status.is_finished() => return (status, message),
+            _ => {}
+        }
+    }
+}
+
+async fn upload_certificate(state: &StationState, client: &ServerClient, job: &FleetJob, operation_id: Uuid) {
+    let content = state
+        .certificate_file(operation_id, true)
+        .map_err(|e| e.to_string())
+        .and_then(|(path, _)| std::fs::read_to_string(&path).map_err(|e| format!("{}: {}", path, e)));
+
+    let uploaded = match content {
+        Ok(content) => client
+            .upload_certificate(job.id, &content, &job.device, &job.standard)
+            .await
+            .map(|certificate| info!(certificate = %certificate.id, job = %job.id, "Certificate uploaded"))
+            .map_err(|e| e.to_string()),
+        Err(e) => Err(e),
+    };
+    if let Err(e) = uploaded {
+        warn!(job = %job.id, error = %e, "Certificate not uploaded");
+    }
+}
+
+async fn report(
+    client: &ServerClient,
+    job_id: Uuid,
+    status: &str,
+    operation_id: Option<Uuid>,
+    progress_percent: f64,
+    bytes_processed: i64,
+    message: Option<String>,
+) {
+    let update = JobProgressUpdate {
+        status: status.to_string(),
+        operation_id,
+        progress_percent,
+        bytes_processed,
+        message,
+    };
+    if let Err(e) = client.push_progress(job_id, &update).await {
+        warn!(job = %job_id, error = %e, "Progress not delivered");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+    use crate::core::{SanitizationStandard, TargetType, WipeScope};
+
+    fn fleet_job(target_type: &str, standard: &str) -> FleetJob {
+        FleetJob {
+            id: Uuid::new_v4(),
+            station_id: Uuid::new_v4(),
+            device: "/dev/sdb".to_string(),
+            target_type: target_type.to_string(),
+            standard: standard.to_string(),
+            scope: serde_json::to_string(&WipeScope::default()).unwrap(),
+            verify: false,
+            status: "assigned".to_string(),
+            progress_percent: 0.0,
+            bytes_processed: 0,
+            message: None,
+            operation_id: None,
+            certificate_id: None,
+            created_by: Uuid::new_v4(),
+            created_at: Utc::now(),
+            assigned_at: Some(Utc::now()),
+            updated_at: None,
+            completed_at: None,
+        }
+    }
+
+    #[test]
+    fn test_final_status_mapping() {
+        assert_eq!(fleet_status(JobStatus::Completed), "completed");
+        assert_eq!(fleet_status(JobStatus::Cancelled), "cancelled");
+        assert_eq!(fleet_status(JobStatus::Failed), "failed");
+        // A wipe that never settled is not reported as a success
+        assert_eq!(fleet_status(JobStatus::Running), "failed");
+        assert_eq!(fleet_status(JobStatus::Cancelling), "failed");
+    }
+
+    #[test]
+    fn test_job_to_wipe_request() {
+        let request = fleet_job("SSD", "NIST_SP_800_88_R1").to_wipe_request().unwrap();
+        assert_eq!(request.device, "/dev/sdb");
+        assert_eq!(request.target_type, TargetType::SSD);
+        assert_eq!(request.standard, SanitizationStandard::NIST_SP_800_88_R1);
+        assert!(!request.verify && request.certificate);
+
+        assert!(fleet_job("Floppy", "NIST_SP_800_88_R1").to_wipe_request().is_err());
+        assert!(fleet_job("SSD", "Unknown").to_wipe_request().is_err());
+    }
+}
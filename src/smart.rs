@@ -0,0 +1,551 @@
+/*!
+ * SMART Device Health Monitoring
+ *
+ * This module reads drive self-monitoring data required for:
+ * - Overall health assessment before a drive is sanitized or redeployed
+ * - Temperature, reallocated/pending sector and wear reporting
+ * - Power-on hours for asset tracking in erasure reports
+ *
+ * ATA drives are queried with SMART READ DATA (B0h/D0h) and SMART READ
+ * THRESHOLDS (B0h/D1h) through SCSI ATA PASS-THROUGH(16) via `sg_raw`.
+ * NVMe drives are queried for the SMART / Health Information log page
+ * (02h) through `nvme-cli`.
+ *
+ * Short self-tests are started with SMART EXECUTE OFF-LINE IMMEDIATE
+ * (B0h/D4h) or the NVMe Device Self-test command, and followed through the
+ * self-test execution status of SMART READ DATA or the Device Self-test log
+ * page (06h).
+ */
+
+use serde::{Deserialize, Serialize};
+use std::io;
+use std::process::Command;
+
+// ============================================================================
+// SMART CONSTANTS
+// ============================================================================
+
+pub const ATA_SMART_COMMAND: u8 = 0xB0;
+pub const SMART_READ_DATA: u8 = 0xD0;
+pub const SMART_READ_THRESHOLDS: u8 = 0xD1;
+pub const SMART_LBA_MID: u8 = 0x4F;
+pub const SMART_LBA_HIGH: u8 = 0xC2;
+
+pub const SMART_EXECUTE_OFFLINE_IMMEDIATE: u8 = 0xD4;
+/// Sub-command of EXECUTE OFF-LINE IMMEDIATE starting a short self-test
+pub const SMART_SHORT_SELF_TEST: u8 = 0x01;
+
+pub const NVME_LOG_SMART_HEALTH: u8 = 0x02;
+pub const NVME_LOG_SELF_TEST: u8 = 0x06;
+pub const NVME_SELF_TEST_LOG_SIZE: usize = 564;
+pub const SMART_DATA_SIZE: usize = 512;
+
+/// Self-test execution status byte of SMART READ DATA
+const SMART_SELF_TEST_STATUS_OFFSET: usize = 363;
+
+const SMART_ATTRIBUTE_COUNT: usize = 30;
+const SMART_ATTRIBUTE_SIZE: usize = 12;
+
+// ATA SMART attribute identifiers
+pub const ATTR_REALLOCATED_SECTORS: u8 = 5;
+pub const ATTR_POWER_ON_HOURS: u8 = 9;
+pub const ATTR_WEAR_LEVELING_COUNT: u8 = 177;
+pub const ATTR_AIRFLOW_TEMPERATURE: u8 = 190;
+pub const ATTR_TEMPERATURE: u8 = 194;
+pub const ATTR_PENDING_SECTORS: u8 = 197;
+pub const ATTR_SSD_LIFE_LEFT: u8 = 231;
+pub const ATTR_MEDIA_WEAROUT: u8 = 233;
+
+// NVMe critical warning bits (log page 02h, byte 0)
+const NVME_WARN_SPARE: u8 = 0x01;
+const NVME_WARN_TEMPERATURE: u8 = 0x02;
+const NVME_WARN_RELIABILITY: u8 = 0x04;
+const NVME_WARN_READ_ONLY: u8 = 0x08;
+
+// ============================================================================
+// DATA STRUCTURES
+// ============================================================================
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum HealthStatus {
+    Good,
+    Warning,
+    Failing,
+    Unknown,
+}
+
+impl HealthStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            HealthStatus::Good => "Good",
+            HealthStatus::Warning => "Warning",
+            HealthStatus::Failing => "Failing",
+            HealthStatus::Unknown => "Unknown",
+        }
+    }
+}
+
+/// One entry of the ATA SMART attribute table
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SmartAttribute {
+    pub id: u8,
+    pub flags: u16,
+    pub current: u8,
+    pub worst: u8,
+    pub raw: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeviceHealth {
+    pub device_path: String,
+    pub health_status: HealthStatus,
+    pub temperature_celsius: Option<i32>,
+    pub reallocated_sectors: Option<u64>,
+    pub pending_sectors: Option<u64>,
+    /// Percentage of rated endurance consumed (NVMe percent used, ATA wear indicators)
+    pub percent_used: Option<u8>,
+    pub power_on_hours: Option<u64>,
+    pub warnings: Vec<String>,
+}
+
+impl DeviceHealth {
+    pub fn unknown(device_path: &str) -> Self {
+        Self {
+            device_path: device_path.to_string(),
+            health_status: HealthStatus::Unknown,
+            temperature_celsius: None,
+            reallocated_sectors: None,
+            pending_sectors: None,
+            percent_used: None,
+            power_on_hours: None,
+            warnings: Vec::new(),
+        }
+    }
+}
+
+/// Where a drive's most recent self-test stands
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum SelfTestStatus {
+    InProgress { percent_complete: u8 },
+    Passed,
+    /// Aborted or failed, with the drive's reason
+    Failed(String),
+}
+
+// ============================================================================
+// PARSERS
+// ============================================================================
+
+/// Parse the attribute table of a SMART READ DATA response
+pub fn parse_ata_smart_attributes(data: &[u8]) -> io::Result<Vec<SmartAttribute>> {
+    if data.len() < SMART_DATA_SIZE {
+        return Err(io::Error::new(io::ErrorKind::InvalidData, "SMART data too short"));
+    }
+
+    let mut attributes = Vec::new();
+    for i in 0..SMART_ATTRIBUTE_COUNT {
+        let entry = &data[2 + i * SMART_ATTRIBUTE_SIZE..2 + (i + 1) * SMART_ATTRIBUTE_SIZE];
+        if entry[0] == 0 {
+            continue;
+        }
+
+        let mut raw = [0u8; 8];
+        raw[..6].copy_from_slice(&entry[5..11]);
+
+        attributes.push(SmartAttribute {
+            id: entry[0],
+            flags: u16::from_le_bytes([entry[1], entry[2]]),
+            current: entry[3],
+            worst: entry[4],
+            raw: u64::from_le_bytes(raw),
+        });
+    }
+
+    Ok(attributes)
+}
+
+/// Parse a SMART READ THRESHOLDS response into (attribute id, threshold) pairs
+pub fn parse_ata_smart_thresholds(data: &[u8]) -> io::Result<Vec<(u8, u8)>> {
+    if data.len() < SMART_DATA_SIZE {
+        return Err(io::Error::new(io::ErrorKind::InvalidData, "SMART threshold data too short"));
+    }
+
+    Ok((0..SMART_ATTRIBUTE_COUNT)
+        .map(|i| &data[2 + i * SMART_ATTRIBUTE_SIZE..2 + (i + 1) * SMART_ATTRIBUTE_SIZE])
+        .filter(|entry| entry[0] != 0)
+        .map(|entry| (entry[0], entry[1]))
+        .collect())
+}
+
+/// Build a health summary from ATA SMART attributes and (optional) thresholds
+pub fn ata_health(device_path: &str, attributes: &[SmartAttribute], thresholds: &[(u8, u8)]) -> DeviceHealth {
+    let mut health = DeviceHealth::unknown(device_path);
+    let find = |id: u8| attributes.iter().find(|a| a.id == id);
+
+    health.reallocated_sectors = find(ATTR_REALLOCATED_SECTORS).map(|a| a.raw & 0xFFFF_FFFF);
+    health.pending_sectors = find(ATTR_PENDING_SECTORS).map(|a| a.raw & 0xFFFF_FFFF);
+    health.power_on_hours = find(ATTR_POWER_ON_HOURS).map(|a| a.raw & 0xFFFF_FFFF);
+    health.temperature_celsius = find(ATTR_TEMPERATURE)
+        .or_else(|| find(ATTR_AIRFLOW_TEMPERATURE))
+        .map(|a| (a.raw & 0xFF) as i32);
+
+    // Wear indicators count down from 100 as rated endurance is consumed
+    health.percent_used = find(ATTR_MEDIA_WEAROUT)
+        .or_else(|| find(ATTR_SSD_LIFE_LEFT))
+        .or_else(|| find(ATTR_WEAR_LEVELING_COUNT))
+        .map(|a| 100u8.saturating_sub(a.current.min(100)));
+
+    let mut status = HealthStatus::Good;
+
+    for &(id, threshold) in thresholds {
+        if let Some(attr) = find(id) {
+            // Pre-fail attributes (flag bit 0) at or below threshold predict imminent failure
+            if threshold > 0 && attr.current <= threshold && attr.flags & 0x0001 != 0 {
+                health.warnings.push(format!("Attribute {} below failure threshold ({} <= {})", id, attr.current, threshold));
+                status = HealthStatus::Failing;
+            }
+        }
+    }
+
+    if let Some(count) = health.reallocated_sectors.filter(|&c| c > 0) {
+        health.warnings.push(format!("{} reallocated sectors", count));
+        if status == HealthStatus::Good {
+            status = HealthStatus::Warning;
+        }
+    }
+    if let Some(count) = health.pending_sectors.filter(|&c| c > 0) {
+        health.warnings.push(format!("{} sectors pending reallocation", count));
+        if status == HealthStatus::Good {
+            status = HealthStatus::Warning;
+        }
+    }
+
+    health.health_status = status;
+    health
+}
+
+/// Parse the NVMe SMART / Health Information log page (02h)
+pub fn parse_nvme_smart_log(device_path: &str, data: &[u8]) -> io::Result<DeviceHealth> {
+    if data.len() < SMART_DATA_SIZE {
+        return Err(io::Error::new(io::ErrorKind::InvalidData, "NVMe SMART log too short"));
+    }
+
+    let read_u64 = |offset: usize| {
+        // 128-bit counters; the upper half is never reached in practice
+        let mut bytes = [0u8; 8];
+        bytes.copy_from_slice(&data[offset..offset + 8]);
+        u64::from_le_bytes(bytes)
+    };
+
+    let critical_warning = data[0];
+    let temperature_kelvin = u16::from_le_bytes([data[1], data[2]]);
+    let available_spare = data[3];
+    let spare_threshold = data[4];
+
+    let mut health = DeviceHealth::unknown(device_path);
+    health.temperature_celsius = if temperature_kelvin > 0 {
+        Some(temperature_kelvin as i32 - 273)
+    } else {
+        None
+    };
+    health.percent_used = Some(data[5]);
+    health.power_on_hours = Some(read_u64(128));
+
+    let media_errors = read_u64(160);
+    let mut status = HealthStatus::Good;
+
+    if critical_warning & (NVME_WARN_RELIABILITY | NVME_WARN_READ_ONLY) != 0 {
+        health.warnings.push("NVM subsystem reliability degraded".to_string());
+        status = HealthStatus::Failing;
+    }
+    if critical_warning & NVME_WARN_SPARE != 0 || available_spare < spare_threshold {
+        health.warnings.push(format!("Available spare {}% below threshold {}%", available_spare, spare_threshold));
+        status = HealthStatus::Failing;
+    }
+    if critical_warning & NVME_WARN_TEMPERATURE != 0 {
+        health.warnings.push("Temperature outside operating range".to_string());
+        if status == HealthStatus::Good {
+            status = HealthStatus::Warning;
+        }
+    }
+    if media_errors > 0 {
+        health.warnings.push(format!("{} media and data integrity errors", media_errors));
+        if status == HealthStatus::Good {
+            status = HealthStatus::Warning;
+        }
+    }
+    if data[5] >= 100 && status == HealthStatus::Good {
+        health.warnings.push("Rated endurance exhausted".to_string());
+        status = HealthStatus::Warning;
+    }
+
+    health.health_status = status;
+    Ok(health)
+}
+
+/// Parse the self-test execution status byte of a SMART READ DATA response
+pub fn parse_ata_self_test_status(data: &[u8]) -> io::Result<SelfTestStatus> {
+    if data.len() < SMART_DATA_SIZE {
+        return Err(io::Error::new(io::ErrorKind::InvalidData, "SMART data too short"));
+    }
+    let status = data[SMART_SELF_TEST_STATUS_OFFSET];
+    // The low nibble counts the remaining work in tens of percent
+    let reason = match status >> 4 {
+        0x0 => return Ok(SelfTestStatus::Passed),
+        0xF => return Ok(SelfTestStatus::InProgress { percent_complete: 100u8.saturating_sub((status & 0x0F) * 10) }),
+        0x1 => "aborted by the host",
+        0x2 => "interrupted by a reset",
+        0x3 => "fatal error",
+        0x5 => "electrical element failed",
+        0x6 => "servo or seek element failed",
+        0x7 => "read element failed",
+        0x8 => "handling damage suspected",
+        _ => "unknown test element failed",
+    };
+    Ok(SelfTestStatus::Failed(reason.to_string()))
+}
+
+/// Parse the NVMe Device Self-test log page (06h): the running test, else
+/// the newest result
+pub fn parse_nvme_self_test_log(data: &[u8]) -> io::Result<SelfTestStatus> {
+    if data.len() < 32 {
+        return Err(io::Error::new(io::ErrorKind::InvalidData, "NVMe self-test log too short"));
+    }
+    if data[0] & 0x0F != 0 {
+        return Ok(SelfTestStatus::InProgress { percent_complete: data[1] & 0x7F });
+    }
+    let reason = match data[4] & 0x0F {
+        0x0 => return Ok(SelfTestStatus::Passed),
+        0x1 => "aborted by a Device Self-test command",
+        0x2 => "aborted by a controller reset",
+        0x3 => "aborted by a namespace removal",
+        0x4 => "aborted by a format",
+        0x5 => "fatal error",
+        0x6 => "failed segment",
+        0x7 => "one or more segments failed",
+        0xF => "no self-test has run",
+        _ => "aborted",
+    };
+    Ok(SelfTestStatus::Failed(reason.to_string()))
+}
+
+// ============================================================================
+// SMART INTERFACE
+// ============================================================================
+
+pub struct SmartInterface {
+    device_path: String,
+}
+
+impl SmartInterface {
+    pub fn new(device_path: &str) -> Self {
+        Self {
+            device_path: device_path.to_string(),
+        }
+    }
+
+    /// Read SMART data and summarise the device health
+    pub fn read_health(&self) -> io::Result<DeviceHealth> {
+        if self.device_path.contains("nvme") {
+            let log = self.nvme_smart_log()?;
+            return parse_nvme_smart_log(&self.device_path, &log);
+        }
+
+        let data = self.ata_smart_command(SMART_READ_DATA)?;
+        let attributes = parse_ata_smart_attributes(&data)?;
+        let thresholds = self.ata_smart_command(SMART_READ_THRESHOLDS)
+            .and_then(|data| parse_ata_smart_thresholds(&data))
+            .unwrap_or_default();
+
+        Ok(ata_health(&self.device_path, &attributes, &thresholds))
+    }
+
+    /// Start the drive's short self-test; it runs in the background of the
+    /// drive, which keeps serving commands
+    pub fn start_short_self_test(&self) -> io::Result<()> {
+        #[cfg(unix)]
+        {
+            let output = if self.device_path.contains("nvme") {
+                Command::new("nvme")
+                    .args(["device-self-test", &self.device_path, "--self-test-code=1"])
+                    .output()?
+            } else {
+                // ATA PASS-THROUGH(16): non-data, short self-test in off-line mode
+                let cdb = [
+                    0x85, 0x06, 0x00, 0x00, SMART_EXECUTE_OFFLINE_IMMEDIATE, 0x00, 0x00, 0x00,
+                    SMART_SHORT_SELF_TEST, 0x00, SMART_LBA_MID, 0x00, SMART_LBA_HIGH, 0x00, ATA_SMART_COMMAND, 0x00,
+                ];
+                let cdb_args: Vec<String> = cdb.iter().map(|b| format!("{:02x}", b)).collect();
+                Command::new("sg_raw").arg(&self.device_path).args(&cdb_args).output()?
+            };
+            if !output.status.success() {
+                return Err(io::Error::other(format!(
+                    "Self-test could not be started: {}",
+                    String::from_utf8_lossy(&output.stderr).trim()
+                )));
+            }
+            Ok(())
+        }
+
+        #[cfg(not(unix))]
+        {
+            Err(io::Error::new(io::ErrorKind::Unsupported, "Self-tests are not available on this platform"))
+        }
+    }
+
+    pub fn self_test_status(&self) -> io::Result<SelfTestStatus> {
+        if self.device_path.contains("nvme") {
+            let log = self.nvme_log(NVME_LOG_SELF_TEST, NVME_SELF_TEST_LOG_SIZE)?;
+            return parse_nvme_self_test_log(&log);
+        }
+        parse_ata_self_test_status(&self.ata_smart_command(SMART_READ_DATA)?)
+    }
+
+    fn nvme_smart_log(&self) -> io::Result<Vec<u8>> {
+        self.nvme_log(NVME_LOG_SMART_HEALTH, SMART_DATA_SIZE)
+    }
+
+    fn nvme_log(&self, log_id: u8, length: usize) -> io::Result<Vec<u8>> {
+        #[cfg(unix)]
+        {
+            let output = Command::new("nvme")
+                .args([
+                    "get-log",
+                    &self.device_path,
+                    &format!("--log-id={}", log_id),
+                    &format!("--log-len={}", length),
+                    "--raw-binary",
+                ])
+                .output()?;
+
+            if !output.status.success() {
+                return Err(io::Error::other(format!("NVMe log page {:02X}h read failed: {}", log_id, String::from_utf8_lossy(&output.stderr).trim())));
+            }
+
+            Ok(output.stdout)
+        }
+
+        #[cfg(not(unix))]
+        {
+            let _ = (log_id, length);
+            Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                "NVMe log page pass-through is not available on this platform"
+            ))
+        }
+    }
+
+    fn ata_smart_command(&self, feature: u8) -> io::Result<Vec<u8>> {
+        #[cfg(unix)]
+        {
+            // ATA PASS-THROUGH(16): PIO data-in, one 512-byte block transferred to host
+            let cdb = [
+                0x85, 0x08, 0x0E, 0x00, feature, 0x00, 0x01, 0x00,
+                0x00, 0x00, SMART_LBA_MID, 0x00, SMART_LBA_HIGH, 0x00, ATA_SMART_COMMAND, 0x00,
+            ];
+            let cdb_args: Vec<String> = cdb.iter().map(|b| format!("{:02x}", b)).collect();
+
+            let output = Command::new("sg_raw")
+                .args(["-r", &SMART_DATA_SIZE.to_string(), "-b", &self.device_path])
+                .args(&cdb_args)
+                .output()?;
+
+            if !output.status.success() || output.stdout.len() < SMART_DATA_SIZE {
+                return Err(io::Error::other(format!("SMART command 0x{:02X} failed: {}", feature, String::from_utf8_lossy(&output.stderr).trim())));
+            }
+
+            Ok(output.stdout)
+        }
+
+        #[cfg(not(unix))]
+        {
+            let _ = feature;
+            Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                "ATA pass-through is not available on this platform"
+            ))
+        }
+    }
+}
+
+/// Convenience wrapper: health for a device, `Unknown` when SMART cannot be read
+pub fn read_device_health(device_path: &str) -> DeviceHealth {
+    SmartInterface::new(device_path)
+        .read_health()
+        .unwrap_or_else(|_| DeviceHealth::unknown(device_path))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn attribute(data: &mut [u8], slot: usize, id: u8, flags: u16, current: u8, raw: u64) {
+        let entry = &mut data[2 + slot * SMART_ATTRIBUTE_SIZE..2 + (slot + 1) * SMART_ATTRIBUTE_SIZE];
+        entry[0] = id;
+        entry[1..3].copy_from_slice(&flags.to_le_bytes());
+        entry[3] = current;
+        entry[4] = current;
+        entry[5..11].copy_from_slice(&raw.to_le_bytes()[..6]);
+    }
+
+    #[test]
+    fn test_ata_smart_health() {
+        let mut data = vec![0u8; SMART_DATA_SIZE];
+        attribute(&mut data, 0, ATTR_REALLOCATED_SECTORS, 0x0033, 100, 8);
+        attribute(&mut data, 1, ATTR_POWER_ON_HOURS, 0x0032, 90, 12345);
+        attribute(&mut data, 2, ATTR_TEMPERATURE, 0x0022, 64, 0x0014_0000_0024);
+        attribute(&mut data, 3, ATTR_MEDIA_WEAROUT, 0x0032, 93, 0);
+
+        let attributes = parse_ata_smart_attributes(&data).unwrap();
+        let health = ata_health("/dev/sda", &attributes, &[(ATTR_REALLOCATED_SECTORS, 10)]);
+
+        assert_eq!(health.reallocated_sectors, Some(8));
+        assert_eq!(health.power_on_hours, Some(12345));
+        assert_eq!(health.temperature_celsius, Some(36));
+        assert_eq!(health.percent_used, Some(7));
+        assert_eq!(health.health_status, HealthStatus::Warning);
+
+        let failing = ata_health("/dev/sda", &attributes, &[(ATTR_REALLOCATED_SECTORS, 100)]);
+        assert_eq!(failing.health_status, HealthStatus::Failing);
+    }
+
+    #[test]
+    fn test_nvme_smart_log() {
+        let mut data = vec![0u8; SMART_DATA_SIZE];
+        data[1..3].copy_from_slice(&310u16.to_le_bytes());
+        data[3] = 100;
+        data[4] = 10;
+        data[5] = 3;
+        data[128..136].copy_from_slice(&4200u64.to_le_bytes());
+
+        let health = parse_nvme_smart_log("/dev/nvme0n1", &data).unwrap();
+        assert_eq!(health.health_status, HealthStatus::Good);
+        assert_eq!(health.temperature_celsius, Some(37));
+        assert_eq!(health.percent_used, Some(3));
+        assert_eq!(health.power_on_hours, Some(4200));
+
+        data[0] = NVME_WARN_RELIABILITY;
+        let health = parse_nvme_smart_log("/dev/nvme0n1", &data).unwrap();
+        assert_eq!(health.health_status, HealthStatus::Failing);
+    }
+
+    #[test]
+    fn test_self_test_status() {
+        let mut data = vec![0u8; SMART_DATA_SIZE];
+        assert_eq!(parse_ata_self_test_status(&data).unwrap(), SelfTestStatus::Passed);
+        data[SMART_SELF_TEST_STATUS_OFFSET] = 0xF7;
+        assert_eq!(parse_ata_self_test_status(&data).unwrap(), SelfTestStatus::InProgress { percent_complete: 30 });
+        data[SMART_SELF_TEST_STATUS_OFFSET] = 0x73;
+        assert_eq!(parse_ata_self_test_status(&data).unwrap(), SelfTestStatus::Failed("read element failed".to_string()));
+
+        let mut log = vec![0u8; NVME_SELF_TEST_LOG_SIZE];
+        log[0] = 0x01;
+        log[1] = 45;
+        assert_eq!(parse_nvme_self_test_log(&log).unwrap(), SelfTestStatus::InProgress { percent_complete: 45 });
+        log[0] = 0;
+        log[4] = 0x17;
+        assert!(matches!(parse_nvme_self_test_log(&log).unwrap(), SelfTestStatus::Failed(_)));
+        log[4] = 0x10;
+        assert_eq!(parse_nvme_self_test_log(&log).unwrap(), SelfTestStatus::Passed);
+    }
+}
@@ -0,0 +1,603 @@
+//! Interactive terminal interface for attended wipes
+//!
+//! `hdd-tool tui --config <file>` lists the attached drives, lets the
+//! operator pick drives and a compliance profile from the keyboard, and
+//! shows a progress bar, write speed and ETA for every running wipe above
+//! a log pane. Built with the `tui` feature.
+//!
+//! The engine reports on stdout while it wipes; on Unix that output is
+//! captured into the log pane so it does not tear the screen.
+
+use std::collections::VecDeque;
+use std::io::{self, Write};
+use std::sync::Arc;
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use chrono::Utc;
+use hdd_tool::core::{
+    default_target_type, ComplianceProfile, OperatorIdentity, SanitizationStandard, WipeEngine, WipeProgress, WipeRequest,
+    WipeResult, WipeResult2, WipeScope,
+};
+use hdd_tool::platform::PhysicalDisk;
+use ratatui::Terminal;
+use ratatui::backend::CrosstermBackend;
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEventKind};
+use ratatui::crossterm::execute;
+use ratatui::crossterm::terminal::{EnterAlternateScreen, LeaveAlternateScreen, disable_raw_mode, enable_raw_mode};
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::Line;
+use ratatui::widgets::{Block, Borders, Gauge, Paragraph, Row, Table, TableState};
+use ratatui::Frame;
+use uuid::Uuid;
+
+const LOG_LINES: usize = 200;
+const SECTOR_SIZE: f64 = 512.0;
+
+/// How a wipe thread reports back: the operation id and the engine's outcome
+type Outcome = (Uuid, WipeResult2<(WipeResult, Option<String>)>);
+
+pub fn run(args: &[String]) -> i32 {
+    const USAGE: &str = "❌ Usage: hdd-tool tui --config <file>\n   with HDD_TOOL_OPERATOR_TOKEN set, and HDD_TOOL_APPROVER_TOKEN when a second operator must approve";
+
+    let mut config_path = None;
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--config" => config_path = iter.next().cloned(),
+            _ => {
+                eprintln!("{}", USAGE);
+                return 2;
+            }
+        }
+    }
+    let operator_token = std::env::var("HDD_TOOL_OPERATOR_TOKEN").ok();
+    let (Some(config_path), Some(operator_token)) = (config_path, operator_token) else {
+        eprintln!("{}", USAGE);
+        return 2;
+    };
+
+    let engine = match crate::cli::load_engine(&config_path, &crate::cli::Output::text()) {
+        Ok(engine) => Arc::new(engine),
+        Err(code) => return code,
+    };
+    let operator = match engine.login_with_token(&operator_token) {
+        Ok(operator) => operator,
+        Err(e) => {
+            eprintln!("❌ {}", e);
+            return 1;
+        }
+    };
+
+    let (log_tx, log_rx) = mpsc::channel();
+    let screen = match capture_stdout(log_tx.clone()) {
+        Ok(screen) => screen,
+        Err(e) => {
+            eprintln!("❌ Failed to set up the terminal: {}", e);
+            return 1;
+        }
+    };
+    let mut app = App::new(engine, operator, log_tx, log_rx);
+    app.rescan();
+
+    let outcome = enable_raw_mode().and_then(|_| {
+        let mut screen = screen;
+        execute!(screen, EnterAlternateScreen)?;
+        let mut terminal = Terminal::new(CrosstermBackend::new(screen))?;
+        let outcome = app.run(&mut terminal);
+        execute!(terminal.backend_mut(), LeaveAlternateScreen)?;
+        terminal.show_cursor()?;
+        outcome
+    });
+    let _ = disable_raw_mode();
+    release_stdout();
+
+    match outcome {
+        Ok(()) => {
+            if app.jobs.iter().any(|job| job.state == JobState::Failed) { 1 } else { 0 }
+        }
+        Err(e) => {
+            eprintln!("❌ {}", e);
+            1
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum JobState {
+    Running,
+    Succeeded,
+    Failed,
+}
+
+/// One wipe started from this session
+struct Job {
+    id: Uuid,
+    device: String,
+    state: JobState,
+    progress: Option<WipeProgress>,
+    /// Smoothed write speed in bytes per second
+    speed: f64,
+    last_sample: Option<(Instant, u64)>,
+}
+
+impl Job {
+    fn record(&mut self, progress: WipeProgress) {
+        let now = Instant::now();
+        // Passes restart the sector count, so only forward steps are rated
+        if let Some((at, sectors)) = self.last_sample
+            && progress.sectors_processed > sectors
+        {
+            let elapsed = now.duration_since(at).as_secs_f64();
+            if elapsed > 0.0 {
+                let rate = (progress.sectors_processed - sectors) as f64 * SECTOR_SIZE / elapsed;
+                self.speed = if self.speed == 0.0 { rate } else { self.speed * 0.8 + rate * 0.2 };
+            }
+        }
+        self.last_sample = Some((now, progress.sectors_processed));
+        self.progress = Some(progress);
+    }
+
+    fn label(&self) -> String {
+        let Some(ref progress) = self.progress else {
+            return format!("{}  starting", self.device);
+        };
+        let mut label = format!(
+            "{}  {:.1}%  pass {}/{}  {}",
+            self.device, progress.percentage, progress.current_pass, progress.total_passes, progress.current_operation
+        );
+        match self.state {
+            JobState::Running => {
+                if self.speed > 0.0 {
+                    label.push_str(&format!("  {}/s", format_bytes(self.speed as u64)));
+                }
+                if let Some(eta) = progress.estimated_completion {
+                    let remaining = (eta - Utc::now()).num_seconds().max(0);
+                    label.push_str(&format!(
+                        "  ETA {:02}:{:02}:{:02}",
+                        remaining / 3600,
+                        remaining / 60 % 60,
+                        remaining % 60
+                    ));
+                }
+            }
+            JobState::Succeeded => label.push_str("  ✅ done"),
+            JobState::Failed => label.push_str("  ❌ failed"),
+        }
+        label
+    }
+}
+
+/// What keystrokes currently go to
+enum Mode {
+    Browse,
+    /// Typing the confirmation phrase for the first queued request
+    Confirm { phrase: String, input: String },
+}
+
+struct App {
+    engine: Arc<WipeEngine>,
+    operator: OperatorIdentity,
+    disks: Vec<PhysicalDisk>,
+    selected: Vec<bool>,
+    table: TableState,
+    profile: usize,
+    mode: Mode,
+    /// Requests waiting for their confirmation phrase
+    queue: VecDeque<WipeRequest>,
+    jobs: Vec<Job>,
+    log: VecDeque<String>,
+    log_tx: Sender<String>,
+    log_rx: Receiver<String>,
+    progress_rx: Receiver<WipeProgress>,
+    outcome_tx: Sender<Outcome>,
+    outcome_rx: Receiver<Outcome>,
+    quit_requested: bool,
+}
+
+impl App {
+    fn new(engine: Arc<WipeEngine>, operator: OperatorIdentity, log_tx: Sender<String>, log_rx: Receiver<String>) -> Self {
+        let profile = engine
+            .default_profile()
+            .and_then(|default| ComplianceProfile::ALL.iter().position(|profile| *profile == default))
+            .unwrap_or(0);
+        let progress_rx = engine.subscribe_progress();
+        let (outcome_tx, outcome_rx) = mpsc::channel();
+        Self {
+            engine,
+            operator,
+            disks: Vec::new(),
+            selected: Vec::new(),
+            table: TableState::default(),
+            profile,
+            mode: Mode::Browse,
+            queue: VecDeque::new(),
+            jobs: Vec::new(),
+            log: VecDeque::new(),
+            log_tx,
+            log_rx,
+            progress_rx,
+            outcome_tx,
+            outcome_rx,
+            quit_requested: false,
+        }
+    }
+
+    fn log(&mut self, line: String) {
+        if self.log.len() == LOG_LINES {
+            self.log.pop_front();
+        }
+        self.log.push_back(line);
+    }
+
+    fn rescan(&mut self) {
+        match self.engine.scan_devices_as(Some(&self.operator)) {
+            Ok(disks) => {
+                self.log(format!("Found {} drives", disks.len()));
+                self.selected = vec![false; disks.len()];
+                self.table.select(if disks.is_empty() { None } else { Some(0) });
+                self.disks = disks;
+            }
+            Err(e) => self.log(format!("❌ {}", e)),
+        }
+    }
+
+    fn run<W: Write>(&mut self, terminal: &mut Terminal<CrosstermBackend<W>>) -> io::Result<()> {
+        loop {
+            self.drain();
+            terminal.draw(|frame| self.draw(frame))?;
+            if !event::poll(Duration::from_millis(200))? {
+                continue;
+            }
+            if let Event::Key(key) = event::read()?
+                && key.kind == KeyEventKind::Press
+                && self.handle_key(key.code)
+            {
+                return Ok(());
+            }
+        }
+    }
+
+    /// Pick up progress, finished wipes and captured engine output
+    fn drain(&mut self) {
+        while let Ok(progress) = self.progress_rx.try_recv() {
+            if let Some(job) = self.jobs.iter_mut().find(|job| job.id == progress.request_id) {
+                job.record(progress);
+            }
+        }
+        while let Ok((id, outcome)) = self.outcome_rx.try_recv() {
+            let Some(index) = self.jobs.iter().position(|job| job.id == id) else {
+                continue;
+            };
+            let device = self.jobs[index].device.clone();
+            let line = match outcome {
+                Ok((result, certificate_path)) => {
+                    self.jobs[index].state = if result.success { JobState::Succeeded } else { JobState::Failed };
+                    match (result.success, certificate_path) {
+                        (true, Some(path)) => format!("✅ {} wiped; certificate {}", device, path),
+                        (true, None) => format!("✅ {} wiped", device),
+                        (false, _) => format!("❌ {}: {}", device, result.error_message.unwrap_or_default()),
+                    }
+                }
+                Err(e) => {
+                    self.jobs[index].state = JobState::Failed;
+                    format!("❌ {}: {}", device, e)
+                }
+            };
+            self.log(line);
+        }
+        while let Ok(line) = self.log_rx.try_recv() {
+            self.log(line);
+        }
+    }
+
+    /// Returns true when the interface should close
+    fn handle_key(&mut self, key: KeyCode) -> bool {
+        if let Mode::Confirm { ref mut input, .. } = self.mode {
+            match key {
+                KeyCode::Char(c) => input.push(c),
+                KeyCode::Backspace => {
+                    input.pop();
+                }
+                KeyCode::Enter => {
+                    let confirmation = input.clone();
+                    if let Some(mut request) = self.queue.pop_front() {
+                        request.confirmation = Some(confirmation);
+                        self.start(request);
+                    }
+                    self.next_confirmation();
+                }
+                KeyCode::Esc => {
+                    if let Some(request) = self.queue.pop_front() {
+                        self.log(format!("Skipped {}", request.target_path));
+                    }
+                    self.next_confirmation();
+                }
+                _ => {}
+            }
+            return false;
+        }
+
+        let row = self.table.selected();
+        match key {
+            KeyCode::Up => self.table.select(row.map(|row| row.saturating_sub(1))),
+            KeyCode::Down => {
+                if let Some(row) = row {
+                    self.table.select(Some((row + 1).min(self.disks.len().saturating_sub(1))));
+                }
+            }
+            KeyCode::Char(' ') => {
+                if let Some(row) = row {
+                    self.selected[row] = !self.selected[row];
+                }
+            }
+            KeyCode::Left => self.profile = (self.profile + ComplianceProfile::ALL.len() - 1) % ComplianceProfile::ALL.len(),
+            KeyCode::Right => self.profile = (self.profile + 1) % ComplianceProfile::ALL.len(),
+            KeyCode::Char('r') => self.rescan(),
+            KeyCode::Char('c') => self.cancel_highlighted(),
+            KeyCode::Enter => self.queue_selected(),
+            KeyCode::Char('q') | KeyCode::Esc => {
+                let running: Vec<Uuid> = self.jobs.iter().filter(|job| job.state == JobState::Running).map(|job| job.id).collect();
+                if running.is_empty() {
+                    return true;
+                }
+                if !self.quit_requested {
+                    self.quit_requested = true;
+                    self.log(format!("⚠️  {} wipes still running; press q again to cancel them and quit", running.len()));
+                    return false;
+                }
+                for id in running {
+                    let _ = self.engine.cancel_wipe_as(&self.operator, id);
+                }
+                return true;
+            }
+            _ => {}
+        }
+        self.quit_requested = false;
+        false
+    }
+
+    fn queue_selected(&mut self) {
+        let profile = ComplianceProfile::ALL[self.profile];
+        let busy: Vec<String> = self.jobs.iter().filter(|job| job.state == JobState::Running).map(|job| job.device.clone()).collect();
+        for (disk, _) in self.disks.iter().zip(&self.selected).filter(|(_, selected)| **selected) {
+            if busy.contains(&disk.path) {
+                continue;
+            }
+            let mut request = WipeEngine::build_request(
+                disk.path.clone(),
+                default_target_type(disk),
+                WipeScope::WholeDevice,
+                SanitizationStandard::NIST_SP_800_88_R1,
+                true,
+                true,
+            );
+            request.profile = Some(profile);
+            self.queue.push_back(request);
+        }
+        self.selected.iter_mut().for_each(|selected| *selected = false);
+        if self.queue.is_empty() {
+            self.log("Select drives with Space first".to_string());
+        }
+        self.next_confirmation();
+    }
+
+    /// Start queued requests until one needs its confirmation phrase typed
+    fn next_confirmation(&mut self) {
+        self.mode = Mode::Browse;
+        while let Some(request) = self.queue.front() {
+            if let Some(phrase) = self.engine.confirmation_phrase(request) {
+                self.mode = Mode::Confirm { phrase, input: String::new() };
+                return;
+            }
+            let request = self.queue.pop_front().unwrap();
+            self.start(request);
+        }
+    }
+
+    fn start(&mut self, request: WipeRequest) {
+        // A second operator at the same console approves with their own token
+        if let Ok(approver_token) = std::env::var("HDD_TOOL_APPROVER_TOKEN") {
+            let approved = self
+                .engine
+                .authenticate_token(&approver_token)
+                .and_then(|approver| self.engine.approve_operation(request.id, &approver));
+            if let Err(e) = approved {
+                self.log(format!("❌ {}: {}", request.target_path, e));
+                return;
+            }
+        }
+
+        self.log(format!("Starting {} on {}", ComplianceProfile::ALL[self.profile].name(), request.target_path));
+        self.jobs.push(Job {
+            id: request.id,
+            device: request.target_path.clone(),
+            state: JobState::Running,
+            progress: None,
+            speed: 0.0,
+            last_sample: None,
+        });
+        let engine = self.engine.clone();
+        let operator = self.operator.clone();
+        let outcome_tx = self.outcome_tx.clone();
+        let log_tx = self.log_tx.clone();
+        thread::spawn(move || {
+            let id = request.id;
+            let outcome = engine.execute_request_as(&operator, request);
+            if outcome_tx.send((id, outcome)).is_err() {
+                let _ = log_tx.send(format!("Wipe {} ended after the interface closed", id));
+            }
+        });
+    }
+
+    fn cancel_highlighted(&mut self) {
+        let Some(disk) = self.table.selected().and_then(|row| self.disks.get(row)) else {
+            return;
+        };
+        let running = self.jobs.iter().find(|job| job.device == disk.path && job.state == JobState::Running);
+        let line = match running {
+            Some(job) => match self.engine.cancel_wipe_as(&self.operator, job.id) {
+                Ok(()) => format!("Cancelling wipe of {}", job.device),
+                Err(e) => format!("❌ {}", e),
+            },
+            None => format!("No wipe running on {}", disk.path),
+        };
+        self.log(line);
+    }
+
+    fn draw(&mut self, frame: &mut Frame) {
+        let running = self.jobs.len().max(1) as u16;
+        let [help, devices, profile, jobs, log] = Layout::vertical([
+            Constraint::Length(1),
+            Constraint::Min(6),
+            Constraint::Length(1),
+            Constraint::Length(running + 2),
+            Constraint::Length(10),
+        ])
+        .areas(frame.area());
+
+        frame.render_widget(
+            Paragraph::new("↑/↓ move  Space select  ←/→ profile  Enter wipe  c cancel  r rescan  q quit")
+                .style(Style::default().add_modifier(Modifier::DIM)),
+            help,
+        );
+        self.draw_devices(frame, devices);
+
+        let current = ComplianceProfile::ALL[self.profile];
+        let profile_line = match self.mode {
+            Mode::Browse => format!("Profile: ◀ {} ▶  {}", current.name(), current.citation()),
+            Mode::Confirm { ref phrase, ref input } => format!(
+                "Wiping {}: type \"{}\" and press Enter (Esc skips): {}",
+                self.queue.front().map(|request| request.target_path.as_str()).unwrap_or_default(),
+                phrase,
+                input
+            ),
+        };
+        frame.render_widget(Paragraph::new(profile_line).style(Style::default().fg(Color::Yellow)), profile);
+
+        let block = Block::default().borders(Borders::ALL).title(" Wipes ");
+        let inner = block.inner(jobs);
+        frame.render_widget(block, jobs);
+        if self.jobs.is_empty() {
+            frame.render_widget(Paragraph::new("No wipes started"), inner);
+        }
+        for (index, job) in self.jobs.iter().enumerate() {
+            let area = Rect { y: inner.y + index as u16, height: 1, ..inner };
+            if area.y >= inner.bottom() {
+                break;
+            }
+            let ratio = job.progress.as_ref().map(|p| p.percentage / 100.0).unwrap_or(0.0).clamp(0.0, 1.0);
+            let color = match job.state {
+                JobState::Running => Color::Cyan,
+                JobState::Succeeded => Color::Green,
+                JobState::Failed => Color::Red,
+            };
+            frame.render_widget(Gauge::default().gauge_style(Style::default().fg(color)).ratio(ratio).label(job.label()), area);
+        }
+
+        let visible = log.height.saturating_sub(2) as usize;
+        let lines: Vec<Line> = self.log.iter().skip(self.log.len().saturating_sub(visible)).map(|line| Line::raw(line.as_str())).collect();
+        frame.render_widget(Paragraph::new(lines).block(Block::default().borders(Borders::ALL).title(" Log ")), log);
+    }
+
+    fn draw_devices(&mut self, frame: &mut Frame, area: Rect) {
+        let rows = self.disks.iter().zip(&self.selected).map(|(disk, selected)| {
+            Row::new(vec![
+                if *selected { "[x]" } else { "[ ]" }.to_string(),
+                disk.path.clone(),
+                disk.model.clone(),
+                disk.serial.clone(),
+                format_bytes(disk.size_bytes),
+                disk.bus_type.clone(),
+                if disk.is_root_disk { "system disk".to_string() } else { disk.kind.as_str().to_string() },
+            ])
+        });
+        let table = Table::new(
+            rows,
+            [
+                Constraint::Length(3),
+                Constraint::Length(20),
+                Constraint::Min(16),
+                Constraint::Length(20),
+                Constraint::Length(10),
+                Constraint::Length(6),
+                Constraint::Length(22),
+            ],
+        )
+        .header(Row::new(vec!["", "Device", "Model", "Serial", "Size", "Bus", "Kind"]).style(Style::default().add_modifier(Modifier::BOLD)))
+        .block(Block::default().borders(Borders::ALL).title(" Drives "))
+        .row_highlight_style(Style::default().add_modifier(Modifier::REVERSED));
+        frame.render_stateful_widget(table, area, &mut self.table);
+    }
+}
+
+fn format_bytes(bytes: u64) -> String {
+    const UNITS: &[&str] = &["B", "KB", "MB", "GB", "TB"];
+    let mut size = bytes as f64;
+    let mut unit_index = 0;
+
+    while size >= 1024.0 && unit_index < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit_index += 1;
+    }
+
+    format!("{:.1} {}", size, UNITS[unit_index])
+}
+
+/// Point stdout at a pipe feeding the log pane and hand back the terminal
+#[cfg(unix)]
+fn capture_stdout(log: Sender<String>) -> io::Result<std::fs::File> {
+    use std::io::BufRead;
+    use std::os::unix::io::FromRawFd;
+
+    io::stdout().flush()?;
+    let mut fds = [0; 2];
+    // SAFETY: plain descriptor juggling; every descriptor is checked before use
+    unsafe {
+        if libc::pipe(fds.as_mut_ptr()) != 0 {
+            return Err(io::Error::last_os_error());
+        }
+        let terminal = libc::dup(libc::STDOUT_FILENO);
+        if terminal < 0 || libc::dup2(fds[1], libc::STDOUT_FILENO) < 0 {
+            return Err(io::Error::last_os_error());
+        }
+        libc::close(fds[1]);
+        SAVED_STDOUT.store(terminal, std::sync::atomic::Ordering::SeqCst);
+
+        let pipe = std::fs::File::from_raw_fd(fds[0]);
+        thread::spawn(move || {
+            for line in io::BufReader::new(pipe).lines().map_while(Result::ok) {
+                if !line.trim().is_empty() && log.send(line).is_err() {
+                    break;
+                }
+            }
+        });
+        Ok(std::fs::File::from_raw_fd(libc::dup(terminal)))
+    }
+}
+
+#[cfg(unix)]
+static SAVED_STDOUT: std::sync::atomic::AtomicI32 = std::sync::atomic::AtomicI32::new(-1);
+
+#[cfg(unix)]
+fn release_stdout() {
+    let terminal = SAVED_STDOUT.swap(-1, std::sync::atomic::Ordering::SeqCst);
+    if terminal >= 0 {
+        let _ = io::stdout().flush();
+        // SAFETY: `terminal` is the descriptor saved by `capture_stdout`
+        unsafe {
+            libc::dup2(terminal, libc::STDOUT_FILENO);
+            libc::close(terminal);
+        }
+    }
+}
+
+#[cfg(not(unix))]
+fn capture_stdout(_log: Sender<String>) -> io::Result<io::Stdout> {
+    Ok(io::stdout())
+}
+
+#[cfg(not(unix))]
+fn release_stdout() {}
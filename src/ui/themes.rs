@@ -1,9 +1,48 @@
 use eframe::egui;
+use serde::{Deserialize, Serialize};
+
+/// Colour scheme the operator picked in Settings
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ThemeMode {
+    #[default]
+    Dark,
+    Light,
+    /// Black background, white text and yellow highlights
+    HighContrast,
+}
+
+impl ThemeMode {
+    pub const ALL: [ThemeMode; 3] = [ThemeMode::Dark, ThemeMode::Light, ThemeMode::HighContrast];
+
+    pub fn label(self) -> String {
+        match self {
+            ThemeMode::Dark => crate::t!("theme-dark"),
+            ThemeMode::Light => crate::t!("theme-light"),
+            ThemeMode::HighContrast => crate::t!("theme-high-contrast"),
+        }
+    }
+}
 
 pub struct SecureTheme;
 
 impl SecureTheme {
-    pub fn apply(ctx: &egui::Context) {
+    /// Apply `mode` and scale the whole UI by `ui_scale`
+    pub fn apply(ctx: &egui::Context, mode: ThemeMode, ui_scale: f32) {
+        let visuals = match mode {
+            ThemeMode::Dark => Self::dark(),
+            ThemeMode::Light => Self::light(),
+            ThemeMode::HighContrast => Self::high_contrast(),
+        };
+        ctx.set_visuals(visuals);
+
+        let ui_scale = ui_scale.clamp(0.5, 3.0);
+        if ctx.zoom_factor() != ui_scale {
+            ctx.set_zoom_factor(ui_scale);
+        }
+    }
+
+    fn dark() -> egui::Visuals {
         let mut visuals = egui::Visuals::dark();
         
         // SHREDX-inspired color scheme
@@ -22,13 +61,71 @@ impl SecureTheme {
         visuals.widgets.active.fg_stroke.color = egui::Color32::WHITE;
         
         // Table/grid colors
-        visuals.widgets.noninteractive.bg_fill = egui::Color32::from_rgb(30, 41, 59); // Table rows
+        visuals.widgets.noninteractive.bg_fill = Self::TABLE_ROW;                    // Table rows
+        visuals.faint_bg_color = Self::TABLE_ROW_ALT;                                 // Alternate table rows
         visuals.selection.bg_fill = egui::Color32::from_rgba_premultiplied(37, 99, 235, 100); // Selection color
         
         // Window borders
         visuals.window_stroke = egui::Stroke::new(1.0, egui::Color32::from_rgb(71, 85, 105));
         
-        ctx.set_visuals(visuals);
+        visuals
+    }
+
+    fn light() -> egui::Visuals {
+        let mut visuals = egui::Visuals::light();
+        
+        visuals.window_fill = egui::Color32::from_rgb(248, 250, 252);
+        visuals.panel_fill = egui::Color32::from_rgb(241, 245, 249);
+        visuals.extreme_bg_color = egui::Color32::WHITE;
+        
+        // Same blue buttons as the dark theme
+        visuals.widgets.inactive.bg_fill = Self::PRIMARY_BLUE;
+        visuals.widgets.hovered.bg_fill = Self::LIGHT_BLUE;
+        visuals.widgets.active.bg_fill = Self::DARK_BLUE;
+        visuals.widgets.inactive.fg_stroke.color = egui::Color32::WHITE;
+        visuals.widgets.hovered.fg_stroke.color = egui::Color32::WHITE;
+        visuals.widgets.active.fg_stroke.color = egui::Color32::WHITE;
+        
+        visuals.widgets.noninteractive.bg_fill = egui::Color32::from_rgb(226, 232, 240);
+        visuals.faint_bg_color = egui::Color32::from_rgb(241, 245, 249);
+        visuals.selection.bg_fill = egui::Color32::from_rgba_premultiplied(37, 99, 235, 60);
+        visuals.window_stroke = egui::Stroke::new(1.0, egui::Color32::from_rgb(148, 163, 184));
+        
+        visuals
+    }
+
+    fn high_contrast() -> egui::Visuals {
+        let mut visuals = egui::Visuals::dark();
+        let yellow = egui::Color32::from_rgb(255, 214, 0);
+        
+        visuals.widgets.noninteractive.fg_stroke = egui::Stroke::new(1.0, egui::Color32::WHITE);
+        visuals.widgets.inactive.fg_stroke = egui::Stroke::new(1.5, egui::Color32::WHITE);
+        visuals.window_fill = egui::Color32::BLACK;
+        visuals.panel_fill = egui::Color32::BLACK;
+        visuals.extreme_bg_color = egui::Color32::BLACK;
+        
+        // Black buttons outlined in white, yellow when hovered or pressed
+        visuals.widgets.inactive.bg_fill = egui::Color32::BLACK;
+        visuals.widgets.inactive.weak_bg_fill = egui::Color32::BLACK;
+        visuals.widgets.inactive.bg_stroke = egui::Stroke::new(2.0, egui::Color32::WHITE);
+        visuals.widgets.hovered.bg_fill = yellow;
+        visuals.widgets.hovered.weak_bg_fill = yellow;
+        visuals.widgets.hovered.bg_stroke = egui::Stroke::new(2.0, yellow);
+        visuals.widgets.active.bg_fill = yellow;
+        visuals.widgets.active.weak_bg_fill = yellow;
+        visuals.widgets.active.bg_stroke = egui::Stroke::new(2.0, egui::Color32::WHITE);
+        visuals.widgets.hovered.fg_stroke = egui::Stroke::new(2.0, egui::Color32::BLACK);
+        visuals.widgets.active.fg_stroke = egui::Stroke::new(2.0, egui::Color32::BLACK);
+        
+        visuals.widgets.noninteractive.bg_fill = egui::Color32::BLACK;
+        visuals.widgets.noninteractive.bg_stroke = egui::Stroke::new(1.0, egui::Color32::WHITE);
+        visuals.faint_bg_color = egui::Color32::from_gray(24);
+        visuals.selection.bg_fill = yellow;
+        visuals.selection.stroke = egui::Stroke::new(2.0, egui::Color32::BLACK);
+        visuals.hyperlink_color = yellow;
+        visuals.window_stroke = egui::Stroke::new(2.0, egui::Color32::WHITE);
+        
+        visuals
     }
     
     // Color constants for consistent usage
@@ -1,5 +1,8 @@
 use eframe::egui;
 use crate::ui::themes::SecureTheme;
+use crate::advanced_wiper::WipingProgress;
+use crate::partitions::PartitionTable;
+use crate::platform::PhysicalDisk;
 
 #[derive(Clone, Debug)]
 pub struct DriveInfo {
@@ -95,6 +98,53 @@ impl DriveInfo {
     }
 }
 
+impl DriveInfo {
+    /// Show the progress an eraser reports for this drive. Erasers count bytes
+    /// per pass; the row counts them across all passes.
+    pub fn update_from_wipe(&mut self, wipe: &WipingProgress) {
+        if wipe.total_bytes == 0 {
+            // Still analysing the device, or a firmware erase with no progress to report
+            self.status = wipe.current_pattern.clone();
+            return;
+        }
+        let passes = wipe.total_passes.max(1) as u64;
+        let pass = wipe.current_pass.clamp(1, passes as u32) as u64;
+        self.bytes_total = wipe.total_bytes * passes;
+        self.bytes_processed = ((pass - 1) * wipe.total_bytes + wipe.bytes_processed.min(wipe.total_bytes)).min(self.bytes_total);
+        self.progress = self.bytes_processed as f32 / self.bytes_total as f32;
+        self.status = format!("Pass {}/{}: {}", pass, passes, wipe.current_pattern);
+        self.last_update = Some(std::time::Instant::now());
+
+        self.speed = if wipe.speed_mbps > 0.0 { format!("{:.1} MB/s", wipe.speed_mbps) } else { "-".to_string() };
+        let remaining = self.bytes_total - self.bytes_processed;
+        self.time_left = if !wipe.estimated_time_remaining.is_zero() {
+            format_duration(wipe.estimated_time_remaining.as_secs_f64())
+        } else if wipe.speed_mbps > 0.0 {
+            format_duration(remaining as f64 / (wipe.speed_mbps * 1024.0 * 1024.0))
+        } else {
+            "Calculating...".to_string()
+        };
+    }
+
+    /// The worker thread has ended; `error` is why the wipe failed
+    pub fn finish_wipe(&mut self, error: Option<&str>) {
+        self.last_update = Some(std::time::Instant::now());
+        match error {
+            None => {
+                self.bytes_processed = self.bytes_total;
+                self.progress = 1.0;
+                self.time_left = "Complete".to_string();
+                self.status = "Complete".to_string();
+            }
+            Some(error) => {
+                self.time_left = "-".to_string();
+                self.speed = "-".to_string();
+                self.status = format!("Failed: {}", error);
+            }
+        }
+    }
+}
+
 fn format_duration(seconds: f64) -> String {
     let total_seconds = seconds as u64;
     let hours = total_seconds / 3600;
@@ -163,6 +213,8 @@ impl TabWidget {
 pub struct DriveTableWidget {
     pub drives: Vec<DriveInfo>,
     pub select_all: bool,
+    /// Taller rows and progress bars with large percentages
+    pub large_progress_text: bool,
 }
 
 impl DriveTableWidget {
@@ -170,6 +222,7 @@ impl DriveTableWidget {
         Self {
             drives: Vec::new(),
             select_all: false,
+            large_progress_text: false,
         }
     }
     
@@ -180,13 +233,18 @@ impl DriveTableWidget {
     pub fn show(&mut self, ui: &mut egui::Ui) {
         // Header
         ui.horizontal(|ui| {
-            ui.label("DRIVES");
+            ui.label(crate::t!("table-drives"));
         });
         
         ui.add_space(10.0);
         
         // Define column widths for consistent alignment
-        let col_widths = [60.0, 100.0, 80.0, 80.0, 80.0, 100.0, 80.0, 80.0];
+        let col_widths = [60.0, 100.0, 80.0, 80.0, 80.0, 100.0, 80.0, 80.0, 140.0];
+        let (row_height, bar_height, percent_size) = if self.large_progress_text {
+            (48.0, 28.0, 20.0)
+        } else {
+            (30.0, 12.0, 9.0)
+        };
         
         // Column headers with fixed widths
         ui.horizontal(|ui| {
@@ -194,56 +252,63 @@ impl DriveTableWidget {
             ui.allocate_ui_with_layout(
                 egui::vec2(col_widths[0], 20.0),
                 egui::Layout::centered_and_justified(egui::Direction::LeftToRight),
-                |ui| { ui.label("Select"); }
+                |ui| { ui.label(crate::t!("table-select")); }
             );
             
             // Drive name column header
             ui.allocate_ui_with_layout(
                 egui::vec2(col_widths[1], 20.0),
                 egui::Layout::centered_and_justified(egui::Direction::LeftToRight),
-                |ui| { ui.label("Drive name"); }
+                |ui| { ui.label(crate::t!("table-drive-name")); }
             );
             
             // Drive path column header
             ui.allocate_ui_with_layout(
                 egui::vec2(col_widths[2], 20.0),
                 egui::Layout::centered_and_justified(egui::Direction::LeftToRight),
-                |ui| { ui.label("Drive path"); }
+                |ui| { ui.label(crate::t!("table-drive-path")); }
             );
             
             // Size column header
             ui.allocate_ui_with_layout(
                 egui::vec2(col_widths[3], 20.0),
                 egui::Layout::centered_and_justified(egui::Direction::LeftToRight),
-                |ui| { ui.label("Size"); }
+                |ui| { ui.label(crate::t!("table-size")); }
             );
             
             // Used column header
             ui.allocate_ui_with_layout(
                 egui::vec2(col_widths[4], 20.0),
                 egui::Layout::centered_and_justified(egui::Direction::LeftToRight),
-                |ui| { ui.label("Used"); }
+                |ui| { ui.label(crate::t!("table-used")); }
             );
             
             // Progress column header
             ui.allocate_ui_with_layout(
                 egui::vec2(col_widths[5], 20.0),
                 egui::Layout::centered_and_justified(egui::Direction::LeftToRight),
-                |ui| { ui.label("Progress"); }
+                |ui| { ui.label(crate::t!("table-progress")); }
             );
             
             // Time left column header
             ui.allocate_ui_with_layout(
                 egui::vec2(col_widths[6], 20.0),
                 egui::Layout::centered_and_justified(egui::Direction::LeftToRight),
-                |ui| { ui.label("Time left"); }
+                |ui| { ui.label(crate::t!("table-time-left")); }
             );
             
             // Speed column header
             ui.allocate_ui_with_layout(
                 egui::vec2(col_widths[7], 20.0),
                 egui::Layout::centered_and_justified(egui::Direction::LeftToRight),
-                |ui| { ui.label("Speed"); }
+                |ui| { ui.label(crate::t!("table-speed")); }
+            );
+            
+            // Status column header
+            ui.allocate_ui_with_layout(
+                egui::vec2(col_widths[8], 20.0),
+                egui::Layout::centered_and_justified(egui::Direction::LeftToRight),
+                |ui| { ui.label(crate::t!("table-status")); }
             );
         });
             
@@ -253,13 +318,13 @@ impl DriveTableWidget {
         let mut rows_to_update = Vec::new();
         for (i, drive) in self.drives.iter().enumerate() {
             let row_bg = if i % 2 == 0 { 
-                SecureTheme::TABLE_ROW 
+                ui.visuals().widgets.noninteractive.bg_fill 
             } else { 
-                SecureTheme::TABLE_ROW_ALT 
+                ui.visuals().faint_bg_color 
             };
             
             let response = ui.allocate_response(
-                egui::vec2(ui.available_width(), 30.0),
+                egui::vec2(ui.available_width(), row_height),
                 egui::Sense::hover()
             );
             
@@ -282,7 +347,7 @@ impl DriveTableWidget {
                 ui.horizontal(|ui| {
                     // Select column
                     ui.allocate_ui_with_layout(
-                        egui::vec2(col_widths[0], 25.0),
+                        egui::vec2(col_widths[0], row_height - 5.0),
                         egui::Layout::centered_and_justified(egui::Direction::LeftToRight),
                         |ui| {
                             let mut selected = drive.selected;
@@ -294,35 +359,35 @@ impl DriveTableWidget {
                     
                     // Drive name column
                     ui.allocate_ui_with_layout(
-                        egui::vec2(col_widths[1], 25.0),
+                        egui::vec2(col_widths[1], row_height - 5.0),
                         egui::Layout::centered_and_justified(egui::Direction::LeftToRight),
                         |ui| { ui.label(&drive.name); }
                     );
                     
                     // Drive path column
                     ui.allocate_ui_with_layout(
-                        egui::vec2(col_widths[2], 25.0),
+                        egui::vec2(col_widths[2], row_height - 5.0),
                         egui::Layout::centered_and_justified(egui::Direction::LeftToRight),
                         |ui| { ui.label(&drive.path); }
                     );
                     
                     // Size column
                     ui.allocate_ui_with_layout(
-                        egui::vec2(col_widths[3], 25.0),
+                        egui::vec2(col_widths[3], row_height - 5.0),
                         egui::Layout::centered_and_justified(egui::Direction::LeftToRight),
                         |ui| { ui.label(&drive.size); }
                     );
                     
                     // Used column
                     ui.allocate_ui_with_layout(
-                        egui::vec2(col_widths[4], 25.0),
+                        egui::vec2(col_widths[4], row_height - 5.0),
                         egui::Layout::centered_and_justified(egui::Direction::LeftToRight),
                         |ui| { ui.label(&drive.used); }
                     );
                     
                     // Progress column
                     ui.allocate_ui_with_layout(
-                        egui::vec2(col_widths[5], 25.0),
+                        egui::vec2(col_widths[5], row_height - 5.0),
                         egui::Layout::centered_and_justified(egui::Direction::LeftToRight),
                         |ui| {
                             if drive.progress > 0.0 {
@@ -331,7 +396,7 @@ impl DriveTableWidget {
                                     // Progress bar with percentage overlay
                                     let progress_bar = egui::ProgressBar::new(drive.progress)
                                         .desired_width(col_widths[5] - 20.0)
-                                        .desired_height(12.0)
+                                        .desired_height(bar_height)
                                         .fill(SecureTheme::LIGHT_BLUE)
                                         .rounding(egui::Rounding::same(4.0));
                                     
@@ -339,7 +404,7 @@ impl DriveTableWidget {
                                     
                                     // Overlay percentage text on progress bar
                                     let text = format!("{}%", percentage);
-                                    let font_id = egui::FontId::monospace(9.0);
+                                    let font_id = egui::FontId::monospace(percent_size);
                                     let text_galley = ui.painter().layout_no_wrap(
                                         text,
                                         font_id,
@@ -361,17 +426,26 @@ impl DriveTableWidget {
                     
                     // Time left column
                     ui.allocate_ui_with_layout(
-                        egui::vec2(col_widths[6], 25.0),
+                        egui::vec2(col_widths[6], row_height - 5.0),
                         egui::Layout::centered_and_justified(egui::Direction::LeftToRight),
                         |ui| { ui.label(&drive.time_left); }
                     );
                     
                     // Speed column
                     ui.allocate_ui_with_layout(
-                        egui::vec2(col_widths[7], 25.0),
+                        egui::vec2(col_widths[7], row_height - 5.0),
                         egui::Layout::centered_and_justified(egui::Direction::LeftToRight),
                         |ui| { ui.label(&drive.speed); }
                     );
+                    
+                    // Status column: queued, running pass, complete or failed
+                    ui.allocate_ui_with_layout(
+                        egui::vec2(col_widths[8], row_height - 5.0),
+                        egui::Layout::centered_and_justified(egui::Direction::LeftToRight),
+                        |ui| {
+                            ui.add(egui::Label::new(&drive.status).truncate()).on_hover_text(&drive.status);
+                        }
+                    );
                 });
             });
         }
@@ -398,10 +472,202 @@ impl DriveTableWidget {
     }
 }
 
+/// Something the disk view can send to sanitization: a whole disk or one of its volumes
+#[derive(Clone, Debug, PartialEq)]
+pub enum WipeTarget {
+    Disk(String),
+    Volume(String),
+}
+
+impl WipeTarget {
+    pub fn path(&self) -> &str {
+        match self {
+            WipeTarget::Disk(path) | WipeTarget::Volume(path) => path,
+        }
+    }
+}
+
+/// A physical disk with the partition table read from it, when readable
+#[derive(Clone, Debug)]
+pub struct DiskNode {
+    pub disk: PhysicalDisk,
+    pub partitions: Option<PartitionTable>,
+}
+
+/// Physical disks with their partitions and volumes as a tree
+pub struct DiskTreeWidget {
+    pub disks: Vec<DiskNode>,
+    pub selected: Vec<WipeTarget>,
+}
+
+impl DiskTreeWidget {
+    pub fn new() -> Self {
+        Self {
+            disks: Vec::new(),
+            selected: Vec::new(),
+        }
+    }
+    
+    /// Replace the disks, keeping selections that still exist
+    pub fn set_disks(&mut self, disks: Vec<DiskNode>) {
+        self.selected.retain(|target| disks.iter().any(|node| match target {
+            WipeTarget::Disk(path) => node.disk.path == *path,
+            WipeTarget::Volume(path) => node.disk.volumes.contains(path),
+        }));
+        self.disks = disks;
+    }
+    
+    fn toggle(&mut self, target: WipeTarget, selected: bool) {
+        self.selected.retain(|t| *t != target);
+        if selected {
+            self.selected.push(target);
+        }
+    }
+    
+    /// `rows` are the sanitization rows, matched to targets by path for progress
+    pub fn show(&mut self, ui: &mut egui::Ui, rows: &[DriveInfo]) {
+        ui.horizontal(|ui| {
+            ui.label("PHYSICAL DISKS");
+        });
+        
+        ui.add_space(10.0);
+        
+        if self.disks.is_empty() {
+            ui.label("No physical disks detected.");
+            return;
+        }
+        
+        let mut toggled = Vec::new();
+        for node in &self.disks {
+            let disk = &node.disk;
+            let disk_target = WipeTarget::Disk(disk.path.clone());
+            
+            ui.group(|ui| {
+                ui.horizontal(|ui| {
+                    let mut selected = self.selected.contains(&disk_target);
+                    if ui.checkbox(&mut selected, "").changed() {
+                        toggled.push((disk_target.clone(), selected));
+                    }
+                    ui.strong(disk.description());
+                    ui.label(&disk.path);
+                    ui.label(format_size(disk.size_bytes));
+                    if disk.is_root_disk {
+                        ui.colored_label(SecureTheme::DANGER_RED, "System disk");
+                    }
+                    show_target_progress(ui, rows, &disk.path);
+                });
+                
+                ui.horizontal(|ui| {
+                    ui.label(format!("Type: {}", disk.kind.as_str()));
+                    if let (Some(controller), Some(namespace_id)) = (&disk.controller, disk.namespace_id) {
+                        ui.label(format!("Namespace {} of {}", namespace_id, controller));
+                    }
+                    ui.label(format!("Bus: {}", disk.bus_type));
+                    ui.label(format!("Serial: {}", if disk.serial.is_empty() { "-" } else { disk.serial.as_str() }));
+                    ui.label(format!("Firmware: {}", if disk.firmware.is_empty() { "-" } else { disk.firmware.as_str() }));
+                });
+                
+                let capabilities = &disk.capabilities;
+                let mut features = Vec::new();
+                if disk.removable {
+                    features.push("Removable".to_string());
+                }
+                if capabilities.discard {
+                    features.push("TRIM/discard".to_string());
+                }
+                if capabilities.write_zeroes {
+                    features.push("Write zeroes".to_string());
+                }
+                if let Some(ssc) = &capabilities.opal {
+                    features.push(format!("Self-encrypting ({})", ssc.as_str()));
+                }
+                if capabilities.logical_block_size > 0 {
+                    features.push(format!("{}/{} B sectors", capabilities.logical_block_size, capabilities.physical_block_size));
+                }
+                if !features.is_empty() {
+                    ui.label(format!("Capabilities: {}", features.join(", ")));
+                }
+                
+                egui::CollapsingHeader::new(format!("Partitions and volumes ({})", disk.volumes.len()))
+                    .id_salt(&disk.path)
+                    .default_open(true)
+                    .show(ui, |ui| {
+                        match &node.partitions {
+                            Some(table) => {
+                                for partition in &table.partitions {
+                                    let (_, length) = partition.byte_range(table.sector_size);
+                                    ui.horizontal(|ui| {
+                                        ui.label(format!("Partition {}", partition.number));
+                                        if !partition.name.is_empty() {
+                                            ui.label(&partition.name);
+                                        }
+                                        ui.label(format_size(length));
+                                        if partition.is_system() {
+                                            ui.colored_label(SecureTheme::WARNING_ORANGE, "System partition");
+                                        }
+                                    });
+                                }
+                            }
+                            None => {
+                                ui.label("Partition table not readable (run elevated) or not present");
+                            }
+                        }
+                        
+                        for volume in &disk.volumes {
+                            let volume_target = WipeTarget::Volume(volume.clone());
+                            ui.horizontal(|ui| {
+                                let mut selected = self.selected.contains(&volume_target);
+                                if ui.checkbox(&mut selected, "").changed() {
+                                    toggled.push((volume_target.clone(), selected));
+                                }
+                                ui.label(format!("Volume {}", volume));
+                                show_target_progress(ui, rows, volume);
+                            });
+                        }
+                    });
+            });
+            ui.add_space(5.0);
+        }
+        
+        for (target, selected) in toggled {
+            self.toggle(target, selected);
+        }
+    }
+}
+
+impl Default for DiskTreeWidget {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn show_target_progress(ui: &mut egui::Ui, rows: &[DriveInfo], path: &str) {
+    if let Some(row) = rows.iter().find(|row| row.path == path && row.start_time.is_some()) {
+        ui.add(egui::ProgressBar::new(row.progress)
+            .desired_width(120.0)
+            .fill(SecureTheme::LIGHT_BLUE)
+            .show_percentage());
+        ui.label(&row.status);
+    }
+}
+
+fn format_size(bytes: u64) -> String {
+    const UNITS: &[&str] = &["B", "KB", "MB", "GB", "TB"];
+    let mut size = bytes as f64;
+    let mut unit_index = 0;
+    while size >= 1000.0 && unit_index < UNITS.len() - 1 {
+        size /= 1000.0;
+        unit_index += 1;
+    }
+    format!("{:.1} {}", size, UNITS[unit_index])
+}
+
 pub struct AdvancedOptionsWidget {
     pub eraser_method: String,
     pub verification: String,
     pub confirm_erase: bool,
+    /// How long the selected drives should take, shown before confirming
+    pub estimate: Option<String>,
 }
 
 impl AdvancedOptionsWidget {
@@ -410,6 +676,7 @@ impl AdvancedOptionsWidget {
             eraser_method: "NIST SP 800-88 and DoD 5220.22-M".to_string(),
             verification: "json".to_string(),
             confirm_erase: false,
+            estimate: None,
         }
     }
     
@@ -459,6 +726,10 @@ impl AdvancedOptionsWidget {
         
         // Confirmation checkbox first, then erase button
         ui.vertical_centered(|ui| {
+            if let Some(estimate) = &self.estimate {
+                ui.label(crate::t!("gui-estimated-duration", duration = estimate.as_str()));
+                ui.add_space(5.0);
+            }
             ui.checkbox(&mut self.confirm_erase, "✅ Confirm to erase the data");
             
             ui.add_space(10.0);
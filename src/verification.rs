@@ -0,0 +1,425 @@
+//! Post-wipe verification
+//!
+//! Reads back the wiped area according to a `VerificationPolicy` and checks it
+//! against the content the last pass should have left behind. The result is a
+//! `VerificationReport` with per-region outcomes and read throughput, suitable
+//! for attaching to an erasure certificate.
+
+use std::fs::File;
+use std::io::{self, Read, Seek, SeekFrom};
+use std::time::Instant;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use crate::bad_sectors::BadSectorLog;
+use crate::pattern_stream::PatternStream;
+
+/// Size of each sampled block for the sampling policies
+pub const SAMPLE_SIZE: u64 = 1024 * 1024;
+/// Number of equal regions the device is split into for stratified sampling
+pub const STRATIFIED_REGIONS: u64 = 256;
+/// Region size used to report a full scan
+const FULL_SCAN_REGION_SIZE: u64 = 1024 * 1024 * 1024;
+const READ_BLOCK_SIZE: u64 = 1024 * 1024;
+const SECTOR_SIZE: u64 = 512;
+/// Random data scores close to 8 bits per byte; anything lower has structure
+const MIN_RANDOM_ENTROPY: f64 = 7.9;
+/// Cap on the failed sector list so a wholly unwiped drive doesn't produce a huge report
+const MAX_REPORTED_FAILED_SECTORS: usize = 1000;
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub enum VerificationPolicy {
+    /// Read back every sector
+    FullScan,
+    /// Read randomly placed blocks covering `coverage` (0.0-1.0) of the device
+    RandomSample { coverage: f64 },
+    /// Read one block at a random offset in each of `STRATIFIED_REGIONS` equal regions
+    #[default]
+    StratifiedSample,
+    /// Stratified sampling judged on entropy instead of content, for random or
+    /// cryptographically erased media
+    EntropyCheck,
+}
+
+impl VerificationPolicy {
+    pub fn description(&self) -> String {
+        match self {
+            VerificationPolicy::FullScan => "Full scan".to_string(),
+            VerificationPolicy::RandomSample { coverage } => format!("Random sample ({:.2}%)", coverage * 100.0),
+            VerificationPolicy::StratifiedSample => format!("Stratified sample ({} regions)", STRATIFIED_REGIONS),
+            VerificationPolicy::EntropyCheck => format!("Entropy check ({} regions)", STRATIFIED_REGIONS),
+        }
+    }
+}
+
+/// What the wiped area is expected to contain
+#[derive(Debug, Clone, PartialEq)]
+pub enum ExpectedContent {
+    /// A repeating pattern, aligned to the start of the verified area
+    Pattern(Vec<u8>),
+    /// The same byte everywhere, either 0x00 or 0xFF (vendor dependent block erase)
+    UniformFill,
+    /// Random data; only entropy can be checked
+    Random,
+    /// A seeded pattern stream, positioned at the start of the verified area
+    Stream(PatternStream),
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RegionResult {
+    pub start_sector: u64,
+    pub sector_count: u64,
+    pub bytes_read: u64,
+    /// Bytes not read because the sectors were skipped as bad during the wipe
+    pub skipped_bytes: u64,
+    pub mismatched_bytes: u64,
+    /// Shannon entropy of the data read, in bits per byte
+    pub entropy: f64,
+    pub passed: bool,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VerificationReport {
+    pub policy: VerificationPolicy,
+    pub total_bytes: u64,
+    pub bytes_verified: u64,
+    /// Fraction of the device actually read back
+    pub coverage: f64,
+    pub duration_seconds: f64,
+    pub throughput_mbps: f64,
+    pub passed: bool,
+    pub failed_sectors: Vec<u64>,
+    pub regions: Vec<RegionResult>,
+}
+
+impl VerificationReport {
+    pub fn failed_regions(&self) -> impl Iterator<Item = &RegionResult> {
+        self.regions.iter().filter(|r| !r.passed)
+    }
+}
+
+/// Bytes the policy reads back from an area of `total_bytes`, without planning
+/// every random sample
+pub fn planned_bytes(total_bytes: u64, policy: &VerificationPolicy) -> u64 {
+    match policy {
+        VerificationPolicy::RandomSample { coverage } => (total_bytes as f64 * coverage.clamp(0.0, 1.0)) as u64,
+        _ => plan_regions(total_bytes, policy).iter().map(|&(_, len)| len).sum(),
+    }
+}
+
+/// Byte ranges `(offset, length)` relative to the verified area that the policy reads
+pub fn plan_regions(total_bytes: u64, policy: &VerificationPolicy) -> Vec<(u64, u64)> {
+    if total_bytes == 0 {
+        return Vec::new();
+    }
+
+    match policy {
+        VerificationPolicy::FullScan => (0..total_bytes)
+            .step_by(FULL_SCAN_REGION_SIZE as usize)
+            .map(|offset| (offset, FULL_SCAN_REGION_SIZE.min(total_bytes - offset)))
+            .collect(),
+        VerificationPolicy::RandomSample { coverage } => {
+            let slots = total_bytes.div_ceil(SAMPLE_SIZE).max(1);
+            let samples = ((slots as f64 * coverage.clamp(0.0, 1.0)).ceil() as u64).clamp(1, slots);
+
+            let mut rng = rand::thread_rng();
+            let mut chosen: Vec<u64> = if samples * 2 > slots {
+                // Dense sampling: pick slots to drop instead of slots to keep
+                let mut all: Vec<u64> = (0..slots).collect();
+                for _ in 0..(slots - samples) {
+                    let index = rng.gen_range(0..all.len());
+                    all.swap_remove(index);
+                }
+                all
+            } else {
+                let mut picked = std::collections::BTreeSet::new();
+                while (picked.len() as u64) < samples {
+                    picked.insert(rng.gen_range(0..slots));
+                }
+                picked.into_iter().collect()
+            };
+            chosen.sort_unstable();
+
+            chosen
+                .into_iter()
+                .map(|slot| {
+                    let offset = slot * SAMPLE_SIZE;
+                    (offset, SAMPLE_SIZE.min(total_bytes - offset))
+                })
+                .collect()
+        }
+        VerificationPolicy::StratifiedSample | VerificationPolicy::EntropyCheck => {
+            let regions = STRATIFIED_REGIONS.min(total_bytes.div_ceil(SAMPLE_SIZE)).max(1);
+            let region_size = total_bytes / regions;
+            let mut rng = rand::thread_rng();
+
+            (0..regions)
+                .map(|index| {
+                    let region_start = index * region_size;
+                    let region_end = if index == regions - 1 { total_bytes } else { region_start + region_size };
+                    let length = SAMPLE_SIZE.min(region_end - region_start);
+                    // Sector-aligned random offset inside the region
+                    let slack = (region_end - region_start - length) / SECTOR_SIZE;
+                    let offset = region_start + if slack > 0 { rng.gen_range(0..=slack) * SECTOR_SIZE } else { 0 };
+                    (offset, length)
+                })
+                .collect()
+        }
+    }
+}
+
+/// Write back and drop the cached pages of a range so the reads that follow
+/// come from the medium rather than the page cache. Needs a writable handle.
+/// Windows does not cache raw disk handles, so there this only flushes.
+pub fn evict_cached(device: &File, offset: u64, length: u64) -> io::Result<()> {
+    device.sync_data()?;
+
+    #[cfg(target_os = "linux")]
+    {
+        use std::os::unix::io::AsRawFd;
+        let result = unsafe {
+            libc::posix_fadvise(device.as_raw_fd(), offset as libc::off_t, length as libc::off_t, libc::POSIX_FADV_DONTNEED)
+        };
+        if result != 0 {
+            return Err(io::Error::from_raw_os_error(result));
+        }
+    }
+    #[cfg(not(target_os = "linux"))]
+    let _ = (offset, length);
+
+    Ok(())
+}
+
+/// Shannon entropy in bits per byte
+pub fn shannon_entropy(histogram: &[u64; 256]) -> f64 {
+    let total: u64 = histogram.iter().sum();
+    if total == 0 {
+        return 0.0;
+    }
+
+    histogram
+        .iter()
+        .filter(|&&count| count > 0)
+        .map(|&count| {
+            let p = count as f64 / total as f64;
+            -p * p.log2()
+        })
+        .sum()
+}
+
+/// Verify `length` bytes starting at `start_offset`. Sectors in `bad_sectors` are
+/// not read. `on_progress` receives bytes read so far and bytes planned.
+pub fn verify_device<D: Read + Seek>(
+    device: &mut D,
+    start_offset: u64,
+    length: u64,
+    expected: &ExpectedContent,
+    policy: &VerificationPolicy,
+    bad_sectors: &BadSectorLog,
+    mut on_progress: impl FnMut(u64, u64),
+) -> io::Result<VerificationReport> {
+    let started = Instant::now();
+    let plan = plan_regions(length, policy);
+    let planned_bytes: u64 = plan.iter().map(|&(_, len)| len).sum();
+    // A seeded stream is compared byte for byte under every policy: random
+    // data from any other source passes an entropy check just as well
+    let entropy_mode = match expected {
+        ExpectedContent::Random => true,
+        ExpectedContent::Stream(_) => false,
+        _ => *policy == VerificationPolicy::EntropyCheck,
+    };
+
+    let mut buffer = vec![0u8; READ_BLOCK_SIZE as usize];
+    let mut uniform_byte: Option<u8> = None;
+    let mut failed_sectors = Vec::new();
+    let mut regions = Vec::with_capacity(plan.len());
+    let mut bytes_verified = 0u64;
+    let mut bytes_done = 0u64;
+
+    for (region_offset, region_length) in plan {
+        let mut region = RegionResult {
+            start_sector: (start_offset + region_offset) / SECTOR_SIZE,
+            sector_count: region_length.div_ceil(SECTOR_SIZE),
+            ..Default::default()
+        };
+        let mut histogram = [0u64; 256];
+        let mut position = 0u64;
+
+        while position < region_length {
+            let read_size = READ_BLOCK_SIZE.min(region_length - position);
+            let relative_offset = region_offset + position;
+            let absolute_offset = start_offset + relative_offset;
+            position += read_size;
+            bytes_done += read_size;
+
+            // Skipped sectors were never overwritten and may not be readable
+            if bad_sectors.overlaps(absolute_offset / SECTOR_SIZE, read_size.div_ceil(SECTOR_SIZE)) {
+                region.skipped_bytes += read_size;
+                continue;
+            }
+
+            let data = &mut buffer[..read_size as usize];
+            if let Err(e) = device.seek(SeekFrom::Start(absolute_offset)).and_then(|_| device.read_exact(data)) {
+                region.error = Some(format!("Read failed at sector {}: {}", absolute_offset / SECTOR_SIZE, e));
+                break;
+            }
+
+            for &byte in data.iter() {
+                histogram[byte as usize] += 1;
+            }
+
+            if !entropy_mode {
+                for (index, sector) in data.chunks(SECTOR_SIZE as usize).enumerate() {
+                    let sector_offset = relative_offset + index as u64 * SECTOR_SIZE;
+                    let mismatches = count_mismatches(expected, sector_offset, sector, &mut uniform_byte);
+                    if mismatches > 0 {
+                        region.mismatched_bytes += mismatches;
+                        if failed_sectors.len() < MAX_REPORTED_FAILED_SECTORS {
+                            failed_sectors.push((start_offset + sector_offset) / SECTOR_SIZE);
+                        }
+                    }
+                }
+            }
+
+            region.bytes_read += read_size;
+            bytes_verified += read_size;
+            on_progress(bytes_done, planned_bytes);
+        }
+
+        region.entropy = shannon_entropy(&histogram);
+        region.passed = region.error.is_none() && if entropy_mode {
+            region.bytes_read == 0 || region.entropy >= MIN_RANDOM_ENTROPY
+        } else {
+            region.mismatched_bytes == 0
+        };
+        regions.push(region);
+    }
+
+    let duration_seconds = started.elapsed().as_secs_f64();
+    let passed = regions.iter().all(|r| r.passed);
+
+    Ok(VerificationReport {
+        policy: policy.clone(),
+        total_bytes: length,
+        bytes_verified,
+        coverage: if length == 0 { 0.0 } else { bytes_verified as f64 / length as f64 },
+        duration_seconds,
+        throughput_mbps: if duration_seconds > 0.0 {
+            bytes_verified as f64 / (1024.0 * 1024.0) / duration_seconds
+        } else {
+            0.0
+        },
+        passed,
+        failed_sectors,
+        regions,
+    })
+}
+
+fn count_mismatches(expected: &ExpectedContent, offset: u64, data: &[u8], uniform_byte: &mut Option<u8>) -> u64 {
+    match expected {
+        ExpectedContent::Pattern(pattern) => {
+            if pattern.is_empty() {
+                return 0;
+            }
+            let start = (offset % pattern.len() as u64) as usize;
+            data.iter()
+                .enumerate()
+                .filter(|&(i, &byte)| byte != pattern[(start + i) % pattern.len()])
+                .count() as u64
+        }
+        ExpectedContent::UniformFill => {
+            let fill = *uniform_byte.get_or_insert(data.first().copied().unwrap_or(0));
+            if fill != 0x00 && fill != 0xFF {
+                return data.len() as u64;
+            }
+            data.iter().filter(|&&byte| byte != fill).count() as u64
+        }
+        ExpectedContent::Random => 0,
+        ExpectedContent::Stream(stream) => {
+            let mut expected = vec![0u8; data.len()];
+            stream.fill_at(offset, &mut expected);
+            data.iter().zip(&expected).filter(|(byte, expected)| byte != expected).count() as u64
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    #[test]
+    fn test_plan_regions_coverage() {
+        let total = 1024 * SAMPLE_SIZE;
+
+        let full = plan_regions(total, &VerificationPolicy::FullScan);
+        assert_eq!(full.iter().map(|r| r.1).sum::<u64>(), total);
+
+        let random = plan_regions(total, &VerificationPolicy::RandomSample { coverage: 0.1 });
+        assert_eq!(random.len(), 103);
+        assert!(random.windows(2).all(|w| w[0].0 + w[0].1 <= w[1].0));
+
+        let stratified = plan_regions(total, &VerificationPolicy::StratifiedSample);
+        assert_eq!(stratified.len() as u64, STRATIFIED_REGIONS);
+        let region_size = total / STRATIFIED_REGIONS;
+        for (index, &(offset, length)) in stratified.iter().enumerate() {
+            assert!(offset >= index as u64 * region_size);
+            assert!(offset + length <= (index as u64 + 1) * region_size);
+        }
+    }
+
+    #[test]
+    fn test_verify_device_reports_mismatched_sectors() {
+        let mut data = vec![0u8; 4 * 1024 * 1024];
+        data[5 * 512 + 7] = 0x42;
+        let mut device = Cursor::new(data);
+
+        let report = verify_device(
+            &mut device,
+            0,
+            4 * 1024 * 1024,
+            &ExpectedContent::Pattern(vec![0x00]),
+            &VerificationPolicy::FullScan,
+            &BadSectorLog::new(),
+            |_, _| {},
+        ).unwrap();
+
+        assert!(!report.passed);
+        assert_eq!(report.failed_sectors, vec![5]);
+        assert_eq!(report.bytes_verified, 4 * 1024 * 1024);
+        assert_eq!(report.coverage, 1.0);
+
+        // The same data is rejected by the entropy check
+        let report = verify_device(
+            &mut device,
+            0,
+            4 * 1024 * 1024,
+            &ExpectedContent::Random,
+            &VerificationPolicy::EntropyCheck,
+            &BadSectorLog::new(),
+            |_, _| {},
+        ).unwrap();
+        assert!(!report.passed);
+    }
+
+    #[test]
+    fn test_random_pass_is_compared_with_its_stream() {
+        use crate::pattern_stream::PatternSeed;
+
+        let seed = PatternSeed::generate();
+        let mut data = vec![0u8; 4 * 1024 * 1024];
+        PatternStream::new(&seed, 3).fill_at(0, &mut data);
+        let mut device = Cursor::new(data);
+        let expected = ExpectedContent::Stream(PatternStream::new(&seed, 3));
+        let verify = |device: &mut Cursor<Vec<u8>>, expected: &ExpectedContent| {
+            verify_device(device, 0, 4 * 1024 * 1024, expected, &VerificationPolicy::EntropyCheck, &BadSectorLog::new(), |_, _| {})
+                .unwrap()
+        };
+        assert!(verify(&mut device, &expected).passed);
+
+        // Random data from another seed passes on entropy, but not against the stream
+        PatternStream::new(&PatternSeed::generate(), 3).fill_at(0, device.get_mut());
+        assert!(verify(&mut device, &ExpectedContent::Random).passed);
+        assert!(!verify(&mut device, &expected).passed);
+    }
+}
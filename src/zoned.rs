@@ -0,0 +1,330 @@
+//! Zoned block devices: host-managed SMR drives and NVMe ZNS namespaces
+//!
+//! A zoned device is split into zones. Conventional zones take writes
+//! anywhere, but sequential-write-required zones only accept writes at their
+//! write pointer, which moves back to the start of the zone when the zone is
+//! reset. A plain overwrite from sector 0 is therefore rejected as soon as it
+//! reaches a zone that already holds data. Wiping one means resetting every
+//! sequential zone and then writing each zone front to back, up to its
+//! capacity. Host-aware drives accept random writes and need none of this.
+//!
+//! Zones are read with the Linux BLKREPORTZONE ioctl and reset with
+//! BLKRESETZONE; other platforms report zoned devices as unsupported.
+
+use std::io;
+
+/// Size of `struct blk_zone`
+const ZONE_DESCRIPTOR_SIZE: usize = 64;
+/// Size of `struct blk_zone_report` without its zones
+const ZONE_REPORT_HEADER_SIZE: usize = 16;
+/// `BLK_ZONE_REP_CAPACITY`: the descriptors carry the zone capacity
+const ZONE_REPORT_CAPACITY: u32 = 1;
+/// Zones asked for per BLKREPORTZONE
+const ZONES_PER_REPORT: usize = 4096;
+/// Buffer alignment for direct I/O
+const DIRECT_IO_ALIGNMENT: usize = 4096;
+
+/// How a device's zones must be written
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ZonedModel {
+    /// Random writes are accepted and the drive manages its zones itself
+    HostAware,
+    /// Sequential zones only accept writes at their write pointer
+    HostManaged,
+}
+
+impl ZonedModel {
+    /// The kernel's `queue/zoned` value
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim() {
+            "host-aware" => Some(ZonedModel::HostAware),
+            "host-managed" => Some(ZonedModel::HostManaged),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ZonedModel::HostAware => "Host-aware zoned",
+            ZonedModel::HostManaged => "Host-managed zoned",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ZoneType {
+    Conventional,
+    SequentialWriteRequired,
+    SequentialWritePreferred,
+    Unknown(u8),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ZoneCondition {
+    NotWritePointer,
+    Empty,
+    ImplicitOpen,
+    ExplicitOpen,
+    Closed,
+    ReadOnly,
+    Full,
+    Offline,
+    Unknown(u8),
+}
+
+/// One zone, in bytes from the start of the device
+#[derive(Debug, Clone, PartialEq)]
+pub struct Zone {
+    pub start: u64,
+    pub length: u64,
+    /// Writable bytes from the start of the zone; less than `length` on ZNS
+    /// namespaces whose zones do not fill their address range
+    pub capacity: u64,
+    pub write_pointer: u64,
+    pub zone_type: ZoneType,
+    pub condition: ZoneCondition,
+}
+
+impl Zone {
+    pub fn is_sequential(&self) -> bool {
+        matches!(self.zone_type, ZoneType::SequentialWriteRequired | ZoneType::SequentialWritePreferred)
+    }
+
+    /// Zones that cannot be written at all
+    pub fn is_unwritable(&self) -> bool {
+        matches!(self.condition, ZoneCondition::ReadOnly | ZoneCondition::Offline)
+    }
+}
+
+/// The zoned model of the device at `device_path`; `None` for ordinary devices
+pub fn detect_zoned_model(device_path: &str) -> Option<ZonedModel> {
+    #[cfg(target_os = "linux")]
+    {
+        let resolved = std::fs::canonicalize(device_path).ok()?;
+        let name = resolved.file_name()?.to_string_lossy().to_string();
+        let value = std::fs::read_to_string(format!("/sys/class/block/{}/queue/zoned", name)).ok()?;
+        ZonedModel::parse(&value)
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    {
+        let _ = device_path;
+        None
+    }
+}
+
+/// Decode the zones of a BLKREPORTZONE reply
+pub fn parse_zone_report(report: &[u8]) -> Vec<Zone> {
+    if report.len() < ZONE_REPORT_HEADER_SIZE {
+        return Vec::new();
+    }
+    let u64_at = |data: &[u8], offset: usize| u64::from_ne_bytes(data[offset..offset + 8].try_into().unwrap());
+    let count = u32::from_ne_bytes(report[8..12].try_into().unwrap()) as usize;
+    let flags = u32::from_ne_bytes(report[12..16].try_into().unwrap());
+
+    report[ZONE_REPORT_HEADER_SIZE..]
+        .chunks_exact(ZONE_DESCRIPTOR_SIZE)
+        .take(count)
+        .map(|descriptor| {
+            let length = u64_at(descriptor, 8) * 512;
+            Zone {
+                start: u64_at(descriptor, 0) * 512,
+                length,
+                capacity: if flags & ZONE_REPORT_CAPACITY != 0 { u64_at(descriptor, 32) * 512 } else { length },
+                write_pointer: u64_at(descriptor, 16) * 512,
+                zone_type: match descriptor[24] {
+                    1 => ZoneType::Conventional,
+                    2 => ZoneType::SequentialWriteRequired,
+                    3 => ZoneType::SequentialWritePreferred,
+                    other => ZoneType::Unknown(other),
+                },
+                condition: match descriptor[25] {
+                    0x0 => ZoneCondition::NotWritePointer,
+                    0x1 => ZoneCondition::Empty,
+                    0x2 => ZoneCondition::ImplicitOpen,
+                    0x3 => ZoneCondition::ExplicitOpen,
+                    0x4 => ZoneCondition::Closed,
+                    0xD => ZoneCondition::ReadOnly,
+                    0xE => ZoneCondition::Full,
+                    0xF => ZoneCondition::Offline,
+                    other => ZoneCondition::Unknown(other),
+                },
+            }
+        })
+        .collect()
+}
+
+/// Every zone of the device, in order (Report Zones)
+pub fn report_zones(device_path: &str) -> io::Result<Vec<Zone>> {
+    #[cfg(target_os = "linux")]
+    {
+        linux::report_zones(device_path)
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    {
+        let _ = device_path;
+        Err(io::Error::new(io::ErrorKind::Unsupported, "Zone reports are not available on this platform"))
+    }
+}
+
+/// Move the write pointer of every sequential zone that holds data back to
+/// the start of the zone; returns how many zones were reset
+pub fn reset_zones(device_path: &str, zones: &[Zone]) -> io::Result<usize> {
+    let written: Vec<&Zone> = zones
+        .iter()
+        .filter(|zone| zone.is_sequential() && zone.condition != ZoneCondition::Empty && !zone.is_unwritable())
+        .collect();
+
+    #[cfg(target_os = "linux")]
+    {
+        for zone in &written {
+            linux::reset_zone(device_path, zone.start / 512, zone.length / 512)?;
+        }
+        Ok(written.len())
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    {
+        let _ = device_path;
+        if written.is_empty() {
+            Ok(0)
+        } else {
+            Err(io::Error::new(io::ErrorKind::Unsupported, "Zone resets are not available on this platform"))
+        }
+    }
+}
+
+/// Write every writable zone front to back, up to its capacity, with direct
+/// I/O so the writes reach each zone in order. Sequential zones must have
+/// been reset first. `fill` fills a block with the content for a device
+/// offset; `on_block` is called after each block with the bytes written so
+/// far, and stops the overwrite when it returns an error. Returns the bytes
+/// written.
+pub fn overwrite_zones(
+    device_path: &str,
+    zones: &[Zone],
+    block_size: usize,
+    mut fill: impl FnMut(u64, &mut [u8]),
+    mut on_block: impl FnMut(u64) -> io::Result<()>,
+) -> io::Result<u64> {
+    use std::io::{Seek, SeekFrom, Write};
+
+    let mut device = open_direct(device_path)?;
+    let block_size = block_size.max(DIRECT_IO_ALIGNMENT) / DIRECT_IO_ALIGNMENT * DIRECT_IO_ALIGNMENT;
+    let mut storage = vec![0u8; block_size + DIRECT_IO_ALIGNMENT];
+    let aligned = storage.as_ptr().align_offset(DIRECT_IO_ALIGNMENT);
+    let block = &mut storage[aligned..aligned + block_size];
+
+    let mut bytes_written = 0u64;
+    for zone in zones.iter().filter(|zone| !zone.is_unwritable()) {
+        device.seek(SeekFrom::Start(zone.start))?;
+        let mut offset = zone.start;
+        let end = zone.start + zone.capacity;
+        while offset < end {
+            let size = block_size.min((end - offset) as usize);
+            fill(offset, &mut block[..size]);
+            device.write_all(&block[..size])?;
+            offset += size as u64;
+            bytes_written += size as u64;
+            on_block(bytes_written)?;
+        }
+    }
+    device.sync_all()?;
+    Ok(bytes_written)
+}
+
+fn open_direct(device_path: &str) -> io::Result<std::fs::File> {
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true);
+    #[cfg(target_os = "linux")]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.custom_flags(libc::O_DIRECT);
+    }
+    options.open(device_path)
+}
+
+#[cfg(target_os = "linux")]
+mod linux {
+    use std::fs::OpenOptions;
+    use std::io;
+    use std::os::unix::io::AsRawFd;
+    use libc::ioctl;
+
+    use super::{parse_zone_report, Zone, ZONES_PER_REPORT, ZONE_DESCRIPTOR_SIZE, ZONE_REPORT_HEADER_SIZE};
+
+    /// _IOWR(0x12, 130, struct blk_zone_report)
+    const BLKREPORTZONE: libc::c_ulong = 0xC010_1282;
+    /// _IOW(0x12, 131, struct blk_zone_range)
+    const BLKRESETZONE: libc::c_ulong = 0x4010_1283;
+
+    pub fn report_zones(device_path: &str) -> io::Result<Vec<Zone>> {
+        let device = OpenOptions::new().read(true).open(device_path)?;
+        let mut zones: Vec<Zone> = Vec::new();
+        let mut report = vec![0u8; ZONE_REPORT_HEADER_SIZE + ZONES_PER_REPORT * ZONE_DESCRIPTOR_SIZE];
+
+        loop {
+            let next_sector = zones.last().map_or(0, |zone| (zone.start + zone.length) / 512);
+            report.fill(0);
+            report[0..8].copy_from_slice(&next_sector.to_ne_bytes());
+            report[8..12].copy_from_slice(&(ZONES_PER_REPORT as u32).to_ne_bytes());
+            if unsafe { ioctl(device.as_raw_fd(), BLKREPORTZONE as _, report.as_mut_ptr()) } < 0 {
+                return Err(io::Error::last_os_error());
+            }
+            let batch = parse_zone_report(&report);
+            if batch.is_empty() {
+                return Ok(zones);
+            }
+            zones.extend(batch);
+        }
+    }
+
+    pub fn reset_zone(device_path: &str, start_sector: u64, sectors: u64) -> io::Result<()> {
+        let device = OpenOptions::new().write(true).open(device_path)?;
+        let range: [u64; 2] = [start_sector, sectors];
+        if unsafe { ioctl(device.as_raw_fd(), BLKRESETZONE as _, range.as_ptr()) } < 0 {
+            return Err(io::Error::last_os_error());
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn descriptor(start: u64, length: u64, write_pointer: u64, zone_type: u8, condition: u8, capacity: u64) -> Vec<u8> {
+        let mut descriptor = vec![0u8; ZONE_DESCRIPTOR_SIZE];
+        descriptor[0..8].copy_from_slice(&start.to_ne_bytes());
+        descriptor[8..16].copy_from_slice(&length.to_ne_bytes());
+        descriptor[16..24].copy_from_slice(&write_pointer.to_ne_bytes());
+        descriptor[24] = zone_type;
+        descriptor[25] = condition;
+        descriptor[32..40].copy_from_slice(&capacity.to_ne_bytes());
+        descriptor
+    }
+
+    #[test]
+    fn test_parse_zone_report() {
+        let mut report = vec![0u8; ZONE_REPORT_HEADER_SIZE];
+        report[8..12].copy_from_slice(&2u32.to_ne_bytes());
+        report[12..16].copy_from_slice(&ZONE_REPORT_CAPACITY.to_ne_bytes());
+        report.extend(descriptor(0, 524288, 0, 1, 0x0, 524288));
+        report.extend(descriptor(524288, 524288, 600000, 2, 0x2, 262144));
+        // Space for more zones than the kernel filled in
+        report.extend(vec![0u8; ZONE_DESCRIPTOR_SIZE]);
+
+        let zones = parse_zone_report(&report);
+        assert_eq!(zones.len(), 2);
+        assert_eq!(zones[0].zone_type, ZoneType::Conventional);
+        assert!(!zones[0].is_sequential());
+        assert_eq!(zones[1].start, 256 * 1024 * 1024);
+        assert_eq!(zones[1].capacity, 128 * 1024 * 1024);
+        assert_eq!(zones[1].condition, ZoneCondition::ImplicitOpen);
+        assert!(zones[1].is_sequential());
+
+        assert_eq!(ZonedModel::parse("host-managed\n"), Some(ZonedModel::HostManaged));
+        assert_eq!(ZonedModel::parse("none"), None);
+    }
+}